@@ -1,14 +1,17 @@
 //! Transport comparison benchmark: per-client vs shared `UdpTransport`
 //!
-//! This benchmark compares performance characteristics between two usage patterns:
+//! This benchmark compares performance characteristics between three usage patterns:
 //! - Per-client: Each client creates its own `UdpTransport` via `.connect()`
 //! - Shared: Multiple clients share a single `UdpTransport` via handles
+//! - Shared + batched I/O: as above, with `recvmmsg`/`sendmmsg` batching
+//!   (Linux only; elsewhere the option is ignored)
 //!
 //! Metrics measured:
 //! - Throughput (requests/sec)
 //! - Latency distribution (p50, p95, p99)
 //! - File descriptor usage
 //! - Memory overhead (RSS delta)
+//! - Achieved batch sizes (batched mode)
 //!
 //! Prerequisites:
 //!   docker build -t async-snmp-test:latest tests/containers/snmpd/
@@ -21,7 +24,7 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
-use async_snmp::{Auth, Client, Retry, UdpHandle, UdpTransport, oid};
+use async_snmp::{Auth, Client, Retry, UdpBatching, UdpHandle, UdpTransport, oid};
 use futures::future::join_all;
 use hdrhistogram::Histogram;
 use testcontainers::core::{IntoContainerPort, WaitFor};
@@ -109,14 +112,33 @@ async fn run_comparison(num_containers: usize, concurrency_per_target: usize) {
 
     // Warmup and benchmark shared transport
     println!("\n[Shared Transport (explicit UdpTransport with handles)]");
-    let shared_result = benchmark_shared(&targets, concurrency_per_target).await;
+    let shared_result = benchmark_shared(&targets, concurrency_per_target, None).await;
 
     let shared_fds = count_fds();
     println!("  FDs during test: {shared_fds} (baseline: {baseline_fds})");
 
+    drop(shared_result.clients);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Warmup and benchmark shared transport with batched I/O
+    println!("\n[Shared Transport + batched I/O (recvmmsg/sendmmsg)]");
+    let batched_result =
+        benchmark_shared(&targets, concurrency_per_target, Some(UdpBatching::new())).await;
+
     // Print comparison
     println!("\n[Comparison]");
-    print_comparison(&non_shared_result.stats, &shared_result.stats);
+    print_comparison(
+        "shared",
+        &shared_result.stats,
+        "per-client",
+        &non_shared_result.stats,
+    );
+    print_comparison(
+        "batched",
+        &batched_result.stats,
+        "shared",
+        &shared_result.stats,
+    );
 
     println!("\n  FD Usage:");
     println!(
@@ -192,10 +214,14 @@ async fn benchmark_non_shared(
 async fn benchmark_shared(
     targets: &[SocketAddr],
     concurrency: usize,
+    batching: Option<UdpBatching>,
 ) -> BenchmarkResult<Client<UdpHandle>> {
     // Create shared transport
-    let shared = UdpTransport::builder()
-        .bind("0.0.0.0:0")
+    let mut builder = UdpTransport::builder().bind("0.0.0.0:0");
+    if let Some(batching) = batching {
+        builder = builder.batched_io(batching);
+    }
+    let shared = builder
         .build()
         .await
         .expect("Failed to bind shared transport");
@@ -222,10 +248,25 @@ async fn benchmark_shared(
     let stats = run_benchmark(&clients, concurrency).await;
 
     print_stats(&stats);
+    if batching.is_some() {
+        print_batch_sizes(&shared.stats());
+    }
 
     BenchmarkResult { stats, clients }
 }
 
+#[allow(clippy::cast_precision_loss, reason = "approximation is fine here")]
+fn print_batch_sizes(stats: &async_snmp::UdpStats) {
+    let mean = |datagrams: u64, batches: u64| datagrams as f64 / batches.max(1) as f64;
+    println!(
+        "  Batches: recv {} calls ({:.2} datagrams/call), send {} calls ({:.2} datagrams/call)",
+        stats.recv_batches,
+        mean(stats.recv_batched_datagrams, stats.recv_batches),
+        stats.send_batches,
+        mean(stats.send_batched_datagrams, stats.send_batches),
+    );
+}
+
 #[allow(clippy::cast_precision_loss, reason = "approximation is fine here")]
 fn print_stats(stats: &BenchmarkStats) {
     println!("  Throughput: {:.0} req/s", stats.requests_per_sec());
@@ -337,14 +378,19 @@ async fn run_benchmark<T: async_snmp::Transport + 'static>(
 }
 
 #[allow(clippy::cast_precision_loss, reason = "approximation is fine here")]
-fn print_comparison(per_client: &BenchmarkStats, shared: &BenchmarkStats) {
-    let throughput_ratio = shared.requests_per_sec() / per_client.requests_per_sec().max(0.001);
-    let p50_ratio = per_client.p50_us as f64 / shared.p50_us.max(1) as f64;
-    let p99_ratio = per_client.p99_us as f64 / shared.p99_us.max(1) as f64;
-
-    println!("  Throughput: shared is {throughput_ratio:.2}x vs per-client");
+fn print_comparison(
+    name: &str,
+    candidate: &BenchmarkStats,
+    baseline_name: &str,
+    baseline: &BenchmarkStats,
+) {
+    let throughput_ratio = candidate.requests_per_sec() / baseline.requests_per_sec().max(0.001);
+    let p50_ratio = baseline.p50_us as f64 / candidate.p50_us.max(1) as f64;
+    let p99_ratio = baseline.p99_us as f64 / candidate.p99_us.max(1) as f64;
+
+    println!("  Throughput: {name} is {throughput_ratio:.2}x vs {baseline_name}");
     println!(
-        "  p50 Latency: shared is {:.2}x {} than per-client",
+        "  p50 Latency: {name} is {:.2}x {} than {baseline_name}",
         if p50_ratio > 1.0 {
             p50_ratio
         } else {
@@ -353,7 +399,7 @@ fn print_comparison(per_client: &BenchmarkStats, shared: &BenchmarkStats) {
         if p50_ratio > 1.0 { "faster" } else { "slower" }
     );
     println!(
-        "  p99 Latency: shared is {:.2}x {} than per-client",
+        "  p99 Latency: {name} is {:.2}x {} than {baseline_name}",
         if p99_ratio > 1.0 {
             p99_ratio
         } else {
@@ -1,8 +1,9 @@
-//! Built-in MIB handlers for SNMP engine, USM, and MPD statistics.
+//! Built-in MIB handlers for SNMP engine, USM, and MPD statistics, and the
+//! snmpCommunityTable.
 //!
-//! These handlers provide read-only access to agent engine state and
-//! RFC-defined statistics counters. They are registered automatically
-//! during agent construction.
+//! These handlers provide read-only access to agent engine state,
+//! RFC-defined statistics counters, and the configured community mapping.
+//! They are registered automatically during agent construction.
 
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
@@ -16,6 +17,7 @@ use crate::value::Value;
 use crate::varbind::VarBind;
 
 use super::AgentState;
+use super::community_table::CommunityTable;
 
 /// Number of arcs in the snmpEngine prefix (1.3.6.1.6.3.10.2.1).
 const SNMP_ENGINE_PREFIX_LEN: usize = 9;
@@ -23,6 +25,8 @@ const SNMP_ENGINE_PREFIX_LEN: usize = 9;
 const USM_STATS_PREFIX_LEN: usize = 9;
 /// Number of arcs in the mpdStats prefix (1.3.6.1.6.3.11.2.1).
 const MPD_STATS_PREFIX_LEN: usize = 9;
+/// Number of arcs in the snmpCommunityTable prefix (1.3.6.1.6.3.18.1.1).
+const COMMUNITY_TABLE_PREFIX_LEN: usize = 9;
 
 // ---------------------------------------------------------------------------
 // SnmpEngineHandler
@@ -283,6 +287,115 @@ impl MibHandler for MpdStatsHandler {
     }
 }
 
+// ---------------------------------------------------------------------------
+// CommunityTableHandler
+// ---------------------------------------------------------------------------
+
+/// Handler for the snmpCommunityTable (1.3.6.1.6.3.18.1.1), RFC 3584.
+///
+/// Rows are indexed by the IMPLIED snmpCommunityIndex and expose read-only
+/// columns 3 through 8 (securityName, contextEngineID, contextName,
+/// transportTag, storageType, status). snmpCommunityName (.2) is not served,
+/// so no view can disclose a configured community.
+pub(crate) struct CommunityTableHandler {
+    state: Arc<AgentState>,
+    table: Arc<CommunityTable>,
+    /// Entry positions sorted by index OID.
+    rows: Vec<(Vec<u32>, usize)>,
+}
+
+impl CommunityTableHandler {
+    /// StorageType readOnly(5).
+    const STORAGE_READ_ONLY: i32 = 5;
+    /// RowStatus active(1).
+    const ROW_ACTIVE: i32 = 1;
+
+    pub(crate) fn new(state: Arc<AgentState>, table: Arc<CommunityTable>) -> Self {
+        let mut rows: Vec<_> = table
+            .entries()
+            .iter()
+            .enumerate()
+            .map(|(position, entry)| {
+                let index = entry.index.iter().map(|&octet| u32::from(octet)).collect();
+                (index, position)
+            })
+            .collect();
+        rows.sort();
+        Self { state, table, rows }
+    }
+
+    fn entry_prefix() -> Oid {
+        oid!(1, 3, 6, 1, 6, 3, 18, 1, 1, 1)
+    }
+
+    fn get_column_value(&self, col: u32, position: usize) -> Option<Value> {
+        let entry = &self.table.entries()[position];
+        Some(match col {
+            3 => Value::OctetString(entry.security_name.clone()),
+            4 if entry.context_engine_id.is_empty() => {
+                Value::OctetString(self.state.engine_id.clone())
+            }
+            4 => Value::OctetString(entry.context_engine_id.clone()),
+            5 => Value::OctetString(entry.context_name.clone()),
+            6 => Value::OctetString(entry.transport_tag.clone()),
+            7 => Value::Integer(Self::STORAGE_READ_ONLY),
+            8 => Value::Integer(Self::ROW_ACTIVE),
+            _ => return None,
+        })
+    }
+}
+
+impl MibHandler for CommunityTableHandler {
+    fn get<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        oid: &'a Oid,
+    ) -> BoxFuture<'a, HandlerResult<GetResult>> {
+        Box::pin(async move {
+            let arcs = oid.arcs();
+            if arcs.len() < COMMUNITY_TABLE_PREFIX_LEN + 3 || arcs[COMMUNITY_TABLE_PREFIX_LEN] != 1
+            {
+                return Ok(GetResult::NoSuchObject);
+            }
+            let col = arcs[COMMUNITY_TABLE_PREFIX_LEN + 1];
+            if !(3..=8).contains(&col) {
+                return Ok(GetResult::NoSuchObject);
+            }
+            let index = &arcs[COMMUNITY_TABLE_PREFIX_LEN + 2..];
+            match self
+                .rows
+                .binary_search_by(|(row, _)| row.as_slice().cmp(index))
+            {
+                Ok(row) => Ok(self
+                    .get_column_value(col, self.rows[row].1)
+                    .map_or(GetResult::NoSuchObject, GetResult::Value)),
+                Err(_) => Ok(GetResult::NoSuchInstance),
+            }
+        })
+    }
+
+    fn get_next<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        oid: &'a Oid,
+    ) -> BoxFuture<'a, HandlerResult<GetNextResult>> {
+        Box::pin(async move {
+            let entry = Self::entry_prefix();
+            for col in 3..=8u32 {
+                let column = entry.child(col);
+                for (index, position) in &self.rows {
+                    let instance = Oid::new(column.arcs().iter().chain(index).copied());
+                    if oid < &instance {
+                        let value = self.get_column_value(col, *position).unwrap();
+                        return Ok(GetNextResult::Value(VarBind::new(instance, value)));
+                    }
+                }
+            }
+            Ok(GetNextResult::EndOfMibView)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -309,6 +422,7 @@ mod tests {
             snmp_unknown_security_models: AtomicU32::new(20),
             snmp_silent_drops: AtomicU32::new(30),
             snmp_unknown_contexts: AtomicU32::new(0),
+            snmp_proxy_drops: AtomicU32::new(0),
             usm_stats: {
                 let stats = crate::v3::process::UsmStats::default();
                 stats.unknown_engine_ids.store(40, Ordering::Relaxed);
@@ -507,6 +621,75 @@ mod tests {
         assert_eq!(count, 6, "should walk through all 6 usmStats counters");
     }
 
+    // -----------------------------------------------------------------------
+    // CommunityTableHandler tests
+    // -----------------------------------------------------------------------
+
+    fn community_table_handler() -> CommunityTableHandler {
+        let mut table = CommunityTable::default();
+        for (name, context) in [(b"bridge".as_slice(), b"vlan-10".as_slice()); 10] {
+            table.push(
+                crate::Community::from(b"secret"),
+                Bytes::copy_from_slice(name),
+                Bytes::copy_from_slice(context),
+                Bytes::new(),
+                Bytes::new(),
+            );
+        }
+        CommunityTableHandler::new(test_state(), Arc::new(table))
+    }
+
+    #[tokio::test]
+    async fn test_community_table_get_defaults_context_engine_id_to_local_engine() {
+        let handler = community_table_handler();
+        let ctx = test_ctx();
+        let result = handler
+            .get(&ctx, &oid!(1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 4, 49))
+            .await
+            .unwrap();
+        match result {
+            GetResult::Value(Value::OctetString(v)) => {
+                assert_eq!(v.as_ref(), &[0x80, 0x00, 0x01, 0x02, 0x03]);
+            }
+            other => panic!("expected OctetString, got {other:?}"),
+        }
+        let missing = handler
+            .get(&ctx, &oid!(1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 4, 57, 57))
+            .await
+            .unwrap();
+        assert!(matches!(missing, GetResult::NoSuchInstance));
+    }
+
+    #[tokio::test]
+    async fn test_community_table_never_serves_community_name() {
+        let handler = community_table_handler();
+        let ctx = test_ctx();
+        let result = handler
+            .get(&ctx, &oid!(1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 2, 49))
+            .await
+            .unwrap();
+        assert!(matches!(result, GetResult::NoSuchObject));
+    }
+
+    #[tokio::test]
+    async fn test_community_table_get_next_walks_rows_in_index_order() {
+        let handler = community_table_handler();
+        let ctx = test_ctx();
+
+        let mut current = oid!(1, 3, 6, 1, 6, 3, 18, 1, 1);
+        let mut walked = Vec::new();
+        while let GetNextResult::Value(vb) = handler.get_next(&ctx, &current).await.unwrap() {
+            assert!(vb.oid > current);
+            walked.push(vb.oid.clone());
+            current = vb.oid;
+        }
+        assert_eq!(walked.len(), 6 * 10, "should walk 6 columns of 10 rows");
+        // IMPLIED "10" sorts between "1" and "2".
+        assert_eq!(walked[0], oid!(1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 3, 49));
+        assert_eq!(walked[1], oid!(1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 3, 49, 48));
+        assert_eq!(walked[2], oid!(1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 3, 50));
+    }
+
     // -----------------------------------------------------------------------
     // MpdStatsHandler tests
     // -----------------------------------------------------------------------
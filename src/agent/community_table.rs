@@ -0,0 +1,198 @@
+//! Community-to-context mapping (RFC 3584 Section 5.2.1, SNMP-COMMUNITY-MIB).
+//!
+//! Each entry maps a v1/v2c community to the securityName, contextEngineID
+//! and contextName a request is processed with. An entry with a non-empty
+//! transport tag only accepts requests from source addresses registered
+//! under that tag.
+
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+use bytes::Bytes;
+
+use crate::community::Community;
+use crate::error::{Error, Result};
+
+/// One snmpCommunityEntry row.
+pub(crate) struct CommunityEntry {
+    /// snmpCommunityIndex; the builder assigns "1", "2", ... in call order.
+    pub(crate) index: Bytes,
+    pub(crate) community: Community,
+    pub(crate) security_name: Bytes,
+    /// Empty selects the local engine.
+    pub(crate) context_engine_id: Bytes,
+    pub(crate) context_name: Bytes,
+    pub(crate) transport_tag: Bytes,
+}
+
+/// Configured snmpCommunityTable with the addresses behind each transport tag.
+#[derive(Default)]
+pub(crate) struct CommunityTable {
+    /// Entries in registration order, which is also lookup order.
+    entries: Vec<CommunityEntry>,
+    tagged_addresses: HashMap<Bytes, HashSet<IpAddr>>,
+}
+
+impl CommunityTable {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn entries(&self) -> &[CommunityEntry] {
+        &self.entries
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        community: Community,
+        security_name: Bytes,
+        context_name: Bytes,
+        context_engine_id: Bytes,
+        transport_tag: Bytes,
+    ) {
+        let index = Bytes::from((self.entries.len() + 1).to_string());
+        self.entries.push(CommunityEntry {
+            index,
+            community,
+            security_name,
+            context_engine_id,
+            context_name,
+            transport_tag,
+        });
+    }
+
+    pub(crate) fn tag_address(&mut self, tag: Bytes, address: IpAddr) {
+        self.tagged_addresses
+            .entry(tag)
+            .or_default()
+            .insert(address.to_canonical());
+    }
+
+    /// Check SNMP-COMMUNITY-MIB size limits and that every transport tag
+    /// names at least one address.
+    pub(crate) fn validate(&self) -> Result<()> {
+        for entry in &self.entries {
+            let problem = if entry.security_name.is_empty() || entry.security_name.len() > 32 {
+                "security name must be 1 to 32 octets"
+            } else if entry.context_name.len() > 32 {
+                "context name exceeds 32 octets"
+            } else if !entry.context_engine_id.is_empty()
+                && !(5..=32).contains(&entry.context_engine_id.len())
+            {
+                "context engine ID must be empty or 5 to 32 octets"
+            } else if entry.transport_tag.len() > 255 {
+                "transport tag exceeds 255 octets"
+            } else if !entry.transport_tag.is_empty()
+                && !self.tagged_addresses.contains_key(&entry.transport_tag)
+            {
+                "transport tag has no tagged addresses"
+            } else {
+                continue;
+            };
+            return Err(Error::Config(
+                format!(
+                    "community entry {}: {problem}",
+                    String::from_utf8_lossy(&entry.index)
+                )
+                .into(),
+            )
+            .boxed());
+        }
+        Ok(())
+    }
+
+    /// Find the first entry for `community` that accepts requests from `source`.
+    ///
+    /// Every entry's community is compared so the time taken does not reveal
+    /// which entry matched.
+    pub(crate) fn lookup(&self, community: &[u8], source: IpAddr) -> Option<&CommunityEntry> {
+        let mut found = None;
+        for entry in &self.entries {
+            if entry.community.matches(community)
+                && found.is_none()
+                && self.accepts_source(&entry.transport_tag, source)
+            {
+                found = Some(entry);
+            }
+        }
+        found
+    }
+
+    fn accepts_source(&self, tag: &[u8], source: IpAddr) -> bool {
+        tag.is_empty()
+            || self
+                .tagged_addresses
+                .get(tag)
+                .is_some_and(|addresses| addresses.contains(&source.to_canonical()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> CommunityTable {
+        let mut table = CommunityTable::default();
+        table.push(
+            Community::from(b"public"),
+            Bytes::from_static(b"mgmt"),
+            Bytes::from_static(b"vlan-10"),
+            Bytes::new(),
+            Bytes::from_static(b"mgmt-hosts"),
+        );
+        table.push(
+            Community::from(b"public"),
+            Bytes::from_static(b"anyone"),
+            Bytes::new(),
+            Bytes::new(),
+            Bytes::new(),
+        );
+        table.tag_address(
+            Bytes::from_static(b"mgmt-hosts"),
+            "192.0.2.1".parse().unwrap(),
+        );
+        table
+    }
+
+    #[test]
+    fn lookup_prefers_first_entry_whose_tag_admits_the_source() {
+        let table = table();
+        table.validate().unwrap();
+
+        let tagged = table
+            .lookup(b"public", "192.0.2.1".parse().unwrap())
+            .unwrap();
+        assert_eq!(tagged.security_name.as_ref(), b"mgmt");
+        assert_eq!(tagged.index.as_ref(), b"1");
+
+        let untagged = table
+            .lookup(b"public", "192.0.2.2".parse().unwrap())
+            .unwrap();
+        assert_eq!(untagged.security_name.as_ref(), b"anyone");
+
+        let mapped = table
+            .lookup(b"public", "::ffff:192.0.2.1".parse().unwrap())
+            .unwrap();
+        assert_eq!(mapped.security_name.as_ref(), b"mgmt");
+
+        assert!(
+            table
+                .lookup(b"private", "192.0.2.1".parse().unwrap())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn validate_rejects_tags_without_addresses() {
+        let mut table = CommunityTable::default();
+        table.push(
+            Community::from(b"public"),
+            Bytes::from_static(b"mgmt"),
+            Bytes::new(),
+            Bytes::new(),
+            Bytes::from_static(b"nowhere"),
+        );
+        let error = table.validate().unwrap_err();
+        assert!(error.to_string().contains("no tagged addresses"));
+    }
+}
@@ -0,0 +1,333 @@
+//! Notification forwarder with version translation.
+//!
+//! A [`NotificationForwarder`] re-emits notifications received by a
+//! [`NotificationReceiver`] to an agent's configured sinks. SNMPv2c and SNMPv3
+//! traps and informs keep their sysUpTime.0, snmpTrapOID.0 and varbinds. An
+//! SNMPv1 trap is converted with the RFC 3584 Section 3.1 proxy varbinds
+//! (snmpTrapAddress.0, snmpTrapEnterprise.0 and, when [`TrapCommunity`]
+//! allows it, snmpTrapCommunity.0), as
+//! [`TrapV1Pdu::to_v2_proxy_pdu`](crate::TrapV1Pdu::to_v2_proxy_pdu) does.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use bytes::Bytes;
+
+use crate::error::Result;
+use crate::notification::{Notification, NotificationReceiver, ReceivedNotification};
+use crate::oid::Oid;
+use crate::varbind::VarBind;
+
+use super::{Agent, NotificationOutcome, NotificationSendStream, NotificationSinkId, SinkStatus};
+
+type ForwardFilter = Arc<dyn Fn(&ReceivedNotification) -> bool + Send + Sync>;
+
+/// What a forwarded SNMPv1 trap carries in snmpTrapCommunity.0.
+///
+/// The received community is a credential for the originating device.
+/// Forwarding it puts it in cleartext in every forwarded notification, so it
+/// is omitted unless [`NotificationForwarder::trap_community`] opts in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TrapCommunity {
+    /// Leave snmpTrapCommunity.0 out.
+    #[default]
+    Omit,
+    /// Carry the community the trap was received with (RFC 3584 Section
+    /// 3.1(4)).
+    Received,
+    /// Carry this value instead of the received community.
+    Replace(Bytes),
+}
+
+/// Re-emits received notifications to an agent's notification sinks.
+///
+/// Each forwarded notification is sent to every sink configured with
+/// [`AgentBuilder::trap_sink`](super::AgentBuilder::trap_sink): as an Inform
+/// to sinks named with [`inform_sink`](Self::inform_sink) and as a trap to
+/// the rest. The agent's notify views, fan-out limit and SNMPv1 conversion
+/// apply as they do for [`Agent::send_trap`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::agent::{Agent, NotificationForwarder};
+/// use async_snmp::{Auth, NotificationReceiver, NotificationSinkId};
+///
+/// # async fn example() -> Result<(), Box<async_snmp::Error>> {
+/// let agent = Agent::builder()
+///     .bind("0.0.0.0:0")
+///     .trap_sink(NotificationSinkId::new("nms").unwrap(), "192.0.2.10:162", Auth::v2c("public"))
+///     .trap_sink(NotificationSinkId::new("siem").unwrap(), "192.0.2.11:162", Auth::v2c("public"))
+///     .allow_all_access()
+///     .build()
+///     .await?;
+///
+/// let forwarder = NotificationForwarder::new(agent)
+///     .inform_sink(NotificationSinkId::new("siem").unwrap())
+///     .filter(|received| !received.notification.varbinds().is_empty());
+///
+/// let receiver = NotificationReceiver::bind("0.0.0.0:162").await?;
+/// forwarder.run(&receiver).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct NotificationForwarder {
+    agent: Agent,
+    inform_sinks: Arc<HashSet<NotificationSinkId>>,
+    filter: Option<ForwardFilter>,
+    trap_community: TrapCommunity,
+}
+
+impl NotificationForwarder {
+    /// Forward through `agent`'s configured notification sinks.
+    #[must_use]
+    pub fn new(agent: Agent) -> Self {
+        Self {
+            agent,
+            inform_sinks: Arc::new(HashSet::new()),
+            filter: None,
+            trap_community: TrapCommunity::Omit,
+        }
+    }
+
+    /// Send Informs rather than traps to the sink with this ID.
+    ///
+    /// SNMPv1 sinks named here are reported as skipped, because SNMPv1 has no
+    /// Inform.
+    #[must_use]
+    pub fn inform_sink(mut self, id: NotificationSinkId) -> Self {
+        Arc::make_mut(&mut self.inform_sinks).insert(id);
+        self
+    }
+
+    /// Forward only notifications for which `filter` returns `true`.
+    ///
+    /// Replaces any previously set filter.
+    #[must_use]
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&ReceivedNotification) -> bool + Send + Sync + 'static,
+    {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Choose what converted SNMPv1 traps carry in snmpTrapCommunity.0.
+    ///
+    /// Defaults to [`TrapCommunity::Omit`]. [`TrapCommunity::Received`]
+    /// exposes the sending device's community to every sink.
+    #[must_use]
+    pub fn trap_community(mut self, trap_community: TrapCommunity) -> Self {
+        self.trap_community = trap_community;
+        self
+    }
+
+    /// The agent whose sinks receive forwarded notifications.
+    #[must_use]
+    pub fn agent(&self) -> &Agent {
+        &self.agent
+    }
+
+    /// Lazily forward one notification, yielding sink outcomes as they
+    /// complete.
+    ///
+    /// Returns `Ok(None)` when the filter rejects the notification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when an SNMPv1 trap cannot be converted to an SNMPv2
+    /// notification (see
+    /// [`TrapV1Pdu::to_v2_proxy_pdu`](crate::TrapV1Pdu::to_v2_proxy_pdu)).
+    pub fn forward_stream(
+        &self,
+        received: &ReceivedNotification,
+    ) -> Result<Option<NotificationSendStream<'_>>> {
+        if self.filter.as_ref().is_some_and(|filter| !filter(received)) {
+            return Ok(None);
+        }
+        let (trap_oid, uptime, varbinds) =
+            forwarded_parts(&received.notification, &self.trap_community)?;
+        Ok(Some(self.agent.send_forward_stream(
+            &trap_oid,
+            uptime,
+            varbinds,
+            Arc::clone(&self.inform_sinks),
+        )))
+    }
+
+    /// Forward one notification to every sink, reporting each outcome in sink
+    /// configuration order.
+    ///
+    /// Returns `Ok(None)` when the filter rejects the notification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when an SNMPv1 trap cannot be converted to an SNMPv2
+    /// notification.
+    pub async fn forward(
+        &self,
+        received: &ReceivedNotification,
+    ) -> Result<Option<NotificationOutcome>> {
+        match self.forward_stream(received)? {
+            Some(stream) => Ok(Some(stream.into_outcome().await)),
+            None => Ok(None),
+        }
+    }
+
+    /// Forward every notification from `receiver` until receiving fails.
+    ///
+    /// Notifications are forwarded one at a time. Conversion errors, failed
+    /// sinks and skipped sinks are logged and do not stop the loop. Drop the
+    /// returned future to stop forwarding.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from [`NotificationReceiver::recv`].
+    pub async fn run(&self, receiver: &NotificationReceiver) -> Result<()> {
+        loop {
+            let received = receiver.recv().await?;
+            let mut stream = match self.forward_stream(&received) {
+                Ok(Some(stream)) => stream,
+                Ok(None) => continue,
+                Err(error) => {
+                    tracing::warn!(target: "async_snmp::agent", { snmp.source = %received.source, error = %error }, "failed to convert notification for forwarding");
+                    continue;
+                }
+            };
+            while let Some(sink) = stream.next().await {
+                match &sink.status {
+                    SinkStatus::Failed(error) => {
+                        tracing::warn!(target: "async_snmp::agent", { snmp.sink_id = %sink.sink.id(), snmp.dest = %sink.sink.dest(), error = %error }, "failed to forward notification");
+                    }
+                    SinkStatus::Skipped(reason) => {
+                        tracing::warn!(target: "async_snmp::agent", { snmp.sink_id = %sink.sink.id(), snmp.dest = %sink.sink.dest(), reason = %reason }, "skipped forwarding sink");
+                    }
+                    SinkStatus::Succeeded => {}
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for NotificationForwarder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotificationForwarder")
+            .field("inform_sinks", &self.inform_sinks)
+            .field("filter", &self.filter.is_some())
+            .field("trap_community", &self.trap_community)
+            .finish_non_exhaustive()
+    }
+}
+
+/// snmpTrapOID.0, sysUpTime.0 and the remaining varbinds to re-emit.
+fn forwarded_parts(
+    notification: &Notification,
+    trap_community: &TrapCommunity,
+) -> Result<(Oid, u32, Vec<VarBind>)> {
+    match notification {
+        Notification::TrapV1 {
+            community, trap, ..
+        } => {
+            let community = match trap_community {
+                TrapCommunity::Omit => None,
+                TrapCommunity::Received => Some(community.as_bytes()),
+                TrapCommunity::Replace(value) => Some(value.as_ref()),
+            };
+            let pdu = trap.to_v2_proxy_pdu(community)?;
+            Ok((
+                pdu.trap_oid().clone(),
+                pdu.uptime(),
+                pdu.varbinds()[2..].to_vec(),
+            ))
+        }
+        Notification::TrapV2c {
+            uptime,
+            trap_oid,
+            varbinds,
+            ..
+        }
+        | Notification::TrapV3 {
+            uptime,
+            trap_oid,
+            varbinds,
+            ..
+        }
+        | Notification::InformV2c {
+            uptime,
+            trap_oid,
+            varbinds,
+            ..
+        }
+        | Notification::InformV3 {
+            uptime,
+            trap_oid,
+            varbinds,
+            ..
+        } => Ok((trap_oid.clone(), *uptime, varbinds.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::oids;
+    use crate::pdu::{GenericTrap, TrapV1Pdu};
+    use crate::value::Value;
+    use crate::{Community, oid};
+
+    fn v1_trap() -> Notification {
+        Notification::TrapV1 {
+            community: Community::from(b"public"),
+            trap: TrapV1Pdu::new(
+                oid!(1, 3, 6, 1, 4, 1, 9999),
+                [192, 0, 2, 1],
+                GenericTrap::EnterpriseSpecific,
+                7,
+                500,
+                vec![VarBind::new(
+                    oid!(1, 3, 6, 1, 4, 1, 9999, 1),
+                    Value::Integer(1),
+                )],
+            ),
+            decode_anomalies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn v1_traps_are_forwarded_with_proxy_varbinds() {
+        let (trap_oid, uptime, varbinds) =
+            forwarded_parts(&v1_trap(), &TrapCommunity::Received).unwrap();
+        assert_eq!(trap_oid, oid!(1, 3, 6, 1, 4, 1, 9999, 0, 7));
+        assert_eq!(uptime, 500);
+        let oids: Vec<_> = varbinds.iter().map(|varbind| varbind.oid.clone()).collect();
+        assert_eq!(
+            oids,
+            [
+                oid!(1, 3, 6, 1, 4, 1, 9999, 1),
+                oids::snmp_trap_address(),
+                oids::snmp_trap_community(),
+                oids::snmp_trap_enterprise(),
+            ]
+        );
+    }
+
+    #[test]
+    fn v1_trap_community_is_omitted_unless_chosen() {
+        let community_of = |trap_community| {
+            let (_, _, varbinds) = forwarded_parts(&v1_trap(), &trap_community).unwrap();
+            varbinds
+                .into_iter()
+                .find(|varbind| varbind.oid == oids::snmp_trap_community())
+                .map(|varbind| varbind.value)
+        };
+
+        assert_eq!(community_of(TrapCommunity::default()), None);
+        assert_eq!(
+            community_of(TrapCommunity::Replace(Bytes::from_static(b"redacted"))),
+            Some(Value::OctetString(Bytes::from_static(b"redacted")))
+        );
+    }
+}
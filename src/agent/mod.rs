@@ -71,17 +71,22 @@
 //! ```
 
 mod builtins;
+mod community_table;
+mod forwarder;
 mod notification;
+mod proxy;
 mod request;
 mod response;
 mod set_handler;
 pub mod vacm;
 
 pub use crate::handler::SecurityModel;
+pub use forwarder::{NotificationForwarder, TrapCommunity};
 pub use notification::{
     NotificationOutcome, NotificationSendStream, NotificationSinkId, NotificationSinkIdError,
     NotificationSinkSummary, SinkOutcome, SinkSkipReason, SinkStatus,
 };
+pub use proxy::{ProxyHandler, ProxyRoute};
 pub use vacm::{
     DuplicateVacmAccessEntry, VacmAccessIndex, VacmBuilder, VacmConfig, VacmSecurityModel, View,
     ViewSubtree,
@@ -89,19 +94,21 @@ pub use vacm::{
 
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 
 use bytes::Bytes;
 use futures_util::stream::{FuturesUnordered, StreamExt};
-use tokio::net::UdpSocket;
 use tokio::sync::Semaphore;
-use tokio::task::{AbortHandle, JoinError, JoinSet};
 use tokio_util::sync::CancellationToken;
 use tracing::instrument;
 
+use crate::credentials::{
+    ActiveCredentials, CredentialProvider, CredentialRefresh, CredentialRules, CredentialSource,
+    RotatingCredentials,
+};
 use crate::error::{ConstructionStage, Error, ErrorStatus, Result};
 use crate::handler::{
     GetNextResult, GetResult, HandlerResult, MibHandler, RequestContext, RequestLifecycle,
@@ -113,10 +120,14 @@ use crate::oid::Oid;
 #[cfg(test)]
 use crate::pdu::NotificationPdu;
 use crate::pdu::{Pdu, PduBody, PduType, ResponsePdu};
+use crate::runtime::{AbortHandle, JoinError, JoinSet, UdpSocket};
+use crate::security_event::{SecurityEventHub, SecurityEventReceiver};
 use crate::transport::normalize_udp_target;
 use crate::transport::udp_error::{
     UdpRecvErrorBackoff, UdpRecvErrorClass, classify_udp_recv_error,
 };
+#[cfg(unix)]
+use crate::transport::{UNIX_SOCKET_ADDR, UnixInbound, UnixReply, UnixSocketKind};
 use crate::udp_responder::{ReceivedDatagram, UdpResponder};
 use crate::util::{
     EmptyCommunityPolicy, PreparedAuthoritativeUsm, ValidatedAuthoritativeUsm, bind_udp_socket,
@@ -129,6 +140,8 @@ use crate::value::Value;
 use crate::varbind::VarBind;
 use crate::version::Version;
 
+use community_table::CommunityTable;
+
 /// Default maximum message size for UDP (RFC 3417 recommendation).
 const DEFAULT_MAX_MESSAGE_SIZE: usize = 1472;
 
@@ -320,10 +333,12 @@ fn v1_rejects_counter64(version: Version, value: &Value) -> bool {
 /// Built-in MIB handler groups that the agent registers automatically.
 ///
 /// By default, the agent registers handlers for standard SNMP MIB objects
-/// (engine parameters, USM statistics, MPD statistics). Use
+/// (engine parameters, USM statistics, MPD statistics, and the
+/// snmpCommunityTable when community entries are configured). Use
 /// [`AgentBuilder::without_builtin_handler`] to disable specific groups
 /// or [`AgentBuilder::without_builtin_handlers`] to disable all of them.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum BuiltinMib {
     /// snmpEngine scalars (1.3.6.1.6.3.10.2.1).
     ///
@@ -340,6 +355,12 @@ pub enum BuiltinMib {
     ///
     /// Provides snmpUnknownSecurityModels and snmpInvalidMsgs.
     MpdStats,
+    /// snmpCommunityTable (1.3.6.1.6.3.18.1.1).
+    ///
+    /// Read-only rows for entries added with
+    /// [`AgentBuilder::community_entry`]; registered only when at least one
+    /// entry is configured.
+    CommunityTable,
 }
 
 /// Shutdown handling for already-dispatched agent requests.
@@ -388,6 +409,24 @@ pub(crate) struct RegisteredHandler {
     pub(crate) handler: Arc<dyn MibHandler>,
 }
 
+/// Handler tree for one SNMPv3 context, configured by [`AgentBuilder::context`].
+#[derive(Default)]
+pub struct ContextBuilder {
+    handlers: Vec<RegisteredHandler>,
+}
+
+impl ContextBuilder {
+    /// Register a MIB handler for an OID subtree in this context.
+    ///
+    /// Matching follows [`AgentBuilder::handler`]: the longest registered
+    /// prefix wins.
+    #[must_use]
+    pub fn handler(mut self, prefix: Oid, handler: Arc<dyn MibHandler>) -> Self {
+        self.handlers.push(RegisteredHandler { prefix, handler });
+        self
+    }
+}
+
 /// Agent authorization policy selected by the builder.
 pub(crate) enum AgentAuthorization {
     Unset,
@@ -451,8 +490,11 @@ impl AgentAuthorization {
 pub struct AgentBuilder {
     bind_addr: String,
     communities: Vec<crate::Community>,
+    community_table: CommunityTable,
     usm_users: HashMap<Bytes, UsmUser>,
+    credentials: Option<CredentialSource>,
     handlers: Vec<RegisteredHandler>,
+    contexts: HashMap<Bytes, Vec<RegisteredHandler>>,
     authoritative_engine: Option<AuthoritativeEngine>,
     des_salt_state: Option<DesSaltState>,
     max_message_size: usize,
@@ -461,6 +503,7 @@ pub struct AgentBuilder {
     recv_buffer_size: Option<usize>,
     authorization: AgentAuthorization,
     cancel: Option<CancellationToken>,
+    proxy: Option<ProxyHandler>,
     trap_sinks: Vec<(NotificationSinkId, String, crate::client::Auth)>,
     trap_send_timeout: Duration,
     inform_timeout: Duration,
@@ -471,6 +514,8 @@ pub struct AgentBuilder {
     construction_timeout: Duration,
     notification_fanout_limit: usize,
     disabled_builtins: HashSet<BuiltinMib>,
+    #[cfg(unix)]
+    unix_listeners: Vec<(std::path::PathBuf, UnixSocketKind)>,
 }
 
 enum TrapSinkTarget {
@@ -491,8 +536,11 @@ struct ValidatedTrapSink {
 struct ValidatedAgentBuilder {
     bind_addr: SocketAddr,
     communities: Vec<crate::Community>,
+    community_table: CommunityTable,
     usm: ValidatedAuthoritativeUsm,
+    credentials: Option<(CredentialSource, CredentialRules)>,
     handlers: Vec<RegisteredHandler>,
+    contexts: HashMap<Bytes, Vec<RegisteredHandler>>,
     max_message_size: usize,
     local_receive_capacity: MessageSize,
     decode_config: crate::DecodeConfig,
@@ -500,6 +548,7 @@ struct ValidatedAgentBuilder {
     recv_buffer_size: Option<usize>,
     authorization: AgentAuthorization,
     cancel: CancellationToken,
+    proxy: Option<ProxyHandler>,
     trap_sinks: Vec<ValidatedTrapSink>,
     trap_send_timeout: Duration,
     inform_timeout: Duration,
@@ -512,6 +561,8 @@ struct ValidatedAgentBuilder {
     disabled_builtins: HashSet<BuiltinMib>,
     requires_privacy: bool,
     des_salt_state: Option<DesSaltState>,
+    #[cfg(unix)]
+    unix_listeners: Vec<(std::path::PathBuf, UnixSocketKind)>,
 }
 
 impl AgentBuilder {
@@ -529,8 +580,11 @@ impl AgentBuilder {
         Self {
             bind_addr: "0.0.0.0:161".to_string(),
             communities: Vec::new(),
+            community_table: CommunityTable::default(),
             usm_users: HashMap::new(),
+            credentials: None,
             handlers: Vec::new(),
+            contexts: HashMap::new(),
             authoritative_engine: None,
             des_salt_state: None,
             max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
@@ -539,6 +593,7 @@ impl AgentBuilder {
             recv_buffer_size: Some(4 * 1024 * 1024), // 4MB
             authorization: AgentAuthorization::Unset,
             cancel: None,
+            proxy: None,
             trap_sinks: Vec::new(),
             trap_send_timeout: crate::client::DEFAULT_SEND_TIMEOUT,
             inform_timeout: Duration::from_secs(5),
@@ -549,6 +604,8 @@ impl AgentBuilder {
             construction_timeout: crate::client::DEFAULT_CONSTRUCTION_TIMEOUT,
             notification_fanout_limit: 32,
             disabled_builtins: HashSet::new(),
+            #[cfg(unix)]
+            unix_listeners: Vec::new(),
         }
     }
 
@@ -595,6 +652,45 @@ impl AgentBuilder {
         self
     }
 
+    /// Also serve requests on a Unix domain socket at `path`.
+    ///
+    /// The UDP socket from [`bind`](Self::bind) is still bound; this adds a
+    /// listener. Stream sockets use the same BER framing as TCP, and
+    /// [`RequestContext::peer_credentials`] reports the connecting process's
+    /// uid, gid, and pid. Requests on Unix sockets report
+    /// [`UNIX_SOCKET_ADDR`] as their source.
+    ///
+    /// The socket file is created by [`build`](Self::build), which fails if
+    /// `path` already exists, and removed when the agent shuts down.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use async_snmp::agent::Agent;
+    /// use async_snmp::transport::UnixSocketKind;
+    ///
+    /// # async fn example() -> Result<(), Box<async_snmp::Error>> {
+    /// let agent = Agent::builder()
+    ///     .bind("127.0.0.1:1161")
+    ///     .listen_unix("/run/snmp-agent.sock", UnixSocketKind::Stream)
+    ///     .community(b"public")
+    ///     .allow_all_access()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(unix)]
+    #[must_use]
+    pub fn listen_unix(
+        mut self,
+        path: impl Into<std::path::PathBuf>,
+        kind: UnixSocketKind,
+    ) -> Self {
+        self.unix_listeners.push((path.into(), kind));
+        self
+    }
+
     /// Add an accepted community string for v1/v2c requests.
     ///
     /// Multiple communities can be added. If none are added,
@@ -652,6 +748,75 @@ impl AgentBuilder {
         self
     }
 
+    /// Add an snmpCommunityTable entry mapping a v1/v2c community to a
+    /// security name and context (RFC 3584 Section 5.2.1).
+    ///
+    /// A request carrying `community` is authorized by VACM as
+    /// `security_name` (reported by
+    /// [`RequestContext::security_name`](crate::handler::RequestContext::security_name))
+    /// and dispatched to the `context_name` handler tree registered with
+    /// [`context()`](Self::context). An empty `context_engine_id` selects the
+    /// local engine. A request mapped to a context this agent does not serve
+    /// is counted in snmpUnknownContexts and dropped.
+    ///
+    /// A non-empty `transport_tag` limits the entry to source addresses added
+    /// with [`tagged_address()`](Self::tagged_address); an empty tag accepts
+    /// any source. When several entries match, the first one added wins.
+    /// Entries are consulted before plain [`community()`](Self::community)
+    /// strings.
+    ///
+    /// Entries are served read-only as the snmpCommunityTable, indexed "1",
+    /// "2", ... in call order. The snmpCommunityName column is not served.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use async_snmp::agent::Agent;
+    /// use bytes::Bytes;
+    ///
+    /// # async fn example() -> Result<(), Box<async_snmp::Error>> {
+    /// // An empty context engine ID selects the local engine.
+    /// let agent = Agent::builder()
+    ///     .bind("0.0.0.0:1161")
+    ///     .community_entry(b"public@10", "bridge", "vlan-10", Bytes::new(), "mgmt")
+    ///     .tagged_address("mgmt", "192.0.2.10".parse().unwrap())
+    ///     .context("vlan-10", |ctx| ctx)
+    ///     .allow_all_access()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn community_entry(
+        mut self,
+        community: impl Into<crate::Community>,
+        security_name: impl Into<Bytes>,
+        context_name: impl Into<Bytes>,
+        context_engine_id: impl Into<Bytes>,
+        transport_tag: impl Into<Bytes>,
+    ) -> Self {
+        self.community_table.push(
+            community.into(),
+            security_name.into(),
+            context_name.into(),
+            context_engine_id.into(),
+            transport_tag.into(),
+        );
+        self
+    }
+
+    /// Add a source address to a transport tag used by
+    /// [`community_entry()`](Self::community_entry).
+    ///
+    /// The source port is ignored, and IPv4-mapped IPv6 sources match their
+    /// IPv4 address.
+    #[must_use]
+    pub fn tagged_address(mut self, tag: impl Into<Bytes>, address: IpAddr) -> Self {
+        self.community_table.tag_address(tag.into(), address);
+        self
+    }
+
     /// Add a USM user for `SNMPv3` authentication.
     ///
     /// Configure supported authentication and privacy mechanisms using the
@@ -714,6 +879,47 @@ impl AgentBuilder {
         Ok(self)
     }
 
+    /// Accept communities and USM users from a rotating credential provider.
+    ///
+    /// The provider is fetched once by [`build`](Self::build), which fails if
+    /// the fetch does, and then every [`CredentialRefresh::interval`]. After
+    /// a rotation the previous credentials stay accepted for
+    /// [`CredentialRefresh::overlap`]. Communities and users configured with
+    /// [`community`](Self::community) and [`usm_user`](Self::usm_user) remain
+    /// accepted throughout. Provided USM users require
+    /// [`authoritative_engine`](Self::authoritative_engine), and DES/3DES
+    /// users also require [`des_salt_state`](Self::des_salt_state); a
+    /// rotation violating either is rejected and logged.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use async_snmp::agent::Agent;
+    /// use async_snmp::credentials::{CredentialRefresh, PollingFileCredentialProvider};
+    ///
+    /// # async fn example() -> Result<(), Box<async_snmp::Error>> {
+    /// let agent = Agent::builder()
+    ///     .bind("0.0.0.0:1161")
+    ///     .credential_provider(
+    ///         PollingFileCredentialProvider::new("agent-credentials.conf"),
+    ///         CredentialRefresh::default(),
+    ///     )
+    ///     .allow_all_access()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn credential_provider(
+        mut self,
+        provider: impl CredentialProvider,
+        refresh: CredentialRefresh,
+    ) -> Self {
+        self.credentials = Some(CredentialSource::new(provider, refresh));
+        self
+    }
+
     /// Set the persisted local authoritative engine state for `SNMPv3`.
     ///
     /// An agent with USM users or V3 trap sinks requires this value. Construct
@@ -886,6 +1092,51 @@ impl AgentBuilder {
         self
     }
 
+    /// Register a separate handler tree for an SNMPv3 context.
+    ///
+    /// SNMPv3 requests are routed by the scopedPDU contextName: the empty
+    /// name selects the default tree built with [`handler()`](Self::handler),
+    /// and any other name selects the tree registered here. Requests naming a
+    /// context that was never registered are counted in snmpUnknownContexts
+    /// and answered with a Report. Community-based requests always use the
+    /// default context.
+    ///
+    /// Built-in handlers serve only the default context. Calling this more
+    /// than once with the same name adds to that context's tree; the empty
+    /// name adds to the default tree. Names are SnmpAdminStrings of at most
+    /// 32 octets.
+    ///
+    /// VACM access entries select contexts with
+    /// [`context_prefix`](vacm::AccessEntryBuilder::context_prefix).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use async_snmp::agent::Agent;
+    /// # use async_snmp::handler::MibHandler;
+    /// # use async_snmp::oid;
+    /// # use std::sync::Arc;
+    /// # fn example(red: Arc<dyn MibHandler>, blue: Arc<dyn MibHandler>) {
+    /// let builder = Agent::builder()
+    ///     .context("vrf-red", |ctx| ctx.handler(oid!(1, 3, 6, 1, 2, 1, 4), red))
+    ///     .context("vrf-blue", |ctx| ctx.handler(oid!(1, 3, 6, 1, 2, 1, 4), blue));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn context<F>(mut self, name: impl Into<Bytes>, configure: F) -> Self
+    where
+        F: FnOnce(ContextBuilder) -> ContextBuilder,
+    {
+        let name = name.into();
+        let handlers = configure(ContextBuilder::default()).handlers;
+        if name.is_empty() {
+            self.handlers.extend(handlers);
+        } else {
+            self.contexts.entry(name).or_default().extend(handlers);
+        }
+        self
+    }
+
     /// Configure VACM (View-based Access Control Model) using a builder function.
     ///
     /// When VACM is configured, all requests are checked against the configured
@@ -975,6 +1226,17 @@ impl AgentBuilder {
         self
     }
 
+    /// Forward requests for the contexts routed by `proxy` to downstream
+    /// devices (RFC 3413 Section 3.5).
+    ///
+    /// The table can be changed through any clone of `proxy` while the agent
+    /// runs. See [`ProxyHandler`] for routing and version translation.
+    #[must_use]
+    pub fn proxy(mut self, proxy: ProxyHandler) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
     /// Add a trap/inform destination.
     ///
     /// [`Agent::send_trap()`] and [`Agent::send_inform()`] send notifications
@@ -1131,8 +1393,8 @@ impl AgentBuilder {
     /// Disable a specific built-in MIB handler group.
     ///
     /// By default, the agent registers handlers for snmpEngine, USM stats,
-    /// and MPD stats. Disable a group when the application provides its own
-    /// handler for those OIDs.
+    /// MPD stats, and the snmpCommunityTable. Disable a group when the
+    /// application provides its own handler for those OIDs.
     #[must_use]
     pub fn without_builtin_handler(mut self, mib: BuiltinMib) -> Self {
         self.disabled_builtins.insert(mib);
@@ -1142,13 +1404,14 @@ impl AgentBuilder {
     /// Disable all built-in MIB handlers.
     ///
     /// The agent does not register internal handlers for snmpEngine, USM
-    /// stats, or MPD stats. Counter accessors such as
+    /// stats, MPD stats, or the snmpCommunityTable. Counter accessors such as
     /// [`Agent::usm_unknown_engine_ids()`] remain available.
     #[must_use]
     pub fn without_builtin_handlers(mut self) -> Self {
         self.disabled_builtins.insert(BuiltinMib::SnmpEngine);
         self.disabled_builtins.insert(BuiltinMib::UsmStats);
         self.disabled_builtins.insert(BuiltinMib::MpdStats);
+        self.disabled_builtins.insert(BuiltinMib::CommunityTable);
         self
     }
 
@@ -1172,9 +1435,8 @@ impl AgentBuilder {
         self.build_with_dependencies(
             |addr, recv_buffer_size| bind_udp_socket(addr, recv_buffer_size, None, false),
             |host, port| async move {
-                tokio::net::lookup_host((host.as_str(), port))
+                crate::runtime::lookup_host(&host, port)
                     .await
-                    .map(|addresses| addresses.collect())
                     .map_err(|error| {
                         Error::Config(
                             format!("could not resolve trap sink address '{host}': {error}").into(),
@@ -1231,6 +1493,32 @@ impl AgentBuilder {
             target: config.bind_addr,
             source,
         })?;
+        #[cfg(unix)]
+        let unix_ingress = if config.unix_listeners.is_empty() {
+            None
+        } else {
+            let (ingress, receiver) = tokio::sync::mpsc::channel(UNIX_INGRESS_CAPACITY);
+            for (path, kind) in &config.unix_listeners {
+                crate::transport::spawn_unix_listener(
+                    path,
+                    *kind,
+                    UDP_RECEIVE_BUFFER_SIZE,
+                    ingress.clone(),
+                    config.cancel.clone(),
+                )
+                .map_err(|source| {
+                    Error::Network {
+                        target: UNIX_SOCKET_ADDR,
+                        source: std::io::Error::new(
+                            source.kind(),
+                            format!("{}: {source}", path.display()),
+                        ),
+                    }
+                    .boxed()
+                })?;
+            }
+            Some(tokio::sync::Mutex::new(receiver))
+        };
 
         let mut trap_sinks = Vec::with_capacity(config.trap_sinks.len());
         for (index, sink) in config.trap_sinks.into_iter().enumerate() {
@@ -1277,6 +1565,20 @@ impl AgentBuilder {
             ));
         }
 
+        let credentials = match config.credentials {
+            Some((source, rules)) => Some(
+                construction
+                    .run(
+                        ConstructionStage::Prepare,
+                        None,
+                        None,
+                        RotatingCredentials::load(source, rules),
+                    )
+                    .await?,
+            ),
+            None => None,
+        };
+
         let PreparedAuthoritativeUsm {
             users: usm_users,
             authoritative_engine,
@@ -1308,6 +1610,7 @@ impl AgentBuilder {
             snmp_unknown_security_models: AtomicU32::new(0),
             snmp_silent_drops: AtomicU32::new(0),
             snmp_unknown_contexts: AtomicU32::new(0),
+            snmp_proxy_drops: AtomicU32::new(0),
             usm_stats: UsmStats::default(),
             health: tokio::sync::watch::channel(AgentHealth::Healthy).0,
         });
@@ -1337,11 +1640,28 @@ impl AgentBuilder {
                 }),
             });
         }
+        let community_table = Arc::new(config.community_table);
+        if !community_table.is_empty()
+            && !config
+                .disabled_builtins
+                .contains(&BuiltinMib::CommunityTable)
+        {
+            config.handlers.push(RegisteredHandler {
+                prefix: oid!(1, 3, 6, 1, 6, 3, 18, 1, 1),
+                handler: Arc::new(builtins::CommunityTableHandler::new(
+                    Arc::clone(&state),
+                    Arc::clone(&community_table),
+                )),
+            });
+        }
 
         // Sort handlers by prefix length (longest first) for matching
         config
             .handlers
             .sort_by_key(|h| std::cmp::Reverse(h.prefix.len()));
+        for handlers in config.contexts.values_mut() {
+            handlers.sort_by_key(|h| std::cmp::Reverse(h.prefix.len()));
+        }
 
         let agent = Agent {
             inner: Arc::new(AgentInner {
@@ -1349,8 +1669,13 @@ impl AgentBuilder {
                 udp_responder,
                 local_addr,
                 communities: config.communities,
+                community_table,
                 usm_users,
+                credentials: credentials
+                    .map(|credentials| ActiveCredentials::start(credentials, false)),
+                security_events: SecurityEventHub::new(),
                 handlers: config.handlers,
+                contexts: config.contexts,
                 state,
                 salt_counter,
                 des_salt_state: config.des_salt_state,
@@ -1358,6 +1683,7 @@ impl AgentBuilder {
                 concurrency_limit: config.concurrency_limit,
                 authorization: config.authorization,
                 cancel: config.cancel,
+                proxy: config.proxy,
                 trap_sinks,
                 inform_transports: notification::InformTransportPool::new(),
                 notification_id: std::sync::atomic::AtomicI32::new(1),
@@ -1370,6 +1696,8 @@ impl AgentBuilder {
                 request_deadline: config.request_deadline,
                 shutdown_policy: config.shutdown_policy,
                 notification_fanout_limit: config.notification_fanout_limit,
+                #[cfg(unix)]
+                unix_ingress,
                 #[cfg(test)]
                 response_send_gate: std::sync::Mutex::new(None),
                 #[cfg(test)]
@@ -1390,8 +1718,9 @@ impl AgentBuilder {
 
     fn validate_and_normalize(mut self) -> Result<ValidatedAgentBuilder> {
         // Stable validation precedence: deadline representability (which reads
-        // the monotonic clock), authorization, sink IDs, sizes/concurrency,
-        // security configuration, then address syntax and family.
+        // the monotonic clock), context names, authorization, sink IDs,
+        // sizes/concurrency, security configuration, then address syntax and
+        // family.
         crate::transport::checked_deadline(self.trap_send_timeout, "trap send timeout")?;
         crate::transport::checked_deadline(self.inform_timeout, "inform timeout")?;
         crate::transport::checked_deadline(self.response_send_timeout, "response send timeout")?;
@@ -1408,6 +1737,16 @@ impl AgentBuilder {
             crate::transport::checked_deadline(grace, "agent shutdown grace")?;
         }
 
+        if let Some(name) = self.contexts.keys().find(|name| name.len() > 32) {
+            return Err(Error::Config(
+                format!(
+                    "context name {} exceeds 32 octets",
+                    String::from_utf8_lossy(name)
+                )
+                .into(),
+            )
+            .boxed());
+        }
         if matches!(self.authorization, AgentAuthorization::Conflict) {
             return Err(Error::Config(
                 "VACM and unrestricted Agent access are mutually exclusive".into(),
@@ -1417,7 +1756,11 @@ impl AgentBuilder {
         if let AgentAuthorization::InvalidVacm(error) = &self.authorization {
             return Err(Error::Config(error.to_string().into()).boxed());
         }
-        if (!self.communities.is_empty() || !self.usm_users.is_empty())
+        self.community_table.validate()?;
+        if (!self.communities.is_empty()
+            || !self.community_table.is_empty()
+            || !self.usm_users.is_empty()
+            || self.credentials.is_some())
             && matches!(self.authorization, AgentAuthorization::Unset)
         {
             return Err(Error::Config(
@@ -1426,13 +1769,25 @@ impl AgentBuilder {
             .boxed());
         }
 
+        if let Some(credentials) = &self.credentials {
+            credentials.validate()?;
+        }
+
         let mut sink_ids = HashSet::with_capacity(self.trap_sinks.len());
-        for (id, _, _) in &self.trap_sinks {
+        for (id, _, auth) in &self.trap_sinks {
             if !sink_ids.insert(id.clone()) {
                 return Err(
                     Error::Config(format!("duplicate notification sink ID: {id}").into()).boxed(),
                 );
             }
+            // Agent sinks send over UDP, which cannot carry TSM.
+            if matches!(auth, crate::client::Auth::Tsm(_)) {
+                return Err(Error::Config(
+                    format!("notification sink {id} uses TSM, which requires a secure transport")
+                        .into(),
+                )
+                .boxed());
+            }
         }
 
         let local_receive_capacity = UDP_RECEIVE_LIMITS.advertised();
@@ -1499,6 +1854,14 @@ impl AgentBuilder {
                 })?;
             }
         }
+        let credentials = self.credentials.map(|source| {
+            let rules = CredentialRules {
+                usm: self.authoritative_engine.is_some(),
+                des: self.des_salt_state.is_some(),
+            };
+            (source, rules)
+        });
+        let uses_aes = uses_aes || credentials.is_some();
         let usm = validate_authoritative_usm_deferred(
             self.usm_users,
             self.authoritative_engine,
@@ -1531,8 +1894,11 @@ impl AgentBuilder {
         Ok(ValidatedAgentBuilder {
             bind_addr,
             communities: self.communities,
+            community_table: self.community_table,
             usm,
+            credentials,
             handlers: self.handlers,
+            contexts: self.contexts,
             max_message_size: self.max_message_size,
             local_receive_capacity,
             decode_config: self.decode_config,
@@ -1540,6 +1906,7 @@ impl AgentBuilder {
             recv_buffer_size: self.recv_buffer_size,
             authorization: self.authorization,
             cancel: self.cancel.unwrap_or_default(),
+            proxy: self.proxy,
             trap_sinks,
             trap_send_timeout: self.trap_send_timeout,
             inform_timeout: self.inform_timeout,
@@ -1550,6 +1917,8 @@ impl AgentBuilder {
             construction_timeout: self.construction_timeout,
             notification_fanout_limit: self.notification_fanout_limit,
             disabled_builtins: self.disabled_builtins,
+            #[cfg(unix)]
+            unix_listeners: self.unix_listeners,
             requires_privacy: uses_aes,
             des_salt_state: self.des_salt_state,
         })
@@ -1587,7 +1956,7 @@ impl AgentConstructionDeadline {
         F: Future<Output = Result<T>>,
     {
         self.check(stage, sink_index, sink_destination.clone())?;
-        tokio::time::timeout_at(self.deadline, future)
+        crate::runtime::timeout_at(self.deadline, future)
             .await
             .map_err(|_| self.timeout_error(stage, sink_index, sink_destination))?
     }
@@ -1700,6 +2069,9 @@ pub(crate) struct AgentState {
     /// snmpUnknownContexts (1.3.6.1.6.3.12.1.5) - requests whose scopedPDU
     /// contextEngineID did not name a context served by this engine
     pub(crate) snmp_unknown_contexts: AtomicU32,
+    /// snmpProxyDrops (1.3.6.1.2.1.11.32) - proxied requests dropped because
+    /// the downstream exchange failed
+    pub(crate) snmp_proxy_drops: AtomicU32,
     /// RFC 3414 usmStats counters
     pub(crate) usm_stats: UsmStats,
     /// Bounded/coalescing current-health publication.
@@ -1782,14 +2154,56 @@ impl AgentState {
     }
 }
 
+/// Bound on requests queued between Unix-domain listeners and the service loop.
+#[cfg(unix)]
+const UNIX_INGRESS_CAPACITY: usize = 256;
+
+/// A request accepted by the service loop.
+enum Inbound {
+    Udp(ReceivedDatagram),
+    #[cfg(unix)]
+    Unix(UnixInbound),
+}
+
+/// Where the response to a request is sent.
+enum ResponseRoute {
+    Udp(ReceivedDatagram),
+    #[cfg(unix)]
+    Unix(UnixReply),
+}
+
+#[cfg(unix)]
+async fn recv_unix(
+    ingress: &mut Option<tokio::sync::MutexGuard<'_, tokio::sync::mpsc::Receiver<UnixInbound>>>,
+) -> Option<Inbound> {
+    match ingress {
+        Some(receiver) => receiver.recv().await.map(Inbound::Unix),
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn recv_unix(_ingress: &mut Option<()>) -> Option<Inbound> {
+    std::future::pending().await
+}
+
 /// Inner state shared across agent clones.
 pub(crate) struct AgentInner {
     pub(crate) socket: Arc<UdpSocket>,
     pub(crate) udp_responder: UdpResponder,
     pub(crate) local_addr: SocketAddr,
     pub(crate) communities: Vec<crate::Community>,
+    /// Community-to-context mapping (snmpCommunityTable).
+    pub(crate) community_table: Arc<CommunityTable>,
     pub(crate) usm_users: HashMap<Bytes, UsmUser>,
+    /// Rotating communities and USM users from a credential provider.
+    pub(crate) credentials: Option<ActiveCredentials>,
+    /// Audit subscribers for rejected requests.
+    pub(crate) security_events: SecurityEventHub,
+    /// Handler tree for the default (empty) context.
     pub(crate) handlers: Vec<RegisteredHandler>,
+    /// Handler trees for named SNMPv3 contexts.
+    pub(crate) contexts: HashMap<Bytes, Vec<RegisteredHandler>>,
     pub(crate) state: Arc<AgentState>,
     pub(crate) salt_counter: Option<SaltCounter>,
     pub(crate) des_salt_state: Option<DesSaltState>,
@@ -1799,6 +2213,8 @@ pub(crate) struct AgentInner {
     pub(crate) authorization: AgentAuthorization,
     /// Cancellation token for graceful shutdown.
     pub(crate) cancel: CancellationToken,
+    /// Forwarding table for proxied requests.
+    pub(crate) proxy: Option<ProxyHandler>,
     /// Configured trap/inform destinations.
     pub(crate) trap_sinks: Vec<notification::TrapSink>,
     /// Inform endpoints shared by all sinks of each destination family.
@@ -1818,6 +2234,9 @@ pub(crate) struct AgentInner {
     pub(crate) response_send_timeout: Duration,
     request_deadline: Option<Duration>,
     shutdown_policy: AgentShutdownPolicy,
+    /// Requests from Unix-domain listeners, drained by the service loop.
+    #[cfg(unix)]
+    unix_ingress: Option<tokio::sync::Mutex<tokio::sync::mpsc::Receiver<UnixInbound>>>,
     #[cfg(test)]
     pub(crate) response_send_gate: std::sync::Mutex<Option<Arc<Semaphore>>>,
     #[cfg(test)]
@@ -1883,6 +2302,22 @@ impl Agent {
         self.inner.trap_sinks.iter().map(|sink| &sink.summary)
     }
 
+    /// Fetch from the credential provider now, returning whether a new
+    /// revision was installed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] when no provider is configured or the
+    /// fetched credentials are invalid, and the provider's error when the
+    /// fetch fails. The installed credentials stay in place on error.
+    pub async fn refresh_credentials(&self) -> Result<bool> {
+        let credentials =
+            self.inner.credentials.as_ref().ok_or_else(|| {
+                Error::Config("no credential provider is configured".into()).boxed()
+            })?;
+        credentials.refresh().await
+    }
+
     /// Returns the local engine ID.
     ///
     /// With an [`AuthoritativeEngine`] this is the stable persisted V3
@@ -1923,6 +2358,18 @@ impl Agent {
         self.inner.state.health.subscribe()
     }
 
+    /// Observe rejected requests as structured [`SecurityEvent`](crate::SecurityEvent)s.
+    ///
+    /// Events cover unknown communities, USM failures other than unknown
+    /// engine IDs, VACM denials, and unknown contexts. Each subscriber gets
+    /// its own queue of `capacity` events (at least one); events arriving
+    /// while it is full are dropped and counted in
+    /// [`SecurityEventReceiver::dropped`]. See [`crate::security_event`].
+    #[must_use]
+    pub fn subscribe_security_events(&self, capacity: usize) -> SecurityEventReceiver {
+        self.inner.security_events.subscribe(capacity)
+    }
+
     /// Initiate graceful shutdown.
     pub fn cancel(&self) {
         self.inner.cancel.cancel();
@@ -2005,10 +2452,10 @@ impl Agent {
 
     /// Returns the snmpUnknownContexts counter value.
     ///
-    /// This counter tracks requests whose scopedPDU contextEngineID did not
-    /// name a context served by this engine (RFC 3413 Section 3.2). Such
-    /// requests are answered with a Report PDU rather than dispatched against
-    /// the local MIB.
+    /// This counter tracks requests whose contextEngineID or contextName did
+    /// not name a context served by this engine (RFC 3413 Section 3.2). SNMPv3
+    /// requests are answered with a Report PDU; community-based requests
+    /// mapped to such a context are dropped.
     ///
     /// OID: 1.3.6.1.6.3.12.1.5
     #[must_use]
@@ -2019,6 +2466,17 @@ impl Agent {
             .load(Ordering::Relaxed)
     }
 
+    /// Returns the snmpProxyDrops counter value.
+    ///
+    /// This counter tracks proxied requests that were not answered because
+    /// the downstream exchange timed out or failed (RFC 3413 Section 3.5).
+    ///
+    /// OID: 1.3.6.1.2.1.11.32
+    #[must_use]
+    pub fn snmp_proxy_drops(&self) -> u32 {
+        self.inner.state.snmp_proxy_drops.load(Ordering::Relaxed)
+    }
+
     /// Returns the usmStatsUnknownEngineIDs counter value.
     ///
     /// This counter tracks messages with unknown engine IDs.
@@ -2168,6 +2626,14 @@ impl Agent {
         let mut request_tasks =
             RequestTasks::new(self.inner.cancel.child_token(), Arc::clone(&self.inner));
         let mut recv_error_backoff = UdpRecvErrorBackoff::default();
+        // `run_active` admits one service loop, so this lock is uncontended.
+        #[cfg(unix)]
+        let mut unix_ingress = match &self.inner.unix_ingress {
+            Some(ingress) => Some(ingress.lock().await),
+            None => None,
+        };
+        #[cfg(not(unix))]
+        let mut unix_ingress = None;
 
         let log_task_result = |result: std::result::Result<(), JoinError>| {
             if let Err(error) = result {
@@ -2180,7 +2646,7 @@ impl Agent {
         };
 
         let run_result = 'service: loop {
-            let recv_meta = loop {
+            let inbound = loop {
                 tokio::select! {
                     biased;
                     () = self.inner.cancel.cancelled() => {
@@ -2196,7 +2662,7 @@ impl Agent {
                         match result {
                             Ok(recv_meta) => {
                                 recv_error_backoff.reset();
-                                break recv_meta;
+                                break Inbound::Udp(recv_meta);
                             }
                             Err(error) => match classify_udp_recv_error(&error) {
                                 UdpRecvErrorClass::DatagramLocal => {
@@ -2212,7 +2678,7 @@ impl Agent {
                                             tracing::info!(target: "async_snmp::agent", "agent shutdown requested");
                                             break 'service Ok(());
                                         }
-                                        () = tokio::time::sleep(delay) => {}
+                                        () = crate::runtime::sleep(delay) => {}
                                     }
                                 }
                                 UdpRecvErrorClass::Fatal => {
@@ -2225,6 +2691,7 @@ impl Agent {
                             },
                         }
                     }
+                    Some(inbound) = recv_unix(&mut unix_ingress) => break inbound,
                 }
             };
 
@@ -2250,10 +2717,22 @@ impl Agent {
                     .forget();
             }
 
-            let data = Bytes::copy_from_slice(&buf[..recv_meta.len]);
-            if data.len() > UDP_RECEIVE_LIMITS.advertised().as_usize() {
-                tracing::debug!(target: "async_snmp::agent", { snmp.source = %recv_meta.source, received_size = data.len(), advertised_size = UDP_RECEIVE_LIMITS.advertised().as_usize() }, "accepted bounded UDP datagram above advertised capacity");
-            }
+            let (data, source, route, peer_credentials) = match inbound {
+                Inbound::Udp(recv_meta) => {
+                    let data = Bytes::copy_from_slice(&buf[..recv_meta.len]);
+                    if data.len() > UDP_RECEIVE_LIMITS.advertised().as_usize() {
+                        tracing::debug!(target: "async_snmp::agent", { snmp.source = %recv_meta.source, received_size = data.len(), advertised_size = UDP_RECEIVE_LIMITS.advertised().as_usize() }, "accepted bounded UDP datagram above advertised capacity");
+                    }
+                    (data, recv_meta.source, ResponseRoute::Udp(recv_meta), None)
+                }
+                #[cfg(unix)]
+                Inbound::Unix(inbound) => (
+                    inbound.data,
+                    UNIX_SOCKET_ADDR,
+                    ResponseRoute::Unix(inbound.reply),
+                    inbound.peer_credentials,
+                ),
+            };
             let agent = self.clone();
 
             let permit = if let Some(ref sem) = self.inner.concurrency_limit {
@@ -2287,36 +2766,37 @@ impl Agent {
                 deadline,
                 request_cancel.clone(),
                 phase.clone(),
-            );
+            )
+            .with_peer_credentials(peer_credentials);
             request_tasks.spawn(phase, async move {
                 let _permit = permit;
 
                 let request = async {
                     match agent
-                        .handle_request_with_lifecycle(data, recv_meta.source, lifecycle)
+                        .handle_request_with_lifecycle(data, source, lifecycle)
                         .await
                     {
                         Ok(Some(response_bytes)) => {
-                            if let Err(e) = agent.send_response(&response_bytes, &recv_meta).await {
-                                tracing::warn!(target: "async_snmp::agent", { snmp.source = %recv_meta.source, error = %e }, "failed to send response");
+                            if let Err(e) = agent.send_response(&response_bytes, &route).await {
+                                tracing::warn!(target: "async_snmp::agent", { snmp.source = %source, error = %e }, "failed to send response");
                             }
                         }
                         Ok(None) => {}
                         Err(e) => {
-                            tracing::warn!(target: "async_snmp::agent", { snmp.source = %recv_meta.source, error = %e }, "error handling request");
+                            tracing::warn!(target: "async_snmp::agent", { snmp.source = %source, error = %e }, "error handling request");
                         }
                     }
                 };
                 tokio::pin!(request);
                 if let Some(deadline) = deadline {
                     if tokio::time::Instant::now() >= deadline {
-                        tracing::debug!(target: "async_snmp::agent", { snmp.source = %recv_meta.source }, "request deadline expired before admission");
+                        tracing::debug!(target: "async_snmp::agent", { snmp.source = %source }, "request deadline expired before admission");
                         request_cancel.cancel();
                         request.await;
                     } else {
                         tokio::select! {
-                            () = tokio::time::sleep_until(deadline) => {
-                                tracing::debug!(target: "async_snmp::agent", { snmp.source = %recv_meta.source }, "request deadline expired");
+                            () = crate::runtime::sleep_until(deadline) => {
+                                tracing::debug!(target: "async_snmp::agent", { snmp.source = %source }, "request deadline expired");
                                 request_cancel.cancel();
                                 request.await;
                             }
@@ -2350,7 +2830,7 @@ impl Agent {
                                 log_task_result(result);
                             }
                         }
-                        () = tokio::time::sleep_until(deadline) => {
+                        () = crate::runtime::sleep_until(deadline) => {
                             request_tasks.abort_retrievals();
                             break;
                         }
@@ -2377,11 +2857,7 @@ impl Agent {
         self.inner.udp_responder.recv(&self.inner.socket, buf).await
     }
 
-    async fn send_response(
-        &self,
-        data: &[u8],
-        recv_meta: &ReceivedDatagram,
-    ) -> std::io::Result<()> {
+    async fn send_response(&self, data: &[u8], route: &ResponseRoute) -> std::io::Result<()> {
         let deadline = tokio::time::Instant::now()
             .checked_add(self.inner.response_send_timeout)
             .expect("response send timeout validated by builder");
@@ -2394,7 +2870,7 @@ impl Agent {
         let response_send_gate = self.inner.response_send_gate.lock().unwrap().clone();
         tokio::select! {
             biased;
-            () = tokio::time::sleep_until(deadline) => Err(timeout_error()),
+            () = crate::runtime::sleep_until(deadline) => Err(timeout_error()),
             result = async {
                 #[cfg(test)]
                 self.inner.response_sends_started.fetch_add(1, Ordering::Relaxed);
@@ -2402,7 +2878,13 @@ impl Agent {
                 if let Some(gate) = &response_send_gate {
                     gate.acquire().await.expect("test send gate remains open").forget();
                 }
-                self.inner.udp_responder.reply(&self.inner.socket, data, recv_meta).await
+                match route {
+                    ResponseRoute::Udp(recv_meta) => {
+                        self.inner.udp_responder.reply(&self.inner.socket, data, recv_meta).await
+                    }
+                    #[cfg(unix)]
+                    ResponseRoute::Unix(reply) => reply.send(data).await,
+                }
             } => result,
         }
     }
@@ -2476,8 +2958,17 @@ impl Agent {
     /// Uses constant-time comparison to prevent timing attacks that could
     /// be used to guess valid community strings character by character.
     pub(crate) fn validate_community(&self, community: &[u8]) -> bool {
+        let credentials = self
+            .inner
+            .credentials
+            .as_ref()
+            .map(ActiveCredentials::snapshot);
         community_matches(
-            &self.inner.communities,
+            self.inner.communities.iter().chain(
+                credentials
+                    .iter()
+                    .flat_map(|snapshot| snapshot.communities()),
+            ),
             community,
             EmptyCommunityPolicy::Deny,
         )
@@ -2548,12 +3039,12 @@ impl Agent {
     /// candidate and the final response are authoritatively decided by exact
     /// message-envelope encoding.
     fn response_overhead(&self, ctx: &RequestContext) -> usize {
-        if ctx.version() != Version::V3 {
+        if let Some(community) = ctx.community() {
             // v1/v2c echo the request's community string in the response
             // wrapper. A long, operator-configured community can otherwise
             // push the encoded Response past the size limit after
             // response_fits has already accepted it.
-            return RESPONSE_OVERHEAD + ctx.security_name().len();
+            return RESPONSE_OVERHEAD + community.as_bytes().len();
         }
         let mut overhead = RESPONSE_OVERHEAD
             + 2 * self.inner.state.engine_id.len()
@@ -2590,7 +3081,7 @@ impl Agent {
                 continue;
             }
 
-            let result = if let Some(handler) = self.find_handler(&vb.oid) {
+            let result = if let Some(handler) = self.find_handler(ctx.context_name(), &vb.oid) {
                 match handler.handler.get(ctx, &vb.oid).await {
                     Ok(result) => result,
                     Err(err) => {
@@ -2831,11 +3322,31 @@ impl Agent {
         Ok(ResponsePdu::success(ctx.version(), pdu.request_id, response_varbinds)?.into_raw())
     }
 
-    /// Find the handler for a given OID.
-    pub(crate) fn find_handler(&self, oid: &Oid) -> Option<&RegisteredHandler> {
+    /// Return whether this agent serves the named context.
+    pub(crate) fn serves_context(&self, context_name: &[u8]) -> bool {
+        context_name.is_empty() || self.inner.contexts.contains_key(context_name)
+    }
+
+    /// Handler tree for a context; empty for contexts that are not served.
+    fn context_handlers(&self, context_name: &[u8]) -> &[RegisteredHandler] {
+        if context_name.is_empty() {
+            &self.inner.handlers
+        } else {
+            self.inner
+                .contexts
+                .get(context_name)
+                .map_or(&[], Vec::as_slice)
+        }
+    }
+
+    /// Find the handler for a given OID in a context.
+    pub(crate) fn find_handler(
+        &self,
+        context_name: &[u8],
+        oid: &Oid,
+    ) -> Option<&RegisteredHandler> {
         // Handlers are sorted by prefix length (longest first)
-        self.inner
-            .handlers
+        self.context_handlers(context_name)
             .iter()
             .find(|&handler| handler.handler.handles(&handler.prefix, oid))
             .map(|v| v as _)
@@ -2890,7 +3401,7 @@ impl Agent {
     ) -> HandlerResult<Option<VarBind>> {
         // Custom `handles` implementations may establish non-prefix ownership,
         // so a registration prefix cannot safely prune this minimum scan.
-        let handlers = &self.inner.handlers;
+        let handlers = self.context_handlers(ctx.context_name());
         let mut best_result: Option<VarBind> = None;
         let mut completed = std::iter::repeat_with(|| None)
             .take(handlers.len())
@@ -3885,6 +4396,79 @@ mod tests {
         run_task.await.unwrap().unwrap();
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_stream_listener_exposes_peer_credentials() {
+        struct PeerUidHandler;
+
+        impl MibHandler for PeerUidHandler {
+            fn get<'a>(
+                &'a self,
+                ctx: &'a RequestContext,
+                _oid: &'a Oid,
+            ) -> BoxFuture<'a, HandlerResult<GetResult>> {
+                Box::pin(async move {
+                    assert_eq!(ctx.source(), UNIX_SOCKET_ADDR);
+                    Ok(match ctx.peer_credentials() {
+                        Some(credentials) => GetResult::Value(Value::Gauge32(credentials.uid)),
+                        None => GetResult::NoSuchObject,
+                    })
+                })
+            }
+
+            fn get_next<'a>(
+                &'a self,
+                _ctx: &'a RequestContext,
+                _oid: &'a Oid,
+            ) -> BoxFuture<'a, HandlerResult<GetNextResult>> {
+                Box::pin(async { Ok(GetNextResult::EndOfMibView) })
+            }
+        }
+
+        let path = std::env::temp_dir().join(format!(
+            "async-snmp-agent-{}-{}.sock",
+            std::process::id(),
+            line!()
+        ));
+        let agent = Agent::builder()
+            .bind("127.0.0.1:0")
+            .listen_unix(&path, UnixSocketKind::Stream)
+            .community(b"public")
+            .handler(oid!(1, 3, 6, 1, 4, 1, 99999), Arc::new(PeerUidHandler))
+            .without_builtin_handlers()
+            .allow_all_access()
+            .build()
+            .await
+            .unwrap();
+        let run_agent = agent.clone();
+        let run_task = tokio::spawn(async move { run_agent.run().await });
+
+        let transport = crate::transport::UnixTransport::connect(&path, UnixSocketKind::Stream)
+            .await
+            .unwrap();
+        let client = crate::ClientBuilder::new(crate::Auth::v2c("public"))
+            .build_with_transport(transport)
+            .unwrap();
+        let varbind = client
+            .get(&oid!(1, 3, 6, 1, 4, 1, 99999, 1, 0))
+            .await
+            .unwrap();
+        // The agent's socket file is owned by this process's effective uid.
+        let uid = std::os::unix::fs::MetadataExt::uid(&std::fs::metadata(&path).unwrap());
+        assert_eq!(varbind.single().unwrap().value, Value::Gauge32(uid));
+
+        agent.cancel();
+        run_task.await.unwrap().unwrap();
+        drop(agent);
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while path.exists() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("agent removes its Unix socket on shutdown");
+    }
+
     #[tokio::test]
     async fn dropped_run_future_releases_exclusivity() {
         let agent = Agent::builder()
@@ -6225,6 +6809,22 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn context_names_longer_than_snmp_admin_string_are_rejected() {
+        let result = Agent::builder()
+            .bind("127.0.0.1:0")
+            .community(b"public")
+            .context([b'x'; 33].to_vec(), |ctx| ctx)
+            .allow_all_access()
+            .build()
+            .await;
+        let error = result.err().expect("33-octet context name must fail");
+        assert!(matches!(
+            *error,
+            Error::Config(ref message) if message.contains("exceeds 32 octets")
+        ));
+    }
+
     #[tokio::test]
     async fn empty_vacm_is_an_explicit_deny_all_policy() {
         Agent::builder()
@@ -6359,6 +6959,39 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_agent_accepts_rotated_communities_during_overlap() {
+        struct Rotating(std::sync::atomic::AtomicUsize);
+
+        impl CredentialProvider for Rotating {
+            fn fetch(&self) -> crate::runtime::BoxFuture<'_, Result<crate::Credentials>> {
+                let revision = self.0.fetch_add(1, Ordering::SeqCst);
+                let community = if revision == 0 { "alpha" } else { "beta" };
+                Box::pin(async move {
+                    Ok(crate::Credentials::new(revision.to_string()).community(community))
+                })
+            }
+        }
+
+        let agent = Agent::builder()
+            .bind("127.0.0.1:0")
+            .credential_provider(
+                Rotating(std::sync::atomic::AtomicUsize::new(0)),
+                CredentialRefresh::default(),
+            )
+            .allow_all_access()
+            .build()
+            .await
+            .unwrap();
+        assert!(agent.validate_community(b"alpha"));
+        assert!(!agent.validate_community(b"beta"));
+
+        assert!(agent.refresh_credentials().await.unwrap());
+        assert!(agent.validate_community(b"alpha"));
+        assert!(agent.validate_community(b"beta"));
+        assert!(!agent.validate_community(b"gamma"));
+    }
+
     #[tokio::test]
     async fn test_invalid_usm_user_is_rejected_before_bind() {
         let result = Agent::builder()
@@ -7054,7 +7687,7 @@ mod tests {
 
         // snmpEngineMaxMessageSize.0 should be queryable
         let handler = agent
-            .find_handler(&oid!(1, 3, 6, 1, 6, 3, 10, 2, 1, 4, 0))
+            .find_handler(b"", &oid!(1, 3, 6, 1, 6, 3, 10, 2, 1, 4, 0))
             .expect("snmpEngine handler should be registered");
         let get_result = handler
             .handler
@@ -7065,7 +7698,7 @@ mod tests {
 
         // usmStatsWrongDigests.0 should be queryable
         let handler = agent
-            .find_handler(&oid!(1, 3, 6, 1, 6, 3, 15, 1, 1, 5, 0))
+            .find_handler(b"", &oid!(1, 3, 6, 1, 6, 3, 15, 1, 1, 5, 0))
             .expect("USM stats handler should be registered");
         let get_result = handler
             .handler
@@ -7076,7 +7709,7 @@ mod tests {
 
         // snmpUnknownSecurityModels.0 should be queryable
         let handler = agent
-            .find_handler(&oid!(1, 3, 6, 1, 6, 3, 11, 2, 1, 1, 0))
+            .find_handler(b"", &oid!(1, 3, 6, 1, 6, 3, 11, 2, 1, 1, 0))
             .expect("MPD stats handler should be registered");
         let get_result = handler
             .handler
@@ -7099,17 +7732,17 @@ mod tests {
 
         assert!(
             agent
-                .find_handler(&oid!(1, 3, 6, 1, 6, 3, 10, 2, 1, 1, 0))
+                .find_handler(b"", &oid!(1, 3, 6, 1, 6, 3, 10, 2, 1, 1, 0))
                 .is_none()
         );
         assert!(
             agent
-                .find_handler(&oid!(1, 3, 6, 1, 6, 3, 15, 1, 1, 1, 0))
+                .find_handler(b"", &oid!(1, 3, 6, 1, 6, 3, 15, 1, 1, 1, 0))
                 .is_none()
         );
         assert!(
             agent
-                .find_handler(&oid!(1, 3, 6, 1, 6, 3, 11, 2, 1, 1, 0))
+                .find_handler(b"", &oid!(1, 3, 6, 1, 6, 3, 11, 2, 1, 1, 0))
                 .is_none()
         );
     }
@@ -7127,17 +7760,17 @@ mod tests {
 
         assert!(
             agent
-                .find_handler(&oid!(1, 3, 6, 1, 6, 3, 10, 2, 1, 1, 0))
+                .find_handler(b"", &oid!(1, 3, 6, 1, 6, 3, 10, 2, 1, 1, 0))
                 .is_some()
         );
         assert!(
             agent
-                .find_handler(&oid!(1, 3, 6, 1, 6, 3, 15, 1, 1, 1, 0))
+                .find_handler(b"", &oid!(1, 3, 6, 1, 6, 3, 15, 1, 1, 1, 0))
                 .is_none()
         );
         assert!(
             agent
-                .find_handler(&oid!(1, 3, 6, 1, 6, 3, 11, 2, 1, 1, 0))
+                .find_handler(b"", &oid!(1, 3, 6, 1, 6, 3, 11, 2, 1, 1, 0))
                 .is_some()
         );
     }
@@ -3,6 +3,7 @@
 //! Provides trap sink configuration and methods for sending notifications
 //! from an agent to configured destinations.
 
+use std::collections::HashSet;
 use std::fmt;
 use std::fmt::Write;
 use std::future::Future;
@@ -277,6 +278,7 @@ impl TrapSink {
             security_level: match &auth {
                 Auth::Community { .. } => None,
                 Auth::Usm(security) => Some(security.security_level()),
+                Auth::Tsm(security) => Some(security.requested_security_level()),
             },
         };
         match auth {
@@ -291,6 +293,7 @@ impl TrapSink {
                 inform_retry,
                 inform_client: AsyncMutex::new(None),
             },
+            Auth::Tsm(_) => unreachable!("AgentBuilder rejects TSM notification sinks"),
             Auth::Usm(security) => TrapSink {
                 summary,
                 auth: sink_auth,
@@ -450,6 +453,13 @@ enum NotificationOperation {
         uptime: u32,
         varbinds: Arc<[VarBind]>,
     },
+    /// Informs to the listed sinks and traps to every other sink.
+    Forward {
+        trap_oid: Arc<Oid>,
+        uptime: u32,
+        varbinds: Arc<[VarBind]>,
+        inform_sinks: Arc<HashSet<NotificationSinkId>>,
+    },
 }
 
 impl NotificationSendStream<'_> {
@@ -508,6 +518,28 @@ impl<'a> NotificationSendStream<'a> {
                             .await
                     })
                 }
+                NotificationOperation::Forward {
+                    trap_oid,
+                    uptime,
+                    varbinds,
+                    inform_sinks,
+                } => {
+                    let trap_oid = Arc::clone(trap_oid);
+                    let varbinds = Arc::clone(varbinds);
+                    let uptime = *uptime;
+                    let inform = inform_sinks.contains(&sink.summary.id);
+                    Box::pin(async move {
+                        if inform {
+                            agent
+                                .inform_sink_outcome(sink, &trap_oid, uptime, &varbinds)
+                                .await
+                        } else {
+                            agent
+                                .trap_sink_outcome(sink, &trap_oid, uptime, &varbinds)
+                                .await
+                        }
+                    })
+                }
             };
             self.pending.push(future);
         }
@@ -823,6 +855,29 @@ impl super::Agent {
             .await
     }
 
+    /// Lazily send a notification as an Inform to `inform_sinks` and as a
+    /// trap to every other configured sink.
+    pub(crate) fn send_forward_stream(
+        &self,
+        trap_oid: &Oid,
+        uptime: u32,
+        varbinds: Vec<VarBind>,
+        inform_sinks: Arc<HashSet<NotificationSinkId>>,
+    ) -> NotificationSendStream<'_> {
+        NotificationSendStream {
+            agent: self,
+            operation: NotificationOperation::Forward {
+                trap_oid: Arc::new(trap_oid.clone()),
+                uptime,
+                varbinds: Arc::from(varbinds),
+                inform_sinks,
+            },
+            next_sink: 0,
+            admission_limit: self.inner.notification_fanout_limit,
+            pending: FuturesUnordered::new(),
+        }
+    }
+
     /// Send an inform to all configured sinks, warning and discarding outcomes.
     ///
     /// Use [`send_inform`](Self::send_inform) when the caller needs to observe
@@ -874,6 +929,12 @@ impl super::Agent {
                 security.security_level(),
                 security.configured_context_name().as_ref(),
             ),
+            Auth::Tsm(security) => (
+                SecurityModel::Tsm,
+                security.security_name().as_ref(),
+                security.requested_security_level(),
+                security.configured_context_name().as_ref(),
+            ),
         };
 
         let Some(group) = vacm.get_group(model, security_name) else {
@@ -1000,7 +1061,7 @@ impl super::Agent {
 }
 
 async fn send_datagram_with_timeout(
-    socket: &tokio::net::UdpSocket,
+    socket: &crate::runtime::UdpSocket,
     data: &[u8],
     target: SocketAddr,
     timeout: Duration,
@@ -1020,7 +1081,7 @@ async fn send_datagram_with_timeout(
         .boxed());
     }
 
-    tokio::time::timeout_at(deadline, socket.send_to(data, target))
+    crate::runtime::timeout_at(deadline, socket.send_to(data, target))
         .await
         .map_err(|_| {
             Error::Timeout {
@@ -0,0 +1,581 @@
+//! Proxy forwarder application (RFC 3413 Section 3.5).
+//!
+//! A [`ProxyHandler`] routes requests by scopedPDU contextEngineID or
+//! contextName (for v1/v2c, the values selected by the community's
+//! snmpCommunityTable entry) to a downstream [`Client`]. The agent forwards
+//! GET, GETNEXT, GETBULK and SET requests under the client's own request IDs
+//! and security settings, then answers the originator under its request ID.
+//!
+//! Version translation follows RFC 3584 Section 4:
+//!
+//! - GETBULK sent to an SNMPv1 device is forwarded as GETNEXT, yielding one
+//!   repetition.
+//! - An SNMPv1 `noSuchName` for a retrieval is retried without the failing
+//!   binding, which is answered `noSuchObject` (GET) or `endOfMibView`
+//!   (GETNEXT/GETBULK). Other SNMPv1 errors map to their SNMPv2 equivalents.
+//! - SNMPv2 errors sent to an SNMPv1 originator use
+//!   [`ErrorStatus::to_v1`]. Exceptions become `noSuchName`; `Counter64`
+//!   becomes `noSuchName` for GET and is stepped over for GETNEXT.
+//!
+//! Each [`ProxyRoute`] names the one principal (snmpProxyParamsIn) whose
+//! requests it forwards. Requests from any other securityModel, securityName
+//! or securityLevel are counted in snmpProxyDrops and not answered. Forwarded
+//! requests must also pass the local VACM for their context, and SET requests
+//! are refused with `noAccess` unless the route allows writes. Downstream
+//! timeouts and other exchange failures are counted in snmpProxyDrops and the
+//! request is not answered.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use bytes::Bytes;
+
+use crate::client::Client;
+use crate::error::{Error, ErrorStatus, Result};
+use crate::handler::{RequestContext, SecurityModel};
+use crate::message::SecurityLevel;
+use crate::pdu::{Pdu, PduType, ResponsePdu, StandardPduType};
+use crate::value::Value;
+use crate::varbind::VarBind;
+use crate::version::Version;
+
+use super::Agent;
+
+/// Maximum GETNEXT re-issues per request while stepping an SNMPv1
+/// originator over `Counter64` values returned by an SNMPv2 device.
+const MAX_COUNTER64_SKIPS: usize = 32;
+
+/// One forwarding table entry: a downstream client and the principal whose
+/// requests it carries.
+///
+/// The securityModel, securityName and securityLevel play the role of the
+/// RFC 3413 snmpProxyParamsIn entry and must all match the incoming request.
+/// For community-based requests the securityName is the one selected by the
+/// community (its snmpCommunityTable entry, or the community itself) and the
+/// level is `noAuthNoPriv`.
+///
+/// ```rust,no_run
+/// use async_snmp::agent::{ProxyRoute, SecurityModel};
+/// use async_snmp::message::SecurityLevel;
+/// use async_snmp::{Auth, Client};
+///
+/// # async fn example() -> Result<(), Box<async_snmp::Error>> {
+/// let switch = Client::builder("192.0.2.20:161", Auth::v2c("private"))
+///     .connect()
+///     .await?;
+/// let route = ProxyRoute::new(switch, SecurityModel::Usm, "noc", SecurityLevel::AuthPriv)
+///     .allow_writes(true);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ProxyRoute {
+    client: Client,
+    security_model: SecurityModel,
+    security_name: Bytes,
+    security_level: SecurityLevel,
+    writable: bool,
+}
+
+impl ProxyRoute {
+    /// Forward requests from one principal to `client`.
+    ///
+    /// The route forwards GET, GETNEXT and GETBULK requests; SET requests are
+    /// refused until [`allow_writes`](Self::allow_writes) enables them.
+    #[must_use]
+    pub fn new(
+        client: Client,
+        security_model: SecurityModel,
+        security_name: impl Into<Bytes>,
+        security_level: SecurityLevel,
+    ) -> Self {
+        Self {
+            client,
+            security_model,
+            security_name: security_name.into(),
+            security_level,
+            writable: false,
+        }
+    }
+
+    /// Forward SET requests as well as retrievals.
+    ///
+    /// Each SET varbind must still be in the principal's VACM write view.
+    ///
+    /// Default: `false`.
+    #[must_use]
+    pub fn allow_writes(mut self, allow: bool) -> Self {
+        self.writable = allow;
+        self
+    }
+
+    /// Downstream client requests are forwarded through.
+    #[must_use]
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Whether the request's principal matches this route's paramsIn.
+    fn admits(&self, ctx: &RequestContext) -> bool {
+        ctx.security_model() == self.security_model
+            && ctx.security_name().as_bytes() == self.security_name.as_ref()
+            && ctx.security_level() == self.security_level
+    }
+}
+
+impl fmt::Debug for ProxyRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProxyRoute")
+            .field("target", &self.client.peer_addr())
+            .field("security_model", &self.security_model)
+            .field(
+                "security_name",
+                &crate::format::hex::Bytes(&self.security_name),
+            )
+            .field("security_level", &self.security_level)
+            .field("writable", &self.writable)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+struct ProxyRoutes {
+    context_engine_ids: HashMap<Bytes, ProxyRoute>,
+    context_names: HashMap<Bytes, ProxyRoute>,
+}
+
+/// Forwarding table for the agent's proxy forwarder.
+///
+/// Clones share one table, so routes can be added and removed while the agent
+/// runs. Install it with [`AgentBuilder::proxy`](super::AgentBuilder::proxy).
+///
+/// A request whose contextEngineID is neither empty nor the local engine ID
+/// is routed by contextEngineID. Any other request is routed by contextName,
+/// ahead of the agent's own context handler trees. Requests without a route
+/// are processed locally. The downstream client's own version, credentials
+/// and context settings are used for the forwarded request.
+///
+/// Routed requests are only forwarded for the principal their
+/// [`ProxyRoute`] names, after the local VACM grants that principal access
+/// to the request's context.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::agent::{Agent, ProxyHandler, ProxyRoute, SecurityModel};
+/// use async_snmp::message::SecurityLevel;
+/// use async_snmp::{Auth, Client};
+/// use bytes::Bytes;
+///
+/// # async fn example() -> Result<(), Box<async_snmp::Error>> {
+/// // Requests with community "noc@switch-20" are forwarded to the switch.
+/// let proxy = ProxyHandler::new();
+/// let agent = Agent::builder()
+///     .bind("0.0.0.0:1161")
+///     .community_entry(b"noc@switch-20", "noc", "switch-20", Bytes::new(), "")
+///     .proxy(proxy.clone())
+///     .allow_all_access()
+///     .build()
+///     .await?;
+///
+/// let switch = Client::builder("192.0.2.20:161", Auth::v2c("public"))
+///     .connect()
+///     .await?;
+/// let route = ProxyRoute::new(switch, SecurityModel::V2c, "noc", SecurityLevel::NoAuthNoPriv);
+/// proxy.route_context_name("switch-20", route);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct ProxyHandler {
+    routes: Arc<RwLock<ProxyRoutes>>,
+}
+
+impl ProxyHandler {
+    /// Create an empty forwarding table.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forward requests for a remote contextEngineID along `route`.
+    ///
+    /// Returns the route previously installed for this contextEngineID.
+    pub fn route_context_engine_id(
+        &self,
+        context_engine_id: impl Into<Bytes>,
+        route: ProxyRoute,
+    ) -> Option<ProxyRoute> {
+        self.write()
+            .context_engine_ids
+            .insert(context_engine_id.into(), route)
+    }
+
+    /// Forward requests for a contextName along `route`.
+    ///
+    /// Returns the route previously installed for this contextName.
+    pub fn route_context_name(
+        &self,
+        context_name: impl Into<Bytes>,
+        route: ProxyRoute,
+    ) -> Option<ProxyRoute> {
+        self.write()
+            .context_names
+            .insert(context_name.into(), route)
+    }
+
+    /// Stop forwarding requests for a contextEngineID.
+    pub fn remove_context_engine_id(&self, context_engine_id: &[u8]) -> Option<ProxyRoute> {
+        self.write().context_engine_ids.remove(context_engine_id)
+    }
+
+    /// Stop forwarding requests for a contextName.
+    pub fn remove_context_name(&self, context_name: &[u8]) -> Option<ProxyRoute> {
+        self.write().context_names.remove(context_name)
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, ProxyRoutes> {
+        self.routes.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn route(
+        &self,
+        context_engine_id: &[u8],
+        context_name: &[u8],
+        local_engine_id: &[u8],
+    ) -> Option<ProxyRoute> {
+        let routes = self.routes.read().unwrap_or_else(PoisonError::into_inner);
+        if !context_engine_id.is_empty() && context_engine_id != local_engine_id {
+            routes.context_engine_ids.get(context_engine_id).cloned()
+        } else {
+            routes.context_names.get(context_name).cloned()
+        }
+    }
+}
+
+impl fmt::Debug for ProxyHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let routes = self.routes.read().unwrap_or_else(PoisonError::into_inner);
+        f.debug_struct("ProxyHandler")
+            .field("context_engine_ids", &routes.context_engine_ids.len())
+            .field("context_names", &routes.context_names.len())
+            .finish()
+    }
+}
+
+/// Outcome of one downstream exchange.
+enum Downstream {
+    Varbinds(Vec<VarBind>),
+    /// error-status and 1-based error-index of the downstream Response.
+    Error(ErrorStatus, usize),
+}
+
+async fn exchange(client: &Client, pdu: Pdu) -> Result<Downstream> {
+    match client.forward(pdu).await {
+        Ok(varbinds) => Ok(Downstream::Varbinds(varbinds)),
+        Err(error) => match *error {
+            Error::Snmp { status, index, .. } => Ok(Downstream::Error(
+                status,
+                usize::try_from(index).unwrap_or(0),
+            )),
+            _ => Err(error),
+        },
+    }
+}
+
+/// SNMPv2 error-status for an SNMPv1 one (RFC 3584 Section 4.4).
+fn v1_status_to_v2(status: ErrorStatus, pdu_type: PduType) -> ErrorStatus {
+    match status {
+        ErrorStatus::BadValue => ErrorStatus::WrongValue,
+        ErrorStatus::ReadOnly => ErrorStatus::NotWritable,
+        ErrorStatus::NoSuchName if pdu_type == PduType::SetRequest => ErrorStatus::NotWritable,
+        other => other,
+    }
+}
+
+fn is_exception(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::NoSuchObject | Value::NoSuchInstance | Value::EndOfMibView
+    )
+}
+
+impl Agent {
+    /// Proxy table entry for a request it routes.
+    pub(super) fn proxy_route(
+        &self,
+        pdu_type: PduType,
+        context_engine_id: &[u8],
+        context_name: &[u8],
+    ) -> Option<ProxyRoute> {
+        if !matches!(
+            pdu_type,
+            PduType::GetRequest
+                | PduType::GetNextRequest
+                | PduType::GetBulkRequest
+                | PduType::SetRequest
+        ) {
+            return None;
+        }
+        self.inner.proxy.as_ref()?.route(
+            context_engine_id,
+            context_name,
+            &self.inner.state.engine_id,
+        )
+    }
+
+    /// Check a routed request against its route and the VACM, then forward it.
+    ///
+    /// Returns `None` after counting snmpProxyDrops when the request's
+    /// principal does not match the route, or when the downstream exchange
+    /// fails or its Response does not match the request.
+    pub(super) async fn forward_request(
+        &self,
+        ctx: &mut RequestContext,
+        route: &ProxyRoute,
+        pdu: &Pdu,
+    ) -> Result<Option<Pdu>> {
+        let version = ctx.version();
+        if !route.admits(ctx) {
+            tracing::debug!(
+                target: "async_snmp::agent",
+                { snmp.source = %ctx.source(), security_model = ?ctx.security_model(), security_level = ?ctx.security_level() },
+                "request principal does not match the proxy route, dropping"
+            );
+            self.inner
+                .state
+                .snmp_proxy_drops
+                .fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+        if !self.resolve_vacm(ctx) {
+            return super::request::access_denied_response(version, pdu);
+        }
+        if pdu.pdu_type() == PduType::SetRequest {
+            let denied = if route.writable {
+                self.inner.authorization.vacm().and_then(|vacm| {
+                    pdu.varbinds
+                        .iter()
+                        .position(|varbind| !vacm.check_access(ctx.write_view(), &varbind.oid))
+                        .map(|position| position + 1)
+                })
+            } else {
+                Some(usize::from(!pdu.varbinds.is_empty()))
+            };
+            if let Some(index) = denied {
+                let status = if version == Version::V1 {
+                    ErrorStatus::NoAccess.to_v1()
+                } else {
+                    ErrorStatus::NoAccess
+                };
+                return pdu.to_error_response(version, status, index).map(Some);
+            }
+        }
+        let client = &route.client;
+        if version == Version::V1 && pdu.pdu_type() == PduType::GetBulkRequest {
+            return pdu
+                .to_error_response(
+                    version,
+                    ErrorStatus::GenErr,
+                    usize::from(!pdu.varbinds.is_empty()),
+                )
+                .map(Some);
+        }
+
+        let downstream = if client.version() == Version::V1 {
+            self.forward_to_v1(client, pdu).await
+        } else {
+            exchange(client, pdu.clone()).await
+        };
+        let downstream = match downstream {
+            Ok(Downstream::Varbinds(varbinds)) => {
+                match self.translate_varbinds(ctx, client, pdu, varbinds).await {
+                    Ok(downstream) => downstream,
+                    Err(error) => return Ok(self.proxy_drop(ctx, &error)),
+                }
+            }
+            Ok(downstream) => downstream,
+            Err(error) => return Ok(self.proxy_drop(ctx, &error)),
+        };
+
+        match downstream {
+            Downstream::Varbinds(varbinds) => Ok(Some(
+                ResponsePdu::success(version, pdu.request_id, varbinds)?.into_raw(),
+            )),
+            Downstream::Error(status, index) => {
+                let status = match status {
+                    ErrorStatus::Unknown(_) => ErrorStatus::GenErr,
+                    status if version == Version::V1 => status.to_v1(),
+                    status => status,
+                };
+                let index = if index > pdu.varbinds.len() { 0 } else { index };
+                pdu.to_error_response(version, status, index).map(Some)
+            }
+        }
+    }
+
+    fn proxy_drop(&self, ctx: &RequestContext, error: &Error) -> Option<Pdu> {
+        tracing::debug!(
+            target: "async_snmp::agent",
+            { snmp.source = %ctx.source(), error = %error },
+            "proxied request failed downstream, dropping"
+        );
+        self.inner
+            .state
+            .snmp_proxy_drops
+            .fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    /// Forward to an SNMPv1 device, retrying retrievals without bindings it
+    /// reports as `noSuchName`.
+    async fn forward_to_v1(&self, client: &Client, pdu: &Pdu) -> Result<Downstream> {
+        let pdu_type = pdu.pdu_type();
+        let south_type = match pdu_type {
+            PduType::GetRequest => StandardPduType::GetRequest,
+            PduType::GetNextRequest | PduType::GetBulkRequest => StandardPduType::GetNextRequest,
+            _ => StandardPduType::SetRequest,
+        };
+        let mut results: Vec<Option<VarBind>> = vec![None; pdu.varbinds.len()];
+        let mut pending: Vec<usize> = (0..pdu.varbinds.len()).collect();
+        while !pending.is_empty() {
+            let request = Pdu::standard(
+                south_type,
+                0,
+                0,
+                0,
+                pending.iter().map(|&i| pdu.varbinds[i].clone()).collect(),
+            );
+            match exchange(client, request).await? {
+                Downstream::Varbinds(varbinds) => {
+                    if varbinds.len() != pending.len() {
+                        return Err(Error::MalformedResponse {
+                            target: client.peer_addr(),
+                        }
+                        .boxed());
+                    }
+                    for (position, varbind) in pending.iter().zip(varbinds) {
+                        results[*position] = Some(varbind);
+                    }
+                    break;
+                }
+                Downstream::Error(ErrorStatus::NoSuchName, index)
+                    if pdu_type != PduType::SetRequest && (1..=pending.len()).contains(&index) =>
+                {
+                    let position = pending.remove(index - 1);
+                    let exception = if pdu_type == PduType::GetRequest {
+                        Value::NoSuchObject
+                    } else {
+                        Value::EndOfMibView
+                    };
+                    results[position] =
+                        Some(VarBind::new(pdu.varbinds[position].oid.clone(), exception));
+                }
+                Downstream::Error(status, index) => {
+                    let index = index
+                        .checked_sub(1)
+                        .and_then(|index| pending.get(index))
+                        .map_or(0, |position| position + 1);
+                    return Ok(Downstream::Error(v1_status_to_v2(status, pdu_type), index));
+                }
+            }
+        }
+        Ok(Downstream::Varbinds(
+            results.into_iter().flatten().collect(),
+        ))
+    }
+
+    /// Check a downstream Response against the request and translate it for
+    /// the originator's version and message size.
+    async fn translate_varbinds(
+        &self,
+        ctx: &RequestContext,
+        client: &Client,
+        pdu: &Pdu,
+        mut varbinds: Vec<VarBind>,
+    ) -> Result<Downstream> {
+        let pdu_type = pdu.pdu_type();
+        let malformed = || {
+            Error::MalformedResponse {
+                target: client.peer_addr(),
+            }
+            .boxed()
+        };
+        if pdu_type == PduType::GetBulkRequest {
+            if varbinds.is_empty() && !pdu.varbinds.is_empty() {
+                return Err(malformed());
+            }
+            // RFC 3416 Section 4.2.3: trim from the end to fit the
+            // originator's message size rather than answering tooBig.
+            let max_size = self.effective_max_size(ctx);
+            let mut size = self.response_overhead(ctx);
+            let fit = varbinds
+                .iter()
+                .take_while(|varbind| {
+                    size += varbind.encoded_size();
+                    size <= max_size
+                })
+                .count();
+            varbinds.truncate(fit.max(1));
+            return Ok(Downstream::Varbinds(varbinds));
+        }
+        if varbinds.len() != pdu.varbinds.len() {
+            return Err(malformed());
+        }
+        if ctx.version() != Version::V1 {
+            return Ok(Downstream::Varbinds(varbinds));
+        }
+
+        if pdu_type == PduType::GetNextRequest {
+            for _ in 0..MAX_COUNTER64_SKIPS {
+                let skipped: Vec<usize> = (0..varbinds.len())
+                    .filter(|&i| matches!(varbinds[i].value, Value::Counter64(_)))
+                    .collect();
+                if skipped.is_empty() {
+                    break;
+                }
+                let request = Pdu::standard(
+                    StandardPduType::GetNextRequest,
+                    0,
+                    0,
+                    0,
+                    skipped
+                        .iter()
+                        .map(|&i| VarBind::null(varbinds[i].oid.clone()))
+                        .collect(),
+                );
+                match exchange(client, request).await? {
+                    Downstream::Varbinds(next) if next.len() == skipped.len() => {
+                        for (position, varbind) in skipped.into_iter().zip(next) {
+                            varbinds[position] = varbind;
+                        }
+                    }
+                    Downstream::Varbinds(_) => return Err(malformed()),
+                    Downstream::Error(status, index) => {
+                        let index = index
+                            .checked_sub(1)
+                            .and_then(|index| skipped.get(index))
+                            .map_or(0, |position| position + 1);
+                        return Ok(Downstream::Error(status, index));
+                    }
+                }
+            }
+        }
+        // RFC 3584 Section 4.2.1: SNMPv1 has no exceptions or Counter64.
+        if let Some(position) = varbinds.iter().position(|varbind| {
+            is_exception(&varbind.value) || matches!(varbind.value, Value::Counter64(_))
+        }) {
+            let status = if pdu_type == PduType::GetNextRequest
+                && matches!(varbinds[position].value, Value::Counter64(_))
+            {
+                ErrorStatus::GenErr
+            } else {
+                ErrorStatus::NoSuchName
+            };
+            return Ok(Downstream::Error(status, position + 1));
+        }
+        Ok(Downstream::Varbinds(varbinds))
+    }
+}
@@ -8,7 +8,8 @@ use crate::Value;
 use crate::error::Result;
 use crate::handler::{RequestContext, RequestLifecycle};
 use crate::message::CommunityMessage;
-use crate::pdu::{PduType, ResponsePdu};
+use crate::pdu::{Pdu, PduType, ResponsePdu};
+use crate::security_event::SecurityEventKind;
 use crate::v3::process::{MpdCounters, V3Inbound, V3LocalContext, V3Role, process_v3_inbound};
 use crate::version::{CommunityVersion, Version};
 
@@ -74,9 +75,20 @@ impl Agent {
         let decode_anomalies = decoded.anomalies;
         let msg = decoded.value;
 
-        // Validate community
-        if !self.validate_community(msg.community().as_bytes()) {
+        // Validate community. RFC 3584 Section 5.2.1: an snmpCommunityTable
+        // entry maps the community to a securityName and context; a plain
+        // community is its own securityName in the default context.
+        let mapping = self
+            .inner
+            .community_table
+            .lookup(msg.community().as_bytes(), source.ip());
+        if mapping.is_none() && !self.validate_community(msg.community().as_bytes()) {
             tracing::debug!(target: "async_snmp::agent", { snmp.source = %source }, "invalid community string");
+            self.inner.security_events.community(
+                SecurityEventKind::BadCommunity,
+                source,
+                msg.community().as_bytes(),
+            );
             return Ok(None);
         }
 
@@ -86,6 +98,38 @@ impl Agent {
             _ => return Ok(None),
         };
 
+        let proxy_route = mapping.and_then(|entry| {
+            self.proxy_route(
+                pdu.pdu_type(),
+                &entry.context_engine_id,
+                &entry.context_name,
+            )
+        });
+        if let Some(entry) = mapping
+            && proxy_route.is_none()
+        {
+            let engine_id = &entry.context_engine_id;
+            if (!engine_id.is_empty() && engine_id.as_ref() != self.inner.state.engine_id.as_ref())
+                || !self.serves_context(&entry.context_name)
+            {
+                tracing::debug!(
+                    target: "async_snmp::agent",
+                    { snmp.source = %source, context_name = %crate::format::hex::Bytes(&entry.context_name) },
+                    "community maps to a context this agent does not serve, dropping"
+                );
+                self.inner
+                    .state
+                    .snmp_unknown_contexts
+                    .fetch_add(1, Ordering::Relaxed);
+                self.inner.security_events.community(
+                    SecurityEventKind::UnknownContext,
+                    source,
+                    msg.community().as_bytes(),
+                );
+                return Ok(None);
+            }
+        }
+
         // Counter64 is not part of the SNMPv1 data types. Decode it so the
         // receive path remains bounded and unambiguous, then silently drop the
         // accepted request before authorization resolution or handler dispatch.
@@ -114,6 +158,9 @@ impl Agent {
             decode_anomalies,
             lifecycle,
         );
+        if let Some(entry) = mapping {
+            ctx.set_community_mapping(entry.security_name.clone(), entry.context_name.clone());
+        }
 
         let encode = |response_pdu| {
             let response_msg = match version {
@@ -142,18 +189,15 @@ impl Agent {
             }
         }
 
-        let response_pdu = if self.resolve_vacm(&mut ctx) {
-            self.dispatch_request(&ctx, pdu).await?
+        let response_pdu = if let Some(route) = proxy_route {
+            self.forward_request(&mut ctx, &route, pdu).await?
+        } else if self.resolve_vacm(&mut ctx) {
+            Some(self.dispatch_request(&ctx, pdu).await?)
         } else {
-            let (status, error_index) = if version == Version::V1 {
-                if pdu.varbinds.is_empty() {
-                    return Ok(None);
-                }
-                (crate::error::ErrorStatus::NoSuchName, 1)
-            } else {
-                (crate::error::ErrorStatus::AuthorizationError, 0)
-            };
-            pdu.to_error_response(version, status, error_index)?
+            access_denied_response(version, pdu)?
+        };
+        let Some(response_pdu) = response_pdu else {
+            return Ok(None);
         };
         let finalized = crate::response_finalizer::finalize_response(
             version,
@@ -185,6 +229,11 @@ impl Agent {
     ) -> Result<Option<Bytes>> {
         let state = &self.inner.state;
         let (engine_boots, engine_time) = state.authoritative_boots_time()?;
+        let credentials = self
+            .inner
+            .credentials
+            .as_ref()
+            .map(crate::credentials::ActiveCredentials::snapshot);
         let usm_ctx = V3LocalContext {
             engine_id: &state.engine_id,
             engine_boots,
@@ -195,11 +244,13 @@ impl Agent {
             decode_config: state.decode_config,
             outbound_limit: state.max_message_size,
             usm_users: &self.inner.usm_users,
+            rotating_users: credentials.as_ref(),
             stats: &state.usm_stats,
             mpd: Some(MpdCounters {
                 invalid_msgs: &state.snmp_invalid_msgs,
                 unknown_security_models: &state.snmp_unknown_security_models,
             }),
+            security_events: Some(&self.inner.security_events),
             source,
         };
 
@@ -222,24 +273,39 @@ impl Agent {
         }
 
         // RFC 3413 Section 3.2: the scopedPDU contextEngineID selects the
-        // context (SNMP entity) that services the request. This engine serves
-        // only its own (single, default) context, so the contextEngineID must
-        // either be empty (the default context) or match the local engine ID.
-        // A request naming any other engine is answered with an
-        // snmpUnknownContexts Report rather than being dispatched blindly
-        // against the local MIB and echoed back.
-        //
-        // Multi-context support (an agent proxying for several contextEngineIDs
-        // via a context table) is not implemented; only the local engine's
-        // context is recognised.
+        // SNMP entity that services the request, and the contextName selects
+        // one of its contexts. The contextEngineID must either be empty or
+        // match the local engine ID, and the contextName must be the default
+        // (empty) context or one registered with `AgentBuilder::context`.
+        // Anything else is answered with an snmpUnknownContexts Report rather
+        // than being dispatched blindly against the local MIB and echoed back,
+        // unless the proxy forwarding table routes it (Section 3.5).
         let ctx_engine_id = &scoped_pdu.context_engine_id;
-        if !ctx_engine_id.is_empty() && ctx_engine_id.as_ref() != state.engine_id.as_ref() {
-            tracing::debug!(
-                target: "async_snmp::agent",
-                { snmp.source = %source, context_engine_id = %crate::format::hex::Bytes(ctx_engine_id) },
-                "scopedPDU contextEngineID does not match local engine, rejecting with snmpUnknownContexts"
-            );
+        let proxy_route = self.proxy_route(pdu.pdu_type(), ctx_engine_id, &scoped_pdu.context_name);
+        let unknown_engine =
+            !ctx_engine_id.is_empty() && ctx_engine_id.as_ref() != state.engine_id.as_ref();
+        if proxy_route.is_none()
+            && (unknown_engine || !self.serves_context(&scoped_pdu.context_name))
+        {
+            if unknown_engine {
+                tracing::debug!(
+                    target: "async_snmp::agent",
+                    { snmp.source = %source, context_engine_id = %crate::format::hex::Bytes(ctx_engine_id) },
+                    "scopedPDU contextEngineID does not match local engine, rejecting with snmpUnknownContexts"
+                );
+            } else {
+                tracing::debug!(
+                    target: "async_snmp::agent",
+                    { snmp.source = %source, context_name = %crate::format::hex::Bytes(&scoped_pdu.context_name) },
+                    "scopedPDU contextName is not served by this agent, rejecting with snmpUnknownContexts"
+                );
+            }
             state.snmp_unknown_contexts.fetch_add(1, Ordering::Relaxed);
+            self.inner.security_events.user(
+                SecurityEventKind::UnknownContext,
+                source,
+                &usm_params.username,
+            );
 
             let report_pdu = ResponsePdu::report(
                 pdu.request_id(),
@@ -305,7 +371,12 @@ impl Agent {
             None
         };
 
-        let response_pdu = if self.resolve_vacm(&mut ctx) {
+        let response_pdu = if let Some(route) = proxy_route {
+            match self.forward_request(&mut ctx, &route, pdu).await? {
+                Some(response_pdu) => response_pdu,
+                None => return Ok(None),
+            }
+        } else if self.resolve_vacm(&mut ctx) {
             self.dispatch_request(&ctx, pdu).await?
         } else {
             pdu.to_error_response(
@@ -333,8 +404,32 @@ impl Agent {
         .map(crate::response_finalizer::FinalizedResponse::into_bytes)
     }
 
-    /// Populate VACM group and view fields on a request context.
-    fn resolve_vacm(&self, ctx: &mut RequestContext) -> bool {
+    /// Populate VACM group and view fields on a request context, reporting a
+    /// denial to security-event subscribers.
+    pub(super) fn resolve_vacm(&self, ctx: &mut RequestContext) -> bool {
+        let allowed = self.check_vacm(ctx);
+        if !allowed {
+            let events = &self.inner.security_events;
+            // Community denials are fingerprinted by the received community,
+            // not a mapped securityName, so they correlate with BadCommunity.
+            if let Some(community) = ctx.community() {
+                events.community(
+                    SecurityEventKind::AccessDenied,
+                    ctx.source(),
+                    community.as_bytes(),
+                );
+            } else {
+                events.user(
+                    SecurityEventKind::AccessDenied,
+                    ctx.source(),
+                    ctx.security_name().as_bytes(),
+                );
+            }
+        }
+        allowed
+    }
+
+    fn check_vacm(&self, ctx: &mut RequestContext) -> bool {
         let Some(vacm) = self.inner.authorization.vacm() else {
             return true;
         };
@@ -394,6 +489,23 @@ fn snmp_unknown_contexts_oid() -> crate::Oid {
     crate::oid!(1, 3, 6, 1, 6, 3, 12, 1, 5, 0)
 }
 
+/// Response to a request the VACM denies.
+///
+/// SNMPv1 has no authorizationError and reports `noSuchName` at the first
+/// binding instead; a v1 request without bindings is dropped (`None`).
+pub(super) fn access_denied_response(version: Version, pdu: &Pdu) -> Result<Option<Pdu>> {
+    let (status, error_index) = if version == Version::V1 {
+        if pdu.varbinds.is_empty() {
+            return Ok(None);
+        }
+        (crate::error::ErrorStatus::NoSuchName, 1)
+    } else {
+        (crate::error::ErrorStatus::AuthorizationError, 0)
+    };
+    pdu.to_error_response(version, status, error_index)
+        .map(Some)
+}
+
 /// Returns whether a PDU type is a request that the agent handles.
 ///
 /// `InformRequest` is a confirmed-class PDU (RFC 3416) that requires a Response.
@@ -735,6 +847,148 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn bad_community_and_vacm_denial_are_security_events() {
+        let source: SocketAddr = "127.0.0.1:9999".parse().unwrap();
+        let callbacks = Arc::new(CallbackCounts::default());
+        let agent = vacm_denial_agent(MissingVacmState::Group, Arc::clone(&callbacks)).await;
+        let mut events = agent.subscribe_security_events(8);
+
+        for community in [b"private".as_slice(), b"public"] {
+            let request =
+                community_request(Version::V2c, PduType::GetRequest, community, Value::Null);
+            agent.handle_v2c(request, source).await.unwrap();
+        }
+
+        let bad_community = events.try_recv().expect("bad community event");
+        assert_eq!(bad_community.kind(), crate::SecurityEventKind::BadCommunity);
+        assert_eq!(bad_community.source(), source);
+        let denied = events.try_recv().expect("access denied event");
+        assert_eq!(denied.kind(), crate::SecurityEventKind::AccessDenied);
+        let (
+            crate::SecurityPrincipal::Community(rejected),
+            crate::SecurityPrincipal::Community(accepted),
+        ) = (bad_community.principal(), denied.principal())
+        else {
+            panic!("community requests are attributed to community fingerprints");
+        };
+        assert_ne!(rejected, accepted);
+        assert!(events.try_recv().is_none());
+        assert_eq!(events.dropped(), 0);
+    }
+
+    /// snmpCommunityTable entries select the VACM security name and the
+    /// context handler tree for v1/v2c requests.
+    #[tokio::test]
+    async fn community_entry_maps_request_to_security_name_and_context() {
+        let source: SocketAddr = "192.0.2.1:9999".parse().unwrap();
+        let default = Arc::new(CallbackCounts::default());
+        let vlan = Arc::new(CallbackCounts::default());
+        let agent = Agent::builder()
+            .bind("127.0.0.1:0")
+            .community_entry(b"public@10", "bridge", "vlan-10", Bytes::new(), "")
+            .community_entry(b"public@20", "bridge", "vlan-20", Bytes::new(), "")
+            .community_entry(b"private", "admin", "vlan-10", Bytes::new(), "admins")
+            .tagged_address("admins", "192.0.2.200".parse().unwrap())
+            .handler(oid!(1, 3, 6, 1, 4, 1, 99999), default.clone())
+            .context("vlan-10", |ctx| {
+                ctx.handler(oid!(1, 3, 6, 1, 4, 1, 99999), vlan.clone())
+            })
+            .vacm(|vacm| {
+                vacm.group("bridge", SecurityModel::V2c, "bridges")
+                    .group("admin", SecurityModel::V2c, "bridges")
+                    .access(
+                        "bridges",
+                        SecurityModel::V2c,
+                        SecurityLevel::NoAuthNoPriv,
+                        |access| access.context_prefix("vlan-10").read_view("all"),
+                    )
+                    .view("all", |view| view.include(oid!(1, 3, 6)))
+            })
+            .build()
+            .await
+            .unwrap();
+        let mut events = agent.subscribe_security_events(4);
+
+        let response = agent
+            .handle_v2c(
+                community_request(Version::V2c, PduType::GetRequest, b"public@10", Value::Null),
+                source,
+            )
+            .await
+            .unwrap()
+            .expect("mapped community produces a Response");
+        let response = CommunityMessage::decode(response, crate::DecodeConfig::default())
+            .unwrap()
+            .value;
+        assert_eq!(response.community().as_bytes(), b"public@10");
+        assert_eq!(response.pdu().standard().unwrap().error_status(), 0);
+        assert_eq!(vlan.get.load(Ordering::Relaxed), 1);
+        assert_no_callbacks(&default);
+
+        // vlan-20 is not a registered context.
+        let dropped = agent
+            .handle_v2c(
+                community_request(Version::V2c, PduType::GetRequest, b"public@20", Value::Null),
+                source,
+            )
+            .await
+            .unwrap();
+        assert!(dropped.is_none());
+        assert_eq!(agent.snmp_unknown_contexts(), 1);
+        let event = events.try_recv().expect("unknown context event");
+        assert_eq!(event.kind(), crate::SecurityEventKind::UnknownContext);
+
+        // The admins tag does not include this source.
+        let dropped = agent
+            .handle_v2c(
+                community_request(Version::V2c, PduType::GetRequest, b"private", Value::Null),
+                source,
+            )
+            .await
+            .unwrap();
+        assert!(dropped.is_none());
+        let event = events.try_recv().expect("bad community event");
+        assert_eq!(event.kind(), crate::SecurityEventKind::BadCommunity);
+        assert_eq!(vlan.get.load(Ordering::Relaxed), 1);
+    }
+
+    /// A VACM denial for a mapped community is attributed to the received
+    /// community, so it correlates with that community's BadCommunity events.
+    #[tokio::test]
+    async fn mapped_community_denial_fingerprints_received_community() {
+        let allowed: SocketAddr = "192.0.2.200:9999".parse().unwrap();
+        let other: SocketAddr = "192.0.2.1:9999".parse().unwrap();
+        let callbacks = Arc::new(CallbackCounts::default());
+        let agent = Agent::builder()
+            .bind("127.0.0.1:0")
+            .community_entry(b"private", "admin", "", Bytes::new(), "admins")
+            .tagged_address("admins", allowed.ip())
+            .handler(oid!(1, 3, 6, 1, 4, 1, 99999), callbacks.clone())
+            .vacm(|vacm| vacm)
+            .build()
+            .await
+            .unwrap();
+        let mut events = agent.subscribe_security_events(4);
+
+        for source in [other, allowed] {
+            agent
+                .handle_v2c(
+                    community_request(Version::V2c, PduType::GetRequest, b"private", Value::Null),
+                    source,
+                )
+                .await
+                .unwrap();
+        }
+
+        let bad_community = events.try_recv().expect("bad community event");
+        assert_eq!(bad_community.kind(), crate::SecurityEventKind::BadCommunity);
+        let denied = events.try_recv().expect("access denied event");
+        assert_eq!(denied.kind(), crate::SecurityEventKind::AccessDenied);
+        assert_eq!(denied.principal(), bad_community.principal());
+        assert_no_callbacks(&callbacks);
+    }
+
     #[tokio::test]
     async fn vacm_denied_set_never_reaches_callbacks() {
         let callbacks = Arc::new(CallbackCounts::default());
@@ -1284,6 +1538,152 @@ mod tests {
         assert_eq!(decoded.pdu().unwrap().pdu_type(), PduType::Response);
     }
 
+    fn build_context_get(engine_id: &[u8], context_name: &'static [u8]) -> Bytes {
+        let global = MsgGlobalData::new(
+            1,
+            crate::MessageSize::from_i32(65507).unwrap(),
+            MsgFlags::new(SecurityLevel::NoAuthNoPriv, true),
+        )
+        .unwrap();
+        let usm_params = UsmSecurityParams::new(
+            Bytes::copy_from_slice(engine_id),
+            0,
+            0,
+            Bytes::from_static(b"noauthuser"),
+        )
+        .unwrap();
+        let pdu = Pdu::get_request(77, &[oid!(1, 3, 6, 1, 4, 1, 99999, 1, 0)]);
+        let scoped = ScopedPdu::new(
+            Bytes::copy_from_slice(engine_id),
+            Bytes::from_static(context_name),
+            pdu,
+        );
+        V3Message::new(global, usm_params.encode().unwrap(), scoped)
+            .unwrap()
+            .encode()
+            .unwrap()
+    }
+
+    /// Requests are dispatched to the handler tree registered for their
+    /// contextName; an unregistered name is answered with an
+    /// snmpUnknownContexts Report.
+    #[tokio::test]
+    async fn v3_context_name_selects_handler_tree() {
+        let engine_id = b"\x80\x00\x00\x00\x01agenteng".to_vec();
+        let default = Arc::new(CallbackCounts::default());
+        let red = Arc::new(CallbackCounts::default());
+        let agent = Agent::builder()
+            .bind("127.0.0.1:0")
+            .engine_id(engine_id.clone())
+            .usm_user("noauthuser", Ok)
+            .unwrap()
+            .handler(oid!(1, 3, 6, 1, 4, 1, 99999), default.clone())
+            .context("vrf-red", |ctx| {
+                ctx.handler(oid!(1, 3, 6, 1, 4, 1, 99999), red.clone())
+            })
+            .allow_all_access()
+            .build()
+            .await
+            .unwrap();
+        let mut events = agent.subscribe_security_events(4);
+        let source: SocketAddr = "127.0.0.1:9999".parse().unwrap();
+
+        let response = agent
+            .handle_v3(build_context_get(&engine_id, b"vrf-red"), source)
+            .await
+            .unwrap()
+            .expect("registered context produces a Response");
+        let decoded = V3Message::decode(response, crate::DecodeConfig::default())
+            .unwrap()
+            .value;
+        assert_eq!(decoded.pdu().unwrap().pdu_type(), PduType::Response);
+        assert_eq!(
+            decoded.scoped_pdu().unwrap().context_name.as_ref(),
+            b"vrf-red"
+        );
+        assert_eq!(red.get.load(Ordering::Relaxed), 1);
+        assert_eq!(default.get.load(Ordering::Relaxed), 0);
+
+        agent
+            .handle_v3(build_context_get(&engine_id, b""), source)
+            .await
+            .unwrap()
+            .expect("default context produces a Response");
+        assert_eq!(default.get.load(Ordering::Relaxed), 1);
+        assert_eq!(agent.snmp_unknown_contexts(), 0);
+
+        let report = agent
+            .handle_v3(build_context_get(&engine_id, b"vrf-green"), source)
+            .await
+            .unwrap()
+            .expect("unknown context produces a Report");
+        assert_eq!(agent.snmp_unknown_contexts(), 1);
+        let decoded = V3Message::decode(report, crate::DecodeConfig::default())
+            .unwrap()
+            .value;
+        let pdu = decoded.pdu().unwrap();
+        assert_eq!(pdu.pdu_type(), PduType::Report);
+        assert_eq!(pdu.varbinds[0].oid, super::snmp_unknown_contexts_oid());
+        assert_eq!(red.get.load(Ordering::Relaxed), 1);
+        assert_eq!(default.get.load(Ordering::Relaxed), 1);
+        let event = events.try_recv().expect("unknown context event");
+        assert_eq!(event.kind(), crate::SecurityEventKind::UnknownContext);
+    }
+
+    /// VACM access entries only grant access to the contexts their
+    /// contextPrefix matches.
+    #[tokio::test]
+    async fn vacm_context_prefix_restricts_access_to_matching_contexts() {
+        let engine_id = b"\x80\x00\x00\x00\x01agenteng".to_vec();
+        let red = Arc::new(CallbackCounts::default());
+        let blue = Arc::new(CallbackCounts::default());
+        let agent = Agent::builder()
+            .bind("127.0.0.1:0")
+            .engine_id(engine_id.clone())
+            .usm_user("noauthuser", Ok)
+            .unwrap()
+            .context("vrf-red", |ctx| {
+                ctx.handler(oid!(1, 3, 6, 1, 4, 1, 99999), red.clone())
+            })
+            .context("vrf-blue", |ctx| {
+                ctx.handler(oid!(1, 3, 6, 1, 4, 1, 99999), blue.clone())
+            })
+            .vacm(|vacm| {
+                vacm.group("noauthuser", SecurityModel::Usm, "red-operators")
+                    .access(
+                        "red-operators",
+                        SecurityModel::Usm,
+                        SecurityLevel::NoAuthNoPriv,
+                        |access| access.context_prefix("vrf-red").read_view("all"),
+                    )
+                    .view("all", |view| view.include(oid!(1, 3, 6)))
+            })
+            .build()
+            .await
+            .unwrap();
+        let source: SocketAddr = "127.0.0.1:9999".parse().unwrap();
+
+        for (context, expected) in [
+            (b"vrf-red".as_slice(), crate::ErrorStatus::NoError),
+            (
+                b"vrf-blue".as_slice(),
+                crate::ErrorStatus::AuthorizationError,
+            ),
+        ] {
+            let response = agent
+                .handle_v3(build_context_get(&engine_id, context), source)
+                .await
+                .unwrap()
+                .expect("served context produces a Response");
+            let response = V3Message::decode(response, crate::DecodeConfig::default())
+                .unwrap()
+                .value;
+            assert_eq!(response.pdu().unwrap().error_status(), expected.as_i32());
+        }
+        assert_eq!(red.get.load(Ordering::Relaxed), 1);
+        assert_no_callbacks(&blue);
+    }
+
     #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
     #[tokio::test]
     async fn unrestricted_agent_accepts_keyed_user_at_noauth_level() {
@@ -113,7 +113,7 @@ impl Agent {
                 return pdu.to_error_response(ctx.version(), status, index + 1);
             }
 
-            let handler = self.find_handler(&vb.oid);
+            let handler = self.find_handler(ctx.context_name(), &vb.oid);
 
             if handler.is_none() {
                 // Free resources for all previously successful varbinds
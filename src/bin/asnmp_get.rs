@@ -7,7 +7,7 @@ use async_snmp::cli::args::{CommonArgs, OutputArgs, V3Args};
 use async_snmp::cli::output::VarBindFormatter;
 use async_snmp::cli::output::{
     OperationType, OutputContext, RequestInfo, build_security_info, write_error,
-    write_verbose_request, write_verbose_response,
+    write_verbose_engine, write_verbose_request, write_verbose_response,
 };
 use async_snmp::{Auth, Client, Oid};
 use clap::Parser;
@@ -175,5 +175,11 @@ async fn run_get(
         .connect()
         .await?;
 
-    client.get_many(oids).await
+    let result = client.get_many(oids).await;
+    if args.output.verbose
+        && let Some(engine_id) = client.engine_id()
+    {
+        write_verbose_engine(&engine_id);
+    }
+    result
 }
@@ -7,7 +7,7 @@ use async_snmp::cli::args::{CommonArgs, OutputArgs, V3Args, ValueType};
 use async_snmp::cli::output::VarBindFormatter;
 use async_snmp::cli::output::{
     OperationType, OutputContext, RequestInfo, build_security_info, write_error,
-    write_verbose_request, write_verbose_response,
+    write_verbose_engine, write_verbose_request, write_verbose_response,
 };
 use async_snmp::client::DEFAULT_MAX_OIDS_PER_REQUEST;
 use async_snmp::{Auth, Client, Oid, Value};
@@ -241,12 +241,18 @@ async fn run_set(
     // Convert to (Oid, Value) pairs
     let pairs: Vec<(Oid, Value)> = varbinds.into_iter().map(|vb| (vb.oid, vb.value)).collect();
 
-    if pairs.len() == 1 {
+    let result = if pairs.len() == 1 {
         let (oid, value) = pairs.into_iter().next().unwrap();
         client.set(&oid, value).await
     } else {
         client.set_many(&pairs).await
+    };
+    if args.output.verbose
+        && let Some(engine_id) = client.engine_id()
+    {
+        write_verbose_engine(&engine_id);
     }
+    result
 }
 
 #[cfg(test)]
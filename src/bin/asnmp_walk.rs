@@ -7,7 +7,7 @@ use async_snmp::cli::args::{CommonArgs, OutputArgs, V3Args, WalkArgs};
 use async_snmp::cli::output::VarBindFormatter;
 use async_snmp::cli::output::{
     OperationType, OutputContext, RequestInfo, build_security_info, write_error,
-    write_verbose_request, write_verbose_response,
+    write_verbose_engine, write_verbose_request, write_verbose_response,
 };
 use async_snmp::{Auth, Client, Oid, VarBind, Version, WalkMethod, WalkOptions};
 use clap::Parser;
@@ -189,5 +189,11 @@ async fn run_walk(
         .await?;
 
     // Use unified walk() which respects the walk_mode setting
-    client.walk(oid)?.collect().await
+    let result = client.walk(oid)?.collect().await;
+    if args.output.verbose
+        && let Some(engine_id) = client.engine_id()
+    {
+        write_verbose_engine(&engine_id);
+    }
+    result
 }
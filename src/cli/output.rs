@@ -6,6 +6,7 @@ use crate::cli::args::{OutputArgs, OutputFormat};
 use crate::cli::hints;
 use crate::client::Auth;
 use crate::format::hex;
+use crate::v3::EngineId;
 use crate::{Oid, Value, ValueKind, VarBind, Version};
 use serde::Serialize;
 use std::io::{self, Write};
@@ -42,6 +43,7 @@ impl std::fmt::Display for OperationType {
 
 /// Security info for verbose output.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum SecurityInfo {
     Community,
     V3 {
@@ -49,6 +51,9 @@ pub enum SecurityInfo {
         auth_protocol: Option<String>,
         priv_protocol: Option<String>,
     },
+    Tsm {
+        security_name: bytes::Bytes,
+    },
 }
 
 /// Request metadata for verbose output.
@@ -89,6 +94,9 @@ fn write_verbose_request_to(mut output: impl Write, info: &RequestInfo) {
                 let _ = writeln!(output, "Privacy:   {}", priv_p);
             }
         }
+        SecurityInfo::Tsm { security_name } => {
+            let _ = writeln!(output, "TSM name:  {security_name:?}");
+        }
     }
 
     let _ = writeln!(output, "Operation: {}", info.operation);
@@ -116,6 +124,18 @@ fn write_verbose_request_to(mut output: impl Write, info: &RequestInfo) {
     let _ = writeln!(output);
 }
 
+/// Write the discovered SNMPv3 engine ID to stderr.
+pub fn write_verbose_engine(engine_id: &EngineId) {
+    let mut stderr = std::io::stderr().lock();
+    write_verbose_engine_to(&mut stderr, engine_id);
+}
+
+fn write_verbose_engine_to(mut output: impl Write, engine_id: &EngineId) {
+    let _ = writeln!(output, "--- Engine ---");
+    let _ = writeln!(output, "Engine ID: {engine_id}");
+    let _ = writeln!(output);
+}
+
 /// Write verbose response summary to stderr.
 pub fn write_verbose_response(
     varbinds: &[VarBind],
@@ -628,6 +648,9 @@ fn format_timeticks(centiseconds: u32) -> String {
 pub fn build_security_info(auth: &Auth) -> SecurityInfo {
     match auth {
         Auth::Community { .. } => SecurityInfo::Community,
+        Auth::Tsm(config) => SecurityInfo::Tsm {
+            security_name: config.security_name().clone(),
+        },
         Auth::Usm(config) => SecurityInfo::V3 {
             username: config.username().clone(),
             auth_protocol: config.auth_protocol().map(|protocol| protocol.to_string()),
@@ -760,6 +783,19 @@ mod tests {
         assert!(!debug.contains(secret));
     }
 
+    #[test]
+    fn verbose_engine_shows_decoded_engine_id() {
+        let engine_id = EngineId::mac(9, [0, 0, 0x0c, 2, 0x58, 8]).unwrap();
+        let mut output = Vec::new();
+
+        write_verbose_engine_to(&mut output, &engine_id);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.contains("Engine ID: 800000090300000c025808 (Cisco, MAC 00:00:0c:02:58:08)")
+        );
+    }
+
     #[test]
     fn verbose_varbind_honors_force_hex() {
         let vb = VarBind::new(
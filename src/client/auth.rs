@@ -1,6 +1,7 @@
 //! Client authentication configuration.
 //!
-//! [`Auth`] supports SNMPv1/v2c community identifiers and SNMPv3 USM.
+//! [`Auth`] supports SNMPv1/v2c community identifiers, SNMPv3 USM, and the
+//! SNMPv3 Transport Security Model over a secure transport.
 //!
 //! # Reusing master keys
 //!
@@ -23,7 +24,7 @@
 //! ```
 
 use crate::Community;
-use crate::v3::UsmConfig;
+use crate::v3::{TsmConfig, UsmConfig};
 pub use crate::version::CommunityVersion;
 use crate::version::Version;
 
@@ -32,6 +33,7 @@ use crate::version::Version;
 /// The [`Debug`] implementation redacts community identifiers so that credentials
 /// are not leaked through logs or diagnostics.
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum Auth {
     /// Community authentication (`SNMPv1` or v2c).
     Community {
@@ -42,6 +44,8 @@ pub enum Auth {
     },
     /// User-based Security Model (`SNMPv3`).
     Usm(UsmConfig),
+    /// Transport Security Model (`SNMPv3` over TLS or DTLS).
+    Tsm(TsmConfig),
 }
 
 impl Default for Auth {
@@ -145,28 +149,35 @@ impl Auth {
                 version: CommunityVersion::V2c,
                 ..
             } => Version::V2c,
-            Auth::Usm(_) => Version::V3,
+            Auth::Usm(_) | Auth::Tsm(_) => Version::V3,
         }
     }
 
     pub(crate) fn community(&self) -> Option<&Community> {
         match self {
             Auth::Community { community, .. } => Some(community),
-            Auth::Usm(_) => None,
+            Auth::Usm(_) | Auth::Tsm(_) => None,
         }
     }
 
     pub(crate) fn community_version(&self) -> Option<CommunityVersion> {
         match self {
             Auth::Community { version, .. } => Some(*version),
-            Auth::Usm(_) => None,
+            Auth::Usm(_) | Auth::Tsm(_) => None,
         }
     }
 
     pub(crate) fn usm_config(&self) -> Option<&UsmConfig> {
         match self {
             Auth::Usm(config) => Some(config),
-            Auth::Community { .. } => None,
+            Auth::Community { .. } | Auth::Tsm(_) => None,
+        }
+    }
+
+    pub(crate) fn tsm_config(&self) -> Option<&TsmConfig> {
+        match self {
+            Auth::Tsm(config) => Some(config),
+            Auth::Community { .. } | Auth::Usm(_) => None,
         }
     }
 }
@@ -177,6 +188,12 @@ impl From<UsmConfig> for Auth {
     }
 }
 
+impl From<TsmConfig> for Auth {
+    fn from(config: TsmConfig) -> Self {
+        Self::Tsm(config)
+    }
+}
+
 /// Placeholder printed in place of a redacted secret value.
 const REDACTED: &str = "[REDACTED]";
 
@@ -189,6 +206,7 @@ impl std::fmt::Debug for Auth {
                 .field("community", &REDACTED)
                 .finish(),
             Auth::Usm(usm) => f.debug_tuple("Auth::Usm").field(usm).finish(),
+            Auth::Tsm(tsm) => f.debug_tuple("Auth::Tsm").field(tsm).finish(),
         }
     }
 }
@@ -209,7 +227,7 @@ mod tests {
                 assert_eq!(version, CommunityVersion::V2c);
                 assert!(community.matches(b"public"));
             }
-            Auth::Usm(_) => panic!("expected Community variant"),
+            Auth::Usm(_) | Auth::Tsm(_) => panic!("expected Community variant"),
         }
     }
 
@@ -221,7 +239,7 @@ mod tests {
                 assert_eq!(version, CommunityVersion::V1);
                 assert!(community.matches(b"private"));
             }
-            Auth::Usm(_) => panic!("expected Community variant"),
+            Auth::Usm(_) | Auth::Tsm(_) => panic!("expected Community variant"),
         }
     }
 
@@ -233,7 +251,7 @@ mod tests {
                 assert_eq!(version, CommunityVersion::V2c);
                 assert!(community.matches(b"secret"));
             }
-            Auth::Usm(_) => panic!("expected Community variant"),
+            Auth::Usm(_) | Auth::Tsm(_) => panic!("expected Community variant"),
         }
     }
 
@@ -253,7 +271,7 @@ mod tests {
                     assert_eq!(version, expected_version);
                     assert!(actual.matches(&community));
                 }
-                Auth::Usm(_) => panic!("expected Community variant"),
+                Auth::Usm(_) | Auth::Tsm(_) => panic!("expected Community variant"),
             }
         }
     }
@@ -273,7 +291,7 @@ mod tests {
                 assert_eq!(usm.security_level(), SecurityLevel::NoAuthNoPriv);
                 assert!(usm.configured_context_name().is_empty());
             }
-            Auth::Community { .. } => panic!("expected Usm variant"),
+            Auth::Community { .. } | Auth::Tsm(_) => panic!("expected Usm variant"),
         }
     }
 
@@ -290,7 +308,7 @@ mod tests {
                 assert_eq!(usm.username().as_ref(), b"admin");
                 assert_eq!(usm.security_level(), SecurityLevel::AuthNoPriv);
             }
-            Auth::Community { .. } => panic!("expected Usm variant"),
+            Auth::Community { .. } | Auth::Tsm(_) => panic!("expected Usm variant"),
         }
     }
 
@@ -312,7 +330,7 @@ mod tests {
                 assert_eq!(usm.username().as_ref(), b"admin");
                 assert_eq!(usm.security_level(), SecurityLevel::AuthPriv);
             }
-            Auth::Community { .. } => panic!("expected Usm variant"),
+            Auth::Community { .. } | Auth::Tsm(_) => panic!("expected Usm variant"),
         }
     }
 
@@ -330,7 +348,7 @@ mod tests {
                 assert_eq!(usm.username().as_ref(), b"admin");
                 assert_eq!(usm.configured_context_name().as_ref(), b"vlan100");
             }
-            Auth::Community { .. } => panic!("expected Usm variant"),
+            Auth::Community { .. } | Auth::Tsm(_) => panic!("expected Usm variant"),
         }
     }
 
@@ -356,7 +374,7 @@ mod tests {
                 assert_eq!(usm.security_level(), SecurityLevel::AuthPriv);
                 assert_eq!(usm.configured_context_name().as_ref(), b"ctx");
             }
-            Auth::Community { .. } => panic!("expected Usm variant"),
+            Auth::Community { .. } | Auth::Tsm(_) => panic!("expected Usm variant"),
         }
     }
 
@@ -8,15 +8,24 @@ use std::fmt;
 use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::task::Poll;
 use std::time::Duration;
 
+use futures_util::stream::{FuturesUnordered, StreamExt};
+
 use super::Client;
+use super::resolution::{
+    self, ResolutionObserver, ResolutionPolicy, SharedObserver, TargetResolver,
+    TargetResolverConfig,
+};
 use crate::client::retry::Retry;
 use crate::client::walk::WalkOptions;
 use crate::client::{Auth, ClientConfig};
+use crate::credentials::{CredentialProvider, CredentialRefresh, CredentialSource};
 use crate::error::{ConstructionStage, Error, Result};
 use crate::transport::{
-    CommunityResponsePolicy, TcpTransport, Transport, UdpControl, UdpHandle, UdpTransport,
+    CommunityResponsePolicy, ShardedUdpTransport, SocketOptions, TcpTransport, Transport,
+    UdpControl, UdpHandle, UdpTransport, UdpTransportBuilder,
 };
 use crate::v3::{AuthoritativeEngine, DesSaltState, EngineCache};
 
@@ -154,6 +163,7 @@ impl From<SocketAddr> for Target {
 pub struct ClientBuilder {
     config: ClientConfig,
     engine_cache: Option<Arc<EngineCache>>,
+    credentials: Option<CredentialSource>,
 }
 
 /// Builder for constructing a client using a library-maintained target
@@ -179,6 +189,9 @@ pub struct TargetClientBuilder {
     target: Target,
     construction_timeout: Duration,
     strict_source: bool,
+    socket_options: SocketOptions,
+    resolution_policy: ResolutionPolicy,
+    resolution_observer: Option<SharedObserver>,
 }
 
 impl ClientBuilder {
@@ -215,6 +228,7 @@ impl ClientBuilder {
         Self {
             config,
             engine_cache: None,
+            credentials: None,
         }
     }
 
@@ -229,6 +243,9 @@ impl ClientBuilder {
             target: target.into(),
             construction_timeout: DEFAULT_CONSTRUCTION_TIMEOUT,
             strict_source: false,
+            socket_options: SocketOptions::new(),
+            resolution_policy: ResolutionPolicy::new(),
+            resolution_observer: None,
         }
     }
 
@@ -457,6 +474,37 @@ impl ClientBuilder {
         self
     }
 
+    /// Take community or USM credentials from a rotating provider.
+    ///
+    /// The provider is fetched once when the client is built and then every
+    /// [`CredentialRefresh::interval`]. Community clients use the first
+    /// community of each set and keep their SNMP version; USM clients use the
+    /// first USM user and keep their context name. Requests use the
+    /// configured authentication until the first fetch completes, so the
+    /// client must be built inside a runtime. Outgoing requests have no
+    /// overlap window: the previous credentials stop being used as soon as a
+    /// new set is installed. TSM clients reject a provider.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use async_snmp::{Auth, ClientBuilder, CredentialRefresh, PollingFileCredentialProvider};
+    ///
+    /// let builder = ClientBuilder::new(Auth::v2c("bootstrap")).credential_provider(
+    ///     PollingFileCredentialProvider::new("/run/secrets/snmp"),
+    ///     CredentialRefresh::default(),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn credential_provider(
+        mut self,
+        provider: impl CredentialProvider,
+        refresh: CredentialRefresh,
+    ) -> Self {
+        self.credentials = Some(CredentialSource::new(provider, refresh));
+        self
+    }
+
     /// Set the v1/v2c response-community correlation policy.
     ///
     /// Exact byte matching is the default while UDP source checking remains
@@ -501,6 +549,9 @@ impl ClientBuilder {
 
     fn validate_and_precompute(&mut self) -> Result<()> {
         self.config.validate_and_precompute()?;
+        if let Some(credentials) = &self.credentials {
+            credentials.validate()?;
+        }
         Ok(())
     }
 
@@ -549,11 +600,15 @@ impl ClientBuilder {
     fn build_inner<T: Transport>(self, transport: T) -> Result<Client<T>> {
         let config = self.config;
 
-        if let Some(cache) = self.engine_cache {
-            Client::with_engine_cache(transport, config, cache)
+        let mut client = if let Some(cache) = self.engine_cache {
+            Client::with_engine_cache(transport, config, cache)?
         } else {
-            Client::new(transport, config)
+            Client::new(transport, config)?
+        };
+        if let Some(credentials) = self.credentials {
+            client.attach_credentials(credentials)?;
         }
+        Ok(client)
     }
 }
 
@@ -602,6 +657,57 @@ impl TargetClientBuilder {
         self
     }
 
+    /// Set interface, source address, and IP header options for the socket
+    /// created by [`connect()`](Self::connect),
+    /// [`connect_with_control()`](Self::connect_with_control), or
+    /// [`connect_tcp()`](Self::connect_tcp).
+    ///
+    /// Shared transports passed to [`build_with()`](Self::build_with) keep
+    /// their own options.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use async_snmp::{Auth, Client};
+    /// use async_snmp::transport::SocketOptions;
+    ///
+    /// # async fn example() -> async_snmp::Result<()> {
+    /// let client = Client::builder("10.0.0.1:161", Auth::v2c("public"))
+    ///     .socket_options(SocketOptions::new().bind_device("mgmt").dscp(16))
+    ///     .connect()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn socket_options(mut self, options: SocketOptions) -> Self {
+        self.socket_options = options;
+        self
+    }
+
+    /// Re-resolve a hostname target after construction (default: never).
+    ///
+    /// See [`ResolutionPolicy`] for the triggers and how the next address is
+    /// chosen. Re-resolution applies to UDP clients; [`connect_tcp()`](Self::connect_tcp)
+    /// rejects a policy with a trigger because its transport is bound to one
+    /// connection, but honors [`ResolutionPolicy::happy_eyeballs`]. Each
+    /// lookup is bounded by the [construction timeout](Self::construction_timeout).
+    #[must_use]
+    pub fn resolution_policy(mut self, policy: ResolutionPolicy) -> Self {
+        self.resolution_policy = policy;
+        self
+    }
+
+    /// Report address changes made by re-resolution.
+    ///
+    /// Has no effect unless [`resolution_policy`](Self::resolution_policy)
+    /// sets a trigger.
+    #[must_use]
+    pub fn on_address_change(mut self, observer: impl ResolutionObserver) -> Self {
+        self.resolution_observer = Some(SharedObserver(Arc::new(observer)));
+        self
+    }
+
     /// Set the confirmed-request timeout.
     #[must_use]
     pub fn request_timeout(mut self, timeout: Duration) -> Self {
@@ -673,6 +779,19 @@ impl TargetClientBuilder {
         self
     }
 
+    /// Take community or USM credentials from a rotating provider.
+    ///
+    /// See [`ClientBuilder::credential_provider`].
+    #[must_use]
+    pub fn credential_provider(
+        mut self,
+        provider: impl CredentialProvider,
+        refresh: CredentialRefresh,
+    ) -> Self {
+        self.client = self.client.credential_provider(provider, refresh);
+        self
+    }
+
     /// Set v1/v2c response-community correlation policy.
     #[must_use]
     pub fn community_response_policy(mut self, policy: CommunityResponsePolicy) -> Self {
@@ -695,33 +814,21 @@ impl TargetClientBuilder {
     #[cfg(test)]
     async fn resolve_targets(&self) -> Result<Vec<SocketAddr>> {
         let deadline = ConstructionDeadline::new(&self.target, self.construction_timeout)?;
-        self.resolve_targets_with(&deadline, |host, port| async move {
-            tokio::net::lookup_host((host.as_str(), port))
-                .await
-                .map(|addresses| addresses.collect())
-                .map_err(|error| {
-                    Error::Config(format!("could not resolve address '{host}': {error}").into())
-                        .boxed()
-                })
-        })
-        .await
+        self.resolve_targets_with(&deadline, &system_lookup).await
     }
 
     async fn resolve_targets_with<F, Fut>(
         &self,
         deadline: &ConstructionDeadline,
-        resolver: F,
+        resolver: &F,
     ) -> Result<Vec<SocketAddr>>
     where
-        F: FnOnce(String, u16) -> Fut,
+        F: Fn(String, u16) -> Fut,
         Fut: Future<Output = Result<Vec<SocketAddr>>>,
     {
-        let (host, port) = match &self.target {
-            Target::Address(addr) => split_host_port(addr),
-            Target::HostPort(host, port) => (host.as_str(), *port),
-        };
-        let host = host.to_owned();
+        let (host, port) = self.host_port();
         let original_target = self.target.clone();
+        let policy = self.resolution_policy;
 
         deadline
             .run(ConstructionStage::Resolve, async move {
@@ -736,13 +843,59 @@ impl TargetClientBuilder {
                     )
                     .boxed());
                 }
-                Ok(addresses)
+                Ok(policy.order(addresses))
             })
             .await
     }
 
+    fn host_port(&self) -> (String, u16) {
+        let (host, port) = match &self.target {
+            Target::Address(addr) => split_host_port(addr),
+            Target::HostPort(host, port) => (host.as_str(), *port),
+        };
+        (host.to_owned(), port)
+    }
+
+    /// Re-resolution state for a UDP client, when the policy asks for it and
+    /// the target names a host.
+    fn target_resolver<R, RFut>(&self, handle: &UdpHandle, resolver: R) -> Option<TargetResolver>
+    where
+        R: Fn(String, u16) -> RFut + Send + Sync + 'static,
+        RFut: Future<Output = Result<Vec<SocketAddr>>> + Send + 'static,
+    {
+        let (host, port) = self.host_port();
+        if !self.resolution_policy.re_resolves() || host.parse::<std::net::IpAddr>().is_ok() {
+            return None;
+        }
+        Some(TargetResolver::new(
+            TargetResolverConfig {
+                target: self.target.clone(),
+                host,
+                port,
+                policy: self.resolution_policy,
+                observer: self.resolution_observer.clone(),
+                lookup: resolution::lookup(resolver),
+                lookup_timeout: self.construction_timeout,
+            },
+            handle.peer(),
+        ))
+    }
+
+    fn build_udp_client<R, RFut>(self, handle: UdpHandle, resolver: R) -> Result<Client<UdpHandle>>
+    where
+        R: Fn(String, u16) -> RFut + Send + Sync + 'static,
+        RFut: Future<Output = Result<Vec<SocketAddr>>> + Send + 'static,
+    {
+        let target_resolver = self.target_resolver(&handle, resolver);
+        let mut client = self.client.build_inner(handle)?;
+        if let Some(target_resolver) = target_resolver {
+            client.attach_resolver(target_resolver);
+        }
+        Ok(client)
+    }
+
     fn select_udp_handle(
-        transport: &UdpTransport,
+        transport: &impl UdpHandleSource,
         target: &Target,
         candidates: &[SocketAddr],
     ) -> Result<UdpHandle> {
@@ -821,19 +974,8 @@ impl TargetClientBuilder {
     /// # }
     /// ```
     pub async fn connect_with_control(self) -> Result<(Client<UdpHandle>, UdpControl)> {
-        self.connect_with_control_using(
-            |host, port| async move {
-                tokio::net::lookup_host((host.as_str(), port))
-                    .await
-                    .map(|addresses| addresses.collect())
-                    .map_err(|error| {
-                        Error::Config(format!("could not resolve address '{host}': {error}").into())
-                            .boxed()
-                    })
-            },
-            |bind_addr| async move { UdpTransport::bind(bind_addr).await },
-        )
-        .await
+        self.connect_with_control_using(system_lookup, |builder| builder.build())
+            .await
     }
 
     async fn connect_with_control_using<R, RFut, B, BFut>(
@@ -842,28 +984,37 @@ impl TargetClientBuilder {
         binder: B,
     ) -> Result<(Client<UdpHandle>, UdpControl)>
     where
-        R: FnOnce(String, u16) -> RFut,
-        RFut: Future<Output = Result<Vec<SocketAddr>>>,
-        B: FnOnce(&'static str) -> BFut,
+        R: Fn(String, u16) -> RFut + Send + Sync + 'static,
+        RFut: Future<Output = Result<Vec<SocketAddr>>> + Send + 'static,
+        B: FnOnce(UdpTransportBuilder) -> BFut,
         BFut: Future<Output = Result<UdpTransport>>,
     {
         self.client.validate_and_precompute()?;
+        self.resolution_policy.validate()?;
         let deadline = ConstructionDeadline::new(&self.target, self.construction_timeout)?;
-        let addr = self.resolve_targets_with(&deadline, resolver).await?[0];
+        let candidates = self.resolve_targets_with(&deadline, &resolver).await?;
+        let addr = candidates[0];
         // Match bind address to target address family for cross-platform
         // compatibility. Dual-stack ([::]:0) only works reliably on Linux;
         // macOS/BSD default to IPV6_V6ONLY=1 and reject IPv4 targets.
-        let bind_addr = if addr.is_ipv6() {
+        // Happy eyeballs needs both families, so it binds dual-stack whenever
+        // an IPv6 candidate exists.
+        let dual_stack =
+            self.resolution_policy.happy_eyeballs && candidates.iter().any(SocketAddr::is_ipv6);
+        let bind_addr = if addr.is_ipv6() || dual_stack {
             "[::]:0"
         } else {
             "0.0.0.0:0"
         };
+        let builder = UdpTransport::builder()
+            .bind(bind_addr)
+            .socket_options(self.socket_options.clone());
         let transport = deadline
-            .run(ConstructionStage::Bind, binder(bind_addr))
+            .run(ConstructionStage::Bind, binder(builder))
             .await?;
         let control = transport.control();
         let handle = transport.handle(addr)?.strict_source(self.strict_source);
-        let client = self.client.build_inner(handle)?;
+        let client = self.build_udp_client(handle, resolver)?;
         Ok((client, control))
     }
 
@@ -898,33 +1049,41 @@ impl TargetClientBuilder {
     /// # }
     /// ```
     pub async fn build_with(self, transport: &UdpTransport) -> Result<Client<UdpHandle>> {
-        self.build_with_resolver(transport, |host, port| async move {
-            tokio::net::lookup_host((host.as_str(), port))
-                .await
-                .map(|addresses| addresses.collect())
-                .map_err(|error| {
-                    Error::Config(format!("could not resolve address '{host}': {error}").into())
-                        .boxed()
-                })
-        })
-        .await
+        self.build_with_resolver(transport, system_lookup).await
+    }
+
+    /// Build a per-target client handle on a sharded UDP transport.
+    ///
+    /// Like [`build_with()`](Self::build_with), but the handle is created on
+    /// the [`ShardedUdpTransport`] shard that serves the resolved address.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] when resolution produces no address compatible
+    /// with the shards' socket family.
+    pub async fn build_with_sharded(
+        self,
+        transport: &ShardedUdpTransport,
+    ) -> Result<Client<UdpHandle>> {
+        self.build_with_resolver(transport, system_lookup).await
     }
 
     async fn build_with_resolver<R, RFut>(
         mut self,
-        transport: &UdpTransport,
+        transport: &impl UdpHandleSource,
         resolver: R,
     ) -> Result<Client<UdpHandle>>
     where
-        R: FnOnce(String, u16) -> RFut,
-        RFut: Future<Output = Result<Vec<SocketAddr>>>,
+        R: Fn(String, u16) -> RFut + Send + Sync + 'static,
+        RFut: Future<Output = Result<Vec<SocketAddr>>> + Send + 'static,
     {
         self.client.validate_and_precompute()?;
+        self.resolution_policy.validate()?;
         let deadline = ConstructionDeadline::new(&self.target, self.construction_timeout)?;
-        let candidates = self.resolve_targets_with(&deadline, resolver).await?;
+        let candidates = self.resolve_targets_with(&deadline, &resolver).await?;
         let handle = Self::select_udp_handle(transport, &self.target, &candidates)?
             .strict_source(self.strict_source);
-        self.client.build_inner(handle)
+        self.build_udp_client(handle, resolver)
     }
 
     /// Connect via TCP.
@@ -964,18 +1123,12 @@ impl TargetClientBuilder {
     /// # }
     /// ```
     pub async fn connect_tcp(self) -> Result<Client<TcpTransport>> {
-        self.connect_tcp_with(
-            |host, port| async move {
-                tokio::net::lookup_host((host.as_str(), port))
-                    .await
-                    .map(|addresses| addresses.collect())
-                    .map_err(|error| {
-                        Error::Config(format!("could not resolve address '{host}': {error}").into())
-                            .boxed()
-                    })
-            },
-            |address| async move { TcpTransport::connect(address).await },
-        )
+        let socket_options = self.socket_options.clone();
+        self.connect_tcp_with(system_lookup, |address| {
+            TcpTransport::builder()
+                .socket_options(socket_options.clone())
+                .connect(address)
+        })
         .await
     }
 
@@ -985,26 +1138,47 @@ impl TargetClientBuilder {
         mut connector: C,
     ) -> Result<Client<TcpTransport>>
     where
-        R: FnOnce(String, u16) -> RFut,
+        R: Fn(String, u16) -> RFut,
         RFut: Future<Output = Result<Vec<SocketAddr>>>,
         C: FnMut(SocketAddr) -> CFut,
         CFut: Future<Output = Result<TcpTransport>>,
     {
         self.client.validate_and_precompute()?;
+        self.resolution_policy.validate()?;
+        if self.resolution_policy.re_resolves() {
+            return Err(Error::Config(
+                "target re-resolution requires a UDP client; a TCP client keeps one connection"
+                    .into(),
+            )
+            .boxed());
+        }
         let deadline = ConstructionDeadline::new(&self.target, self.construction_timeout)?;
-        let candidates = self.resolve_targets_with(&deadline, resolver).await?;
+        let candidates = self.resolve_targets_with(&deadline, &resolver).await?;
         let mut last_error = None;
 
-        for address in candidates {
+        if self.resolution_policy.happy_eyeballs {
             match deadline
-                .run(ConstructionStage::Connect, connector(address))
+                .run(ConstructionStage::Connect, async {
+                    Ok(race_connections(candidates, &mut connector).await)
+                })
                 .await
             {
-                Ok(transport) => return self.client.build_inner(transport),
-                Err(error) if matches!(*error, Error::ConstructionTimeout { .. }) => {
-                    return Err(error);
+                Ok(Ok(transport)) => return self.client.build_inner(transport),
+                Ok(Err(error)) => last_error = error,
+                Err(error) => return Err(error),
+            }
+        } else {
+            for address in candidates {
+                match deadline
+                    .run(ConstructionStage::Connect, connector(address))
+                    .await
+                {
+                    Ok(transport) => return self.client.build_inner(transport),
+                    Err(error) if matches!(*error, Error::ConstructionTimeout { .. }) => {
+                        return Err(error);
+                    }
+                    Err(error) => last_error = Some(error),
                 }
-                Err(error) => last_error = Some(error),
             }
         }
 
@@ -1022,6 +1196,82 @@ impl TargetClientBuilder {
     }
 }
 
+/// Delay before starting the next connection attempt while earlier attempts
+/// are still pending (RFC 8305 Section 5).
+const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Race connection attempts to `candidates` in order (RFC 8305 Section 5).
+///
+/// Each attempt starts [`CONNECTION_ATTEMPT_DELAY`] after the previous one,
+/// or as soon as every started attempt has failed. The first connection
+/// wins and the attempts still pending are dropped. When every attempt fails,
+/// returns the last error.
+async fn race_connections<C, CFut>(
+    candidates: Vec<SocketAddr>,
+    connector: &mut C,
+) -> std::result::Result<TcpTransport, Option<Box<Error>>>
+where
+    C: FnMut(SocketAddr) -> CFut,
+    CFut: Future<Output = Result<TcpTransport>>,
+{
+    enum Step {
+        Finished(Result<TcpTransport>),
+        Idle,
+        Delay,
+    }
+
+    let mut candidates = candidates.into_iter().peekable();
+    let mut attempts = FuturesUnordered::new();
+    let mut delay = std::pin::pin!(crate::runtime::sleep(CONNECTION_ATTEMPT_DELAY));
+    let mut last_error = None;
+    if let Some(address) = candidates.next() {
+        attempts.push(connector(address));
+    }
+    loop {
+        let step = std::future::poll_fn(|context| {
+            match attempts.poll_next_unpin(context) {
+                Poll::Ready(Some(result)) => return Poll::Ready(Step::Finished(result)),
+                Poll::Ready(None) => return Poll::Ready(Step::Idle),
+                Poll::Pending => {}
+            }
+            if candidates.peek().is_some() && delay.as_mut().poll(context).is_ready() {
+                return Poll::Ready(Step::Delay);
+            }
+            Poll::Pending
+        })
+        .await;
+        match step {
+            Step::Finished(Ok(transport)) => return Ok(transport),
+            Step::Finished(Err(error)) => {
+                last_error = Some(error);
+                if !attempts.is_empty() {
+                    continue;
+                }
+            }
+            Step::Idle | Step::Delay => {}
+        }
+        let Some(address) = candidates.next() else {
+            if attempts.is_empty() {
+                return Err(last_error);
+            }
+            continue;
+        };
+        attempts.push(connector(address));
+        delay
+            .as_mut()
+            .set(crate::runtime::sleep(CONNECTION_ATTEMPT_DELAY));
+    }
+}
+
+/// Resolve a host with the system resolver.
+async fn system_lookup(host: String, port: u16) -> Result<Vec<SocketAddr>> {
+    crate::runtime::lookup_host(&host, port)
+        .await
+        .map_err(|error| {
+            Error::Config(format!("could not resolve address '{host}': {error}").into()).boxed()
+        })
+}
+
 struct ConstructionDeadline {
     target: Target,
     started: tokio::time::Instant,
@@ -1049,7 +1299,7 @@ impl ConstructionDeadline {
             return Err(self.timeout_error(stage));
         }
 
-        tokio::time::timeout_at(self.deadline, future)
+        crate::runtime::timeout_at(self.deadline, future)
             .await
             .map_err(|_| self.timeout_error(stage))?
     }
@@ -1099,9 +1349,36 @@ fn split_host_port(target: &str) -> (&str, u16) {
     (target, DEFAULT_PORT)
 }
 
+/// Socket owners that derive per-target [`UdpHandle`]s.
+trait UdpHandleSource {
+    fn handle(&self, target: SocketAddr) -> Result<UdpHandle>;
+    fn local_addr(&self) -> SocketAddr;
+}
+
+impl UdpHandleSource for UdpTransport {
+    fn handle(&self, target: SocketAddr) -> Result<UdpHandle> {
+        UdpTransport::handle(self, target)
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        UdpTransport::local_addr(self)
+    }
+}
+
+impl UdpHandleSource for ShardedUdpTransport {
+    fn handle(&self, target: SocketAddr) -> Result<UdpHandle> {
+        ShardedUdpTransport::handle(self, target)
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        self.shards()[0].local_addr()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::{AddressChange, ResolutionTrigger};
     #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
     use crate::v3::MasterKeys;
     use crate::v3::UsmConfig;
@@ -1955,4 +2232,258 @@ mod tests {
             .build_config();
         assert_eq!(configured.decode_config, targeted);
     }
+
+    /// Spawn a v2c agent that answers every request with its own varbinds.
+    async fn spawn_echo_agent() -> SocketAddr {
+        let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut data = [0; 1500];
+            while let Ok((len, source)) = socket.recv_from(&mut data).await {
+                let request = crate::message::CommunityMessage::decode(
+                    bytes::Bytes::copy_from_slice(&data[..len]),
+                    crate::DecodeConfig::default(),
+                )
+                .unwrap()
+                .value;
+                let pdu = request
+                    .pdu()
+                    .standard()
+                    .unwrap()
+                    .to_response(crate::Version::V2c)
+                    .unwrap();
+                let response = crate::message::CommunityMessage::v2c(
+                    bytes::Bytes::from_static(b"public"),
+                    pdu,
+                )
+                .unwrap();
+                let _ = socket.send_to(&response.encode().unwrap(), source).await;
+            }
+        });
+        addr
+    }
+
+    type Changes = Arc<std::sync::Mutex<Vec<AddressChange>>>;
+
+    fn recording_observer() -> (Changes, impl ResolutionObserver) {
+        let changes = Changes::default();
+        let recorded = Arc::clone(&changes);
+        (changes, move |change: &AddressChange| {
+            recorded.lock().unwrap().push(change.clone());
+        })
+    }
+
+    #[tokio::test]
+    async fn ttl_re_resolution_moves_client_and_invalidates_engine_cache() {
+        let first = spawn_echo_agent().await;
+        let second = spawn_echo_agent().await;
+        let answer = Arc::new(std::sync::Mutex::new(first));
+        let resolver_answer = Arc::clone(&answer);
+        let cache = Arc::new(EngineCache::new());
+        for address in [first, second] {
+            cache
+                .insert_discovered(
+                    address,
+                    crate::v3::DiscoveredEngine::new(
+                        bytes::Bytes::from_static(b"engine"),
+                        crate::MessageSize::new(1400).unwrap(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap();
+        }
+        let (changes, observer) = recording_observer();
+
+        let (client, _control) = Client::builder("device.test", Auth::v2c("public"))
+            .resolution_policy(ResolutionPolicy::new().ttl(Duration::from_millis(50)))
+            .on_address_change(observer)
+            .engine_cache(Arc::clone(&cache))
+            .connect_with_control_using(
+                move |_, _| {
+                    let address = *resolver_answer.lock().unwrap();
+                    async move { Ok(vec![address]) }
+                },
+                |builder| builder.build(),
+            )
+            .await
+            .unwrap();
+        let oid = crate::oid!(1, 3, 6, 1, 2, 1, 1, 3, 0);
+        client.get(&oid).await.unwrap();
+        assert_eq!(client.peer_addr(), first);
+        assert!(changes.lock().unwrap().is_empty());
+
+        *answer.lock().unwrap() = second;
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        client.get(&oid).await.unwrap();
+        assert_eq!(client.peer_addr(), second);
+
+        let changes = changes.lock().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].previous(), first);
+        assert_eq!(changes[0].current(), second);
+        assert_eq!(changes[0].trigger(), ResolutionTrigger::Ttl);
+        assert_eq!(changes[0].target(), &Target::from("device.test"));
+        assert!(cache.get(&first).is_none());
+        assert!(cache.get(&second).is_none());
+    }
+
+    #[tokio::test]
+    async fn consecutive_timeouts_fail_over_to_next_resolved_address() {
+        let silent = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let unresponsive = silent.local_addr().unwrap();
+        let agent = spawn_echo_agent().await;
+        let (changes, observer) = recording_observer();
+
+        let (client, _control) = Client::builder("device.test", Auth::v2c("public"))
+            .request_timeout(Duration::from_millis(50))
+            .retry(Retry::none())
+            .resolution_policy(ResolutionPolicy::new().after_timeouts(1))
+            .on_address_change(observer)
+            .connect_with_control_using(
+                move |_, _| async move { Ok(vec![unresponsive, agent]) },
+                |builder| builder.build(),
+            )
+            .await
+            .unwrap();
+        let oid = crate::oid!(1, 3, 6, 1, 2, 1, 1, 3, 0);
+        let error = client.get(&oid).await.unwrap_err();
+        assert!(matches!(*error, Error::Timeout { .. }));
+        assert_eq!(client.peer_addr(), unresponsive);
+
+        client.get(&oid).await.unwrap();
+        assert_eq!(client.peer_addr(), agent);
+        let changes = changes.lock().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].trigger(), ResolutionTrigger::Timeouts);
+    }
+
+    #[tokio::test]
+    async fn failed_re_resolution_keeps_current_address() {
+        let agent = spawn_echo_agent().await;
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let resolver_calls = Arc::clone(&calls);
+        let (client, _control) = Client::builder("device.test", Auth::v2c("public"))
+            .resolution_policy(ResolutionPolicy::new().ttl(Duration::from_millis(20)))
+            .connect_with_control_using(
+                move |_, _| {
+                    let call = resolver_calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    async move {
+                        if call == 0 {
+                            Ok(vec![agent])
+                        } else {
+                            Err(Error::Config("lookup failed".into()).boxed())
+                        }
+                    }
+                },
+                |builder| builder.build(),
+            )
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        client
+            .get(&crate::oid!(1, 3, 6, 1, 2, 1, 1, 3, 0))
+            .await
+            .unwrap();
+        assert_eq!(client.peer_addr(), agent);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn numeric_targets_and_tcp_clients_do_not_re_resolve() {
+        let policy = ResolutionPolicy::new().ttl(Duration::from_secs(1));
+        let client = Client::builder("127.0.0.1:161", Auth::v2c("public"))
+            .resolution_policy(policy)
+            .connect()
+            .await
+            .unwrap();
+        assert!(client.inner.resolver.is_none());
+
+        let error = Client::builder("device.test", Auth::v2c("public"))
+            .resolution_policy(policy)
+            .connect_tcp_with(
+                |_, _| async { panic!("policy must be rejected before resolution") },
+                |_| async { panic!("policy must be rejected before connecting") },
+            )
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(*error, Error::Config(_)));
+
+        let error = Client::builder("device.test", Auth::v2c("public"))
+            .resolution_policy(ResolutionPolicy::new().after_timeouts(0))
+            .connect()
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(*error, Error::Config(_)));
+    }
+
+    #[tokio::test]
+    async fn happy_eyeballs_orders_tcp_candidates_by_alternating_family() {
+        let attempts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&attempts);
+        let candidates: Vec<SocketAddr> = vec![
+            "[2001:db8::1]:161".parse().unwrap(),
+            "[2001:db8::2]:161".parse().unwrap(),
+            "192.0.2.1:161".parse().unwrap(),
+        ];
+        let resolved = candidates.clone();
+        let _ = Client::builder("device.test", Auth::v2c("public"))
+            .resolution_policy(ResolutionPolicy::new().happy_eyeballs(true))
+            .connect_tcp_with(
+                move |_, _| {
+                    let resolved = resolved.clone();
+                    async move { Ok(resolved) }
+                },
+                move |address| {
+                    recorded.lock().unwrap().push(address);
+                    async move {
+                        Err(Error::Network {
+                            target: address,
+                            source: std::io::ErrorKind::ConnectionRefused.into(),
+                        }
+                        .boxed())
+                    }
+                },
+            )
+            .await;
+        assert_eq!(
+            *attempts.lock().unwrap(),
+            vec![candidates[0], candidates[2], candidates[1]]
+        );
+    }
+
+    #[tokio::test]
+    async fn happy_eyeballs_starts_next_tcp_attempt_while_first_is_pending() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let reachable = listener.local_addr().unwrap();
+        let stalled: SocketAddr = "[2001:db8::1]:161".parse().unwrap();
+        let attempts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&attempts);
+        let started = tokio::time::Instant::now();
+        let client = Client::builder("device.test", Auth::v2c("public"))
+            .resolution_policy(ResolutionPolicy::new().happy_eyeballs(true))
+            .connect_tcp_with(
+                move |_, _| async move { Ok(vec![stalled, reachable]) },
+                move |address| {
+                    recorded.lock().unwrap().push((address, started.elapsed()));
+                    async move {
+                        if address == stalled {
+                            return std::future::pending().await;
+                        }
+                        TcpTransport::builder().connect(address).await
+                    }
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(client.peer_addr(), reachable);
+        let attempts = attempts.lock().unwrap();
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[0].0, stalled);
+        assert_eq!(attempts[1].0, reachable);
+        assert!(attempts[1].1 >= CONNECTION_ATTEMPT_DELAY);
+        assert!(attempts[1].1 < DEFAULT_CONSTRUCTION_TIMEOUT);
+    }
 }
@@ -0,0 +1,283 @@
+//! SNMPv3 USM key changes (RFC 3414 Section 5).
+//!
+//! Rotating a user's passwords on a remote engine writes `KeyChange` values
+//! to the user's `usmUserTable` row instead of sending the new keys: each
+//! value is only meaningful to a party that already knows the old key.
+
+use bytes::Bytes;
+use tracing::instrument;
+
+use crate::error::{Error, Result};
+use crate::oid::Oid;
+use crate::transport::Transport;
+use crate::v3::auth::key_change_with_backend;
+use crate::v3::{EngineId, UsmConfig};
+use crate::value::Value;
+
+use super::{Auth, Client};
+
+/// `usmUserSpinLock.0` (1.3.6.1.6.3.15.1.2.1.0).
+const USM_USER_SPIN_LOCK: &[u32] = &[1, 3, 6, 1, 6, 3, 15, 1, 2, 1, 0];
+/// `usmUserEntry` (1.3.6.1.6.3.15.1.2.2.1).
+const USM_USER_ENTRY: &[u32] = &[1, 3, 6, 1, 6, 3, 15, 1, 2, 2, 1];
+
+const USM_USER_AUTH_KEY_CHANGE: u32 = 6;
+const USM_USER_OWN_AUTH_KEY_CHANGE: u32 = 7;
+const USM_USER_PRIV_KEY_CHANGE: u32 = 9;
+const USM_USER_OWN_PRIV_KEY_CHANGE: u32 = 10;
+
+/// Build `usmUserEntry.<column>.<engineID>.<userName>`.
+///
+/// Both index components are non-`IMPLIED` octet strings, so each is prefixed
+/// with its length.
+fn usm_user_column(column: u32, engine_id: &[u8], username: &[u8]) -> Oid {
+    let mut arcs = Vec::with_capacity(USM_USER_ENTRY.len() + 3 + engine_id.len() + username.len());
+    arcs.extend_from_slice(USM_USER_ENTRY);
+    arcs.push(column);
+    arcs.push(engine_id.len() as u32);
+    arcs.extend(engine_id.iter().map(|&octet| u32::from(octet)));
+    arcs.push(username.len() as u32);
+    arcs.extend(username.iter().map(|&octet| u32::from(octet)));
+    Oid::from_slice(&arcs)
+}
+
+fn random_bytes(len: usize) -> Result<Vec<u8>> {
+    let mut random = vec![0u8; len];
+    getrandom::fill(&mut random).map_err(|source| Error::RandomSource { source }.boxed())?;
+    Ok(random)
+}
+
+impl<T: Transport + Clone> Client<T> {
+    /// Change a USM user's keys on the remote engine (RFC 3414 Section 5).
+    ///
+    /// `target_user` holds the user's current credentials, which supply the
+    /// old localized keys that the `KeyChange` values are computed against.
+    /// When it names this client's own user, the `usmUserOwnAuthKeyChange` and
+    /// `usmUserOwnPrivKeyChange` columns are written, so the change works even
+    /// for a user that may only modify its own row. Any other user is changed
+    /// through `usmUserAuthKeyChange` and `usmUserPrivKeyChange`, which needs
+    /// write access to that user's row.
+    ///
+    /// The operation reads `usmUserSpinLock.0` and includes it in the single
+    /// SET that carries the key changes, so a concurrent change by another
+    /// manager fails with `inconsistentValue` instead of interleaving. A new
+    /// privacy password is required exactly when the target user has privacy.
+    ///
+    /// After the SET succeeds, a GET is sent with the new credentials to
+    /// verify them, and the verified configuration is returned. A client whose
+    /// own keys were changed keeps using its old keys; build a new client
+    /// with the returned configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if this client does not use authenticated USM,
+    /// the target user has no authentication, the new passwords do not match
+    /// the target's security level or are too short, `target_user` is
+    /// invalid, or its keys are held by a
+    /// [`CryptoProvider`](crate::v3::CryptoProvider). Errors from the SET are returned unchanged. Errors from the
+    /// verification GET are also returned unchanged; the keys have then
+    /// already been changed on the remote engine.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use async_snmp::{AuthProtocol, Client, PrivProtocol, UsmConfig};
+    /// # async fn example() -> async_snmp::Result<()> {
+    /// let current = UsmConfig::new("admin")
+    ///     .auth_priv(AuthProtocol::Sha256, "old-auth-pass", PrivProtocol::Aes128, "old-priv-pass")
+    ///     .unwrap();
+    /// let client = Client::builder("192.168.1.1", current.clone()).connect().await?;
+    ///
+    /// let rotated = client
+    ///     .change_usm_keys(&current, b"new-auth-pass", Some(b"new-priv-pass"))
+    ///     .await?;
+    /// let client = Client::builder("192.168.1.1", rotated).connect().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(skip_all, err, fields(snmp.target = %self.peer_addr()))]
+    pub async fn change_usm_keys(
+        &self,
+        target_user: &UsmConfig,
+        new_auth_password: &[u8],
+        new_priv_password: Option<&[u8]>,
+    ) -> Result<UsmConfig> {
+        let auth = self.auth();
+        let own_user = auth
+            .usm_config()
+            .filter(|security| security.auth_protocol().is_some())
+            .ok_or_else(|| {
+                Error::Config("USM key change requires an authenticated SNMPv3 client".into())
+                    .boxed()
+            })?
+            .username()
+            .clone();
+        let auth_protocol = target_user.auth_protocol().ok_or_else(|| {
+            Error::Config("USM key change requires a target user with authentication".into())
+                .boxed()
+        })?;
+        let invalid = |error: crate::v3::CryptoError| {
+            Error::Config(format!("invalid USM key change: {error}").into()).boxed()
+        };
+        let new_user = match (target_user.priv_protocol(), new_priv_password) {
+            (None, None) => target_user
+                .clone()
+                .auth(auth_protocol, new_auth_password)
+                .map_err(invalid)?,
+            (Some(priv_protocol), Some(new_priv_password)) => target_user
+                .clone()
+                .auth_priv(
+                    auth_protocol,
+                    new_auth_password,
+                    priv_protocol,
+                    new_priv_password,
+                )
+                .map_err(invalid)?,
+            (None, Some(_)) => {
+                return Err(Error::Config(
+                    "target user has no privacy protocol for a new privacy password".into(),
+                )
+                .boxed());
+            }
+            (Some(_), None) => {
+                return Err(Error::Config(
+                    "a new privacy password is required for a target user with privacy".into(),
+                )
+                .boxed());
+            }
+        };
+        if target_user.crypto_provider().is_some() {
+            // KeyChange values are computed from the old and new key bytes.
+            return Err(invalid(crate::v3::CryptoError::KeyMaterialUnavailable));
+        }
+        // Password-to-key derivation hashes a megabyte per password, so keep
+        // it off the async task.
+        let mut old_user = target_user.clone();
+        let old_user = crate::runtime::spawn_blocking(move || {
+            old_user.validate_and_precompute().map(|()| old_user)
+        })
+        .await
+        .map_err(invalid)?;
+
+        self.ensure_engine_discovered().await?;
+        let engine_id: Bytes = self
+            .inner
+            .engine
+            .read()
+            .map_err(|_| Error::Config("engine lock poisoned".into()).boxed())?
+            .as_ref()
+            .map(|engine| engine.state.engine_id().clone())
+            .ok_or_else(|| Error::Config("engine not discovered".into()).boxed())?;
+
+        let (old_keys, new_keys) = {
+            let new_user = new_user.clone();
+            let engine_id = engine_id.clone();
+            crate::runtime::spawn_blocking(move || {
+                Ok::<_, crate::v3::CryptoError>((
+                    old_user.derive_keys_inner(&engine_id)?,
+                    new_user.derive_keys_inner(&engine_id)?,
+                ))
+            })
+            .await
+            .map_err(invalid)?
+        };
+        let backend = old_keys
+            .auth_key
+            .as_ref()
+            .map(crate::v3::LocalizedKey::crypto_backend)
+            .ok_or_else(|| invalid(crate::v3::CryptoError::InvalidKeyLength))?;
+
+        let own = target_user.username() == &own_user;
+        let (auth_column, priv_column) = if own {
+            (USM_USER_OWN_AUTH_KEY_CHANGE, USM_USER_OWN_PRIV_KEY_CHANGE)
+        } else {
+            (USM_USER_AUTH_KEY_CHANGE, USM_USER_PRIV_KEY_CHANGE)
+        };
+        let username = target_user.username();
+
+        let spin_lock_oid = Oid::from_slice(USM_USER_SPIN_LOCK);
+        let spin_lock = self
+            .get(&spin_lock_oid)
+            .await?
+            .into_single()
+            .ok()
+            .and_then(|varbind| match varbind.value {
+                Value::Integer(value) => Some(value),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                Error::MalformedResponse {
+                    target: self.peer_addr(),
+                }
+                .boxed()
+            })?;
+
+        let mut varbinds = vec![(spin_lock_oid.clone(), Value::Integer(spin_lock))];
+        if let (Some(old_key), Some(new_key)) = (&old_keys.auth_key, &new_keys.auth_key) {
+            let random = random_bytes(old_key.as_bytes().len())?;
+            let value = key_change_with_backend(
+                backend,
+                auth_protocol,
+                old_key.as_bytes(),
+                new_key.as_bytes(),
+                &random,
+            )
+            .map_err(invalid)?;
+            varbinds.push((
+                usm_user_column(auth_column, &engine_id, username),
+                Value::OctetString(value.into()),
+            ));
+        }
+        if let (Some(old_key), Some(new_key)) = (&old_keys.priv_key, &new_keys.priv_key) {
+            let random = random_bytes(old_key.key_change_secret().len())?;
+            let value = key_change_with_backend(
+                backend,
+                auth_protocol,
+                old_key.key_change_secret(),
+                new_key.key_change_secret(),
+                &random,
+            )
+            .map_err(invalid)?;
+            varbinds.push((
+                usm_user_column(priv_column, &engine_id, username),
+                Value::OctetString(value.into()),
+            ));
+        }
+
+        tracing::debug!(target: "async_snmp::client", { snmp.engine_id = %EngineId::from_validated(engine_id.clone()), snmp.own_user = own, snmp.varbind_count = varbinds.len() }, "changing USM keys");
+        self.set_many(&varbinds).await?;
+
+        let mut config = self.inner.config.clone();
+        config.auth = Auth::Usm(new_user.clone());
+        let verifier = Self::with_optional_engine_cache(
+            self.inner.transport.clone(),
+            config,
+            self.inner.engine_cache.clone(),
+        )?;
+        match verifier.get(&spin_lock_oid).await {
+            // Any response authenticated with the new keys verifies them, even
+            // one refusing access to the spin lock.
+            Ok(_) => {}
+            Err(error) if matches!(*error, Error::Snmp { .. }) => {}
+            Err(error) => return Err(error),
+        }
+        tracing::debug!(target: "async_snmp::client", { snmp.own_user = own }, "verified changed USM keys");
+        Ok(new_user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usm_user_column_indexes_engine_id_and_username() {
+        let oid = usm_user_column(USM_USER_OWN_AUTH_KEY_CHANGE, &[0x80, 0x01], b"ab");
+        assert_eq!(
+            oid,
+            Oid::from_slice(&[
+                1, 3, 6, 1, 6, 3, 15, 1, 2, 2, 1, 7, 2, 0x80, 0x01, 2, 97, 98
+            ])
+        );
+    }
+}
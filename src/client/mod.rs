@@ -3,14 +3,18 @@
 mod auth;
 mod builder;
 mod chunks;
+mod key_change;
+mod resolution;
 mod response_shape;
 mod retry;
+mod tsm;
 mod v3;
 mod walk;
 
 pub use auth::{Auth, CommunityVersion};
 pub use builder::{ClientBuilder, DEFAULT_CONSTRUCTION_TIMEOUT, Target, TargetClientBuilder};
 pub use chunks::{FixedCardinalityChunk, FixedCardinalityChunkError, FixedCardinalityChunkStream};
+pub use resolution::{AddressChange, ResolutionObserver, ResolutionPolicy, ResolutionTrigger};
 pub use response_shape::{
     BulkResponse, FixedCardinalityOperation, FixedCardinalityResponse, ResponseMetadata,
     ResponseShapeAnomaly, ResponseShapePolicy,
@@ -70,6 +74,9 @@ impl Client<UdpHandle> {
         self.inner.transport.stats()
     }
 }
+use crate::credentials::{
+    ActiveCredentials, CredentialRules, CredentialSource, PreparedCredentials, RotatingCredentials,
+};
 #[cfg(test)]
 use crate::error::ErrorStatus;
 use crate::error::{Error, Result};
@@ -382,11 +389,99 @@ impl ClientEngine {
     }
 }
 
+/// Installs rotated credentials into a client's live authentication state.
+struct CredentialInstaller {
+    auth: Arc<RwLock<Arc<Auth>>>,
+    engine: Arc<RwLock<Option<ClientEngine>>>,
+    local_derived_keys: Arc<RwLock<Option<DerivedKeys>>>,
+    local_engine_id: Option<Vec<u8>>,
+}
+
+impl CredentialInstaller {
+    /// Replace the client's authentication with the set's first usable entry.
+    ///
+    /// Community clients take the first community and keep their version;
+    /// USM clients take the first user and keep their context name. Keys are
+    /// re-localized and the authentication swapped under the engine lock, so
+    /// a request never pairs one user's name with another user's keys.
+    fn install(&self, prepared: &PreparedCredentials) {
+        let current = Arc::clone(
+            &self
+                .auth
+                .read()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        );
+        let rotated = match &*current {
+            Auth::Community { version, .. } => {
+                prepared
+                    .communities
+                    .first()
+                    .map(|community| Auth::Community {
+                        version: *version,
+                        community: community.clone(),
+                    })
+            }
+            Auth::Usm(config) => prepared.usm_configs.first().map(|user| {
+                Auth::Usm(
+                    user.clone()
+                        .context_name(config.configured_context_name().clone()),
+                )
+            }),
+            Auth::Tsm(_) => None,
+        };
+        let Some(rotated) = rotated else {
+            tracing::warn!(target: "async_snmp::credentials", "credential set has no entry for this client; keeping current authentication");
+            return;
+        };
+
+        let mut engine = self
+            .engine
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut local_derived_keys = self
+            .local_derived_keys
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Auth::Usm(user) = &rotated {
+            let derived = engine
+                .as_ref()
+                .map(|engine| user.derive_keys_inner(engine.state.engine_id()))
+                .transpose()
+                .and_then(|remote| {
+                    let local = self
+                        .local_engine_id
+                        .as_deref()
+                        .map(|engine_id| user.derive_keys_inner(engine_id))
+                        .transpose()?;
+                    Ok((remote, local))
+                });
+            let (remote, local) = match derived {
+                Ok(keys) => keys,
+                Err(error) => {
+                    tracing::warn!(target: "async_snmp::credentials", { error = %error }, "cannot localize rotated USM user; keeping current authentication");
+                    return;
+                }
+            };
+            if let (Some(engine), Some(keys)) = (engine.as_mut(), remote) {
+                engine.derived_keys = keys;
+            }
+            *local_derived_keys = local;
+        }
+        *self
+            .auth
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Arc::new(rotated);
+    }
+}
+
 struct ClientInner<T: Transport> {
     transport: T,
     config: ClientConfig,
+    /// Authentication in effect: `config.auth` until a credential provider
+    /// rotates it.
+    auth: Arc<RwLock<Arc<Auth>>>,
     /// Coherent V3 identity, trusted time, and identity-localized keys.
-    engine: RwLock<Option<ClientEngine>>,
+    engine: Arc<RwLock<Option<ClientEngine>>>,
     /// Salt counter for privacy (V3)
     salt_counter: Option<SaltCounter>,
     /// Shared engine cache (V3, optional)
@@ -396,7 +491,11 @@ struct ClientInner<T: Transport> {
     /// Cancellation-safe ordinary-discovery flights, shared through the cache.
     discovery_coordinator: Arc<DiscoveryCoordinator>,
     /// Keys derived against the local authoritative engine ID for V3 traps.
-    local_derived_keys: RwLock<Option<DerivedKeys>>,
+    local_derived_keys: Arc<RwLock<Option<DerivedKeys>>>,
+    /// Rotating credentials from a credential provider.
+    credentials: Option<ActiveCredentials>,
+    /// Hostname re-resolution for clients built with a resolution policy.
+    resolver: Option<resolution::TargetResolver>,
     #[cfg(test)]
     authenticated_response_validated_hook: RwLock<Option<Arc<dyn Fn() + Send + Sync>>>,
 }
@@ -535,13 +634,6 @@ impl ClientConfig {
         self.auth.version()
     }
 
-    fn community(&self) -> Result<crate::Community> {
-        self.auth
-            .community()
-            .cloned()
-            .ok_or_else(|| Error::Config("community authentication required".into()).boxed())
-    }
-
     fn community_version(&self) -> Result<CommunityVersion> {
         self.auth
             .community_version()
@@ -643,6 +735,9 @@ impl<T: Transport> Client<T> {
         engine_cache: Option<Arc<EngineCache>>,
     ) -> Result<Self> {
         config.validate_and_precompute()?;
+        if let Auth::Tsm(security) = &config.auth {
+            security.validate_for(transport.transport_security().as_ref())?;
+        }
         let salt_counter = config
             .usm_config()
             .filter(|security| {
@@ -660,23 +755,112 @@ impl<T: Transport> Client<T> {
         Ok(Self {
             inner: Arc::new(ClientInner {
                 transport,
+                auth: Arc::new(RwLock::new(Arc::new(config.auth.clone()))),
                 config,
-                engine: RwLock::new(None),
+                engine: Arc::new(RwLock::new(None)),
                 salt_counter,
                 engine_cache,
                 discovery_lock: AsyncMutex::new(()),
                 discovery_coordinator,
-                local_derived_keys: RwLock::new(None),
+                local_derived_keys: Arc::new(RwLock::new(None)),
+                credentials: None,
+                resolver: None,
                 #[cfg(test)]
                 authenticated_response_validated_hook: RwLock::new(None),
             }),
         })
     }
 
+    /// Attach re-resolution to a client that has not been shared yet.
+    pub(super) fn attach_resolver(&mut self, resolver: resolution::TargetResolver) {
+        Arc::get_mut(&mut self.inner)
+            .expect("resolver is attached before the client is shared")
+            .resolver = Some(resolver);
+    }
+
+    /// Attach a credential provider to a client that has not been shared yet.
+    ///
+    /// The first fetch starts immediately in the background; requests use
+    /// the configured authentication until it completes.
+    pub(super) fn attach_credentials(&mut self, source: CredentialSource) -> Result<()> {
+        source.validate()?;
+        let inner = Arc::get_mut(&mut self.inner)
+            .expect("credentials are attached before the client is shared");
+        if matches!(inner.config.auth, Auth::Tsm(_)) {
+            return Err(
+                Error::Config("credential providers do not apply to TSM clients".into()).boxed(),
+            );
+        }
+        // A rotated user may raise the security level to AES privacy.
+        if inner.salt_counter.is_none() && matches!(inner.config.auth, Auth::Usm(_)) {
+            inner.salt_counter = Some(SaltCounter::new()?);
+        }
+        let install = CredentialInstaller {
+            auth: Arc::clone(&inner.auth),
+            engine: Arc::clone(&inner.engine),
+            local_derived_keys: Arc::clone(&inner.local_derived_keys),
+            local_engine_id: inner
+                .config
+                .local_authoritative_engine
+                .as_ref()
+                .map(|engine| engine.engine_id().to_vec()),
+        };
+        let rules = CredentialRules {
+            usm: true,
+            des: inner.config.des_salt_state.is_some(),
+        };
+        let credentials = RotatingCredentials::new(
+            source,
+            rules,
+            Some(Box::new(move |prepared| install.install(prepared))),
+        );
+        inner.credentials = Some(ActiveCredentials::start(credentials, true));
+        Ok(())
+    }
+
+    /// Fetch credentials from the configured provider now.
+    ///
+    /// Returns `Ok(true)` when a new revision was installed and `Ok(false)`
+    /// when the provider returned the revision already in use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] when the client was built without a
+    /// credential provider, or the provider's error when the fetch or
+    /// validation fails. A failed refresh leaves the current credentials in
+    /// place.
+    pub async fn refresh_credentials(&self) -> Result<bool> {
+        let credentials = self
+            .inner
+            .credentials
+            .as_ref()
+            .ok_or_else(|| Error::Config("client has no credential provider".into()).boxed())?;
+        credentials.refresh().await
+    }
+
+    /// Authentication currently in effect.
+    fn auth(&self) -> Arc<Auth> {
+        Arc::clone(
+            &self
+                .inner
+                .auth
+                .read()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        )
+    }
+
+    fn community(&self) -> Result<crate::Community> {
+        self.auth()
+            .community()
+            .cloned()
+            .ok_or_else(|| Error::Config("community authentication required".into()).boxed())
+    }
+
     /// Returns the peer address.
     ///
     /// Returns the remote address that this client sends requests to.
-    /// Named to match [`std::net::TcpStream::peer_addr()`].
+    /// Named to match [`std::net::TcpStream::peer_addr()`]. A client built
+    /// with a [`ResolutionPolicy`] may move to a new address between requests.
     #[must_use]
     pub fn peer_addr(&self) -> SocketAddr {
         self.inner.transport.peer_addr()
@@ -704,17 +888,18 @@ impl<T: Transport> Client<T> {
         self.inner.config.walk_options
     }
 
-    /// Returns the configured SNMPv3 USM security level.
+    /// Returns the configured SNMPv3 security level.
     ///
     /// Returns `None` for SNMPv1 and SNMPv2c clients. For SNMPv3 clients, the
-    /// value describes the configured security level and does not expose the
-    /// USM identity or credentials.
+    /// value describes the configured USM or TSM security level and does not
+    /// expose the security identity or credentials.
     #[must_use]
     pub fn security_level(&self) -> Option<SecurityLevel> {
-        self.inner
-            .config
-            .usm_config()
-            .map(UsmConfig::security_level)
+        match &*self.auth() {
+            Auth::Community { .. } => None,
+            Auth::Usm(security) => Some(security.security_level()),
+            Auth::Tsm(security) => Some(security.requested_security_level()),
+        }
     }
 
     /// Generate next request ID.
@@ -724,11 +909,16 @@ impl<T: Transport> Client<T> {
         self.inner.transport.alloc_request_id()
     }
 
-    /// Check if using V3 with authentication/encryption configured.
-    fn is_v3(&self) -> bool {
+    /// Check if using SNMPv3 with the User-based Security Model.
+    fn is_usm(&self) -> bool {
         matches!(self.inner.config.auth, Auth::Usm(_))
     }
 
+    /// Check if using SNMPv3 with the Transport Security Model.
+    fn is_tsm(&self) -> bool {
+        matches!(self.inner.config.auth, Auth::Tsm(_))
+    }
+
     /// Enforce the exact encoded size before transport I/O.
     ///
     /// SNMPv3 request/response exchanges additionally honor the remote
@@ -803,6 +993,87 @@ impl<T: Transport> Client<T> {
         )
     )]
     async fn send_and_recv(&self, request_id: i32, data: &[u8]) -> Result<DecodedResponse> {
+        let version = self.inner.config.version();
+        let community_version = match version {
+            Version::V1 => CommunityVersion::V1,
+            Version::V2c => CommunityVersion::V2c,
+            Version::V3 => unreachable!("community request path cannot use SNMPv3"),
+        };
+        let community = self.community()?;
+        self.request_with_retries(
+            request_id,
+            data,
+            |deadline| {
+                crate::transport::RequestRegistration::community(
+                    request_id,
+                    deadline,
+                    community_version,
+                    community.clone(),
+                    self.inner.config.community_response_policy,
+                )
+            },
+            |response_data, source| {
+                tracing::trace!(target: "async_snmp::client", { snmp.bytes = response_data.len() }, "received response candidate");
+                let Ok(decoded) = Message::decode_bounded_with_target(
+                    response_data,
+                    self.inner.transport.receive_limits().accepted(),
+                    Some(source),
+                    self.inner.config.decode_config,
+                ) else {
+                    return Ok(Candidate::Reject);
+                };
+                let response = decoded.value;
+                if response.version() != version {
+                    return Ok(Candidate::Reject);
+                }
+                if let Message::Community(ref message) = response
+                    && !community.matches(message.community().as_bytes())
+                {
+                    let accepted = match self.inner.config.community_response_policy {
+                        crate::transport::CommunityResponsePolicy::Exact => false,
+                        crate::transport::CommunityResponsePolicy::AllowMismatchFromTarget => {
+                            source == self.peer_addr()
+                        }
+                        crate::transport::CommunityResponsePolicy::AllowMismatchFromAnySource => true,
+                    };
+                    if !accepted {
+                        return Ok(Candidate::Reject);
+                    }
+                }
+                let Some(response_pdu) = response.into_pdu() else {
+                    return Ok(Candidate::Reject);
+                };
+                if response_pdu.pdu_type() != PduType::Response
+                    || response_pdu.request_id != request_id
+                {
+                    return Ok(Candidate::Reject);
+                }
+                Ok(Candidate::Accept(DecodedResponse {
+                    pdu: response_pdu,
+                    decode_anomalies: decoded.anomalies,
+                }))
+            },
+        )
+        .await
+    }
+
+    /// Run one pre-encoded exchange with timeout retries.
+    ///
+    /// `register` builds the registration for each transmission deadline and
+    /// `validate` screens every correlated candidate. An accepted Report ends
+    /// the exchange as [`Error::Report`]; an accepted Response carrying an
+    /// error status ends it as [`Error::Snmp`].
+    pub(super) async fn request_with_retries<R, V>(
+        &self,
+        request_id: i32,
+        data: &[u8],
+        register: R,
+        mut validate: V,
+    ) -> Result<DecodedResponse>
+    where
+        R: Fn(tokio::time::Instant) -> crate::transport::RequestRegistration,
+        V: FnMut(bytes::Bytes, SocketAddr) -> Result<Candidate<DecodedResponse>> + Send,
+    {
         self.enforce_outbound_size(data.len(), None)?;
         let start = Instant::now();
         let exchange_deadline = self.start_exchange_deadline()?;
@@ -823,21 +1094,8 @@ impl<T: Transport> Client<T> {
             }
 
             // Register (or re-register) with fresh deadline before sending
-            let version = self.inner.config.version();
-            let community_version = match version {
-                Version::V1 => CommunityVersion::V1,
-                Version::V2c => CommunityVersion::V2c,
-                Version::V3 => unreachable!("community request path cannot use SNMPv3"),
-            };
-            let community = self.inner.config.community()?;
-            let registration = crate::transport::RequestRegistration::community(
-                request_id,
-                self.transmission_deadline(exchange_deadline)?,
-                community_version,
-                community.clone(),
-                self.inner.config.community_response_policy,
-            )
-            .with_decode_config(self.inner.config.decode_config);
+            let registration = register(self.transmission_deadline(exchange_deadline)?)
+                .with_decode_config(self.inner.config.decode_config);
 
             // Send request and wait for response as a single unit. Combining the
             // two lets reliable transports (TCP) own their stream lock for the
@@ -847,60 +1105,32 @@ impl<T: Transport> Client<T> {
             match self
                 .inner
                 .transport
-                .request_with(data, registration, |response_data, source| {
-                    tracing::trace!(target: "async_snmp::client", { snmp.bytes = response_data.len() }, "received response candidate");
-                    let Ok(decoded) = Message::decode_bounded_with_target(
-                        response_data,
-                        self.inner.transport.receive_limits().accepted(),
-                        Some(source),
-                        self.inner.config.decode_config,
-                    ) else {
-                        return Ok(Candidate::Reject);
-                    };
-                    let response = decoded.value;
-                    if response.version() != version {
-                        return Ok(Candidate::Reject);
-                    }
-                    if let Message::Community(ref message) = response
-                        && !community.matches(message.community().as_bytes())
-                    {
-                        let accepted = match self.inner.config.community_response_policy {
-                            crate::transport::CommunityResponsePolicy::Exact => false,
-                            crate::transport::CommunityResponsePolicy::AllowMismatchFromTarget => {
-                                source == self.peer_addr()
+                .request_with(data, registration, &mut validate)
+                .await
+            {
+                Ok(response) => {
+                    Span::current().record("snmp.elapsed_ms", start.elapsed().as_millis() as u64);
+                    let metadata =
+                        ResponseMetadata::from_decode_anomalies(response.decode_anomalies.clone());
+                    if response.pdu.pdu_type() == PduType::Report {
+                        let status = crate::v3::classify_report(&response.pdu).map_err(|_| {
+                            Error::MalformedResponse {
+                                target: self.peer_addr(),
                             }
-                            crate::transport::CommunityResponsePolicy::AllowMismatchFromAnySource => true,
-                        };
-                        if !accepted {
-                            return Ok(Candidate::Reject);
+                            .boxed()
+                            .with_prior_response_metadata(&metadata)
+                        })?;
+                        return Err(Error::Report {
+                            target: self.peer_addr(),
+                            status: Box::new(status),
+                            metadata: Box::new(metadata),
                         }
+                        .boxed());
                     }
-                    let Some(response_pdu) = response.into_pdu() else {
-                        return Ok(Candidate::Reject);
-                    };
-                    if response_pdu.pdu_type() != PduType::Response
-                        || response_pdu.request_id != request_id
+                    if let Some(err) = pdu_to_snmp_error(&response.pdu, self.peer_addr(), metadata)
                     {
-                        return Ok(Candidate::Reject);
-                    }
-                    Ok(Candidate::Accept(DecodedResponse {
-                        pdu: response_pdu,
-                        decode_anomalies: decoded.anomalies,
-                    }))
-                })
-                .await
-            {
-                Ok(response) => {
-                    if let Some(err) = pdu_to_snmp_error(
-                        &response.pdu,
-                        self.peer_addr(),
-                        ResponseMetadata::from_decode_anomalies(response.decode_anomalies.clone()),
-                    ) {
-                        Span::current()
-                            .record("snmp.elapsed_ms", start.elapsed().as_millis() as u64);
                         return Err(err);
                     }
-                    Span::current().record("snmp.elapsed_ms", start.elapsed().as_millis() as u64);
                     return Ok(response);
                 }
                 Err(e) if matches!(*e, Error::Timeout { .. }) => {
@@ -941,17 +1171,27 @@ impl<T: Transport> Client<T> {
 
     /// Send a standard request (GET, GETNEXT, SET) and wait for response.
     async fn send_request(&self, pdu: Pdu) -> Result<DecodedResponse> {
-        // Dispatch to V3 handler if configured
-        if self.is_v3() {
+        self.refresh_target().await;
+        let result = self.dispatch_request(pdu).await;
+        self.record_resolution_outcome(&result);
+        result
+    }
+
+    async fn dispatch_request(&self, pdu: Pdu) -> Result<DecodedResponse> {
+        // Dispatch to V3 handlers if configured
+        if self.is_usm() {
             return self.send_v3_and_recv(pdu).await;
         }
+        if self.is_tsm() {
+            return self.send_tsm_and_recv(pdu).await;
+        }
 
         tracing::debug!(target: "async_snmp::client", { snmp.pdu_type = ?pdu.pdu_type(), snmp.varbind_count = pdu.varbinds.len() }, "sending {} request", pdu.pdu_type());
 
         let request_id = pdu.request_id;
         let message = CommunityMessage::new(
             self.inner.config.community_version()?,
-            self.inner.config.community()?,
+            self.community()?,
             pdu,
         )?;
         let data = message.encode()?;
@@ -962,6 +1202,17 @@ impl<T: Transport> Client<T> {
         Ok(response)
     }
 
+    /// Send a request PDU on behalf of a proxied originator (RFC 3413
+    /// Section 3.5) and return the response varbinds.
+    ///
+    /// The PDU is sent under a fresh request ID from this client's allocator;
+    /// the caller answers its originator under the original one.
+    #[cfg(feature = "agent")]
+    pub(crate) async fn forward(&self, mut pdu: Pdu) -> Result<Vec<VarBind>> {
+        pdu.request_id = self.next_request_id();
+        Ok(self.send_request(pdu).await?.pdu.varbinds)
+    }
+
     fn apply_response_shape_policy(
         &self,
         response: FixedCardinalityResponse,
@@ -1248,7 +1499,16 @@ impl<T: Transport> Client<T> {
             varbinds,
         )?;
 
-        if self.is_v3() {
+        if self.is_tsm() {
+            let msg_id = self.next_request_id();
+            let data = self.build_tsm_trap_message(pdu.as_raw(), msg_id)?;
+            self.enforce_outbound_size(data.len(), None)?;
+            tracing::debug!(target: "async_snmp::client", { snmp.pdu_type = "TrapV2", snmp.varbind_count = pdu.as_raw().varbinds().len(), snmp.bytes = data.len() }, "sending TSM trap");
+            self.inner
+                .transport
+                .send_with_timeout(&data, self.inner.config.send_timeout)
+                .await?;
+        } else if self.is_usm() {
             self.ensure_local_keys_derived()?;
             let msg_id = self.next_request_id();
             let data = self.build_v3_trap_message(pdu.as_raw(), msg_id)?;
@@ -1261,7 +1521,7 @@ impl<T: Transport> Client<T> {
         } else {
             let message = CommunityMessage::new(
                 self.inner.config.community_version()?,
-                self.inner.config.community()?,
+                self.community()?,
                 pdu,
             )?;
             let data = message.encode()?;
@@ -1311,7 +1571,7 @@ impl<T: Transport> Client<T> {
             return Err(Error::Config("send_v1_trap requires a V1 client".into()).boxed());
         }
 
-        let message = CommunityMessage::v1_trap(self.inner.config.community()?, trap.into_raw())?;
+        let message = CommunityMessage::v1_trap(self.community()?, trap.into_raw())?;
         let data = message.encode()?;
         self.enforce_outbound_size(data.len(), None)?;
         tracing::debug!(target: "async_snmp::client", { snmp.pdu_type = "TrapV1", snmp.bytes = data.len() }, "sending v1 trap");
@@ -0,0 +1,517 @@
+//! Hostname re-resolution for long-lived UDP clients.
+//!
+//! A [`Target`] naming a host is resolved once when the client is built. A
+//! [`ResolutionPolicy`] set with
+//! [`TargetClientBuilder::resolution_policy`](crate::TargetClientBuilder::resolution_policy)
+//! resolves it again after a TTL or after consecutive request timeouts and
+//! moves the client's UDP handle in place. Re-resolution runs before a
+//! request, never in the background, and a failed lookup keeps the current
+//! address.
+
+use std::fmt;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{Client, Target};
+use crate::error::{Error, Result};
+use crate::transport::{Transport, UdpPeer};
+
+/// When a client built from a hostname target resolves it again.
+///
+/// The default policy never re-resolves and keeps resolver order, matching
+/// clients built without a policy. Re-resolution applies to UDP clients built
+/// with [`connect()`](crate::TargetClientBuilder::connect),
+/// [`connect_with_control()`](crate::TargetClientBuilder::connect_with_control),
+/// [`build_with()`](crate::TargetClientBuilder::build_with), or
+/// [`build_with_sharded()`](crate::TargetClientBuilder::build_with_sharded);
+/// numeric targets are never re-resolved.
+///
+/// When the chosen address changes, the client's SNMPv3 engine state and any
+/// shared [`EngineCache`](crate::v3::EngineCache) entries for the previous and
+/// new address are invalidated, so the next SNMPv3 request rediscovers the
+/// engine.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::{Auth, Client, ResolutionPolicy};
+/// use std::time::Duration;
+///
+/// # async fn example() -> async_snmp::Result<()> {
+/// let client = Client::builder("switch.example.net", Auth::v2c("public"))
+///     .resolution_policy(
+///         ResolutionPolicy::new()
+///             .ttl(Duration::from_secs(300))
+///             .after_timeouts(3)
+///             .happy_eyeballs(true),
+///     )
+///     .on_address_change(|change: &async_snmp::AddressChange| {
+///         println!("{} moved to {}", change.target(), change.current());
+///     })
+///     .connect()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResolutionPolicy {
+    ttl: Option<Duration>,
+    after_timeouts: Option<u32>,
+    pub(crate) happy_eyeballs: bool,
+}
+
+impl ResolutionPolicy {
+    /// Create a policy that never re-resolves.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Re-resolve before the first request made `ttl` after the last
+    /// resolution.
+    ///
+    /// The current address is kept while it is still among the resolved
+    /// addresses. Must be non-zero.
+    #[must_use]
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Re-resolve after `timeouts` consecutive requests end in
+    /// [`Error::Timeout`].
+    ///
+    /// The client then moves to the resolved address after the current one,
+    /// so a host with several records fails over instead of retrying the
+    /// unresponsive address. Must be non-zero.
+    #[must_use]
+    pub fn after_timeouts(mut self, timeouts: u32) -> Self {
+        self.after_timeouts = Some(timeouts);
+        self
+    }
+
+    /// Interleave IPv6 and IPv4 addresses and race TCP connection attempts
+    /// (RFC 8305).
+    ///
+    /// Candidates alternate between families, starting with the family the
+    /// resolver listed first, so timeout failover tries the other family next.
+    /// A dedicated UDP socket is bound dual-stack when any candidate is IPv6.
+    /// [`connect_tcp()`](crate::TargetClientBuilder::connect_tcp) starts a
+    /// connection attempt to each candidate in the same order, 250 ms after
+    /// the previous one or as soon as it fails, and keeps the first
+    /// connection established. All attempts share the construction timeout.
+    #[must_use]
+    pub fn happy_eyeballs(mut self, enabled: bool) -> Self {
+        self.happy_eyeballs = enabled;
+        self
+    }
+
+    /// Whether any trigger re-resolves the target after construction.
+    pub(crate) fn re_resolves(&self) -> bool {
+        self.ttl.is_some() || self.after_timeouts.is_some()
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.ttl.is_some_and(|ttl| ttl.is_zero()) {
+            return Err(Error::Config("re-resolution TTL must be non-zero".into()).boxed());
+        }
+        if self.after_timeouts == Some(0) {
+            return Err(
+                Error::Config("re-resolution timeout threshold must be non-zero".into()).boxed(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Order resolver results for selection.
+    pub(crate) fn order(&self, addresses: Vec<SocketAddr>) -> Vec<SocketAddr> {
+        let mut unique = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            if !unique.contains(&address) {
+                unique.push(address);
+            }
+        }
+        if !self.happy_eyeballs {
+            return unique;
+        }
+        let Some(first) = unique.first() else {
+            return unique;
+        };
+        let first_is_ipv6 = first.is_ipv6();
+        let (preferred, other): (Vec<_>, Vec<_>) = unique
+            .into_iter()
+            .partition(|address| address.is_ipv6() == first_is_ipv6);
+        let mut ordered = Vec::with_capacity(preferred.len() + other.len());
+        let mut other = other.into_iter();
+        for address in preferred {
+            ordered.push(address);
+            ordered.extend(other.next());
+        }
+        ordered.extend(other);
+        ordered
+    }
+}
+
+/// Why a client re-resolved its target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResolutionTrigger {
+    /// The [`ResolutionPolicy::ttl`] elapsed.
+    Ttl,
+    /// [`ResolutionPolicy::after_timeouts`] consecutive requests timed out.
+    Timeouts,
+}
+
+/// A re-resolved client moved to a new address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressChange {
+    target: Target,
+    previous: SocketAddr,
+    current: SocketAddr,
+    trigger: ResolutionTrigger,
+}
+
+impl AddressChange {
+    /// Returns the configured target that was re-resolved.
+    #[must_use]
+    pub fn target(&self) -> &Target {
+        &self.target
+    }
+
+    /// Returns the address requests were sent to before the change.
+    #[must_use]
+    pub fn previous(&self) -> SocketAddr {
+        self.previous
+    }
+
+    /// Returns the address requests are now sent to.
+    #[must_use]
+    pub fn current(&self) -> SocketAddr {
+        self.current
+    }
+
+    /// Returns what caused the re-resolution.
+    #[must_use]
+    pub fn trigger(&self) -> ResolutionTrigger {
+        self.trigger
+    }
+}
+
+/// Receives address changes made by client re-resolution.
+///
+/// Called synchronously on the request path after the client has moved, so
+/// implementations should return quickly. The blanket implementation accepts
+/// closures with the same signature. A panic is contained and logged.
+pub trait ResolutionObserver: Send + Sync + 'static {
+    /// Called once for each address change.
+    fn address_changed(&self, change: &AddressChange);
+}
+
+impl<F> ResolutionObserver for F
+where
+    F: Fn(&AddressChange) + Send + Sync + 'static,
+{
+    fn address_changed(&self, change: &AddressChange) {
+        self(change);
+    }
+}
+
+/// Shared observer stored by the builder.
+#[derive(Clone)]
+pub(crate) struct SharedObserver(pub(crate) Arc<dyn ResolutionObserver>);
+
+impl fmt::Debug for SharedObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<observer>")
+    }
+}
+
+type LookupFuture = Pin<Box<dyn Future<Output = Result<Vec<SocketAddr>>> + Send>>;
+
+/// Host lookup retained for re-resolution.
+pub(crate) type Lookup = Arc<dyn Fn(String, u16) -> LookupFuture + Send + Sync>;
+
+/// Box a resolver closure for re-resolution.
+pub(crate) fn lookup<R, RFut>(resolver: R) -> Lookup
+where
+    R: Fn(String, u16) -> RFut + Send + Sync + 'static,
+    RFut: Future<Output = Result<Vec<SocketAddr>>> + Send + 'static,
+{
+    Arc::new(move |host, port| Box::pin(resolver(host, port)))
+}
+
+/// Re-resolution state owned by one client.
+pub(crate) struct TargetResolver {
+    target: Target,
+    host: String,
+    port: u16,
+    policy: ResolutionPolicy,
+    observer: Option<SharedObserver>,
+    lookup: Lookup,
+    lookup_timeout: Duration,
+    peer: UdpPeer,
+    state: Mutex<ResolverState>,
+    /// Held by the one request performing a lookup.
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+struct ResolverState {
+    resolved_at: tokio::time::Instant,
+    consecutive_timeouts: u32,
+}
+
+pub(crate) struct TargetResolverConfig {
+    pub(crate) target: Target,
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) policy: ResolutionPolicy,
+    pub(crate) observer: Option<SharedObserver>,
+    pub(crate) lookup: Lookup,
+    pub(crate) lookup_timeout: Duration,
+}
+
+impl TargetResolver {
+    pub(crate) fn new(config: TargetResolverConfig, peer: UdpPeer) -> Self {
+        Self {
+            target: config.target,
+            host: config.host,
+            port: config.port,
+            policy: config.policy,
+            observer: config.observer,
+            lookup: config.lookup,
+            lookup_timeout: config.lookup_timeout,
+            peer,
+            state: Mutex::new(ResolverState {
+                resolved_at: tokio::time::Instant::now(),
+                consecutive_timeouts: 0,
+            }),
+            refreshing: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, ResolverState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Return the trigger that makes re-resolution due, if any.
+    fn due(&self) -> Option<ResolutionTrigger> {
+        let state = self.lock_state();
+        if self
+            .policy
+            .after_timeouts
+            .is_some_and(|threshold| state.consecutive_timeouts >= threshold)
+        {
+            return Some(ResolutionTrigger::Timeouts);
+        }
+        self.policy
+            .ttl
+            .filter(|ttl| state.resolved_at.elapsed() >= *ttl)
+            .map(|_| ResolutionTrigger::Ttl)
+    }
+
+    /// Count a finished request toward the timeout trigger.
+    fn record_outcome(&self, timed_out: bool) {
+        let mut state = self.lock_state();
+        state.consecutive_timeouts = if timed_out {
+            state.consecutive_timeouts.saturating_add(1)
+        } else {
+            0
+        };
+    }
+
+    /// Look the host up again and choose the next address.
+    ///
+    /// Returns a change only when the chosen address differs from the current
+    /// one. Every attempt restarts both triggers, including failed lookups.
+    async fn resolve(&self, trigger: ResolutionTrigger) -> Option<AddressChange> {
+        let result = crate::runtime::timeout(
+            self.lookup_timeout,
+            (self.lookup)(self.host.clone(), self.port),
+        )
+        .await;
+        {
+            let mut state = self.lock_state();
+            state.resolved_at = tokio::time::Instant::now();
+            state.consecutive_timeouts = 0;
+        }
+        let addresses = match result {
+            Ok(Ok(addresses)) => addresses,
+            Ok(Err(error)) => {
+                tracing::debug!(target: "async_snmp::client", { snmp.target = %self.target, ?trigger, %error }, "target re-resolution failed; keeping current address");
+                return None;
+            }
+            Err(_) => {
+                tracing::debug!(target: "async_snmp::client", { snmp.target = %self.target, ?trigger, timeout = ?self.lookup_timeout }, "target re-resolution timed out; keeping current address");
+                return None;
+            }
+        };
+        let candidates = self.policy.order(
+            addresses
+                .into_iter()
+                .filter_map(|address| self.peer.normalize(address).ok())
+                .collect(),
+        );
+        let current = self.peer.current();
+        let next = match (trigger, candidates.iter().position(|c| *c == current)) {
+            (_, None) => *candidates.first()?,
+            (ResolutionTrigger::Ttl, Some(_)) => current,
+            (ResolutionTrigger::Timeouts, Some(index)) => {
+                candidates[(index + 1) % candidates.len()]
+            }
+        };
+        tracing::debug!(target: "async_snmp::client", { snmp.target = %self.target, ?trigger, candidates = candidates.len(), %current, %next }, "re-resolved target");
+        (next != current).then(|| AddressChange {
+            target: self.target.clone(),
+            previous: current,
+            current: next,
+            trigger,
+        })
+    }
+
+    fn notify(&self, change: &AddressChange) {
+        let Some(SharedObserver(observer)) = &self.observer else {
+            return;
+        };
+        if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            observer.address_changed(change);
+        }))
+        .is_err()
+        {
+            tracing::warn!(target: "async_snmp::client", { snmp.target = %self.target }, "resolution observer panicked");
+        }
+    }
+}
+
+impl<T: Transport> Client<T> {
+    /// Re-resolve the target before a request when the policy says so.
+    ///
+    /// Concurrent requests do not wait for a lookup another request has
+    /// started; they use the current address.
+    pub(super) async fn refresh_target(&self) {
+        let Some(resolver) = &self.inner.resolver else {
+            return;
+        };
+        if resolver.due().is_none() {
+            return;
+        }
+        let Ok(_refreshing) = resolver.refreshing.try_lock() else {
+            return;
+        };
+        // Another request may have finished a lookup since the first check.
+        let Some(trigger) = resolver.due() else {
+            return;
+        };
+        let Some(change) = resolver.resolve(trigger).await else {
+            return;
+        };
+
+        {
+            // Serialize with explicit rediscovery so it cannot install an
+            // engine for the previous address after the reset.
+            let _discovery = self.inner.discovery_lock.lock().await;
+            if let Err(error) = resolver.peer.replace(change.current) {
+                tracing::debug!(target: "async_snmp::client", { snmp.target = %resolver.target, %error }, "re-resolved address rejected by UDP socket");
+                return;
+            }
+            *self
+                .inner
+                .engine
+                .write()
+                .unwrap_or_else(std::sync::PoisonError::into_inner) = None;
+            if let Some(cache) = &self.inner.engine_cache {
+                cache.remove(&change.previous);
+                cache.remove(&change.current);
+            }
+        }
+        tracing::info!(target: "async_snmp::client", { snmp.target = %resolver.target, previous = %change.previous, current = %change.current, trigger = ?change.trigger }, "target address changed");
+        resolver.notify(&change);
+    }
+
+    /// Feed a finished request into the timeout trigger.
+    pub(super) fn record_resolution_outcome<R>(&self, result: &Result<R>) {
+        if let Some(resolver) = &self.inner.resolver {
+            resolver.record_outcome(
+                matches!(result, Err(error) if matches!(**error, Error::Timeout { .. })),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn happy_eyeballs_interleaves_families_from_first_result() {
+        let policy = ResolutionPolicy::new().happy_eyeballs(true);
+        let ordered = policy.order(vec![
+            addr("[2001:db8::1]:161"),
+            addr("[2001:db8::2]:161"),
+            addr("[2001:db8::3]:161"),
+            addr("192.0.2.1:161"),
+            addr("[2001:db8::1]:161"),
+        ]);
+        assert_eq!(
+            ordered,
+            vec![
+                addr("[2001:db8::1]:161"),
+                addr("192.0.2.1:161"),
+                addr("[2001:db8::2]:161"),
+                addr("[2001:db8::3]:161"),
+            ]
+        );
+
+        let ordered = policy.order(vec![addr("192.0.2.1:161"), addr("[2001:db8::1]:161")]);
+        assert_eq!(ordered[0], addr("192.0.2.1:161"));
+    }
+
+    #[test]
+    fn default_order_keeps_resolver_order_without_duplicates() {
+        let ordered = ResolutionPolicy::new().order(vec![
+            addr("[2001:db8::1]:161"),
+            addr("[2001:db8::2]:161"),
+            addr("192.0.2.1:161"),
+            addr("[2001:db8::2]:161"),
+        ]);
+        assert_eq!(
+            ordered,
+            vec![
+                addr("[2001:db8::1]:161"),
+                addr("[2001:db8::2]:161"),
+                addr("192.0.2.1:161"),
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_triggers_are_rejected() {
+        assert!(
+            ResolutionPolicy::new()
+                .ttl(Duration::ZERO)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ResolutionPolicy::new()
+                .after_timeouts(0)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ResolutionPolicy::new()
+                .ttl(Duration::from_secs(1))
+                .after_timeouts(1)
+                .validate()
+                .is_ok()
+        );
+    }
+}
@@ -17,7 +17,7 @@ use std::time::Duration;
 pub const MAX_RETRIES: u32 = 16;
 
 /// Wait before another retry without allowing zero-delay loops to monopolize
-/// the current Tokio worker.
+/// the current executor thread.
 pub(crate) async fn wait_for_retry(
     delay: Duration,
     deadline: Option<tokio::time::Instant>,
@@ -35,13 +35,13 @@ pub(crate) async fn wait_for_retry(
             .checked_add(delay)
             .unwrap_or(deadline);
         if deadline <= wake {
-            tokio::time::sleep_until(deadline).await;
+            crate::runtime::sleep_until(deadline).await;
             return false;
         }
-        tokio::time::sleep_until(wake).await;
+        crate::runtime::sleep_until(wake).await;
         true
     } else {
-        tokio::time::sleep(delay).await;
+        crate::runtime::sleep(delay).await;
         true
     }
 }
@@ -0,0 +1,359 @@
+//! SNMPv3 Transport Security Model client processing (RFC 5591).
+//!
+//! TSM messages carry no per-message security parameters: the secure
+//! transport authenticated the peer when the session was established, so the
+//! client only checks that the session still provides the requested level and
+//! that responses correlate by msgID, security model, level, and scope.
+
+use bytes::Bytes;
+use tracing::instrument;
+
+use crate::error::{Error, Result};
+use crate::message::{
+    MsgFlags, MsgGlobalData, ScopedPdu, SecurityLevel, V3Message, V3SecurityModel,
+};
+use crate::pdu::{Pdu, PduType};
+use crate::transport::{Candidate, RequestRegistration, Transport};
+use crate::v3::{LOCAL_ENGINE_ID, TsmConfig};
+
+use super::{Client, DecodedResponse};
+
+impl<T: Transport> Client<T> {
+    fn tsm_config(&self) -> Result<&TsmConfig> {
+        let security = self
+            .inner
+            .config
+            .auth
+            .tsm_config()
+            .ok_or_else(|| Error::Config("TSM security not configured".into()).boxed())?;
+        // RFC 5591 Section 5.1: the session must provide the requested level
+        // every time a message is sent, not only when the client was built.
+        security.validate_for(self.inner.transport.transport_security().as_ref())?;
+        Ok(security)
+    }
+
+    fn build_tsm_message(
+        &self,
+        security: &TsmConfig,
+        pdu: &Pdu,
+        msg_id: i32,
+        context_engine_id: Bytes,
+        reportable: bool,
+    ) -> Result<Bytes> {
+        let global_data = MsgGlobalData::new(
+            msg_id,
+            self.inner.transport.receive_limits().advertised(),
+            MsgFlags::new(security.requested_security_level(), reportable),
+        )?;
+        let scoped_pdu = ScopedPdu::new(
+            context_engine_id,
+            security.configured_context_name().clone(),
+            pdu.clone(),
+        );
+        V3Message::tsm(global_data, scoped_pdu)?.encode()
+    }
+
+    /// Send a request under TSM and wait for the correlated response.
+    #[instrument(
+        level = "debug",
+        skip(self, pdu),
+        fields(
+            snmp.target = %self.peer_addr(),
+            snmp.request_id = pdu.request_id,
+            snmp.attempt = tracing::field::Empty,
+            snmp.elapsed_ms = tracing::field::Empty,
+        )
+    )]
+    pub(super) async fn send_tsm_and_recv(&self, pdu: Pdu) -> Result<DecodedResponse> {
+        let security = self.tsm_config()?;
+        let requested_level = security.requested_security_level();
+        let context_engine_id = security.configured_context_engine_id().clone();
+        let context_name = security.configured_context_name().clone();
+        let request_id = pdu.request_id;
+        let msg_id = self.next_request_id();
+        let data =
+            self.build_tsm_message(security, &pdu, msg_id, context_engine_id.clone(), true)?;
+
+        tracing::debug!(target: "async_snmp::client", { snmp.pdu_type = ?pdu.pdu_type(), snmp.varbind_count = pdu.varbinds.len(), snmp.msg_id = msg_id }, "sending TSM {} request", pdu.pdu_type());
+        let response = self
+            .request_with_retries(
+                request_id,
+                &data,
+                |deadline| RequestRegistration::v3(msg_id, deadline),
+                |response_data, _source| {
+                    if response_data.len() > self.inner.transport.receive_limits().accepted() {
+                        return Ok(Candidate::Reject);
+                    }
+                    let Ok(decoded) =
+                        V3Message::decode(response_data, self.inner.config.decode_config)
+                    else {
+                        return Ok(Candidate::Reject);
+                    };
+                    let message = decoded.value;
+                    if message.global_data().msg_security_model() != V3SecurityModel::Tsm
+                        || message.msg_id() != msg_id
+                    {
+                        return Ok(Candidate::Reject);
+                    }
+                    let received_level = message.security_level();
+                    let Some(scoped_pdu) = message.into_scoped_pdu() else {
+                        return Ok(Candidate::Reject);
+                    };
+                    if !tsm_response_matches(
+                        &scoped_pdu,
+                        received_level,
+                        requested_level,
+                        request_id,
+                        &context_engine_id,
+                        &context_name,
+                    ) {
+                        return Ok(Candidate::Reject);
+                    }
+                    Ok(Candidate::Accept(DecodedResponse {
+                        pdu: scoped_pdu.pdu,
+                        decode_anomalies: decoded.anomalies,
+                    }))
+                },
+            )
+            .await?;
+
+        tracing::debug!(target: "async_snmp::client", { snmp.pdu_type = ?response.pdu.pdu_type(), snmp.varbind_count = response.pdu.varbinds.len(), snmp.error_status = response.pdu.error_status(), snmp.error_index = response.pdu.error_index() }, "received TSM {} response", response.pdu.pdu_type());
+        Ok(response)
+    }
+
+    /// Encode an unconfirmed notification under TSM.
+    ///
+    /// The sender is authoritative for traps (RFC 3412 Section 6.4), so the
+    /// contextEngineID is the local engine's ID.
+    pub(super) fn build_tsm_trap_message(&self, pdu: &Pdu, msg_id: i32) -> Result<Bytes> {
+        let security = self.tsm_config()?;
+        let engine_id = Bytes::copy_from_slice(self.local_engine_for_trap()?.engine_id());
+        self.build_tsm_message(security, pdu, msg_id, engine_id, false)
+    }
+}
+
+/// Check a decoded TSM response against the outstanding request.
+///
+/// RFC 3412 Section 7.2 step 12 requires the securityLevel and scope of a
+/// Response to match the request; a Report may arrive at a lower level. When
+/// the request addressed [`LOCAL_ENGINE_ID`] the responder substitutes its own
+/// engine ID (RFC 5343), so any contextEngineID is accepted.
+fn tsm_response_matches(
+    scoped_pdu: &ScopedPdu,
+    received_level: SecurityLevel,
+    requested_level: SecurityLevel,
+    request_id: i32,
+    context_engine_id: &[u8],
+    context_name: &[u8],
+) -> bool {
+    let engine_matches =
+        context_engine_id == LOCAL_ENGINE_ID || scoped_pdu.context_engine_id == context_engine_id;
+    match scoped_pdu.pdu.pdu_type() {
+        PduType::Response => {
+            received_level == requested_level
+                && scoped_pdu.pdu.request_id == request_id
+                && engine_matches
+                && scoped_pdu.context_name == context_name
+        }
+        PduType::Report => received_level <= requested_level,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClientBuilder;
+    use crate::v3::{ReportStatus, TransportSecurity};
+    use crate::{Retry, Value, VarBind, oid};
+    use std::net::SocketAddr;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    type Responder = fn(&V3Message) -> Vec<Bytes>;
+
+    /// Local stand-in for a DTLS agent: the session is "secure" and answers
+    /// each TSM request with scripted messages.
+    struct SecureAgentStandIn {
+        session: Option<TransportSecurity>,
+        respond: Responder,
+        requests: Mutex<Vec<V3Message>>,
+    }
+
+    impl SecureAgentStandIn {
+        fn new(session_level: Option<SecurityLevel>, respond: Responder) -> Self {
+            Self {
+                session: session_level.map(|level| TransportSecurity::new("dtls", level)),
+                respond,
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Transport for SecureAgentStandIn {
+        async fn send(&self, data: &[u8]) -> Result<()> {
+            let request = V3Message::decode(Bytes::copy_from_slice(data), Default::default())?;
+            self.requests.lock().unwrap().push(request.value);
+            Ok(())
+        }
+
+        async fn request_with<R, F>(
+            &self,
+            data: &[u8],
+            registration: RequestRegistration,
+            mut validate: F,
+        ) -> Result<R>
+        where
+            R: Send,
+            F: FnMut(Bytes, SocketAddr) -> Result<Candidate<R>> + Send,
+        {
+            let request =
+                V3Message::decode(Bytes::copy_from_slice(data), Default::default())?.value;
+            let responses = (self.respond)(&request);
+            self.requests.lock().unwrap().push(request);
+            for response in responses {
+                if registration
+                    .evaluate_response_identity(&response, true)
+                    .eq(&crate::ResponseIdentity::Reject)
+                {
+                    continue;
+                }
+                if let Candidate::Accept(value) = validate(response, self.peer_addr())? {
+                    return Ok(value);
+                }
+            }
+            Err(Error::Timeout {
+                target: self.peer_addr(),
+                elapsed: Duration::ZERO,
+                retries: 0,
+            }
+            .boxed())
+        }
+
+        fn peer_addr(&self) -> SocketAddr {
+            "192.0.2.1:10161".parse().unwrap()
+        }
+
+        fn local_addr(&self) -> SocketAddr {
+            "0.0.0.0:0".parse().unwrap()
+        }
+
+        fn is_reliable(&self) -> bool {
+            false
+        }
+
+        fn transport_security(&self) -> Option<TransportSecurity> {
+            self.session.clone()
+        }
+    }
+
+    fn reply(request: &V3Message, level: SecurityLevel, pdu: Pdu) -> Bytes {
+        let global = MsgGlobalData::new(
+            request.msg_id(),
+            request.global_data().msg_max_size(),
+            MsgFlags::new(level, false),
+        )
+        .unwrap();
+        let scoped = request.scoped_pdu().unwrap();
+        let scoped = ScopedPdu::new(
+            Bytes::from_static(b"\x80\x00\x1f\x88\x04agent"),
+            scoped.context_name.clone(),
+            pdu,
+        );
+        V3Message::tsm(global, scoped).unwrap().encode().unwrap()
+    }
+
+    fn sys_descr(request: &V3Message) -> Pdu {
+        Pdu::response(
+            request.pdu().unwrap().request_id,
+            0,
+            0,
+            vec![VarBind::new(
+                oid!(1, 3, 6, 1, 2, 1, 1, 1, 0),
+                Value::from("edge router"),
+            )],
+        )
+    }
+
+    fn client(transport: SecureAgentStandIn) -> Result<Client<SecureAgentStandIn>> {
+        ClientBuilder::new(TsmConfig::new("monitor").context_name("ctx"))
+            .retry(Retry::none())
+            .build_with_transport(transport)
+    }
+
+    #[tokio::test]
+    async fn get_over_secure_session_uses_tsm_envelope() {
+        let client = client(SecureAgentStandIn::new(
+            Some(SecurityLevel::AuthPriv),
+            |request| vec![reply(request, SecurityLevel::AuthPriv, sys_descr(request))],
+        ))
+        .unwrap();
+        assert_eq!(client.version(), crate::Version::V3);
+        assert_eq!(client.security_level(), Some(SecurityLevel::AuthPriv));
+
+        let response = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).await.unwrap();
+        assert_eq!(response.single().unwrap().value, Value::from("edge router"));
+
+        let requests = client.inner.transport.requests.lock().unwrap();
+        let request = &requests[0];
+        assert_eq!(
+            request.global_data().msg_security_model(),
+            V3SecurityModel::Tsm
+        );
+        assert_eq!(request.security_level(), SecurityLevel::AuthPriv);
+        let scoped = request.scoped_pdu().unwrap();
+        assert_eq!(scoped.context_engine_id.as_ref(), LOCAL_ENGINE_ID);
+        assert_eq!(scoped.context_name.as_ref(), b"ctx");
+    }
+
+    #[tokio::test]
+    async fn tsm_requires_an_adequate_secure_session() {
+        let plain = client(SecureAgentStandIn::new(None, |_| Vec::new()));
+        assert!(matches!(
+            plain.err().map(|error| *error),
+            Some(Error::Config(_))
+        ));
+
+        let weak = client(SecureAgentStandIn::new(
+            Some(SecurityLevel::AuthNoPriv),
+            |_| Vec::new(),
+        ));
+        assert!(matches!(
+            weak.err().map(|error| *error),
+            Some(Error::Config(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn downgraded_response_is_ignored_and_report_terminates() {
+        let client = client(SecureAgentStandIn::new(
+            Some(SecurityLevel::AuthPriv),
+            |request| {
+                // snmpTsmInadequateSecurityLevels.0
+                let report = crate::ResponsePdu::report(
+                    request.pdu().unwrap().request_id,
+                    vec![VarBind::new(
+                        oid!(1, 3, 6, 1, 2, 1, 190, 1, 2, 2, 0),
+                        Value::Counter32(1),
+                    )],
+                )
+                .unwrap()
+                .into_raw();
+                vec![
+                    reply(request, SecurityLevel::NoAuthNoPriv, sys_descr(request)),
+                    reply(request, SecurityLevel::NoAuthNoPriv, report),
+                ]
+            },
+        ))
+        .unwrap();
+
+        let error = client
+            .get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0))
+            .await
+            .unwrap_err();
+        let Error::Report { status, .. } = *error else {
+            panic!("expected Report, got {error:?}");
+        };
+        assert!(matches!(*status, ReportStatus::Other { .. }));
+    }
+}
@@ -6,7 +6,6 @@
 use crate::ber::EncodeBuf;
 use crate::error::internal::{AuthErrorKind, CryptoErrorKind};
 use crate::error::{Error, Result};
-use crate::format::hex;
 use crate::message::{
     RawMsgData, RawV3Message, ScopedPdu, SecurityLevel, V3Message, combine_staged_v3_anomalies,
     decode_scoped_pdu,
@@ -14,7 +13,7 @@ use crate::message::{
 use crate::pdu::{Pdu, PduType};
 use crate::transport::{Candidate, CorrelationWindow, RequestRegistration, Transport};
 use crate::v3::{
-    EngineCache, EngineState, ReportStatus, TimelinessCandidateOutcome,
+    EngineCache, EngineId, EngineState, ReportStatus, TimelinessCandidateOutcome,
     TimelinessPublicationOutcome, UsmSecurityParams, auth::verify_message, classify_report,
     validate_engine_id,
 };
@@ -170,7 +169,7 @@ impl<T: Transport> Client<T> {
                 } else {
                     tokio::select! {
                         biased;
-                        () = tokio::time::sleep_until(deadline) => timeout_result(),
+                        () = crate::runtime::sleep_until(deadline) => timeout_result(),
                         result = async {
                             let _rediscovery_guard = self.inner.discovery_lock.lock().await;
                             self.discover_engine_state_locked(&flight.retries).await
@@ -217,7 +216,7 @@ impl<T: Transport> Client<T> {
             }
             tokio::select! {
                 biased;
-                () = tokio::time::sleep_until(deadline) => return Err(Error::Timeout {
+                () = crate::runtime::sleep_until(deadline) => return Err(Error::Timeout {
                     target: self.peer_addr(),
                     elapsed: timeout,
                     retries: 0,
@@ -315,7 +314,7 @@ impl<T: Transport> Client<T> {
         } else {
             discovery.engine_state
         };
-        tracing::debug!(target: "async_snmp::client", { snmp.engine_id = %hex::Bytes(state.engine_id()), snmp.msg_max_size = state.msg_max_size().as_usize() }, "discovered engine identity");
+        tracing::debug!(target: "async_snmp::client", { snmp.engine_id = %EngineId::from_validated(state.engine_id().clone()), snmp.msg_max_size = state.msg_max_size().as_usize() }, "discovered engine identity");
         Ok(DiscoveredState {
             state,
             metadata: discovery.metadata,
@@ -349,9 +348,8 @@ impl<T: Transport> Client<T> {
             } else {
                 state
             };
-            let security = self
-                .inner
-                .config
+            let auth = self.auth();
+            let security = auth
                 .usm_config()
                 .ok_or_else(|| Error::Config("V3 security not configured".into()).boxed())?;
             let derived_keys = security
@@ -364,6 +362,19 @@ impl<T: Transport> Client<T> {
         Ok(())
     }
 
+    /// Return the authoritative engine ID this client has discovered.
+    ///
+    /// Returns `None` before discovery and for clients that do not use USM.
+    #[must_use]
+    pub fn engine_id(&self) -> Option<EngineId> {
+        self.inner
+            .engine
+            .read()
+            .ok()?
+            .as_ref()
+            .map(|engine| EngineId::from_validated(engine.state.engine_id().clone()))
+    }
+
     /// Discover and replace the established authoritative engine.
     ///
     /// This is the intentional recovery path when a device at the target
@@ -390,7 +401,7 @@ impl<T: Transport> Client<T> {
     /// Discover and replace the authoritative engine while retaining accepted
     /// wire deviations from the discovery Report.
     pub async fn rediscover_engine_with_metadata(&self) -> Result<ResponseMetadata> {
-        if !self.is_v3() {
+        if !self.is_usm() {
             return Err(Error::Config("engine discovery requires SNMPv3".into()).boxed());
         }
 
@@ -412,7 +423,7 @@ impl<T: Transport> Client<T> {
         }
         tokio::select! {
             biased;
-            () = tokio::time::sleep_until(deadline) => Err(timeout_error()),
+            () = crate::runtime::sleep_until(deadline) => Err(timeout_error()),
             result = async {
                 let _guard = self.inner.discovery_lock.lock().await;
                 self.rediscover_engine_locked(&retries).await
@@ -428,22 +439,23 @@ impl<T: Transport> Client<T> {
         let discovery = self.discover_engine_response(retries_performed).await?;
         let engine_state = discovery.engine_state;
         let metadata = discovery.metadata;
-        tracing::debug!(target: "async_snmp::client", { snmp.engine_id = %hex::Bytes(engine_state.engine_id()), snmp.msg_max_size = engine_state.msg_max_size().as_usize() }, "discovered engine identity");
+        tracing::debug!(target: "async_snmp::client", { snmp.engine_id = %EngineId::from_validated(engine_state.engine_id().clone()), snmp.msg_max_size = engine_state.msg_max_size().as_usize() }, "discovered engine identity");
 
         let install_result: Result<()> = (|| {
-            let security = self
+            // Localize under the engine lock so a concurrent credential
+            // rotation cannot install keys for the replaced user.
+            let mut engine = self
                 .inner
-                .config
+                .engine
+                .write()
+                .map_err(|_| Error::Config("engine lock poisoned".into()).boxed())?;
+            let auth = self.auth();
+            let security = auth
                 .usm_config()
                 .ok_or_else(|| Error::Config("V3 security not configured".into()).boxed())?;
             let derived_keys = security
                 .derive_keys_inner(engine_state.engine_id())
                 .map_err(|e| Error::Config(e.to_string().into()).boxed())?;
-            let mut engine = self
-                .inner
-                .engine
-                .write()
-                .map_err(|_| Error::Config("engine lock poisoned".into()).boxed())?;
             let engine_state = if let Some(cache) = &self.inner.engine_cache {
                 cache.replace_target(self.peer_addr(), engine_state)
             } else {
@@ -524,7 +536,7 @@ impl<T: Transport> Client<T> {
             let exchange_result = tokio::select! {
                 biased;
                 result = &mut discovery_exchange => Some(result),
-                () = tokio::time::sleep_until(attempt_deadline) => None,
+                () = crate::runtime::sleep_until(attempt_deadline) => None,
             };
             let exchange_result = match exchange_result {
                 Some(result) => result,
@@ -686,9 +698,19 @@ impl<T: Transport> Client<T> {
         msg_id: i32,
         engine_time_override: Option<&PacketLocalEngineTime>,
     ) -> Result<EncodedV3Request> {
-        let security = self
+        self.refresh_engine_from_cache()?;
+        let engine = self
             .inner
-            .config
+            .engine
+            .read()
+            .map_err(|_| Error::Config("engine lock poisoned".into()).boxed())?;
+        let engine = engine
+            .as_ref()
+            .ok_or_else(|| Error::Config("engine not discovered".into()).boxed())?;
+        // Read the user under the engine lock so its name and the localized
+        // keys come from the same credential rotation.
+        let auth = self.auth();
+        let security = auth
             .usm_config()
             .ok_or_else(|| Error::Config("V3 security not configured".into()).boxed())?;
 
@@ -715,16 +737,6 @@ impl<T: Transport> Client<T> {
             _ => None,
         };
 
-        self.refresh_engine_from_cache()?;
-        let engine = self
-            .inner
-            .engine
-            .read()
-            .map_err(|_| Error::Config("engine lock poisoned".into()).boxed())?;
-        let engine = engine
-            .as_ref()
-            .ok_or_else(|| Error::Config("engine not discovered".into()).boxed())?;
-
         let context_engine_id = engine.state.engine_id().clone();
         let context_name = security.configured_context_name().clone();
         let (engine_boots, engine_time) = if let Some(engine_time) = engine_time_override {
@@ -739,7 +751,7 @@ impl<T: Transport> Client<T> {
             }
             (engine_time.boots, engine_time.time)
         } else {
-            engine.state.estimated_boots_time()
+            engine.state.outgoing_boots_time()
         };
         let data = crate::v3::encode::encode_v3_message(
             pdu,
@@ -780,9 +792,8 @@ impl<T: Transport> Client<T> {
         response_usm: &UsmSecurityParams,
         received_level: SecurityLevel,
     ) -> Result<Arc<()>> {
-        let security = self
-            .inner
-            .config
+        let auth = self.auth();
+        let security = auth
             .usm_config()
             .ok_or_else(|| Error::Config("V3 security not configured".into()).boxed())?;
 
@@ -1079,7 +1090,7 @@ impl<T: Transport> Client<T> {
         fields(
             snmp.target = %self.peer_addr(),
             snmp.request_id = pdu.request_id,
-            snmp.security_level = ?self.inner.config.usm_config().map(crate::v3::UsmConfig::security_level),
+            snmp.security_level = ?self.auth().usm_config().map(crate::v3::UsmConfig::security_level),
             snmp.attempt = tracing::field::Empty,
             snmp.protocol_correction = tracing::field::Empty,
             snmp.elapsed_ms = tracing::field::Empty,
@@ -1103,9 +1114,9 @@ impl<T: Transport> Client<T> {
             .start_exchange_deadline()
             .map_err(|error| error.with_prior_response_metadata(&exchange_metadata))?;
 
-        let security = self
-            .inner
-            .config
+        let auth = self.auth();
+
+        let security = auth
             .usm_config()
             .ok_or_else(|| Error::Config("V3 security not configured".into()).boxed())
             .map_err(|error| error.with_prior_response_metadata(&exchange_metadata))?;
@@ -1184,7 +1195,7 @@ impl<T: Transport> Client<T> {
                 let exchange_result = tokio::select! {
                     biased;
                     result = &mut request_exchange => Some(result),
-                    () = tokio::time::sleep_until(attempt_deadline) => None,
+                    () = crate::runtime::sleep_until(attempt_deadline) => None,
                 };
                 match exchange_result {
                     Some(result) => result,
@@ -1518,9 +1529,9 @@ impl<T: Transport> Client<T> {
 
         let local_engine = self.local_engine_for_trap()?;
 
-        let security = self
-            .inner
-            .config
+        let auth = self.auth();
+
+        let security = auth
             .usm_config()
             .ok_or_else(|| Error::Config("V3 security not configured".into()).boxed())?;
 
@@ -1538,7 +1549,7 @@ impl<T: Transport> Client<T> {
         Ok(())
     }
 
-    fn local_engine_for_trap(&self) -> Result<&crate::v3::AuthoritativeEngine> {
+    pub(super) fn local_engine_for_trap(&self) -> Result<&crate::v3::AuthoritativeEngine> {
         self.inner
             .config
             .local_authoritative_engine
@@ -1557,9 +1568,8 @@ impl<T: Transport> Client<T> {
     /// trap PDUs. Uses the persisted local engine state and sets
     /// reportable=false (no Report PDU expected for traps).
     pub(super) fn build_v3_trap_message(&self, pdu: &Pdu, msg_id: i32) -> Result<Vec<u8>> {
-        let security = self
-            .inner
-            .config
+        let auth = self.auth();
+        let security = auth
             .usm_config()
             .ok_or_else(|| Error::Config("V3 security not configured".into()).boxed())?;
 
@@ -3820,4 +3830,41 @@ mod response_validation_tests {
             "single-response transport must exhaust the rejected stale candidate, got: {err}"
         );
     }
+
+    /// A rotated USM password re-localizes the discovered engine's keys, so
+    /// the next request authenticates with the new secret.
+    #[tokio::test]
+    async fn v3_credential_rotation_relocalizes_engine_keys() {
+        struct Rotated;
+
+        impl crate::CredentialProvider for Rotated {
+            fn fetch(&self) -> crate::runtime::BoxFuture<'_, Result<crate::Credentials>> {
+                Box::pin(async {
+                    Ok(crate::Credentials::new("rotated").usm_user(
+                        UsmConfig::new("user")
+                            .auth(AuthProtocol::Sha1, "authpass12345678")
+                            .unwrap(),
+                    ))
+                })
+            }
+        }
+
+        let stale = UsmConfig::new("user")
+            .auth(AuthProtocol::Sha1, "stalepass1234567")
+            .unwrap();
+        let response = build_response(PduType::Response, 123, 1, 1000, Some(b"authpass12345678"));
+        let mut client = canned_client(response, 1, 1000, stale);
+        client
+            .attach_credentials(crate::credentials::CredentialSource::new(
+                Rotated,
+                crate::CredentialRefresh::default(),
+            ))
+            .unwrap();
+        // Waits for the immediate background fetch if it is still running.
+        client.refresh_credentials().await.unwrap();
+
+        let pdu = Pdu::get_request(123, &[oid!(1, 3, 6, 1, 1)]);
+        let result = client.send_v3_and_recv(pdu).await;
+        assert!(result.is_ok(), "expected Ok, got: {:?}", result.err());
+    }
 }
@@ -0,0 +1,745 @@
+//! Credentials that rotate while a client, agent, or receiver is running.
+//!
+//! A [`CredentialProvider`] supplies [`Credentials`]: communities and USM
+//! users fetched from a secrets manager or another external store. Pass one to
+//! [`ClientBuilder::credential_provider`](crate::ClientBuilder::credential_provider),
+//! `AgentBuilder::credential_provider`, or
+//! [`NotificationReceiverBuilder::credential_provider`](crate::NotificationReceiverBuilder::credential_provider).
+//! A background task then fetches on every [`CredentialRefresh::interval`],
+//! and `refresh_credentials` on the client, agent, or receiver fetches on
+//! demand.
+//!
+//! Password-to-key derivation (RFC 3414 Section A.2 hashes a megabyte per
+//! password) runs on a blocking thread, so a refresh never stalls message
+//! processing. A fetch returning the revision already installed is ignored.
+//!
+//! # Overlap
+//!
+//! Peers do not switch credentials at the same instant. After a rotation,
+//! agents and notification receivers keep accepting the previous credentials
+//! for [`CredentialRefresh::overlap`]: a community matches when it appears in
+//! either set, and a USM message naming a user in both sets is authenticated
+//! against each in turn. Clients always send with the newest credentials.
+//!
+//! Communities and users configured directly on a builder stay in force
+//! alongside the provider's.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use async_snmp::credentials::{CredentialRefresh, PollingFileCredentialProvider};
+//! use async_snmp::{Auth, Client};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> async_snmp::Result<()> {
+//! let client = Client::builder("192.168.1.1:161", Auth::v2c("public"))
+//!     .credential_provider(
+//!         PollingFileCredentialProvider::new("credentials.conf"),
+//!         CredentialRefresh {
+//!             interval: Duration::from_secs(5),
+//!             ..CredentialRefresh::default()
+//!         },
+//!     )
+//!     .connect()
+//!     .await?;
+//! # let _ = client;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{BuildHasher, RandomState};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, Weak};
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::time::Instant;
+
+use crate::error::{Error, Result};
+use crate::runtime::BoxFuture;
+use crate::{AuthProtocol, Community, PrivProtocol, UsmConfig, UsmUser};
+
+/// Default interval between credential fetches (5 minutes).
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Default time previous credentials stay accepted after a rotation
+/// (15 minutes).
+pub const DEFAULT_OVERLAP: Duration = Duration::from_secs(15 * 60);
+
+/// One revision of rotating communities and USM users.
+///
+/// The revision identifies the contents: a secrets manager's version ID or a
+/// digest of the secret. Fetching the revision already installed does not
+/// re-derive keys or restart the overlap window.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+/// # fn main() -> async_snmp::CryptoResult<()> {
+/// use async_snmp::credentials::Credentials;
+/// use async_snmp::{AuthProtocol, UsmConfig};
+///
+/// let credentials = Credentials::new("2026-10-01")
+///     .community("rotated-community")
+///     .usm_user(UsmConfig::new("monitor").auth(AuthProtocol::Sha256, "rotated-password")?);
+/// assert_eq!(credentials.usm_users().len(), 1);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(any(feature = "crypto-rustcrypto", feature = "crypto-fips")))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct Credentials {
+    revision: String,
+    communities: Vec<Community>,
+    usm_users: Vec<UsmConfig>,
+}
+
+impl Credentials {
+    /// Create an empty credential set identified by `revision`.
+    pub fn new(revision: impl Into<String>) -> Self {
+        Self {
+            revision: revision.into(),
+            communities: Vec::new(),
+            usm_users: Vec::new(),
+        }
+    }
+
+    /// Add a community.
+    #[must_use]
+    pub fn community(mut self, community: impl Into<Community>) -> Self {
+        self.communities.push(community.into());
+        self
+    }
+
+    /// Add a USM user.
+    ///
+    /// Agents and receivers accept the user for inbound messages; a USM
+    /// client sends as the first user in the set.
+    #[must_use]
+    pub fn usm_user(mut self, user: UsmConfig) -> Self {
+        self.usm_users.push(user);
+        self
+    }
+
+    /// Return the revision identifier.
+    #[must_use]
+    pub fn revision(&self) -> &str {
+        &self.revision
+    }
+
+    /// Return the communities in the order they were added.
+    #[must_use]
+    pub fn communities(&self) -> &[Community] {
+        &self.communities
+    }
+
+    /// Return the USM users in the order they were added.
+    #[must_use]
+    pub fn usm_users(&self) -> &[UsmConfig] {
+        &self.usm_users
+    }
+
+    /// Validate the set and derive every user's master keys.
+    fn prepare(self, rules: CredentialRules) -> Result<PreparedCredentials> {
+        if !rules.usm && !self.usm_users.is_empty() {
+            return Err(Error::Config(
+                "USM users from a credential provider require authoritative engine state".into(),
+            )
+            .boxed());
+        }
+        let mut usm_users = HashMap::with_capacity(self.usm_users.len());
+        let mut usm_configs = Vec::with_capacity(self.usm_users.len());
+        for mut config in self.usm_users {
+            if !rules.des
+                && config
+                    .priv_protocol()
+                    .is_some_and(PrivProtocol::is_des_family)
+            {
+                return Err(Error::Config(
+                    "DES/3DES users from a credential provider require durable DES sender state"
+                        .into(),
+                )
+                .boxed());
+            }
+            config.validate_and_precompute().map_err(|error| {
+                Error::Config(format!("invalid USM user from credential provider: {error}").into())
+                    .boxed()
+            })?;
+            let username = config.username().clone();
+            if usm_users
+                .insert(username, UsmUser::from_config(config.clone()))
+                .is_some()
+            {
+                return Err(Error::Config(
+                    "duplicate USM username from credential provider".into(),
+                )
+                .boxed());
+            }
+            usm_configs.push(config);
+        }
+        Ok(PreparedCredentials {
+            communities: self.communities,
+            usm_users,
+            usm_configs,
+        })
+    }
+}
+
+/// Source of rotating [`Credentials`].
+///
+/// Implement this over a secrets manager client. Agents and receivers fetch
+/// once while building and then on every refresh; clients fetch when built
+/// and send with their configured [`Auth`](crate::Auth) until the first fetch
+/// completes. A failed fetch leaves the installed credentials in place.
+pub trait CredentialProvider: Send + Sync + 'static {
+    /// Fetch the current credentials.
+    fn fetch(&self) -> BoxFuture<'_, Result<Credentials>>;
+}
+
+impl<P: CredentialProvider + ?Sized> CredentialProvider for Arc<P> {
+    fn fetch(&self) -> BoxFuture<'_, Result<Credentials>> {
+        (**self).fetch()
+    }
+}
+
+/// Timing of credential refreshes.
+///
+/// ```rust
+/// use async_snmp::credentials::CredentialRefresh;
+/// use std::time::Duration;
+///
+/// let refresh = CredentialRefresh {
+///     overlap: Duration::from_secs(3600),
+///     ..CredentialRefresh::default()
+/// };
+/// # let _ = refresh;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialRefresh {
+    /// Time between fetches (default: [`DEFAULT_REFRESH_INTERVAL`]).
+    pub interval: Duration,
+    /// Time the previous credentials stay accepted on receive paths after a
+    /// rotation (default: [`DEFAULT_OVERLAP`]).
+    pub overlap: Duration,
+}
+
+impl Default for CredentialRefresh {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_REFRESH_INTERVAL,
+            overlap: DEFAULT_OVERLAP,
+        }
+    }
+}
+
+/// Reads credentials from a local file on every refresh.
+///
+/// The file is polled, not watched: an edit is picked up only by the next
+/// scheduled refresh or an explicit `refresh_credentials` call. Intended for
+/// local testing, so pair it with a short [`CredentialRefresh::interval`].
+/// Each non-empty line not starting with `#` is one entry:
+///
+/// ```text
+/// community <community>
+/// usm <user>
+/// usm <user> <auth-protocol> <auth-password>
+/// usm <user> <auth-protocol> <auth-password> <priv-protocol> <priv-password>
+/// ```
+///
+/// Fields are separated by whitespace, so values cannot contain any.
+/// Protocol names are parsed as by [`AuthProtocol`] and [`PrivProtocol`]'s
+/// `FromStr` implementations. The revision is a digest of the file contents.
+pub struct PollingFileCredentialProvider {
+    path: PathBuf,
+    digest: RandomState,
+}
+
+impl PollingFileCredentialProvider {
+    /// Create a provider reading `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            digest: RandomState::new(),
+        }
+    }
+
+    /// Return the path of the credentials file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Debug for PollingFileCredentialProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PollingFileCredentialProvider")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CredentialProvider for PollingFileCredentialProvider {
+    fn fetch(&self) -> BoxFuture<'_, Result<Credentials>> {
+        Box::pin(async move {
+            let path = self.path.clone();
+            let contents = crate::runtime::spawn_blocking(move || std::fs::read(path))
+                .await
+                .map(zeroize::Zeroizing::new)
+                .map_err(|error| {
+                    Error::Config(format!("could not read {}: {error}", self.path.display()).into())
+                        .boxed()
+                })?;
+            let revision = format!("{:016x}", self.digest.hash_one(contents.as_slice()));
+            let text = std::str::from_utf8(&contents).map_err(|_| {
+                Error::Config(format!("{} is not valid UTF-8", self.path.display()).into()).boxed()
+            })?;
+            parse_credentials(text, revision).map_err(|(line, reason)| {
+                Error::Config(format!("{}:{line}: {reason}", self.path.display()).into()).boxed()
+            })
+        })
+    }
+}
+
+/// Parse the [`PollingFileCredentialProvider`] format, reporting the failing line.
+fn parse_credentials(
+    text: &str,
+    revision: String,
+) -> std::result::Result<Credentials, (usize, String)> {
+    let mut credentials = Credentials::new(revision);
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        credentials = match fields.as_slice() {
+            ["community", community] => credentials.community(community.as_bytes().to_vec()),
+            ["usm", user, security @ ..] => {
+                let user = UsmConfig::new(user.as_bytes().to_vec());
+                let parse_auth = |protocol: &str| {
+                    protocol
+                        .parse::<AuthProtocol>()
+                        .map_err(|error| (line_number, error.to_string()))
+                };
+                let parse_priv = |protocol: &str| {
+                    protocol
+                        .parse::<PrivProtocol>()
+                        .map_err(|error| (line_number, error.to_string()))
+                };
+                let user = match *security {
+                    [] => user,
+                    [auth_protocol, auth_password] => user
+                        .auth(parse_auth(auth_protocol)?, auth_password)
+                        .map_err(|error| (line_number, error.to_string()))?,
+                    [auth_protocol, auth_password, priv_protocol, priv_password] => user
+                        .auth_priv(
+                            parse_auth(auth_protocol)?,
+                            auth_password,
+                            parse_priv(priv_protocol)?,
+                            priv_password,
+                        )
+                        .map_err(|error| (line_number, error.to_string()))?,
+                    _ => {
+                        return Err((
+                            line_number,
+                            "expected `usm <user> [<auth-protocol> <auth-password> \
+                             [<priv-protocol> <priv-password>]]`"
+                                .into(),
+                        ));
+                    }
+                };
+                credentials.usm_user(user)
+            }
+            _ => {
+                return Err((line_number, "expected a `community` or `usm` entry".into()));
+            }
+        };
+    }
+    Ok(credentials)
+}
+
+/// A provider and refresh timing configured on a builder.
+#[derive(Clone)]
+pub(crate) struct CredentialSource {
+    pub(crate) provider: Arc<dyn CredentialProvider>,
+    pub(crate) refresh: CredentialRefresh,
+}
+
+impl CredentialSource {
+    pub(crate) fn new(provider: impl CredentialProvider, refresh: CredentialRefresh) -> Self {
+        Self {
+            provider: Arc::new(provider),
+            refresh,
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.refresh.interval.is_zero() {
+            return Err(
+                Error::Config("credential refresh interval must be non-zero".into()).boxed(),
+            );
+        }
+        crate::transport::checked_deadline(self.refresh.interval, "credential refresh interval")?;
+        crate::transport::checked_deadline(self.refresh.overlap, "credential overlap")?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CredentialSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CredentialSource")
+            .field("refresh", &self.refresh)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Which provided credentials a role can use.
+#[derive(Clone, Copy)]
+pub(crate) struct CredentialRules {
+    /// The role has authoritative engine state for USM users.
+    pub(crate) usm: bool,
+    /// The role has durable DES sender state.
+    pub(crate) des: bool,
+}
+
+/// A validated credential set with master keys derived.
+pub(crate) struct PreparedCredentials {
+    pub(crate) communities: Vec<Community>,
+    pub(crate) usm_users: HashMap<Bytes, UsmUser>,
+    /// USM users in provider order, for clients.
+    pub(crate) usm_configs: Vec<UsmConfig>,
+}
+
+struct Generations {
+    revision: Option<String>,
+    current: Arc<PreparedCredentials>,
+    previous: Option<(Arc<PreparedCredentials>, Instant)>,
+}
+
+/// Callback that applies a new set to owner state before it becomes current.
+pub(crate) type InstallHook = Box<dyn Fn(&PreparedCredentials) + Send + Sync>;
+
+/// Installed credentials and the one set still accepted during overlap.
+pub(crate) struct RotatingCredentials {
+    source: CredentialSource,
+    rules: CredentialRules,
+    generations: RwLock<Generations>,
+    generation: AtomicU64,
+    refresh_lock: tokio::sync::Mutex<()>,
+    on_install: Option<InstallHook>,
+}
+
+impl RotatingCredentials {
+    /// Create an empty rotation; `on_install` runs for each new set before
+    /// it becomes current.
+    pub(crate) fn new(
+        source: CredentialSource,
+        rules: CredentialRules,
+        on_install: Option<InstallHook>,
+    ) -> Self {
+        Self {
+            source,
+            rules,
+            generations: RwLock::new(Generations {
+                revision: None,
+                current: Arc::new(PreparedCredentials {
+                    communities: Vec::new(),
+                    usm_users: HashMap::new(),
+                    usm_configs: Vec::new(),
+                }),
+                previous: None,
+            }),
+            generation: AtomicU64::new(0),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            on_install,
+        }
+    }
+
+    /// Create a rotation holding the provider's first credentials.
+    pub(crate) async fn load(source: CredentialSource, rules: CredentialRules) -> Result<Self> {
+        let credentials = Self::new(source, rules, None);
+        credentials.refresh().await?;
+        Ok(credentials)
+    }
+
+    /// Fetch from the provider and install a new revision, returning whether
+    /// one was installed.
+    pub(crate) async fn refresh(&self) -> Result<bool> {
+        let _refresh = self.refresh_lock.lock().await;
+        let credentials = self.source.provider.fetch().await?;
+        if self.read().revision.as_deref() == Some(credentials.revision()) {
+            return Ok(false);
+        }
+        let revision = credentials.revision.clone();
+        let rules = self.rules;
+        let prepared =
+            Arc::new(crate::runtime::spawn_blocking(move || credentials.prepare(rules)).await?);
+        if let Some(on_install) = &self.on_install {
+            on_install(&prepared);
+        }
+        let mut generations = self
+            .generations
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let previous = std::mem::replace(&mut generations.current, prepared);
+        generations.previous = Some((previous, Instant::now() + self.source.refresh.overlap));
+        generations.revision = Some(revision);
+        self.generation.fetch_add(1, Ordering::Release);
+        tracing::debug!(target: "async_snmp::credentials", { communities = generations.current.communities.len(), usm_users = generations.current.usm_configs.len() }, "installed rotated credentials");
+        Ok(true)
+    }
+
+    /// Return the credentials accepted right now.
+    pub(crate) fn snapshot(&self) -> CredentialSnapshot {
+        let generations = self.read();
+        CredentialSnapshot {
+            current: Arc::clone(&generations.current),
+            previous: generations
+                .previous
+                .as_ref()
+                .filter(|(_, expires)| Instant::now() < *expires)
+                .map(|(previous, _)| Arc::clone(previous)),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Generations> {
+        self.generations
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Refresh every configured interval until the handle or every [`Arc`]
+    /// to the rotation is dropped, starting with a fetch when `immediate`.
+    fn spawn_refresh(self: &Arc<Self>, immediate: bool) -> CredentialRefreshTask {
+        let credentials: Weak<Self> = Arc::downgrade(self);
+        let interval = self.source.refresh.interval;
+        let handle = crate::runtime::spawn(async move {
+            let mut wait = !immediate;
+            loop {
+                if wait {
+                    crate::runtime::sleep(interval).await;
+                }
+                wait = true;
+                let Some(credentials) = credentials.upgrade() else {
+                    break;
+                };
+                if let Err(error) = credentials.refresh().await {
+                    tracing::warn!(target: "async_snmp::credentials", { error = %error }, "credential refresh failed; keeping installed credentials");
+                }
+            }
+        });
+        CredentialRefreshTask {
+            abort: handle.abort_handle(),
+        }
+    }
+}
+
+/// Stops a credential refresh task when dropped.
+struct CredentialRefreshTask {
+    abort: crate::runtime::AbortHandle,
+}
+
+impl Drop for CredentialRefreshTask {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+/// A rotation together with its refresh task, owned by a client, agent, or
+/// receiver.
+pub(crate) struct ActiveCredentials {
+    credentials: Arc<RotatingCredentials>,
+    _refresh: CredentialRefreshTask,
+}
+
+impl ActiveCredentials {
+    /// Start refreshing `credentials`, fetching at once when `immediate`.
+    pub(crate) fn start(credentials: RotatingCredentials, immediate: bool) -> Self {
+        let credentials = Arc::new(credentials);
+        let refresh = credentials.spawn_refresh(immediate);
+        Self {
+            credentials,
+            _refresh: refresh,
+        }
+    }
+
+    pub(crate) async fn refresh(&self) -> Result<bool> {
+        self.credentials.refresh().await
+    }
+
+    pub(crate) fn snapshot(&self) -> CredentialSnapshot {
+        self.credentials.snapshot()
+    }
+}
+
+/// The current credentials and, within the overlap window, the previous ones.
+pub(crate) struct CredentialSnapshot {
+    current: Arc<PreparedCredentials>,
+    previous: Option<Arc<PreparedCredentials>>,
+}
+
+impl CredentialSnapshot {
+    fn sets(&self) -> impl Iterator<Item = &PreparedCredentials> {
+        std::iter::once(&*self.current).chain(self.previous.as_deref())
+    }
+
+    /// Return accepted communities, current first.
+    pub(crate) fn communities(&self) -> impl Iterator<Item = &Community> {
+        self.sets().flat_map(|set| &set.communities)
+    }
+
+    /// Return the accepted users named `username`, current first.
+    pub(crate) fn usm_users<'a>(&'a self, username: &'a [u8]) -> impl Iterator<Item = &'a UsmUser> {
+        self.sets()
+            .filter_map(move |set| set.usm_users.get(username))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct Revisions(Mutex<Vec<Credentials>>);
+
+    impl CredentialProvider for Revisions {
+        fn fetch(&self) -> BoxFuture<'_, Result<Credentials>> {
+            let mut revisions = self.0.lock().unwrap();
+            let next = if revisions.len() > 1 {
+                revisions.remove(0)
+            } else {
+                revisions[0].clone()
+            };
+            Box::pin(async move { Ok(next) })
+        }
+    }
+
+    fn rotation(revisions: Vec<Credentials>, overlap: Duration) -> RotatingCredentials {
+        RotatingCredentials::new(
+            CredentialSource::new(
+                Revisions(Mutex::new(revisions)),
+                CredentialRefresh {
+                    overlap,
+                    ..CredentialRefresh::default()
+                },
+            ),
+            CredentialRules {
+                usm: true,
+                des: false,
+            },
+            None,
+        )
+    }
+
+    fn accepts(snapshot: &CredentialSnapshot, community: &[u8]) -> bool {
+        snapshot.communities().any(|c| c.matches(community))
+    }
+
+    #[tokio::test]
+    async fn previous_credentials_are_accepted_during_overlap() {
+        let credentials = rotation(
+            vec![
+                Credentials::new("1").community("old"),
+                Credentials::new("2").community("new"),
+            ],
+            Duration::from_secs(60),
+        );
+        assert!(credentials.refresh().await.unwrap());
+        assert!(credentials.refresh().await.unwrap());
+        assert!(!credentials.refresh().await.unwrap());
+        assert_eq!(credentials.generation(), 2);
+
+        let snapshot = credentials.snapshot();
+        assert!(accepts(&snapshot, b"new"));
+        assert!(accepts(&snapshot, b"old"));
+    }
+
+    #[tokio::test]
+    async fn previous_credentials_expire_after_overlap() {
+        let credentials = rotation(
+            vec![
+                Credentials::new("1").community("old"),
+                Credentials::new("2").community("new"),
+            ],
+            Duration::ZERO,
+        );
+        credentials.refresh().await.unwrap();
+        credentials.refresh().await.unwrap();
+
+        let snapshot = credentials.snapshot();
+        assert!(accepts(&snapshot, b"new"));
+        assert!(!accepts(&snapshot, b"old"));
+    }
+
+    #[tokio::test]
+    async fn invalid_credentials_leave_installed_set_in_place() {
+        let credentials = rotation(
+            vec![
+                Credentials::new("1").community("old"),
+                Credentials::new("2")
+                    .usm_user(UsmConfig::new("dup"))
+                    .usm_user(UsmConfig::new("dup")),
+            ],
+            Duration::ZERO,
+        );
+        credentials.refresh().await.unwrap();
+        assert!(credentials.refresh().await.is_err());
+        assert_eq!(credentials.generation(), 1);
+        assert!(accepts(&credentials.snapshot(), b"old"));
+    }
+
+    #[test]
+    fn file_format_parses_communities_and_users() {
+        let text = "# rotated monthly\n\ncommunity public\nusm guest\n";
+        let credentials = parse_credentials(text, "r".into()).unwrap();
+        assert_eq!(credentials.communities().len(), 1);
+        assert_eq!(credentials.usm_users()[0].username().as_ref(), b"guest");
+
+        let error = parse_credentials("community\n", "r".into()).unwrap_err();
+        assert_eq!(error.0, 1);
+        let error = parse_credentials("usm a SHA-256\n", "r".into()).unwrap_err();
+        assert_eq!(error.0, 1);
+    }
+
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    #[test]
+    fn file_format_parses_protocols() {
+        let text = "usm admin SHA-256 authpass123 AES-128 privpass123\n";
+        let credentials = parse_credentials(text, "r".into()).unwrap();
+        let user = &credentials.usm_users()[0];
+        assert_eq!(user.auth_protocol(), Some(AuthProtocol::Sha256));
+        assert_eq!(user.priv_protocol(), Some(PrivProtocol::Aes128));
+
+        let error = parse_credentials("usm admin ROT13 authpass123\n", "r".into()).unwrap_err();
+        assert_eq!(error.0, 1);
+    }
+
+    #[tokio::test]
+    async fn file_provider_revision_follows_contents() {
+        let path = std::env::temp_dir().join(format!(
+            "async-snmp-credentials-{}.conf",
+            std::process::id()
+        ));
+        std::fs::write(&path, "community first\n").unwrap();
+        let provider = PollingFileCredentialProvider::new(&path);
+        let first = provider.fetch().await.unwrap();
+        assert_eq!(provider.fetch().await.unwrap().revision(), first.revision());
+
+        std::fs::write(&path, "community second\n").unwrap();
+        let second = provider.fetch().await.unwrap();
+        assert_ne!(second.revision(), first.revision());
+        assert!(second.communities()[0].matches(b"second"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -377,6 +377,8 @@ pub enum ConstructionStage {
     Bind,
     /// Establishing a TCP connection.
     Connect,
+    /// Completing a TLS or DTLS handshake.
+    Handshake,
     /// Preparing local protocol state after socket and destination setup.
     Prepare,
 }
@@ -15,6 +15,7 @@ use crate::Community;
 use crate::DecodeAnomaly;
 use crate::message::SecurityLevel;
 use crate::pdu::PduType;
+use crate::transport::PeerCredentials;
 use crate::version::{CommunityVersion, Version};
 
 use super::SecurityModel;
@@ -22,13 +23,20 @@ use super::SecurityModel;
 /// A request's model-specific security name.
 ///
 /// Community variants retain transitive Debug redaction, while USM usernames
-/// remain visible in diagnostics.
+/// and mapped security names remain visible in diagnostics.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum SecurityName {
     /// SNMPv1/v2c community identifier.
     Community(Community),
     /// SNMPv3 USM username.
     Usm(Bytes),
+    /// securityName selected for a v1/v2c community by an
+    /// snmpCommunityTable entry (RFC 3584).
+    ///
+    /// The received community remains available from
+    /// [`RequestContext::community`].
+    Mapped(Bytes),
 }
 
 impl SecurityName {
@@ -37,7 +45,7 @@ impl SecurityName {
     pub fn as_bytes(&self) -> &[u8] {
         match self {
             Self::Community(community) => community.as_bytes(),
-            Self::Usm(username) => username,
+            Self::Usm(username) | Self::Mapped(username) => username,
         }
     }
 
@@ -56,8 +64,8 @@ impl SecurityName {
     /// Return whether `candidate` is the same kind of security name with the
     /// same protocol octets.
     ///
-    /// Community names match only community names, and USM usernames match
-    /// only USM usernames. For same-variant, equal-length inputs, comparison
+    /// Community names match only community names, USM usernames match only
+    /// USM usernames, and mapped names match only mapped names. For same-variant, equal-length inputs, comparison
     /// time does not depend on the octet values. Variant and length are not
     /// concealed and a mismatch in either may return sooner.
     ///
@@ -73,11 +81,12 @@ impl SecurityName {
             (Self::Community(expected), Self::Community(actual)) => {
                 expected.matches(actual.as_bytes())
             }
-            (Self::Usm(expected), Self::Usm(actual)) => {
+            (Self::Usm(expected), Self::Usm(actual))
+            | (Self::Mapped(expected), Self::Mapped(actual)) => {
                 expected.len() == actual.len()
                     && bool::from(expected.as_ref().ct_eq(actual.as_ref()))
             }
-            (Self::Community(_), Self::Usm(_)) | (Self::Usm(_), Self::Community(_)) => false,
+            _ => false,
         }
     }
 }
@@ -196,6 +205,9 @@ pub struct RequestContext {
     /// Agent-owned phase record used to protect mutation cleanup.
     phase: Arc<AtomicU8>,
 
+    /// Credentials of the peer process, for requests on a Unix stream socket.
+    peer_credentials: Option<PeerCredentials>,
+
     /// Source address of the request.
     ///
     /// Use this for logging or additional access control beyond VACM.
@@ -211,9 +223,12 @@ pub struct RequestContext {
     /// - `Usm` for `SNMPv3` User-based Security Model
     security_model: SecurityModel,
 
-    /// Model-specific community identifier or USM username.
+    /// Model-specific community identifier, mapped securityName, or USM username.
     security_name: SecurityName,
 
+    /// Community received with a v1/v2c request (`None` for v3).
+    community: Option<Community>,
+
     /// Security level (v3 only, `NoAuthNoPriv` for v1/v2c).
     ///
     /// Indicates whether authentication and/or privacy were used.
@@ -267,7 +282,7 @@ pub struct RequestContext {
 
 impl RequestContext {
     #[cfg(test)]
-    pub(crate) fn community(
+    pub(crate) fn from_community(
         source: SocketAddr,
         version: CommunityVersion,
         community: Community,
@@ -306,10 +321,12 @@ impl RequestContext {
             deadline: lifecycle.deadline,
             cancellation: lifecycle.cancellation,
             phase: lifecycle.phase,
+            peer_credentials: lifecycle.peer_credentials,
             source,
             version,
             security_model,
-            security_name: SecurityName::Community(community),
+            security_name: SecurityName::Community(community.clone()),
+            community: Some(community),
             security_level: SecurityLevel::NoAuthNoPriv,
             context_name: Bytes::new(),
             request_id,
@@ -366,10 +383,12 @@ impl RequestContext {
             deadline: lifecycle.deadline,
             cancellation: lifecycle.cancellation,
             phase: lifecycle.phase,
+            peer_credentials: lifecycle.peer_credentials,
             source,
             version: Version::V3,
             security_model: SecurityModel::Usm,
             security_name: SecurityName::Usm(username),
+            community: None,
             security_level,
             context_name,
             request_id,
@@ -443,12 +462,32 @@ impl RequestContext {
         self.write_view = Some(write_view);
     }
 
+    /// Apply an snmpCommunityTable mapping to a community-based request.
+    ///
+    /// The received community is kept; only the security name and context
+    /// are replaced.
+    pub(crate) fn set_community_mapping(&mut self, security_name: Bytes, context_name: Bytes) {
+        self.security_name = SecurityName::Mapped(security_name);
+        self.context_name = context_name;
+    }
+
     /// Return the source address of the request.
     #[must_use]
     pub const fn source(&self) -> SocketAddr {
         self.source
     }
 
+    /// Return the credentials of the peer process.
+    ///
+    /// Populated for requests received on a Unix stream listener (see
+    /// [`AgentBuilder::listen_unix`](crate::agent::AgentBuilder::listen_unix))
+    /// from `SO_PEERCRED` or the platform equivalent. `None` for UDP
+    /// requests and Unix datagrams, which carry no peer credentials.
+    #[must_use]
+    pub const fn peer_credentials(&self) -> Option<&PeerCredentials> {
+        self.peer_credentials.as_ref()
+    }
+
     /// Return the SNMP version.
     #[must_use]
     pub const fn version(&self) -> Version {
@@ -462,11 +501,23 @@ impl RequestContext {
     }
 
     /// Return the model-specific community identifier or USM username.
+    ///
+    /// For a v1/v2c request matched by an snmpCommunityTable entry this is
+    /// [`SecurityName::Mapped`], the securityName VACM authorizes.
     #[must_use]
     pub const fn security_name(&self) -> &SecurityName {
         &self.security_name
     }
 
+    /// Return the community received with a v1/v2c request.
+    ///
+    /// This is the wire community even when an snmpCommunityTable entry
+    /// mapped it to a different security name. `None` for v3 requests.
+    #[must_use]
+    pub const fn community(&self) -> Option<&Community> {
+        self.community.as_ref()
+    }
+
     /// Return the security level used for the request.
     #[must_use]
     pub const fn security_level(&self) -> SecurityLevel {
@@ -550,6 +601,7 @@ pub(crate) struct RequestLifecycle {
     pub(crate) deadline: Option<tokio::time::Instant>,
     pub(crate) cancellation: CancellationToken,
     pub(crate) phase: Arc<AtomicU8>,
+    pub(crate) peer_credentials: Option<PeerCredentials>,
 }
 
 impl RequestLifecycle {
@@ -566,9 +618,16 @@ impl RequestLifecycle {
             deadline,
             cancellation,
             phase,
+            peer_credentials: None,
         }
     }
 
+    /// Attach the connecting process's credentials from a Unix-domain listener.
+    pub(crate) fn with_peer_credentials(mut self, credentials: Option<PeerCredentials>) -> Self {
+        self.peer_credentials = credentials;
+        self
+    }
+
     #[cfg(test)]
     fn for_test(pdu_type: PduType) -> Self {
         let lifecycle = Self::standalone();
@@ -616,7 +675,7 @@ mod tests {
             (CommunityVersion::V1, Version::V1, SecurityModel::V1),
             (CommunityVersion::V2c, Version::V2c, SecurityModel::V2c),
         ] {
-            let context = RequestContext::community(
+            let context = RequestContext::from_community(
                 source,
                 community_version,
                 community.clone(),
@@ -688,7 +747,7 @@ mod tests {
 
     #[test]
     fn debug_redacts_community_and_keeps_usm_username() {
-        let community = RequestContext::community(
+        let community = RequestContext::from_community(
             "192.0.2.55:6161".parse().unwrap(),
             CommunityVersion::V2c,
             Community::from("community-redaction-sentinel-4d91"),
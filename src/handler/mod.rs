@@ -142,6 +142,7 @@ pub use traits::{BoxFuture, MibHandler, PreparedSet};
 
 /// Concrete security model used to authenticate an SNMP request (RFC 3411).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum SecurityModel {
     /// `SNMPv1` community-based security.
     V1 = 1,
@@ -149,12 +150,15 @@ pub enum SecurityModel {
     V2c = 2,
     /// `SNMPv3` User-based Security Model.
     Usm = 3,
+    /// `SNMPv3` Transport Security Model (RFC 5591).
+    Tsm = 4,
 }
 
 impl From<crate::message::V3SecurityModel> for SecurityModel {
     fn from(model: crate::message::V3SecurityModel) -> Self {
         match model {
             crate::message::V3SecurityModel::Usm => Self::Usm,
+            crate::message::V3SecurityModel::Tsm => Self::Tsm,
         }
     }
 }
@@ -170,5 +174,9 @@ mod security_model_tests {
             SecurityModel::from(V3SecurityModel::Usm),
             SecurityModel::Usm
         );
+        assert_eq!(
+            SecurityModel::from(V3SecurityModel::Tsm),
+            SecurityModel::Tsm
+        );
     }
 }
@@ -8,13 +8,20 @@
 //!
 //! ## Protocol and API coverage
 //!
-//! - `SNMPv1`, v2c, and v3 USM clients
+//! - `SNMPv1`, v2c, and v3 USM clients, plus the v3 Transport Security Model
+//!   over secure transports
 //! - GET, GETNEXT, GETBULK, SET, WALK, and BULKWALK operations
 //! - Trap and inform sending and receiving through [`notification`]
-//! - Per-client UDP, shared UDP, and TCP transports
+//! - Per-client UDP, shared UDP, and TCP transports, plus TLS and DTLS
+//!   (RFC 6353) with the `tls` and `dtls` features and Unix domain sockets on
+//!   Unix platforms
 //! - An SNMP agent with async handlers, two-phase SET processing, VACM, and
 //!   built-in engine/USM/MPD objects when the `agent` feature is enabled
 //! - Automatic `tooBig` recovery for GET and GETNEXT batches
+//! - Queryable, diffable walk results through [`snapshot::MibSnapshot`]
+//! - net-snmp walk and snmpsim `.snmprec` file support through [`record`]
+//! - Network-free client testing against recorded data through [`ReplayTransport`]
+//! - pcap/pcapng capture of any transport's exchanges through [`RecordingTransport`]
 //!
 //! GETBULK, BULKWALK, and informs require SNMPv2c or SNMPv3. Structured
 //! outbound encoding rejects values that cannot be represented on the wire;
@@ -381,7 +388,7 @@
 //! | `snmp.max_repetitions` | GETBULK max-repetitions parameter |
 //! | `snmp.username` | `SNMPv3` USM username |
 //! | `snmp.security_level` | `SNMPv3` security level |
-//! | `snmp.engine_id` | `SNMPv3` engine identifier (hex) |
+//! | `snmp.engine_id` | `SNMPv3` engine identifier (hex and decoded format) |
 //! | `snmp.local_addr` | Local bind address |
 //!
 //! ### Tracing targets and filters
@@ -396,6 +403,7 @@
 //! | `async_snmp::notification` | Trap/inform sending and receiving |
 //! | `async_snmp::message`, `async_snmp::pdu` | SNMP message and PDU processing |
 //! | `async_snmp::ber`, `async_snmp::oid`, `async_snmp::value` | BER and SMI value processing |
+//! | `async_snmp::credentials` | Credential provider refreshes |
 //! | `async_snmp::v3`, `async_snmp::usm`, `async_snmp::crypto`, `async_snmp::engine` | `SNMPv3`/USM processing |
 //! | `async_snmp::transport`, `async_snmp::transport::udp`, `async_snmp::transport::tcp` | Transport processing |
 //! | `async_snmp::walk`, `async_snmp::error` | Walk and error processing |
@@ -545,6 +553,14 @@
 //! - `cli`: Builds `asnmp-get`, `asnmp-walk`, and `asnmp-set`.
 //! - `mib`: MIB integration through mib-rs.
 //! - `rt-multi-thread`: Tokio's multi-threaded runtime.
+//! - `rt-smol`: [`SmolRuntime`](runtime::SmolRuntime), for running on
+//!   smol/async-io without a Tokio runtime; see [`runtime`].
+//! - `testing`: [`FaultyTransport`](testing::FaultyTransport) and paused-clock
+//!   helpers for testing retry and timeout handling, and
+//!   [`check_crypto_provider`](testing::check_crypto_provider) for validating
+//!   custom crypto providers.
+//! - `tls`: `TlsTransport`, SNMP over TLS (RFC 6353 TLSTM) through rustls.
+//! - `dtls`: `DtlsTransport`, SNMP over DTLS (RFC 6353 TLSTM) through OpenSSL.
 //!
 //! Client, protocol, transport, notification, and noAuthNoPriv APIs are always
 //! available. The agent and crypto backend features are independent and
@@ -556,6 +572,7 @@ pub mod ber;
 pub mod client;
 mod community;
 pub mod compatibility;
+pub mod credentials;
 pub mod error;
 pub mod format;
 #[cfg(feature = "agent")]
@@ -566,7 +583,11 @@ pub mod notification;
 pub mod oid;
 pub mod pdu;
 pub mod prelude;
+pub mod record;
 mod response_finalizer;
+pub mod runtime;
+pub mod security_event;
+pub mod snapshot;
 pub mod transport;
 mod udp_responder;
 pub mod v3;
@@ -585,27 +606,34 @@ pub mod cli;
 #[cfg(feature = "mib")]
 pub mod mib_support;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+
 // Re-exports for convenience
 #[cfg(feature = "agent")]
 pub use agent::{
-    Agent, AgentBuilder, AgentShutdownPolicy, BuiltinMib, DuplicateVacmAccessEntry,
-    NotificationOutcome, NotificationSendStream, NotificationSinkId, NotificationSinkIdError,
-    NotificationSinkSummary, SinkOutcome, SinkSkipReason, SinkStatus, VacmAccessIndex, VacmBuilder,
-    VacmConfig, VacmSecurityModel, View,
+    Agent, AgentBuilder, AgentShutdownPolicy, BuiltinMib, ContextBuilder, DuplicateVacmAccessEntry,
+    NotificationForwarder, NotificationOutcome, NotificationSendStream, NotificationSinkId,
+    NotificationSinkIdError, NotificationSinkSummary, ProxyHandler, ProxyRoute, SinkOutcome,
+    SinkSkipReason, SinkStatus, VacmAccessIndex, VacmBuilder, VacmConfig, VacmSecurityModel, View,
 };
 pub use client::{
-    Auth, BulkResponse, Client, ClientBuilder, ClientConfig, CommunityVersion,
+    AddressChange, Auth, BulkResponse, Client, ClientBuilder, ClientConfig, CommunityVersion,
     DEFAULT_CONSTRUCTION_TIMEOUT, DEFAULT_MAX_OIDS_PER_REQUEST, DEFAULT_MAX_REPETITIONS,
     DEFAULT_REQUEST_TIMEOUT, DEFAULT_SEND_TIMEOUT, FixedCardinalityChunk,
     FixedCardinalityChunkError, FixedCardinalityChunkStream, FixedCardinalityOperation,
-    FixedCardinalityResponse, MAX_RETRIES, OidOrdering, ResponseMetadata, ResponseShapeAnomaly,
-    ResponseShapePolicy, Retry, RetryBuilder, RetryConfigError, Target, TargetClientBuilder,
-    WalkCollection, WalkError, WalkItem, WalkMetadataStream, WalkMethod, WalkOptions, WalkStream,
+    FixedCardinalityResponse, MAX_RETRIES, OidOrdering, ResolutionObserver, ResolutionPolicy,
+    ResolutionTrigger, ResponseMetadata, ResponseShapeAnomaly, ResponseShapePolicy, Retry,
+    RetryBuilder, RetryConfigError, Target, TargetClientBuilder, WalkCollection, WalkError,
+    WalkItem, WalkMetadataStream, WalkMethod, WalkOptions, WalkStream,
 };
 pub use community::Community;
 pub use compatibility::{
     BoundedStringKind, DecodeAnomaly, DecodeConfig, ExceptionKind, GetBulkField,
 };
+pub use credentials::{
+    CredentialProvider, CredentialRefresh, Credentials, PollingFileCredentialProvider,
+};
 pub use error::{
     ConstructionStage, DecodeError, DecodeErrorKind, DecodeErrorOrigin, Error, ErrorKind,
     ErrorStatus, Result, WalkAbortReason,
@@ -633,16 +661,31 @@ pub use pdu::{
     ErrorIndex, GenericTrap, GetBulkPdu, NotificationPdu, OutboundErrorStatus, OutboundPdu, Pdu,
     PduBody, PduType, RequestPdu, ResponsePdu, StandardPduType, TrapV1Notification, TrapV1Pdu,
 };
+pub use security_event::{
+    CommunityFingerprint, SecurityEvent, SecurityEventKind, SecurityEventReceiver,
+    SecurityPrincipal,
+};
+pub use snapshot::MibSnapshot;
 pub use transport::{
-    BuiltinTransport, Candidate, CommunityResponsePolicy, RequestRegistration, ResponseIdentity,
-    TcpTransport, Transport, UdpControl, UdpHandle, UdpStats, UdpTransport,
+    BuiltinTransport, Candidate, CaptureFormat, CommunityResponsePolicy, GetBulkBehavior,
+    PeerCredentials, RecordingStats, RecordingTransport, RecordingTransportBuilder,
+    ReplayTransport, ReplayTransportBuilder, RequestRegistration, ResponseIdentity,
+    ShardedUdpControl, ShardedUdpTransport, ShardedUdpTransportBuilder, SocketOptions,
+    TcpTransport, Transport, UdpBatching, UdpControl, UdpHandle, UdpStats, UdpTransport,
 };
+#[cfg(feature = "dtls")]
+pub use transport::{DtlsTransport, DtlsTransportBuilder};
+#[cfg(feature = "tls")]
+pub use transport::{TlsTransport, TlsTransportBuilder};
+#[cfg(unix)]
+pub use transport::{UNIX_SOCKET_ADDR, UnixSocketKind, UnixTransport, UnixTransportBuilder};
 pub use v3::{
     AuthProtocol, AuthoritativeEngine, AuthoritativeEnginePersistenceError,
     AuthoritativeEnginePersistenceOperation, CryptoBackend, CryptoError, CryptoResult,
     DesSaltPersistenceError, DesSaltPersistenceOperation, DesSaltState, DesSaltStateError,
-    DiscoveredEngine, EngineCache, ParseProtocolError, PersistedAuthoritativeEngine,
-    PersistedDesSaltState, PrivProtocol, UsmConfig, UsmUser, generate_engine_id,
+    DiscoveredEngine, EngineCache, EngineId, EngineIdFormat, ParseProtocolError,
+    PersistedAuthoritativeEngine, PersistedDesSaltState, PrivProtocol, TransportSecurity,
+    TsmConfig, UsmConfig, UsmUser, generate_engine_id,
 };
 #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
 pub use v3::{LocalizedKey, MasterKey, MasterKeys};
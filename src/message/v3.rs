@@ -33,9 +33,12 @@ use crate::pdu::Pdu;
 /// `SNMPv3` security model identifiers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(i32)]
+#[non_exhaustive]
 pub enum V3SecurityModel {
     /// User-based Security Model (RFC 3414)
     Usm = 3,
+    /// Transport Security Model (RFC 5591)
+    Tsm = 4,
 }
 
 impl V3SecurityModel {
@@ -44,6 +47,7 @@ impl V3SecurityModel {
     pub fn from_i32(value: i32) -> Option<Self> {
         match value {
             3 => Some(Self::Usm),
+            4 => Some(Self::Tsm),
             _ => None,
         }
     }
@@ -173,10 +177,14 @@ pub struct MsgGlobalData {
     pub(crate) msg_max_size: MessageSize,
     /// Message flags (security level + reportable)
     pub(crate) msg_flags: MsgFlags,
-    /// Security model (always USM=3 for our implementation)
+    /// Security model (USM=3 or TSM=4)
     pub(crate) msg_security_model: V3SecurityModel,
 }
 
+/// Encoded msgSecurityParameters contents for TSM: a zero-length
+/// `tsmSecurityParameters` OCTET STRING (RFC 5591 Section 4.2.1).
+pub(crate) const TSM_SECURITY_PARAMETERS: [u8; 2] = [0x04, 0x00];
+
 impl MsgGlobalData {
     /// Create validated global data.
     pub fn new(msg_id: i32, msg_max_size: MessageSize, msg_flags: MsgFlags) -> Result<Self> {
@@ -191,6 +199,13 @@ impl MsgGlobalData {
         })
     }
 
+    /// Return this header with a different msgSecurityModel.
+    #[must_use]
+    pub fn with_security_model(mut self, model: V3SecurityModel) -> Self {
+        self.msg_security_model = model;
+        self
+    }
+
     /// Return the message identifier.
     #[must_use]
     pub fn msg_id(&self) -> i32 {
@@ -216,7 +231,7 @@ impl MsgGlobalData {
     }
 
     fn validate(&self) -> Result<()> {
-        if self.msg_id < 0 || self.msg_security_model != V3SecurityModel::Usm {
+        if self.msg_id < 0 {
             return Err(Error::Config("invalid SNMPv3 global data".into()).boxed());
         }
         Ok(())
@@ -240,7 +255,7 @@ impl MsgGlobalData {
     /// Validates that:
     /// - `msgID` is in range 0..2147483647 (RFC 3412 `HeaderData`)
     /// - `msgMaxSize` is in range 484..2147483647 (RFC 3412 `HeaderData`)
-    /// - `msgSecurityModel` identifies USM (3)
+    /// - `msgSecurityModel` identifies USM (3) or TSM (4)
     pub fn decode(decoder: &mut Decoder) -> Result<Self> {
         let mut seq = decoder.read_sequence()?;
 
@@ -456,7 +471,7 @@ pub(crate) fn combine_staged_v3_anomalies(
 pub struct V3Message {
     /// Global data (header)
     pub(crate) global_data: MsgGlobalData,
-    /// Security parameters (opaque, USM-encoded)
+    /// Security parameters (opaque, encoded by the security model)
     pub(crate) security_params: Bytes,
     /// Message data - either plaintext `ScopedPdu` or encrypted bytes
     pub(crate) data: V3MessageData,
@@ -493,6 +508,20 @@ impl V3Message {
         Ok(value)
     }
 
+    /// Create a Transport Security Model (RFC 5591) message.
+    ///
+    /// The header's security model is set to TSM and msgSecurityParameters to
+    /// the empty `tsmSecurityParameters`. TSM relies on the secure transport
+    /// for protection, so the scoped PDU stays in plaintext even when the
+    /// flags request privacy.
+    pub fn tsm(global_data: MsgGlobalData, scoped_pdu: ScopedPdu) -> Result<Self> {
+        Self::new(
+            global_data.with_security_model(V3SecurityModel::Tsm),
+            Bytes::from_static(&TSM_SECURITY_PARAMETERS),
+            scoped_pdu,
+        )
+    }
+
     /// Create an SNMPv3 message from opaque encrypted scoped-PDU bytes.
     ///
     /// This is a trusted/raw escape hatch for callers that already encrypted a
@@ -538,6 +567,9 @@ impl V3Message {
     // interoperability.
     fn validate_inbound_envelope(&self) -> Result<()> {
         self.global_data.validate()?;
+        if self.global_data.msg_security_model == V3SecurityModel::Tsm {
+            return self.validate_tsm_envelope();
+        }
         let level = self.global_data.msg_flags.security_level;
         if level.requires_priv() != matches!(self.data, V3MessageData::Encrypted(_)) {
             return Err(Error::InvalidMessage(
@@ -552,6 +584,21 @@ impl V3Message {
         self.validate_decoded_inbound_envelope(&usm.value)
     }
 
+    fn validate_tsm_envelope(&self) -> Result<()> {
+        if self.security_params.as_ref() != TSM_SECURITY_PARAMETERS {
+            return Err(Error::InvalidMessage(
+                "TSM msgSecurityParameters must be an empty OCTET STRING".into(),
+            )
+            .boxed());
+        }
+        if matches!(self.data, V3MessageData::Encrypted(_)) {
+            return Err(
+                Error::InvalidMessage("TSM msgData must be a plaintext ScopedPDU".into()).boxed(),
+            );
+        }
+        Ok(())
+    }
+
     fn validate_decoded_inbound_envelope(&self, usm: &crate::v3::UsmSecurityParams) -> Result<()> {
         usm.validate_for_security_level(self.global_data.msg_flags.security_level)
             .map_err(|error| Error::InvalidMessage(error.to_string().into()).boxed())
@@ -667,6 +714,18 @@ impl V3Message {
 
         // msgSecurityParameters (OCTET STRING containing USM params)
         let security_params = seq.read_octet_string()?;
+        if global_data.msg_security_model == V3SecurityModel::Tsm {
+            // TSM leaves protection to the transport: msgData is always a
+            // plaintext ScopedPDU, whatever the flags say.
+            let scoped_pdu = ScopedPdu::decode(seq)?;
+            let value = Self {
+                global_data,
+                security_params,
+                data: V3MessageData::Plaintext(scoped_pdu),
+            };
+            value.validate_tsm_envelope()?;
+            return Ok(value);
+        }
         let security_params_offset = seq.offset().saturating_sub(security_params.len());
         let mut security_decoder =
             seq.decoder_for_same_origin(security_params.clone(), security_params_offset);
@@ -800,6 +859,12 @@ impl RawV3Message {
         }
 
         let global_data = MsgGlobalData::decode(&mut seq)?;
+        // Staged processing below is USM's; no other model is dispatched here.
+        if global_data.msg_security_model != V3SecurityModel::Usm {
+            let model = global_data.msg_security_model.as_i32();
+            tracing::debug!(target: "async_snmp::v3", { offset = seq.offset(), model, kind = %DecodeErrorKind::UnknownSecurityModel(model) }, "decode error");
+            return Err(seq.malformed(DecodeErrorKind::UnknownSecurityModel(model)));
+        }
         let security_params = seq.read_octet_string()?;
         let security_params_offset = seq.offset().saturating_sub(security_params.len());
 
@@ -897,7 +962,9 @@ pub(crate) fn classify_mpd_failure(data: Bytes) -> Option<MpdFailure> {
         return Some(MpdFailure::InvalidMsgFlags);
     }
     let model = global.read_bounded_integer(1, i32::MAX).ok()?;
-    if V3SecurityModel::from_i32(model).is_none() {
+    // Staged processing only dispatches USM, so a recognised but unserved
+    // model (TSM) is counted like an unknown one.
+    if V3SecurityModel::from_i32(model) != Some(V3SecurityModel::Usm) {
         return Some(MpdFailure::UnknownSecurityModel);
     }
     None
@@ -1776,6 +1843,61 @@ mod tests {
         assert!(crate::message::Message::decode(with_root_trailing, DecodeConfig::STRICT).is_err());
     }
 
+    #[test]
+    fn tsm_message_keeps_plaintext_under_privacy_flags() {
+        let global = MsgGlobalData::new(
+            7,
+            crate::MessageSize::new(65507).unwrap(),
+            MsgFlags::new(SecurityLevel::AuthPriv, true),
+        )
+        .unwrap();
+        let scoped = ScopedPdu::new(
+            Bytes::from_static(&[0x80, 0x00, 0x00, 0x00, 0x06]),
+            Bytes::new(),
+            Pdu::get_request(7, &[crate::oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)]),
+        );
+        let message = V3Message::tsm(global, scoped.clone()).unwrap();
+        assert_eq!(
+            message.global_data().msg_security_model(),
+            V3SecurityModel::Tsm
+        );
+        assert_eq!(message.security_params().as_ref(), TSM_SECURITY_PARAMETERS);
+
+        let encoded = message.encode().unwrap();
+        let decoded = V3Message::decode(encoded.clone(), DecodeConfig::STRICT)
+            .unwrap()
+            .value;
+        assert_eq!(decoded, message);
+        assert_eq!(decoded.scoped_pdu(), Some(&scoped));
+        assert_eq!(decoded.security_level(), SecurityLevel::AuthPriv);
+
+        // The staged USM path does not serve TSM and counts it as unknown.
+        assert!(matches!(
+            &*RawV3Message::decode(encoded.clone(), DecodeConfig::STRICT).unwrap_err(),
+            Error::Decode(error) if matches!(error.kind, DecodeErrorKind::UnknownSecurityModel(4))
+        ));
+        assert_eq!(
+            classify_mpd_failure(encoded),
+            Some(MpdFailure::UnknownSecurityModel)
+        );
+    }
+
+    #[test]
+    fn tsm_message_rejects_usm_security_parameters() {
+        let global = MsgGlobalData::new(
+            7,
+            crate::MessageSize::new(65507).unwrap(),
+            MsgFlags::new(SecurityLevel::NoAuthNoPriv, true),
+        )
+        .unwrap()
+        .with_security_model(V3SecurityModel::Tsm);
+        let scoped = ScopedPdu::with_empty_context(Pdu::get_request(7, &[]));
+        assert!(matches!(
+            *V3Message::new(global, no_auth_security_params(), scoped).unwrap_err(),
+            Error::InvalidMessage(_)
+        ));
+    }
+
     #[test]
     fn test_msg_global_data_roundtrip() {
         let global = MsgGlobalData::new(
@@ -1931,16 +2053,17 @@ mod tests {
     }
 
     #[test]
-    fn v3_security_model_accepts_only_usm() {
+    fn v3_security_model_accepts_usm_and_tsm() {
         assert_eq!(V3SecurityModel::from_i32(3), Some(V3SecurityModel::Usm));
-        for model in [i32::MIN, -1, 0, 1, 2, 4, 99, i32::MAX] {
+        assert_eq!(V3SecurityModel::from_i32(4), Some(V3SecurityModel::Tsm));
+        for model in [i32::MIN, -1, 0, 1, 2, 5, 99, i32::MAX] {
             assert_eq!(V3SecurityModel::from_i32(model), None);
         }
     }
 
     #[test]
-    fn msg_global_data_rejects_non_usm_security_models() {
-        for model in [-1, 0, 1, 2, 4, 99, i32::MAX] {
+    fn msg_global_data_rejects_unknown_security_models() {
+        for model in [-1, 0, 1, 2, 5, 99, i32::MAX] {
             let mut buf = EncodeBuf::new();
             buf.push_sequence(|buf| {
                 buf.push_integer(model);
@@ -7,11 +7,10 @@ use std::net::SocketAddr;
 
 use bytes::Bytes;
 
-use crate::udp_responder::DestinationMetadata;
-
 use crate::error::{Error, Result};
 use crate::message::{CommunityMessage, MsgGlobalData};
 use crate::pdu::{Pdu, PduType};
+use crate::security_event::SecurityEventKind;
 use crate::v3::UsmSecurityParams;
 use crate::v3::encode::encode_v3_response;
 use crate::v3::process::{UsmFailure, V3Inbound, V3LocalContext, V3Role, process_v3_inbound};
@@ -19,7 +18,7 @@ use crate::v3::process::{UsmFailure, V3Inbound, V3LocalContext, V3Role, process_
 use super::varbind::extract_notification_varbinds;
 use super::{
     InformAckOutcome, Notification, NotificationPduClass, NotificationWireIdentity,
-    ReceivedNotification, ReceiverInner, V3NotificationWireIdentity,
+    ReceivedNotification, ReceiverInner, ResponseRoute, V3NotificationWireIdentity,
 };
 use crate::v3::DerivedKeys;
 
@@ -35,12 +34,13 @@ impl super::NotificationReceiver {
         let decode_anomalies = decoded.anomalies;
         let msg = decoded.value;
 
-        if !crate::util::community_matches(
-            &self.inner.communities,
-            msg.community().as_bytes(),
-            crate::util::EmptyCommunityPolicy::Allow,
-        ) {
+        if !self.inner.accepts_community(msg.community().as_bytes()) {
             tracing::debug!(target: "async_snmp::notification", { snmp.source = %source }, "dropped v1 notification with unaccepted community");
+            self.inner.security_events.community(
+                SecurityEventKind::BadCommunity,
+                source,
+                msg.community().as_bytes(),
+            );
             return Ok(None);
         }
 
@@ -77,26 +77,28 @@ impl super::NotificationReceiver {
         data: Bytes,
         source: SocketAddr,
     ) -> Result<Option<ReceivedNotification>> {
-        self.handle_v2c_at(data, source, None).await
+        self.handle_v2c_at(data, source, &ResponseRoute::Udp(None))
+            .await
     }
 
     pub(super) async fn handle_v2c_at(
         &self,
         data: Bytes,
         source: SocketAddr,
-        response_source: Option<DestinationMetadata>,
+        response_route: &ResponseRoute,
     ) -> Result<Option<ReceivedNotification>> {
         let decoded =
             CommunityMessage::decode_with_target(data, Some(source), self.inner.decode_config)?;
         let decode_anomalies = decoded.anomalies;
         let msg = decoded.value;
 
-        if !crate::util::community_matches(
-            &self.inner.communities,
-            msg.community().as_bytes(),
-            crate::util::EmptyCommunityPolicy::Allow,
-        ) {
+        if !self.inner.accepts_community(msg.community().as_bytes()) {
             tracing::debug!(target: "async_snmp::notification", { snmp.source = %source }, "dropped v2c notification with unaccepted community");
+            self.inner.security_events.community(
+                SecurityEventKind::BadCommunity,
+                source,
+                msg.community().as_bytes(),
+            );
             return Ok(None);
         }
 
@@ -184,7 +186,7 @@ impl super::NotificationReceiver {
                             .into_bytes()
                             .expect("non-dropped response contains bytes");
                         match self
-                            .send_response(&response_bytes, source, response_source)
+                            .send_response(&response_bytes, source, response_route)
                             .await
                         {
                             Ok(()) => {
@@ -231,14 +233,15 @@ impl super::NotificationReceiver {
         data: Bytes,
         source: SocketAddr,
     ) -> Result<Option<ReceivedNotification>> {
-        self.handle_v3_at(data, source, None).await
+        self.handle_v3_at(data, source, &ResponseRoute::Udp(None))
+            .await
     }
 
     pub(super) async fn handle_v3_at(
         &self,
         data: Bytes,
         source: SocketAddr,
-        response_source: Option<DestinationMetadata>,
+        response_route: &ResponseRoute,
     ) -> Result<Option<ReceivedNotification>> {
         let (our_boots, our_time) = super::unpack_boots_time(
             self.inner
@@ -246,6 +249,11 @@ impl super::NotificationReceiver {
                 .load(std::sync::atomic::Ordering::Relaxed),
         );
         let sample_authoritative_time = || self.inner.authoritative_boots_time();
+        let credentials = self
+            .inner
+            .credentials
+            .as_ref()
+            .map(crate::credentials::ActiveCredentials::snapshot);
         let usm_ctx = V3LocalContext {
             engine_id: &self.inner.engine_id,
             engine_boots: our_boots,
@@ -256,12 +264,15 @@ impl super::NotificationReceiver {
             decode_config: self.inner.decode_config,
             outbound_limit: self.inner.max_message_size,
             usm_users: &self.inner.usm_users,
+            rotating_users: credentials.as_ref(),
             stats: &self.inner.usm_stats,
             mpd: None,
+            security_events: Some(&self.inner.security_events),
             source,
         };
         let role = V3Role::Receiver {
             remote_engines: &self.inner.remote_engines,
+            remote_users: &self.inner.remote_users,
         };
 
         let inbound = match process_v3_inbound(data, &usm_ctx, &role)? {
@@ -271,7 +282,7 @@ impl super::NotificationReceiver {
                 // is diagnosable at the default log level.
                 tracing::warn!(target: "async_snmp::notification", { snmp.source = %source, snmp.failure = ?failure }, "USM processing failed for inbound message");
                 if let Some(report) = report {
-                    if let Err(e) = self.send_response(&report, source, response_source).await {
+                    if let Err(e) = self.send_response(&report, source, response_route).await {
                         tracing::debug!(target: "async_snmp::notification", { snmp.source = %source, error = %e }, "failed to send USM report");
                     } else {
                         tracing::debug!(target: "async_snmp::notification", { snmp.source = %source }, "sent USM report");
@@ -415,7 +426,7 @@ impl super::NotificationReceiver {
                             .into_bytes()
                             .expect("non-dropped response contains bytes");
                         match self
-                            .send_response(&response_bytes, source, response_source)
+                            .send_response(&response_bytes, source, response_route)
                             .await
                         {
                             Ok(()) => {
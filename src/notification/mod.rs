@@ -146,6 +146,14 @@
 //! incoming tuple. Configuring any USM user therefore requires a persisted
 //! [`AuthoritativeEngine`].
 //!
+//! Because each trap sender localizes keys to its own engine ID, one username
+//! can have different keys on each device.
+//! [`NotificationReceiverBuilder::engine_usm_user`] registers a user for a
+//! single remote engine ID; other engines fall back to the same-named
+//! [`usm_user`](NotificationReceiverBuilder::usm_user), whose keys are
+//! localized on demand and cached up to
+//! [`engine_key_cache_capacity`](NotificationReceiverBuilder::engine_key_cache_capacity).
+//!
 //! # Notification varbind validation
 //!
 //! SNMPv2c and SNMPv3 TrapV2 and Inform PDUs start with an uptime value and a
@@ -197,24 +205,32 @@ use std::time::{Duration, Instant};
 
 use crate::Community;
 use bytes::Bytes;
-use tokio::net::UdpSocket;
 use tokio::sync::Mutex as AsyncMutex;
 use tracing::instrument;
 
+use crate::credentials::{
+    ActiveCredentials, CredentialProvider, CredentialRefresh, CredentialRules, CredentialSource,
+    RotatingCredentials,
+};
 use crate::error::{Error, Result};
 use crate::message::SecurityLevel;
 use crate::oid::Oid;
 use crate::pdu::TrapV1Pdu;
+use crate::runtime::UdpSocket;
+use crate::security_event::{SecurityEventHub, SecurityEventReceiver};
 use crate::transport::udp_error::{UdpRecvErrorClass, classify_udp_recv_error};
-use crate::udp_responder::{ReceivedDatagram, UdpResponder};
+#[cfg(unix)]
+use crate::transport::{UNIX_SOCKET_ADDR, UnixInbound, UnixReply, UnixSocketKind};
+use crate::udp_responder::{DestinationMetadata, ReceivedDatagram, UdpResponder};
 use crate::util::{PreparedAuthoritativeUsm, bind_udp_socket, validate_authoritative_usm};
 use crate::v3::process::RemoteEngineTable;
 use crate::v3::process::UsmStats;
+use crate::v3::remote_users::RemoteUsmUsers;
 use crate::v3::{AuthoritativeEngine, DesSaltState, PrivProtocol, SaltCounter};
 use crate::varbind::VarBind;
 use crate::version::Version;
 
-use crate::v3::UsmUser;
+use crate::v3::{DerivedKeys, UsmUser};
 pub use varbind::{NotificationVarbindValidation, validate_notification_varbinds};
 
 /// Maximum number of distinct remote authoritative engines whose timeliness
@@ -224,6 +240,9 @@ pub use varbind::{NotificationVarbindValidation, validate_notification_varbinds}
 /// least-recently-updated engine is evicted when full.
 const MAX_REMOTE_ENGINES: usize = 8192;
 
+/// Default bound on keys localized on demand for remote engines.
+const DEFAULT_ENGINE_KEY_CACHE_CAPACITY: usize = 1024;
+
 /// Notification PDU class presented to an acceptance policy.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum NotificationPduClass {
@@ -626,6 +645,12 @@ pub mod oids {
         oid!(1, 3, 6, 1, 6, 3, 18, 1, 3, 0)
     }
 
+    /// snmpTrapCommunity.0 - community of a proxied v1 trap (RFC 3584 Section 3)
+    #[must_use]
+    pub fn snmp_trap_community() -> crate::Oid {
+        oid!(1, 3, 6, 1, 6, 3, 18, 1, 4, 0)
+    }
+
     /// Standard trap OID prefix (snmpTraps)
     #[must_use]
     pub fn snmp_traps() -> crate::Oid {
@@ -681,7 +706,10 @@ pub mod oids {
 pub struct NotificationReceiverBuilder {
     bind_addr: String,
     usm_users: HashMap<Bytes, UsmUser>,
+    engine_usm_users: HashMap<(Bytes, Bytes), UsmUser>,
+    engine_key_cache_capacity: usize,
     communities: Vec<Community>,
+    credentials: Option<CredentialSource>,
     authoritative_engine: Option<AuthoritativeEngine>,
     des_salt_state: Option<DesSaltState>,
     varbind_validation: NotificationVarbindValidation,
@@ -689,6 +717,8 @@ pub struct NotificationReceiverBuilder {
     decode_config: crate::DecodeConfig,
     acceptance_policy: Option<Arc<dyn NotificationAcceptancePolicy>>,
     response_send_timeout: Duration,
+    #[cfg(unix)]
+    unix_listeners: Vec<(std::path::PathBuf, UnixSocketKind)>,
 }
 
 impl NotificationReceiverBuilder {
@@ -697,6 +727,7 @@ impl NotificationReceiverBuilder {
     /// Defaults:
     /// - Bind address: `0.0.0.0:162` (UDP, standard SNMP trap port)
     /// - No USM users (v3 notifications rejected until users are added)
+    /// - Up to 1024 cached key localizations for remote engines
     /// - No authoritative engine (required when adding a USM user)
     /// - Tolerant notification varbind validation
     /// - No explicit acceptance policy (required when adding a USM user)
@@ -705,7 +736,10 @@ impl NotificationReceiverBuilder {
         Self {
             bind_addr: "0.0.0.0:162".to_string(),
             usm_users: HashMap::new(),
+            engine_usm_users: HashMap::new(),
+            engine_key_cache_capacity: DEFAULT_ENGINE_KEY_CACHE_CAPACITY,
             communities: Vec::new(),
+            credentials: None,
             authoritative_engine: None,
             des_salt_state: None,
             varbind_validation: NotificationVarbindValidation::Tolerant,
@@ -713,6 +747,8 @@ impl NotificationReceiverBuilder {
             decode_config: crate::DecodeConfig::default(),
             acceptance_policy: None,
             response_send_timeout: crate::client::DEFAULT_SEND_TIMEOUT,
+            #[cfg(unix)]
+            unix_listeners: Vec::new(),
         }
     }
 
@@ -725,6 +761,25 @@ impl NotificationReceiverBuilder {
         self
     }
 
+    /// Also receive notifications on a Unix domain socket at `path`.
+    ///
+    /// The UDP socket is still bound. Notifications received on a Unix socket
+    /// report [`UNIX_SOCKET_ADDR`] as their source; Inform responses go back
+    /// over the same connection, or to the sender's bound path for datagram
+    /// sockets. The socket file is created by [`build`](Self::build), which
+    /// fails if `path` already exists, and removed when the receiver is
+    /// dropped.
+    #[cfg(unix)]
+    #[must_use]
+    pub fn listen_unix(
+        mut self,
+        path: impl Into<std::path::PathBuf>,
+        kind: UnixSocketKind,
+    ) -> Self {
+        self.unix_listeners.push((path.into(), kind));
+        self
+    }
+
     /// Set validation for the standard SNMPv2c/v3 notification varbind prefix.
     ///
     /// The default [`NotificationVarbindValidation::Tolerant`] mode accepts
@@ -882,6 +937,75 @@ impl NotificationReceiverBuilder {
         Ok(self)
     }
 
+    /// Add a USM user whose keys are localized to one remote engine.
+    ///
+    /// Trap senders are authoritative, so the same username can have
+    /// different keys on each device. A user added here authenticates only
+    /// traps sent under `engine_id`, and takes precedence over a
+    /// [`usm_user`](Self::usm_user) of the same name, whose password is then
+    /// localized on demand for every other engine. This matches net-snmp's
+    /// `createUser -e`.
+    ///
+    /// Informs are always localized to this receiver's own engine ID, which
+    /// therefore cannot be used here. Users can also be added and removed
+    /// at runtime with [`NotificationReceiver::add_engine_usm_user`] and
+    /// [`NotificationReceiver::remove_engine_usm_user`]. The engine ID and
+    /// keys are validated by [`build`](Self::build).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use async_snmp::notification::NotificationReceiver;
+    /// use async_snmp::{AuthProtocol, AuthoritativeEngine};
+    /// use std::convert::Infallible;
+    ///
+    /// # async fn example() -> Result<(), Box<async_snmp::Error>> {
+    /// # let engine = AuthoritativeEngine::install(b"receiver-engine".to_vec(), |_| {
+    /// #     Ok::<(), Infallible>(())
+    /// # })?;
+    /// let router_engine_id = async_snmp::format::hex::decode("800000090300000c025808").unwrap();
+    /// let receiver = NotificationReceiver::builder()
+    ///     .authoritative_engine(engine)
+    ///     .engine_usm_user(router_engine_id, "trapuser", |u| {
+    ///         u.auth(AuthProtocol::Sha256, b"router-specific-pass")
+    ///     }).unwrap()
+    ///     .usm_user("trapuser", |u| u.auth(AuthProtocol::Sha256, b"fleet-wide-pass"))
+    ///     .unwrap()
+    ///     .accept_all_notifications()
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn engine_usm_user<F>(
+        mut self,
+        engine_id: impl Into<Bytes>,
+        username: impl Into<Bytes>,
+        configure: F,
+    ) -> crate::CryptoResult<Self>
+    where
+        F: FnOnce(UsmUser) -> crate::CryptoResult<UsmUser>,
+    {
+        let username_bytes: Bytes = username.into();
+        let config = configure(UsmUser::new(username_bytes.clone()))?;
+        self.engine_usm_users
+            .insert((engine_id.into(), username_bytes), config);
+        Ok(self)
+    }
+
+    /// Bound the cache of keys localized on demand for remote engines.
+    ///
+    /// Traps authenticated with a [`usm_user`](Self::usm_user) under a remote
+    /// engine ID cache the localized keys for that engine. When the cache is
+    /// full the least recently used localization is evicted and recomputed
+    /// if that engine sends again. The default is 1024 entries; zero is
+    /// rejected by [`build`](Self::build).
+    #[must_use]
+    pub fn engine_key_cache_capacity(mut self, capacity: usize) -> Self {
+        self.engine_key_cache_capacity = capacity;
+        self
+    }
+
     /// Restrict accepted v1/v2c notifications to the given community string.
     ///
     /// Community filtering is opt-in. With no community configured the
@@ -918,6 +1042,28 @@ impl NotificationReceiverBuilder {
         self
     }
 
+    /// Accept communities and USM users from a rotating credential provider.
+    ///
+    /// The provider is fetched once by [`build`](Self::build), which fails if
+    /// the fetch does, and then every [`CredentialRefresh::interval`]. After
+    /// a rotation the previous credentials stay accepted for
+    /// [`CredentialRefresh::overlap`]. Provided communities join those
+    /// configured with [`community`](Self::community) in the allowlist.
+    /// Provided USM users require an
+    /// [`acceptance_policy`](Self::acceptance_policy) and an
+    /// [`authoritative_engine`](Self::authoritative_engine); a rotation
+    /// without them, or with DES/3DES users but no
+    /// [`des_salt_state`](Self::des_salt_state), is rejected and logged.
+    #[must_use]
+    pub fn credential_provider(
+        mut self,
+        provider: impl CredentialProvider,
+        refresh: CredentialRefresh,
+    ) -> Self {
+        self.credentials = Some(CredentialSource::new(provider, refresh));
+        self
+    }
+
     /// Restrict accepted v1/v2c notifications to any of the given communities.
     ///
     /// Convenience for calling [`Self::community`] once per entry. See that
@@ -1009,7 +1155,8 @@ impl NotificationReceiverBuilder {
         self,
         generate_engine_id: impl FnOnce() -> Result<Bytes>,
     ) -> Result<NotificationReceiver> {
-        if !self.usm_users.is_empty() && self.acceptance_policy.is_none() {
+        let has_usm_users = !self.usm_users.is_empty() || !self.engine_usm_users.is_empty();
+        if has_usm_users && self.acceptance_policy.is_none() {
             return Err(Error::Config(
                 "a notification receiver with USM users requires an acceptance policy".into(),
             )
@@ -1029,6 +1176,12 @@ impl NotificationReceiverBuilder {
             self.response_send_timeout,
             "notification response send timeout",
         )?;
+        if self.engine_key_cache_capacity == 0 {
+            return Err(Error::Config("engine_key_cache_capacity must be non-zero".into()).boxed());
+        }
+        if let Some(credentials) = &self.credentials {
+            credentials.validate()?;
+        }
 
         let uses_des = self.usm_users.values().any(|security| {
             security
@@ -1055,14 +1208,30 @@ impl NotificationReceiverBuilder {
             )
             .boxed());
         }
-        let requires_authoritative_engine = !self.usm_users.is_empty();
         let validated_usm = validate_authoritative_usm(
             self.usm_users,
             self.authoritative_engine,
-            requires_authoritative_engine,
+            has_usm_users,
             "invalid USM user configuration",
             "authoritative engine state is required for SNMPv3 notification receiving",
         )?;
+        let credential_rules = CredentialRules {
+            usm: self.acceptance_policy.is_some() && validated_usm.authoritative_engine().is_some(),
+            des: self.des_salt_state.is_some(),
+        };
+        let mut remote_users = RemoteUsmUsers::new(self.engine_key_cache_capacity);
+        if !self.engine_usm_users.is_empty() {
+            let local_engine_id = validated_usm
+                .authoritative_engine()
+                .expect("engine-scoped users require an authoritative engine")
+                .engine_id();
+            let mut users: Vec<_> = self.engine_usm_users.into_iter().collect();
+            users.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+            for ((engine_id, username), user) in users {
+                let keys = localize_engine_user(&engine_id, user, local_engine_id)?;
+                remote_users.insert_scoped(engine_id, username, keys);
+            }
+        }
 
         let bind_addr: SocketAddr = self.bind_addr.parse().map_err(|_| {
             Error::Config(format!("invalid bind address: {}", self.bind_addr).into())
@@ -1079,6 +1248,36 @@ impl NotificationReceiverBuilder {
             target: bind_addr,
             source: e,
         })?;
+        #[cfg(unix)]
+        let unix_ingress = if self.unix_listeners.is_empty() {
+            None
+        } else {
+            let (ingress, receiver) = tokio::sync::mpsc::channel(UNIX_INGRESS_CAPACITY);
+            // Listener tasks stop when the receiver, and so the channel, drops.
+            let cancel = tokio_util::sync::CancellationToken::new();
+            for (path, kind) in &self.unix_listeners {
+                crate::transport::spawn_unix_listener(
+                    path,
+                    *kind,
+                    crate::UDP_RECEIVE_BUFFER_SIZE,
+                    ingress.clone(),
+                    cancel.clone(),
+                )
+                .map_err(|source| Error::Network {
+                    target: UNIX_SOCKET_ADDR,
+                    source: std::io::Error::new(
+                        source.kind(),
+                        format!("{}: {source}", path.display()),
+                    ),
+                })?;
+            }
+            Some(AsyncMutex::new(receiver))
+        };
+
+        let credentials = match self.credentials {
+            Some(source) => Some(RotatingCredentials::load(source, credential_rules).await?),
+            None => None,
+        };
 
         let PreparedAuthoritativeUsm {
             users: usm_users,
@@ -1086,7 +1285,9 @@ impl NotificationReceiverBuilder {
             engine_id,
             engine_boots,
         } = validated_usm.prepare(generate_engine_id)?;
-        let salt_counter = uses_aes.then(SaltCounter::new).transpose()?;
+        let salt_counter = (uses_aes || credentials.is_some())
+            .then(SaltCounter::new)
+            .transpose()?;
         let udp_responder = UdpResponder::new(&socket);
 
         Ok(NotificationReceiver {
@@ -1097,6 +1298,9 @@ impl NotificationReceiverBuilder {
                 local_addr,
                 usm_users,
                 communities: self.communities,
+                credentials: credentials
+                    .map(|credentials| ActiveCredentials::start(credentials, false)),
+                security_events: SecurityEventHub::new(),
                 varbind_validation: self.varbind_validation,
                 engine_id,
                 salt_counter,
@@ -1106,6 +1310,7 @@ impl NotificationReceiverBuilder {
                 engine_start: Instant::now(),
                 usm_stats: UsmStats::default(),
                 remote_engines: Mutex::new(RemoteEngineTable::new(MAX_REMOTE_ENGINES)),
+                remote_users: Mutex::new(remote_users),
                 max_message_size: self.max_message_size,
                 decode_config: self.decode_config,
                 snmp_silent_drops: AtomicU32::new(0),
@@ -1122,6 +1327,8 @@ impl NotificationReceiverBuilder {
                 #[cfg(test)]
                 receive_errors: Mutex::new(std::collections::VecDeque::new()),
                 recv_gate: AsyncMutex::new(vec![0; crate::UDP_RECEIVE_BUFFER_SIZE]),
+                #[cfg(unix)]
+                unix_ingress,
             }),
         })
     }
@@ -1404,6 +1611,10 @@ struct ReceiverInner {
     /// (community filtering is opt-in); otherwise a v1/v2c notification whose
     /// community matches none of these is dropped.
     communities: Vec<Community>,
+    /// Rotating communities and USM users from a credential provider.
+    credentials: Option<ActiveCredentials>,
+    /// Audit subscribers for rejected notifications.
+    security_events: SecurityEventHub,
     /// Validation policy for SNMPv2c/v3 standard notification varbind prefixes.
     varbind_validation: NotificationVarbindValidation,
     /// Engine ID for V3 discovery responses
@@ -1427,6 +1638,9 @@ struct ReceiverInner {
     /// without limit by fabricating engine IDs. An exact ordered index keeps
     /// eviction bounded without retaining stale auxiliary records.
     remote_engines: Mutex<RemoteEngineTable>,
+    /// Users scoped to remote engine IDs and a bounded cache of keys
+    /// localized on demand for trap senders.
+    remote_users: Mutex<RemoteUsmUsers>,
     /// Local outbound response policy limit.
     max_message_size: usize,
     decode_config: crate::DecodeConfig,
@@ -1446,9 +1660,61 @@ struct ReceiverInner {
     receive_errors: Mutex<std::collections::VecDeque<std::io::Error>>,
     /// Fairly serializes cloned `recv` calls and retains their shared UDP buffer.
     recv_gate: AsyncMutex<Vec<u8>>,
+    /// Messages from Unix-domain listeners; locked only under `recv_gate`.
+    #[cfg(unix)]
+    unix_ingress: Option<AsyncMutex<tokio::sync::mpsc::Receiver<UnixInbound>>>,
+}
+
+/// Bound on messages queued between Unix-domain listeners and `recv`.
+#[cfg(unix)]
+const UNIX_INGRESS_CAPACITY: usize = 256;
+
+/// A message taken from one of the receiver's sockets.
+enum Inbound {
+    Udp(std::io::Result<ReceivedDatagram>),
+    #[cfg(unix)]
+    Unix(UnixInbound),
+}
+
+/// Where Inform responses and Reports for a received message are sent.
+pub(super) enum ResponseRoute {
+    /// Reply from the UDP socket, preserving the local destination when known.
+    Udp(Option<DestinationMetadata>),
+    #[cfg(unix)]
+    Unix(UnixReply),
+}
+
+#[cfg(unix)]
+async fn recv_unix(
+    ingress: &mut Option<tokio::sync::MutexGuard<'_, tokio::sync::mpsc::Receiver<UnixInbound>>>,
+) -> Option<Inbound> {
+    match ingress {
+        Some(receiver) => receiver.recv().await.map(Inbound::Unix),
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn recv_unix(_ingress: &mut Option<()>) -> Option<Inbound> {
+    std::future::pending().await
 }
 
 impl ReceiverInner {
+    /// Check a v1/v2c community against the configured and rotating
+    /// allowlists; with neither configured every community is accepted.
+    fn accepts_community(&self, community: &[u8]) -> bool {
+        let credentials = self.credentials.as_ref().map(ActiveCredentials::snapshot);
+        crate::util::community_matches(
+            self.communities.iter().chain(
+                credentials
+                    .iter()
+                    .flat_map(|snapshot| snapshot.communities()),
+            ),
+            community,
+            crate::util::EmptyCommunityPolicy::Allow,
+        )
+    }
+
     fn accepts(&self, source: SocketAddr, notification: &Notification) -> bool {
         let Some(policy) = &self.acceptance_policy else {
             return true;
@@ -1493,6 +1759,27 @@ impl ReceiverInner {
     }
 }
 
+/// Validate an engine-scoped user and localize its keys to `engine_id`.
+fn localize_engine_user(
+    engine_id: &[u8],
+    mut user: UsmUser,
+    local_engine_id: &[u8],
+) -> Result<DerivedKeys> {
+    let invalid = |error: &dyn std::fmt::Display| {
+        Error::Config(format!("invalid engine-scoped USM user: {error}").into()).boxed()
+    };
+    crate::v3::validate_engine_id(engine_id).map_err(|error| invalid(&error))?;
+    if engine_id == local_engine_id {
+        return Err(Error::Config(
+            "engine-scoped USM users must name a remote engine, not the receiver's own".into(),
+        )
+        .boxed());
+    }
+    user.validate_and_precompute()
+        .map_err(|error| invalid(&error))?;
+    user.derive_keys(engine_id).map_err(|error| invalid(&error))
+}
+
 const fn pack_boots_time((boots, time): (u32, u32)) -> u64 {
     (boots as u64) << 32 | time as u64
 }
@@ -1511,6 +1798,88 @@ impl NotificationReceiver {
         NotificationReceiverBuilder::new()
     }
 
+    /// Add or replace a USM user whose keys are localized to one remote
+    /// engine, returning whether an existing entry was replaced.
+    ///
+    /// See [`NotificationReceiverBuilder::engine_usm_user`]. The change
+    /// applies to every clone of this receiver from the next message on.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the receiver was built without an
+    /// acceptance policy, `engine_id` is invalid or is this receiver's own
+    /// engine ID, or the user's credentials are invalid.
+    pub fn add_engine_usm_user<F>(
+        &self,
+        engine_id: impl Into<Bytes>,
+        username: impl Into<Bytes>,
+        configure: F,
+    ) -> Result<bool>
+    where
+        F: FnOnce(UsmUser) -> crate::CryptoResult<UsmUser>,
+    {
+        if self.inner.acceptance_policy.is_none() {
+            return Err(Error::Config(
+                "a notification receiver with USM users requires an acceptance policy".into(),
+            )
+            .boxed());
+        }
+        let engine_id: Bytes = engine_id.into();
+        let username: Bytes = username.into();
+        let user = configure(UsmUser::new(username.clone())).map_err(|error| {
+            Error::Config(format!("invalid engine-scoped USM user: {error}").into()).boxed()
+        })?;
+        let keys = localize_engine_user(&engine_id, user, &self.inner.engine_id)?;
+        let replaced = self
+            .inner
+            .remote_users
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert_scoped(engine_id, username, keys);
+        Ok(replaced)
+    }
+
+    /// Remove a USM user added for one remote engine, returning whether it
+    /// existed.
+    ///
+    /// Later traps from that engine fall back to a same-named
+    /// [`usm_user`](NotificationReceiverBuilder::usm_user), if any.
+    pub fn remove_engine_usm_user(&self, engine_id: &[u8], username: &[u8]) -> bool {
+        self.inner
+            .remote_users
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove_scoped(engine_id, username)
+    }
+
+    /// Fetch from the credential provider now, returning whether a new
+    /// revision was installed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] when no provider is configured or the
+    /// fetched credentials are invalid, and the provider's error when the
+    /// fetch fails. The installed credentials stay in place on error.
+    pub async fn refresh_credentials(&self) -> Result<bool> {
+        let credentials =
+            self.inner.credentials.as_ref().ok_or_else(|| {
+                Error::Config("no credential provider is configured".into()).boxed()
+            })?;
+        credentials.refresh().await
+    }
+
+    /// Observe rejected notifications as structured [`SecurityEvent`](crate::SecurityEvent)s.
+    ///
+    /// Events cover unaccepted communities and USM failures other than
+    /// unknown engine IDs. Each subscriber gets its own queue of `capacity`
+    /// events (at least one); events arriving while it is full are dropped
+    /// and counted in [`SecurityEventReceiver::dropped`]. See
+    /// [`crate::security_event`].
+    #[must_use]
+    pub fn subscribe_security_events(&self, capacity: usize) -> SecurityEventReceiver {
+        self.inner.security_events.subscribe(capacity)
+    }
+
     /// Return the receiver's `snmpSilentDrops` value.
     ///
     /// This counter changes only when a confirmed Inform response is oversized
@@ -1710,9 +2079,45 @@ impl NotificationReceiver {
         // malformed/non-notification datagrams are skipped prevents a later
         // cloned receiver from overtaking the earlier call.
         let mut buf = self.inner.recv_gate.lock().await;
+        #[cfg(unix)]
+        let mut unix_ingress = match &self.inner.unix_ingress {
+            Some(ingress) => Some(ingress.lock().await),
+            None => None,
+        };
+        #[cfg(not(unix))]
+        let mut unix_ingress = None;
 
         loop {
-            let received = match self.recv_packet(buf.as_mut_slice()).await {
+            let inbound = tokio::select! {
+                biased;
+                result = self.recv_packet(buf.as_mut_slice()) => Inbound::Udp(result),
+                Some(inbound) = recv_unix(&mut unix_ingress) => inbound,
+            };
+            let received = match inbound {
+                #[cfg(unix)]
+                Inbound::Unix(inbound) => {
+                    match self
+                        .parse_and_respond(
+                            inbound.data,
+                            UNIX_SOCKET_ADDR,
+                            &ResponseRoute::Unix(inbound.reply),
+                        )
+                        .await
+                    {
+                        Ok(Some(notification)) => return Ok(notification),
+                        Ok(None) => {}
+                        Err(e) if e.kind() == crate::ErrorKind::AuthoritativeEnginePersistence => {
+                            return Err(e);
+                        }
+                        Err(e) => {
+                            tracing::warn!(target: "async_snmp::notification", { error = %e }, "failed to parse notification from Unix socket");
+                        }
+                    }
+                    continue;
+                }
+                Inbound::Udp(received) => received,
+            };
+            let received = match received {
                 Ok(received) => received,
                 Err(error) => match classify_udp_recv_error(&error) {
                     UdpRecvErrorClass::DatagramLocal => {
@@ -1744,7 +2149,7 @@ impl NotificationReceiver {
             let data = Bytes::copy_from_slice(&buf[..received.len]);
 
             match self
-                .parse_and_respond(data, source, received.destination)
+                .parse_and_respond(data, source, &ResponseRoute::Udp(received.destination))
                 .await
             {
                 Ok(Some(notification)) => return Ok(notification),
@@ -1770,12 +2175,12 @@ impl NotificationReceiver {
         &self,
         data: Bytes,
         source: SocketAddr,
-        response_source: Option<crate::udp_responder::DestinationMetadata>,
+        response_route: &ResponseRoute,
     ) -> Result<Option<ReceivedNotification>> {
         match crate::message::peek_version(data.clone(), source)? {
             Version::V1 => self.handle_v1(data, source).await,
-            Version::V2c => self.handle_v2c_at(data, source, response_source).await,
-            Version::V3 => self.handle_v3_at(data, source, response_source).await,
+            Version::V2c => self.handle_v2c_at(data, source, response_route).await,
+            Version::V3 => self.handle_v3_at(data, source, response_route).await,
         }
     }
 
@@ -1783,7 +2188,7 @@ impl NotificationReceiver {
         &self,
         data: &[u8],
         destination: SocketAddr,
-        source: Option<crate::udp_responder::DestinationMetadata>,
+        route: &ResponseRoute,
     ) -> std::io::Result<()> {
         let deadline = tokio::time::Instant::now()
             .checked_add(self.inner.response_send_timeout)
@@ -1801,7 +2206,7 @@ impl NotificationReceiver {
         let response_send_gate = self.inner.response_send_gate.lock().unwrap().clone();
         tokio::select! {
             biased;
-            () = tokio::time::sleep_until(deadline) => Err(timeout_error()),
+            () = crate::runtime::sleep_until(deadline) => Err(timeout_error()),
             result = async {
                 #[cfg(test)]
                 self.inner.response_sends_started.fetch_add(1, Ordering::Relaxed);
@@ -1813,7 +2218,13 @@ impl NotificationReceiver {
                 if let Some(gate) = &response_send_gate {
                     gate.acquire().await.expect("test send gate remains open").forget();
                 }
-                self.inner.udp_responder.send_to(&self.inner.socket, data, destination, source).await
+                match route {
+                    ResponseRoute::Udp(source) => {
+                        self.inner.udp_responder.send_to(&self.inner.socket, data, destination, *source).await
+                    }
+                    #[cfg(unix)]
+                    ResponseRoute::Unix(reply) => reply.send(data).await,
+                }
             } => result,
         }
     }
@@ -2962,6 +3373,160 @@ mod tests {
         );
     }
 
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    fn build_v3_trap_with_password(engine_id: &[u8], password: &[u8]) -> Bytes {
+        build_v3_notification(
+            crate::pdu::PduType::TrapV2,
+            engine_id,
+            3,
+            300,
+            b"trapuser",
+            Some((password, AuthProtocol::Sha1)),
+        )
+    }
+
+    /// An engine-scoped user authenticates traps only from its engine and
+    /// takes precedence over the same-named wildcard user there.
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    #[tokio::test]
+    async fn engine_scoped_user_overrides_wildcard_for_its_engine() {
+        let receiver = NotificationReceiver::builder()
+            .bind("127.0.0.1:0")
+            .engine_id(b"my-receiver-engine".to_vec())
+            .engine_boots(1)
+            .usm_user("trapuser", |u| {
+                u.auth(AuthProtocol::Sha1, b"authpass12345678")
+            })
+            .unwrap()
+            .engine_usm_user(b"scoped-sender".as_slice(), "trapuser", |u| {
+                u.auth(AuthProtocol::Sha1, b"scopedpass123456")
+            })
+            .unwrap()
+            .accept_all_notifications()
+            .build()
+            .await
+            .unwrap();
+        let source: SocketAddr = "127.0.0.1:9999".parse().unwrap();
+
+        let scoped = build_v3_trap_with_password(b"scoped-sender", b"scopedpass123456");
+        assert!(receiver.handle_v3(scoped, source).await.unwrap().is_some());
+        let wildcard = build_v3_trap_with_password(b"scoped-sender", b"authpass12345678");
+        assert!(receiver.handle_v3(wildcard, source).await.is_err());
+
+        let other = build_v3_trap_with_password(b"other-sender", b"authpass12345678");
+        assert!(receiver.handle_v3(other, source).await.unwrap().is_some());
+        let other = build_v3_trap_with_password(b"third-sender", b"scopedpass123456");
+        assert!(receiver.handle_v3(other, source).await.is_err());
+    }
+
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    #[tokio::test]
+    async fn engine_scoped_users_are_added_and_removed_at_runtime() {
+        let receiver = remote_trap_receiver().await;
+        let source: SocketAddr = "127.0.0.1:9999".parse().unwrap();
+        let trap = || build_v3_trap_with_password(b"scoped-sender", b"scopedpass123456");
+
+        assert!(receiver.handle_v3(trap(), source).await.is_err());
+        let replaced = receiver
+            .add_engine_usm_user(b"scoped-sender".as_slice(), "trapuser", |u| {
+                u.auth(AuthProtocol::Sha1, b"scopedpass123456")
+            })
+            .unwrap();
+        assert!(!replaced);
+        assert!(receiver.handle_v3(trap(), source).await.unwrap().is_some());
+
+        assert!(receiver.remove_engine_usm_user(b"scoped-sender", b"trapuser"));
+        assert!(!receiver.remove_engine_usm_user(b"scoped-sender", b"trapuser"));
+        assert!(receiver.handle_v3(trap(), source).await.is_err());
+        let wildcard = build_v3_trap_with_password(b"scoped-sender", b"authpass12345678");
+        assert!(
+            receiver
+                .handle_v3(wildcard, source)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        let local =
+            receiver.add_engine_usm_user(b"my-receiver-engine".as_slice(), "trapuser", |u| {
+                u.auth(AuthProtocol::Sha1, b"scopedpass123456")
+            });
+        assert!(local.is_err());
+        let invalid = receiver.add_engine_usm_user(b"abc".as_slice(), "trapuser", Ok);
+        assert!(invalid.is_err());
+
+        let community_only = NotificationReceiver::bind("127.0.0.1:0").await.unwrap();
+        let unguarded = community_only.add_engine_usm_user(b"scoped-sender".as_slice(), "u", Ok);
+        assert!(unguarded.is_err());
+    }
+
+    /// Keys localized on demand for remote engines are cached up to the
+    /// configured bound.
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    #[tokio::test]
+    async fn engine_key_cache_is_bounded() {
+        let receiver = NotificationReceiver::builder()
+            .bind("127.0.0.1:0")
+            .engine_id(b"my-receiver-engine".to_vec())
+            .engine_boots(1)
+            .usm_user("trapuser", |u| {
+                u.auth(AuthProtocol::Sha1, b"authpass12345678")
+            })
+            .unwrap()
+            .engine_key_cache_capacity(2)
+            .accept_all_notifications()
+            .build()
+            .await
+            .unwrap();
+        let source: SocketAddr = "127.0.0.1:9999".parse().unwrap();
+
+        for engine_id in [b"sender-engine-a", b"sender-engine-b", b"sender-engine-c"] {
+            let trap = build_v3_trap_with_password(engine_id, b"authpass12345678");
+            assert!(receiver.handle_v3(trap, source).await.unwrap().is_some());
+        }
+        // Unauthenticated traps never populate the cache.
+        let trap = build_noauth_v3_trap(b"sender-engine-d", b"trapuser");
+        assert!(receiver.handle_v3(trap, source).await.unwrap().is_some());
+
+        let users = receiver.inner.remote_users.lock().unwrap();
+        assert_eq!(users.cached_len(), 2);
+    }
+
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    #[tokio::test]
+    async fn invalid_engine_scoped_configuration_is_rejected() {
+        let builder = || {
+            NotificationReceiver::builder()
+                .bind("127.0.0.1:0")
+                .engine_id(b"my-receiver-engine".to_vec())
+                .accept_all_notifications()
+        };
+        let scoped = |engine_id: &'static [u8]| {
+            builder()
+                .engine_usm_user(engine_id, "trapuser", |u| {
+                    u.auth(AuthProtocol::Sha1, b"scopedpass123456")
+                })
+                .unwrap()
+        };
+
+        assert!(scoped(b"my-receiver-engine").build().await.is_err());
+        assert!(scoped(b"abc").build().await.is_err());
+        assert!(
+            builder()
+                .engine_key_cache_capacity(0)
+                .build()
+                .await
+                .is_err()
+        );
+        let without_policy = NotificationReceiver::builder()
+            .bind("127.0.0.1:0")
+            .engine_id(b"my-receiver-engine".to_vec())
+            .engine_usm_user(b"scoped-sender".as_slice(), "trapuser", Ok)
+            .unwrap();
+        assert!(without_policy.build().await.is_err());
+        assert!(scoped(b"scoped-sender").build().await.is_ok());
+    }
+
     /// The remote-engine table is bounded: once `MAX_REMOTE_ENGINES` entries
     /// exist, an authenticated trap under a new engine ID evicts an old entry
     /// rather than growing the map, so a credential holder cannot exhaust
@@ -4011,6 +4576,7 @@ mod tests {
             .await
             .unwrap();
         let source: SocketAddr = "127.0.0.1:9999".parse().unwrap();
+        let mut events = receiver.subscribe_security_events(4);
 
         let msg = build_v3_notification(
             crate::pdu::PduType::TrapV2,
@@ -4023,6 +4589,13 @@ mod tests {
         assert!(receiver.handle_v3(msg, source).await.is_err());
         assert_eq!(receiver.usm_wrong_digests(), 1);
         assert_eq!(policy_calls.load(Ordering::Relaxed), 0);
+        let event = events.try_recv().expect("wrong digest event");
+        assert_eq!(event.kind(), crate::SecurityEventKind::WrongDigest);
+        assert_eq!(event.source(), source);
+        assert_eq!(
+            event.principal(),
+            &crate::SecurityPrincipal::User(Bytes::from_static(b"trapuser"))
+        );
     }
 
     /// RFC 3414 Section 3.2 Step 4: an authenticated message for a user not
@@ -4895,12 +5468,19 @@ mod tests {
             .await
             .unwrap();
         let source: SocketAddr = "127.0.0.1:9999".parse().unwrap();
+        let mut events = receiver.subscribe_security_events(1);
 
         let result = receiver
             .handle_v2c(build_v2c_trap(b"private"), source)
             .await
             .unwrap();
         assert!(result.is_none());
+        let event = events.try_recv().expect("bad community event");
+        assert_eq!(event.kind(), crate::SecurityEventKind::BadCommunity);
+        assert!(matches!(
+            event.principal(),
+            crate::SecurityPrincipal::Community(_)
+        ));
     }
 
     #[tokio::test]
@@ -5213,6 +5793,50 @@ mod tests {
         );
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_datagram_inform_is_received_and_acked_to_sender_path() {
+        let dir = std::env::temp_dir();
+        let name = |role: &str| dir.join(format!("async-snmp-{role}-{}.sock", std::process::id()));
+        let (listen_path, sender_path) = (name("receiver"), name("inform-sender"));
+        let receiver = NotificationReceiver::builder()
+            .bind("127.0.0.1:0")
+            .listen_unix(&listen_path, UnixSocketKind::Datagram)
+            .community(b"public")
+            .build()
+            .await
+            .unwrap();
+
+        let sender = tokio::net::UnixDatagram::bind(&sender_path).unwrap();
+        sender
+            .send_to(&build_v2c_inform(b"public"), &listen_path)
+            .await
+            .unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(1), receiver.recv())
+            .await
+            .expect("inform over Unix datagram must be received")
+            .unwrap();
+        assert_eq!(received.source, UNIX_SOCKET_ADDR);
+        assert!(matches!(received.inform_ack, Some(InformAckOutcome::Sent)));
+
+        let mut buf = vec![0u8; 4096];
+        let len = tokio::time::timeout(Duration::from_secs(1), sender.recv(&mut buf))
+            .await
+            .expect("inform must be acknowledged to the sender's path")
+            .unwrap();
+        assert!(len > 0);
+
+        drop(receiver);
+        let _ = std::fs::remove_file(&sender_path);
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while listen_path.exists() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("receiver removes its Unix socket when dropped");
+    }
+
     #[tokio::test]
     async fn each_accepted_inform_datagram_is_returned_exactly_once() {
         let receiver = NotificationReceiver::bind("127.0.0.1:0").await.unwrap();
@@ -2022,7 +2022,8 @@ impl TrapV1Pdu {
     /// sysUpTime.0 and snmpTrapOID.0 prefix followed by the original varbinds.
     /// Per RFC 3584 Section 3.1(4), the additional proxy varbinds
     /// (snmpTrapAddress.0, snmpTrapCommunity.0, snmpTrapEnterprise.0) are only
-    /// appended when a proxy forwards a received trap.
+    /// appended when a proxy forwards a received trap; use
+    /// [`Self::to_v2_proxy_pdu`] for that case.
     ///
     /// The `request_id` is set to 0. Assign an application-specific request ID
     /// before sending the PDU.
@@ -2043,6 +2044,48 @@ impl TrapV1Pdu {
         )
     }
 
+    /// Convert a received trap to a v2 notification PDU for forwarding
+    /// (RFC 3584 Section 3.1(4)).
+    ///
+    /// Performs the [`Self::to_v2_pdu`] conversion, then appends
+    /// snmpTrapAddress.0 (`agent_addr`), snmpTrapCommunity.0 (`community`) and
+    /// snmpTrapEnterprise.0 (`enterprise`) unless the trap already carries
+    /// them. snmpTrapCommunity.0 is omitted when `community` is `None`.
+    ///
+    /// A community passed here is carried in cleartext to every destination
+    /// of the converted notification, where it can be replayed against the
+    /// originating device. Pass `None` unless those destinations may hold it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`Self::to_v2_pdu`].
+    pub fn to_v2_proxy_pdu(&self, community: Option<&[u8]>) -> crate::Result<NotificationPdu> {
+        use crate::notification::oids;
+
+        let trap_oid = self.v2_trap_oid()?;
+        let mut varbinds = self.varbinds.clone();
+        let community = community.map(|community| {
+            (
+                oids::snmp_trap_community(),
+                Value::OctetString(bytes::Bytes::copy_from_slice(community)),
+            )
+        });
+        let proxy_varbinds = [
+            Some((oids::snmp_trap_address(), Value::IpAddress(self.agent_addr))),
+            community,
+            Some((
+                oids::snmp_trap_enterprise(),
+                Value::ObjectIdentifier(self.enterprise.clone()),
+            )),
+        ];
+        for (oid, value) in proxy_varbinds.into_iter().flatten() {
+            if !self.varbinds.iter().any(|varbind| varbind.oid == oid) {
+                varbinds.push(VarBind::new(oid, value));
+            }
+        }
+        NotificationPdu::trap_v2(Version::V2c, 0, self.time_stamp, &trap_oid, varbinds)
+    }
+
     pub(crate) fn validate_outbound(&self) -> Result<()> {
         self.enterprise.validate_for_wire()?;
         if matches!(self.generic_trap, GenericTrap::Unknown(_)) {
@@ -3448,6 +3491,47 @@ mod tests {
         assert_eq!(pdu.varbinds().len(), 2);
     }
 
+    #[test]
+    fn v1_to_v2_proxy_appends_missing_proxy_varbinds() {
+        use crate::notification::oids;
+        use crate::value::Value;
+        use crate::varbind::VarBind;
+
+        let trap = TrapV1Pdu::new(
+            oid!(1, 3, 6, 1, 4, 1, 9999),
+            [192, 168, 1, 1],
+            GenericTrap::ColdStart,
+            0,
+            100,
+            vec![VarBind::new(
+                oids::snmp_trap_address(),
+                Value::IpAddress([10, 0, 0, 1]),
+            )],
+        );
+
+        let pdu = trap.to_v2_proxy_pdu(Some(b"public")).unwrap();
+        let varbinds = &pdu.varbinds()[2..];
+        // The trap's own snmpTrapAddress.0 is kept rather than duplicated.
+        assert_eq!(varbinds.len(), 3);
+        assert_eq!(varbinds[0].value, Value::IpAddress([10, 0, 0, 1]));
+        assert_eq!(varbinds[1].oid, oids::snmp_trap_community());
+        assert_eq!(
+            varbinds[1].value,
+            Value::OctetString(bytes::Bytes::from_static(b"public"))
+        );
+        assert_eq!(varbinds[2].oid, oids::snmp_trap_enterprise());
+        assert_eq!(
+            varbinds[2].value,
+            Value::ObjectIdentifier(oid!(1, 3, 6, 1, 4, 1, 9999))
+        );
+
+        // Without a community, snmpTrapCommunity.0 is not added.
+        let pdu = trap.to_v2_proxy_pdu(None).unwrap();
+        let varbinds = &pdu.varbinds()[2..];
+        assert_eq!(varbinds.len(), 2);
+        assert_eq!(varbinds[1].oid, oids::snmp_trap_enterprise());
+    }
+
     #[test]
     fn test_v1_to_v2_enterprise_specific() {
         use crate::value::Value;
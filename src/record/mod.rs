@@ -0,0 +1,117 @@
+//! Reading and writing recorded device data.
+//!
+//! Two line-oriented formats are supported:
+//!
+//! - [`snmpwalk`]: the numeric output of net-snmp's `snmpwalk -On`, such as
+//!   `.1.3.6.1.2.1.1.5.0 = STRING: "core-1"`.
+//! - [`snmprec`]: the snmpsim `.snmprec` format, such as
+//!   `1.3.6.1.2.1.1.5.0|4|core-1`.
+//!
+//! Both writers produce output their readers accept for every [`Value`]
+//! variant, including Counter64, Opaque, exception values, and OCTET STRINGs
+//! that are not printable text. Readers return bindings in file order; collect
+//! them into a [`MibSnapshot`](crate::snapshot::MibSnapshot) for OID-ordered
+//! queries.
+//!
+//! ```rust
+//! use async_snmp::record::{snmprec, snmpwalk};
+//! use async_snmp::{Value, VarBind, oid};
+//!
+//! let varbinds = vec![
+//!     VarBind::new(oid!(1, 3, 6, 1, 2, 1, 1, 5, 0), Value::from("core-1")),
+//!     VarBind::new(oid!(1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 1), Value::Counter64(1 << 40)),
+//! ];
+//!
+//! let mut walk = Vec::new();
+//! snmpwalk::write(&mut walk, &varbinds).unwrap();
+//! assert_eq!(snmpwalk::read(walk.as_slice()).unwrap(), varbinds);
+//!
+//! let mut rec = Vec::new();
+//! snmprec::write(&mut rec, &varbinds).unwrap();
+//! assert_eq!(snmprec::read(rec.as_slice()).unwrap(), varbinds);
+//! ```
+//!
+//! [`Value`]: crate::Value
+
+pub mod snmprec;
+pub mod snmpwalk;
+
+/// Error returned when reading recorded data.
+#[derive(Debug, thiserror::Error)]
+pub enum RecordError {
+    /// The underlying reader failed.
+    #[error("failed to read record data: {0}")]
+    Io(#[from] std::io::Error),
+    /// A line could not be parsed.
+    #[error("line {line}: {kind}")]
+    Malformed {
+        /// One-based line number where the malformed record starts.
+        line: usize,
+        /// What was wrong with the record.
+        kind: MalformedRecord,
+    },
+}
+
+impl RecordError {
+    /// Returns the one-based line number of a malformed record.
+    #[must_use]
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::Io(_) => None,
+            Self::Malformed { line, .. } => Some(*line),
+        }
+    }
+}
+
+/// Reason a recorded line was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum MalformedRecord {
+    /// The line does not separate an OID from its value.
+    #[error("missing OID/value separator")]
+    MissingSeparator,
+    /// The OID is not in numeric dotted form.
+    #[error("invalid OID '{0}'")]
+    InvalidOid(String),
+    /// The value type is not recognized.
+    #[error("unsupported value type '{0}'")]
+    UnsupportedType(String),
+    /// The value text does not match its declared type.
+    #[error("invalid {type_name} value '{value}'")]
+    InvalidValue {
+        /// The declared type as written in the file.
+        type_name: String,
+        /// The rejected value text.
+        value: String,
+    },
+    /// A quoted string was still open at end of input.
+    #[error("unterminated quoted string")]
+    UnterminatedString,
+}
+
+impl MalformedRecord {
+    fn invalid_value(type_name: &str, value: &str) -> Self {
+        Self::InvalidValue {
+            type_name: type_name.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    fn at(self, line: usize) -> RecordError {
+        RecordError::Malformed { line, kind: self }
+    }
+}
+
+/// Returns whether bytes can be written as single-line text.
+fn is_single_line_text(bytes: &[u8]) -> bool {
+    std::str::from_utf8(bytes).is_ok_and(|s| s.chars().all(|c| c == ' ' || c.is_ascii_graphic()))
+}
+
+fn parse_oid(text: &str) -> Result<crate::oid::Oid, MalformedRecord> {
+    let oid = crate::oid::Oid::parse(text.trim())
+        .map_err(|_| MalformedRecord::InvalidOid(text.trim().to_owned()))?;
+    if oid.is_empty() {
+        return Err(MalformedRecord::InvalidOid(text.trim().to_owned()));
+    }
+    Ok(oid)
+}
@@ -0,0 +1,255 @@
+//! snmpsim `.snmprec` data files.
+//!
+//! Each line is `OID|TAG|VALUE`, where `OID` is dotted-decimal without a
+//! leading dot and `TAG` is the decimal BER tag of the value. A tag suffixed
+//! with `x` carries a hex-encoded value, which the writer uses for OCTET
+//! STRINGs that are not single-line text and always for Opaque, NSAP, and
+//! unknown tags. Exception values use their context tags (128-130) with an
+//! empty value.
+//!
+//! snmpsim variation modules (`TAG:module`) are not interpreted and are
+//! reported as unsupported types. Blank lines and `#` comments are skipped.
+
+use std::io::{BufRead, Write};
+use std::net::Ipv4Addr;
+
+use bytes::Bytes;
+
+use super::{MalformedRecord, RecordError, is_single_line_text, parse_oid};
+use crate::ber::tag;
+use crate::format::hex;
+use crate::oid::Oid;
+use crate::value::Value;
+use crate::varbind::VarBind;
+
+/// Parse `.snmprec` data held in memory.
+pub fn parse(input: &str) -> Result<Vec<VarBind>, RecordError> {
+    read(input.as_bytes())
+}
+
+/// Read `.snmprec` data.
+///
+/// Records are returned in file order. Errors report the offending line.
+pub fn read<R: BufRead>(reader: R) -> Result<Vec<VarBind>, RecordError> {
+    let mut varbinds = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        varbinds.push(parse_line(line).map_err(|kind| kind.at(index + 1))?);
+    }
+    Ok(varbinds)
+}
+
+/// Write bindings as `.snmprec` records, one per line.
+///
+/// snmpsim expects records sorted by OID; write a
+/// [`MibSnapshot`](crate::snapshot::MibSnapshot) to guarantee that order.
+pub fn write<'a, W, I>(mut writer: W, varbinds: I) -> std::io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a VarBind>,
+{
+    for varbind in varbinds {
+        writeln!(writer, "{}", format_varbind(varbind))?;
+    }
+    Ok(())
+}
+
+/// Format one binding as a `.snmprec` record without a line terminator.
+#[must_use]
+pub fn format_varbind(varbind: &VarBind) -> String {
+    let oid = &varbind.oid;
+    match &varbind.value {
+        Value::Integer(v) => format!("{oid}|{}|{v}", tag::universal::INTEGER),
+        Value::OctetString(data) if is_single_line_text(data) => format!(
+            "{oid}|{}|{}",
+            tag::universal::OCTET_STRING,
+            std::str::from_utf8(data).expect("checked text")
+        ),
+        Value::OctetString(data) => hex_record(oid, tag::universal::OCTET_STRING, data),
+        Value::Null => format!("{oid}|{}|", tag::universal::NULL),
+        Value::ObjectIdentifier(value) => {
+            format!("{oid}|{}|{value}", tag::universal::OBJECT_IDENTIFIER)
+        }
+        Value::IpAddress([a, b, c, d]) => {
+            format!("{oid}|{}|{a}.{b}.{c}.{d}", tag::application::IP_ADDRESS)
+        }
+        Value::Counter32(v) => format!("{oid}|{}|{v}", tag::application::COUNTER32),
+        Value::Gauge32(v) => format!("{oid}|{}|{v}", tag::application::GAUGE32),
+        Value::UInteger32(v) => format!("{oid}|{}|{v}", tag::application::UINTEGER32),
+        Value::TimeTicks(v) => format!("{oid}|{}|{v}", tag::application::TIMETICKS),
+        Value::Opaque(data) => hex_record(oid, tag::application::OPAQUE, data),
+        Value::Nsap(data) => hex_record(oid, tag::application::NSAP, data),
+        Value::Counter64(v) => format!("{oid}|{}|{v}", tag::application::COUNTER64),
+        Value::NoSuchObject => format!("{oid}|{}|", tag::context::NO_SUCH_OBJECT),
+        Value::NoSuchInstance => format!("{oid}|{}|", tag::context::NO_SUCH_INSTANCE),
+        Value::EndOfMibView => format!("{oid}|{}|", tag::context::END_OF_MIB_VIEW),
+        Value::Unknown { tag, data } => hex_record(oid, *tag, data),
+    }
+}
+
+fn hex_record(oid: &Oid, tag: u8, data: &[u8]) -> String {
+    format!("{oid}|{tag}x|{}", hex::encode(data))
+}
+
+fn parse_line(line: &str) -> Result<VarBind, MalformedRecord> {
+    let mut fields = line.splitn(3, '|');
+    let (Some(oid), Some(tag_text), Some(text)) = (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(MalformedRecord::MissingSeparator);
+    };
+    let oid = parse_oid(oid)?;
+
+    let (digits, is_hex) = match tag_text.strip_suffix('x') {
+        Some(digits) => (digits, true),
+        None => (tag_text, false),
+    };
+    let tag: u8 = digits
+        .parse()
+        .map_err(|_| MalformedRecord::UnsupportedType(tag_text.to_owned()))?;
+    let invalid = || MalformedRecord::invalid_value(tag_text, text);
+
+    let data = if is_hex {
+        Bytes::from(hex::decode_relaxed(text).map_err(|_| invalid())?)
+    } else {
+        Bytes::copy_from_slice(text.as_bytes())
+    };
+    // Hex-encoded numeric values are accepted, so the text form is taken from
+    // the decoded bytes rather than the raw field.
+    let text = std::str::from_utf8(&data).unwrap_or_default();
+
+    let value = match tag {
+        tag::universal::INTEGER => Value::Integer(text.parse().map_err(|_| invalid())?),
+        tag::universal::OCTET_STRING => Value::OctetString(data),
+        tag::universal::NULL if data.is_empty() => Value::Null,
+        tag::universal::OBJECT_IDENTIFIER => {
+            Value::ObjectIdentifier(Oid::parse(text).map_err(|_| invalid())?)
+        }
+        tag::application::IP_ADDRESS => {
+            Value::IpAddress(text.parse::<Ipv4Addr>().map_err(|_| invalid())?.octets())
+        }
+        tag::application::COUNTER32 => Value::Counter32(text.parse().map_err(|_| invalid())?),
+        tag::application::GAUGE32 => Value::Gauge32(text.parse().map_err(|_| invalid())?),
+        tag::application::UINTEGER32 => Value::UInteger32(text.parse().map_err(|_| invalid())?),
+        tag::application::TIMETICKS => Value::TimeTicks(text.parse().map_err(|_| invalid())?),
+        tag::application::OPAQUE => Value::Opaque(data),
+        tag::application::NSAP => Value::Nsap(data),
+        tag::application::COUNTER64 => Value::Counter64(text.parse().map_err(|_| invalid())?),
+        tag::context::NO_SUCH_OBJECT if data.is_empty() => Value::NoSuchObject,
+        tag::context::NO_SUCH_INSTANCE if data.is_empty() => Value::NoSuchInstance,
+        tag::context::END_OF_MIB_VIEW if data.is_empty() => Value::EndOfMibView,
+        tag::universal::NULL
+        | tag::context::NO_SUCH_OBJECT
+        | tag::context::NO_SUCH_INSTANCE
+        | tag::context::END_OF_MIB_VIEW => return Err(invalid()),
+        tag => Value::Unknown { tag, data },
+    };
+    Ok(VarBind::new(oid, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oid;
+
+    #[test]
+    fn round_trips_every_value_variant() {
+        let base = oid!(1, 3, 6, 1, 4, 1, 99999);
+        let varbinds: Vec<_> = [
+            Value::Integer(i32::MIN),
+            Value::OctetString(Bytes::from_static(b"a|b with spaces")),
+            Value::OctetString(Bytes::from_static(&[0xff, 0x00, b'\n'])),
+            Value::OctetString(Bytes::new()),
+            Value::Null,
+            Value::ObjectIdentifier(oid!(1, 3, 6, 1)),
+            Value::IpAddress([192, 0, 2, 1]),
+            Value::Counter32(1),
+            Value::Gauge32(2),
+            Value::UInteger32(3),
+            Value::TimeTicks(4),
+            Value::Opaque(Bytes::from_static(&[0x9f, 0x76, 0x01, 0x05])),
+            Value::Nsap(Bytes::from_static(&[0x47])),
+            Value::Counter64(u64::MAX),
+            Value::NoSuchObject,
+            Value::NoSuchInstance,
+            Value::EndOfMibView,
+            Value::Unknown {
+                tag: 0x9a,
+                data: Bytes::from_static(b"x"),
+            },
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| VarBind::new(base.child(index as u32), value))
+        .collect();
+
+        let mut out = Vec::new();
+        write(&mut out, &varbinds).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("1.3.6.1.4.1.99999.1|4|a|b with spaces\n"));
+        assert!(text.contains("1.3.6.1.4.1.99999.2|4x|ff000a\n"));
+        assert_eq!(parse(&text).unwrap(), varbinds);
+    }
+
+    #[test]
+    fn reads_snmpsim_records() {
+        let input = "\
+# recorded from core-1
+1.3.6.1.2.1.1.1.0|4|Cisco IOS Software\r
+1.3.6.1.2.1.1.2.0|6|1.3.6.1.4.1.9.1.1208
+
+1.3.6.1.2.1.2.2.1.6.1|4x|001122334455
+";
+        let varbinds = parse(input).unwrap();
+        assert_eq!(varbinds.len(), 3);
+        assert_eq!(varbinds[0].value.as_str(), Some("Cisco IOS Software"));
+        assert_eq!(
+            varbinds[1].value,
+            Value::ObjectIdentifier(oid!(1, 3, 6, 1, 4, 1, 9, 1, 1208))
+        );
+        assert_eq!(varbinds[2].value.as_bytes().map(<[u8]>::len), Some(6));
+    }
+
+    #[test]
+    fn malformed_lines_report_line_numbers() {
+        let err = parse("1.3.6.1.2.1.1.1.0|4|ok\n1.3.6.1.2.1.1.3.0|67\n").unwrap_err();
+        assert_eq!(err.line(), Some(2));
+        assert!(matches!(
+            err,
+            RecordError::Malformed {
+                kind: MalformedRecord::MissingSeparator,
+                ..
+            }
+        ));
+
+        let err = parse("1.3.6.1.2.1.1.3.0|67|soon").unwrap_err();
+        assert!(matches!(
+            err,
+            RecordError::Malformed {
+                line: 1,
+                kind: MalformedRecord::InvalidValue { .. }
+            }
+        ));
+
+        let err = parse("1.3.6.1.2.1.1.3.0|67:numeric|rate=10").unwrap_err();
+        assert!(matches!(
+            err,
+            RecordError::Malformed {
+                kind: MalformedRecord::UnsupportedType(_),
+                ..
+            }
+        ));
+
+        let err = parse("1.3.6.1.2.1.1.3.0|128|payload").unwrap_err();
+        assert!(matches!(
+            err,
+            RecordError::Malformed {
+                kind: MalformedRecord::InvalidValue { .. },
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,435 @@
+//! net-snmp `snmpwalk -On` text output.
+//!
+//! Each binding is written as `.OID = TYPE: value`, using the type labels
+//! net-snmp prints. OCTET STRINGs that are single-line text are quoted with
+//! `\"` and `\\` escapes; other octet strings use `Hex-STRING`. Exceptions
+//! use net-snmp's fixed messages, and an empty OCTET STRING is written as
+//! `""`.
+//!
+//! The reader also accepts common variations found in captured output:
+//! quoted strings spanning several lines, wrapped `Hex-STRING` continuation
+//! lines, enumerated `INTEGER: up(1)` labels, `Timeticks` with or without the
+//! parenthesized tick count, `Wrong Type (should be ...)` prefixes, `BITS`,
+//! and `Opaque: Float`/`Double` values.
+//!
+//! [`Value::Unknown`] has no net-snmp representation; it is written as
+//! `Unknown (tag 0xNN): hex` and read back in the same form.
+
+use std::io::{BufRead, Write};
+use std::net::Ipv4Addr;
+
+use bytes::Bytes;
+
+use super::{MalformedRecord, RecordError, is_single_line_text, parse_oid};
+use crate::format::format_hex_display;
+use crate::format::hex::decode_relaxed;
+use crate::oid::Oid;
+use crate::value::Value;
+use crate::varbind::VarBind;
+
+const NO_SUCH_OBJECT: &str = "No Such Object available on this agent at this OID";
+const NO_SUCH_INSTANCE: &str = "No Such Instance currently exists at this OID";
+const END_OF_MIB_VIEW: &str =
+    "No more variables left in this MIB View (It is past the end of the MIB tree)";
+
+/// Parse `snmpwalk -On` output held in memory.
+pub fn parse(input: &str) -> Result<Vec<VarBind>, RecordError> {
+    read(input.as_bytes())
+}
+
+/// Read `snmpwalk -On` output.
+///
+/// Blank lines between records are ignored. Errors report the line where the
+/// malformed record starts.
+pub fn read<R: BufRead>(reader: R) -> Result<Vec<VarBind>, RecordError> {
+    let mut lines = reader
+        .lines()
+        .enumerate()
+        .map(|(index, line)| line.map(|line| (index + 1, strip_cr(line))))
+        .peekable();
+    let mut varbinds = Vec::new();
+
+    while let Some(next) = lines.next() {
+        let (number, line) = next?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (oid_text, value_text) =
+            split_record(&line).ok_or(MalformedRecord::MissingSeparator.at(number))?;
+        let oid = parse_oid(oid_text).map_err(|kind| kind.at(number))?;
+
+        let value = match parse_value(value_text).map_err(|kind| kind.at(number))? {
+            Parsed::Done(value) => value,
+            Parsed::OpenString(mut text) => {
+                // net-snmp prints embedded newlines verbatim inside quotes.
+                loop {
+                    let Some(next) = lines.next() else {
+                        return Err(MalformedRecord::UnterminatedString.at(number));
+                    };
+                    let (_, continuation) = next?;
+                    text.push('\n');
+                    text.push_str(&continuation);
+                    if let Some(value) = close_quoted(&text).map_err(|kind| kind.at(number))? {
+                        break value;
+                    }
+                }
+            }
+            Parsed::Hex(mut bytes) => {
+                // Long Hex-STRING values wrap onto lines holding only hex pairs.
+                while let Some(Ok((_, continuation))) = lines.peek() {
+                    let continuation = continuation.trim();
+                    if continuation.is_empty() || continuation.starts_with('.') {
+                        break;
+                    }
+                    let Ok(more) = decode_relaxed(continuation) else {
+                        break;
+                    };
+                    bytes.extend_from_slice(&more);
+                    lines.next();
+                }
+                Value::OctetString(Bytes::from(bytes))
+            }
+        };
+
+        varbinds.push(VarBind::new(oid, value));
+    }
+
+    Ok(varbinds)
+}
+
+/// Write bindings as `snmpwalk -On` output, one record per line.
+pub fn write<'a, W, I>(mut writer: W, varbinds: I) -> std::io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a VarBind>,
+{
+    for varbind in varbinds {
+        writeln!(writer, "{}", format_varbind(varbind))?;
+    }
+    Ok(())
+}
+
+/// Format one binding as an `snmpwalk -On` line without a line terminator.
+#[must_use]
+pub fn format_varbind(varbind: &VarBind) -> String {
+    format!(".{} = {}", varbind.oid, format_value(&varbind.value))
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Integer(v) => format!("INTEGER: {v}"),
+        Value::OctetString(data) if data.is_empty() => "\"\"".to_owned(),
+        Value::OctetString(data) if is_single_line_text(data) => {
+            let text = std::str::from_utf8(data).expect("checked text");
+            format!(
+                "STRING: \"{}\"",
+                text.replace('\\', "\\\\").replace('"', "\\\"")
+            )
+        }
+        Value::OctetString(data) => format!("Hex-STRING: {}", format_hex_display(data)),
+        Value::Null => "NULL".to_owned(),
+        Value::ObjectIdentifier(oid) => format!("OID: .{oid}"),
+        Value::IpAddress([a, b, c, d]) => format!("IpAddress: {a}.{b}.{c}.{d}"),
+        Value::Counter32(v) => format!("Counter32: {v}"),
+        Value::Gauge32(v) => format!("Gauge32: {v}"),
+        Value::UInteger32(v) => format!("UInteger32: {v}"),
+        Value::TimeTicks(v) => {
+            format!("Timeticks: ({v}) {}", crate::format::format_timeticks(*v))
+        }
+        Value::Opaque(data) => format!("Opaque: {}", format_hex_display(data)),
+        Value::Nsap(data) => format!("NsapAddress: {}", format_hex_display(data)),
+        Value::Counter64(v) => format!("Counter64: {v}"),
+        Value::NoSuchObject => NO_SUCH_OBJECT.to_owned(),
+        Value::NoSuchInstance => NO_SUCH_INSTANCE.to_owned(),
+        Value::EndOfMibView => END_OF_MIB_VIEW.to_owned(),
+        Value::Unknown { tag, data } => {
+            format!("Unknown (tag 0x{tag:02X}): {}", format_hex_display(data))
+        }
+    }
+}
+
+fn strip_cr(mut line: String) -> String {
+    if line.ends_with('\r') {
+        line.pop();
+    }
+    line
+}
+
+fn split_record(line: &str) -> Option<(&str, &str)> {
+    if let Some((oid, value)) = line.split_once(" = ") {
+        return Some((oid, value));
+    }
+    // An empty value leaves only the separator's leading half.
+    line.trim_end().strip_suffix(" =").map(|oid| (oid, ""))
+}
+
+enum Parsed {
+    Done(Value),
+    OpenString(String),
+    Hex(Vec<u8>),
+}
+
+fn parse_value(text: &str) -> Result<Parsed, MalformedRecord> {
+    let trimmed = text.trim_end();
+    match trimmed {
+        "" | "\"\"" => return Ok(Parsed::Done(Value::OctetString(Bytes::new()))),
+        "NULL" => return Ok(Parsed::Done(Value::Null)),
+        NO_SUCH_OBJECT => return Ok(Parsed::Done(Value::NoSuchObject)),
+        NO_SUCH_INSTANCE => return Ok(Parsed::Done(Value::NoSuchInstance)),
+        END_OF_MIB_VIEW => return Ok(Parsed::Done(Value::EndOfMibView)),
+        _ => {}
+    }
+
+    let text = strip_wrong_type(text);
+    let Some((type_name, payload)) = text.split_once(':') else {
+        return Err(MalformedRecord::UnsupportedType(trimmed.to_owned()));
+    };
+    let type_name = type_name.trim();
+    let payload = payload.strip_prefix(' ').unwrap_or(payload);
+    let token = payload.trim();
+    let invalid = || MalformedRecord::invalid_value(type_name, token);
+
+    let value = match type_name {
+        "INTEGER" => Value::Integer(parse_labelled(token).ok_or_else(invalid)?),
+        "STRING" => {
+            if payload.starts_with('"') {
+                return Ok(match close_quoted(payload)? {
+                    Some(value) => Parsed::Done(value),
+                    None => Parsed::OpenString(payload.to_owned()),
+                });
+            }
+            Value::OctetString(Bytes::copy_from_slice(payload.trim_end().as_bytes()))
+        }
+        "Hex-STRING" => return Ok(Parsed::Hex(decode_relaxed(token).map_err(|_| invalid())?)),
+        "BITS" => {
+            // Named bits follow the hex octets: `BITS: 80 00 up(0)`.
+            let hex: Vec<&str> = token
+                .split_whitespace()
+                .take_while(|part| part.len() == 2 && decode_relaxed(part).is_ok())
+                .collect();
+            Value::OctetString(Bytes::from(
+                decode_relaxed(&hex.concat()).map_err(|_| invalid())?,
+            ))
+        }
+        "OID" => Value::ObjectIdentifier(Oid::parse(token).map_err(|_| invalid())?),
+        "IpAddress" => Value::IpAddress(token.parse::<Ipv4Addr>().map_err(|_| invalid())?.octets()),
+        "Network Address" => {
+            let bytes = decode_relaxed(token).map_err(|_| invalid())?;
+            Value::IpAddress(bytes.try_into().map_err(|_| invalid())?)
+        }
+        "Counter32" => Value::Counter32(first_token(token).ok_or_else(invalid)?),
+        "Gauge32" | "Unsigned32" => Value::Gauge32(first_token(token).ok_or_else(invalid)?),
+        "UInteger32" => Value::UInteger32(first_token(token).ok_or_else(invalid)?),
+        "Counter64" => Value::Counter64(first_token(token).ok_or_else(invalid)?),
+        "Timeticks" => Value::TimeTicks(parse_labelled(token).ok_or_else(invalid)?),
+        "Opaque" => Value::Opaque(parse_opaque(token).ok_or_else(invalid)?),
+        "NsapAddress" => Value::Nsap(Bytes::from(decode_relaxed(token).map_err(|_| invalid())?)),
+        other => match parse_unknown_tag(other) {
+            Some(tag) => Value::Unknown {
+                tag,
+                data: Bytes::from(decode_relaxed(token).map_err(|_| invalid())?),
+            },
+            None => return Err(MalformedRecord::UnsupportedType(other.to_owned())),
+        },
+    };
+    Ok(Parsed::Done(value))
+}
+
+/// Drop net-snmp's `Wrong Type (should be X): ` prefix, keeping the wire type.
+fn strip_wrong_type(text: &str) -> &str {
+    text.strip_prefix("Wrong Type (should be ")
+        .and_then(|rest| rest.split_once("): "))
+        .map_or(text, |(_, rest)| rest)
+}
+
+/// Parse a number that may be wrapped in an enumeration or tick label, such
+/// as `up(1)` or `(12345) 0:02:03.45`.
+fn parse_labelled<T: std::str::FromStr>(token: &str) -> Option<T> {
+    if let Some(start) = token.find('(')
+        && let Some(end) = token[start..].find(')')
+        && let Ok(value) = token[start + 1..start + end].parse()
+    {
+        return Some(value);
+    }
+    first_token(token)
+}
+
+fn first_token<T: std::str::FromStr>(token: &str) -> Option<T> {
+    token.split_whitespace().next()?.parse().ok()
+}
+
+fn parse_opaque(token: &str) -> Option<Bytes> {
+    if let Some(float) = token.strip_prefix("Float:") {
+        let value: f32 = float.trim().parse().ok()?;
+        let mut data = vec![0x9f, 0x78, 0x04];
+        data.extend_from_slice(&value.to_be_bytes());
+        return Some(Bytes::from(data));
+    }
+    if let Some(double) = token.strip_prefix("Double:") {
+        let value: f64 = double.trim().parse().ok()?;
+        let mut data = vec![0x9f, 0x79, 0x08];
+        data.extend_from_slice(&value.to_be_bytes());
+        return Some(Bytes::from(data));
+    }
+    decode_relaxed(token).ok().map(Bytes::from)
+}
+
+fn parse_unknown_tag(type_name: &str) -> Option<u8> {
+    let hex = type_name
+        .strip_prefix("Unknown (tag 0x")?
+        .strip_suffix(')')?;
+    u8::from_str_radix(hex, 16).ok()
+}
+
+/// Decode a quoted string if its closing quote has been seen.
+///
+/// Returns `Ok(None)` while the string is still open.
+fn close_quoted(text: &str) -> Result<Option<Value>, MalformedRecord> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.strip_prefix('"').unwrap_or(text).chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped) => out.push(escaped),
+                None => return Ok(None),
+            },
+            '"' => {
+                let rest = chars.as_str();
+                if !rest.trim().is_empty() {
+                    return Err(MalformedRecord::invalid_value("STRING", text));
+                }
+                return Ok(Some(Value::OctetString(Bytes::from(out))));
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oid;
+
+    fn all_values() -> Vec<VarBind> {
+        let base = oid!(1, 3, 6, 1, 4, 1, 99999);
+        vec![
+            Value::Integer(-42),
+            Value::OctetString(Bytes::from_static(b"say \"hi\" \\ bye")),
+            Value::OctetString(Bytes::from_static(&[0x00, 0x1a, 0xff])),
+            Value::OctetString(Bytes::from_static(b"line\nbreak")),
+            Value::OctetString(Bytes::new()),
+            Value::Null,
+            Value::ObjectIdentifier(oid!(1, 3, 6, 1, 4, 1, 8072, 3, 2, 10)),
+            Value::IpAddress([10, 0, 0, 1]),
+            Value::Counter32(u32::MAX),
+            Value::Gauge32(1_000_000_000),
+            Value::UInteger32(7),
+            Value::TimeTicks(8_640_123),
+            Value::Opaque(Bytes::from_static(&[
+                0x9f, 0x78, 0x04, 0x3f, 0xc0, 0x00, 0x00,
+            ])),
+            Value::Nsap(Bytes::from_static(&[0x47, 0x00, 0x05])),
+            Value::Counter64(u64::MAX),
+            Value::NoSuchObject,
+            Value::NoSuchInstance,
+            Value::EndOfMibView,
+            Value::Unknown {
+                tag: 0x88,
+                data: Bytes::from_static(&[0xde, 0xad]),
+            },
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| VarBind::new(base.child(index as u32).child(0), value))
+        .collect()
+    }
+
+    #[test]
+    fn round_trips_every_value_variant() {
+        let varbinds = all_values();
+        let mut out = Vec::new();
+        write(&mut out, &varbinds).unwrap();
+        assert_eq!(read(out.as_slice()).unwrap(), varbinds);
+    }
+
+    #[test]
+    fn reads_net_snmp_output_variations() {
+        let input = "\
+.1.3.6.1.2.1.1.1.0 = STRING: \"Linux router
+kernel 6.1\"
+.1.3.6.1.2.1.1.3.0 = Timeticks: (12345) 0:02:03.45
+.1.3.6.1.2.1.2.2.1.7.1 = INTEGER: up(1)
+.1.3.6.1.2.1.2.2.1.6.2 = Hex-STRING: 00 11 22 33 44 55 66 77 88 99 AA BB CC DD EE FF
+00 01
+.1.3.6.1.2.1.1.4.0 = \"\"
+
+.1.3.6.1.2.1.1.9.1.2.1 = Wrong Type (should be OBJECT IDENTIFIER): INTEGER: 5
+.1.3.6.1.2.1.88.1.1.1.0 = BITS: 80 00 enabled(0)
+.1.3.6.1.4.1.2021.10.1.6.1 = Opaque: Float: 0.5
+";
+        let varbinds = parse(input).unwrap();
+        assert_eq!(varbinds.len(), 8);
+        assert_eq!(
+            varbinds[0].value.as_bytes(),
+            Some(&b"Linux router\nkernel 6.1"[..])
+        );
+        assert_eq!(varbinds[1].value, Value::TimeTicks(12345));
+        assert_eq!(varbinds[2].value, Value::Integer(1));
+        assert_eq!(varbinds[3].value.as_bytes().map(<[u8]>::len), Some(18));
+        assert_eq!(varbinds[4].value, Value::OctetString(Bytes::new()));
+        assert_eq!(varbinds[5].value, Value::Integer(5));
+        assert_eq!(varbinds[6].value.as_bytes(), Some(&[0x80, 0x00][..]));
+        assert_eq!(varbinds[7].value.as_opaque_float(), Some(0.5));
+    }
+
+    #[test]
+    fn malformed_lines_report_line_numbers() {
+        let err = parse(".1.3.6.1.2.1.1.1.0 = INTEGER: 1\n\nnot a record\n").unwrap_err();
+        assert_eq!(err.line(), Some(3));
+        assert!(matches!(
+            err,
+            RecordError::Malformed {
+                kind: MalformedRecord::MissingSeparator,
+                ..
+            }
+        ));
+
+        let err = parse(".1.3.6.1.2.1.1.1.0 = INTEGER: many").unwrap_err();
+        assert!(matches!(
+            err,
+            RecordError::Malformed {
+                line: 1,
+                kind: MalformedRecord::InvalidValue { .. }
+            }
+        ));
+
+        let err = parse(".1.3.6.1.2.1.1.1.0 = Float: 1.0").unwrap_err();
+        assert!(matches!(
+            err,
+            RecordError::Malformed {
+                kind: MalformedRecord::UnsupportedType(_),
+                ..
+            }
+        ));
+
+        let err = parse("\n.1.3.6.1.2.1.1.1.0 = STRING: \"open\nstill open").unwrap_err();
+        assert!(matches!(
+            err,
+            RecordError::Malformed {
+                line: 2,
+                kind: MalformedRecord::UnterminatedString
+            }
+        ));
+
+        let err = parse("SNMPv2-MIB::sysDescr.0 = STRING: x").unwrap_err();
+        assert!(matches!(
+            err,
+            RecordError::Malformed {
+                kind: MalformedRecord::InvalidOid(_),
+                ..
+            }
+        ));
+    }
+}
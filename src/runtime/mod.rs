@@ -0,0 +1,641 @@
+//! Async runtime abstraction.
+//!
+//! The transports, the agent, and the notification receiver reach the async
+//! runtime only through the [`Runtime`] trait: spawning background tasks,
+//! sleeping until a deadline, registering UDP sockets and TCP connections with
+//! the reactor, and resolving host names. Two implementations are provided:
+//!
+//! | Runtime | Feature | Notes |
+//! |---------|---------|-------|
+//! | [`TokioRuntime`] | always available | Default; used inside a Tokio runtime context |
+//! | [`SmolRuntime`] | `rt-smol` | smol executor with the async-io reactor and timers |
+//!
+//! # Runtime selection
+//!
+//! The runtime is chosen when a socket is created, a task is spawned, or a
+//! timer starts:
+//!
+//! 1. A runtime registered with [`install`] always wins.
+//! 2. Otherwise, [`TokioRuntime`] is used when called from within a Tokio
+//!    runtime context.
+//! 3. Otherwise, [`SmolRuntime`] is used when the `rt-smol` feature is
+//!    enabled.
+//! 4. Otherwise, [`TokioRuntime`] is used and panics outside a Tokio runtime,
+//!    as Tokio itself does.
+//!
+//! Without the `rt-smol` feature and without [`install`], behavior is exactly
+//! that of earlier releases. Tokio remains a dependency in every
+//! configuration for its runtime-independent synchronization primitives.
+//!
+//! The Unix domain socket transport stays Tokio-only.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "rt-smol")]
+//! # fn main() -> async_snmp::Result<()> {
+//! use async_snmp::{Auth, Client, oid};
+//!
+//! // Requires the `rt-smol` feature; no Tokio runtime is started.
+//! smol::block_on(async {
+//!     let client = Client::builder("192.168.1.1:161", Auth::v2c("public"))
+//!         .connect()
+//!         .await?;
+//!     let response = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).await?;
+//!     println!("{:?}", response.varbinds);
+//!     Ok(())
+//! })
+//! # }
+//! # #[cfg(not(feature = "rt-smol"))]
+//! # fn main() {}
+//! ```
+
+#[cfg(feature = "rt-smol")]
+mod smol_runtime;
+#[cfg_attr(
+    not(feature = "agent"),
+    allow(
+        dead_code,
+        reason = "cancellation and join sets serve agent request tasks"
+    )
+)]
+mod task;
+mod tokio_runtime;
+
+#[cfg(feature = "rt-smol")]
+pub use smol_runtime::SmolRuntime;
+pub(crate) use task::{AbortHandle, JoinHandle};
+#[cfg(feature = "agent")]
+pub(crate) use task::{JoinError, JoinSet};
+pub use tokio_runtime::TokioRuntime;
+
+use crate::error::{Error, Result};
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::time::Instant;
+
+/// A boxed, sendable future, as returned by [`Runtime`] methods.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The socket, timer, and spawn services the library needs from an async
+/// runtime.
+///
+/// Implement this to run on a runtime other than Tokio or smol, then register
+/// the implementation with [`install`].
+pub trait Runtime: Send + Sync + 'static {
+    /// Run `task` in the background until it completes.
+    ///
+    /// The library tracks completion and cancellation itself, so the task is
+    /// detached.
+    fn spawn(&self, task: BoxFuture<'static, ()>);
+
+    /// Complete at `deadline`.
+    fn sleep_until(&self, deadline: std::time::Instant) -> BoxFuture<'static, ()>;
+
+    /// Register a bound, non-blocking UDP socket with the reactor.
+    fn udp_socket(&self, socket: std::net::UdpSocket) -> io::Result<Box<dyn AsyncUdpSocket>>;
+
+    /// Connect a configured but unconnected, non-blocking TCP socket to `target`.
+    ///
+    /// The socket may already be bound to a source address.
+    fn connect_tcp(
+        &self,
+        socket: std::net::TcpStream,
+        target: SocketAddr,
+    ) -> BoxFuture<'static, io::Result<Box<dyn AsyncTcpStream>>>;
+
+    /// Resolve `host` to socket addresses with `port`.
+    fn lookup_host(
+        &self,
+        host: String,
+        port: u16,
+    ) -> BoxFuture<'static, io::Result<Vec<SocketAddr>>>;
+}
+
+/// Readiness a UDP socket operation waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interest {
+    /// The socket has a datagram to receive.
+    Readable,
+    /// The socket can accept a datagram to send.
+    Writable,
+}
+
+/// A UDP socket registered with a runtime's reactor.
+///
+/// Operations are non-blocking: `try_*` methods return
+/// [`io::ErrorKind::WouldBlock`] when the socket is not ready, after which the
+/// caller waits on [`ready`](Self::ready) and retries.
+pub trait AsyncUdpSocket: Send + Sync + fmt::Debug + 'static {
+    /// Wait until the socket may be ready for `interest`.
+    ///
+    /// Several tasks may wait on the same socket concurrently.
+    fn ready(&self, interest: Interest) -> BoxFuture<'_, io::Result<()>>;
+
+    /// Run `op` against the socket, clearing readiness for `interest` when it
+    /// returns [`io::ErrorKind::WouldBlock`].
+    fn try_io(&self, interest: Interest, op: &mut dyn FnMut() -> io::Result<()>) -> io::Result<()>;
+
+    /// Receive one datagram without waiting.
+    fn try_recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)>;
+
+    /// Send one datagram without waiting.
+    fn try_send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize>;
+
+    /// Return the bound local address.
+    fn local_addr(&self) -> io::Result<SocketAddr>;
+
+    /// Borrow the socket descriptor for platform socket calls.
+    #[cfg(unix)]
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_>;
+
+    /// Borrow the socket handle for platform socket calls.
+    #[cfg(windows)]
+    fn as_socket(&self) -> std::os::windows::io::BorrowedSocket<'_>;
+}
+
+/// A connected TCP stream registered with a runtime's reactor.
+pub trait AsyncTcpStream: AsyncRead + AsyncWrite + Send + Sync + Unpin + fmt::Debug {
+    /// Return the local address of the connection.
+    fn local_addr(&self) -> io::Result<SocketAddr>;
+}
+
+static INSTALLED: OnceLock<Box<dyn Runtime>> = OnceLock::new();
+
+/// Register `runtime` for every later socket, task, and timer in the process.
+///
+/// Returns a configuration error when a runtime was already installed. Sockets
+/// and tasks created earlier keep the runtime they were created with.
+pub fn install(runtime: impl Runtime) -> Result<()> {
+    INSTALLED
+        .set(Box::new(runtime))
+        .map_err(|_| Error::Config("an async runtime is already installed".into()).boxed())
+}
+
+/// The runtime selected for the calling context.
+///
+/// Built-in runtimes are named so that hot paths can use their concrete
+/// timer and readiness futures instead of boxed trait-object ones.
+enum Selected {
+    Installed(&'static dyn Runtime),
+    Tokio,
+    #[cfg(feature = "rt-smol")]
+    Smol,
+}
+
+fn select() -> Selected {
+    if let Some(runtime) = INSTALLED.get() {
+        return Selected::Installed(runtime.as_ref());
+    }
+    #[cfg(feature = "rt-smol")]
+    if tokio::runtime::Handle::try_current().is_err() {
+        return Selected::Smol;
+    }
+    Selected::Tokio
+}
+
+/// Select the runtime for the calling context.
+fn current() -> &'static dyn Runtime {
+    match select() {
+        Selected::Installed(runtime) => runtime,
+        Selected::Tokio => &TokioRuntime,
+        #[cfg(feature = "rt-smol")]
+        Selected::Smol => &SmolRuntime,
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// A sleep future created by [`sleep_until`] or [`sleep`].
+    #[project = SleepProj]
+    pub(crate) enum Sleep {
+        Tokio { #[pin] sleep: tokio::time::Sleep },
+        Other { sleep: OtherSleep },
+    }
+}
+
+/// Timers that are `Unpin`; only those of installed runtimes are boxed.
+enum OtherSleep {
+    #[cfg(feature = "rt-smol")]
+    Smol(smol::Timer),
+    Installed(BoxFuture<'static, ()>),
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<()> {
+        match self.project() {
+            SleepProj::Tokio { sleep } => sleep.poll(cx),
+            SleepProj::Other { sleep } => match sleep {
+                #[cfg(feature = "rt-smol")]
+                OtherSleep::Smol(timer) => Pin::new(timer).poll(cx).map(drop),
+                OtherSleep::Installed(sleep) => sleep.as_mut().poll(cx),
+            },
+        }
+    }
+}
+
+/// Complete at `deadline`.
+///
+/// Deadlines stay `tokio::time::Instant` so Tokio's paused test clock keeps
+/// applying when the Tokio runtime is selected.
+pub(crate) fn sleep_until(deadline: Instant) -> Sleep {
+    match select() {
+        Selected::Installed(runtime) => Sleep::Other {
+            sleep: OtherSleep::Installed(runtime.sleep_until(deadline.into_std())),
+        },
+        Selected::Tokio => Sleep::Tokio {
+            sleep: tokio::time::sleep_until(deadline),
+        },
+        #[cfg(feature = "rt-smol")]
+        Selected::Smol => Sleep::Other {
+            sleep: OtherSleep::Smol(smol::Timer::at(deadline.into_std())),
+        },
+    }
+}
+
+/// Complete after `duration`.
+pub(crate) fn sleep(duration: Duration) -> Sleep {
+    let deadline = Instant::now()
+        .checked_add(duration)
+        .unwrap_or_else(far_future);
+    sleep_until(deadline)
+}
+
+/// A deadline far enough away to never elapse in practice.
+fn far_future() -> Instant {
+    // Roughly 30 years, as used by Tokio for its own far-future deadlines.
+    Instant::now() + Duration::from_secs(86400 * 365 * 30)
+}
+
+/// The deadline of [`timeout`] or [`timeout_at`] elapsed first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Elapsed;
+
+/// Run `future` until it completes or `deadline` passes.
+///
+/// The future is polled before the deadline, so a ready future wins a tie.
+pub(crate) async fn timeout_at<F: Future>(
+    deadline: Instant,
+    future: F,
+) -> std::result::Result<F::Output, Elapsed> {
+    let sleep = sleep_until(deadline);
+    tokio::select! {
+        biased;
+        output = future => Ok(output),
+        () = sleep => Err(Elapsed),
+    }
+}
+
+/// Run `future` until it completes or `duration` passes.
+pub(crate) async fn timeout<F: Future>(
+    duration: Duration,
+    future: F,
+) -> std::result::Result<F::Output, Elapsed> {
+    let deadline = Instant::now()
+        .checked_add(duration)
+        .unwrap_or_else(far_future);
+    timeout_at(deadline, future).await
+}
+
+/// Spawn `future` on the selected runtime.
+pub(crate) fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    task::spawn_on(current(), future)
+}
+
+/// Run CPU-bound `work` on a thread where blocking is allowed.
+///
+/// Inside a Tokio runtime context this uses Tokio's blocking pool; elsewhere
+/// the work gets a dedicated thread. A panic in `work` resumes on the caller.
+pub(crate) async fn spawn_blocking<F, R>(work: F) -> R
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return match tokio::task::spawn_blocking(work).await {
+            Ok(output) => output,
+            Err(error) => std::panic::resume_unwind(error.into_panic()),
+        };
+    }
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    let thread = std::thread::spawn(move || {
+        let _ = sender.send(work());
+    });
+    match receiver.await {
+        Ok(output) => output,
+        Err(_) => match thread.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(()) => unreachable!("blocking work sends its output before returning"),
+        },
+    }
+}
+
+/// Resolve `host` and `port` on the selected runtime.
+pub(crate) async fn lookup_host(host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+    current().lookup_host(host.to_owned(), port).await
+}
+
+/// A connected TCP stream on the selected runtime.
+pub(crate) type TcpStream = Box<dyn AsyncTcpStream>;
+
+/// Connect an unconnected socket from [`crate::util::tcp_socket`] to `target`.
+pub(crate) async fn connect_tcp(
+    socket: std::net::TcpStream,
+    target: SocketAddr,
+) -> io::Result<TcpStream> {
+    current().connect_tcp(socket, target).await
+}
+
+/// A UDP socket on the selected runtime.
+///
+/// Mirrors the subset of `tokio::net::UdpSocket` the library uses.
+#[derive(Debug)]
+pub(crate) struct UdpSocket {
+    io: UdpIo,
+}
+
+/// Sockets of the built-in runtimes are held concretely so readiness waits
+/// do not allocate; only installed runtimes go through a boxed future.
+#[derive(Debug)]
+enum UdpIo {
+    Tokio(tokio::net::UdpSocket),
+    #[cfg(feature = "rt-smol")]
+    Smol(smol::Async<std::net::UdpSocket>),
+    Installed(Box<dyn AsyncUdpSocket>),
+}
+
+impl UdpIo {
+    fn as_dyn(&self) -> &dyn AsyncUdpSocket {
+        match self {
+            Self::Tokio(socket) => socket,
+            #[cfg(feature = "rt-smol")]
+            Self::Smol(socket) => socket,
+            Self::Installed(socket) => socket.as_ref(),
+        }
+    }
+
+    async fn ready(&self, interest: Interest) -> io::Result<()> {
+        match self {
+            Self::Tokio(socket) => tokio_runtime::udp_ready(socket, interest).await,
+            #[cfg(feature = "rt-smol")]
+            Self::Smol(socket) => smol_runtime::udp_ready(socket, interest).await,
+            Self::Installed(socket) => socket.ready(interest).await,
+        }
+    }
+}
+
+impl UdpSocket {
+    /// Register a bound, non-blocking socket with the selected runtime.
+    pub(crate) fn from_std(socket: std::net::UdpSocket) -> io::Result<Self> {
+        let io = match select() {
+            Selected::Installed(runtime) => UdpIo::Installed(runtime.udp_socket(socket)?),
+            Selected::Tokio => UdpIo::Tokio(tokio::net::UdpSocket::from_std(socket)?),
+            #[cfg(feature = "rt-smol")]
+            Selected::Smol => UdpIo::Smol(smol::Async::new(socket)?),
+        };
+        Ok(Self { io })
+    }
+
+    /// Bind a non-blocking socket to `addr`.
+    #[cfg(test)]
+    pub(crate) async fn bind(addr: impl std::net::ToSocketAddrs) -> io::Result<Self> {
+        let socket = std::net::UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        Self::from_std(socket)
+    }
+
+    pub(crate) fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.io.as_dyn().local_addr()
+    }
+
+    /// Wait until the socket may be readable.
+    pub(crate) async fn readable(&self) -> io::Result<()> {
+        self.io.ready(Interest::Readable).await
+    }
+
+    /// Wait until the socket may be writable.
+    pub(crate) async fn writable(&self) -> io::Result<()> {
+        self.io.ready(Interest::Writable).await
+    }
+
+    /// Run `op` once, clearing readiness when it would block.
+    pub(crate) fn try_io<R>(
+        &self,
+        interest: Interest,
+        op: impl FnOnce() -> io::Result<R>,
+    ) -> io::Result<R> {
+        let mut op = Some(op);
+        let mut output = None;
+        self.io.as_dyn().try_io(interest, &mut || {
+            let op = op.take().expect("try_io runs its operation at most once");
+            output = Some(op()?);
+            Ok(())
+        })?;
+        Ok(output.expect("try_io succeeded without running its operation"))
+    }
+
+    /// Wait for readiness and run `op` until it stops returning
+    /// [`io::ErrorKind::WouldBlock`].
+    pub(crate) async fn async_io<R>(
+        &self,
+        interest: Interest,
+        mut op: impl FnMut() -> io::Result<R>,
+    ) -> io::Result<R> {
+        loop {
+            self.io.ready(interest).await?;
+            match self.try_io(interest, &mut op) {
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => {}
+                result => return result,
+            }
+        }
+    }
+
+    pub(crate) async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        loop {
+            match self.io.as_dyn().try_recv_from(buf) {
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                    self.io.ready(Interest::Readable).await?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    pub(crate) async fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        loop {
+            match self.io.as_dyn().try_send_to(buf, target) {
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                    self.io.ready(Interest::Writable).await?;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl std::os::fd::AsFd for UdpSocket {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        self.io.as_dyn().as_fd()
+    }
+}
+
+#[cfg(unix)]
+impl std::os::fd::AsRawFd for UdpSocket {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.io.as_dyn().as_fd().as_raw_fd()
+    }
+}
+
+#[cfg(windows)]
+impl std::os::windows::io::AsSocket for UdpSocket {
+    fn as_socket(&self) -> std::os::windows::io::BorrowedSocket<'_> {
+        self.io.as_dyn().as_socket()
+    }
+}
+
+#[cfg(windows)]
+impl std::os::windows::io::AsRawSocket for UdpSocket {
+    fn as_raw_socket(&self) -> std::os::windows::io::RawSocket {
+        self.io.as_dyn().as_socket().as_raw_socket()
+    }
+}
+
+#[cfg(test)]
+mod selection_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn tokio_context_uses_unboxed_timers_and_sockets() {
+        assert!(matches!(sleep(Duration::ZERO), Sleep::Tokio { .. }));
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        assert!(matches!(socket.io, UdpIo::Tokio(_)));
+    }
+}
+
+#[cfg(all(test, feature = "rt-smol"))]
+mod tests {
+    use super::*;
+    use crate::transport::{TcpTransport, Transport};
+
+    #[test]
+    fn smol_timers_and_tasks_run_without_tokio() {
+        smol::block_on(async {
+            assert!(tokio::runtime::Handle::try_current().is_err());
+            assert!(matches!(
+                sleep(Duration::ZERO),
+                Sleep::Other {
+                    sleep: OtherSleep::Smol(_)
+                }
+            ));
+
+            let started = Instant::now();
+            let result = timeout(Duration::from_millis(20), std::future::pending::<()>()).await;
+            assert_eq!(result, Err(Elapsed));
+            assert!(started.elapsed() >= Duration::from_millis(20));
+
+            assert_eq!(spawn(async { 7 }).await.unwrap(), 7);
+            let pending = spawn(std::future::pending::<()>());
+            pending.abort_handle().abort();
+            assert!(pending.await.unwrap_err().is_cancelled());
+            let panicked = spawn(async { panic!("boom") }).await.unwrap_err();
+            assert_eq!(panicked.to_string(), "task panicked with message \"boom\"");
+        });
+    }
+
+    #[test]
+    fn tcp_transport_connects_on_smol() {
+        smol::block_on(async {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let target = listener.local_addr().unwrap();
+            let transport = TcpTransport::connect(target).await.unwrap();
+            let (_, peer) = listener.accept().unwrap();
+            assert_eq!(transport.local_addr(), peer);
+
+            drop(listener);
+            let Err(error) = TcpTransport::connect(target).await else {
+                panic!("connect to a closed port succeeded");
+            };
+            assert!(
+                matches!(*error, Error::Network { target: refused, .. } if refused == target),
+                "{error:?}"
+            );
+        });
+    }
+
+    #[cfg(feature = "agent")]
+    #[test]
+    fn client_queries_agent_on_smol() {
+        use crate::agent::Agent;
+        use crate::handler::{GetNextResult, GetResult, HandlerResult, MibHandler, RequestContext};
+        use crate::{Auth, Client, Oid, Value, oid};
+        use std::sync::Arc;
+
+        struct SysDescr;
+
+        impl MibHandler for SysDescr {
+            fn get<'a>(
+                &'a self,
+                _ctx: &'a RequestContext,
+                oid: &'a Oid,
+            ) -> crate::handler::BoxFuture<'a, HandlerResult<GetResult>> {
+                Box::pin(async move {
+                    Ok(if oid == &oid!(1, 3, 6, 1, 2, 1, 1, 1, 0) {
+                        GetResult::Value(Value::OctetString("smol".into()))
+                    } else {
+                        GetResult::NoSuchObject
+                    })
+                })
+            }
+
+            fn get_next<'a>(
+                &'a self,
+                _ctx: &'a RequestContext,
+                _oid: &'a Oid,
+            ) -> crate::handler::BoxFuture<'a, HandlerResult<GetNextResult>> {
+                Box::pin(async { Ok(GetNextResult::EndOfMibView) })
+            }
+        }
+
+        smol::block_on(async {
+            let agent = Arc::new(
+                Agent::builder()
+                    .bind("127.0.0.1:0")
+                    .community(b"public")
+                    .handler(oid!(1, 3, 6, 1, 2, 1, 1), Arc::new(SysDescr))
+                    .allow_all_access()
+                    .build()
+                    .await
+                    .unwrap(),
+            );
+            let running = smol::spawn({
+                let agent = Arc::clone(&agent);
+                async move { agent.run().await }
+            });
+
+            let client = Client::builder(agent.local_addr(), Auth::v2c("public"))
+                .connect()
+                .await
+                .unwrap();
+            let response = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).await.unwrap();
+            assert_eq!(
+                response.single().unwrap().value,
+                Value::OctetString("smol".into())
+            );
+
+            agent.cancel();
+            running.await.unwrap();
+        });
+    }
+}
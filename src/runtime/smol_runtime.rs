@@ -0,0 +1,196 @@
+//! smol/async-io implementation of [`Runtime`].
+
+use super::{AsyncTcpStream, AsyncUdpSocket, BoxFuture, Interest, Runtime};
+use smol::Async;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::ReadBuf;
+
+/// The smol runtime.
+///
+/// Tasks run on smol's global executor; sockets and timers use the async-io
+/// reactor, so no Tokio runtime is required. Futures may be driven by any
+/// executor, including `smol::block_on`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SmolRuntime;
+
+impl Runtime for SmolRuntime {
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        smol::spawn(task).detach();
+    }
+
+    fn sleep_until(&self, deadline: std::time::Instant) -> BoxFuture<'static, ()> {
+        Box::pin(async move {
+            smol::Timer::at(deadline).await;
+        })
+    }
+
+    fn udp_socket(&self, socket: std::net::UdpSocket) -> io::Result<Box<dyn AsyncUdpSocket>> {
+        Ok(Box::new(Async::new(socket)?))
+    }
+
+    fn connect_tcp(
+        &self,
+        socket: std::net::TcpStream,
+        target: SocketAddr,
+    ) -> BoxFuture<'static, io::Result<Box<dyn AsyncTcpStream>>> {
+        Box::pin(async move {
+            let socket = socket2::Socket::from(socket);
+            socket.set_nonblocking(true)?;
+            match socket.connect(&target.into()) {
+                Ok(()) => {}
+                Err(error) if connect_in_progress(&error) => {}
+                Err(error) => return Err(error),
+            }
+            let stream = Async::new(std::net::TcpStream::from(socket))?;
+            // Writability signals completion; the pending error or a missing
+            // peer reports failure.
+            stream.writable().await?;
+            if let Some(error) = stream.get_ref().take_error()? {
+                return Err(error);
+            }
+            stream.get_ref().peer_addr()?;
+            Ok(Box::new(SmolTcpStream(stream)) as Box<dyn AsyncTcpStream>)
+        })
+    }
+
+    fn lookup_host(
+        &self,
+        host: String,
+        port: u16,
+    ) -> BoxFuture<'static, io::Result<Vec<SocketAddr>>> {
+        Box::pin(async move { smol::net::resolve((host.as_str(), port)).await })
+    }
+}
+
+/// Report whether a non-blocking connect is still in progress.
+///
+/// Unix reports `EINPROGRESS` and Windows `WSAEWOULDBLOCK`.
+fn connect_in_progress(error: &io::Error) -> bool {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "visionos",
+        target_os = "freebsd"
+    ))]
+    const EINPROGRESS: Option<i32> = Some(nix::errno::Errno::EINPROGRESS as i32);
+    #[cfg(any(target_os = "dragonfly", target_os = "netbsd", target_os = "openbsd"))]
+    const EINPROGRESS: Option<i32> = Some(libc::EINPROGRESS);
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "visionos",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    const EINPROGRESS: Option<i32> = None;
+
+    error.kind() == io::ErrorKind::WouldBlock
+        || (EINPROGRESS.is_some() && error.raw_os_error() == EINPROGRESS)
+}
+
+/// Wait until an async-io UDP socket may be ready for `interest`.
+pub(super) async fn udp_ready(
+    socket: &Async<std::net::UdpSocket>,
+    interest: Interest,
+) -> io::Result<()> {
+    match interest {
+        Interest::Readable => socket.readable().await,
+        Interest::Writable => socket.writable().await,
+    }
+}
+
+impl AsyncUdpSocket for Async<std::net::UdpSocket> {
+    fn ready(&self, interest: Interest) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(udp_ready(self, interest))
+    }
+
+    fn try_io(
+        &self,
+        _interest: Interest,
+        op: &mut dyn FnMut() -> io::Result<()>,
+    ) -> io::Result<()> {
+        // async-io re-arms interest on every readiness wait, so there is no
+        // cached readiness to clear.
+        op()
+    }
+
+    fn try_recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.get_ref().recv_from(buf)
+    }
+
+    fn try_send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        self.get_ref().send_to(buf, target)
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.get_ref().local_addr()
+    }
+
+    #[cfg(unix)]
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        std::os::fd::AsFd::as_fd(self.get_ref())
+    }
+
+    #[cfg(windows)]
+    fn as_socket(&self) -> std::os::windows::io::BorrowedSocket<'_> {
+        std::os::windows::io::AsSocket::as_socket(self.get_ref())
+    }
+}
+
+/// An async-io TCP stream exposed through Tokio's I/O traits.
+#[derive(Debug)]
+struct SmolTcpStream(Async<std::net::TcpStream>);
+
+impl tokio::io::AsyncRead for SmolTcpStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let unfilled = buf.initialize_unfilled();
+        let read = std::task::ready!(smol::io::AsyncRead::poll_read(
+            Pin::new(&mut self.0),
+            cx,
+            unfilled
+        ))?;
+        buf.advance(read);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl tokio::io::AsyncWrite for SmolTcpStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        smol::io::AsyncWrite::poll_write(Pin::new(&mut self.0), cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        smol::io::AsyncWrite::poll_flush(Pin::new(&mut self.0), cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        smol::io::AsyncWrite::poll_close(Pin::new(&mut self.0), cx)
+    }
+}
+
+impl AsyncTcpStream for SmolTcpStream {
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.get_ref().local_addr()
+    }
+}
@@ -0,0 +1,150 @@
+//! Runtime-independent task handles.
+//!
+//! [`Runtime::spawn`] only detaches a task, so cancellation, completion, and
+//! panic reporting are layered on top here with the same shape as Tokio's
+//! `JoinHandle` and `JoinSet`.
+
+use super::Runtime;
+use futures_util::FutureExt;
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use std::any::Any;
+use std::fmt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+/// Why a task did not produce its output.
+pub(crate) enum JoinError {
+    /// The task was aborted, or its runtime shut down first.
+    Cancelled,
+    /// The task panicked.
+    Panic(Box<dyn Any + Send + 'static>),
+}
+
+impl JoinError {
+    pub(crate) fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled)
+    }
+}
+
+impl fmt::Debug for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => f.write_str("task was cancelled"),
+            Self::Panic(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| payload.downcast_ref::<String>().map(String::as_str));
+                match message {
+                    Some(message) => write!(f, "task panicked with message {message:?}"),
+                    None => f.write_str("task panicked"),
+                }
+            }
+        }
+    }
+}
+
+/// Cancels a spawned task.
+#[derive(Clone, Debug)]
+pub(crate) struct AbortHandle {
+    cancel: CancellationToken,
+}
+
+impl AbortHandle {
+    /// Stop the task at its next await point.
+    pub(crate) fn abort(&self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Owned handle to a spawned task; dropping it detaches the task.
+pub(crate) struct JoinHandle<T> {
+    output: oneshot::Receiver<Result<T, JoinError>>,
+    abort: AbortHandle,
+}
+
+impl<T> JoinHandle<T> {
+    pub(crate) fn abort_handle(&self) -> AbortHandle {
+        self.abort.clone()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.output)
+            .poll(cx)
+            .map(|result| result.unwrap_or(Err(JoinError::Cancelled)))
+    }
+}
+
+pub(super) fn spawn_on<F>(runtime: &dyn Runtime, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let (sender, output) = oneshot::channel();
+    let cancel = CancellationToken::new();
+    let cancelled = cancel.clone();
+    runtime.spawn(Box::pin(async move {
+        let result = tokio::select! {
+            biased;
+            () = cancelled.cancelled() => Err(JoinError::Cancelled),
+            result = AssertUnwindSafe(future).catch_unwind() => result.map_err(JoinError::Panic),
+        };
+        let _ = sender.send(result);
+    }));
+    JoinHandle {
+        output,
+        abort: AbortHandle { cancel },
+    }
+}
+
+/// A set of spawned tasks awaited in completion order.
+pub(crate) struct JoinSet<T> {
+    tasks: FuturesUnordered<JoinHandle<T>>,
+}
+
+impl<T: Send + 'static> JoinSet<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            tasks: FuturesUnordered::new(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Spawn `future` into the set on the selected runtime.
+    pub(crate) fn spawn<F>(&mut self, future: F) -> AbortHandle
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let handle = super::spawn(future);
+        let abort = handle.abort_handle();
+        self.tasks.push(handle);
+        abort
+    }
+
+    /// Wait for the next task to finish; `None` when the set is empty.
+    pub(crate) async fn join_next(&mut self) -> Option<Result<T, JoinError>> {
+        self.tasks.next().await
+    }
+
+    /// Forget every task without aborting it.
+    pub(crate) fn detach_all(&mut self) {
+        self.tasks.clear();
+    }
+}
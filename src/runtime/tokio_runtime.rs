@@ -0,0 +1,105 @@
+//! Tokio implementation of [`Runtime`].
+
+use super::{AsyncTcpStream, AsyncUdpSocket, BoxFuture, Interest, Runtime};
+use std::io;
+use std::net::SocketAddr;
+
+/// The Tokio runtime.
+///
+/// Tasks, timers, and sockets use the Tokio runtime of the calling context, so
+/// using it outside one panics as Tokio does.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioRuntime;
+
+impl Runtime for TokioRuntime {
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        tokio::spawn(task);
+    }
+
+    fn sleep_until(&self, deadline: std::time::Instant) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep_until(deadline.into()))
+    }
+
+    fn udp_socket(&self, socket: std::net::UdpSocket) -> io::Result<Box<dyn AsyncUdpSocket>> {
+        Ok(Box::new(tokio::net::UdpSocket::from_std(socket)?))
+    }
+
+    fn connect_tcp(
+        &self,
+        socket: std::net::TcpStream,
+        target: SocketAddr,
+    ) -> BoxFuture<'static, io::Result<Box<dyn AsyncTcpStream>>> {
+        Box::pin(async move {
+            let stream = tokio::net::TcpSocket::from_std_stream(socket)
+                .connect(target)
+                .await?;
+            Ok(Box::new(stream) as Box<dyn AsyncTcpStream>)
+        })
+    }
+
+    fn lookup_host(
+        &self,
+        host: String,
+        port: u16,
+    ) -> BoxFuture<'static, io::Result<Vec<SocketAddr>>> {
+        Box::pin(async move {
+            tokio::net::lookup_host((host.as_str(), port))
+                .await
+                .map(Iterator::collect)
+        })
+    }
+}
+
+fn tokio_interest(interest: Interest) -> tokio::io::Interest {
+    match interest {
+        Interest::Readable => tokio::io::Interest::READABLE,
+        Interest::Writable => tokio::io::Interest::WRITABLE,
+    }
+}
+
+/// Wait until a Tokio UDP socket may be ready for `interest`.
+pub(super) async fn udp_ready(
+    socket: &tokio::net::UdpSocket,
+    interest: Interest,
+) -> io::Result<()> {
+    socket.ready(tokio_interest(interest)).await?;
+    Ok(())
+}
+
+impl AsyncUdpSocket for tokio::net::UdpSocket {
+    fn ready(&self, interest: Interest) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(udp_ready(self, interest))
+    }
+
+    fn try_io(&self, interest: Interest, op: &mut dyn FnMut() -> io::Result<()>) -> io::Result<()> {
+        tokio::net::UdpSocket::try_io(self, tokio_interest(interest), op)
+    }
+
+    fn try_recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        tokio::net::UdpSocket::try_recv_from(self, buf)
+    }
+
+    fn try_send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        tokio::net::UdpSocket::try_send_to(self, buf, target)
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        tokio::net::UdpSocket::local_addr(self)
+    }
+
+    #[cfg(unix)]
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        std::os::fd::AsFd::as_fd(self)
+    }
+
+    #[cfg(windows)]
+    fn as_socket(&self) -> std::os::windows::io::BorrowedSocket<'_> {
+        std::os::windows::io::AsSocket::as_socket(self)
+    }
+}
+
+impl AsyncTcpStream for tokio::net::TcpStream {
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        tokio::net::TcpStream::local_addr(self)
+    }
+}
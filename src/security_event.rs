@@ -0,0 +1,307 @@
+//! Structured security audit events from agents and notification receivers.
+//!
+//! The usmStats counters say how often a check failed; a [`SecurityEvent`]
+//! says who failed it, and from where. Subscribe with
+//! `Agent::subscribe_security_events` or
+//! [`NotificationReceiver::subscribe_security_events`](crate::NotificationReceiver::subscribe_security_events)
+//! and forward the events to a SIEM or audit log.
+//!
+//! Each subscriber has its own bounded queue. Message processing never waits
+//! for a subscriber: when a queue is full the event is discarded and counted
+//! in [`SecurityEventReceiver::dropped`].
+//!
+//! Community strings are secrets, and a rejected community is often a typo of
+//! the real one, so events carry a [`CommunityFingerprint`] instead of the
+//! octets. USM usernames are not secret and are reported as received.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use async_snmp::NotificationReceiver;
+//!
+//! # async fn example() -> async_snmp::Result<()> {
+//! let receiver = NotificationReceiver::bind("0.0.0.0:162").await?;
+//! let mut events = receiver.subscribe_security_events(1024);
+//! tokio::spawn(async move {
+//!     while let Some(event) = events.recv().await {
+//!         println!("{:?} from {}", event.kind(), event.source());
+//!     }
+//! });
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::hash::{BuildHasher, RandomState};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use bytes::Bytes;
+use tokio::sync::mpsc;
+
+/// The check a [`SecurityEvent`] failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SecurityEventKind {
+    /// A v1 or v2c message carried a community that is not configured.
+    BadCommunity,
+    /// USM user lookup failed (usmStatsUnknownUserNames).
+    UnknownUserName,
+    /// USM authentication failed (usmStatsWrongDigests).
+    WrongDigest,
+    /// An authenticated message was outside the time window
+    /// (usmStatsNotInTimeWindows, or RFC 3414 Step 7b for a remote engine).
+    NotInTimeWindow,
+    /// The user does not support the requested security level
+    /// (usmStatsUnsupportedSecLevels).
+    UnsupportedSecurityLevel,
+    /// The scoped PDU could not be decrypted (usmStatsDecryptionErrors).
+    DecryptionError,
+    /// VACM denied an authenticated request.
+    AccessDenied,
+    /// A request named a context this engine does not serve
+    /// (snmpUnknownContexts).
+    UnknownContext,
+}
+
+/// Keyed digest identifying a community without revealing it.
+///
+/// Fingerprints are stable for the life of one agent or receiver, so repeated
+/// attempts with the same community can be correlated. They are keyed with a
+/// per-process secret and cannot be compared across restarts or reversed by
+/// hashing candidate communities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommunityFingerprint(u64);
+
+impl fmt::Display for CommunityFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// The identity presented by the message that failed a check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SecurityPrincipal {
+    /// Fingerprint of the v1/v2c community.
+    Community(CommunityFingerprint),
+    /// USM username, as received.
+    User(Bytes),
+}
+
+/// One failed security check.
+#[derive(Debug, Clone)]
+pub struct SecurityEvent {
+    kind: SecurityEventKind,
+    source: SocketAddr,
+    principal: SecurityPrincipal,
+    timestamp: SystemTime,
+}
+
+impl SecurityEvent {
+    /// The check that failed.
+    #[must_use]
+    pub fn kind(&self) -> SecurityEventKind {
+        self.kind
+    }
+
+    /// Transport address the message came from.
+    #[must_use]
+    pub fn source(&self) -> SocketAddr {
+        self.source
+    }
+
+    /// Community fingerprint or username presented by the message.
+    #[must_use]
+    pub fn principal(&self) -> &SecurityPrincipal {
+        &self.principal
+    }
+
+    /// Wall-clock time the failure was observed.
+    #[must_use]
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+}
+
+/// Bounded stream of [`SecurityEvent`]s for one subscriber.
+#[derive(Debug)]
+pub struct SecurityEventReceiver {
+    events: mpsc::Receiver<SecurityEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl SecurityEventReceiver {
+    /// Wait for the next event.
+    ///
+    /// Returns `None` once the agent or receiver has been dropped and every
+    /// queued event has been received.
+    pub async fn recv(&mut self) -> Option<SecurityEvent> {
+        self.events.recv().await
+    }
+
+    /// Return the next queued event without waiting.
+    pub fn try_recv(&mut self) -> Option<SecurityEvent> {
+        self.events.try_recv().ok()
+    }
+
+    /// Number of events discarded because this subscriber's queue was full.
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+struct Subscriber {
+    events: mpsc::Sender<SecurityEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Fan-out of security events to every live subscriber.
+pub(crate) struct SecurityEventHub {
+    subscribers: Mutex<Vec<Subscriber>>,
+    fingerprint_key: RandomState,
+}
+
+impl SecurityEventHub {
+    pub(crate) fn new() -> Self {
+        Self {
+            subscribers: Mutex::new(Vec::new()),
+            fingerprint_key: RandomState::new(),
+        }
+    }
+
+    /// Add a subscriber with room for `capacity` queued events (at least one).
+    pub(crate) fn subscribe(&self, capacity: usize) -> SecurityEventReceiver {
+        let (events, receiver) = mpsc::channel(capacity.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Subscriber {
+                events,
+                dropped: Arc::clone(&dropped),
+            });
+        SecurityEventReceiver {
+            events: receiver,
+            dropped,
+        }
+    }
+
+    /// Record a failure attributed to a v1/v2c community.
+    pub(crate) fn community(&self, kind: SecurityEventKind, source: SocketAddr, community: &[u8]) {
+        self.publish(kind, source, || {
+            SecurityPrincipal::Community(CommunityFingerprint(
+                self.fingerprint_key.hash_one(community),
+            ))
+        });
+    }
+
+    /// Record a failure attributed to a USM username.
+    pub(crate) fn user(&self, kind: SecurityEventKind, source: SocketAddr, username: &[u8]) {
+        self.publish(kind, source, || {
+            SecurityPrincipal::User(Bytes::copy_from_slice(username))
+        });
+    }
+
+    fn publish(
+        &self,
+        kind: SecurityEventKind,
+        source: SocketAddr,
+        principal: impl FnOnce() -> SecurityPrincipal,
+    ) {
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        subscribers.retain(|subscriber| !subscriber.events.is_closed());
+        if subscribers.is_empty() {
+            return;
+        }
+        let event = SecurityEvent {
+            kind,
+            source,
+            principal: principal(),
+            timestamp: SystemTime::now(),
+        };
+        for subscriber in subscribers.iter() {
+            if subscriber.events.try_send(event.clone()).is_err() {
+                subscriber.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SecurityEventHub {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecurityEventHub")
+            .field(
+                "subscribers",
+                &self
+                    .subscribers
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .len(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source() -> SocketAddr {
+        "192.0.2.1:40000".parse().unwrap()
+    }
+
+    #[test]
+    fn full_queue_counts_dropped_events() {
+        let hub = SecurityEventHub::new();
+        let mut events = hub.subscribe(1);
+        hub.user(SecurityEventKind::WrongDigest, source(), b"admin");
+        hub.user(SecurityEventKind::WrongDigest, source(), b"admin");
+
+        let event = events.try_recv().expect("first event is queued");
+        assert_eq!(event.kind(), SecurityEventKind::WrongDigest);
+        assert_eq!(event.source(), source());
+        assert_eq!(
+            event.principal(),
+            &SecurityPrincipal::User(Bytes::from_static(b"admin"))
+        );
+        assert!(events.try_recv().is_none());
+        assert_eq!(events.dropped(), 1);
+    }
+
+    #[test]
+    fn community_fingerprints_hide_and_correlate_communities() {
+        let hub = SecurityEventHub::new();
+        let mut events = hub.subscribe(4);
+        hub.community(SecurityEventKind::BadCommunity, source(), b"publik");
+        hub.community(SecurityEventKind::BadCommunity, source(), b"publik");
+        hub.community(SecurityEventKind::BadCommunity, source(), b"private");
+
+        let fingerprints: Vec<_> = std::iter::from_fn(|| events.try_recv())
+            .map(|event| match event.principal() {
+                SecurityPrincipal::Community(fingerprint) => *fingerprint,
+                SecurityPrincipal::User(_) => panic!("expected a community fingerprint"),
+            })
+            .collect();
+        assert_eq!(fingerprints[0], fingerprints[1]);
+        assert_ne!(fingerprints[0], fingerprints[2]);
+        assert!(!format!("{:?}", fingerprints[0]).contains("publik"));
+    }
+
+    #[test]
+    fn dropped_subscribers_are_pruned() {
+        let hub = SecurityEventHub::new();
+        drop(hub.subscribe(1));
+        let mut live = hub.subscribe(1);
+        hub.user(SecurityEventKind::UnknownUserName, source(), b"guest");
+
+        assert!(live.try_recv().is_some());
+        assert_eq!(hub.subscribers.lock().unwrap().len(), 1);
+    }
+}
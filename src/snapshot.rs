@@ -0,0 +1,447 @@
+//! In-memory MIB snapshots.
+//!
+//! [`MibSnapshot`] holds the bindings returned by one or more walks as an
+//! OID-ordered tree. It answers exact and successor lookups, iterates
+//! subtrees, groups conceptual table rows by index, and compares two
+//! snapshots for drift detection.
+//!
+//! ```rust
+//! use async_snmp::snapshot::MibSnapshot;
+//! use async_snmp::{Value, VarBind, oid};
+//!
+//! let before: MibSnapshot = [
+//!     VarBind::new(oid!(1, 3, 6, 1, 2, 1, 1, 5, 0), Value::from("core-1")),
+//!     VarBind::new(oid!(1, 3, 6, 1, 2, 1, 1, 6, 0), Value::from("rack 4")),
+//! ]
+//! .into_iter()
+//! .collect();
+//!
+//! let after: MibSnapshot = [
+//!     VarBind::new(oid!(1, 3, 6, 1, 2, 1, 1, 5, 0), Value::from("core-1")),
+//!     VarBind::new(oid!(1, 3, 6, 1, 2, 1, 1, 6, 0), Value::from("rack 7")),
+//! ]
+//! .into_iter()
+//! .collect();
+//!
+//! let diff = before.diff(&after);
+//! assert_eq!(diff.changed.len(), 1);
+//! assert_eq!(diff.changed[0].oid, oid!(1, 3, 6, 1, 2, 1, 1, 6, 0));
+//! ```
+
+use std::collections::BTreeMap;
+use std::collections::btree_map;
+use std::ops::Bound;
+
+use crate::client::WalkCollection;
+use crate::oid::Oid;
+use crate::value::Value;
+use crate::varbind::VarBind;
+
+/// OID-ordered bindings collected from one or more walks.
+///
+/// Iteration and successor lookups use [`Oid`]'s lexicographic ordering, the
+/// same ordering agents use for GETNEXT. When the same OID is inserted more
+/// than once, the most recent value is retained, so later walks override
+/// earlier ones where they overlap.
+///
+/// Walk terminators such as [`Value::EndOfMibView`] are stored like any other
+/// value if they are inserted; walk streams do not yield them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MibSnapshot {
+    entries: BTreeMap<Oid, Value>,
+}
+
+impl MibSnapshot {
+    /// Create an empty snapshot.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a snapshot from several walks.
+    ///
+    /// Walks are applied in iteration order; overlapping OIDs keep the value
+    /// from the last walk that returned them.
+    pub fn from_walks<I>(walks: I) -> Self
+    where
+        I: IntoIterator<Item = WalkCollection>,
+    {
+        let mut snapshot = Self::new();
+        for walk in walks {
+            snapshot.extend(walk.varbinds);
+        }
+        snapshot
+    }
+
+    /// Insert a binding, returning the value it replaced.
+    pub fn insert(&mut self, oid: Oid, value: Value) -> Option<Value> {
+        self.entries.insert(oid, value)
+    }
+
+    /// Remove an OID, returning its value if it was present.
+    pub fn remove(&mut self, oid: &Oid) -> Option<Value> {
+        self.entries.remove(oid)
+    }
+
+    /// Returns the value for an exact OID match.
+    #[must_use]
+    pub fn get(&self, oid: &Oid) -> Option<&Value> {
+        self.entries.get(oid)
+    }
+
+    /// Returns the lexicographically next OID and value after `oid`.
+    ///
+    /// Like GETNEXT, an exact match is skipped. Returns `None` at the end of
+    /// the snapshot.
+    #[must_use]
+    pub fn get_next(&self, oid: &Oid) -> Option<(&Oid, &Value)> {
+        self.entries
+            .range((Bound::Excluded(oid), Bound::Unbounded))
+            .next()
+    }
+
+    /// Iterate over the bindings at or below `prefix` in OID order.
+    ///
+    /// The prefix itself is included when it holds a value.
+    pub fn subtree<'a>(&'a self, prefix: &Oid) -> impl Iterator<Item = (&'a Oid, &'a Value)> + 'a {
+        let prefix = prefix.clone();
+        self.entries
+            .range(prefix.clone()..)
+            .take_while(move |(oid, _)| oid.starts_with(&prefix))
+    }
+
+    /// Group the instances below a conceptual table entry into rows.
+    ///
+    /// `entry` is the table entry OID, such as `ifEntry`
+    /// (`1.3.6.1.2.1.2.2.1`). Each instance `entry.column.index` contributes
+    /// its value to the row for `index` under `column`. Rows are returned in
+    /// index order. Instances with no index arcs are skipped.
+    #[must_use]
+    pub fn table(&self, entry: &Oid) -> Vec<TableRow<'_>> {
+        let mut rows: BTreeMap<Oid, BTreeMap<u32, &Value>> = BTreeMap::new();
+        for (oid, value) in self.subtree(entry) {
+            let arcs = &oid.arcs()[entry.len()..];
+            let [column, index @ ..] = arcs else {
+                continue;
+            };
+            if index.is_empty() {
+                continue;
+            }
+            rows.entry(Oid::from_slice(index))
+                .or_default()
+                .insert(*column, value);
+        }
+        rows.into_iter()
+            .map(|(index, columns)| TableRow { index, columns })
+            .collect()
+    }
+
+    /// Compare this snapshot with a newer one.
+    ///
+    /// OIDs only in `other` are reported as added, OIDs only in `self` as
+    /// removed, and OIDs present in both with unequal values as changed. Each
+    /// list is in OID order.
+    #[must_use]
+    pub fn diff(&self, other: &MibSnapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+        let mut old = self.entries.iter().peekable();
+        let mut new = other.entries.iter().peekable();
+
+        loop {
+            match (old.peek(), new.peek()) {
+                (Some((old_oid, _)), Some((new_oid, _))) => match old_oid.cmp(new_oid) {
+                    std::cmp::Ordering::Less => {
+                        let (oid, value) = old.next().expect("peeked");
+                        diff.removed.push(VarBind::new(oid.clone(), value.clone()));
+                    }
+                    std::cmp::Ordering::Greater => {
+                        let (oid, value) = new.next().expect("peeked");
+                        diff.added.push(VarBind::new(oid.clone(), value.clone()));
+                    }
+                    std::cmp::Ordering::Equal => {
+                        let (oid, old_value) = old.next().expect("peeked");
+                        let (_, new_value) = new.next().expect("peeked");
+                        if old_value != new_value {
+                            diff.changed.push(ChangedValue {
+                                oid: oid.clone(),
+                                old: old_value.clone(),
+                                new: new_value.clone(),
+                            });
+                        }
+                    }
+                },
+                (Some(_), None) => {
+                    let (oid, value) = old.next().expect("peeked");
+                    diff.removed.push(VarBind::new(oid.clone(), value.clone()));
+                }
+                (None, Some(_)) => {
+                    let (oid, value) = new.next().expect("peeked");
+                    diff.added.push(VarBind::new(oid.clone(), value.clone()));
+                }
+                (None, None) => break,
+            }
+        }
+        diff
+    }
+
+    /// Returns the number of bindings.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the snapshot is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over all bindings in OID order.
+    pub fn iter(&self) -> btree_map::Iter<'_, Oid, Value> {
+        self.entries.iter()
+    }
+
+    /// Returns the bindings as a vector in OID order.
+    #[must_use]
+    pub fn to_varbinds(&self) -> Vec<VarBind> {
+        self.entries
+            .iter()
+            .map(|(oid, value)| VarBind::new(oid.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl From<WalkCollection> for MibSnapshot {
+    fn from(walk: WalkCollection) -> Self {
+        walk.varbinds.into_iter().collect()
+    }
+}
+
+impl From<Vec<VarBind>> for MibSnapshot {
+    fn from(varbinds: Vec<VarBind>) -> Self {
+        varbinds.into_iter().collect()
+    }
+}
+
+impl FromIterator<VarBind> for MibSnapshot {
+    /// Build a snapshot, retaining the last value for duplicate OIDs.
+    fn from_iter<T: IntoIterator<Item = VarBind>>(iter: T) -> Self {
+        let mut snapshot = Self::new();
+        snapshot.extend(iter);
+        snapshot
+    }
+}
+
+impl Extend<VarBind> for MibSnapshot {
+    /// Add bindings, retaining the last value for duplicate OIDs.
+    fn extend<T: IntoIterator<Item = VarBind>>(&mut self, iter: T) {
+        self.entries
+            .extend(iter.into_iter().map(|vb| (vb.oid, vb.value)));
+    }
+}
+
+impl IntoIterator for MibSnapshot {
+    type Item = (Oid, Value);
+    type IntoIter = btree_map::IntoIter<Oid, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a MibSnapshot {
+    type Item = (&'a Oid, &'a Value);
+    type IntoIter = btree_map::Iter<'a, Oid, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// One conceptual table row grouped by [`MibSnapshot::table`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRow<'a> {
+    /// Index arcs following the column arc.
+    pub index: Oid,
+    /// Column values keyed by column arc.
+    pub columns: BTreeMap<u32, &'a Value>,
+}
+
+impl<'a> TableRow<'a> {
+    /// Returns the value for a column arc.
+    #[must_use]
+    pub fn get(&self, column: u32) -> Option<&'a Value> {
+        self.columns.get(&column).copied()
+    }
+}
+
+/// Difference between two snapshots, from [`MibSnapshot::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// Bindings present only in the newer snapshot.
+    pub added: Vec<VarBind>,
+    /// Bindings present only in the older snapshot.
+    pub removed: Vec<VarBind>,
+    /// OIDs present in both snapshots with different values.
+    pub changed: Vec<ChangedValue>,
+}
+
+impl SnapshotDiff {
+    /// Returns whether the snapshots were identical.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// An OID whose value differs between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedValue {
+    /// The object identifier.
+    pub oid: Oid,
+    /// Value in the older snapshot.
+    pub old: Value,
+    /// Value in the newer snapshot.
+    pub new: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ResponseMetadata;
+    use crate::oid;
+
+    fn snapshot(bindings: &[(Oid, Value)]) -> MibSnapshot {
+        bindings
+            .iter()
+            .cloned()
+            .map(|(oid, value)| VarBind::new(oid, value))
+            .collect()
+    }
+
+    #[test]
+    fn get_next_skips_exact_match_and_uses_oid_order() {
+        let snapshot = snapshot(&[
+            (oid!(1, 3, 6, 1, 2, 1, 1, 10, 0), Value::Integer(10)),
+            (oid!(1, 3, 6, 1, 2, 1, 1, 9, 0), Value::Integer(9)),
+            (oid!(1, 3, 6, 1, 2, 1, 1, 1, 0), Value::Integer(1)),
+        ]);
+
+        assert_eq!(
+            snapshot.get(&oid!(1, 3, 6, 1, 2, 1, 1, 9, 0)),
+            Some(&Value::Integer(9))
+        );
+        let (next, value) = snapshot.get_next(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).unwrap();
+        assert_eq!(next, &oid!(1, 3, 6, 1, 2, 1, 1, 9, 0));
+        assert_eq!(value, &Value::Integer(9));
+        let (next, _) = snapshot.get_next(&oid!(1, 3, 6, 1, 2, 1, 1, 9)).unwrap();
+        assert_eq!(next, &oid!(1, 3, 6, 1, 2, 1, 1, 9, 0));
+        assert!(
+            snapshot
+                .get_next(&oid!(1, 3, 6, 1, 2, 1, 1, 10, 0))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn subtree_is_bounded_by_prefix() {
+        let snapshot = snapshot(&[
+            (oid!(1, 3, 6, 1, 2, 1, 1, 1, 0), Value::Integer(1)),
+            (oid!(1, 3, 6, 1, 2, 1, 2, 1, 0), Value::Integer(2)),
+            (oid!(1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 1), Value::Integer(3)),
+            (oid!(1, 3, 6, 1, 2, 1, 3, 1, 0), Value::Integer(4)),
+        ]);
+
+        let prefix = oid!(1, 3, 6, 1, 2, 1, 2);
+        let oids: Vec<_> = snapshot
+            .subtree(&prefix)
+            .map(|(oid, _)| oid.clone())
+            .collect();
+        assert_eq!(
+            oids,
+            vec![
+                oid!(1, 3, 6, 1, 2, 1, 2, 1, 0),
+                oid!(1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn table_groups_columns_by_index() {
+        let entry = oid!(1, 3, 6, 1, 2, 1, 2, 2, 1);
+        let snapshot = snapshot(&[
+            (entry.child(1).child(1), Value::Integer(1)),
+            (entry.child(1).child(2), Value::Integer(2)),
+            (entry.child(2).child(1), Value::from("lo")),
+            (entry.child(2).child(2), Value::from("eth0")),
+            (entry.clone(), Value::Null),
+            (entry.child(3), Value::Null),
+        ]);
+
+        let rows = snapshot.table(&entry);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].index, oid!(1));
+        assert_eq!(rows[0].get(2), Some(&Value::from("lo")));
+        assert_eq!(rows[1].index, oid!(2));
+        assert_eq!(rows[1].get(1), Some(&Value::Integer(2)));
+        assert_eq!(rows[1].get(3), None);
+    }
+
+    #[test]
+    fn later_walks_override_earlier_values() {
+        let first = WalkCollection {
+            varbinds: vec![VarBind::new(
+                oid!(1, 3, 6, 1, 2, 1, 1, 3, 0),
+                Value::TimeTicks(1),
+            )],
+            metadata: ResponseMetadata::default(),
+        };
+        let second = WalkCollection {
+            varbinds: vec![VarBind::new(
+                oid!(1, 3, 6, 1, 2, 1, 1, 3, 0),
+                Value::TimeTicks(2),
+            )],
+            metadata: ResponseMetadata::default(),
+        };
+
+        let snapshot = MibSnapshot::from_walks([first, second]);
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(
+            snapshot.get(&oid!(1, 3, 6, 1, 2, 1, 1, 3, 0)),
+            Some(&Value::TimeTicks(2))
+        );
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed() {
+        let old = snapshot(&[
+            (oid!(1, 1), Value::Integer(1)),
+            (oid!(1, 2), Value::Integer(2)),
+            (oid!(1, 3), Value::Integer(3)),
+        ]);
+        let new = snapshot(&[
+            (oid!(1, 2), Value::Integer(2)),
+            (oid!(1, 3), Value::Integer(30)),
+            (oid!(1, 4), Value::Integer(4)),
+        ]);
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.removed,
+            vec![VarBind::new(oid!(1, 1), Value::Integer(1))]
+        );
+        assert_eq!(
+            diff.added,
+            vec![VarBind::new(oid!(1, 4), Value::Integer(4))]
+        );
+        assert_eq!(
+            diff.changed,
+            vec![ChangedValue {
+                oid: oid!(1, 3),
+                old: Value::Integer(3),
+                new: Value::Integer(30),
+            }]
+        );
+        assert!(old.diff(&old).is_empty());
+    }
+}
@@ -41,7 +41,7 @@ pub(crate) fn community_request_context_with(
     request_id: i32,
     pdu_type: PduType,
 ) -> RequestContext {
-    RequestContext::community(source, version, community, request_id, pdu_type, Vec::new())
+    RequestContext::from_community(source, version, community, request_id, pdu_type, Vec::new())
 }
 
 pub(crate) fn usm_request_context(
@@ -0,0 +1,90 @@
+//! Known-answer checks for [`CryptoProvider`] implementations.
+
+use crate::format::hex::encode;
+use crate::v3::{AuthProtocol, CipherParams, CryptoProvider, PrivProtocol};
+
+/// Engine ID the RFC 3414 Appendix A.3 keys are localized to.
+const ENGINE_ID: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+const MESSAGE: &[u8; 24] = b"async-snmp provider KAT!";
+const PARAMS: CipherParams = CipherParams {
+    engine_boots: 1,
+    engine_time: 2,
+    salt: [0, 1, 2, 3, 4, 5, 6, 7],
+};
+
+/// Check a provider against HMAC and cipher known-answer vectors.
+///
+/// Keys are localized from the RFC 3414 Appendix A.3 password "maplesyrup"
+/// and used only through [`CryptoProvider`], so the check applies to
+/// providers that keep key material in an HSM or PKCS#11 token. The SHA-1
+/// HMAC and AES-128 vectors always run; `legacy` adds MD5 and DES for
+/// providers that support them.
+///
+/// ```rust
+/// # #[cfg(feature = "crypto-rustcrypto")]
+/// async_snmp::testing::check_crypto_provider(&async_snmp::v3::CryptoBackend::RustCrypto, true);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the provider fails an operation or produces a wrong result.
+pub fn check_crypto_provider(provider: &dyn CryptoProvider, legacy: bool) {
+    let mut auth_vectors = vec![(AuthProtocol::Sha1, "55e6d8d4a1d3598779549b64")];
+    if legacy {
+        auth_vectors.push((AuthProtocol::Md5, "9c4714eae9f21b1f9c96f36e"));
+    }
+    for (protocol, expected) in auth_vectors {
+        let key = provider
+            .localize_auth_key(protocol, b"maplesyrup", &ENGINE_ID)
+            .unwrap_or_else(|error| panic!("{protocol:?} key localization failed: {error}"));
+        let mac = provider
+            .compute_hmac(&key, protocol, &[&MESSAGE[..10], &MESSAGE[10..]])
+            .unwrap_or_else(|error| panic!("{protocol:?} HMAC failed: {error}"));
+        assert_eq!(encode(&mac), expected, "{protocol:?} HMAC mismatch");
+        assert!(
+            provider
+                .verify_hmac(&key, protocol, &[MESSAGE], &mac)
+                .unwrap_or_else(|error| panic!("{protocol:?} HMAC verification failed: {error}")),
+            "{protocol:?} HMAC verification rejected a valid tag"
+        );
+        let mut forged = mac.clone();
+        forged[0] ^= 1;
+        assert!(
+            !provider
+                .verify_hmac(&key, protocol, &[MESSAGE], &forged)
+                .unwrap_or_else(|error| panic!("{protocol:?} HMAC verification failed: {error}")),
+            "{protocol:?} HMAC verification accepted a forged tag"
+        );
+    }
+
+    let mut priv_vectors = vec![(
+        AuthProtocol::Sha1,
+        PrivProtocol::Aes128,
+        "faa67953e5f8bd491ce52550cdd7378ee2acfcc4a960044d",
+    )];
+    if legacy {
+        priv_vectors.push((
+            AuthProtocol::Md5,
+            PrivProtocol::Des,
+            "4590a4f9f0c27c95830939178e0acea66f9582a6aa591bfb",
+        ));
+    }
+    for (auth_protocol, priv_protocol, expected) in priv_vectors {
+        let key = provider
+            .localize_priv_key(auth_protocol, priv_protocol, b"maplesyrup", &ENGINE_ID)
+            .unwrap_or_else(|error| panic!("{priv_protocol:?} key localization failed: {error}"));
+        let mut data = MESSAGE.to_vec();
+        provider
+            .encrypt(&key, priv_protocol, &PARAMS, &mut data)
+            .unwrap_or_else(|error| panic!("{priv_protocol:?} encryption failed: {error}"));
+        assert_eq!(
+            encode(&data),
+            expected,
+            "{priv_protocol:?} ciphertext mismatch"
+        );
+        provider
+            .decrypt(&key, priv_protocol, &PARAMS, &mut data)
+            .unwrap_or_else(|error| panic!("{priv_protocol:?} decryption failed: {error}"));
+        assert_eq!(&data[..], MESSAGE, "{priv_protocol:?} decryption mismatch");
+    }
+}
@@ -0,0 +1,596 @@
+//! Fault-injecting transport wrapper.
+
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::error::{Error, Result};
+use crate::message_size::ReceiveLimits;
+use crate::transport::{Candidate, RequestRegistration, ResponseIdentity, Transport};
+
+/// Seed used when [`FaultyTransportBuilder::seed`] is not called.
+const DEFAULT_SEED: u64 = 0x5eed_5eed_5eed_5eed;
+
+/// Cumulative [`FaultyTransport`] counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FaultStats {
+    /// Requests passed to the wrapper, including dropped ones.
+    pub requests: u64,
+    /// Requests discarded before reaching the wrapped transport.
+    pub dropped_requests: u64,
+    /// Requests sent after an injected delay.
+    pub delayed_requests: u64,
+    /// Response candidates discarded before validation.
+    pub dropped_responses: u64,
+    /// Response candidates queued for redelivery as late duplicates.
+    pub duplicated_responses: u64,
+    /// Response candidates held back and delivered after a later response.
+    pub reordered_responses: u64,
+    /// Response candidates with one bit flipped.
+    pub corrupted_responses: u64,
+    /// Response candidates shortened.
+    pub truncated_responses: u64,
+    /// Response candidates delivered with a spoofed source address.
+    pub spoofed_responses: u64,
+}
+
+/// Builder for [`FaultyTransport`].
+///
+/// Probabilities are clamped to `0.0..=1.0`. Every fault is disabled by
+/// default.
+#[derive(Debug, Clone)]
+pub struct FaultyTransportBuilder<T> {
+    inner: T,
+    seed: u64,
+    config: FaultConfig,
+}
+
+#[derive(Debug, Clone, Default)]
+struct FaultConfig {
+    drop_first_requests: u64,
+    drop_requests: f64,
+    delay: Option<(Duration, Duration)>,
+    drop_responses: f64,
+    duplicate_responses: f64,
+    reorder_responses: f64,
+    flip_bits: f64,
+    truncate_responses: f64,
+    spoof_source: Option<(f64, SocketAddr)>,
+}
+
+impl<T: Transport> FaultyTransportBuilder<T> {
+    /// Seed for the fault schedule.
+    ///
+    /// The same seed, configuration, and request sequence reproduce the same
+    /// faults.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Discard the first `count` requests unconditionally.
+    #[must_use]
+    pub fn drop_first_requests(mut self, count: u64) -> Self {
+        self.config.drop_first_requests = count;
+        self
+    }
+
+    /// Discard requests with this probability before they are sent.
+    #[must_use]
+    pub fn drop_requests(mut self, probability: f64) -> Self {
+        self.config.drop_requests = clamp(probability);
+        self
+    }
+
+    /// Delay each request by a uniformly chosen duration in `min..=max`.
+    ///
+    /// Delays count against the request deadline, so a delay past the
+    /// deadline times the request out without sending it.
+    #[must_use]
+    pub fn delay(mut self, min: Duration, max: Duration) -> Self {
+        self.config.delay = Some((min, max.max(min)));
+        self
+    }
+
+    /// Discard response candidates with this probability.
+    #[must_use]
+    pub fn drop_responses(mut self, probability: f64) -> Self {
+        self.config.drop_responses = clamp(probability);
+        self
+    }
+
+    /// Deliver response candidates and queue a copy with this probability.
+    ///
+    /// The copy is delivered ahead of the next response candidate, as a late
+    /// duplicate from an earlier exchange.
+    #[must_use]
+    pub fn duplicate_responses(mut self, probability: f64) -> Self {
+        self.config.duplicate_responses = clamp(probability);
+        self
+    }
+
+    /// Hold response candidates back with this probability.
+    ///
+    /// A held response is not delivered to its own exchange; it arrives
+    /// ahead of the next response candidate instead, such as the response
+    /// to a retry.
+    #[must_use]
+    pub fn reorder_responses(mut self, probability: f64) -> Self {
+        self.config.reorder_responses = clamp(probability);
+        self
+    }
+
+    /// Flip one random bit of response candidates with this probability.
+    #[must_use]
+    pub fn flip_bits(mut self, probability: f64) -> Self {
+        self.config.flip_bits = clamp(probability);
+        self
+    }
+
+    /// Cut response candidates to a random shorter length with this
+    /// probability.
+    #[must_use]
+    pub fn truncate_responses(mut self, probability: f64) -> Self {
+        self.config.truncate_responses = clamp(probability);
+        self
+    }
+
+    /// Report `source` as the sender of response candidates with this
+    /// probability.
+    ///
+    /// Spoofed candidates are re-checked against the request's response
+    /// identity as coming from a non-target source, then passed to the
+    /// validator with the spoofed address. Source filtering performed inside
+    /// the wrapped transport, such as
+    /// [`UdpHandle::strict_source`](crate::UdpHandle::strict_source), has
+    /// already run against the real source.
+    #[must_use]
+    pub fn spoof_source(mut self, probability: f64, source: SocketAddr) -> Self {
+        self.config.spoof_source = Some((clamp(probability), source));
+        self
+    }
+
+    /// Build the wrapper.
+    #[must_use]
+    pub fn build(self) -> FaultyTransport<T> {
+        FaultyTransport {
+            inner: self.inner,
+            shared: Arc::new(FaultShared {
+                config: self.config,
+                state: Mutex::new(FaultState {
+                    rng: SplitMix64(self.seed),
+                    held: VecDeque::new(),
+                }),
+                stats: AtomicStats::default(),
+            }),
+        }
+    }
+}
+
+/// Transport wrapper that injects reproducible network faults.
+///
+/// Request faults apply before the wrapped transport sends; response faults
+/// apply to each candidate the wrapped transport delivers for validation, so
+/// they exercise the same correlation and decoding paths as real network
+/// faults. All random choices come from a seeded generator, so a failing test
+/// reproduces exactly when rerun with the same seed and request sequence.
+///
+/// Clones share the fault schedule, held responses, and counters.
+#[derive(Debug, Clone)]
+pub struct FaultyTransport<T> {
+    inner: T,
+    shared: Arc<FaultShared>,
+}
+
+#[derive(Debug)]
+struct FaultShared {
+    config: FaultConfig,
+    state: Mutex<FaultState>,
+    stats: AtomicStats,
+}
+
+#[derive(Debug)]
+struct FaultState {
+    rng: SplitMix64,
+    /// Duplicated or reordered responses awaiting redelivery.
+    held: VecDeque<Bytes>,
+}
+
+#[derive(Debug, Default)]
+struct AtomicStats {
+    requests: AtomicU64,
+    dropped_requests: AtomicU64,
+    delayed_requests: AtomicU64,
+    dropped_responses: AtomicU64,
+    duplicated_responses: AtomicU64,
+    reordered_responses: AtomicU64,
+    corrupted_responses: AtomicU64,
+    truncated_responses: AtomicU64,
+    spoofed_responses: AtomicU64,
+}
+
+/// What happens to the current request.
+struct RequestPlan {
+    drop: bool,
+    delay: Duration,
+}
+
+/// What happens to one response candidate.
+enum Delivery {
+    Discard,
+    Deliver(Bytes, SocketAddr),
+}
+
+impl<T: Transport> FaultyTransport<T> {
+    /// Create a builder wrapping `inner`.
+    pub fn builder(inner: T) -> FaultyTransportBuilder<T> {
+        FaultyTransportBuilder {
+            inner,
+            seed: DEFAULT_SEED,
+            config: FaultConfig::default(),
+        }
+    }
+
+    /// Return the wrapped transport.
+    #[must_use]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Return a snapshot of the fault counters.
+    #[must_use]
+    pub fn stats(&self) -> FaultStats {
+        let stats = &self.shared.stats;
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        FaultStats {
+            requests: load(&stats.requests),
+            dropped_requests: load(&stats.dropped_requests),
+            delayed_requests: load(&stats.delayed_requests),
+            dropped_responses: load(&stats.dropped_responses),
+            duplicated_responses: load(&stats.duplicated_responses),
+            reordered_responses: load(&stats.reordered_responses),
+            corrupted_responses: load(&stats.corrupted_responses),
+            truncated_responses: load(&stats.truncated_responses),
+            spoofed_responses: load(&stats.spoofed_responses),
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, FaultState> {
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    fn plan_request(&self) -> RequestPlan {
+        let config = &self.shared.config;
+        let stats = &self.shared.stats;
+        let sequence = stats.requests.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut state = self.lock_state();
+        let drop = sequence <= config.drop_first_requests || state.rng.chance(config.drop_requests);
+        let delay = config
+            .delay
+            .map_or(Duration::ZERO, |(min, max)| state.rng.duration(min, max));
+        if drop {
+            stats.dropped_requests.fetch_add(1, Ordering::Relaxed);
+        }
+        if !delay.is_zero() {
+            stats.delayed_requests.fetch_add(1, Ordering::Relaxed);
+        }
+        RequestPlan { drop, delay }
+    }
+
+    fn plan_response(&self, mut response: Bytes, mut source: SocketAddr) -> Delivery {
+        let config = &self.shared.config;
+        let stats = &self.shared.stats;
+        let mut state = self.lock_state();
+
+        if state.rng.chance(config.drop_responses) {
+            stats.dropped_responses.fetch_add(1, Ordering::Relaxed);
+            return Delivery::Discard;
+        }
+        if state.rng.chance(config.reorder_responses) {
+            stats.reordered_responses.fetch_add(1, Ordering::Relaxed);
+            state.held.push_back(response);
+            return Delivery::Discard;
+        }
+        if state.rng.chance(config.duplicate_responses) {
+            stats.duplicated_responses.fetch_add(1, Ordering::Relaxed);
+            state.held.push_back(response.clone());
+        }
+        if !response.is_empty() && state.rng.chance(config.truncate_responses) {
+            stats.truncated_responses.fetch_add(1, Ordering::Relaxed);
+            let len = state.rng.below(response.len() as u64) as usize;
+            response.truncate(len);
+        }
+        if !response.is_empty() && state.rng.chance(config.flip_bits) {
+            stats.corrupted_responses.fetch_add(1, Ordering::Relaxed);
+            let bit = state.rng.below(response.len() as u64 * 8) as usize;
+            let mut corrupted = response.to_vec();
+            corrupted[bit / 8] ^= 1 << (bit % 8);
+            response = Bytes::from(corrupted);
+        }
+        if let Some((probability, spoofed)) = config.spoof_source
+            && state.rng.chance(probability)
+        {
+            stats.spoofed_responses.fetch_add(1, Ordering::Relaxed);
+            source = spoofed;
+        }
+        Delivery::Deliver(response, source)
+    }
+
+    async fn expire(
+        &self,
+        deadline: tokio::time::Instant,
+        started: tokio::time::Instant,
+    ) -> Box<Error> {
+        crate::runtime::sleep_until(deadline).await;
+        Error::Timeout {
+            target: self.inner.peer_addr(),
+            elapsed: started.elapsed(),
+            retries: 0,
+        }
+        .boxed()
+    }
+}
+
+impl<T: Transport> Transport for FaultyTransport<T> {
+    async fn send(&self, data: &[u8]) -> Result<()> {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity())?;
+        let plan = self.plan_request();
+        crate::runtime::sleep(plan.delay).await;
+        if plan.drop {
+            return Ok(());
+        }
+        self.inner.send(data).await
+    }
+
+    async fn request_with<U, F>(
+        &self,
+        data: &[u8],
+        registration: RequestRegistration,
+        mut validate: F,
+    ) -> Result<U>
+    where
+        U: Send,
+        F: FnMut(Bytes, SocketAddr) -> Result<Candidate<U>> + Send,
+    {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity())?;
+        let started = tokio::time::Instant::now();
+        let deadline = registration.deadline();
+        let plan = self.plan_request();
+        if !plan.delay.is_zero() {
+            if started + plan.delay >= deadline {
+                return Err(self.expire(deadline, started).await);
+            }
+            crate::runtime::sleep(plan.delay).await;
+        }
+        if plan.drop {
+            registration.mark_sent();
+            return Err(self.expire(deadline, started).await);
+        }
+
+        let identity = registration.clone();
+        let target = self.inner.peer_addr();
+        self.inner
+            .request_with(data, registration, |response, source| {
+                // Responses held from earlier exchanges arrive first and go
+                // through the same identity check a transport would apply.
+                let held: Vec<Bytes> = self.lock_state().held.drain(..).collect();
+                for stale in held {
+                    if identity.evaluate_response_identity(&stale, true) == ResponseIdentity::Reject
+                    {
+                        continue;
+                    }
+                    if let Candidate::Accept(value) = validate(stale, source)? {
+                        return Ok(Candidate::Accept(value));
+                    }
+                }
+
+                match self.plan_response(response, source) {
+                    Delivery::Discard => Ok(Candidate::Reject),
+                    Delivery::Deliver(response, source) => {
+                        if source != target
+                            && identity.evaluate_response_identity(&response, false)
+                                == ResponseIdentity::Reject
+                        {
+                            return Ok(Candidate::Reject);
+                        }
+                        validate(response, source)
+                    }
+                }
+            })
+            .await
+    }
+
+    fn peer_addr(&self) -> SocketAddr {
+        self.inner.peer_addr()
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        self.inner.local_addr()
+    }
+
+    fn alloc_request_id(&self) -> i32 {
+        self.inner.alloc_request_id()
+    }
+
+    fn is_reliable(&self) -> bool {
+        self.inner.is_reliable()
+    }
+
+    fn receive_limits(&self) -> ReceiveLimits {
+        self.inner.receive_limits()
+    }
+
+    fn send_capacity(&self) -> usize {
+        self.inner.send_capacity()
+    }
+
+    fn transport_security(&self) -> Option<crate::v3::TransportSecurity> {
+        self.inner.transport_security()
+    }
+}
+
+fn clamp(probability: f64) -> f64 {
+    if probability.is_nan() {
+        0.0
+    } else {
+        probability.clamp(0.0, 1.0)
+    }
+}
+
+/// SplitMix64: small, fast, and stable across releases, so seeds reproduce.
+#[derive(Debug)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in `0.0..1.0`.
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Draw only for enabled faults, so enabling one fault does not shift
+    /// the schedule of faults that stay disabled.
+    fn chance(&mut self, probability: f64) -> bool {
+        probability > 0.0 && self.unit() < probability
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    fn duration(&mut self, min: Duration, max: Duration) -> Duration {
+        let span = (max - min).as_nanos() as u64;
+        if span == 0 {
+            return min;
+        }
+        min + Duration::from_nanos(self.next_u64() % (span + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{Auth, ClientBuilder, Retry};
+    use crate::oid;
+    use crate::transport::ReplayTransport;
+    use crate::value::Value;
+    use crate::varbind::VarBind;
+
+    fn replay() -> ReplayTransport {
+        ReplayTransport::new(vec![VarBind::new(
+            oid!(1, 3, 6, 1, 2, 1, 1, 5, 0),
+            Value::from("core-1"),
+        )])
+    }
+
+    fn client(
+        transport: FaultyTransport<ReplayTransport>,
+        retries: u32,
+    ) -> crate::Client<FaultyTransport<ReplayTransport>> {
+        ClientBuilder::new(Auth::v2c("public"))
+            .request_timeout(Duration::from_secs(1))
+            .retry(Retry::fixed(retries, Duration::ZERO).unwrap())
+            .build_with_transport(transport)
+            .unwrap()
+    }
+
+    async fn outcomes(seed: u64) -> (Vec<bool>, FaultStats) {
+        let faulty = FaultyTransport::builder(replay())
+            .seed(seed)
+            .drop_requests(0.3)
+            .flip_bits(0.3)
+            .truncate_responses(0.2)
+            .build();
+        let client = client(faulty.clone(), 0);
+        let mut results = Vec::new();
+        for _ in 0..20 {
+            results.push(client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 5, 0)).await.is_ok());
+        }
+        (results, faulty.stats())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn same_seed_reproduces_the_same_faults() {
+        let (first, stats) = outcomes(7).await;
+        assert_eq!(outcomes(7).await, (first.clone(), stats));
+        assert!(first.contains(&true) && first.contains(&false));
+        assert_eq!(stats.requests, 20);
+        assert!(stats.dropped_requests > 0);
+        assert_ne!(outcomes(8).await.0, first);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reordered_response_completes_the_retry() {
+        let faulty = FaultyTransport::builder(replay())
+            .reorder_responses(1.0)
+            .build();
+        let client = client(faulty.clone(), 1);
+
+        // The first response is held; the retry's response triggers delivery
+        // of the held one, which matches the first attempt's request ID alias.
+        let (result, elapsed) =
+            crate::testing::measure(client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 5, 0))).await;
+        assert!(result.is_ok());
+        assert_eq!(elapsed, Duration::from_secs(1));
+        assert_eq!(faulty.stats().reordered_responses, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn late_duplicates_do_not_satisfy_new_requests() {
+        let faulty = FaultyTransport::builder(replay())
+            .duplicate_responses(1.0)
+            .build();
+        let client = client(faulty.clone(), 0);
+        for _ in 0..3 {
+            let response = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 5, 0)).await.unwrap();
+            assert_eq!(response.single().unwrap().value.as_str(), Some("core-1"));
+        }
+        assert_eq!(faulty.stats().duplicated_responses, 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn spoofed_source_and_delay_are_observable() {
+        let spoofed: SocketAddr = "192.0.2.66:161".parse().unwrap();
+        let faulty = FaultyTransport::builder(replay())
+            .spoof_source(1.0, spoofed)
+            .delay(Duration::from_millis(200), Duration::from_millis(200))
+            .build();
+        let client = client(faulty.clone(), 0);
+
+        let (result, elapsed) =
+            crate::testing::measure(client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 5, 0))).await;
+        assert!(result.is_ok());
+        assert_eq!(elapsed, Duration::from_millis(200));
+        let stats = faulty.stats();
+        assert_eq!((stats.spoofed_responses, stats.delayed_requests), (1, 1));
+
+        let slow = FaultyTransport::builder(replay())
+            .delay(Duration::from_secs(2), Duration::from_secs(3))
+            .build();
+        let error = client_for_timeout(slow).await;
+        assert!(matches!(*error, Error::Timeout { .. }));
+    }
+
+    async fn client_for_timeout(transport: FaultyTransport<ReplayTransport>) -> Box<Error> {
+        client(transport, 0)
+            .get(&oid!(1, 3, 6, 1, 2, 1, 1, 5, 0))
+            .await
+            .unwrap_err()
+    }
+}
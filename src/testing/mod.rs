@@ -0,0 +1,77 @@
+//! Test utilities for applications built on this crate.
+//!
+//! Enabled by the `testing` feature. [`FaultyTransport`] wraps any
+//! [`Transport`](crate::Transport) and injects seeded, reproducible network
+//! faults; the paused-clock helpers run those tests on Tokio's virtual clock
+//! so retry and timeout schedules complete instantly and deterministically.
+//! [`check_crypto_provider`] runs known-answer vectors against a custom
+//! [`CryptoProvider`](crate::v3::CryptoProvider).
+//!
+//! Wrapping a [`ReplayTransport`](crate::ReplayTransport) keeps a test free of
+//! sockets entirely. Real sockets still work, but Tokio auto-advances a paused
+//! clock whenever the runtime is idle, including while a datagram is in
+//! flight, so timeouts can fire before a real peer answers.
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use async_snmp::testing::{self, FaultyTransport};
+//! use async_snmp::{Auth, ClientBuilder, ReplayTransport, Retry, Value, VarBind, oid};
+//!
+//! testing::block_on_paused(async {
+//!     let data = vec![VarBind::new(oid!(1, 3, 6, 1, 2, 1, 1, 5, 0), Value::from("core-1"))];
+//!     let faulty = FaultyTransport::builder(ReplayTransport::new(data))
+//!         .drop_first_requests(2)
+//!         .build();
+//!     let client = ClientBuilder::new(Auth::v2c("public"))
+//!         .request_timeout(Duration::from_secs(1))
+//!         .retry(Retry::fixed(2, Duration::ZERO).unwrap())
+//!         .build_with_transport(faulty.clone())
+//!         .unwrap();
+//!
+//!     let (result, elapsed) = testing::measure(client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 5, 0))).await;
+//!     assert!(result.is_ok());
+//!     assert_eq!(elapsed, Duration::from_secs(2));
+//!     assert_eq!(faulty.stats().dropped_requests, 2);
+//! });
+//! ```
+
+mod crypto;
+mod faulty;
+
+pub use crypto::*;
+pub use faulty::*;
+
+use std::future::Future;
+use std::time::Duration;
+
+/// Build a current-thread runtime whose clock starts paused.
+///
+/// # Panics
+///
+/// Panics if the runtime cannot be created.
+#[must_use]
+pub fn paused_runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .start_paused(true)
+        .build()
+        .expect("failed to build paused Tokio runtime")
+}
+
+/// Run `future` to completion on a fresh [`paused_runtime`].
+///
+/// Inside async tests, `#[tokio::test(start_paused = true)]` is equivalent.
+pub fn block_on_paused<F: Future>(future: F) -> F::Output {
+    paused_runtime().block_on(future)
+}
+
+/// Await `future` and return its output with the elapsed Tokio time.
+///
+/// On a paused clock the duration is exact virtual time, which makes timeout
+/// and backoff schedules assertable with `assert_eq!`.
+pub async fn measure<F: Future>(future: F) -> (F::Output, Duration) {
+    let started = tokio::time::Instant::now();
+    let output = future.await;
+    (output, started.elapsed())
+}
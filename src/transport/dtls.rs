@@ -0,0 +1,1187 @@
+//! DTLS transport (TLSTM, RFC 6353) for SNMP clients.
+//!
+//! [`DtlsTransport`] carries one BER-encoded SNMP message per DTLS record on a
+//! connected UDP socket. Response correlation is shared with
+//! [`UdpHandle`](super::UdpHandle): concurrent requests are matched by
+//! request ID and timeouts are retried. DTLS 1.2 is provided by OpenSSL.
+//!
+//! # Server authentication
+//!
+//! The server is authenticated after the handshake with the same RFC 6353
+//! Section 5.3.1 policy as [`TlsTransport`](super::TlsTransport):
+//!
+//! - **Fingerprint pinning** ([`pin_server_fingerprint`](DtlsTransportBuilder::pin_server_fingerprint)):
+//!   the session is accepted when a pin matches the server's certificate.
+//!   A pin may instead name a CA certificate, presented in the chain or
+//!   configured as a trust anchor; the chain and the server name are then
+//!   validated against that CA.
+//! - **Certificate path validation**: with no pins, the server's chain must
+//!   validate against the configured trust anchors and name the
+//!   [`server_name`](DtlsTransportBuilder::server_name).
+//!
+//! A rejected server has its session closed before any SNMP message is sent.
+//! Fingerprints are computed with the crate's crypto backend. Without
+//! `crypto-rustcrypto` or `crypto-fips`, no pin matches and connections that
+//! rely on pinning or certToTSN fail closed.
+//!
+//! # Security names
+//!
+//! A [`CertToTsn`] table maps the server's certificate chain to a
+//! `tmSecurityName`, reported through [`Transport::transport_security`]. When
+//! a table is configured and no entry maps the chain, the session is closed
+//! and construction fails with [`Error::Auth`].
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use async_snmp::transport::DtlsTransport;
+//! use async_snmp::{ClientBuilder, TsmConfig};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let transport = DtlsTransport::builder()
+//!     .server_name("agent.example.com")
+//!     .trust_anchors_pem(std::fs::read("ca.pem")?)
+//!     .client_identity_pem(std::fs::read("manager.pem")?, std::fs::read("manager.key")?)
+//!     .connect("192.0.2.1:10161".parse()?)
+//!     .await?;
+//!
+//! let client = ClientBuilder::new(TsmConfig::new("manager")).build_with_transport(transport)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::tcp::tcp_deadline;
+use super::udp_core::UdpCore;
+use super::udp_error::{UdpRecvErrorBackoff, UdpRecvErrorClass, classify_udp_recv_error};
+use super::{Candidate, RequestRegistration, SocketOptions, Transport, extract_request_id};
+use crate::error::{ConstructionStage, Error, Result};
+use crate::message::SecurityLevel;
+use crate::message_size::ReceiveLimits;
+use crate::runtime::{Interest, UdpSocket};
+use crate::util::connected_udp_socket;
+use crate::v3::{CertToTsn, CertificateFingerprint, TransportSecurity};
+use bytes::Bytes;
+use openssl::pkey::{PKey, Private};
+use openssl::ssl::{
+    ErrorCode, Ssl, SslContext, SslMethod, SslRef, SslStream, SslVerifyMode, SslVersion,
+};
+use openssl::stack::Stack;
+use openssl::x509::store::X509StoreBuilder;
+use openssl::x509::verify::{X509VerifyFlags, X509VerifyParam};
+use openssl::x509::{X509, X509StoreContext};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+/// `tmSecurityName` prefix for DTLS sessions (RFC 6353 Section 5.1).
+const DTLS_TRANSPORT_PREFIX: &str = "dtls";
+
+/// Largest plaintext a single DTLS record carries (RFC 6347 Section 4.1).
+///
+/// RFC 6353 Section 5.1.1 places each SNMP message in one record.
+const MAX_DTLS_MESSAGE_SIZE: usize = 16_384;
+
+/// Default path MTU for handshake fragmentation; leaves room for IPv6 and
+/// tunnel headers on a 1500-byte link.
+const DEFAULT_MTU: u32 = 1400;
+
+/// How often a stalled handshake is resumed so OpenSSL can retransmit a lost
+/// flight once its timer expires.
+const HANDSHAKE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Certificate or key material as supplied to the builder.
+#[derive(Clone)]
+enum Material {
+    Der(Vec<u8>),
+    Pem(Vec<u8>),
+}
+
+/// Identity the server certificate must name.
+enum ServerIdentity {
+    Dns(String),
+    Ip(IpAddr),
+}
+
+/// Builder for [`DtlsTransport`].
+///
+/// At least one trust anchor or server fingerprint pin is required.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::transport::DtlsTransport;
+/// use async_snmp::v3::{CertToTsn, CertToTsnMapping, CertificateFingerprint};
+/// use std::time::Duration;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let pin: CertificateFingerprint = "sha256:4F:1A:...".parse()?;
+/// let transport = DtlsTransport::builder()
+///     .connect_timeout(Duration::from_secs(5))
+///     .pin_server_fingerprint(pin.clone())
+///     .cert_to_tsn(CertToTsn::new().entry(pin, CertToTsnMapping::SanDnsName))
+///     .connect("192.0.2.1:10161".parse()?)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DtlsTransportBuilder {
+    connect_timeout: Option<Duration>,
+    max_message_size: usize,
+    send_capacity: Option<usize>,
+    mtu: u32,
+    socket_options: SocketOptions,
+    server_name: Option<String>,
+    trust_anchors: Vec<Material>,
+    pins: Vec<CertificateFingerprint>,
+    client_identity: Option<(Vec<Material>, Material)>,
+    cert_to_tsn: Option<CertToTsn>,
+}
+
+impl DtlsTransportBuilder {
+    /// Create a builder with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            connect_timeout: None,
+            max_message_size: MAX_DTLS_MESSAGE_SIZE,
+            send_capacity: None,
+            mtu: DEFAULT_MTU,
+            socket_options: SocketOptions::new(),
+            server_name: None,
+            trust_anchors: Vec::new(),
+            pins: Vec::new(),
+            client_identity: None,
+            cert_to_tsn: None,
+        }
+    }
+
+    /// Bound the DTLS handshake by one total timeout.
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum total encoded size for incoming messages.
+    ///
+    /// Default and upper bound: 16384 bytes, the largest DTLS record.
+    #[must_use]
+    pub fn max_message_size(mut self, size: usize) -> Self {
+        self.max_message_size = size;
+        self
+    }
+
+    /// Set the maximum exact encoded size for outgoing messages.
+    ///
+    /// Default: the same as the incoming limit.
+    #[must_use]
+    pub fn send_capacity(mut self, size: usize) -> Self {
+        self.send_capacity = Some(size);
+        self
+    }
+
+    /// Set the largest UDP payload used for handshake messages.
+    ///
+    /// Larger handshake messages, such as certificate chains, are fragmented
+    /// to fit. Default: 1400 bytes.
+    #[must_use]
+    pub fn mtu(mut self, mtu: u32) -> Self {
+        self.mtu = mtu;
+        self
+    }
+
+    /// Set interface, source address, and IP header options for the socket.
+    #[must_use]
+    pub fn socket_options(mut self, options: SocketOptions) -> Self {
+        self.socket_options = options;
+        self
+    }
+
+    /// Set the expected server identity, sent as SNI when it is a DNS name.
+    ///
+    /// Default: the target's IP address.
+    #[must_use]
+    pub fn server_name(mut self, name: impl Into<String>) -> Self {
+        self.server_name = Some(name.into());
+        self
+    }
+
+    /// Trust a DER-encoded CA certificate for certificate path validation.
+    #[must_use]
+    pub fn trust_anchor_der(mut self, certificate: impl Into<Vec<u8>>) -> Self {
+        self.trust_anchors.push(Material::Der(certificate.into()));
+        self
+    }
+
+    /// Trust every certificate in a PEM bundle for certificate path validation.
+    ///
+    /// The bundle is parsed by [`connect`](Self::connect).
+    #[must_use]
+    pub fn trust_anchors_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.trust_anchors.push(Material::Pem(pem.into()));
+        self
+    }
+
+    /// Accept a server whose certificate, or one of its CAs, has this
+    /// fingerprint (`snmpTlstmAddrServerFingerprint`).
+    #[must_use]
+    pub fn pin_server_fingerprint(mut self, fingerprint: CertificateFingerprint) -> Self {
+        self.pins.push(fingerprint);
+        self
+    }
+
+    /// Present a client certificate chain and its DER-encoded private key.
+    ///
+    /// The chain starts with the client's own certificate.
+    #[must_use]
+    pub fn client_identity_der(mut self, chain: Vec<Vec<u8>>, private_key: Vec<u8>) -> Self {
+        self.client_identity = Some((
+            chain.into_iter().map(Material::Der).collect(),
+            Material::Der(private_key),
+        ));
+        self
+    }
+
+    /// Present a PEM client certificate chain and PEM private key.
+    ///
+    /// Both are parsed by [`connect`](Self::connect).
+    #[must_use]
+    pub fn client_identity_pem(
+        mut self,
+        chain: impl Into<Vec<u8>>,
+        private_key: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.client_identity = Some((
+            vec![Material::Pem(chain.into())],
+            Material::Pem(private_key.into()),
+        ));
+        self
+    }
+
+    /// Map the server's certificate chain to a `tmSecurityName`.
+    ///
+    /// Sessions whose chain matches no entry are rejected.
+    #[must_use]
+    pub fn cert_to_tsn(mut self, table: CertToTsn) -> Self {
+        self.cert_to_tsn = Some(table);
+        self
+    }
+
+    /// Complete the DTLS handshake with the target address.
+    pub async fn connect(self, target: SocketAddr) -> Result<DtlsTransport> {
+        let started = tokio::time::Instant::now();
+        let deadline = self
+            .connect_timeout
+            .map(|timeout| tcp_deadline(timeout, "DTLS connect timeout"))
+            .transpose()?;
+        let send_capacity = self.send_capacity.unwrap_or(self.max_message_size);
+        if self.max_message_size > MAX_DTLS_MESSAGE_SIZE || send_capacity > MAX_DTLS_MESSAGE_SIZE {
+            return Err(Error::Config(
+                format!("DTLS messages are limited to {MAX_DTLS_MESSAGE_SIZE} bytes").into(),
+            )
+            .boxed());
+        }
+        let receive_limits = ReceiveLimits::tcp(self.max_message_size)
+            .map_err(|error| Error::Config(error.to_string().into()).boxed())?;
+        let identity = match &self.server_name {
+            Some(name) => match name.parse() {
+                Ok(ip) => ServerIdentity::Ip(ip),
+                Err(_) => ServerIdentity::Dns(name.clone()),
+            },
+            None => ServerIdentity::Ip(target.ip()),
+        };
+        let mut trust_anchors = Vec::new();
+        for material in &self.trust_anchors {
+            trust_anchors.extend(parse_certificates(material)?);
+        }
+        if trust_anchors.is_empty() && self.pins.is_empty() {
+            return Err(Error::Config(
+                "DTLS transport requires a trust anchor or a server fingerprint".into(),
+            )
+            .boxed());
+        }
+        let context = self.client_context()?;
+        let mut ssl = Ssl::new(&context).map_err(dtls_config_error)?;
+        ssl.set_mtu(self.mtu).map_err(dtls_config_error)?;
+        if let ServerIdentity::Dns(name) = &identity {
+            ssl.set_hostname(name).map_err(dtls_config_error)?;
+        }
+
+        let network_error = |source| Error::Network { target, source }.boxed();
+        let std_socket =
+            connected_udp_socket(target, &self.socket_options).map_err(network_error)?;
+        let io = std_socket.try_clone().map_err(network_error)?;
+        let socket = Arc::new(UdpSocket::from_std(std_socket).map_err(network_error)?);
+        let local_addr = socket.local_addr().map_err(network_error)?;
+        let io = DatagramIo {
+            socket: Arc::clone(&socket),
+            io,
+        };
+        let stream = SslStream::new(ssl, io).map_err(dtls_config_error)?;
+        let handshake = handshake(stream, &socket);
+        let mut stream = match deadline {
+            Some(deadline) => crate::runtime::timeout_at(deadline, handshake)
+                .await
+                .map_err(|_| {
+                    Error::ConstructionTimeout {
+                        target: target.into(),
+                        stage: ConstructionStage::Handshake,
+                        elapsed: started.elapsed(),
+                    }
+                    .boxed()
+                })?,
+            None => handshake.await,
+        }
+        .map_err(network_error)?;
+
+        let chain = peer_chain(stream.ssl());
+        if let Err(reason) = self.verify_server(&chain, &identity, &trust_anchors) {
+            tracing::warn!(target: "async_snmp::transport::dtls", { %target, %reason }, "server certificate rejected");
+            let _ = stream.shutdown();
+            return Err(network_error(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("server certificate rejected: {reason}"),
+            )));
+        }
+        let peer_certificates = chain
+            .iter()
+            .map(|certificate| certificate.to_der().map(Bytes::from))
+            .collect::<std::result::Result<Arc<[Bytes]>, _>>()
+            .map_err(|error| network_error(io::Error::other(error)))?;
+        let mut security = TransportSecurity::new(DTLS_TRANSPORT_PREFIX, SecurityLevel::AuthPriv);
+        if let Some(table) = &self.cert_to_tsn {
+            let Some(name) = table.security_name(&peer_certificates) else {
+                tracing::warn!(target: "async_snmp::transport::dtls", { %target }, "server certificate maps to no tmSecurityName");
+                let _ = stream.shutdown();
+                return Err(Error::Auth { target }.boxed());
+            };
+            security = security.with_peer_security_name(name);
+        }
+
+        tracing::debug!(target: "async_snmp::transport::dtls", { %target, %local_addr }, "DTLS session established");
+        Ok(DtlsTransport {
+            inner: Arc::new(DtlsTransportInner::start(
+                stream,
+                socket,
+                target,
+                local_addr,
+                receive_limits,
+                send_capacity,
+            )),
+            peer_certificates,
+            security,
+        })
+    }
+
+    fn client_context(&self) -> Result<SslContext> {
+        let mut builder =
+            SslContext::builder(SslMethod::dtls_client()).map_err(dtls_config_error)?;
+        builder
+            .set_min_proto_version(Some(SslVersion::DTLS1_2))
+            .map_err(dtls_config_error)?;
+        // The server chain is checked against the RFC 6353 policy once the
+        // handshake completes, so OpenSSL's own verification stays off.
+        builder.set_verify(SslVerifyMode::NONE);
+        if let Some((chain, key)) = &self.client_identity {
+            let mut certificates = Vec::new();
+            for material in chain {
+                certificates.extend(parse_certificates(material)?);
+            }
+            let mut certificates = certificates.into_iter();
+            let leaf = certificates.next().ok_or_else(|| {
+                Error::Config("DTLS client certificate chain is empty".into()).boxed()
+            })?;
+            builder.set_certificate(&leaf).map_err(dtls_config_error)?;
+            for certificate in certificates {
+                builder
+                    .add_extra_chain_cert(certificate)
+                    .map_err(dtls_config_error)?;
+            }
+            let key = parse_private_key(key)?;
+            builder.set_private_key(&key).map_err(dtls_config_error)?;
+            builder.check_private_key().map_err(dtls_config_error)?;
+        }
+        Ok(builder.build())
+    }
+
+    /// Server certificate policy of RFC 6353 Section 5.3.1.
+    fn verify_server(
+        &self,
+        chain: &[X509],
+        identity: &ServerIdentity,
+        trust_anchors: &[X509],
+    ) -> std::result::Result<(), String> {
+        let Some(leaf) = chain.first() else {
+            return Err("no certificate presented".into());
+        };
+        if self.pins.is_empty() {
+            return verify_path(trust_anchors, chain, identity);
+        }
+        // A pinned server certificate stands on its own, self-signed or not.
+        if self.pinned(leaf) {
+            return Ok(());
+        }
+        // A pinned CA becomes the sole trust anchor for path validation.
+        let anchors: Vec<X509> = chain[1..]
+            .iter()
+            .chain(trust_anchors)
+            .filter(|certificate| self.pinned(certificate))
+            .cloned()
+            .collect();
+        if anchors.is_empty() {
+            return Err("no pinned fingerprint matches the certificate chain".into());
+        }
+        verify_path(&anchors, chain, identity)
+    }
+
+    fn pinned(&self, certificate: &X509) -> bool {
+        certificate
+            .to_der()
+            .is_ok_and(|der| self.pins.iter().any(|pin| pin.matches(&der)))
+    }
+}
+
+impl Default for DtlsTransportBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for DtlsTransportBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DtlsTransportBuilder")
+            .field("connect_timeout", &self.connect_timeout)
+            .field("max_message_size", &self.max_message_size)
+            .field("send_capacity", &self.send_capacity)
+            .field("mtu", &self.mtu)
+            .field("server_name", &self.server_name)
+            .field("trust_anchors", &self.trust_anchors.len())
+            .field("pins", &self.pins)
+            .field("client_identity", &self.client_identity.is_some())
+            .field("cert_to_tsn", &self.cert_to_tsn)
+            .finish()
+    }
+}
+
+fn dtls_config_error(error: impl std::fmt::Display) -> Box<Error> {
+    Error::Config(format!("invalid DTLS configuration: {error}").into()).boxed()
+}
+
+fn parse_certificates(material: &Material) -> Result<Vec<X509>> {
+    match material {
+        Material::Der(der) => Ok(vec![X509::from_der(der).map_err(dtls_config_error)?]),
+        Material::Pem(pem) => X509::stack_from_pem(pem).map_err(dtls_config_error),
+    }
+}
+
+fn parse_private_key(material: &Material) -> Result<PKey<Private>> {
+    match material {
+        Material::Der(der) => PKey::private_key_from_der(der),
+        Material::Pem(pem) => PKey::private_key_from_pem(pem),
+    }
+    .map_err(dtls_config_error)
+}
+
+/// Certificate chain presented by the server, leaf first.
+fn peer_chain(ssl: &SslRef) -> Vec<X509> {
+    ssl.peer_cert_chain()
+        .map(|chain| chain.iter().map(ToOwned::to_owned).collect())
+        .unwrap_or_default()
+}
+
+/// Validate `chain` against `anchors` and check that it names `identity`.
+fn verify_path(
+    anchors: &[X509],
+    chain: &[X509],
+    identity: &ServerIdentity,
+) -> std::result::Result<(), String> {
+    let error = |error: openssl::error::ErrorStack| error.to_string();
+    let mut store = X509StoreBuilder::new().map_err(error)?;
+    for anchor in anchors {
+        store.add_cert(anchor.clone()).map_err(error)?;
+    }
+    // Any configured or pinned certificate is a trust anchor, not only
+    // self-signed roots.
+    store
+        .set_flags(X509VerifyFlags::PARTIAL_CHAIN)
+        .map_err(error)?;
+    let mut param = X509VerifyParam::new().map_err(error)?;
+    match identity {
+        ServerIdentity::Dns(name) => param.set_host(name),
+        ServerIdentity::Ip(ip) => param.set_ip(*ip),
+    }
+    .map_err(error)?;
+    store.set_param(&param).map_err(error)?;
+    let store = store.build();
+
+    let mut intermediates = Stack::new().map_err(error)?;
+    for certificate in &chain[1..] {
+        intermediates.push(certificate.clone()).map_err(error)?;
+    }
+    let mut context = X509StoreContext::new().map_err(error)?;
+    let failure = context
+        .init(&store, &chain[0], &intermediates, |context| {
+            Ok((!context.verify_cert()?).then(|| context.error()))
+        })
+        .map_err(error)?;
+    match failure {
+        Some(result) => Err(result.error_string().to_owned()),
+        None => Ok(()),
+    }
+}
+
+/// Drive the client handshake to completion on a non-blocking socket.
+async fn handshake(
+    mut stream: SslStream<DatagramIo>,
+    socket: &UdpSocket,
+) -> io::Result<SslStream<DatagramIo>> {
+    let mut attempt = stream.connect();
+    loop {
+        let error = match attempt {
+            Ok(()) => return Ok(stream),
+            Err(error) => error,
+        };
+        match error.code() {
+            ErrorCode::WANT_WRITE => socket.writable().await?,
+            ErrorCode::WANT_READ => {
+                if let Ok(ready) =
+                    crate::runtime::timeout(HANDSHAKE_POLL_INTERVAL, socket.readable()).await
+                {
+                    ready?;
+                }
+            }
+            _ => return Err(session_io_error(error)),
+        }
+        attempt = stream.do_handshake();
+    }
+}
+
+fn session_io_error(error: openssl::ssl::Error) -> io::Error {
+    error.into_io_error().unwrap_or_else(io::Error::other)
+}
+
+/// Synchronous datagram I/O for OpenSSL on a registered, connected socket.
+///
+/// Each call moves one datagram and reports [`io::ErrorKind::WouldBlock`]
+/// when the socket is not ready, which OpenSSL surfaces as `WANT_READ` or
+/// `WANT_WRITE`.
+#[derive(Debug)]
+struct DatagramIo {
+    socket: Arc<UdpSocket>,
+    io: std::net::UdpSocket,
+}
+
+impl io::Read for DatagramIo {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.socket.try_io(Interest::Readable, || self.io.recv(buf)) {
+                // ICMP errors are unauthenticated, so DTLS ignores them.
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        io::ErrorKind::ConnectionRefused | io::ErrorKind::ConnectionReset
+                    ) => {}
+                result => return result,
+            }
+        }
+    }
+}
+
+impl io::Write for DatagramIo {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.socket.try_io(Interest::Writable, || self.io.send(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct DtlsTransportInner {
+    session: Arc<Mutex<SslStream<DatagramIo>>>,
+    socket: Arc<UdpSocket>,
+    core: Arc<UdpCore>,
+    target: SocketAddr,
+    local_addr: SocketAddr,
+    receive_limits: ReceiveLimits,
+    send_capacity: usize,
+    // Cancels the recv task when the last transport clone drops. The task
+    // holds no reference to this struct.
+    _shutdown_guard: DropGuard,
+}
+
+fn lock(session: &Mutex<SslStream<DatagramIo>>) -> MutexGuard<'_, SslStream<DatagramIo>> {
+    session.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+impl DtlsTransportInner {
+    fn start(
+        stream: SslStream<DatagramIo>,
+        socket: Arc<UdpSocket>,
+        target: SocketAddr,
+        local_addr: SocketAddr,
+        receive_limits: ReceiveLimits,
+        send_capacity: usize,
+    ) -> Self {
+        let session = Arc::new(Mutex::new(stream));
+        let core = Arc::new(UdpCore::new());
+        let shutdown = CancellationToken::new();
+
+        let recv_session = Arc::clone(&session);
+        let recv_socket = Arc::clone(&socket);
+        let recv_core = Arc::clone(&core);
+        let recv_shutdown = shutdown.clone();
+        crate::runtime::spawn(async move {
+            let mut buf = vec![0u8; MAX_DTLS_MESSAGE_SIZE];
+            let mut next_cleanup = tokio::time::Instant::now() + CLEANUP_INTERVAL;
+            let mut cleanup_timer = std::pin::pin!(crate::runtime::sleep_until(next_cleanup));
+            let mut recv_error_backoff = UdpRecvErrorBackoff::default();
+            'recv: loop {
+                tokio::select! {
+                    biased;
+
+                    () = recv_shutdown.cancelled() => break,
+
+                    () = &mut cleanup_timer => {
+                        recv_core.cleanup_expired();
+                        next_cleanup += CLEANUP_INTERVAL;
+                        cleanup_timer.set(crate::runtime::sleep_until(next_cleanup));
+                        continue;
+                    }
+
+                    result = recv_socket.readable() => if let Err(error) = result {
+                        tracing::error!(target: "async_snmp::transport::dtls", { %error }, "DTLS socket failed");
+                        break;
+                    },
+                }
+
+                // Drain every record already queued on the socket.
+                loop {
+                    let result = lock(&recv_session).ssl_read(&mut buf);
+                    let error = match result {
+                        Ok(len) => {
+                            recv_error_backoff.reset();
+                            deliver(&recv_core, &buf[..len], receive_limits, target);
+                            continue;
+                        }
+                        Err(error) => error,
+                    };
+                    match error.code() {
+                        ErrorCode::WANT_READ | ErrorCode::WANT_WRITE => break,
+                        ErrorCode::ZERO_RETURN => {
+                            tracing::debug!(target: "async_snmp::transport::dtls", { %target }, "agent closed the DTLS session");
+                            break 'recv;
+                        }
+                        ErrorCode::SYSCALL => {
+                            let error = session_io_error(error);
+                            if classify_udp_recv_error(&error) == UdpRecvErrorClass::Fatal {
+                                tracing::error!(target: "async_snmp::transport::dtls", { %error }, "fatal DTLS recv error");
+                                break 'recv;
+                            }
+                            let delay = recv_error_backoff.advance();
+                            tracing::warn!(target: "async_snmp::transport::dtls", { %error, backoff = ?delay }, "transient DTLS recv error");
+                            tokio::select! {
+                                biased;
+                                () = recv_shutdown.cancelled() => break 'recv,
+                                () = crate::runtime::sleep(delay) => break,
+                            }
+                        }
+                        _ => {
+                            tracing::warn!(target: "async_snmp::transport::dtls", { %target, %error }, "DTLS session failed");
+                            break 'recv;
+                        }
+                    }
+                }
+            }
+            let _ = lock(&recv_session).shutdown();
+            recv_core.close();
+        });
+
+        Self {
+            session,
+            socket,
+            core,
+            target,
+            local_addr,
+            receive_limits,
+            send_capacity,
+            _shutdown_guard: shutdown.drop_guard(),
+        }
+    }
+
+    async fn send(&self, data: &[u8]) -> Result<()> {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity)?;
+        tracing::trace!(target: "async_snmp::transport::dtls", { snmp.bytes = data.len() }, "DTLS send");
+        loop {
+            let result = lock(&self.session).ssl_write(data);
+            match result {
+                Ok(_) => return Ok(()),
+                Err(error) if error.code() == ErrorCode::WANT_WRITE => {
+                    self.socket.writable().await.map_err(|source| {
+                        Error::Network {
+                            target: self.target,
+                            source,
+                        }
+                        .boxed()
+                    })?;
+                }
+                Err(error) => {
+                    return Err(Error::Network {
+                        target: self.target,
+                        source: session_io_error(error),
+                    }
+                    .boxed());
+                }
+            }
+        }
+    }
+
+    async fn request_with<T, F>(
+        &self,
+        data: &[u8],
+        registration: RequestRegistration,
+        validate: F,
+    ) -> Result<T>
+    where
+        F: FnMut(Bytes, SocketAddr) -> Result<Candidate<T>>,
+    {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity)?;
+        let registration = self.core.register(registration, self.target, true)?;
+        let deadline = registration.deadline();
+        if tokio::time::Instant::now() >= deadline {
+            return Err(registration.timeout_error(self.target));
+        }
+        tokio::select! {
+            biased;
+            () = crate::runtime::sleep_until(deadline) => {
+                return Err(registration.timeout_error(self.target));
+            }
+            result = self.send(data) => result?,
+        }
+        registration.mark_sent();
+        self.core
+            .wait_for_response_with(&registration, self.target, validate)
+            .await
+    }
+}
+
+/// Hand one decrypted message to its waiting request.
+fn deliver(core: &UdpCore, message: &[u8], receive_limits: ReceiveLimits, target: SocketAddr) {
+    if message.len() > receive_limits.accepted() {
+        core.note_malformed();
+        tracing::debug!(target: "async_snmp::transport::dtls", { snmp.bytes = message.len() }, "response exceeds the receive limit");
+        return;
+    }
+    let data = Bytes::copy_from_slice(message);
+    if let Some(request_id) = extract_request_id(&data) {
+        if !core.deliver(request_id, data, target) {
+            tracing::debug!(target: "async_snmp::transport::dtls", { snmp.request_id = request_id }, "response for unknown request");
+        }
+    } else {
+        core.note_malformed();
+        tracing::debug!(target: "async_snmp::transport::dtls", { snmp.bytes = message.len() }, "malformed response (no request_id)");
+    }
+}
+
+/// DTLS transport for a single target (RFC 6353 TLSTM).
+///
+/// Behaves like [`UdpHandle`](super::UdpHandle) on an authenticated,
+/// encrypted session: concurrent requests are correlated by request ID and
+/// the client retries timeouts. The session always provides `authPriv`, so
+/// it can carry [`TsmConfig`](crate::TsmConfig) clients at any security
+/// level.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::transport::DtlsTransport;
+/// use async_snmp::{Auth, ClientBuilder, TsmConfig};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let transport = DtlsTransport::builder()
+///     .trust_anchors_pem(std::fs::read("ca.pem")?)
+///     .connect("192.0.2.1:10161".parse()?)
+///     .await?;
+///
+/// let client = ClientBuilder::new(Auth::Tsm(TsmConfig::new("monitor")))
+///     .build_with_transport(transport)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DtlsTransport {
+    inner: Arc<DtlsTransportInner>,
+    peer_certificates: Arc<[Bytes]>,
+    security: TransportSecurity,
+}
+
+impl DtlsTransport {
+    /// Create a builder for custom configuration.
+    #[must_use]
+    pub fn builder() -> DtlsTransportBuilder {
+        DtlsTransportBuilder::new()
+    }
+
+    /// DER-encoded certificate chain presented by the server, leaf first.
+    pub fn peer_certificates(&self) -> &[Bytes] {
+        &self.peer_certificates
+    }
+}
+
+impl std::fmt::Debug for DtlsTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DtlsTransport")
+            .field("target", &self.inner.target)
+            .field("local_addr", &self.inner.local_addr)
+            .field("security", &self.security)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Transport for DtlsTransport {
+    async fn send(&self, data: &[u8]) -> Result<()> {
+        self.inner.send(data).await
+    }
+
+    async fn send_with_timeout(&self, data: &[u8], timeout: Duration) -> Result<()> {
+        match crate::runtime::timeout(timeout, self.inner.send(data)).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::Timeout {
+                target: self.inner.target,
+                elapsed: timeout,
+                retries: 0,
+            }
+            .boxed()),
+        }
+    }
+
+    async fn request_with<T, F>(
+        &self,
+        data: &[u8],
+        registration: RequestRegistration,
+        validate: F,
+    ) -> Result<T>
+    where
+        T: Send,
+        F: FnMut(Bytes, SocketAddr) -> Result<Candidate<T>> + Send,
+    {
+        self.inner.request_with(data, registration, validate).await
+    }
+
+    fn peer_addr(&self) -> SocketAddr {
+        self.inner.target
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        self.inner.local_addr
+    }
+
+    fn is_reliable(&self) -> bool {
+        false
+    }
+
+    fn receive_limits(&self) -> ReceiveLimits {
+        self.inner.receive_limits
+    }
+
+    fn send_capacity(&self) -> usize {
+        self.inner.send_capacity
+    }
+
+    fn transport_security(&self) -> Option<TransportSecurity> {
+        Some(self.security.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{MsgFlags, MsgGlobalData, ScopedPdu, V3Message};
+    use crate::pdu::Pdu;
+    use crate::v3::{CertToTsnMapping, FingerprintAlgorithm};
+    use crate::{ClientBuilder, TsmConfig, Value, VarBind, oid};
+    use std::io::{Read, Write};
+
+    const CA: &[u8] = include_bytes!("testdata/tls/ca.pem");
+    const AGENT_CERT: &[u8] = include_bytes!("testdata/tls/agent.pem");
+    const AGENT_KEY: &[u8] = include_bytes!("testdata/tls/agent.key");
+    const MANAGER_CERT: &[u8] = include_bytes!("testdata/tls/manager.pem");
+    const MANAGER_KEY: &[u8] = include_bytes!("testdata/tls/manager.key");
+
+    fn der(pem: &[u8]) -> Vec<u8> {
+        X509::from_pem(pem).unwrap().to_der().unwrap()
+    }
+
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    fn fingerprint(pem: &[u8]) -> CertificateFingerprint {
+        CertificateFingerprint::compute(FingerprintAlgorithm::Sha256, &der(pem)).unwrap()
+    }
+
+    /// Blocking datagram I/O for the agent stand-in.
+    struct BlockingDatagram(std::net::UdpSocket);
+
+    impl Read for BlockingDatagram {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.recv(buf)
+        }
+    }
+
+    impl Write for BlockingDatagram {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.send(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Local stand-in for a DTLS agent serving one session and answering each
+    /// request with the client's mapped `tmSecurityName` (or "anonymous") as
+    /// sysDescr.
+    fn spawn_agent() -> SocketAddr {
+        let mut context = SslContext::builder(SslMethod::dtls_server()).unwrap();
+        context
+            .set_certificate(&X509::from_pem(AGENT_CERT).unwrap())
+            .unwrap();
+        context
+            .add_extra_chain_cert(X509::from_pem(CA).unwrap())
+            .unwrap();
+        context
+            .set_private_key(&PKey::private_key_from_pem(AGENT_KEY).unwrap())
+            .unwrap();
+        // Request a client certificate; the fingerprint map below decides.
+        context.set_verify_callback(SslVerifyMode::PEER, |_, _| true);
+        let context = context.build();
+        // Without a crypto backend no fingerprint matches; every client is
+        // then anonymous.
+        let ca_pin = CertificateFingerprint::compute(FingerprintAlgorithm::Sha256, &der(CA))
+            .unwrap_or_else(|_| {
+                CertificateFingerprint::new(FingerprintAlgorithm::Sha256, vec![0; 32]).unwrap()
+            });
+        let client_map = CertToTsn::new().entry(ca_pin, CertToTsnMapping::SanRfc822Name);
+
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        std::thread::spawn(move || {
+            socket
+                .set_read_timeout(Some(Duration::from_secs(10)))
+                .unwrap();
+            let Ok((_, peer)) = socket.peek_from(&mut [0u8; 1]) else {
+                return;
+            };
+            socket.connect(peer).unwrap();
+            let ssl = Ssl::new(&context).unwrap();
+            let Ok(mut stream) = ssl.accept(BlockingDatagram(socket)) else {
+                return;
+            };
+            // A server's peer chain omits the client's own certificate.
+            let chain: Vec<Bytes> = stream
+                .ssl()
+                .peer_certificate()
+                .into_iter()
+                .chain(peer_chain(stream.ssl()))
+                .map(|certificate| Bytes::from(certificate.to_der().unwrap()))
+                .collect();
+            let name = client_map
+                .security_name(&chain)
+                .unwrap_or_else(|| Bytes::from_static(b"anonymous"));
+            let mut buf = [0u8; MAX_DTLS_MESSAGE_SIZE];
+            while let Ok(len) = stream.ssl_read(&mut buf) {
+                let request =
+                    V3Message::decode(Bytes::copy_from_slice(&buf[..len]), Default::default())
+                        .unwrap()
+                        .value;
+                if stream.ssl_write(&reply(&request, name.clone())).is_err() {
+                    break;
+                }
+            }
+        });
+        addr
+    }
+
+    fn reply(request: &V3Message, sys_descr: Bytes) -> Bytes {
+        let global = MsgGlobalData::new(
+            request.msg_id(),
+            request.global_data().msg_max_size(),
+            MsgFlags::new(request.security_level(), false),
+        )
+        .unwrap();
+        let scoped = request.scoped_pdu().unwrap();
+        let pdu = Pdu::response(
+            request.pdu().unwrap().request_id,
+            0,
+            0,
+            vec![VarBind::new(
+                oid!(1, 3, 6, 1, 2, 1, 1, 1, 0),
+                Value::OctetString(sys_descr),
+            )],
+        );
+        let scoped = ScopedPdu::new(
+            Bytes::from_static(b"\x80\x00\x1f\x88\x04agent"),
+            scoped.context_name.clone(),
+            pdu,
+        );
+        V3Message::tsm(global, scoped).unwrap().encode().unwrap()
+    }
+
+    async fn sys_descr(transport: DtlsTransport) -> Value {
+        let client = ClientBuilder::new(TsmConfig::new("ops"))
+            .build_with_transport(transport)
+            .unwrap();
+        let response = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).await.unwrap();
+        response.single().unwrap().value.clone()
+    }
+
+    #[tokio::test]
+    async fn tsm_get_over_validated_session_with_client_certificate() {
+        let transport = DtlsTransport::builder()
+            .server_name("agent.example.com")
+            .trust_anchors_pem(CA)
+            .client_identity_pem([MANAGER_CERT, CA].concat(), MANAGER_KEY)
+            .connect(spawn_agent())
+            .await
+            .unwrap();
+        let security = transport.transport_security().unwrap();
+        assert_eq!(security.prefix, "dtls");
+        assert_eq!(security.security_level, SecurityLevel::AuthPriv);
+        assert_eq!(security.peer_security_name, None);
+        assert_eq!(transport.peer_certificates().len(), 2);
+        assert!(!transport.is_reliable());
+
+        let expected: &[u8] = if cfg!(any(feature = "crypto-rustcrypto", feature = "crypto-fips")) {
+            b"ops@example.com"
+        } else {
+            b"anonymous"
+        };
+        assert_eq!(sys_descr(transport).await, Value::from(expected));
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_share_one_session() {
+        let transport = DtlsTransport::builder()
+            .trust_anchor_der(der(CA))
+            .connect(spawn_agent())
+            .await
+            .unwrap();
+        let client = ClientBuilder::new(TsmConfig::new("ops"))
+            .build_with_transport(transport)
+            .unwrap();
+        let sys_descr = oid!(1, 3, 6, 1, 2, 1, 1, 1, 0);
+        let (first, second) = tokio::join!(client.get(&sys_descr), client.get(&sys_descr));
+        assert_eq!(
+            first.unwrap().single().unwrap().value,
+            Value::from("anonymous")
+        );
+        assert_eq!(
+            second.unwrap().single().unwrap().value,
+            Value::from("anonymous")
+        );
+    }
+
+    #[tokio::test]
+    async fn path_validation_checks_anchor_and_server_name() {
+        // The agent certificate carries an IP address SAN for 127.0.0.1.
+        let transport = DtlsTransport::builder()
+            .trust_anchor_der(der(CA))
+            .connect(spawn_agent())
+            .await
+            .unwrap();
+        assert_eq!(sys_descr(transport).await, Value::from("anonymous"));
+
+        let error = DtlsTransport::builder()
+            .server_name("other.example.com")
+            .trust_anchors_pem(CA)
+            .connect(spawn_agent())
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Network { .. }));
+
+        let error = DtlsTransport::builder()
+            .trust_anchors_pem(MANAGER_CERT)
+            .connect(spawn_agent())
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Network { .. }));
+
+        let error = DtlsTransport::builder()
+            .connect(spawn_agent())
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Config(_)));
+
+        let error = DtlsTransport::builder()
+            .trust_anchors_pem(CA)
+            .max_message_size(MAX_DTLS_MESSAGE_SIZE + 1)
+            .connect(spawn_agent())
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Config(_)));
+    }
+
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    #[tokio::test]
+    async fn fingerprint_pins_accept_leaf_or_validated_ca() {
+        let transport = DtlsTransport::builder()
+            .server_name("ignored.example.com")
+            .pin_server_fingerprint(fingerprint(AGENT_CERT))
+            .connect(spawn_agent())
+            .await
+            .unwrap();
+        assert_eq!(sys_descr(transport).await, Value::from("anonymous"));
+
+        DtlsTransport::builder()
+            .server_name("agent.example.com")
+            .pin_server_fingerprint(fingerprint(CA))
+            .connect(spawn_agent())
+            .await
+            .unwrap();
+        // A CA pin still requires the server name to match.
+        let error = DtlsTransport::builder()
+            .server_name("other.example.com")
+            .pin_server_fingerprint(fingerprint(CA))
+            .connect(spawn_agent())
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Network { .. }));
+
+        let error = DtlsTransport::builder()
+            .pin_server_fingerprint(fingerprint(MANAGER_CERT))
+            .trust_anchors_pem(CA)
+            .connect(spawn_agent())
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Network { .. }));
+    }
+
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    #[tokio::test]
+    async fn cert_to_tsn_names_the_server_or_rejects_it() {
+        let transport = DtlsTransport::builder()
+            .trust_anchors_pem(CA)
+            .cert_to_tsn(CertToTsn::new().entry(fingerprint(CA), CertToTsnMapping::SanDnsName))
+            .connect(spawn_agent())
+            .await
+            .unwrap();
+        assert_eq!(
+            transport.transport_security().unwrap().peer_security_name,
+            Some(Bytes::from_static(b"agent.example.com"))
+        );
+
+        let agent = spawn_agent();
+        let error = DtlsTransport::builder()
+            .trust_anchors_pem(CA)
+            .cert_to_tsn(
+                CertToTsn::new().entry(fingerprint(MANAGER_CERT), CertToTsnMapping::CommonName),
+            )
+            .connect(agent)
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Auth { target } if target == agent));
+    }
+}
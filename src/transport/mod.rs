@@ -3,7 +3,11 @@
 //! The module provides the [`Transport`] trait and these implementations:
 //!
 //! - [`UdpTransport`] + [`UdpHandle`] - UDP socket with per-target handles
+//! - [`ShardedUdpTransport`] - several UDP sockets and receive tasks, targets assigned by hash
 //! - [`TcpTransport`] - TCP stream with BER framing
+//! - `TlsTransport` - TLS over TCP (RFC 6353 TLSTM), with the `tls` feature
+//! - `DtlsTransport` - DTLS over UDP (RFC 6353 TLSTM), with the `dtls` feature
+//! - `UnixTransport` - Unix domain stream or datagram socket, on Unix platforms
 //! - [`BuiltinTransport`] - runtime selection between library-maintained transports
 //!
 //! # Choosing a transport
@@ -12,19 +16,47 @@
 //! |----------|---------|
 //! | Single target or few targets | [`Client::builder().connect()`](crate::Client::builder) - each client gets its own socket |
 //! | Many UDP targets from one process | Pass a preconstructed [`UdpTransport`] socket owner to [`TargetClientBuilder::build_with`](crate::TargetClientBuilder::build_with) - each target gets a handle on one socket and receive loop |
+//! | Thousands of requests per second on one shared UDP socket (Linux) | Enable [`UdpTransportBuilder::batched_io`] to receive and send with `recvmmsg`/`sendmmsg` |
+//! | Many-core poller limited by one UDP receive task | [`ShardedUdpTransport`] with [`TargetClientBuilder::build_with_sharded`](crate::TargetClientBuilder::build_with_sharded), optionally on one port with `SO_REUSEPORT` |
+//! | Multi-homed host, management VRF, or QoS marking | [`SocketOptions`] via [`TargetClientBuilder::socket_options`](crate::TargetClientBuilder::socket_options) or the transport builders - interface binding, source address, DSCP, TTL |
 //! | UDP blocked or messages exceed MTU | [`Client::builder().connect_tcp()`](crate::TargetClientBuilder::connect_tcp) |
 //! | Preconstruct or implement any client transport | Pass the [`Transport`] implementation to [`ClientBuilder::build_with_transport`](crate::ClientBuilder::build_with_transport) without a target |
+//! | Test against recorded device data without a network | [`ReplayTransport`] serving a [`MibSnapshot`](crate::snapshot::MibSnapshot) |
+//! | Capture exchanges for Wireshark | Wrap any transport in [`RecordingTransport`] |
 //! | Choose UDP or TCP at runtime | Configure the concrete transport, convert it to [`BuiltinTransport`], then pass it to [`ClientBuilder::build_with_transport`](crate::ClientBuilder::build_with_transport) |
 
 mod builtin;
+#[cfg(feature = "dtls")]
+mod dtls;
+mod recording;
+mod replay;
+mod socket_options;
 mod tcp;
+#[cfg(feature = "tls")]
+mod tls;
 mod udp;
+#[cfg(target_os = "linux")]
+mod udp_batch;
 mod udp_core;
 pub(crate) mod udp_error;
+mod udp_port_pool;
+mod udp_sharded;
+#[cfg(unix)]
+mod unix;
 
 pub use builtin::*;
+#[cfg(feature = "dtls")]
+pub use dtls::*;
+pub use recording::*;
+pub use replay::*;
+pub use socket_options::*;
 pub use tcp::*;
+#[cfg(feature = "tls")]
+pub use tls::*;
 pub use udp::*;
+pub use udp_sharded::*;
+#[cfg(unix)]
+pub use unix::*;
 
 use crate::Community;
 use crate::DecodeConfig;
@@ -32,6 +64,7 @@ use crate::ber::length::parse_ber_length;
 use crate::error::Error;
 use crate::error::Result;
 use crate::message_size::{ReceiveLimits, UDP_RECEIVE_LIMITS};
+use crate::v3::TransportSecurity;
 use crate::version::{CommunityVersion, Version};
 use bytes::Bytes;
 use std::collections::BTreeSet;
@@ -223,6 +256,24 @@ pub struct RequestRegistration {
     aliases: BTreeSet<i32>,
     /// Internal lifetime shared by registrations in one V3 retry window.
     correlation_window: Option<Arc<CorrelationWindow>>,
+    /// Observer told when the transport has written the request.
+    sent_hook: Option<SentHook>,
+}
+
+/// Callback run by [`RequestRegistration::mark_sent`].
+#[derive(Clone)]
+pub(crate) struct SentHook(Arc<dyn Fn() + Send + Sync>);
+
+impl SentHook {
+    pub(crate) fn run(&self) {
+        (self.0)();
+    }
+}
+
+impl std::fmt::Debug for SentHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SentHook")
+    }
 }
 
 impl RequestRegistration {
@@ -246,6 +297,7 @@ impl RequestRegistration {
             decode_config: DecodeConfig::DEFAULT,
             aliases: BTreeSet::new(),
             correlation_window: None,
+            sent_hook: None,
         }
     }
 
@@ -259,6 +311,7 @@ impl RequestRegistration {
             decode_config: DecodeConfig::DEFAULT,
             aliases: BTreeSet::new(),
             correlation_window: None,
+            sent_hook: None,
         }
     }
 
@@ -274,6 +327,7 @@ impl RequestRegistration {
             decode_config: DecodeConfig::DEFAULT,
             aliases: BTreeSet::new(),
             correlation_window: None,
+            sent_hook: None,
         }
     }
 
@@ -327,6 +381,24 @@ impl RequestRegistration {
         self.correlation_window = None;
     }
 
+    /// Run `hook` each time a transport reports the request as written.
+    pub(crate) fn with_sent_hook(mut self, hook: impl Fn() + Send + Sync + 'static) -> Self {
+        self.sent_hook = Some(SentHook(Arc::new(hook)));
+        self
+    }
+
+    /// Report that the request data has been written.
+    ///
+    /// Transports call this once the write completes, before waiting for a
+    /// response, so that wrappers such as
+    /// [`RecordingTransport`](crate::RecordingTransport) observe the send
+    /// however the exchange ends.
+    pub fn mark_sent(&self) {
+        if let Some(hook) = &self.sent_hook {
+            hook.run();
+        }
+    }
+
     /// Decode configuration used by correlation and full validation.
     #[must_use]
     pub const fn decode_config(&self) -> DecodeConfig {
@@ -453,6 +525,21 @@ static_assertions::assert_not_impl_any!(
     std::hash::Hash
 );
 
+/// Credentials of the process on the other end of a Unix-domain stream socket.
+///
+/// Read from the kernel (`SO_PEERCRED` on Linux, `getpeereid` on the BSDs)
+/// when a connection is accepted, so the values cannot be forged by the peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct PeerCredentials {
+    /// Effective user ID of the peer process.
+    pub uid: u32,
+    /// Effective group ID of the peer process.
+    pub gid: u32,
+    /// Process ID of the peer, when the platform reports it.
+    pub pid: Option<i32>,
+}
+
 /// Result of validating a correlated response candidate.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Candidate<T> {
@@ -535,7 +622,7 @@ pub trait Transport: Send + Sync {
                 }
                 .boxed());
             }
-            tokio::time::timeout_at(deadline, self.send(data))
+            crate::runtime::timeout_at(deadline, self.send(data))
                 .await
                 .map_err(|_| {
                     Error::Timeout {
@@ -553,8 +640,9 @@ pub trait Transport: Send + Sync {
     /// This is the required complete request/response extension hook. The
     /// implementation must install the primary identity and every alias before
     /// sending, use [`RequestRegistration::deadline`] across queueing, write,
-    /// candidate rejection, and response wait, and remove all registration
-    /// state on every completion or cancellation path. Identity-correlated
+    /// candidate rejection, and response wait, call
+    /// [`RequestRegistration::mark_sent`] once the write completes, and remove
+    /// all registration state on every completion or cancellation path. Identity-correlated
     /// candidates must pass through `validate`; rejection continues under the
     /// same deadline. Reliable transports must retain exclusive framing
     /// ownership across the complete operation and make an unsafe partially
@@ -608,6 +696,16 @@ pub trait Transport: Send + Sync {
     fn send_capacity(&self) -> usize {
         usize::MAX
     }
+
+    /// Security of the established session, for transports that
+    /// authenticate their peer.
+    ///
+    /// The Transport Security Model requires this: TLS and DTLS transports
+    /// report the session's security level and any `tmSecurityName` derived
+    /// from the peer certificate. Plain transports return `None`.
+    fn transport_security(&self) -> Option<TransportSecurity> {
+        None
+    }
 }
 
 /// Adapt a unit-test double's scripted response stream to the full
@@ -1142,6 +1240,16 @@ impl<'a> CorrelationEnvelope<'a> {
     }
 }
 
+/// Byte range of the v1/v2c community string within a message envelope.
+///
+/// Used to redact captured messages in place without re-encoding them.
+pub(crate) fn community_range(data: &[u8]) -> Option<std::ops::Range<usize>> {
+    let (_, community) =
+        CorrelationEnvelope::parse(data, DecodeConfig::default())?.community_identity()?;
+    let start = (community.as_ptr() as usize).checked_sub(data.as_ptr() as usize)?;
+    Some(start..start + community.len())
+}
+
 /// Extract a checked v1/v2c version and borrowed community without allocating.
 ///
 /// The default compatible policy mirrors [`extract_request_id`]. Returned
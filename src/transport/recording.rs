@@ -0,0 +1,1001 @@
+//! Packet-capture recording wrapper for any transport.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+
+use super::{Candidate, RequestRegistration, Transport};
+use crate::error::Result;
+use crate::message_size::ReceiveLimits;
+
+/// `LINKTYPE_RAW`: packets begin with an IPv4 or IPv6 header.
+const LINKTYPE_RAW: u16 = 101;
+
+/// Largest UDP payload representable in a synthetic IPv4 datagram.
+///
+/// Longer messages, which only stream transports carry, are captured
+/// truncated to this length with their original length recorded.
+const MAX_CAPTURED_PAYLOAD: usize = 65_507;
+
+const UDP_HEADER_LEN: usize = 8;
+const IPV4_HEADER_LEN: usize = 20;
+const IPV6_HEADER_LEN: usize = 40;
+
+/// Snapshot length covering the largest synthetic datagram, an IPv6 one.
+const SNAPLEN: u32 = (IPV6_HEADER_LEN + UDP_HEADER_LEN + MAX_CAPTURED_PAYLOAD) as u32;
+
+/// Packets queued for the capture writer thread before new packets are
+/// dropped.
+const WRITER_QUEUE_CAPACITY: usize = 1024;
+
+/// Capture file format written by [`RecordingTransport`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CaptureFormat {
+    /// Classic libpcap format with microsecond timestamps.
+    #[default]
+    Pcap,
+    /// pcapng, which also records each packet's direction.
+    PcapNg,
+}
+
+/// Cumulative [`RecordingTransport`] counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RecordingStats {
+    /// Packets written to a capture file.
+    pub packets_written: u64,
+    /// Packets not written because the size limit was reached without
+    /// rotation.
+    pub packets_skipped: u64,
+    /// Packets lost to capture file I/O errors.
+    pub write_errors: u64,
+    /// Completed file rotations.
+    pub rotations: u64,
+    /// Packets not written because the capture writer fell behind and its
+    /// queue was full.
+    pub packets_dropped: u64,
+}
+
+/// Builder for [`RecordingTransport`].
+#[derive(Debug)]
+pub struct RecordingTransportBuilder<T> {
+    inner: T,
+    path: PathBuf,
+    format: CaptureFormat,
+    max_file_size: Option<u64>,
+    max_files: usize,
+    redact_communities: bool,
+}
+
+impl<T: Transport> RecordingTransportBuilder<T> {
+    /// Capture file format.
+    ///
+    /// Default: [`CaptureFormat::Pcap`].
+    #[must_use]
+    pub fn format(mut self, format: CaptureFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Maximum size in bytes of one capture file, including its header.
+    ///
+    /// A packet that would exceed the limit triggers rotation when
+    /// [`max_files`](Self::max_files) is above one; otherwise recording stops
+    /// and further packets are counted as skipped. A file always receives at
+    /// least one packet. Default: unlimited.
+    #[must_use]
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Number of capture files kept, including the active one.
+    ///
+    /// On rotation the active file is renamed with a `.1` suffix, earlier
+    /// rotations move up by one, and the oldest beyond the limit is removed.
+    /// Values below one are treated as one. Default: 1 (no rotation).
+    #[must_use]
+    pub fn max_files(mut self, count: usize) -> Self {
+        self.max_files = count.max(1);
+        self
+    }
+
+    /// Replace SNMPv1 and SNMPv2c community strings with `*` before writing.
+    ///
+    /// The replacement has the same length, so captured messages still
+    /// decode. SNMPv3 messages are written as sent, with scoped PDUs encrypted
+    /// whenever privacy is in use. Default: disabled.
+    #[must_use]
+    pub fn redact_communities(mut self, enabled: bool) -> Self {
+        self.redact_communities = enabled;
+        self
+    }
+
+    /// Create the capture file, start its writer thread and build the
+    /// wrapper.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the capture file cannot be created, its header
+    /// cannot be written, or the writer thread cannot be spawned.
+    pub fn build(self) -> io::Result<RecordingTransport<T>> {
+        let file = CaptureFile::create(self.path, self.format, self.max_file_size, self.max_files)?;
+        let counters = Arc::new(RecordingCounters::default());
+        let (writer, commands) = mpsc::sync_channel(WRITER_QUEUE_CAPACITY);
+        let thread_counters = Arc::clone(&counters);
+        std::thread::Builder::new()
+            .name("snmp-capture".into())
+            .spawn(move || run_writer(file, &commands, &thread_counters))?;
+        Ok(RecordingTransport {
+            inner: self.inner,
+            shared: Arc::new(RecordingShared {
+                writer,
+                redact_communities: self.redact_communities,
+                counters,
+            }),
+        })
+    }
+}
+
+/// Transport wrapper that writes every exchanged message to a capture file.
+///
+/// Each sent message and each response candidate delivered by the wrapped
+/// transport is written as a UDP datagram inside a synthetic IPv4 or IPv6
+/// header between [`local_addr`](Transport::local_addr) and the remote
+/// address, so captures open directly in Wireshark and other pcap tools.
+/// Responses the wrapped transport discards before validation, such as
+/// uncorrelated datagrams, are not seen and not recorded. Stream transports
+/// are recorded the same way, one datagram per framed message.
+///
+/// A request is recorded once a response to it arrives or the exchange
+/// times out; requests whose send fails are not recorded.
+///
+/// Capture I/O never fails or delays an SNMP operation. Packets are handed to
+/// a dedicated writer thread, which flushes each one so a capture survives an
+/// abrupt exit. Write errors are logged and counted in
+/// [`RecordingStats::write_errors`]; packets arriving while the writer is
+/// [`WRITER_QUEUE_CAPACITY`] packets behind are counted in
+/// [`RecordingStats::packets_dropped`]. Use [`flush`](Self::flush) to wait
+/// for queued packets to reach the file.
+///
+/// Clones share the capture file, writer thread and counters. The thread
+/// exits after writing the remaining queue once the last clone is dropped.
+///
+/// ```rust,no_run
+/// use async_snmp::transport::{CaptureFormat, RecordingTransport};
+/// use async_snmp::{Auth, ClientBuilder, UdpTransport, oid};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let udp = UdpTransport::bind("0.0.0.0:0").await?;
+/// let transport = udp.handle("192.0.2.1:161".parse()?)?;
+/// let recording = RecordingTransport::builder(transport, "core-1.pcapng")
+///     .format(CaptureFormat::PcapNg)
+///     .max_file_size(16 * 1024 * 1024)
+///     .max_files(4)
+///     .redact_communities(true)
+///     .build()?;
+///
+/// let client = ClientBuilder::new(Auth::v2c("public")).build_with_transport(recording)?;
+/// client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordingTransport<T> {
+    inner: T,
+    shared: Arc<RecordingShared>,
+}
+
+#[derive(Debug)]
+struct RecordingShared {
+    writer: SyncSender<WriterCommand>,
+    redact_communities: bool,
+    counters: Arc<RecordingCounters>,
+}
+
+#[derive(Debug, Default)]
+struct RecordingCounters {
+    packets_written: AtomicU64,
+    packets_skipped: AtomicU64,
+    write_errors: AtomicU64,
+    rotations: AtomicU64,
+    packets_dropped: AtomicU64,
+}
+
+#[derive(Debug)]
+enum WriterCommand {
+    Packet {
+        timestamp: Duration,
+        direction: Direction,
+        packet: Vec<u8>,
+        original_len: usize,
+    },
+    Flush(tokio::sync::oneshot::Sender<()>),
+}
+
+/// Writer thread body: write queued packets until every sender is dropped.
+fn run_writer(
+    mut file: CaptureFile,
+    commands: &Receiver<WriterCommand>,
+    counters: &RecordingCounters,
+) {
+    for command in commands {
+        let (timestamp, direction, packet, original_len) = match command {
+            WriterCommand::Packet {
+                timestamp,
+                direction,
+                packet,
+                original_len,
+            } => (timestamp, direction, packet, original_len),
+            WriterCommand::Flush(done) => {
+                let _ = done.send(());
+                continue;
+            }
+        };
+        match file.write_packet(timestamp, direction, &packet, original_len) {
+            Ok(PacketOutcome::Written { rotated }) => {
+                counters.packets_written.fetch_add(1, Ordering::Relaxed);
+                if rotated {
+                    counters.rotations.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            Ok(PacketOutcome::Full) => {
+                counters.packets_skipped.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(error) => {
+                counters.write_errors.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(target: "async_snmp::transport", { %error, path = %file.path.display() }, "failed to write capture packet");
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Outbound,
+    Inbound,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    /// Create a builder wrapping `inner` and recording to `path`.
+    pub fn builder(inner: T, path: impl AsRef<Path>) -> RecordingTransportBuilder<T> {
+        RecordingTransportBuilder {
+            inner,
+            path: path.as_ref().to_path_buf(),
+            format: CaptureFormat::Pcap,
+            max_file_size: None,
+            max_files: 1,
+            redact_communities: false,
+        }
+    }
+
+    /// Return the wrapped transport.
+    #[must_use]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Return a snapshot of the recording counters.
+    ///
+    /// Packets still queued for the writer thread are not yet counted; call
+    /// [`flush`](Self::flush) first for exact totals.
+    #[must_use]
+    pub fn stats(&self) -> RecordingStats {
+        let counters = &self.shared.counters;
+        RecordingStats {
+            packets_written: counters.packets_written.load(Ordering::Relaxed),
+            packets_skipped: counters.packets_skipped.load(Ordering::Relaxed),
+            write_errors: counters.write_errors.load(Ordering::Relaxed),
+            rotations: counters.rotations.load(Ordering::Relaxed),
+            packets_dropped: counters.packets_dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Wait until every packet recorded so far has been written and flushed.
+    pub async fn flush(&self) {
+        let (done, written) = tokio::sync::oneshot::channel();
+        // Unlike packets, the flush marker waits for room in a full queue, on
+        // a blocking thread so no async runtime timer is needed.
+        let queued = match self.shared.writer.try_send(WriterCommand::Flush(done)) {
+            Ok(()) => true,
+            Err(TrySendError::Full(command)) => {
+                let writer = self.shared.writer.clone();
+                crate::runtime::spawn_blocking(move || writer.send(command).is_ok()).await
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        };
+        if queued {
+            let _ = written.await;
+        }
+    }
+
+    fn record(&self, direction: Direction, remote: SocketAddr, message: &[u8]) {
+        self.shared.record_at(
+            capture_timestamp(),
+            direction,
+            self.inner.local_addr(),
+            remote,
+            message,
+        );
+    }
+}
+
+impl RecordingShared {
+    fn record_at(
+        &self,
+        timestamp: Duration,
+        direction: Direction,
+        local: SocketAddr,
+        remote: SocketAddr,
+        message: &[u8],
+    ) {
+        let (source, destination) = match direction {
+            Direction::Outbound => (local, remote),
+            Direction::Inbound => (remote, local),
+        };
+
+        let mut redacted;
+        let mut payload = message;
+        if self.redact_communities
+            && let Some(range) = super::community_range(message)
+        {
+            redacted = message.to_vec();
+            redacted[range].fill(b'*');
+            payload = &redacted;
+        }
+        let original_len = payload.len();
+        let packet = synthetic_udp_packet(
+            source,
+            destination,
+            &payload[..original_len.min(MAX_CAPTURED_PAYLOAD)],
+        );
+        let header_len = packet.len() - original_len.min(MAX_CAPTURED_PAYLOAD);
+
+        let command = WriterCommand::Packet {
+            timestamp,
+            direction,
+            packet,
+            original_len: header_len + original_len,
+        };
+        match self.writer.try_send(command) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.counters
+                    .packets_dropped
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                self.counters.write_errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl<T: Transport> Transport for RecordingTransport<T> {
+    async fn send(&self, data: &[u8]) -> Result<()> {
+        self.inner.send(data).await?;
+        self.record(Direction::Outbound, self.inner.peer_addr(), data);
+        Ok(())
+    }
+
+    async fn send_with_timeout(&self, data: &[u8], timeout: Duration) -> Result<()> {
+        self.inner.send_with_timeout(data, timeout).await?;
+        self.record(Direction::Outbound, self.inner.peer_addr(), data);
+        Ok(())
+    }
+
+    async fn request_with<U, F>(
+        &self,
+        data: &[u8],
+        registration: RequestRegistration,
+        mut validate: F,
+    ) -> Result<U>
+    where
+        U: Send,
+        F: FnMut(Bytes, SocketAddr) -> Result<Candidate<U>> + Send,
+    {
+        // Transports report the write through the registration, so the
+        // request is recorded however the exchange ends. Transports that do
+        // not are covered by recording it with its start time once a response
+        // proves it was sent, or once the exchange times out.
+        let started = capture_timestamp();
+        let recorded = Arc::new(AtomicBool::new(false));
+        let record_request = {
+            let shared = self.shared.clone();
+            let recorded = recorded.clone();
+            let local = self.inner.local_addr();
+            let peer = self.inner.peer_addr();
+            let request = Bytes::copy_from_slice(data);
+            Arc::new(move |timestamp| {
+                if !recorded.swap(true, Ordering::Relaxed) {
+                    shared.record_at(timestamp, Direction::Outbound, local, peer, &request);
+                }
+            })
+        };
+        let registration = {
+            let record_request = record_request.clone();
+            registration.with_sent_hook(move || record_request(capture_timestamp()))
+        };
+        let result = self
+            .inner
+            .request_with(data, registration, |response, source| {
+                record_request(started);
+                self.record(Direction::Inbound, source, &response);
+                validate(response, source)
+            })
+            .await;
+        if let Err(error) = &result
+            && matches!(**error, crate::error::Error::Timeout { .. })
+        {
+            record_request(started);
+        }
+        result
+    }
+
+    fn peer_addr(&self) -> SocketAddr {
+        self.inner.peer_addr()
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        self.inner.local_addr()
+    }
+
+    fn alloc_request_id(&self) -> i32 {
+        self.inner.alloc_request_id()
+    }
+
+    fn is_reliable(&self) -> bool {
+        self.inner.is_reliable()
+    }
+
+    fn receive_limits(&self) -> ReceiveLimits {
+        self.inner.receive_limits()
+    }
+
+    fn send_capacity(&self) -> usize {
+        self.inner.send_capacity()
+    }
+
+    fn transport_security(&self) -> Option<crate::v3::TransportSecurity> {
+        self.inner.transport_security()
+    }
+}
+
+enum PacketOutcome {
+    Written { rotated: bool },
+    Full,
+}
+
+#[derive(Debug)]
+struct CaptureFile {
+    path: PathBuf,
+    format: CaptureFormat,
+    max_file_size: Option<u64>,
+    max_files: usize,
+    /// `None` once the size limit is reached without rotation.
+    writer: Option<BufWriter<File>>,
+    size: u64,
+    packets: u64,
+}
+
+impl CaptureFile {
+    fn create(
+        path: PathBuf,
+        format: CaptureFormat,
+        max_file_size: Option<u64>,
+        max_files: usize,
+    ) -> io::Result<Self> {
+        let mut file = Self {
+            path,
+            format,
+            max_file_size,
+            max_files,
+            writer: None,
+            size: 0,
+            packets: 0,
+        };
+        file.open()?;
+        Ok(file)
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(&self.path)?);
+        let header = match self.format {
+            CaptureFormat::Pcap => pcap_header(),
+            CaptureFormat::PcapNg => pcapng_header(),
+        };
+        writer.write_all(&header)?;
+        writer.flush()?;
+        self.writer = Some(writer);
+        self.size = header.len() as u64;
+        self.packets = 0;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.writer = None;
+        let rotated = |index: usize| {
+            let mut name = self.path.clone().into_os_string();
+            name.push(format!(".{index}"));
+            PathBuf::from(name)
+        };
+        let oldest = rotated(self.max_files - 1);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for index in (1..self.max_files - 1).rev() {
+            let from = rotated(index);
+            if from.exists() {
+                fs::rename(&from, rotated(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated(1))?;
+        self.open()
+    }
+
+    fn write_packet(
+        &mut self,
+        timestamp: Duration,
+        direction: Direction,
+        packet: &[u8],
+        original_len: usize,
+    ) -> io::Result<PacketOutcome> {
+        let record = match self.format {
+            CaptureFormat::Pcap => pcap_record(timestamp, packet, original_len),
+            CaptureFormat::PcapNg => pcapng_record(timestamp, direction, packet, original_len),
+        };
+
+        let mut rotated = false;
+        let over_limit = self
+            .max_file_size
+            .is_some_and(|limit| self.size + record.len() as u64 > limit);
+        if over_limit && self.packets > 0 {
+            if self.max_files > 1 {
+                self.rotate()?;
+                rotated = true;
+            } else {
+                self.writer = None;
+            }
+        }
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(PacketOutcome::Full);
+        };
+        writer.write_all(&record)?;
+        writer.flush()?;
+        self.size += record.len() as u64;
+        self.packets += 1;
+        Ok(PacketOutcome::Written { rotated })
+    }
+}
+
+fn capture_timestamp() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+fn pcap_header() -> Vec<u8> {
+    let mut header = Vec::with_capacity(24);
+    header.extend_from_slice(&0xa1b2_c3d4_u32.to_le_bytes());
+    header.extend_from_slice(&2u16.to_le_bytes());
+    header.extend_from_slice(&4u16.to_le_bytes());
+    header.extend_from_slice(&0i32.to_le_bytes());
+    header.extend_from_slice(&0u32.to_le_bytes());
+    header.extend_from_slice(&SNAPLEN.to_le_bytes());
+    header.extend_from_slice(&u32::from(LINKTYPE_RAW).to_le_bytes());
+    header
+}
+
+fn pcap_record(timestamp: Duration, packet: &[u8], original_len: usize) -> Vec<u8> {
+    let mut record = Vec::with_capacity(16 + packet.len());
+    record.extend_from_slice(&(timestamp.as_secs() as u32).to_le_bytes());
+    record.extend_from_slice(&timestamp.subsec_micros().to_le_bytes());
+    record.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+    record.extend_from_slice(&(original_len as u32).to_le_bytes());
+    record.extend_from_slice(packet);
+    record
+}
+
+/// Section header block followed by one interface description block.
+fn pcapng_header() -> Vec<u8> {
+    let mut header = Vec::with_capacity(48);
+    header.extend_from_slice(&0x0a0d_0d0a_u32.to_le_bytes());
+    header.extend_from_slice(&28u32.to_le_bytes());
+    header.extend_from_slice(&0x1a2b_3c4d_u32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    header.extend_from_slice(&(-1i64).to_le_bytes());
+    header.extend_from_slice(&28u32.to_le_bytes());
+
+    header.extend_from_slice(&1u32.to_le_bytes());
+    header.extend_from_slice(&20u32.to_le_bytes());
+    header.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    header.extend_from_slice(&0u32.to_le_bytes());
+    header.extend_from_slice(&20u32.to_le_bytes());
+    header
+}
+
+/// Enhanced packet block with an `epb_flags` direction option.
+fn pcapng_record(
+    timestamp: Duration,
+    direction: Direction,
+    packet: &[u8],
+    original_len: usize,
+) -> Vec<u8> {
+    let padded = packet.len().next_multiple_of(4);
+    // Fixed fields, epb_flags option, end-of-options, trailing length.
+    let block_len = 28 + padded + 8 + 4 + 4;
+    let micros = timestamp.as_micros() as u64;
+    let flags: u32 = match direction {
+        Direction::Inbound => 0b01,
+        Direction::Outbound => 0b10,
+    };
+
+    let mut record = Vec::with_capacity(block_len);
+    record.extend_from_slice(&6u32.to_le_bytes());
+    record.extend_from_slice(&(block_len as u32).to_le_bytes());
+    record.extend_from_slice(&0u32.to_le_bytes());
+    record.extend_from_slice(&((micros >> 32) as u32).to_le_bytes());
+    record.extend_from_slice(&(micros as u32).to_le_bytes());
+    record.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+    record.extend_from_slice(&(original_len as u32).to_le_bytes());
+    record.extend_from_slice(packet);
+    record.resize(28 + padded, 0);
+    record.extend_from_slice(&2u16.to_le_bytes());
+    record.extend_from_slice(&4u16.to_le_bytes());
+    record.extend_from_slice(&flags.to_le_bytes());
+    record.extend_from_slice(&0u32.to_le_bytes());
+    record.extend_from_slice(&(block_len as u32).to_le_bytes());
+    record
+}
+
+/// Build an IPv4 or IPv6 UDP datagram carrying `payload`.
+///
+/// Mixed address families are written as IPv6 with the IPv4 address mapped.
+fn synthetic_udp_packet(source: SocketAddr, destination: SocketAddr, payload: &[u8]) -> Vec<u8> {
+    let udp_len = (UDP_HEADER_LEN + payload.len()) as u16;
+    let mut udp = Vec::with_capacity(usize::from(udp_len));
+    udp.extend_from_slice(&source.port().to_be_bytes());
+    udp.extend_from_slice(&destination.port().to_be_bytes());
+    udp.extend_from_slice(&udp_len.to_be_bytes());
+    udp.extend_from_slice(&[0, 0]);
+    udp.extend_from_slice(payload);
+
+    match (source.ip(), destination.ip()) {
+        (IpAddr::V4(src), IpAddr::V4(dst)) => {
+            let mut pseudo = Vec::with_capacity(12);
+            pseudo.extend_from_slice(&src.octets());
+            pseudo.extend_from_slice(&dst.octets());
+            pseudo.extend_from_slice(&[0, 17]);
+            pseudo.extend_from_slice(&udp_len.to_be_bytes());
+            set_udp_checksum(&mut udp, &pseudo);
+
+            let total_len = (IPV4_HEADER_LEN + udp.len()) as u16;
+            let mut packet = Vec::with_capacity(usize::from(total_len));
+            packet.extend_from_slice(&[0x45, 0]);
+            packet.extend_from_slice(&total_len.to_be_bytes());
+            packet.extend_from_slice(&[0, 0, 0x40, 0, 64, 17, 0, 0]);
+            packet.extend_from_slice(&src.octets());
+            packet.extend_from_slice(&dst.octets());
+            let checksum = internet_checksum(&[&packet]);
+            packet[10..12].copy_from_slice(&checksum.to_be_bytes());
+            packet.extend_from_slice(&udp);
+            packet
+        }
+        (src, dst) => {
+            let src = to_ipv6(src);
+            let dst = to_ipv6(dst);
+            let mut pseudo = Vec::with_capacity(40);
+            pseudo.extend_from_slice(&src.octets());
+            pseudo.extend_from_slice(&dst.octets());
+            pseudo.extend_from_slice(&u32::from(udp_len).to_be_bytes());
+            pseudo.extend_from_slice(&[0, 0, 0, 17]);
+            set_udp_checksum(&mut udp, &pseudo);
+
+            let mut packet = Vec::with_capacity(IPV6_HEADER_LEN + udp.len());
+            packet.extend_from_slice(&[0x60, 0, 0, 0]);
+            packet.extend_from_slice(&udp_len.to_be_bytes());
+            packet.extend_from_slice(&[17, 64]);
+            packet.extend_from_slice(&src.octets());
+            packet.extend_from_slice(&dst.octets());
+            packet.extend_from_slice(&udp);
+            packet
+        }
+    }
+}
+
+fn to_ipv6(addr: IpAddr) -> Ipv6Addr {
+    match addr {
+        IpAddr::V4(v4) => v4.to_ipv6_mapped(),
+        IpAddr::V6(v6) => v6,
+    }
+}
+
+fn set_udp_checksum(udp: &mut [u8], pseudo_header: &[u8]) {
+    let checksum = match internet_checksum(&[pseudo_header, udp]) {
+        // RFC 768: a computed zero is transmitted as all ones.
+        0 => 0xffff,
+        checksum => checksum,
+    };
+    udp[6..8].copy_from_slice(&checksum.to_be_bytes());
+}
+
+/// RFC 1071 ones' complement checksum; only the last part may have odd length.
+fn internet_checksum(parts: &[&[u8]]) -> u16 {
+    let mut sum: u32 = 0;
+    for part in parts {
+        let mut chunks = part.chunks_exact(2);
+        for chunk in &mut chunks {
+            sum += u32::from(u16::from_be_bytes([chunk[0], chunk[1]]));
+        }
+        if let [last] = chunks.remainder() {
+            sum += u32::from(*last) << 8;
+        }
+    }
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{Auth, ClientBuilder};
+    use crate::oid;
+    use crate::snapshot::MibSnapshot;
+    use crate::transport::ReplayTransport;
+    use crate::value::Value;
+    use crate::varbind::VarBind;
+
+    fn replay() -> ReplayTransport {
+        let data: MibSnapshot = [VarBind::new(
+            oid!(1, 3, 6, 1, 2, 1, 1, 5, 0),
+            Value::from("core-1"),
+        )]
+        .into_iter()
+        .collect();
+        ReplayTransport::new(data)
+    }
+
+    /// Split a classic pcap file into (captured packet, original length).
+    fn pcap_packets(data: &[u8]) -> Vec<(&[u8], u32)> {
+        assert_eq!(&data[..4], &0xa1b2_c3d4_u32.to_le_bytes());
+        assert_eq!(&data[20..24], &101u32.to_le_bytes());
+        let mut packets = Vec::new();
+        let mut rest = &data[24..];
+        while !rest.is_empty() {
+            let captured = u32::from_le_bytes(rest[8..12].try_into().unwrap()) as usize;
+            let original = u32::from_le_bytes(rest[12..16].try_into().unwrap());
+            packets.push((&rest[16..16 + captured], original));
+            rest = &rest[16 + captured..];
+        }
+        packets
+    }
+
+    #[tokio::test]
+    async fn records_request_and_response_with_valid_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.pcap");
+        let recording = RecordingTransport::builder(replay(), &path)
+            .build()
+            .unwrap();
+        let client = ClientBuilder::new(Auth::v2c("secret"))
+            .build_with_transport(recording.clone())
+            .unwrap();
+        client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 5, 0)).await.unwrap();
+        recording.flush().await;
+
+        assert_eq!(recording.stats().packets_written, 2);
+        let data = fs::read(&path).unwrap();
+        let packets = pcap_packets(&data);
+        assert_eq!(packets.len(), 2);
+        for (packet, original) in &packets {
+            assert_eq!(*original as usize, packet.len());
+            assert_eq!(packet[0], 0x45);
+            assert_eq!(internet_checksum(&[&packet[..IPV4_HEADER_LEN]]), 0);
+            assert_eq!(
+                usize::from(u16::from_be_bytes([packet[2], packet[3]])),
+                packet.len()
+            );
+        }
+        // Request goes to the agent port; the response comes back from it.
+        let request = packets[0].0;
+        let response = packets[1].0;
+        assert_eq!(&request[22..24], &161u16.to_be_bytes());
+        assert_eq!(&response[20..22], &161u16.to_be_bytes());
+        assert!(request.windows(6).any(|window| window == b"secret"));
+    }
+
+    #[tokio::test]
+    async fn records_sent_request_when_exchange_is_cancelled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.pcap");
+        let snapshot = replay().snapshot();
+        let replay = ReplayTransport::builder(snapshot)
+            .latency(Duration::from_secs(60))
+            .build();
+        let recording = RecordingTransport::builder(replay, &path).build().unwrap();
+        let client = ClientBuilder::new(Auth::v2c("public"))
+            .build_with_transport(recording.clone())
+            .unwrap();
+        let name = oid!(1, 3, 6, 1, 2, 1, 1, 5, 0);
+        let get = client.get(&name);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), get)
+                .await
+                .is_err()
+        );
+        recording.flush().await;
+
+        assert_eq!(recording.stats().packets_written, 1);
+        let data = fs::read(&path).unwrap();
+        let packets = pcap_packets(&data);
+        assert_eq!(packets.len(), 1);
+        assert_eq!(&packets[0].0[22..24], &161u16.to_be_bytes());
+    }
+
+    #[tokio::test]
+    async fn redacts_communities_and_writes_pcapng_direction() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.pcapng");
+        let recording = RecordingTransport::builder(replay(), &path)
+            .format(CaptureFormat::PcapNg)
+            .redact_communities(true)
+            .build()
+            .unwrap();
+        let client = ClientBuilder::new(Auth::v2c("secret"))
+            .build_with_transport(recording.clone())
+            .unwrap();
+        let response = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 5, 0)).await.unwrap();
+        assert_eq!(response.single().unwrap().value.as_str(), Some("core-1"));
+        recording.flush().await;
+
+        let data = fs::read(&path).unwrap();
+        assert!(!data.windows(6).any(|window| window == b"secret"));
+        assert_eq!(
+            data.windows(6)
+                .filter(|window| *window == b"******")
+                .count(),
+            2
+        );
+
+        let mut blocks = Vec::new();
+        let mut rest = data.as_slice();
+        while !rest.is_empty() {
+            let kind = u32::from_le_bytes(rest[..4].try_into().unwrap());
+            let len = u32::from_le_bytes(rest[4..8].try_into().unwrap()) as usize;
+            assert_eq!(len % 4, 0);
+            assert_eq!(&rest[len - 4..len], &rest[4..8]);
+            blocks.push((kind, &rest[..len]));
+            rest = &rest[len..];
+        }
+        let kinds: Vec<_> = blocks.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds, [0x0a0d_0d0a, 1, 6, 6]);
+        let flags = |block: &[u8]| {
+            u32::from_le_bytes(block[block.len() - 12..block.len() - 8].try_into().unwrap())
+        };
+        assert_eq!(flags(blocks[2].1), 0b10);
+        assert_eq!(flags(blocks[3].1), 0b01);
+    }
+
+    #[tokio::test]
+    async fn size_limit_rotates_or_stops_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let name = oid!(1, 3, 6, 1, 2, 1, 1, 5, 0);
+
+        let path = dir.path().join("rotating.pcap");
+        let recording = RecordingTransport::builder(replay(), &path)
+            .max_file_size(150)
+            .max_files(2)
+            .build()
+            .unwrap();
+        let client = ClientBuilder::new(Auth::v2c("public"))
+            .build_with_transport(recording.clone())
+            .unwrap();
+        for _ in 0..3 {
+            client.get(&name).await.unwrap();
+        }
+        recording.flush().await;
+        let stats = recording.stats();
+        assert_eq!(stats.packets_written, 6);
+        assert_eq!(stats.rotations, 5);
+        assert!(dir.path().join("rotating.pcap.1").exists());
+        assert!(!dir.path().join("rotating.pcap.2").exists());
+        assert_eq!(pcap_packets(&fs::read(&path).unwrap()).len(), 1);
+
+        let path = dir.path().join("bounded.pcap");
+        let recording = RecordingTransport::builder(replay(), &path)
+            .max_file_size(200)
+            .build()
+            .unwrap();
+        let client = ClientBuilder::new(Auth::v2c("public"))
+            .build_with_transport(recording.clone())
+            .unwrap();
+        client.get(&name).await.unwrap();
+        client.get(&name).await.unwrap();
+        recording.flush().await;
+        let stats = recording.stats();
+        assert_eq!(stats.packets_written + stats.packets_skipped, 4);
+        assert!(stats.packets_skipped > 0);
+        assert!(fs::metadata(&path).unwrap().len() <= 200);
+    }
+
+    #[tokio::test]
+    async fn pcap_snaplen_covers_maximum_ipv6_datagram() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.pcap");
+        let peer: SocketAddr = "[2001:db8::1]:161".parse().unwrap();
+        let replay = ReplayTransport::builder(replay().snapshot())
+            .peer_addr(peer)
+            .build();
+        let recording = RecordingTransport::builder(replay, &path).build().unwrap();
+        recording.record(Direction::Outbound, peer, &[0x30; MAX_CAPTURED_PAYLOAD]);
+        recording.flush().await;
+
+        let data = fs::read(&path).unwrap();
+        let snaplen = u32::from_le_bytes(data[16..20].try_into().unwrap());
+        let packets = pcap_packets(&data);
+        assert_eq!(packets.len(), 1);
+        let (packet, original) = packets[0];
+        assert_eq!(packet[0] >> 4, 6);
+        assert_eq!(packet.len(), 65_555);
+        assert_eq!(original as usize, packet.len());
+        assert!(packet.len() <= snaplen as usize);
+    }
+
+    /// Waiting for room in a full writer queue must not need a Tokio timer.
+    #[test]
+    fn flush_waits_for_a_full_queue_outside_tokio() {
+        let (writer, commands) = mpsc::sync_channel(1);
+        let recording = RecordingTransport {
+            inner: replay(),
+            shared: Arc::new(RecordingShared {
+                writer,
+                redact_communities: false,
+                counters: Arc::default(),
+            }),
+        };
+        let peer: SocketAddr = "192.0.2.1:161".parse().unwrap();
+        recording.record(Direction::Outbound, peer, b"queued");
+        let drain = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            let mut packets = 0;
+            for command in &commands {
+                match command {
+                    WriterCommand::Packet { .. } => packets += 1,
+                    WriterCommand::Flush(done) => {
+                        let _ = done.send(());
+                        return packets;
+                    }
+                }
+            }
+            packets
+        });
+
+        futures::executor::block_on(recording.flush());
+        assert_eq!(drain.join().unwrap(), 1);
+    }
+
+    #[test]
+    fn ipv6_udp_checksum_verifies() {
+        let source: SocketAddr = "[2001:db8::1]:40000".parse().unwrap();
+        let destination: SocketAddr = "192.0.2.1:161".parse().unwrap();
+        let packet = synthetic_udp_packet(source, destination, b"odd");
+        assert_eq!(packet[0] >> 4, 6);
+        let mut pseudo = Vec::new();
+        pseudo.extend_from_slice(&packet[8..40]);
+        pseudo.extend_from_slice(&11u32.to_be_bytes());
+        pseudo.extend_from_slice(&[0, 0, 0, 17]);
+        assert_eq!(internet_checksum(&[&pseudo, &packet[40..]]), 0);
+    }
+}
@@ -0,0 +1,804 @@
+//! In-memory replay transport serving recorded device data.
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use super::{Candidate, RequestRegistration, ResponseIdentity, Transport};
+use crate::community::Community;
+use crate::compatibility::DecodeConfig;
+use crate::error::{Error, Result};
+use crate::message::{CommunityMessage, Message};
+use crate::oid::Oid;
+use crate::pdu::{ErrorIndex, OutboundErrorStatus, Pdu, PduType, ResponsePdu};
+use crate::snapshot::MibSnapshot;
+use crate::value::Value;
+use crate::varbind::VarBind;
+use crate::version::{CommunityVersion, Version};
+
+/// Upper bound on bindings generated for one GETBULK response.
+///
+/// The exact response-size check still applies; this only bounds the work
+/// done for very large `max-repetitions` values before encoding.
+const MAX_BULK_VARBINDS: usize = 10_000;
+
+/// How a [`ReplayTransport`] answers GETBULK requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GetBulkBehavior {
+    /// Answer as RFC 3416 Section 4.2.3 describes.
+    #[default]
+    Standard,
+    /// Never answer, like agents that silently discard GETBULK.
+    NoResponse,
+    /// Answer with `genErr`.
+    GenErr,
+    /// Ignore `max-repetitions` and answer each binding like GETNEXT.
+    IgnoreRepetitions,
+}
+
+/// Builder for [`ReplayTransport`].
+///
+/// Every fault is disabled by default, so an unconfigured replay transport
+/// behaves like a well-formed, lossless agent.
+#[derive(Debug, Clone)]
+pub struct ReplayTransportBuilder {
+    data: MibSnapshot,
+    peer: SocketAddr,
+    community: Option<Community>,
+    latency: Duration,
+    drop_every: Option<u64>,
+    too_big_threshold: Option<usize>,
+    get_bulk: GetBulkBehavior,
+    reorder_bulk_responses: bool,
+    stale_responses: bool,
+    trailing_bytes: bool,
+}
+
+impl ReplayTransportBuilder {
+    fn new(data: MibSnapshot) -> Self {
+        Self {
+            data,
+            peer: SocketAddr::from((Ipv4Addr::LOCALHOST, 161)),
+            community: None,
+            latency: Duration::ZERO,
+            drop_every: None,
+            too_big_threshold: None,
+            get_bulk: GetBulkBehavior::Standard,
+            reorder_bulk_responses: false,
+            stale_responses: false,
+            trailing_bytes: false,
+        }
+    }
+
+    /// Address reported as the peer and as the source of every response.
+    ///
+    /// Default: `127.0.0.1:161`.
+    #[must_use]
+    pub fn peer_addr(mut self, addr: SocketAddr) -> Self {
+        self.peer = addr;
+        self
+    }
+
+    /// Only answer requests carrying this community.
+    ///
+    /// Requests with any other community are discarded without a response,
+    /// as agents do. By default every community is answered.
+    #[must_use]
+    pub fn community(mut self, community: impl Into<Community>) -> Self {
+        self.community = Some(community.into());
+        self
+    }
+
+    /// Delay before each response is delivered.
+    #[must_use]
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Discard every `n`th request without answering.
+    ///
+    /// Loss is deterministic: with `n = 3`, the third, sixth, and so on
+    /// requests are lost. A value of 0 or 1 would lose everything or nothing
+    /// in surprising ways, so values below 2 disable loss.
+    #[must_use]
+    pub fn drop_every(mut self, n: u64) -> Self {
+        self.drop_every = (n >= 2).then_some(n);
+        self
+    }
+
+    /// Answer with `tooBig` when the encoded response exceeds `bytes`.
+    ///
+    /// When even the `tooBig` response is larger, the request is discarded.
+    #[must_use]
+    pub fn too_big_threshold(mut self, bytes: usize) -> Self {
+        self.too_big_threshold = Some(bytes);
+        self
+    }
+
+    /// Select how GETBULK requests are answered.
+    #[must_use]
+    pub fn get_bulk_behavior(mut self, behavior: GetBulkBehavior) -> Self {
+        self.get_bulk = behavior;
+        self
+    }
+
+    /// Swap the first two bindings of every GETBULK response with more than
+    /// two bindings.
+    ///
+    /// The response is then non-increasing while its last binding still
+    /// advances the walk, which exercises
+    /// [`OidOrdering`](crate::OidOrdering) handling.
+    #[must_use]
+    pub fn reorder_bulk_responses(mut self, enabled: bool) -> Self {
+        self.reorder_bulk_responses = enabled;
+        self
+    }
+
+    /// Deliver the previous exchange's response before each new response.
+    ///
+    /// The stale response carries the old request ID and must be discarded by
+    /// response correlation without ending the pending request.
+    #[must_use]
+    pub fn stale_responses(mut self, enabled: bool) -> Self {
+        self.stale_responses = enabled;
+        self
+    }
+
+    /// Append two zero bytes after every encoded response message.
+    ///
+    /// [`DecodeConfig::DEFAULT`] accepts the suffix and records an anomaly;
+    /// [`DecodeConfig::STRICT`] rejects the response.
+    #[must_use]
+    pub fn trailing_bytes(mut self, enabled: bool) -> Self {
+        self.trailing_bytes = enabled;
+        self
+    }
+
+    /// Build the transport.
+    #[must_use]
+    pub fn build(self) -> ReplayTransport {
+        ReplayTransport {
+            inner: Arc::new(ReplayInner {
+                data: Mutex::new(self.data),
+                peer: self.peer,
+                community: self.community,
+                latency: self.latency,
+                drop_every: self.drop_every,
+                too_big_threshold: self.too_big_threshold,
+                get_bulk: self.get_bulk,
+                reorder_bulk_responses: self.reorder_bulk_responses,
+                stale_responses: self.stale_responses,
+                trailing_bytes: self.trailing_bytes,
+                previous_response: Mutex::new(None),
+                requests: AtomicU64::new(0),
+                dropped: AtomicU64::new(0),
+                silent_drops: AtomicU32::new(0),
+            }),
+        }
+    }
+}
+
+/// Transport that answers requests from an in-memory OID tree.
+///
+/// Requests are BER-decoded and responses BER-encoded exactly as an agent
+/// would, so a [`Client`](crate::Client) built with
+/// [`ClientBuilder::build_with_transport`](crate::ClientBuilder::build_with_transport)
+/// exercises the same correlation, decoding, and walk logic as on the wire.
+/// GET, GETNEXT, GETBULK, SET, and InformRequest are answered for SNMPv1 and
+/// SNMPv2c; SNMPv3 requests return a configuration error. SET replaces
+/// existing values of the same type and cannot create new objects.
+///
+/// The transport is unreliable, so clients apply their retry policy to
+/// dropped requests. Clones share data, faults, and counters.
+///
+/// ```rust
+/// use async_snmp::record::snmpwalk;
+/// use async_snmp::snapshot::MibSnapshot;
+/// use async_snmp::transport::ReplayTransport;
+/// use async_snmp::{Auth, ClientBuilder, oid};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> async_snmp::Result<()> {
+/// let recorded = ".1.3.6.1.2.1.1.5.0 = STRING: \"core-1\"\n";
+/// let data = MibSnapshot::from(snmpwalk::parse(recorded).unwrap());
+/// let client = ClientBuilder::new(Auth::v2c("public"))
+///     .build_with_transport(ReplayTransport::new(data))?;
+///
+/// let response = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 5, 0)).await?;
+/// assert_eq!(response.single().unwrap().value.as_str(), Some("core-1"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplayTransport {
+    inner: Arc<ReplayInner>,
+}
+
+#[derive(Debug)]
+struct ReplayInner {
+    data: Mutex<MibSnapshot>,
+    peer: SocketAddr,
+    community: Option<Community>,
+    latency: Duration,
+    drop_every: Option<u64>,
+    too_big_threshold: Option<usize>,
+    get_bulk: GetBulkBehavior,
+    reorder_bulk_responses: bool,
+    stale_responses: bool,
+    trailing_bytes: bool,
+    previous_response: Mutex<Option<Bytes>>,
+    requests: AtomicU64,
+    dropped: AtomicU64,
+    silent_drops: AtomicU32,
+}
+
+impl ReplayTransport {
+    /// Create a fault-free replay transport serving `data`.
+    #[must_use]
+    pub fn new(data: impl Into<MibSnapshot>) -> Self {
+        Self::builder(data).build()
+    }
+
+    /// Create a builder for a replay transport serving `data`.
+    #[must_use]
+    pub fn builder(data: impl Into<MibSnapshot>) -> ReplayTransportBuilder {
+        ReplayTransportBuilder::new(data.into())
+    }
+
+    /// Return a copy of the data currently served, including applied SETs.
+    #[must_use]
+    pub fn snapshot(&self) -> MibSnapshot {
+        self.lock_data().clone()
+    }
+
+    /// Number of requests received, including dropped ones.
+    #[must_use]
+    pub fn requests(&self) -> u64 {
+        self.inner.requests.load(Ordering::Relaxed)
+    }
+
+    /// Number of requests discarded without a response.
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.inner.dropped.load(Ordering::Relaxed)
+    }
+
+    fn lock_data(&self) -> std::sync::MutexGuard<'_, MibSnapshot> {
+        self.inner
+            .data
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    fn timeout_error(&self, elapsed: Duration) -> Box<Error> {
+        Error::Timeout {
+            target: self.inner.peer,
+            elapsed,
+            retries: 0,
+        }
+        .boxed()
+    }
+
+    /// Produce the encoded response for one request, or `None` when the
+    /// request is discarded.
+    fn respond(&self, data: &[u8]) -> Result<Option<Bytes>> {
+        let sequence = self.inner.requests.fetch_add(1, Ordering::Relaxed) + 1;
+        if self
+            .inner
+            .drop_every
+            .is_some_and(|n| sequence.is_multiple_of(n))
+        {
+            return Ok(None);
+        }
+
+        let message = Message::decode(Bytes::copy_from_slice(data), DecodeConfig::DEFAULT)?.value;
+        let Message::Community(message) = message else {
+            return Err(
+                Error::Config("replay transport serves SNMPv1 and SNMPv2c only".into()).boxed(),
+            );
+        };
+        let (version, community, pdu) = message.into_parts();
+        if self
+            .inner
+            .community
+            .as_ref()
+            .is_some_and(|expected| !expected.matches(community.as_bytes()))
+        {
+            return Ok(None);
+        }
+        let Some(request) = pdu.standard().cloned() else {
+            return Ok(None);
+        };
+        let community_version = match version {
+            Version::V1 => CommunityVersion::V1,
+            _ => CommunityVersion::V2c,
+        };
+
+        let candidate = match request.pdu_type() {
+            PduType::GetRequest => self.get(version, &request)?,
+            PduType::GetNextRequest => self.get_next(version, &request)?,
+            PduType::GetBulkRequest => match self.inner.get_bulk {
+                GetBulkBehavior::Standard => self.get_bulk(version, &request, None)?,
+                GetBulkBehavior::NoResponse => return Ok(None),
+                GetBulkBehavior::GenErr => error_response(
+                    version,
+                    &request,
+                    OutboundErrorStatus::GenErr,
+                    1.min(request.varbinds().len()),
+                )?,
+                GetBulkBehavior::IgnoreRepetitions => self.get_bulk(version, &request, Some(1))?,
+            },
+            PduType::SetRequest => self.set(version, &request)?,
+            PduType::InformRequest => {
+                ResponsePdu::success(version, request.request_id(), request.varbinds().to_vec())?
+            }
+            _ => return Ok(None),
+        };
+
+        let finalized = crate::response_finalizer::finalize_response(
+            version,
+            &request,
+            candidate.into_raw(),
+            self.inner.too_big_threshold.unwrap_or(usize::MAX),
+            None,
+            &self.inner.silent_drops,
+            |response| {
+                CommunityMessage::new(community_version, community.clone(), response)?.encode()
+            },
+        )?;
+        let Some(response) = finalized.into_bytes() else {
+            return Ok(None);
+        };
+        if !self.inner.trailing_bytes {
+            return Ok(Some(response));
+        }
+        let mut padded = response.to_vec();
+        padded.extend_from_slice(&[0, 0]);
+        Ok(Some(Bytes::from(padded)))
+    }
+
+    fn get(&self, version: Version, request: &Pdu) -> Result<ResponsePdu> {
+        let data = self.lock_data();
+        let mut varbinds = Vec::with_capacity(request.varbinds().len());
+        for (index, varbind) in request.varbinds().iter().enumerate() {
+            let value = match data.get(&varbind.oid) {
+                Some(value) if representable(version, value) => value.clone(),
+                _ if version == Version::V1 => {
+                    return error_response(
+                        version,
+                        request,
+                        OutboundErrorStatus::NoSuchName,
+                        index + 1,
+                    );
+                }
+                _ if varbind
+                    .oid
+                    .parent()
+                    .is_some_and(|parent| data.subtree(&parent).next().is_some()) =>
+                {
+                    Value::NoSuchInstance
+                }
+                _ => Value::NoSuchObject,
+            };
+            varbinds.push(VarBind::new(varbind.oid.clone(), value));
+        }
+        ResponsePdu::success(version, request.request_id(), varbinds)
+    }
+
+    fn get_next(&self, version: Version, request: &Pdu) -> Result<ResponsePdu> {
+        let data = self.lock_data();
+        let mut varbinds = Vec::with_capacity(request.varbinds().len());
+        for (index, varbind) in request.varbinds().iter().enumerate() {
+            match successor(&data, version, &varbind.oid) {
+                Some(next) => varbinds.push(next),
+                None if version == Version::V1 => {
+                    return error_response(
+                        version,
+                        request,
+                        OutboundErrorStatus::NoSuchName,
+                        index + 1,
+                    );
+                }
+                None => varbinds.push(VarBind::new(varbind.oid.clone(), Value::EndOfMibView)),
+            }
+        }
+        ResponsePdu::success(version, request.request_id(), varbinds)
+    }
+
+    fn get_bulk(
+        &self,
+        version: Version,
+        request: &Pdu,
+        repetitions_override: Option<u32>,
+    ) -> Result<ResponsePdu> {
+        let (non_repeaters, max_repetitions) = request.get_bulk_fields().unwrap_or((0, 0));
+        let max_repetitions = repetitions_override.unwrap_or(max_repetitions);
+        let non_repeaters = (non_repeaters as usize).min(request.varbinds().len());
+        let (fixed, repeating) = request.varbinds().split_at(non_repeaters);
+
+        let data = self.lock_data();
+        let next_or_end = |oid: &Oid| {
+            successor(&data, version, oid)
+                .unwrap_or_else(|| VarBind::new(oid.clone(), Value::EndOfMibView))
+        };
+
+        let mut varbinds: Vec<VarBind> = fixed.iter().map(|vb| next_or_end(&vb.oid)).collect();
+        let mut cursors: Vec<Oid> = repeating.iter().map(|vb| vb.oid.clone()).collect();
+        for _ in 0..max_repetitions {
+            if cursors.is_empty() || varbinds.len() >= MAX_BULK_VARBINDS {
+                break;
+            }
+            let mut all_ended = true;
+            for cursor in &mut cursors {
+                let next = next_or_end(cursor);
+                if next.value != Value::EndOfMibView {
+                    all_ended = false;
+                    *cursor = next.oid.clone();
+                }
+                varbinds.push(next);
+            }
+            if all_ended {
+                break;
+            }
+        }
+        drop(data);
+
+        if self.inner.reorder_bulk_responses && varbinds.len() > 2 {
+            varbinds.swap(0, 1);
+        }
+        ResponsePdu::success(version, request.request_id(), varbinds)
+    }
+
+    fn set(&self, version: Version, request: &Pdu) -> Result<ResponsePdu> {
+        let mut data = self.lock_data();
+        for (index, varbind) in request.varbinds().iter().enumerate() {
+            let status = match data.get(&varbind.oid) {
+                Some(current) if current.kind() == varbind.value.kind() => continue,
+                Some(_) if version == Version::V1 => OutboundErrorStatus::BadValue,
+                Some(_) => OutboundErrorStatus::WrongType,
+                None if version == Version::V1 => OutboundErrorStatus::NoSuchName,
+                None => OutboundErrorStatus::NoCreation,
+            };
+            return error_response(version, request, status, index + 1);
+        }
+        for varbind in request.varbinds() {
+            data.insert(varbind.oid.clone(), varbind.value.clone());
+        }
+        ResponsePdu::success(version, request.request_id(), request.varbinds().to_vec())
+    }
+
+    async fn wait_until(
+        &self,
+        deadline: tokio::time::Instant,
+        started: tokio::time::Instant,
+    ) -> Box<Error> {
+        crate::runtime::sleep_until(deadline).await;
+        self.timeout_error(started.elapsed())
+    }
+}
+
+/// Return the next binding after `oid` that `version` can carry.
+fn successor(data: &MibSnapshot, version: Version, oid: &Oid) -> Option<VarBind> {
+    let mut cursor = oid;
+    loop {
+        let (next, value) = data.get_next(cursor)?;
+        if representable(version, value) {
+            return Some(VarBind::new(next.clone(), value.clone()));
+        }
+        cursor = next;
+    }
+}
+
+/// SNMPv1 cannot carry Counter64 or exception values (RFC 3584 Section 4.2.2).
+fn representable(version: Version, value: &Value) -> bool {
+    version != Version::V1 || !(matches!(value, Value::Counter64(_)) || value.is_exception())
+}
+
+fn error_response(
+    version: Version,
+    request: &Pdu,
+    status: OutboundErrorStatus,
+    index: usize,
+) -> Result<ResponsePdu> {
+    let index = u32::try_from(index)
+        .ok()
+        .filter(|index| *index > 0)
+        .map(|index| ErrorIndex::new(index, request.varbinds().len()))
+        .transpose()?;
+    ResponsePdu::new(
+        version,
+        request.request_id(),
+        status,
+        index,
+        request.varbinds().to_vec(),
+    )
+}
+
+impl Transport for ReplayTransport {
+    async fn send(&self, data: &[u8]) -> Result<()> {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity())
+    }
+
+    async fn request_with<T, F>(
+        &self,
+        data: &[u8],
+        registration: RequestRegistration,
+        mut validate: F,
+    ) -> Result<T>
+    where
+        T: Send,
+        F: FnMut(Bytes, SocketAddr) -> Result<Candidate<T>> + Send,
+    {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity())?;
+        let started = tokio::time::Instant::now();
+        let deadline = registration.deadline();
+        if started >= deadline {
+            return Err(self.timeout_error(Duration::ZERO));
+        }
+        registration.mark_sent();
+
+        let Some(response) = self.respond(data)? else {
+            self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+            return Err(self.wait_until(deadline, started).await);
+        };
+
+        if !self.inner.latency.is_zero() {
+            let ready = started + self.inner.latency;
+            if ready >= deadline {
+                return Err(self.wait_until(deadline, started).await);
+            }
+            crate::runtime::sleep_until(ready).await;
+        }
+
+        let stale = {
+            let mut previous = self
+                .inner
+                .previous_response
+                .lock()
+                .unwrap_or_else(|error| error.into_inner());
+            previous.replace(response.clone())
+        };
+        let candidates = stale
+            .filter(|_| self.inner.stale_responses)
+            .into_iter()
+            .chain(std::iter::once(response));
+
+        for candidate in candidates {
+            if registration.evaluate_response_identity(&candidate, true) == ResponseIdentity::Reject
+            {
+                continue;
+            }
+            if let Candidate::Accept(value) = validate(candidate, self.inner.peer)? {
+                return Ok(value);
+            }
+        }
+        Err(self.wait_until(deadline, started).await)
+    }
+
+    fn peer_addr(&self) -> SocketAddr {
+        self.inner.peer
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::LOCALHOST, 0))
+    }
+
+    fn is_reliable(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{Auth, ClientBuilder, OidOrdering, Retry, WalkMethod, WalkOptions};
+    use crate::error::WalkAbortReason;
+    use crate::oid;
+
+    fn system() -> MibSnapshot {
+        let system = oid!(1, 3, 6, 1, 2, 1, 1);
+        (1..=9)
+            .map(|column| {
+                VarBind::new(
+                    system.child(column).child(0),
+                    Value::OctetString(Bytes::from(format!("value {column}"))),
+                )
+            })
+            .chain([VarBind::new(
+                oid!(1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 1),
+                Value::Counter64(5),
+            )])
+            .collect()
+    }
+
+    fn client(transport: ReplayTransport, auth: Auth) -> crate::Client<ReplayTransport> {
+        ClientBuilder::new(auth)
+            .retry(Retry::none())
+            .request_timeout(Duration::from_secs(1))
+            .build_with_transport(transport)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_and_set_round_trip_through_ber() {
+        let transport = ReplayTransport::new(system());
+        let client = client(transport.clone(), Auth::v2c("public"));
+
+        let name = oid!(1, 3, 6, 1, 2, 1, 1, 5, 0);
+        let response = client.get(&name).await.unwrap();
+        assert_eq!(response.single().unwrap().value.as_str(), Some("value 5"));
+
+        let missing = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 5, 1)).await.unwrap();
+        assert_eq!(missing.single().unwrap().value, Value::NoSuchInstance);
+
+        client.set(&name, Value::from("renamed")).await.unwrap();
+        assert_eq!(
+            transport.snapshot().get(&name),
+            Some(&Value::from("renamed"))
+        );
+
+        let error = client.set(&name, Value::Integer(1)).await.unwrap_err();
+        assert!(matches!(
+            *error,
+            Error::Snmp {
+                status: crate::ErrorStatus::WrongType,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn v1_skips_counter64_and_reports_no_such_name() {
+        let client = client(ReplayTransport::new(system()), Auth::v1("public"));
+        let walked = client
+            .walk(oid!(1, 3, 6, 1, 2, 1))
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(walked.len(), 9);
+
+        let error = client
+            .get(&oid!(1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 1))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            *error,
+            Error::Snmp {
+                status: crate::ErrorStatus::NoSuchName,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn bulk_walk_matches_get_next_walk() {
+        let client = client(ReplayTransport::new(system()), Auth::v2c("public"));
+        let bulk = client
+            .walk_with(
+                oid!(1, 3, 6, 1, 2, 1),
+                WalkOptions {
+                    method: WalkMethod::GetBulk,
+                    max_repetitions: 4,
+                    ..WalkOptions::default()
+                },
+            )
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let next = client
+            .walk_with(
+                oid!(1, 3, 6, 1, 2, 1),
+                WalkOptions {
+                    method: WalkMethod::GetNext,
+                    ..WalkOptions::default()
+                },
+            )
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(bulk.len(), 10);
+        assert_eq!(bulk, next);
+    }
+
+    #[tokio::test]
+    async fn reordered_bulk_responses_need_non_increasing_ordering() {
+        let transport = ReplayTransport::builder(system())
+            .reorder_bulk_responses(true)
+            .build();
+        let client = client(transport, Auth::v2c("public"));
+        let strict = WalkOptions {
+            method: WalkMethod::GetBulk,
+            max_repetitions: 5,
+            ..WalkOptions::default()
+        };
+
+        let error = client
+            .walk_with(oid!(1, 3, 6, 1, 2, 1), strict)
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            *error,
+            Error::WalkAborted {
+                reason: WalkAbortReason::NonIncreasing,
+                ..
+            }
+        ));
+
+        let tolerant = WalkOptions {
+            ordering: OidOrdering::AllowNonIncreasing,
+            result_limit: Some(100),
+            ..strict
+        };
+        let walked = client
+            .walk_with(oid!(1, 3, 6, 1, 2, 1), tolerant)
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(walked.len(), 10);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dropped_and_unanswered_requests_time_out() {
+        let transport = ReplayTransport::builder(system())
+            .drop_every(2)
+            .get_bulk_behavior(GetBulkBehavior::NoResponse)
+            .build();
+        let client = client(transport.clone(), Auth::v2c("public"));
+        let name = oid!(1, 3, 6, 1, 2, 1, 1, 5, 0);
+
+        client.get(&name).await.unwrap();
+        let error = client.get(&name).await.unwrap_err();
+        assert!(matches!(*error, Error::Timeout { .. }));
+        let error = client.get_bulk(&[name], 0, 5).await.unwrap_err();
+        assert!(matches!(*error, Error::Timeout { .. }));
+        assert_eq!(transport.requests(), 3);
+        assert_eq!(transport.dropped(), 2);
+    }
+
+    #[tokio::test]
+    async fn too_big_threshold_triggers_get_recovery() {
+        let transport = ReplayTransport::builder(system())
+            .too_big_threshold(80)
+            .build();
+        let client = client(transport, Auth::v2c("public"));
+        let oids: Vec<_> = (1..=4)
+            .map(|column| oid!(1, 3, 6, 1, 2, 1, 1).child(column).child(0))
+            .collect();
+
+        let response = client.get_many(&oids).await.unwrap();
+        assert_eq!(response.varbinds.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn stale_responses_and_trailing_bytes_follow_correlation_and_decode_policy() {
+        let transport = ReplayTransport::builder(system())
+            .stale_responses(true)
+            .trailing_bytes(true)
+            .build();
+        let client = client(transport.clone(), Auth::v2c("public"));
+        let name = oid!(1, 3, 6, 1, 2, 1, 1, 5, 0);
+        client.get(&name).await.unwrap();
+        let response = client.get(&name).await.unwrap();
+        assert_eq!(response.single().unwrap().value.as_str(), Some("value 5"));
+        assert!(!response.metadata.decode_anomalies.is_empty());
+
+        let strict = ClientBuilder::new(Auth::v2c("public"))
+            .retry(Retry::none())
+            .request_timeout(Duration::from_millis(50))
+            .decode_config(DecodeConfig::STRICT)
+            .build_with_transport(transport)
+            .unwrap();
+        assert!(strict.get(&name).await.is_err());
+    }
+}
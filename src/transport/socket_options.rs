@@ -0,0 +1,91 @@
+//! IP-level options for client sockets.
+
+use std::net::IpAddr;
+
+/// Interface, source address, and IP header options for a client socket.
+///
+/// Applied before the socket is bound (UDP) or connected (TCP). Options left
+/// unset keep the operating system defaults. Setting an option the platform
+/// does not support fails socket creation with
+/// [`std::io::ErrorKind::Unsupported`] instead of being silently ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use async_snmp::transport::SocketOptions;
+///
+/// // Leave through the management VRF, marked as network control (CS6).
+/// let options = SocketOptions::new().bind_device("mgmt").dscp(48);
+/// assert_eq!(options, SocketOptions::new().bind_device("mgmt").tos(192).ipv6_tclass(192));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SocketOptions {
+    pub(crate) bind_device: Option<String>,
+    pub(crate) source_addr: Option<IpAddr>,
+    pub(crate) tos: Option<u8>,
+    pub(crate) ipv6_tclass: Option<u8>,
+    pub(crate) ttl: Option<u32>,
+}
+
+impl SocketOptions {
+    /// Create options that keep every operating system default.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind the socket to a network interface (`SO_BINDTODEVICE`).
+    ///
+    /// Traffic then leaves through, and is only received on, that interface.
+    /// Naming a Linux VRF device places the socket in the VRF. Linux and
+    /// Android only; binding usually requires `CAP_NET_RAW`.
+    #[must_use]
+    pub fn bind_device(mut self, interface: impl Into<String>) -> Self {
+        self.bind_device = Some(interface.into());
+        self
+    }
+
+    /// Send from this local address.
+    ///
+    /// For UDP this replaces the IP of the bind address and keeps its port.
+    /// For TCP the socket is bound to this address on an ephemeral port
+    /// before connecting.
+    #[must_use]
+    pub fn source_addr(mut self, addr: IpAddr) -> Self {
+        self.source_addr = Some(addr);
+        self
+    }
+
+    /// Set the IPv4 type-of-service byte (`IP_TOS`).
+    #[must_use]
+    pub fn tos(mut self, tos: u8) -> Self {
+        self.tos = Some(tos);
+        self
+    }
+
+    /// Set the IPv6 traffic class (`IPV6_TCLASS`).
+    #[must_use]
+    pub fn ipv6_tclass(mut self, tclass: u8) -> Self {
+        self.ipv6_tclass = Some(tclass);
+        self
+    }
+
+    /// Mark traffic with a DSCP code point (0-63) for both IPv4 and IPv6.
+    ///
+    /// Sets [`tos`](Self::tos) and [`ipv6_tclass`](Self::ipv6_tclass) to
+    /// `dscp << 2`, leaving the ECN bits clear. Bits above the 6-bit DSCP
+    /// range are ignored.
+    #[must_use]
+    pub fn dscp(self, dscp: u8) -> Self {
+        let value = (dscp & 0x3f) << 2;
+        self.tos(value).ipv6_tclass(value)
+    }
+
+    /// Set the unicast TTL (`IP_TTL`) or, on IPv6 sockets, hop limit
+    /// (`IPV6_UNICAST_HOPS`).
+    #[must_use]
+    pub fn ttl(mut self, ttl: u32) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+}
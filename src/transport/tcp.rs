@@ -71,16 +71,19 @@
 //! # }
 //! ```
 
-use super::{Candidate, RequestRegistration, ResponseIdentity, Transport, extract_request_id};
+use super::{
+    Candidate, RequestRegistration, ResponseIdentity, SocketOptions, Transport, extract_request_id,
+};
 use crate::error::{ConstructionStage, Error, Result};
 use crate::message_size::ReceiveLimits;
+use crate::runtime::TcpStream;
+use crate::util::tcp_socket;
 use bytes::{Bytes, BytesMut};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::sync::{Mutex, OwnedMutexGuard};
 #[cfg(test)]
 use tokio::time::timeout;
@@ -149,6 +152,7 @@ impl Default for TcpOptions {
 pub struct TcpTransportBuilder {
     connect_timeout: Option<Duration>,
     options: TcpOptions,
+    socket_options: SocketOptions,
 }
 
 impl TcpTransportBuilder {
@@ -158,6 +162,7 @@ impl TcpTransportBuilder {
         Self {
             connect_timeout: None,
             options: TcpOptions::default(),
+            socket_options: SocketOptions::new(),
         }
     }
 
@@ -187,6 +192,13 @@ impl TcpTransportBuilder {
         self
     }
 
+    /// Set interface, source address, and IP header options for the socket.
+    #[must_use]
+    pub fn socket_options(mut self, options: SocketOptions) -> Self {
+        self.socket_options = options;
+        self
+    }
+
     /// Connect to the target address.
     pub async fn connect(self, target: SocketAddr) -> Result<TcpTransport> {
         // Validate the deadline before normalizing limits or starting connect
@@ -198,47 +210,21 @@ impl TcpTransportBuilder {
             .transpose()?;
         let receive_limits = ReceiveLimits::tcp(self.options.max_message_size)
             .map_err(|error| Error::Config(error.to_string().into()).boxed())?;
-        let stream = match (self.connect_timeout, connect_deadline) {
-            (Some(_), Some(deadline)) if tokio::time::Instant::now() >= deadline => {
-                return Err(Error::ConstructionTimeout {
-                    target: target.into(),
-                    stage: ConstructionStage::Connect,
-                    elapsed: started.elapsed(),
-                }
-                .boxed());
-            }
-            (Some(_), Some(deadline)) => {
-                tokio::time::timeout_at(deadline, TcpStream::connect(target))
-                    .await
-                    .map_err(|_| {
-                        Error::ConstructionTimeout {
-                            target: target.into(),
-                            stage: ConstructionStage::Connect,
-                            elapsed: started.elapsed(),
-                        }
-                        .boxed()
-                    })?
-                    .map_err(|e| Error::Network { target, source: e }.boxed())?
-            }
-            (None, None) => TcpStream::connect(target)
-                .await
-                .map_err(|e| Error::Network { target, source: e }.boxed())?,
-            _ => unreachable!("deadline presence follows timeout presence"),
-        };
+        let stream =
+            connect_stream(target, &self.socket_options, started, connect_deadline).await?;
 
         let local_addr = stream
             .local_addr()
             .map_err(|e| Error::Network { target, source: e }.boxed())?;
 
         Ok(TcpTransport {
-            inner: Arc::new(TcpTransportInner {
-                stream: Arc::new(Mutex::new(stream)),
+            inner: Arc::new(TcpTransportInner::new(
+                stream,
                 target,
                 local_addr,
                 receive_limits,
-                send_capacity: self.options.send_capacity,
-                poisoned: AtomicBool::new(false),
-            }),
+                self.options.send_capacity,
+            )),
         })
     }
 }
@@ -301,9 +287,9 @@ pub struct TcpTransport {
     inner: Arc<TcpTransportInner>,
 }
 
-struct TcpTransportInner {
-    /// The TCP stream, wrapped in Arc for owned guard pattern
-    stream: Arc<Mutex<TcpStream>>,
+pub(super) struct TcpTransportInner<S = TcpStream> {
+    /// The byte stream, wrapped in Arc for owned guard pattern
+    stream: Arc<Mutex<S>>,
     target: SocketAddr,
     local_addr: SocketAddr,
     /// One total-message bound shared by framing, decoding, and advertisement.
@@ -321,11 +307,144 @@ struct TcpTransportInner {
     poisoned: AtomicBool,
 }
 
-impl TcpTransportInner {
+impl<S> TcpTransportInner<S> {
+    /// Wrap a connected stream carrying BER-framed messages.
+    pub(super) fn new(
+        stream: S,
+        target: SocketAddr,
+        local_addr: SocketAddr,
+        receive_limits: ReceiveLimits,
+        send_capacity: usize,
+    ) -> Self {
+        Self {
+            stream: Arc::new(Mutex::new(stream)),
+            target,
+            local_addr,
+            receive_limits,
+            send_capacity,
+            poisoned: AtomicBool::new(false),
+        }
+    }
+
     /// Report whether the stream framing has been marked lost.
     fn is_poisoned(&self) -> bool {
         self.poisoned.load(Ordering::Acquire)
     }
+
+    pub(super) fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    pub(super) fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub(super) fn receive_limits(&self) -> ReceiveLimits {
+        self.receive_limits
+    }
+
+    pub(super) fn send_capacity(&self) -> usize {
+        self.send_capacity
+    }
+}
+
+impl<S> TcpTransportInner<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    /// Write one message under the stream lock.
+    pub(super) async fn send(&self, data: &[u8]) -> Result<()> {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity)?;
+        // Do not arm the transaction until the lock is acquired: cancellation
+        // while queued has not touched stream state and must leave it reusable.
+        let mut stream = self.stream.clone().lock_owned().await;
+        let target = self.target;
+        if self.is_poisoned() {
+            return Err(Error::Closed { target }.boxed());
+        }
+
+        let mut transaction = TcpTransactionGuard::new(self);
+        write_message(&mut *stream, target, data).await?;
+        transaction.disarm();
+        Ok(())
+    }
+
+    /// Write one message under one total timeout, including lock wait.
+    pub(super) async fn send_with_timeout(&self, data: &[u8], timeout: Duration) -> Result<()> {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity)?;
+        let target = self.target;
+        let deadline = transaction_deadline(timeout)?;
+        let mut stream = lock_stream_before(self, deadline, timeout).await?;
+
+        if self.is_poisoned() {
+            return Err(Error::Closed { target }.boxed());
+        }
+
+        // `lock_stream_before` rejects an exhausted budget. Once the guarded
+        // write is polled, cancellation or expiry may leave partial stream I/O.
+        let mut transaction = TcpTransactionGuard::unarmed(self);
+        crate::runtime::timeout_at(
+            deadline,
+            arm_when_polled(&mut transaction, write_message(&mut *stream, target, data)),
+        )
+        .await
+        .map_err(|_| timeout_error(target, timeout))??;
+        transaction.disarm();
+        Ok(())
+    }
+
+    /// Run one write-then-read exchange while holding the stream lock.
+    pub(super) async fn request_with<T, F>(
+        &self,
+        data: &[u8],
+        registration: RequestRegistration,
+        validate: F,
+    ) -> Result<T>
+    where
+        T: Send,
+        F: FnMut(Bytes, SocketAddr) -> Result<Candidate<T>> + Send,
+    {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity)?;
+        let request_id = registration.request_id();
+        let started = tokio::time::Instant::now();
+        let deadline = registration.deadline();
+        let recv_timeout = deadline.saturating_duration_since(started);
+        let target = self.target;
+        let mut stream = lock_stream_before(self, deadline, recv_timeout).await?;
+
+        if self.is_poisoned() {
+            return Err(Error::Closed { target }.boxed());
+        }
+        let mut transaction = TcpTransactionGuard::unarmed(self);
+
+        crate::runtime::timeout_at(
+            deadline,
+            arm_when_polled(&mut transaction, write_message(&mut *stream, target, data)),
+        )
+        .await
+        .map_err(|_| timeout_error(target, recv_timeout))??;
+        registration.mark_sent();
+
+        let result = crate::runtime::timeout_at(
+            deadline,
+            arm_when_polled(
+                &mut transaction,
+                read_validated_message(
+                    &mut *stream,
+                    target,
+                    self.receive_limits.accepted(),
+                    &registration,
+                    validate,
+                ),
+            ),
+        )
+        .await
+        .map_err(|_| CorrelatedReadError::Timeout)
+        .and_then(std::convert::identity);
+        let value = finish_correlated_read(result, target, request_id, recv_timeout)?;
+        transaction.disarm();
+        Ok(value)
+    }
 }
 
 /// Marks a locked stream unusable unless its transaction completes cleanly.
@@ -339,14 +458,14 @@ struct TcpTransactionGuard<'a> {
 }
 
 impl<'a> TcpTransactionGuard<'a> {
-    fn new(inner: &'a TcpTransportInner) -> Self {
+    fn new<S>(inner: &'a TcpTransportInner<S>) -> Self {
         Self {
             poisoned: &inner.poisoned,
             armed: true,
         }
     }
 
-    fn unarmed(inner: &'a TcpTransportInner) -> Self {
+    fn unarmed<S>(inner: &'a TcpTransportInner<S>) -> Self {
         Self {
             poisoned: &inner.poisoned,
             armed: false,
@@ -457,56 +576,24 @@ impl TcpTransport {
             .map_err(|e| Error::Network { target, source: e }.boxed())?;
 
         Ok(Self {
-            inner: Arc::new(TcpTransportInner {
-                stream: Arc::new(Mutex::new(stream)),
+            inner: Arc::new(TcpTransportInner::new(
+                Box::new(stream),
                 target,
                 local_addr,
                 receive_limits,
-                send_capacity: options.send_capacity,
-                poisoned: AtomicBool::new(false),
-            }),
+                options.send_capacity,
+            )),
         })
     }
 }
 
 impl Transport for TcpTransport {
     async fn send(&self, data: &[u8]) -> Result<()> {
-        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity())?;
-        // Do not arm the transaction until the lock is acquired: cancellation
-        // while queued has not touched stream state and must leave it reusable.
-        let mut stream = self.inner.stream.clone().lock_owned().await;
-        let target = self.inner.target;
-        if self.inner.is_poisoned() {
-            return Err(Error::Closed { target }.boxed());
-        }
-
-        let mut transaction = TcpTransactionGuard::new(&self.inner);
-        write_message(&mut *stream, target, data).await?;
-        transaction.disarm();
-        Ok(())
+        self.inner.send(data).await
     }
 
     async fn send_with_timeout(&self, data: &[u8], timeout: Duration) -> Result<()> {
-        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity())?;
-        let target = self.inner.target;
-        let deadline = transaction_deadline(timeout)?;
-        let mut stream = lock_stream_before(&self.inner, deadline, timeout).await?;
-
-        if self.inner.is_poisoned() {
-            return Err(Error::Closed { target }.boxed());
-        }
-
-        // `lock_stream_before` rejects an exhausted budget. Once the guarded
-        // write is polled, cancellation or expiry may leave partial stream I/O.
-        let mut transaction = TcpTransactionGuard::unarmed(&self.inner);
-        tokio::time::timeout_at(
-            deadline,
-            arm_when_polled(&mut transaction, write_message(&mut *stream, target, data)),
-        )
-        .await
-        .map_err(|_| timeout_error(target, timeout))??;
-        transaction.disarm();
-        Ok(())
+        self.inner.send_with_timeout(data, timeout).await
     }
 
     async fn request_with<T, F>(
@@ -519,53 +606,15 @@ impl Transport for TcpTransport {
         T: Send,
         F: FnMut(Bytes, SocketAddr) -> Result<Candidate<T>> + Send,
     {
-        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity())?;
-        let request_id = registration.request_id();
-        let started = tokio::time::Instant::now();
-        let deadline = registration.deadline();
-        let recv_timeout = deadline.saturating_duration_since(started);
-        let target = self.inner.target;
-        let mut stream = lock_stream_before(&self.inner, deadline, recv_timeout).await?;
-
-        if self.inner.is_poisoned() {
-            return Err(Error::Closed { target }.boxed());
-        }
-        let mut transaction = TcpTransactionGuard::unarmed(&self.inner);
-
-        tokio::time::timeout_at(
-            deadline,
-            arm_when_polled(&mut transaction, write_message(&mut *stream, target, data)),
-        )
-        .await
-        .map_err(|_| timeout_error(target, recv_timeout))??;
-
-        let result = tokio::time::timeout_at(
-            deadline,
-            arm_when_polled(
-                &mut transaction,
-                read_validated_message(
-                    &mut stream,
-                    target,
-                    self.inner.receive_limits.accepted(),
-                    &registration,
-                    validate,
-                ),
-            ),
-        )
-        .await
-        .map_err(|_| CorrelatedReadError::Timeout)
-        .and_then(std::convert::identity);
-        let value = finish_correlated_read(result, target, request_id, recv_timeout)?;
-        transaction.disarm();
-        Ok(value)
+        self.inner.request_with(data, registration, validate).await
     }
 
     fn peer_addr(&self) -> SocketAddr {
-        self.inner.target
+        self.inner.target()
     }
 
     fn local_addr(&self) -> SocketAddr {
-        self.inner.local_addr
+        self.inner.local_addr()
     }
 
     fn is_reliable(&self) -> bool {
@@ -573,11 +622,11 @@ impl Transport for TcpTransport {
     }
 
     fn receive_limits(&self) -> ReceiveLimits {
-        self.inner.receive_limits
+        self.inner.receive_limits()
     }
 
     fn send_capacity(&self) -> usize {
-        self.inner.send_capacity
+        self.inner.send_capacity()
     }
 }
 
@@ -595,12 +644,12 @@ impl TcpTransport {
             return Err(Error::Closed { target }.boxed());
         }
         let mut transaction = TcpTransactionGuard::unarmed(&self.inner);
-        let result = tokio::time::timeout_at(
+        let result = crate::runtime::timeout_at(
             deadline,
             arm_when_polled(
                 &mut transaction,
                 read_validated_message(
-                    &mut stream,
+                    &mut *stream,
                     target,
                     self.inner.receive_limits.accepted(),
                     &registration,
@@ -634,7 +683,7 @@ enum CorrelatedReadError {
     Timeout,
 }
 
-fn tcp_deadline(timeout: Duration, description: &str) -> Result<tokio::time::Instant> {
+pub(super) fn tcp_deadline(timeout: Duration, description: &str) -> Result<tokio::time::Instant> {
     tokio::time::Instant::now()
         .checked_add(timeout)
         .ok_or_else(|| {
@@ -643,21 +692,50 @@ fn tcp_deadline(timeout: Duration, description: &str) -> Result<tokio::time::Ins
         })
 }
 
+/// Connect to `target`, bounded by an optional absolute construction deadline.
+pub(super) async fn connect_stream(
+    target: SocketAddr,
+    socket_options: &SocketOptions,
+    started: tokio::time::Instant,
+    deadline: Option<tokio::time::Instant>,
+) -> Result<TcpStream> {
+    let construction_timeout = || {
+        Error::ConstructionTimeout {
+            target: target.into(),
+            stage: ConstructionStage::Connect,
+            elapsed: started.elapsed(),
+        }
+        .boxed()
+    };
+    let connect =
+        async { crate::runtime::connect_tcp(tcp_socket(target, socket_options)?, target).await };
+    match deadline {
+        Some(deadline) if tokio::time::Instant::now() >= deadline => Err(construction_timeout()),
+        Some(deadline) => crate::runtime::timeout_at(deadline, connect)
+            .await
+            .map_err(|_| construction_timeout())?
+            .map_err(|e| Error::Network { target, source: e }.boxed()),
+        None => connect
+            .await
+            .map_err(|e| Error::Network { target, source: e }.boxed()),
+    }
+}
+
 fn transaction_deadline(timeout: Duration) -> Result<tokio::time::Instant> {
     tcp_deadline(timeout, "TCP timeout")
 }
 
-async fn lock_stream_before(
-    inner: &TcpTransportInner,
+async fn lock_stream_before<S>(
+    inner: &TcpTransportInner<S>,
     deadline: tokio::time::Instant,
     timeout: Duration,
-) -> Result<OwnedMutexGuard<TcpStream>> {
+) -> Result<OwnedMutexGuard<S>> {
     let target = inner.target;
     if tokio::time::Instant::now() >= deadline {
         return Err(timeout_error(target, timeout));
     }
 
-    let stream = tokio::time::timeout_at(deadline, inner.stream.clone().lock_owned())
+    let stream = crate::runtime::timeout_at(deadline, inner.stream.clone().lock_owned())
         .await
         .map_err(|_| timeout_error(target, timeout))?;
 
@@ -704,14 +782,15 @@ where
         .map_err(|source| Error::Network { target, source }.boxed())
 }
 
-async fn read_validated_message<T, F>(
-    stream: &mut TcpStream,
+async fn read_validated_message<S, T, F>(
+    stream: &mut S,
     target: SocketAddr,
     max_message_size: usize,
     registration: &RequestRegistration,
     mut validate: F,
 ) -> std::result::Result<T, CorrelatedReadError>
 where
+    S: AsyncRead + Unpin,
     F: FnMut(Bytes, SocketAddr) -> Result<Candidate<T>>,
 {
     let request_id = registration.request_id();
@@ -766,17 +845,20 @@ fn finish_correlated_read<T>(
     }
 }
 
-/// Read a complete BER-encoded SNMP message from a TCP stream.
+/// Read a complete BER-encoded SNMP message from a byte stream.
 ///
 /// SNMP messages are SEQUENCE types (tag 0x30). We read:
 /// 1. Tag byte (must be 0x30)
 /// 2. Length field (definite form only)
 /// 3. Content bytes
-async fn read_ber_message(
-    stream: &mut TcpStream,
+pub(super) async fn read_ber_message<S>(
+    stream: &mut S,
     target: SocketAddr,
     max_message_size: usize,
-) -> Result<Bytes> {
+) -> Result<Bytes>
+where
+    S: AsyncRead + Unpin,
+{
     // Read tag byte
     let mut tag_buf = [0u8; 1];
     stream
@@ -918,7 +1000,7 @@ mod tests {
     use std::pin::Pin;
     use std::task::{Context, Poll};
     use tokio::io::{AsyncWrite, AsyncWriteExt};
-    use tokio::net::TcpListener;
+    use tokio::net::{TcpListener, TcpStream};
 
     fn deadline_after(timeout: Duration) -> tokio::time::Instant {
         tokio::time::Instant::now() + timeout
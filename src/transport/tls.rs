@@ -0,0 +1,835 @@
+//! TLS transport (TLSTM, RFC 6353) for SNMP clients.
+//!
+//! [`TlsTransport`] carries BER-encoded SNMP messages over a TLS session on a
+//! TCP connection. Framing, request serialization, and connection poisoning
+//! are shared with [`TcpTransport`](super::TcpTransport): TLS only changes the
+//! byte stream underneath.
+//!
+//! # Server authentication
+//!
+//! RFC 6353 Section 5.3.1 identifies the server in one of two ways, and the
+//! builder supports both:
+//!
+//! - **Fingerprint pinning** ([`pin_server_fingerprint`](TlsTransportBuilder::pin_server_fingerprint)):
+//!   the session is accepted when a pin matches the server's certificate.
+//!   A pin may instead name a CA certificate, presented in the chain or
+//!   configured as a trust anchor; the chain and the server name are then
+//!   validated against that CA.
+//! - **Certificate path validation**: with no pins, the server's chain must
+//!   validate against the configured trust anchors and name the
+//!   [`server_name`](TlsTransportBuilder::server_name).
+//!
+//! The server name is also sent as SNI when it is a DNS name. Without one,
+//! the target's IP address is the expected identity.
+//!
+//! Fingerprints are computed with the crate's crypto backend. Without
+//! `crypto-rustcrypto` or `crypto-fips`, no pin matches and connections that
+//! rely on pinning or certToTSN fail closed.
+//!
+//! # Security names
+//!
+//! A [`CertToTsn`] table maps the server's certificate chain to a
+//! `tmSecurityName`, reported through
+//! [`Transport::transport_security`]. When a table is configured and no entry
+//! maps the chain, the session is closed and construction fails with
+//! [`Error::Auth`], as RFC 6353 requires.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use async_snmp::transport::TlsTransport;
+//! use async_snmp::{ClientBuilder, TsmConfig};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let transport = TlsTransport::builder()
+//!     .server_name("agent.example.com")
+//!     .trust_anchors_pem(std::fs::read("ca.pem")?)
+//!     .client_identity_pem(std::fs::read("manager.pem")?, std::fs::read("manager.key")?)
+//!     .connect("192.0.2.1:10161".parse()?)
+//!     .await?;
+//!
+//! let client = ClientBuilder::new(TsmConfig::new("manager")).build_with_transport(transport)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::tcp::{TcpOptions, TcpTransportInner, connect_stream, tcp_deadline};
+use super::{Candidate, RequestRegistration, SocketOptions, Transport};
+use crate::error::{ConstructionStage, Error, Result};
+use crate::message::SecurityLevel;
+use crate::message_size::ReceiveLimits;
+use crate::runtime::TcpStream;
+use crate::v3::{CertToTsn, CertificateFingerprint, TransportSecurity};
+use bytes::Bytes;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::{ClientConfig, WebPkiServerVerifier};
+use rustls::crypto::{CryptoProvider, WebPkiSupportedAlgorithms};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{CertificateError, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+
+/// `tmSecurityName` prefix for TLS sessions (RFC 6353 Section 5.1).
+const TLS_TRANSPORT_PREFIX: &str = "tls";
+
+/// Certificate or key material as supplied to the builder.
+#[derive(Clone)]
+enum Material {
+    Der(Vec<u8>),
+    Pem(Vec<u8>),
+}
+
+/// Builder for [`TlsTransport`].
+///
+/// At least one trust anchor or server fingerprint pin is required.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::transport::TlsTransport;
+/// use async_snmp::v3::{CertToTsn, CertToTsnMapping, CertificateFingerprint};
+/// use std::time::Duration;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let pin: CertificateFingerprint = "sha256:4F:1A:...".parse()?;
+/// let transport = TlsTransport::builder()
+///     .connect_timeout(Duration::from_secs(5))
+///     .pin_server_fingerprint(pin.clone())
+///     .cert_to_tsn(CertToTsn::new().entry(pin, CertToTsnMapping::SanDnsName))
+///     .connect("192.0.2.1:10161".parse()?)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TlsTransportBuilder {
+    connect_timeout: Option<Duration>,
+    options: TcpOptions,
+    socket_options: SocketOptions,
+    server_name: Option<String>,
+    trust_anchors: Vec<Material>,
+    pins: Vec<CertificateFingerprint>,
+    client_identity: Option<(Vec<Material>, Material)>,
+    cert_to_tsn: Option<CertToTsn>,
+}
+
+impl TlsTransportBuilder {
+    /// Create a builder with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            connect_timeout: None,
+            options: TcpOptions::default(),
+            socket_options: SocketOptions::new(),
+            server_name: None,
+            trust_anchors: Vec::new(),
+            pins: Vec::new(),
+            client_identity: None,
+            cert_to_tsn: None,
+        }
+    }
+
+    /// Bound TCP connection and TLS handshake by one total timeout.
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum total encoded size for incoming messages.
+    ///
+    /// Default: 10MB.
+    #[must_use]
+    pub fn max_message_size(mut self, size: usize) -> Self {
+        self.options.max_message_size = size;
+        self
+    }
+
+    /// Set the maximum exact encoded size for outgoing messages.
+    #[must_use]
+    pub fn send_capacity(mut self, size: usize) -> Self {
+        self.options.send_capacity = size;
+        self
+    }
+
+    /// Set interface, source address, and IP header options for the socket.
+    #[must_use]
+    pub fn socket_options(mut self, options: SocketOptions) -> Self {
+        self.socket_options = options;
+        self
+    }
+
+    /// Set the expected server identity, sent as SNI when it is a DNS name.
+    ///
+    /// Default: the target's IP address.
+    #[must_use]
+    pub fn server_name(mut self, name: impl Into<String>) -> Self {
+        self.server_name = Some(name.into());
+        self
+    }
+
+    /// Trust a DER-encoded CA certificate for certificate path validation.
+    #[must_use]
+    pub fn trust_anchor_der(mut self, certificate: impl Into<Vec<u8>>) -> Self {
+        self.trust_anchors.push(Material::Der(certificate.into()));
+        self
+    }
+
+    /// Trust every certificate in a PEM bundle for certificate path validation.
+    ///
+    /// The bundle is parsed by [`connect`](Self::connect).
+    #[must_use]
+    pub fn trust_anchors_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.trust_anchors.push(Material::Pem(pem.into()));
+        self
+    }
+
+    /// Accept a server whose certificate, or one of its CAs, has this
+    /// fingerprint (`snmpTlstmAddrServerFingerprint`).
+    #[must_use]
+    pub fn pin_server_fingerprint(mut self, fingerprint: CertificateFingerprint) -> Self {
+        self.pins.push(fingerprint);
+        self
+    }
+
+    /// Present a client certificate chain and its DER-encoded private key.
+    ///
+    /// The chain starts with the client's own certificate.
+    #[must_use]
+    pub fn client_identity_der(mut self, chain: Vec<Vec<u8>>, private_key: Vec<u8>) -> Self {
+        self.client_identity = Some((
+            chain.into_iter().map(Material::Der).collect(),
+            Material::Der(private_key),
+        ));
+        self
+    }
+
+    /// Present a PEM client certificate chain and PEM private key.
+    ///
+    /// Both are parsed by [`connect`](Self::connect).
+    #[must_use]
+    pub fn client_identity_pem(
+        mut self,
+        chain: impl Into<Vec<u8>>,
+        private_key: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.client_identity = Some((
+            vec![Material::Pem(chain.into())],
+            Material::Pem(private_key.into()),
+        ));
+        self
+    }
+
+    /// Map the server's certificate chain to a `tmSecurityName`.
+    ///
+    /// Sessions whose chain matches no entry are rejected.
+    #[must_use]
+    pub fn cert_to_tsn(mut self, table: CertToTsn) -> Self {
+        self.cert_to_tsn = Some(table);
+        self
+    }
+
+    /// Connect to the target address and complete the TLS handshake.
+    pub async fn connect(self, target: SocketAddr) -> Result<TlsTransport> {
+        let started = tokio::time::Instant::now();
+        let deadline = self
+            .connect_timeout
+            .map(|timeout| tcp_deadline(timeout, "TLS connect timeout"))
+            .transpose()?;
+        let receive_limits = ReceiveLimits::tcp(self.options.max_message_size)
+            .map_err(|error| Error::Config(error.to_string().into()).boxed())?;
+        let server_name = match &self.server_name {
+            Some(name) => ServerName::try_from(name.clone()).map_err(|_| {
+                Error::Config(format!("invalid TLS server name {name:?}").into()).boxed()
+            })?,
+            None => ServerName::IpAddress(target.ip().into()),
+        };
+        let connector = TlsConnector::from(Arc::new(self.client_config()?));
+
+        let stream = connect_stream(target, &self.socket_options, started, deadline).await?;
+        let local_addr = stream
+            .local_addr()
+            .map_err(|e| Error::Network { target, source: e }.boxed())?;
+        let handshake = connector.connect(server_name, stream);
+        let stream = match deadline {
+            Some(deadline) => crate::runtime::timeout_at(deadline, handshake)
+                .await
+                .map_err(|_| {
+                    Error::ConstructionTimeout {
+                        target: target.into(),
+                        stage: ConstructionStage::Handshake,
+                        elapsed: started.elapsed(),
+                    }
+                    .boxed()
+                })?,
+            None => handshake.await,
+        }
+        .map_err(|e| Error::Network { target, source: e }.boxed())?;
+
+        let peer_certificates: Arc<[Bytes]> = stream
+            .get_ref()
+            .1
+            .peer_certificates()
+            .unwrap_or_default()
+            .iter()
+            .map(|certificate| Bytes::copy_from_slice(certificate))
+            .collect();
+        let mut security = TransportSecurity::new(TLS_TRANSPORT_PREFIX, SecurityLevel::AuthPriv);
+        if let Some(table) = &self.cert_to_tsn {
+            let Some(name) = table.security_name(&peer_certificates) else {
+                tracing::warn!(target: "async_snmp::transport::tls", { %target }, "server certificate maps to no tmSecurityName");
+                return Err(Error::Auth { target }.boxed());
+            };
+            security = security.with_peer_security_name(name);
+        }
+
+        Ok(TlsTransport {
+            inner: Arc::new(TcpTransportInner::new(
+                stream,
+                target,
+                local_addr,
+                receive_limits,
+                self.options.send_capacity,
+            )),
+            peer_certificates,
+            security,
+        })
+    }
+
+    fn client_config(&self) -> Result<ClientConfig> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut trust_anchors = Vec::new();
+        for material in &self.trust_anchors {
+            trust_anchors.extend(parse_certificates(material)?);
+        }
+        if trust_anchors.is_empty() && self.pins.is_empty() {
+            return Err(Error::Config(
+                "TLS transport requires a trust anchor or a server fingerprint".into(),
+            )
+            .boxed());
+        }
+        let verifier = SnmpServerVerifier {
+            webpki: if trust_anchors.is_empty() {
+                None
+            } else {
+                Some(webpki_verifier(&trust_anchors, &provider)?)
+            },
+            trust_anchors,
+            pins: self.pins.clone(),
+            provider: provider.clone(),
+        };
+
+        let builder = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(tls_config_error)?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier));
+        match &self.client_identity {
+            Some((chain, key)) => {
+                let mut certificates = Vec::new();
+                for material in chain {
+                    certificates.extend(parse_certificates(material)?);
+                }
+                if certificates.is_empty() {
+                    return Err(
+                        Error::Config("TLS client certificate chain is empty".into()).boxed(),
+                    );
+                }
+                let key = match key {
+                    Material::Der(der) => PrivateKeyDer::try_from(der.clone())
+                        .map_err(|error| Error::Config(error.into()).boxed())?,
+                    Material::Pem(pem) => {
+                        PrivateKeyDer::from_pem_slice(pem).map_err(tls_config_error)?
+                    }
+                };
+                builder
+                    .with_client_auth_cert(certificates, key)
+                    .map_err(tls_config_error)
+            }
+            None => Ok(builder.with_no_client_auth()),
+        }
+    }
+}
+
+impl Default for TlsTransportBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for TlsTransportBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsTransportBuilder")
+            .field("connect_timeout", &self.connect_timeout)
+            .field("options", &self.options)
+            .field("server_name", &self.server_name)
+            .field("trust_anchors", &self.trust_anchors.len())
+            .field("pins", &self.pins)
+            .field("client_identity", &self.client_identity.is_some())
+            .field("cert_to_tsn", &self.cert_to_tsn)
+            .finish()
+    }
+}
+
+fn tls_config_error(error: impl std::fmt::Display) -> Box<Error> {
+    Error::Config(format!("invalid TLS configuration: {error}").into()).boxed()
+}
+
+fn parse_certificates(material: &Material) -> Result<Vec<CertificateDer<'static>>> {
+    match material {
+        Material::Der(der) => Ok(vec![CertificateDer::from(der.clone())]),
+        Material::Pem(pem) => CertificateDer::pem_slice_iter(pem)
+            .collect::<std::result::Result<_, _>>()
+            .map_err(tls_config_error),
+    }
+}
+
+fn webpki_verifier(
+    anchors: &[CertificateDer<'static>],
+    provider: &Arc<CryptoProvider>,
+) -> Result<Arc<WebPkiServerVerifier>> {
+    let mut roots = RootCertStore::empty();
+    for anchor in anchors {
+        roots.add(anchor.clone()).map_err(tls_config_error)?;
+    }
+    WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+        .build()
+        .map_err(tls_config_error)
+}
+
+/// Server certificate policy of RFC 6353 Section 5.3.1.
+struct SnmpServerVerifier {
+    /// Path validation against the configured trust anchors.
+    webpki: Option<Arc<WebPkiServerVerifier>>,
+    trust_anchors: Vec<CertificateDer<'static>>,
+    pins: Vec<CertificateFingerprint>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl std::fmt::Debug for SnmpServerVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnmpServerVerifier")
+            .field("trust_anchors", &self.trust_anchors.len())
+            .field("pins", &self.pins)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SnmpServerVerifier {
+    fn algorithms(&self) -> &WebPkiSupportedAlgorithms {
+        &self.provider.signature_verification_algorithms
+    }
+
+    fn pinned(&self, certificate: &[u8]) -> bool {
+        self.pins.iter().any(|pin| pin.matches(certificate))
+    }
+}
+
+impl ServerCertVerifier for SnmpServerVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        if self.pins.is_empty() {
+            let webpki = self
+                .webpki
+                .as_ref()
+                .ok_or(rustls::Error::InvalidCertificate(
+                    CertificateError::UnknownIssuer,
+                ))?;
+            return webpki.verify_server_cert(
+                end_entity,
+                intermediates,
+                server_name,
+                ocsp_response,
+                now,
+            );
+        }
+        // A pinned server certificate stands on its own, self-signed or not.
+        if self.pinned(end_entity) {
+            return Ok(ServerCertVerified::assertion());
+        }
+        // A pinned CA becomes the sole trust anchor for path validation.
+        let anchors: Vec<CertificateDer<'static>> = intermediates
+            .iter()
+            .chain(&self.trust_anchors)
+            .filter(|certificate| self.pinned(certificate))
+            .map(|certificate| certificate.clone().into_owned())
+            .collect();
+        if anchors.is_empty() {
+            return Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            ));
+        }
+        webpki_verifier(&anchors, &self.provider)
+            .map_err(|error| rustls::Error::General(error.to_string()))?
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, self.algorithms())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, self.algorithms())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms().supported_schemes()
+    }
+}
+
+/// TLS transport for a single target (RFC 6353 TLSTM).
+///
+/// Behaves like [`TcpTransport`](super::TcpTransport) on an authenticated,
+/// encrypted session: requests are serialized, the client does not retry,
+/// and framing loss poisons the connection. The session always provides
+/// `authPriv`, so it can carry [`TsmConfig`](crate::TsmConfig) clients at any
+/// security level.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::transport::TlsTransport;
+/// use async_snmp::{Auth, ClientBuilder, TsmConfig};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let transport = TlsTransport::builder()
+///     .trust_anchors_pem(std::fs::read("ca.pem")?)
+///     .connect("192.0.2.1:10161".parse()?)
+///     .await?;
+///
+/// let client = ClientBuilder::new(Auth::Tsm(TsmConfig::new("monitor")))
+///     .build_with_transport(transport)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TlsTransport {
+    inner: Arc<TcpTransportInner<TlsStream<TcpStream>>>,
+    peer_certificates: Arc<[Bytes]>,
+    security: TransportSecurity,
+}
+
+impl TlsTransport {
+    /// Create a builder for custom configuration.
+    #[must_use]
+    pub fn builder() -> TlsTransportBuilder {
+        TlsTransportBuilder::new()
+    }
+
+    /// DER-encoded certificate chain presented by the server, leaf first.
+    pub fn peer_certificates(&self) -> &[Bytes] {
+        &self.peer_certificates
+    }
+}
+
+impl std::fmt::Debug for TlsTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsTransport")
+            .field("target", &self.inner.target())
+            .field("local_addr", &self.inner.local_addr())
+            .field("security", &self.security)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Transport for TlsTransport {
+    async fn send(&self, data: &[u8]) -> Result<()> {
+        self.inner.send(data).await
+    }
+
+    async fn send_with_timeout(&self, data: &[u8], timeout: Duration) -> Result<()> {
+        self.inner.send_with_timeout(data, timeout).await
+    }
+
+    async fn request_with<T, F>(
+        &self,
+        data: &[u8],
+        registration: RequestRegistration,
+        validate: F,
+    ) -> Result<T>
+    where
+        T: Send,
+        F: FnMut(Bytes, SocketAddr) -> Result<Candidate<T>> + Send,
+    {
+        self.inner.request_with(data, registration, validate).await
+    }
+
+    fn peer_addr(&self) -> SocketAddr {
+        self.inner.target()
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        self.inner.local_addr()
+    }
+
+    fn is_reliable(&self) -> bool {
+        true
+    }
+
+    fn receive_limits(&self) -> ReceiveLimits {
+        self.inner.receive_limits()
+    }
+
+    fn send_capacity(&self) -> usize {
+        self.inner.send_capacity()
+    }
+
+    fn transport_security(&self) -> Option<TransportSecurity> {
+        Some(self.security.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{MsgFlags, MsgGlobalData, ScopedPdu, V3Message};
+    use crate::pdu::Pdu;
+    use crate::v3::{CertToTsnMapping, FingerprintAlgorithm};
+    use crate::{ClientBuilder, TsmConfig, Value, VarBind, oid};
+    use rustls::server::{ServerConfig, WebPkiClientVerifier};
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+
+    const CA: &[u8] = include_bytes!("testdata/tls/ca.pem");
+    const AGENT_CERT: &[u8] = include_bytes!("testdata/tls/agent.pem");
+    const AGENT_KEY: &[u8] = include_bytes!("testdata/tls/agent.key");
+    const MANAGER_CERT: &[u8] = include_bytes!("testdata/tls/manager.pem");
+    const MANAGER_KEY: &[u8] = include_bytes!("testdata/tls/manager.key");
+
+    fn der(pem: &[u8]) -> CertificateDer<'static> {
+        CertificateDer::from_pem_slice(pem).unwrap()
+    }
+
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    fn fingerprint(pem: &[u8]) -> CertificateFingerprint {
+        CertificateFingerprint::compute(FingerprintAlgorithm::Sha256, &der(pem)).unwrap()
+    }
+
+    /// TLSTM agent answering each request with the client's mapped
+    /// `tmSecurityName` (or "anonymous") as sysDescr.
+    async fn spawn_agent() -> SocketAddr {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut roots = RootCertStore::empty();
+        roots.add(der(CA)).unwrap();
+        let client_verifier =
+            WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                .allow_unauthenticated()
+                .build()
+                .unwrap();
+        let config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_client_cert_verifier(client_verifier)
+            .with_single_cert(
+                vec![der(AGENT_CERT), der(CA)],
+                PrivateKeyDer::from_pem_slice(AGENT_KEY).unwrap(),
+            )
+            .unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+        // Without a crypto backend no fingerprint matches; every client is
+        // then anonymous.
+        let ca_pin = CertificateFingerprint::compute(FingerprintAlgorithm::Sha256, &der(CA))
+            .unwrap_or_else(|_| {
+                CertificateFingerprint::new(FingerprintAlgorithm::Sha256, vec![0; 32]).unwrap()
+            });
+        let client_map = CertToTsn::new().entry(ca_pin, CertToTsnMapping::SanRfc822Name);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let acceptor = acceptor.clone();
+                let client_map = client_map.clone();
+                tokio::spawn(async move {
+                    let Ok(mut stream) = acceptor.accept(stream).await else {
+                        return;
+                    };
+                    let chain: Vec<Bytes> = stream
+                        .get_ref()
+                        .1
+                        .peer_certificates()
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|certificate| Bytes::copy_from_slice(certificate))
+                        .collect();
+                    let name = client_map
+                        .security_name(&chain)
+                        .unwrap_or_else(|| Bytes::from_static(b"anonymous"));
+                    while let Ok(frame) =
+                        super::super::tcp::read_ber_message(&mut stream, addr, 65535).await
+                    {
+                        let request = V3Message::decode(frame, Default::default()).unwrap().value;
+                        let reply = reply(&request, name.clone());
+                        tokio::io::AsyncWriteExt::write_all(&mut stream, &reply)
+                            .await
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    fn reply(request: &V3Message, sys_descr: Bytes) -> Bytes {
+        let global = MsgGlobalData::new(
+            request.msg_id(),
+            request.global_data().msg_max_size(),
+            MsgFlags::new(request.security_level(), false),
+        )
+        .unwrap();
+        let scoped = request.scoped_pdu().unwrap();
+        let pdu = Pdu::response(
+            request.pdu().unwrap().request_id,
+            0,
+            0,
+            vec![VarBind::new(
+                oid!(1, 3, 6, 1, 2, 1, 1, 1, 0),
+                Value::OctetString(sys_descr),
+            )],
+        );
+        let scoped = ScopedPdu::new(
+            Bytes::from_static(b"\x80\x00\x1f\x88\x04agent"),
+            scoped.context_name.clone(),
+            pdu,
+        );
+        V3Message::tsm(global, scoped).unwrap().encode().unwrap()
+    }
+
+    async fn sys_descr(transport: TlsTransport) -> Value {
+        let client = ClientBuilder::new(TsmConfig::new("ops"))
+            .build_with_transport(transport)
+            .unwrap();
+        let response = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).await.unwrap();
+        response.single().unwrap().value.clone()
+    }
+
+    #[tokio::test]
+    async fn tsm_get_over_validated_session_with_client_certificate() {
+        let agent = spawn_agent().await;
+        let transport = TlsTransport::builder()
+            .server_name("agent.example.com")
+            .trust_anchors_pem(CA)
+            .client_identity_pem([MANAGER_CERT, CA].concat(), MANAGER_KEY)
+            .connect(agent)
+            .await
+            .unwrap();
+        let security = transport.transport_security().unwrap();
+        assert_eq!(security.prefix, "tls");
+        assert_eq!(security.security_level, SecurityLevel::AuthPriv);
+        assert_eq!(security.peer_security_name, None);
+        assert_eq!(transport.peer_certificates().len(), 2);
+
+        let expected: &[u8] = if cfg!(any(feature = "crypto-rustcrypto", feature = "crypto-fips")) {
+            b"ops@example.com"
+        } else {
+            b"anonymous"
+        };
+        assert_eq!(sys_descr(transport).await, Value::from(expected));
+    }
+
+    #[tokio::test]
+    async fn path_validation_checks_anchor_and_server_name() {
+        let agent = spawn_agent().await;
+        // The agent certificate carries an IP address SAN for 127.0.0.1.
+        let transport = TlsTransport::builder()
+            .trust_anchor_der(der(CA).to_vec())
+            .connect(agent)
+            .await
+            .unwrap();
+        assert_eq!(sys_descr(transport).await, Value::from("anonymous"));
+
+        let error = TlsTransport::builder()
+            .server_name("other.example.com")
+            .trust_anchors_pem(CA)
+            .connect(agent)
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Network { .. }));
+
+        let error = TlsTransport::builder().connect(agent).await.unwrap_err();
+        assert!(matches!(*error, Error::Config(_)));
+    }
+
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    #[tokio::test]
+    async fn fingerprint_pins_accept_leaf_or_validated_ca() {
+        let agent = spawn_agent().await;
+        let transport = TlsTransport::builder()
+            .server_name("ignored.example.com")
+            .pin_server_fingerprint(fingerprint(AGENT_CERT))
+            .connect(agent)
+            .await
+            .unwrap();
+        assert_eq!(sys_descr(transport).await, Value::from("anonymous"));
+
+        TlsTransport::builder()
+            .server_name("agent.example.com")
+            .pin_server_fingerprint(fingerprint(CA))
+            .connect(agent)
+            .await
+            .unwrap();
+        // A CA pin still requires the server name to match.
+        let error = TlsTransport::builder()
+            .server_name("other.example.com")
+            .pin_server_fingerprint(fingerprint(CA))
+            .connect(agent)
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Network { .. }));
+
+        let error = TlsTransport::builder()
+            .pin_server_fingerprint(fingerprint(MANAGER_CERT))
+            .trust_anchors_pem(CA)
+            .connect(agent)
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Network { .. }));
+    }
+
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    #[tokio::test]
+    async fn cert_to_tsn_names_the_server_or_rejects_it() {
+        let agent = spawn_agent().await;
+        let transport = TlsTransport::builder()
+            .trust_anchors_pem(CA)
+            .cert_to_tsn(CertToTsn::new().entry(fingerprint(CA), CertToTsnMapping::SanDnsName))
+            .connect(agent)
+            .await
+            .unwrap();
+        assert_eq!(
+            transport.transport_security().unwrap().peer_security_name,
+            Some(Bytes::from_static(b"agent.example.com"))
+        );
+
+        let error = TlsTransport::builder()
+            .trust_anchors_pem(CA)
+            .cert_to_tsn(
+                CertToTsn::new().entry(fingerprint(MANAGER_CERT), CertToTsnMapping::CommonName),
+            )
+            .connect(agent)
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Auth { target } if target == agent));
+    }
+}
@@ -79,16 +79,20 @@
 use super::udp_core::UdpCore;
 pub use super::udp_core::UdpStats;
 use super::udp_error::{UdpRecvErrorBackoff, UdpRecvErrorClass, classify_udp_recv_error};
-use super::{Candidate, RequestRegistration, Transport, extract_request_id, normalize_udp_target};
+use super::udp_port_pool::{MAX_SOURCE_PORT_POOL, PortLease, SourcePortPool};
+use super::udp_sharded::ShardRouting;
+use super::{
+    Candidate, RequestRegistration, SocketOptions, Transport, extract_request_id,
+    normalize_udp_target,
+};
 use crate::error::{Error, Result};
 use crate::message_size::{ReceiveLimits, UDP_RECEIVE_BUFFER_SIZE};
-use crate::util::bind_udp_socket;
+use crate::runtime::{JoinHandle, UdpSocket};
+use crate::util::{UdpBindOptions, bind_udp_socket_with};
 use bytes::Bytes;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::net::UdpSocket;
-use tokio::task::JoinHandle;
 use tokio_util::sync::{CancellationToken, DropGuard};
 
 /// Configuration for UDP transport.
@@ -135,6 +139,11 @@ struct UdpTransportInner {
     // guard would never fire.
     _shutdown_guard: DropGuard,
     recv_task: tokio::sync::Mutex<Option<JoinHandle<()>>>,
+    /// Per-request source sockets, present when a source port pool is configured.
+    port_pool: Option<Arc<SourcePortPool>>,
+    /// `sendmmsg` queue, present when batched I/O is enabled.
+    #[cfg(target_os = "linux")]
+    send_queue: Option<super::udp_batch::SendQueue>,
     #[cfg(test)]
     receive_errors: Arc<std::sync::Mutex<std::collections::VecDeque<std::io::Error>>>,
     #[cfg(test)]
@@ -164,6 +173,9 @@ async fn recv_datagram(
     }
 }
 
+/// How often the recv task sweeps expired requests.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(1);
+
 struct UdpRecvTaskCleanup {
     core: Arc<UdpCore>,
     shutdown_complete: CancellationToken,
@@ -176,6 +188,81 @@ impl Drop for UdpRecvTaskCleanup {
     }
 }
 
+/// Receive buffers owned by the recv task.
+enum RecvBuffers {
+    /// One `recv_from` per wakeup.
+    Single {
+        buf: Vec<u8>,
+        len: usize,
+        source: SocketAddr,
+    },
+    /// One `recvmmsg` per wakeup.
+    #[cfg(target_os = "linux")]
+    Batched(super::udp_batch::RecvBatch),
+}
+
+impl RecvBuffers {
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    fn new(batching: Option<UdpBatching>) -> Self {
+        #[cfg(target_os = "linux")]
+        if let Some(batching) = batching {
+            return Self::Batched(super::udp_batch::RecvBatch::new(
+                batching.recv_batch,
+                UDP_RECEIVE_BUFFER_SIZE,
+            ));
+        }
+        Self::Single {
+            buf: vec![0u8; UDP_RECEIVE_BUFFER_SIZE],
+            len: 0,
+            source: SocketAddr::from(([0, 0, 0, 0], 0)),
+        }
+    }
+
+    /// Receive at least one datagram, returning how many are available.
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    async fn recv(
+        &mut self,
+        socket: &UdpSocket,
+        core: &UdpCore,
+        #[cfg(test)] receive_errors: &std::sync::Mutex<std::collections::VecDeque<std::io::Error>>,
+        #[cfg(test)] receive_error_ready: &tokio::sync::Notify,
+    ) -> std::io::Result<usize> {
+        match self {
+            Self::Single { buf, len, source } => {
+                (*len, *source) = recv_datagram(
+                    socket,
+                    #[cfg(test)]
+                    receive_errors,
+                    #[cfg(test)]
+                    receive_error_ready,
+                    buf,
+                )
+                .await?;
+                Ok(1)
+            }
+            #[cfg(target_os = "linux")]
+            Self::Batched(batch) => {
+                let count = batch.recv(socket).await?;
+                if count > 0 {
+                    core.note_recv_batch(count);
+                }
+                Ok(count)
+            }
+        }
+    }
+
+    fn datagram(&self, index: usize) -> (&[u8], SocketAddr) {
+        match self {
+            Self::Single { buf, len, source } => {
+                debug_assert_eq!(index, 0);
+                (&buf[..*len], *source)
+            }
+            #[cfg(target_os = "linux")]
+            Self::Batched(batch) => batch.datagram(index),
+        }
+    }
+}
+
 impl UdpTransport {
     /// Bind to the given address with default configuration.
     ///
@@ -207,7 +294,7 @@ impl UdpTransport {
         let target = normalize_udp_target(self.inner.local_addr, target)?;
         Ok(UdpHandle {
             inner: self.inner.clone(),
-            target,
+            target: Arc::new(std::sync::RwLock::new(target)),
             strict_source: false,
             #[cfg(test)]
             send_gate: None,
@@ -247,7 +334,11 @@ impl UdpTransport {
         self.inner.receive_error_ready.notify_one();
     }
 
-    fn start_recv_loop(inner: &Arc<UdpTransportInner>) {
+    fn start_recv_loop(
+        inner: &Arc<UdpTransportInner>,
+        batching: Option<UdpBatching>,
+        routing: Option<Arc<ShardRouting>>,
+    ) {
         // The task captures only the pieces it needs, never the inner Arc:
         // Drop-based cancellation relies on the DropGuard firing when the
         // last transport/handle reference drops, which can only happen if
@@ -265,12 +356,13 @@ impl UdpTransport {
         let receive_errors = Arc::clone(&inner.receive_errors);
         #[cfg(test)]
         let receive_error_ready = Arc::clone(&inner.receive_error_ready);
-        let handle = tokio::spawn(async move {
+        let mut buffers = RecvBuffers::new(batching);
+        let handle = crate::runtime::spawn(async move {
             // Constructed before spawning so dropping an unpolled task still
             // closes the core and signals completion synchronously.
             let _cleanup = cleanup;
-            let mut buf = vec![0u8; UDP_RECEIVE_BUFFER_SIZE];
-            let mut cleanup_interval = tokio::time::interval(Duration::from_secs(1));
+            let mut next_cleanup = tokio::time::Instant::now();
+            let mut cleanup_timer = std::pin::pin!(crate::runtime::sleep_until(next_cleanup));
             // Backoff applied after a recv error to avoid a hot spin when the
             // socket is in a persistent error state (e.g. ENOBUFS or a stream
             // of ICMP port-unreachable errors). Reset on any successful recv so
@@ -286,31 +378,41 @@ impl UdpTransport {
                         break;
                     }
 
-                    _ = cleanup_interval.tick() => {
+                    () = &mut cleanup_timer => {
                         core.cleanup_expired();
+                        next_cleanup += CLEANUP_INTERVAL;
+                        cleanup_timer.set(crate::runtime::sleep_until(next_cleanup));
                     }
 
-                    result = recv_datagram(
+                    result = buffers.recv(
                         &socket,
+                        &core,
                         #[cfg(test)] receive_errors.as_ref(),
                         #[cfg(test)] receive_error_ready.as_ref(),
-                        &mut buf,
                     ) => {
                         match result {
-                            Ok((len, source)) => {
+                            Ok(count) => {
                                 recv_error_backoff.reset();
-                                if len > receive_limits.advertised().as_usize() {
-                                    tracing::debug!(target: "async_snmp::transport", { snmp.source = %source, received_size = len, advertised_size = receive_limits.advertised().as_usize() }, "accepted bounded UDP datagram above advertised capacity");
-                                }
-                                let data = Bytes::copy_from_slice(&buf[..len]);
-
-                                if let Some(request_id) = extract_request_id(&data) {
-                                    if !core.deliver(request_id, data, source) {
-                                        tracing::debug!(target: "async_snmp::transport", { snmp.request_id = request_id, snmp.source = %source }, "response for unknown request");
+                                for index in 0..count {
+                                    let (datagram, source) = buffers.datagram(index);
+                                    let len = datagram.len();
+                                    if len > receive_limits.advertised().as_usize() {
+                                        tracing::debug!(target: "async_snmp::transport", { snmp.source = %source, received_size = len, advertised_size = receive_limits.advertised().as_usize() }, "accepted bounded UDP datagram above advertised capacity");
+                                    }
+                                    let data = Bytes::copy_from_slice(datagram);
+
+                                    if let Some(request_id) = extract_request_id(&data) {
+                                        let delivered = match &routing {
+                                            Some(routing) => routing.deliver(request_id, data, source),
+                                            None => core.deliver(request_id, data, source),
+                                        };
+                                        if !delivered {
+                                            tracing::debug!(target: "async_snmp::transport", { snmp.request_id = request_id, snmp.source = %source }, "response for unknown request");
+                                        }
+                                    } else {
+                                        core.note_malformed();
+                                        tracing::debug!(target: "async_snmp::transport", { snmp.source = %source, snmp.bytes = len }, "malformed response (no request_id)");
                                     }
-                                } else {
-                                    core.note_malformed();
-                                    tracing::debug!(target: "async_snmp::transport", { snmp.source = %source, snmp.bytes = len }, "malformed response (no request_id)");
                                 }
                             }
                             Err(_) if shutdown.is_cancelled() => break,
@@ -325,7 +427,7 @@ impl UdpTransport {
                                     tokio::select! {
                                         biased;
                                         () = shutdown.cancelled() => break,
-                                        () = tokio::time::sleep(delay) => {}
+                                        () = crate::runtime::sleep(delay) => {}
                                     }
                                 }
                                 UdpRecvErrorClass::Fatal => {
@@ -346,12 +448,76 @@ impl UdpTransport {
     }
 }
 
+/// Batch sizes for [`UdpTransportBuilder::batched_io`].
+///
+/// Both sizes default to 32 and must be between 1 and 1024 (the kernel's
+/// `UIO_MAXIOV` limit).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UdpBatching {
+    recv_batch: usize,
+    send_batch: usize,
+}
+
+impl UdpBatching {
+    /// Largest batch accepted by `recvmmsg` and `sendmmsg`.
+    const MAX_BATCH: usize = 1024;
+
+    /// Create batch settings with the defaults.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            recv_batch: 32,
+            send_batch: 32,
+        }
+    }
+
+    /// Set the maximum datagrams received per `recvmmsg` call.
+    #[must_use]
+    pub const fn recv_batch(mut self, size: usize) -> Self {
+        self.recv_batch = size;
+        self
+    }
+
+    /// Set the maximum queued datagrams sent per `sendmmsg` call.
+    #[must_use]
+    pub const fn send_batch(mut self, size: usize) -> Self {
+        self.send_batch = size;
+        self
+    }
+
+    fn validate(self) -> Result<()> {
+        for (name, size) in [("receive", self.recv_batch), ("send", self.send_batch)] {
+            if !(1..=Self::MAX_BATCH).contains(&size) {
+                return Err(Error::Config(
+                    format!(
+                        "UDP {name} batch size {size} must be between 1 and {}",
+                        Self::MAX_BATCH
+                    )
+                    .into(),
+                )
+                .boxed());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for UdpBatching {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Builder for [`UdpTransport`].
+#[derive(Clone)]
 pub struct UdpTransportBuilder {
     bind_addr: String,
     config: UdpTransportConfig,
     recv_buffer_size: Option<usize>,
     send_buffer_size: Option<usize>,
+    batching: Option<UdpBatching>,
+    source_port_pool: Option<usize>,
+    socket_options: SocketOptions,
 }
 
 impl UdpTransportBuilder {
@@ -365,6 +531,9 @@ impl UdpTransportBuilder {
             config: UdpTransportConfig::default(),
             recv_buffer_size: None,
             send_buffer_size: None,
+            batching: None,
+            source_port_pool: None,
+            socket_options: SocketOptions::new(),
         }
     }
 
@@ -438,8 +607,121 @@ impl UdpTransportBuilder {
         self
     }
 
+    /// Enable batched datagram I/O (`recvmmsg`/`sendmmsg`) on Linux.
+    ///
+    /// The receive task drains up to
+    /// [`recv_batch`](UdpBatching::recv_batch) datagrams per system call, and
+    /// sends from every handle are queued and flushed up to
+    /// [`send_batch`](UdpBatching::send_batch) datagrams per system call. This
+    /// pays off on busy shared transports, where per-datagram syscall overhead
+    /// dominates; [`UdpStats`] reports the batch sizes achieved.
+    ///
+    /// Each receive slot holds a full-size UDP buffer, so the receive batch
+    /// costs about 64KB of memory per slot. On other platforms the setting is
+    /// validated and otherwise ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use async_snmp::transport::{UdpBatching, UdpTransport};
+    ///
+    /// # async fn example() -> async_snmp::Result<()> {
+    /// let transport = UdpTransport::builder()
+    ///     .batched_io(UdpBatching::new().recv_batch(64).send_batch(32))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn batched_io(mut self, batching: UdpBatching) -> Self {
+        self.batching = Some(batching);
+        self
+    }
+
+    /// Set interface, source address, and IP header options for the socket.
+    ///
+    /// A [`source_addr`](SocketOptions::source_addr) replaces the IP of the
+    /// [`bind`](Self::bind) address. The options also apply to every socket of
+    /// a [`source_port_pool`](Self::source_port_pool).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use async_snmp::transport::{SocketOptions, UdpTransport};
+    ///
+    /// # async fn example() -> async_snmp::Result<()> {
+    /// let transport = UdpTransport::builder()
+    ///     .socket_options(SocketOptions::new().bind_device("mgmt").dscp(16).ttl(8))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn socket_options(mut self, options: SocketOptions) -> Self {
+        self.socket_options = options;
+        self
+    }
+
+    /// Send each request from its own pre-bound ephemeral port.
+    ///
+    /// The transport binds `size` extra sockets on the bind address's IP. A
+    /// request leases a randomly chosen idle socket, sends from it, and only
+    /// accepts a response that arrives on that socket's port, so a spoofed
+    /// response must match the port as well as the request ID. When every
+    /// socket is leased, requests wait for one to be released, bounded by
+    /// their timeout. [`UdpStats`] counts acquisitions, releases, and waits.
+    ///
+    /// Responses to an earlier retry attempt arrive on that attempt's port
+    /// and are discarded. Fire-and-forget sends and batched I/O still use the
+    /// main socket. `size` must be between 1 and 1024.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use async_snmp::transport::UdpTransport;
+    ///
+    /// # async fn example() -> async_snmp::Result<()> {
+    /// let transport = UdpTransport::builder()
+    ///     .source_port_pool(64)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn source_port_pool(mut self, size: usize) -> Self {
+        self.source_port_pool = Some(size);
+        self
+    }
+
     /// Build the transport.
     pub async fn build(self) -> Result<UdpTransport> {
+        self.build_shard(None, Arc::new(UdpCore::new()), None).await
+    }
+
+    /// Parse the bind address, applying any configured source address.
+    pub(super) fn parsed_bind_addr(&self) -> Result<SocketAddr> {
+        let mut addr: SocketAddr = self.bind_addr.parse().map_err(|_| {
+            Error::Config(format!("invalid bind address: {}", self.bind_addr).into()).boxed()
+        })?;
+        if let Some(source) = self.socket_options.source_addr {
+            addr.set_ip(source);
+        }
+        Ok(addr)
+    }
+
+    /// Bind the socket owner used by one shard of a sharded transport.
+    ///
+    /// `reuse_port_addr` overrides the bind address and sets `SO_REUSEPORT`;
+    /// `routing` lets this shard's recv task deliver to sibling cores.
+    pub(super) async fn build_shard(
+        self,
+        reuse_port_addr: Option<SocketAddr>,
+        core: Arc<UdpCore>,
+        routing: Option<Arc<ShardRouting>>,
+    ) -> Result<UdpTransport> {
         // Validate before parsing or binding so invalid size configuration has
         // deterministic precedence and can never be narrowed on advertisement.
         let receive_limits = ReceiveLimits::udp(self.config.local_receive_capacity)
@@ -455,15 +737,34 @@ impl UdpTransportBuilder {
             )
             .boxed());
         }
-        let bind_addr: SocketAddr = self.bind_addr.parse().map_err(|_| {
-            Error::Config(format!("invalid bind address: {}", self.bind_addr).into())
-        })?;
+        if let Some(batching) = self.batching {
+            batching.validate()?;
+        }
+        if let Some(size) = self.source_port_pool
+            && !(1..=MAX_SOURCE_PORT_POOL).contains(&size)
+        {
+            return Err(Error::Config(
+                format!(
+                    "UDP source port pool size {size} must be between 1 and {MAX_SOURCE_PORT_POOL}"
+                )
+                .into(),
+            )
+            .boxed());
+        }
+        let bind_addr = match reuse_port_addr {
+            Some(addr) => addr,
+            None => self.parsed_bind_addr()?,
+        };
 
-        let socket = bind_udp_socket(
+        let socket = bind_udp_socket_with(
             bind_addr,
-            self.recv_buffer_size,
-            self.send_buffer_size,
-            true,
+            &UdpBindOptions {
+                recv_buffer_size: self.recv_buffer_size,
+                send_buffer_size: self.send_buffer_size,
+                reuse_address: true,
+                reuse_port: reuse_port_addr.is_some(),
+                socket: self.socket_options.clone(),
+            },
         )
         .await
         .map_err(|e| Error::Network {
@@ -480,10 +781,37 @@ impl UdpTransportBuilder {
 
         let shutdown = CancellationToken::new();
         let shutdown_complete = CancellationToken::new();
+        let socket = Arc::new(socket);
+        let port_pool = match self.source_port_pool {
+            Some(size) => Some(
+                SourcePortPool::bind(
+                    local_addr,
+                    &self.socket_options,
+                    size,
+                    Arc::clone(&core),
+                    &shutdown,
+                )
+                .await
+                .map_err(|e| Error::Network {
+                    target: bind_addr,
+                    source: e,
+                })?,
+            ),
+            None => None,
+        };
+        #[cfg(target_os = "linux")]
+        let send_queue = self.batching.map(|batching| {
+            super::udp_batch::SendQueue::spawn(
+                Arc::clone(&socket),
+                Arc::clone(&core),
+                batching.send_batch,
+                shutdown.clone(),
+            )
+        });
         let inner = Arc::new(UdpTransportInner {
-            socket: Arc::new(socket),
+            socket,
             local_addr,
-            core: Arc::new(UdpCore::new()),
+            core,
             config: self.config,
             receive_limits,
             _shutdown_guard: shutdown.clone().drop_guard(),
@@ -491,13 +819,16 @@ impl UdpTransportBuilder {
             shutdown_complete,
             operations: tokio::sync::RwLock::new(()),
             recv_task: tokio::sync::Mutex::new(None),
+            port_pool,
+            #[cfg(target_os = "linux")]
+            send_queue,
             #[cfg(test)]
             receive_errors: Arc::new(std::sync::Mutex::new(std::collections::VecDeque::new())),
             #[cfg(test)]
             receive_error_ready: Arc::new(tokio::sync::Notify::new()),
         });
 
-        UdpTransport::start_recv_loop(&inner);
+        UdpTransport::start_recv_loop(&inner, self.batching, routing);
 
         Ok(UdpTransport { inner })
     }
@@ -564,15 +895,60 @@ impl Default for UdpTransportBuilder {
 ///     handle.shutdown().await;
 /// }
 /// ```
-#[derive(Clone)]
 pub struct UdpHandle {
     inner: Arc<UdpTransportInner>,
-    target: SocketAddr,
+    /// Replaced in place when a client re-resolves its target. Each clone
+    /// starts from a copy, so retargeting never moves another handle.
+    target: Arc<std::sync::RwLock<SocketAddr>>,
     strict_source: bool,
     #[cfg(test)]
     send_gate: Option<Arc<tokio::sync::Semaphore>>,
 }
 
+impl Clone for UdpHandle {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            target: Arc::new(std::sync::RwLock::new(self.target())),
+            strict_source: self.strict_source,
+            #[cfg(test)]
+            send_gate: self.send_gate.clone(),
+        }
+    }
+}
+
+/// Replaceable target address of one [`UdpHandle`].
+#[derive(Clone)]
+pub(crate) struct UdpPeer {
+    target: Arc<std::sync::RwLock<SocketAddr>>,
+    local_addr: SocketAddr,
+}
+
+impl UdpPeer {
+    /// Normalize `target` for the handle's socket family.
+    pub(crate) fn normalize(&self, target: SocketAddr) -> Result<SocketAddr> {
+        normalize_udp_target(self.local_addr, target)
+    }
+
+    /// Returns the handle's current target.
+    pub(crate) fn current(&self) -> SocketAddr {
+        *self
+            .target
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Point the handle at `target`, returning the previous address.
+    pub(crate) fn replace(&self, target: SocketAddr) -> Result<SocketAddr> {
+        let target = self.normalize(target)?;
+        let mut current = self
+            .target
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Ok(std::mem::replace(&mut *current, target))
+    }
+}
+
 impl UdpHandle {
     /// Snapshot cumulative statistics for this handle's UDP endpoint.
     ///
@@ -596,6 +972,21 @@ impl UdpHandle {
         self.strict_source = strict;
         self
     }
+
+    /// Shared access to this handle's target for in-place retargeting.
+    pub(crate) fn peer(&self) -> UdpPeer {
+        UdpPeer {
+            target: Arc::clone(&self.target),
+            local_addr: self.inner.local_addr,
+        }
+    }
+
+    fn target(&self) -> SocketAddr {
+        *self
+            .target
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
 }
 
 impl Transport for UdpHandle {
@@ -603,7 +994,7 @@ impl Transport for UdpHandle {
         crate::message_size::enforce_outbound_size(data.len(), self.send_capacity())?;
         let _operation = self.inner.operations.read().await;
         self.ensure_open()?;
-        self.send_datagram(data).await
+        self.send_datagram_to(self.target(), data, None).await
     }
 
     async fn request_with<T, F>(
@@ -619,31 +1010,43 @@ impl Transport for UdpHandle {
         crate::message_size::enforce_outbound_size(data.len(), self.send_capacity())?;
         let _operation = self.inner.operations.read().await;
         self.ensure_open()?;
+        let target = self.target();
+
+        // Declared before the registration so the port stays leased until the
+        // registration has been removed.
+        let lease = match &self.inner.port_pool {
+            Some(pool) => Some(self.acquire_source_port(pool, &registration).await?),
+            None => None,
+        };
 
-        // Registration is the first protocol work performed when this future
-        // is polled. The guard owns primary and alias cleanup across both
+        // Registration is the first protocol work performed once any source
+        // port is leased. The guard owns primary and alias cleanup across both
         // awaits, including cancellation and send failure.
-        let registration =
-            self.inner
-                .core
-                .register(registration, self.target, self.strict_source)?;
+        let registration = self.inner.core.register_on(
+            registration,
+            target,
+            self.strict_source,
+            lease.as_ref().map(PortLease::port),
+        )?;
         let deadline = registration.deadline();
         if tokio::time::Instant::now() >= deadline {
-            return Err(registration.timeout_error(self.target));
+            return Err(registration.timeout_error(target));
         }
         tokio::select! {
             biased;
-            () = tokio::time::sleep_until(deadline) => {
-                tracing::debug!(target: "async_snmp::transport::udp", { request_id = registration.request_id(), target = %self.target }, "transport timeout during UDP send");
-                return Err(registration.timeout_error(self.target));
+            () = crate::runtime::sleep_until(deadline) => {
+                tracing::debug!(target: "async_snmp::transport::udp", { request_id = registration.request_id(), target = %target }, "transport timeout during UDP send");
+                return Err(registration.timeout_error(target));
             }
-            result = self.send_datagram(data) => result?,
+            result = self.send_datagram_to(target, data, lease.as_ref()) => result?,
         }
-        self.recv_registered_with(&registration, validate).await
+        registration.mark_sent();
+        self.recv_registered_with(&registration, target, validate)
+            .await
     }
 
     fn peer_addr(&self) -> SocketAddr {
-        self.target
+        self.target()
     }
 
     fn local_addr(&self) -> SocketAddr {
@@ -667,14 +1070,42 @@ impl UdpHandle {
     fn ensure_open(&self) -> Result<()> {
         if self.inner.shutdown.is_cancelled() {
             return Err(Error::Closed {
-                target: self.target,
+                target: self.target(),
             }
             .boxed());
         }
         Ok(())
     }
 
-    async fn send_datagram(&self, data: &[u8]) -> Result<()> {
+    /// Lease a pooled source socket before `registration`'s deadline.
+    async fn acquire_source_port(
+        &self,
+        pool: &Arc<SourcePortPool>,
+        registration: &RequestRegistration,
+    ) -> Result<PortLease> {
+        let started = tokio::time::Instant::now();
+        tokio::select! {
+            biased;
+            lease = pool.acquire() => Ok(lease),
+            () = crate::runtime::sleep_until(registration.deadline()) => {
+                tracing::debug!(target: "async_snmp::transport::udp", { request_id = registration.request_id(), target = %self.target() }, "transport timeout waiting for a pooled source port");
+                Err(Error::Timeout {
+                    target: self.target(),
+                    elapsed: started.elapsed(),
+                    retries: 0,
+                }
+                .boxed())
+            }
+            () = self.inner.shutdown.cancelled() => Err(Error::Closed { target: self.target() }.boxed()),
+        }
+    }
+
+    async fn send_datagram_to(
+        &self,
+        target: SocketAddr,
+        data: &[u8],
+        lease: Option<&PortLease>,
+    ) -> Result<()> {
         crate::message_size::enforce_outbound_size(data.len(), self.send_capacity())?;
         #[cfg(test)]
         if let Some(gate) = &self.send_gate {
@@ -683,15 +1114,19 @@ impl UdpHandle {
                 .expect("test send gate remains open")
                 .forget();
         }
-        tracing::trace!(target: "async_snmp::transport", { snmp.target = %self.target, snmp.bytes = data.len() }, "UDP send");
+        tracing::trace!(target: "async_snmp::transport", { snmp.target = %target, snmp.bytes = data.len() }, "UDP send");
+        if let Some(lease) = lease {
+            return lease.send(data, target).await;
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(queue) = &self.inner.send_queue {
+            return queue.send(data, target).await;
+        }
         self.inner
             .socket
-            .send_to(data, self.target)
+            .send_to(data, target)
             .await
-            .map_err(|e| Error::Network {
-                target: self.target,
-                source: e,
-            })?;
+            .map_err(|e| Error::Network { target, source: e })?;
         Ok(())
     }
     #[cfg(test)]
@@ -699,7 +1134,7 @@ impl UdpHandle {
         &self,
         registration: &super::udp_core::UdpRegistration,
     ) -> Result<(Bytes, SocketAddr)> {
-        self.recv_registered_with(registration, |data, source| {
+        self.recv_registered_with(registration, self.target(), |data, source| {
             Ok(Candidate::Accept((data, source)))
         })
         .await
@@ -708,28 +1143,29 @@ impl UdpHandle {
     async fn recv_registered_with<T, F>(
         &self,
         registration: &super::udp_core::UdpRegistration,
+        target: SocketAddr,
         mut validate: F,
     ) -> Result<T>
     where
         F: FnMut(Bytes, SocketAddr) -> Result<Candidate<T>>,
     {
         let request_id = registration.request_id();
-        tracing::trace!(target: "async_snmp::transport", { snmp.target = %self.target, snmp.request_id = request_id }, "UDP recv waiting");
+        tracing::trace!(target: "async_snmp::transport", { snmp.target = %target, snmp.request_id = request_id }, "UDP recv waiting");
 
         let result = self
             .inner
             .core
-            .wait_for_response_with(registration, self.target, |data, source| {
-                if self.inner.config.warn_on_source_mismatch && source != self.target {
-                    tracing::warn!(target: "async_snmp::transport", { snmp.request_id = request_id, snmp.target = %self.target, snmp.source = %source }, "response source address mismatch");
+            .wait_for_response_with(registration, target, |data, source| {
+                if self.inner.config.warn_on_source_mismatch && source != target {
+                    tracing::warn!(target: "async_snmp::transport", { snmp.request_id = request_id, snmp.target = %target, snmp.source = %source }, "response source address mismatch");
                 }
-                tracing::trace!(target: "async_snmp::transport", { snmp.target = %self.target, snmp.source = %source, snmp.bytes = data.len() }, "UDP recv candidate");
+                tracing::trace!(target: "async_snmp::transport", { snmp.target = %target, snmp.source = %source, snmp.bytes = data.len() }, "UDP recv candidate");
                 validate(data, source)
             })
             .await;
 
         if result.is_err() {
-            tracing::trace!(target: "async_snmp::transport", { snmp.target = %self.target, snmp.request_id = request_id }, "UDP recv failed");
+            tracing::trace!(target: "async_snmp::transport", { snmp.target = %target, snmp.request_id = request_id }, "UDP recv failed");
         }
         result
     }
@@ -760,12 +1196,30 @@ mod tests {
             .core
             .register(
                 RequestRegistration::test_unchecked(request_id, timeout),
-                handle.target,
+                handle.target(),
                 handle.strict_source,
             )
             .unwrap()
     }
 
+    #[tokio::test]
+    async fn retargeting_moves_only_the_original_handle() {
+        let transport = UdpTransport::bind("[::]:0").await.unwrap();
+        let handle = transport.handle("192.0.2.1:161".parse().unwrap()).unwrap();
+        let clone = handle.clone();
+
+        let previous = handle
+            .peer()
+            .replace("192.0.2.2:161".parse().unwrap())
+            .unwrap();
+        assert_eq!(previous, "[::ffff:192.0.2.1]:161".parse().unwrap());
+        assert_eq!(
+            handle.peer_addr(),
+            "[::ffff:192.0.2.2]:161".parse().unwrap()
+        );
+        assert_eq!(clone.peer_addr(), previous);
+    }
+
     #[tokio::test]
     async fn ipv6_transport_maps_ipv4_target() {
         let transport = UdpTransport::bind("[::]:0").await.unwrap();
@@ -906,11 +1360,231 @@ mod tests {
         assert!(transport.local_addr().port() > 0);
     }
 
+    #[tokio::test]
+    async fn out_of_range_batch_size_is_rejected() {
+        for batching in [
+            UdpBatching::new().recv_batch(0),
+            UdpBatching::new().send_batch(1025),
+        ] {
+            let error = UdpTransport::builder()
+                .batched_io(batching)
+                .build()
+                .await
+                .err()
+                .expect("invalid batch size must fail");
+            assert!(matches!(*error, Error::Config(_)));
+            assert!(error.to_string().contains("batch size"));
+        }
+    }
+
+    #[tokio::test]
+    async fn source_port_pool_sends_each_request_from_a_leased_port() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let transport = UdpTransport::builder()
+            .bind("127.0.0.1:0")
+            .source_port_pool(4)
+            .build()
+            .await
+            .unwrap();
+        let handle = transport.handle(server.local_addr().unwrap()).unwrap();
+
+        let mut ports = std::collections::HashSet::new();
+        for msg_id in 1..=8 {
+            let payload = [msg_id];
+            let request = handle.request_with(
+                &payload,
+                RequestRegistration::v3(i32::from(msg_id), deadline_after(Duration::from_secs(2))),
+                |data, _| Ok(Candidate::Accept(data)),
+            );
+            let respond = async {
+                let mut data = [0; 64];
+                let (_, source) = server.recv_from(&mut data).await.unwrap();
+                // Spoofed from the main socket's port first: must be ignored.
+                let spoof = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+                spoof
+                    .send_to(&v3_identity(msg_id), transport.local_addr())
+                    .await
+                    .unwrap();
+                server.send_to(&v3_identity(msg_id), source).await.unwrap();
+                source.port()
+            };
+            let (result, port) = tokio::join!(request, respond);
+            assert_eq!(result.unwrap(), v3_identity(msg_id));
+            assert_ne!(port, transport.local_addr().port());
+            ports.insert(port);
+        }
+        assert!(ports.len() <= 4);
+
+        let stats = transport.stats();
+        assert_eq!(stats.source_port_acquisitions, 8);
+        assert_eq!(stats.source_port_releases, 8);
+        assert_eq!(stats.correlated_datagrams, 8);
+    }
+
+    #[tokio::test]
+    async fn source_port_correlation_rejects_other_ports() {
+        let transport = UdpTransport::builder()
+            .bind("127.0.0.1:0")
+            .source_port_pool(1)
+            .build()
+            .await
+            .unwrap();
+        let pool = transport.inner.port_pool.clone().unwrap();
+        let lease = pool.acquire().await;
+        let target: SocketAddr = "127.0.0.1:161".parse().unwrap();
+        let _registration = transport
+            .inner
+            .core
+            .register_on(
+                RequestRegistration::v3(9, deadline_after(Duration::from_secs(2))),
+                target,
+                false,
+                Some(lease.port()),
+            )
+            .unwrap();
+
+        assert!(!transport.inner.core.deliver(9, v3_identity(9), target));
+        assert!(!transport.inner.core.deliver_on(
+            9,
+            v3_identity(9),
+            target,
+            Some(lease.port().wrapping_add(1))
+        ));
+        assert!(
+            transport
+                .inner
+                .core
+                .deliver_on(9, v3_identity(9), target, Some(lease.port()))
+        );
+        assert_eq!(transport.stats().discarded_datagrams, 2);
+    }
+
+    #[tokio::test]
+    async fn exhausted_source_port_pool_waits_until_request_deadline() {
+        let transport = UdpTransport::builder()
+            .bind("127.0.0.1:0")
+            .source_port_pool(1)
+            .build()
+            .await
+            .unwrap();
+        let lease = transport.inner.port_pool.clone().unwrap().acquire().await;
+        let handle = transport.handle("127.0.0.1:9".parse().unwrap()).unwrap();
+
+        let error = handle
+            .request_with(
+                b"request",
+                RequestRegistration::v3(1, deadline_after(Duration::from_millis(20))),
+                |data, source| Ok(Candidate::Accept((data, source))),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Timeout { .. }));
+
+        drop(lease);
+        let stats = transport.stats();
+        assert_eq!(stats.source_port_acquisitions, 1);
+        assert_eq!(stats.source_port_releases, 1);
+        assert_eq!(stats.source_port_waits, 0);
+    }
+
+    #[tokio::test]
+    async fn source_addr_replaces_bind_ip_for_main_and_pooled_sockets() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let transport = UdpTransport::builder()
+            .bind("0.0.0.0:0")
+            .socket_options(SocketOptions::new().source_addr("127.0.0.1".parse().unwrap()))
+            .source_port_pool(1)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(transport.local_addr().ip().to_string(), "127.0.0.1");
+
+        let handle = transport.handle(server.local_addr().unwrap()).unwrap();
+        let request = handle.request_with(
+            b"request",
+            RequestRegistration::v3(3, deadline_after(Duration::from_secs(2))),
+            |data, _| Ok(Candidate::Accept(data)),
+        );
+        let respond = async {
+            let mut data = [0; 64];
+            let (_, source) = server.recv_from(&mut data).await.unwrap();
+            server.send_to(&v3_identity(3), source).await.unwrap();
+            source
+        };
+        let (result, source) = tokio::join!(request, respond);
+        result.unwrap();
+        assert_eq!(source.ip().to_string(), "127.0.0.1");
+    }
+
+    #[tokio::test]
+    async fn out_of_range_source_port_pool_is_rejected() {
+        for size in [0, 1025] {
+            let error = UdpTransport::builder()
+                .source_port_pool(size)
+                .build()
+                .await
+                .err()
+                .expect("invalid pool size must fail");
+            assert!(matches!(*error, Error::Config(_)));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn batched_io_correlates_responses_and_counts_batches() {
+        const REQUESTS: u8 = 16;
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let transport = UdpTransport::builder()
+            .bind("127.0.0.1:0")
+            .batched_io(UdpBatching::new().recv_batch(8).send_batch(8))
+            .build()
+            .await
+            .unwrap();
+
+        let requests = (1..=REQUESTS).map(|msg_id| {
+            let handle = transport.handle(server.local_addr().unwrap()).unwrap();
+            tokio::spawn(async move {
+                handle
+                    .request_with(
+                        &[msg_id],
+                        RequestRegistration::v3(
+                            i32::from(msg_id),
+                            deadline_after(Duration::from_secs(5)),
+                        ),
+                        |data, _| Ok(Candidate::Accept(data)),
+                    )
+                    .await
+            })
+        });
+        let requests: Vec<_> = requests.collect();
+
+        // Collect every request before answering so the replies arrive as a burst.
+        let mut pending = Vec::new();
+        let mut data = [0; 64];
+        while pending.len() < usize::from(REQUESTS) {
+            let (len, source) = server.recv_from(&mut data).await.unwrap();
+            assert_eq!(len, 1);
+            pending.push((data[0], source));
+        }
+        for (msg_id, source) in pending {
+            server.send_to(&v3_identity(msg_id), source).await.unwrap();
+        }
+
+        for request in requests {
+            request.await.unwrap().unwrap();
+        }
+        let stats = transport.stats();
+        assert_eq!(stats.send_batched_datagrams, u64::from(REQUESTS));
+        assert_eq!(stats.recv_batched_datagrams, u64::from(REQUESTS));
+        assert!(stats.send_batches > 0 && stats.send_batches <= u64::from(REQUESTS));
+        assert!(stats.recv_batches > 0 && stats.recv_batches <= u64::from(REQUESTS));
+    }
+
     #[tokio::test]
     async fn final_endpoint_reference_drop_stops_recv_task() {
         let transport = UdpTransport::bind("127.0.0.1:0").await.unwrap();
         let handle = transport.handle("127.0.0.1:161".parse().unwrap()).unwrap();
-        let task = transport
+        let mut task = transport
             .inner
             .recv_task
             .try_lock()
@@ -922,7 +1596,9 @@ mod tests {
         drop(transport);
         tokio::task::yield_now().await;
         assert!(
-            !task.is_finished(),
+            tokio::time::timeout(Duration::ZERO, &mut task)
+                .await
+                .is_err(),
             "recv task stopped while a handle still held the endpoint"
         );
 
@@ -0,0 +1,176 @@
+//! Batched UDP I/O through `recvmmsg` and `sendmmsg` on Linux.
+//!
+//! The receive side replaces the recv task's single-datagram read with one
+//! `recvmmsg` call per readiness event. The send side funnels every handle's
+//! datagrams through a bounded queue drained by one task, which flushes up to
+//! a batch of queued datagrams per `sendmmsg` call.
+
+use super::udp_core::UdpCore;
+use crate::error::{Error, Result};
+use crate::runtime::{Interest, UdpSocket};
+use bytes::Bytes;
+use nix::sys::socket::{
+    ControlMessage, MsgFlags, MultiHeaders, SockaddrStorage, recvmmsg, sendmmsg,
+};
+use std::io::{self, IoSlice, IoSliceMut};
+use std::net::SocketAddr;
+use std::os::fd::AsRawFd;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+
+/// Receive buffers for one `recvmmsg` call.
+pub(super) struct RecvBatch {
+    buffers: Vec<Vec<u8>>,
+    /// `(buffer index, length, source)` for each datagram of the last call.
+    received: Vec<(usize, usize, SocketAddr)>,
+}
+
+impl RecvBatch {
+    pub(super) fn new(batch: usize, buffer_size: usize) -> Self {
+        Self {
+            buffers: vec![vec![0; buffer_size]; batch],
+            received: Vec::with_capacity(batch),
+        }
+    }
+
+    /// Wait for readability, then receive every queued datagram up to the batch size.
+    ///
+    /// Returns the number of datagrams now available through [`datagram`](Self::datagram).
+    pub(super) async fn recv(&mut self, socket: &UdpSocket) -> io::Result<usize> {
+        let Self { buffers, received } = self;
+        socket
+            .async_io(Interest::Readable, || {
+                received.clear();
+                let mut headers = MultiHeaders::<SockaddrStorage>::preallocate(buffers.len(), None);
+                let mut slices: Vec<[IoSliceMut<'_>; 1]> = buffers
+                    .iter_mut()
+                    .map(|buffer| [IoSliceMut::new(buffer)])
+                    .collect();
+                let messages = recvmmsg(
+                    socket.as_raw_fd(),
+                    &mut headers,
+                    slices.iter_mut(),
+                    MsgFlags::empty(),
+                    None,
+                )
+                .map_err(io::Error::from)?;
+                for (index, message) in messages.enumerate() {
+                    // Every UDP datagram carries a source; skip anything else.
+                    if let Some(source) = message.address.as_ref().and_then(decode_socket_addr) {
+                        received.push((index, message.bytes, source));
+                    }
+                }
+                Ok(received.len())
+            })
+            .await
+    }
+
+    /// Return the `index`th datagram of the last [`recv`](Self::recv) call.
+    pub(super) fn datagram(&self, index: usize) -> (&[u8], SocketAddr) {
+        let (buffer, len, source) = self.received[index];
+        (&self.buffers[buffer][..len], source)
+    }
+}
+
+fn decode_socket_addr(storage: &SockaddrStorage) -> Option<SocketAddr> {
+    storage
+        .as_sockaddr_in()
+        .copied()
+        .map(SocketAddr::from)
+        .or_else(|| storage.as_sockaddr_in6().copied().map(SocketAddr::from))
+}
+
+struct SendJob {
+    data: Bytes,
+    target: SocketAddr,
+    done: oneshot::Sender<io::Result<()>>,
+}
+
+/// Queue feeding the `sendmmsg` task.
+pub(super) struct SendQueue {
+    jobs: mpsc::Sender<SendJob>,
+}
+
+impl SendQueue {
+    /// Spawn the send task for `socket`.
+    ///
+    /// The task holds no reference to the transport; it stops when `shutdown`
+    /// fires or every queue handle is dropped.
+    pub(super) fn spawn(
+        socket: Arc<UdpSocket>,
+        core: Arc<UdpCore>,
+        batch: usize,
+        shutdown: CancellationToken,
+    ) -> Self {
+        let (jobs, mut queue) = mpsc::channel::<SendJob>(batch.saturating_mul(4));
+        crate::runtime::spawn(async move {
+            let mut batch_jobs = Vec::with_capacity(batch);
+            let mut outcomes = Vec::with_capacity(batch);
+            loop {
+                let queued = tokio::select! {
+                    biased;
+                    () = shutdown.cancelled() => break,
+                    queued = queue.recv_many(&mut batch_jobs, batch) => queued,
+                };
+                if queued == 0 {
+                    break;
+                }
+                while outcomes.len() < batch_jobs.len() {
+                    let pending = &batch_jobs[outcomes.len()..];
+                    match socket
+                        .async_io(Interest::Writable, || send_batch(&socket, pending))
+                        .await
+                    {
+                        Ok(sent) => {
+                            core.note_send_batch(sent);
+                            outcomes.extend((0..sent).map(|_| Ok(())));
+                        }
+                        // sendmmsg reports an error only for the first
+                        // unsent datagram; later datagrams are retried.
+                        Err(error) => outcomes.push(Err(error)),
+                    }
+                }
+                for (job, outcome) in batch_jobs.drain(..).zip(outcomes.drain(..)) {
+                    let _ = job.done.send(outcome);
+                }
+            }
+        });
+        Self { jobs }
+    }
+
+    /// Queue one datagram and wait until it has been handed to the kernel.
+    pub(super) async fn send(&self, data: &[u8], target: SocketAddr) -> Result<()> {
+        let (done, outcome) = oneshot::channel();
+        let job = SendJob {
+            data: Bytes::copy_from_slice(data),
+            target,
+            done,
+        };
+        let closed = || Error::Closed { target }.boxed();
+        self.jobs.send(job).await.map_err(|_| closed())?;
+        outcome
+            .await
+            .map_err(|_| closed())?
+            .map_err(|source| Error::Network { target, source }.boxed())
+    }
+}
+
+fn send_batch(socket: &UdpSocket, jobs: &[SendJob]) -> io::Result<usize> {
+    let mut headers = MultiHeaders::<SockaddrStorage>::preallocate(jobs.len(), None);
+    let slices: Vec<[IoSlice<'_>; 1]> = jobs.iter().map(|job| [IoSlice::new(&job.data)]).collect();
+    let addresses: Vec<Option<SockaddrStorage>> = jobs
+        .iter()
+        .map(|job| Some(SockaddrStorage::from(job.target)))
+        .collect();
+    let sent = sendmmsg(
+        socket.as_raw_fd(),
+        &mut headers,
+        &slices,
+        &addresses,
+        [] as [ControlMessage<'_>; 0],
+        MsgFlags::empty(),
+    )
+    .map_err(io::Error::from)?;
+    Ok(sent.count())
+}
@@ -11,7 +11,7 @@ use std::time::Duration;
 use tokio::sync::Notify;
 use tokio::time::Instant;
 
-use super::{Candidate, CorrelationWindow, RequestRegistration, ResponseIdentity};
+use super::{Candidate, CorrelationWindow, RequestRegistration, ResponseIdentity, SentHook};
 use crate::error::{Error, Result};
 
 const SHARDS: usize = 64;
@@ -40,6 +40,20 @@ struct CoreStats {
     discarded_datagrams: AtomicU64,
     /// Datagrams from which no request ID could be extracted.
     malformed_datagrams: AtomicU64,
+    /// Batched receive calls that returned at least one datagram.
+    recv_batches: AtomicU64,
+    /// Datagrams returned by batched receive calls.
+    recv_batched_datagrams: AtomicU64,
+    /// Batched send calls that sent at least one datagram.
+    send_batches: AtomicU64,
+    /// Datagrams sent by batched send calls.
+    send_batched_datagrams: AtomicU64,
+    /// Pooled source sockets leased to requests.
+    source_port_acquisitions: AtomicU64,
+    /// Pooled source sockets returned to the pool.
+    source_port_releases: AtomicU64,
+    /// Leases that waited because every pooled socket was in use.
+    source_port_waits: AtomicU64,
 }
 
 /// UDP endpoint statistics.
@@ -58,6 +72,67 @@ pub struct UdpStats {
     pub discarded_datagrams: u64,
     /// Datagrams from which no request ID could be extracted.
     pub malformed_datagrams: u64,
+    /// `recvmmsg` calls that returned at least one datagram.
+    ///
+    /// Zero unless batched I/O is enabled on Linux. Divide
+    /// [`recv_batched_datagrams`](Self::recv_batched_datagrams) by this to get
+    /// the mean achieved receive batch size.
+    pub recv_batches: u64,
+    /// Datagrams returned by `recvmmsg` calls.
+    pub recv_batched_datagrams: u64,
+    /// `sendmmsg` calls that sent at least one datagram.
+    ///
+    /// Zero unless batched I/O is enabled on Linux. Divide
+    /// [`send_batched_datagrams`](Self::send_batched_datagrams) by this to get
+    /// the mean achieved send batch size.
+    pub send_batches: u64,
+    /// Datagrams sent by `sendmmsg` calls.
+    pub send_batched_datagrams: u64,
+    /// Pooled source sockets leased to requests.
+    ///
+    /// Zero unless a source port pool is configured. The difference from
+    /// [`source_port_releases`](Self::source_port_releases) is the number of
+    /// sockets currently leased.
+    pub source_port_acquisitions: u64,
+    /// Pooled source sockets returned to the pool.
+    pub source_port_releases: u64,
+    /// Leases that had to wait because every pooled socket was in use.
+    pub source_port_waits: u64,
+}
+
+impl std::iter::Sum for UdpStats {
+    /// Add counters field by field, e.g. to aggregate per-shard statistics.
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(
+            Self {
+                correlated_datagrams: 0,
+                expired_registrations: 0,
+                discarded_datagrams: 0,
+                malformed_datagrams: 0,
+                recv_batches: 0,
+                recv_batched_datagrams: 0,
+                send_batches: 0,
+                send_batched_datagrams: 0,
+                source_port_acquisitions: 0,
+                source_port_releases: 0,
+                source_port_waits: 0,
+            },
+            |total, stats| Self {
+                correlated_datagrams: total.correlated_datagrams + stats.correlated_datagrams,
+                expired_registrations: total.expired_registrations + stats.expired_registrations,
+                discarded_datagrams: total.discarded_datagrams + stats.discarded_datagrams,
+                malformed_datagrams: total.malformed_datagrams + stats.malformed_datagrams,
+                recv_batches: total.recv_batches + stats.recv_batches,
+                recv_batched_datagrams: total.recv_batched_datagrams + stats.recv_batched_datagrams,
+                send_batches: total.send_batches + stats.send_batches,
+                send_batched_datagrams: total.send_batched_datagrams + stats.send_batched_datagrams,
+                source_port_acquisitions: total.source_port_acquisitions
+                    + stats.source_port_acquisitions,
+                source_port_releases: total.source_port_releases + stats.source_port_releases,
+                source_port_waits: total.source_port_waits + stats.source_port_waits,
+            },
+        )
+    }
 }
 
 struct Shard {
@@ -93,6 +168,9 @@ struct ResponseSlot {
     target_source: SocketAddr,
     /// When true, only responses from exactly the target are delivered.
     strict_source: bool,
+    /// Local port of the pooled socket the request was sent from, if any.
+    /// Responses must arrive on exactly this port.
+    local_port: Option<u16>,
     registration: RequestRegistration,
 }
 
@@ -126,6 +204,7 @@ pub(crate) struct UdpRegistration {
     aliases: BTreeSet<i32>,
     owner: Arc<RegistrationOwner>,
     correlation_window: Option<Arc<CorrelationWindow>>,
+    sent_hook: Option<SentHook>,
 }
 
 impl UdpRegistration {
@@ -137,6 +216,13 @@ impl UdpRegistration {
         self.owner.deadline
     }
 
+    /// See [`RequestRegistration::mark_sent`].
+    pub(crate) fn mark_sent(&self) {
+        if let Some(hook) = &self.sent_hook {
+            hook.run();
+        }
+    }
+
     pub(crate) fn timeout_error(&self, target: SocketAddr) -> Box<Error> {
         let now = Instant::now();
         self.owner.note_expired(&self.core.stats);
@@ -179,6 +265,13 @@ impl UdpCore {
                 expired_registrations: AtomicU64::new(0),
                 discarded_datagrams: AtomicU64::new(0),
                 malformed_datagrams: AtomicU64::new(0),
+                recv_batches: AtomicU64::new(0),
+                recv_batched_datagrams: AtomicU64::new(0),
+                send_batches: AtomicU64::new(0),
+                send_batched_datagrams: AtomicU64::new(0),
+                source_port_acquisitions: AtomicU64::new(0),
+                source_port_releases: AtomicU64::new(0),
+                source_port_waits: AtomicU64::new(0),
             },
             closed: AtomicBool::new(false),
         }
@@ -223,6 +316,20 @@ impl UdpCore {
         registration: RequestRegistration,
         target_source: SocketAddr,
         strict_source: bool,
+    ) -> Result<UdpRegistration> {
+        self.register_on(registration, target_source, strict_source, None)
+    }
+
+    /// Register a request sent from a pooled socket bound to `local_port`.
+    ///
+    /// Only [`deliver_on`](Self::deliver_on) calls reporting the same local
+    /// port can satisfy the registration.
+    pub(crate) fn register_on(
+        self: &Arc<Self>,
+        registration: RequestRegistration,
+        target_source: SocketAddr,
+        strict_source: bool,
+        local_port: Option<u16>,
     ) -> Result<UdpRegistration> {
         if self.closed.load(Ordering::Acquire) {
             return Err(Error::Closed {
@@ -236,6 +343,7 @@ impl UdpCore {
         let deadline = registration.deadline();
         let aliases = registration.aliases().clone();
         let correlation_window = registration.correlation_window().cloned();
+        let sent_hook = registration.sent_hook.clone();
         let correlation_window_id = correlation_window.as_deref().map(CorrelationWindow::id);
 
         let mut ids = Vec::with_capacity(1 + aliases.len());
@@ -339,6 +447,7 @@ impl UdpCore {
             owner: owner.clone(),
             target_source,
             strict_source,
+            local_port,
             registration,
         };
 
@@ -381,6 +490,7 @@ impl UdpCore {
             aliases,
             owner,
             correlation_window,
+            sent_hook,
         })
     }
 
@@ -415,6 +525,20 @@ impl UdpCore {
     /// Returns `true` if the slot existed and the response was stored,
     /// `false` if there was no matching pending request.
     pub fn deliver(&self, request_id: i32, data: Bytes, source: SocketAddr) -> bool {
+        self.deliver_on(request_id, data, source, None)
+    }
+
+    /// Deliver a response received on the pooled socket bound to `local_port`.
+    ///
+    /// `None` means the transport's main socket. The response is discarded
+    /// unless the registration was made for the same local port.
+    pub(crate) fn deliver_on(
+        &self,
+        request_id: i32,
+        data: Bytes,
+        source: SocketAddr,
+        local_port: Option<u16>,
+    ) -> bool {
         let (target_id, owner) = {
             let pending = self.shard(request_id).pending.lock().unwrap();
             match pending.get(&request_id) {
@@ -423,7 +547,14 @@ impl UdpCore {
                 None => (request_id, None),
             }
         };
-        self.deliver_to(target_id, owner.as_ref(), request_id, data, source)
+        self.deliver_to(
+            target_id,
+            owner.as_ref(),
+            request_id,
+            data,
+            source,
+            local_port,
+        )
     }
 
     fn deliver_to(
@@ -433,6 +564,7 @@ impl UdpCore {
         request_id: i32,
         data: Bytes,
         source: SocketAddr,
+        local_port: Option<u16>,
     ) -> bool {
         let shard = self.shard(target_id);
         let mut pending = shard.pending.lock().unwrap();
@@ -451,6 +583,17 @@ impl UdpCore {
                 return false;
             }
 
+            if slot.local_port != local_port {
+                // Guessing the request ID is not enough: a spoofed response
+                // must also hit the port the request was sent from.
+                drop(pending);
+                self.stats
+                    .discarded_datagrams
+                    .fetch_add(1, Ordering::Relaxed);
+                tracing::debug!(target: "async_snmp::transport::udp", { request_id, %source }, "response rejected by source port correlation");
+                return false;
+            }
+
             let source_is_target = slot.target_source == source;
             if slot.strict_source && !source_is_target {
                 // Leave the slot and original deadline intact.
@@ -593,7 +736,7 @@ impl UdpCore {
 
             tokio::select! {
                 () = registration.owner.notify.notified() => {}
-                () = tokio::time::sleep_until(registration.owner.deadline) => {}
+                () = crate::runtime::sleep_until(registration.owner.deadline) => {}
             }
         }
     }
@@ -617,9 +760,49 @@ impl UdpCore {
             expired_registrations: self.stats.expired_registrations.load(Ordering::Relaxed),
             discarded_datagrams: self.stats.discarded_datagrams.load(Ordering::Relaxed),
             malformed_datagrams: self.stats.malformed_datagrams.load(Ordering::Relaxed),
+            recv_batches: self.stats.recv_batches.load(Ordering::Relaxed),
+            recv_batched_datagrams: self.stats.recv_batched_datagrams.load(Ordering::Relaxed),
+            send_batches: self.stats.send_batches.load(Ordering::Relaxed),
+            send_batched_datagrams: self.stats.send_batched_datagrams.load(Ordering::Relaxed),
+            source_port_acquisitions: self.stats.source_port_acquisitions.load(Ordering::Relaxed),
+            source_port_releases: self.stats.source_port_releases.load(Ordering::Relaxed),
+            source_port_waits: self.stats.source_port_waits.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Record one batched receive call that returned `datagrams` datagrams.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn note_recv_batch(&self, datagrams: usize) {
+        self.stats.recv_batches.fetch_add(1, Ordering::Relaxed);
+        self.stats
+            .recv_batched_datagrams
+            .fetch_add(datagrams as u64, Ordering::Relaxed);
+    }
+
+    /// Record one batched send call that sent `datagrams` datagrams.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn note_send_batch(&self, datagrams: usize) {
+        self.stats.send_batches.fetch_add(1, Ordering::Relaxed);
+        self.stats
+            .send_batched_datagrams
+            .fetch_add(datagrams as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn note_source_port_acquired(&self, waited: bool) {
+        self.stats
+            .source_port_acquisitions
+            .fetch_add(1, Ordering::Relaxed);
+        if waited {
+            self.stats.source_port_waits.fetch_add(1, Ordering::Relaxed);
         }
     }
 
+    pub(crate) fn note_source_port_released(&self) {
+        self.stats
+            .source_port_releases
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
     fn remove_owned(&self, request_id: i32, owner: &Arc<RegistrationOwner>) {
         let mut pending = self.shard(request_id).pending.lock().unwrap();
         let matches = match pending.get(&request_id) {
@@ -634,6 +817,15 @@ impl UdpCore {
         }
     }
 
+    /// Return whether `request_id` has a pending slot or alias.
+    pub(crate) fn is_pending(&self, request_id: i32) -> bool {
+        self.shard(request_id)
+            .pending
+            .lock()
+            .unwrap()
+            .contains_key(&request_id)
+    }
+
     #[cfg(test)]
     pub(crate) fn pending_counts(&self) -> (usize, usize) {
         self.shards.iter().fold((0, 0), |(slots, aliases), shard| {
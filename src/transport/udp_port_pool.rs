@@ -0,0 +1,198 @@
+//! Pre-bound UDP sockets leased to one request at a time.
+//!
+//! With a shared socket, an off-path attacker who can guess the request ID
+//! can forge a response. Sending each request from its own randomly chosen
+//! pooled socket adds the socket's ephemeral port to what must be guessed:
+//! the correlation core accepts a response only when it arrives on the port
+//! the request was sent from.
+//!
+//! Every pooled socket has a receive task that delivers into the transport's
+//! correlation core, tagged with the socket's local port. Late responses to a
+//! previous lease are discarded like any other unregistered datagram.
+
+use super::udp_core::UdpCore;
+use super::udp_error::{UdpRecvErrorBackoff, UdpRecvErrorClass, classify_udp_recv_error};
+use super::{SocketOptions, extract_request_id};
+use crate::error::{Error, Result};
+use crate::message_size::UDP_RECEIVE_BUFFER_SIZE;
+use crate::runtime::UdpSocket;
+use crate::util::{UdpBindOptions, bind_udp_socket_with};
+use bytes::Bytes;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+
+/// Largest pool accepted by
+/// [`UdpTransportBuilder::source_port_pool`](super::UdpTransportBuilder::source_port_pool).
+pub(super) const MAX_SOURCE_PORT_POOL: usize = 1024;
+
+struct PooledSocket {
+    socket: UdpSocket,
+    port: u16,
+    /// Set when the receive task stopped on a fatal error.
+    failed: AtomicBool,
+}
+
+/// Bounded pool of pre-bound source sockets.
+pub(super) struct SourcePortPool {
+    idle: Mutex<Vec<Arc<PooledSocket>>>,
+    available: Arc<Semaphore>,
+    core: Arc<UdpCore>,
+}
+
+impl SourcePortPool {
+    /// Bind `size` sockets on `bind_addr`'s IP with `socket_options` and start
+    /// their receive tasks.
+    ///
+    /// The tasks hold no reference to the pool; they stop when `shutdown`
+    /// fires.
+    pub(super) async fn bind(
+        bind_addr: SocketAddr,
+        socket_options: &SocketOptions,
+        size: usize,
+        core: Arc<UdpCore>,
+        shutdown: &CancellationToken,
+    ) -> std::io::Result<Arc<Self>> {
+        let options = UdpBindOptions {
+            reuse_address: true,
+            socket: socket_options.clone(),
+            ..UdpBindOptions::default()
+        };
+        let mut idle = Vec::with_capacity(size);
+        for _ in 0..size {
+            let socket = bind_udp_socket_with(SocketAddr::new(bind_addr.ip(), 0), &options).await?;
+            let port = socket.local_addr()?.port();
+            let pooled = Arc::new(PooledSocket {
+                socket,
+                port,
+                failed: AtomicBool::new(false),
+            });
+            crate::runtime::spawn(recv_pooled(
+                Arc::clone(&pooled),
+                Arc::clone(&core),
+                shutdown.clone(),
+            ));
+            idle.push(pooled);
+        }
+        Ok(Arc::new(Self {
+            idle: Mutex::new(idle),
+            available: Arc::new(Semaphore::new(size)),
+            core,
+        }))
+    }
+
+    /// Lease a random idle socket, waiting while every socket is in use.
+    pub(super) async fn acquire(self: &Arc<Self>) -> PortLease {
+        let (permit, waited) = match Arc::clone(&self.available).try_acquire_owned() {
+            Ok(permit) => (permit, false),
+            Err(_) => (
+                Arc::clone(&self.available)
+                    .acquire_owned()
+                    .await
+                    .expect("source port semaphore is never closed"),
+                true,
+            ),
+        };
+        let socket = {
+            let mut idle = self.idle.lock().unwrap();
+            // A predictable reuse order would let an attacker follow the pool.
+            let index = getrandom::u32().map_or(0, |value| value as usize % idle.len());
+            idle.swap_remove(index)
+        };
+        self.core.note_source_port_acquired(waited);
+        PortLease {
+            pool: Arc::clone(self),
+            socket,
+            permit: Some(permit),
+        }
+    }
+}
+
+/// Exclusive use of one pooled socket, returned to the pool on drop.
+pub(super) struct PortLease {
+    pool: Arc<SourcePortPool>,
+    socket: Arc<PooledSocket>,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl PortLease {
+    /// Local port responses must arrive on.
+    pub(super) fn port(&self) -> u16 {
+        self.socket.port
+    }
+
+    /// Send one datagram from the leased socket.
+    pub(super) async fn send(&self, data: &[u8], target: SocketAddr) -> Result<()> {
+        self.socket
+            .socket
+            .send_to(data, target)
+            .await
+            .map_err(|source| Error::Network { target, source }.boxed())?;
+        Ok(())
+    }
+}
+
+impl Drop for PortLease {
+    fn drop(&mut self) {
+        self.pool.core.note_source_port_released();
+        let permit = self.permit.take().expect("permit held until drop");
+        if self.socket.failed.load(Ordering::Acquire) {
+            // Retire the socket: shrink the pool instead of handing out a
+            // port nobody is listening on.
+            permit.forget();
+            return;
+        }
+        self.pool
+            .idle
+            .lock()
+            .unwrap()
+            .push(Arc::clone(&self.socket));
+        drop(permit);
+    }
+}
+
+async fn recv_pooled(pooled: Arc<PooledSocket>, core: Arc<UdpCore>, shutdown: CancellationToken) {
+    let mut buf = vec![0u8; UDP_RECEIVE_BUFFER_SIZE];
+    let mut recv_error_backoff = UdpRecvErrorBackoff::default();
+    loop {
+        tokio::select! {
+            biased;
+
+            () = shutdown.cancelled() => break,
+
+            result = pooled.socket.recv_from(&mut buf) => match result {
+                Ok((len, source)) => {
+                    recv_error_backoff.reset();
+                    let data = Bytes::copy_from_slice(&buf[..len]);
+                    if let Some(request_id) = extract_request_id(&data) {
+                        if !core.deliver_on(request_id, data, source, Some(pooled.port)) {
+                            tracing::debug!(target: "async_snmp::transport", { snmp.request_id = request_id, snmp.source = %source, snmp.local_port = pooled.port }, "response for unknown request on pooled source port");
+                        }
+                    } else {
+                        core.note_malformed();
+                        tracing::debug!(target: "async_snmp::transport", { snmp.source = %source, snmp.bytes = len }, "malformed response (no request_id)");
+                    }
+                }
+                Err(e) => match classify_udp_recv_error(&e) {
+                    UdpRecvErrorClass::DatagramLocal => recv_error_backoff.reset(),
+                    UdpRecvErrorClass::Transient => {
+                        let delay = recv_error_backoff.advance();
+                        tracing::debug!(target: "async_snmp::transport", { error = %e, backoff = ?delay, snmp.local_port = pooled.port }, "transient UDP recv error on pooled source port");
+                        tokio::select! {
+                            biased;
+                            () = shutdown.cancelled() => break,
+                            () = crate::runtime::sleep(delay) => {}
+                        }
+                    }
+                    UdpRecvErrorClass::Fatal => {
+                        tracing::error!(target: "async_snmp::transport", { error = %e, snmp.local_port = pooled.port }, "fatal UDP recv error; retiring pooled source port");
+                        pooled.failed.store(true, Ordering::Release);
+                        break;
+                    }
+                },
+            },
+        }
+    }
+}
@@ -0,0 +1,466 @@
+//! UDP transport sharded across several sockets and receive tasks.
+//!
+//! A single [`UdpTransport`] drains its socket from one task, which limits a
+//! many-core poller to one core's worth of receive work. [`ShardedUdpTransport`]
+//! opens N independent [`UdpTransport`] shards, each with its own socket,
+//! receive task, and correlation state, and assigns every target to one shard
+//! by hashing its address. Handles are ordinary [`UdpHandle`]s.
+//!
+//! # Shared port
+//!
+//! By default each shard binds its own ephemeral port. With
+//! [`reuse_port`](ShardedUdpTransportBuilder::reuse_port) every shard binds
+//! the same port with `SO_REUSEPORT`, so agents see one source port. The kernel
+//! then spreads incoming datagrams across the shard sockets by its own hash,
+//! which does not match the target assignment, so each receive task hands a
+//! response to the shard that registered its request ID.
+
+use super::normalize_udp_target;
+use super::udp::{UdpControl, UdpHandle, UdpStats, UdpTransport, UdpTransportBuilder};
+use super::udp_core::UdpCore;
+use crate::error::{Error, Result};
+use bytes::Bytes;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Largest shard count accepted by [`ShardedUdpTransportBuilder::shards`].
+const MAX_SHARDS: usize = 1024;
+
+/// Pick the shard serving `target`.
+///
+/// Used both to place handles and to route responses, so it must hash the
+/// normalized socket address exactly as the receive path observes it.
+fn shard_index(target: SocketAddr, shards: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    target.hash(&mut hasher);
+    (hasher.finish() % shards as u64) as usize
+}
+
+/// Cross-shard response delivery for shards sharing one port.
+pub(super) struct ShardRouting {
+    cores: Box<[Arc<UdpCore>]>,
+}
+
+impl ShardRouting {
+    /// Deliver a response received by any shard to the shard that owns it.
+    ///
+    /// Responses normally come from the address the request was sent to, so
+    /// the source's shard is tried first. A response from another address
+    /// (a multihomed agent) falls back to whichever shard has the request ID
+    /// pending.
+    pub(super) fn deliver(&self, request_id: i32, data: Bytes, source: SocketAddr) -> bool {
+        let preferred = &self.cores[shard_index(source, self.cores.len())];
+        if !preferred.is_pending(request_id)
+            && let Some(owner) = self.cores.iter().find(|core| core.is_pending(request_id))
+        {
+            return owner.deliver(request_id, data, source);
+        }
+        preferred.deliver(request_id, data, source)
+    }
+}
+
+/// UDP transport spreading targets across several sockets.
+///
+/// Each shard is a complete [`UdpTransport`]. A target is always served by the
+/// same shard, so handles behave exactly like handles on a single transport,
+/// including [`UdpHandle::strict_source`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::{Auth, Client};
+/// use async_snmp::transport::ShardedUdpTransport;
+///
+/// # async fn example() -> async_snmp::Result<()> {
+/// let transport = ShardedUdpTransport::builder()
+///     .shards(4)
+///     .reuse_port(true)
+///     .build()
+///     .await?;
+///
+/// let client = Client::builder("192.168.1.1:161", Auth::v2c("public"))
+///     .build_with_sharded(&transport)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ShardedUdpTransport {
+    shards: Arc<[UdpTransport]>,
+}
+
+impl ShardedUdpTransport {
+    /// Create a builder for custom configuration.
+    #[must_use]
+    pub fn builder() -> ShardedUdpTransportBuilder {
+        ShardedUdpTransportBuilder::new()
+    }
+
+    /// Create a handle for a specific target on the target's shard.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] when the target's address family cannot be
+    /// represented by the shard sockets; see [`UdpTransport::handle`].
+    pub fn handle(&self, target: SocketAddr) -> Result<UdpHandle> {
+        self.shard_for(target)?.handle(target)
+    }
+
+    /// Return the shard that serves `target`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] when the target's address family cannot be
+    /// represented by the shard sockets.
+    pub fn shard_for(&self, target: SocketAddr) -> Result<&UdpTransport> {
+        let target = normalize_udp_target(self.shards[0].local_addr(), target)?;
+        Ok(&self.shards[shard_index(target, self.shards.len())])
+    }
+
+    /// Return every shard, in shard order.
+    #[must_use]
+    pub fn shards(&self) -> &[UdpTransport] {
+        &self.shards
+    }
+
+    /// Return the local bind address of every shard, in shard order.
+    ///
+    /// With [`reuse_port`](ShardedUdpTransportBuilder::reuse_port) all
+    /// addresses are equal.
+    #[must_use]
+    pub fn local_addrs(&self) -> Vec<SocketAddr> {
+        self.shards.iter().map(UdpTransport::local_addr).collect()
+    }
+
+    /// Snapshot statistics summed across all shards.
+    #[must_use]
+    pub fn stats(&self) -> UdpStats {
+        self.shards.iter().map(UdpTransport::stats).sum()
+    }
+
+    /// Snapshot statistics for each shard, in shard order.
+    ///
+    /// Correlation counters are attributed to the shard that owns the request,
+    /// even when another shard's socket received the datagram. Batch counters
+    /// describe each shard's own socket.
+    #[must_use]
+    pub fn shard_stats(&self) -> Vec<UdpStats> {
+        self.shards.iter().map(UdpTransport::stats).collect()
+    }
+
+    /// Acquire lifecycle authority over every shard.
+    #[must_use]
+    pub fn control(&self) -> ShardedUdpControl {
+        ShardedUdpControl {
+            controls: self.shards.iter().map(UdpTransport::control).collect(),
+        }
+    }
+}
+
+/// Lifecycle authority over every shard of a [`ShardedUdpTransport`].
+///
+/// Behaves like [`UdpControl`] applied to each shard.
+#[derive(Clone)]
+pub struct ShardedUdpControl {
+    controls: Arc<[UdpControl]>,
+}
+
+impl ShardedUdpControl {
+    /// Snapshot statistics summed across all shards.
+    #[must_use]
+    pub fn stats(&self) -> UdpStats {
+        self.controls.iter().map(UdpControl::stats).sum()
+    }
+
+    /// Return whether shutdown has been requested for every shard.
+    #[must_use]
+    pub fn is_shutdown(&self) -> bool {
+        self.controls.iter().all(UdpControl::is_shutdown)
+    }
+
+    /// Irreversibly shut down every shard.
+    ///
+    /// See [`UdpControl::shutdown`] for the guarantees made per shard.
+    pub async fn shutdown(&self) {
+        futures_util::future::join_all(self.controls.iter().map(UdpControl::shutdown)).await;
+    }
+}
+
+/// Builder for [`ShardedUdpTransport`].
+pub struct ShardedUdpTransportBuilder {
+    transport: UdpTransportBuilder,
+    shards: usize,
+    reuse_port: bool,
+}
+
+impl ShardedUdpTransportBuilder {
+    /// Create a builder with the default settings.
+    ///
+    /// Defaults to one shard per available CPU, separate ports, and the
+    /// [`UdpTransportBuilder`] defaults for each shard.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            transport: UdpTransportBuilder::new(),
+            shards: std::thread::available_parallelism().map_or(1, usize::from),
+            reuse_port: false,
+        }
+    }
+
+    /// Set the number of shards (default: available parallelism).
+    #[must_use]
+    pub fn shards(mut self, shards: usize) -> Self {
+        self.shards = shards;
+        self
+    }
+
+    /// Bind every shard to the same port with `SO_REUSEPORT` (default: false).
+    ///
+    /// When the bind address has port 0, the first shard picks an ephemeral
+    /// port and the remaining shards join it. `SO_REUSEPORT` lets any other
+    /// socket of the same user bind the port too, so only enable it where
+    /// that is acceptable. Not available on Windows.
+    #[must_use]
+    pub fn reuse_port(mut self, reuse_port: bool) -> Self {
+        self.reuse_port = reuse_port;
+        self
+    }
+
+    /// Set the local bind address of every shard.
+    ///
+    /// Shorthand for configuring [`transport`](Self::transport) with
+    /// [`UdpTransportBuilder::bind`].
+    #[must_use]
+    pub fn bind(mut self, addr: impl AsRef<str>) -> Self {
+        self.transport = self.transport.bind(addr);
+        self
+    }
+
+    /// Set the configuration applied to every shard.
+    ///
+    /// Message sizes, buffer sizes, source mismatch warnings, and batched I/O
+    /// all apply per shard.
+    #[must_use]
+    pub fn transport(mut self, transport: UdpTransportBuilder) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Build the transport, binding every shard.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] for a shard count outside `1..=1024`, or for
+    /// several shards on a fixed port without
+    /// [`reuse_port`](Self::reuse_port). Returns [`Error::Network`] when a
+    /// shard cannot bind, including when `SO_REUSEPORT` is unsupported.
+    pub async fn build(self) -> Result<ShardedUdpTransport> {
+        if !(1..=MAX_SHARDS).contains(&self.shards) {
+            return Err(Error::Config(
+                format!(
+                    "UDP shard count {} must be between 1 and {MAX_SHARDS}",
+                    self.shards
+                )
+                .into(),
+            )
+            .boxed());
+        }
+        let bind_addr = self.transport.parsed_bind_addr()?;
+        if self.shards > 1 && bind_addr.port() != 0 && !self.reuse_port {
+            return Err(Error::Config(
+                format!(
+                    "binding {} UDP shards to port {} requires reuse_port",
+                    self.shards,
+                    bind_addr.port()
+                )
+                .into(),
+            )
+            .boxed());
+        }
+
+        let cores: Vec<Arc<UdpCore>> = (0..self.shards).map(|_| Arc::new(UdpCore::new())).collect();
+        let routing = self.reuse_port.then(|| {
+            Arc::new(ShardRouting {
+                cores: cores.clone().into_boxed_slice(),
+            })
+        });
+
+        let mut shards = Vec::with_capacity(self.shards);
+        let mut shared_addr = self.reuse_port.then_some(bind_addr);
+        for core in cores {
+            let shard = self
+                .transport
+                .clone()
+                .build_shard(shared_addr, core, routing.clone())
+                .await?;
+            if self.reuse_port {
+                shared_addr = Some(shard.local_addr());
+            }
+            shards.push(shard);
+        }
+
+        tracing::debug!(target: "async_snmp::transport", { snmp.shards = self.shards, snmp.reuse_port = self.reuse_port }, "sharded UDP transport bound");
+
+        Ok(ShardedUdpTransport {
+            shards: shards.into(),
+        })
+    }
+}
+
+impl Default for ShardedUdpTransportBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{Candidate, RequestRegistration, Transport};
+    use std::time::Duration;
+    use tokio::net::UdpSocket;
+
+    /// Spawn an agent answering each one-byte request with a v3 header whose
+    /// msgID is that byte.
+    async fn spawn_agent() -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut data = [0; 64];
+            while let Ok((_, source)) = socket.recv_from(&mut data).await {
+                let response = [
+                    0x30, 0x08, 0x02, 0x01, 0x03, 0x30, 0x03, 0x02, 0x01, data[0],
+                ];
+                let _ = socket.send_to(&response, source).await;
+            }
+        });
+        addr
+    }
+
+    async fn request(handle: &UdpHandle, msg_id: u8) -> Result<SocketAddr> {
+        handle
+            .request_with(
+                &[msg_id],
+                RequestRegistration::v3(
+                    i32::from(msg_id),
+                    tokio::time::Instant::now() + Duration::from_secs(5),
+                ),
+                |_, source| Ok(Candidate::Accept(source)),
+            )
+            .await
+    }
+
+    async fn exchange_with_agents(transport: &ShardedUdpTransport, agents: u8) {
+        let mut pending = Vec::new();
+        for msg_id in 1..=agents {
+            let agent = spawn_agent().await;
+            let handle = transport.handle(agent).unwrap().strict_source(true);
+            pending.push(tokio::spawn(async move {
+                assert_eq!(request(&handle, msg_id).await.unwrap(), agent);
+            }));
+        }
+        for task in pending {
+            task.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn handles_are_placed_on_the_target_shard() {
+        let transport = ShardedUdpTransport::builder()
+            .bind("127.0.0.1:0")
+            .shards(4)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(transport.shards().len(), 4);
+        let addrs = transport.local_addrs();
+        assert_eq!(
+            addrs.iter().collect::<std::collections::HashSet<_>>().len(),
+            4
+        );
+
+        for port in 1000..1032 {
+            let target = SocketAddr::from(([127, 0, 0, 1], port));
+            let handle = transport.handle(target).unwrap();
+            assert_eq!(
+                handle.local_addr(),
+                transport.shard_for(target).unwrap().local_addr()
+            );
+            assert_eq!(
+                transport.handle(target).unwrap().local_addr(),
+                handle.local_addr()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn separate_port_shards_correlate_and_aggregate_stats() {
+        let transport = ShardedUdpTransport::builder()
+            .bind("127.0.0.1:0")
+            .shards(3)
+            .build()
+            .await
+            .unwrap();
+        exchange_with_agents(&transport, 12).await;
+
+        let per_shard = transport.shard_stats();
+        assert_eq!(per_shard.len(), 3);
+        assert_eq!(transport.stats(), per_shard.into_iter().sum());
+        assert_eq!(transport.stats().correlated_datagrams, 12);
+        assert_eq!(transport.stats().discarded_datagrams, 0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn reuse_port_shards_route_responses_to_owning_shard() {
+        let transport = ShardedUdpTransport::builder()
+            .bind("127.0.0.1:0")
+            .shards(4)
+            .reuse_port(true)
+            .build()
+            .await
+            .unwrap();
+        let addrs = transport.local_addrs();
+        assert!(addrs.iter().all(|addr| *addr == addrs[0]));
+
+        exchange_with_agents(&transport, 32).await;
+
+        let stats = transport.stats();
+        assert_eq!(stats.correlated_datagrams, 32);
+        assert_eq!(stats.discarded_datagrams, 0);
+    }
+
+    #[tokio::test]
+    async fn invalid_shard_configuration_is_rejected() {
+        for builder in [
+            ShardedUdpTransport::builder().shards(0),
+            ShardedUdpTransport::builder()
+                .bind("127.0.0.1:16100")
+                .shards(2),
+        ] {
+            let error = builder.build().await.err().expect("must fail");
+            assert!(matches!(*error, Error::Config(_)), "{error}");
+        }
+    }
+
+    #[tokio::test]
+    async fn control_shuts_down_every_shard() {
+        let transport = ShardedUdpTransport::builder()
+            .bind("127.0.0.1:0")
+            .shards(2)
+            .build()
+            .await
+            .unwrap();
+        let control = transport.control();
+        control.shutdown().await;
+        assert!(control.is_shutdown());
+        for shard in transport.shards() {
+            assert!(shard.control().is_shutdown());
+        }
+        let error = request(&transport.handle(spawn_agent().await).unwrap(), 1)
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::Closed { .. }));
+    }
+}
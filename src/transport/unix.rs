@@ -0,0 +1,726 @@
+//! Unix domain socket transport.
+//!
+//! Stream sockets carry BER-framed messages exactly like [`TcpTransport`](super::TcpTransport).
+//! Datagram sockets carry one message per datagram and correlate responses by
+//! request ID like [`UdpHandle`](super::UdpHandle).
+//!
+//! Unix endpoints have no IP address. Everywhere the [`Transport`] trait or an
+//! agent reports a [`SocketAddr`] for a Unix peer, [`UNIX_SOCKET_ADDR`] is used.
+
+use super::tcp::{TcpTransportInner, read_ber_message};
+use super::udp_core::UdpCore;
+use super::{Candidate, PeerCredentials, RequestRegistration, Transport, extract_request_id};
+use crate::error::{Error, Result};
+use crate::message_size::{ReceiveLimits, UDP_RECEIVE_BUFFER_SIZE};
+use bytes::Bytes;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::unix::OwnedWriteHalf;
+use tokio::net::{UnixDatagram, UnixListener, UnixStream};
+use tokio::sync::mpsc;
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+/// Placeholder address reported for Unix-domain endpoints.
+///
+/// Returned by [`Transport::peer_addr`] and [`Transport::local_addr`] for
+/// [`UnixTransport`], and used as the request source for agent requests and
+/// notifications received on a Unix socket.
+pub const UNIX_SOCKET_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+
+/// Default incoming and outgoing limit for Unix stream sockets.
+const DEFAULT_STREAM_MESSAGE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Socket type of a Unix-domain endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnixSocketKind {
+    /// `SOCK_STREAM`: BER-framed messages over a connection.
+    Stream,
+    /// `SOCK_DGRAM`: one message per datagram.
+    Datagram,
+}
+
+/// Builder for [`UnixTransport`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::transport::{UnixSocketKind, UnixTransport};
+/// use async_snmp::{Auth, ClientBuilder};
+///
+/// # async fn example() -> async_snmp::Result<()> {
+/// let transport = UnixTransport::builder()
+///     .max_message_size(65_535)
+///     .connect("/run/snmpd.sock", UnixSocketKind::Stream)
+///     .await?;
+///
+/// let client = ClientBuilder::new(Auth::v2c("public"))
+///     .build_with_transport(transport)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UnixTransportBuilder {
+    max_message_size: Option<usize>,
+    send_capacity: Option<usize>,
+    local_path: Option<PathBuf>,
+}
+
+impl UnixTransportBuilder {
+    /// Create a builder with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum total encoded size for incoming messages.
+    ///
+    /// Default: 10MB for stream sockets, 65535 bytes for datagram sockets.
+    #[must_use]
+    pub fn max_message_size(mut self, size: usize) -> Self {
+        self.max_message_size = Some(size);
+        self
+    }
+
+    /// Set the maximum exact encoded size for outgoing messages.
+    ///
+    /// Default: the same as the incoming limit.
+    #[must_use]
+    pub fn send_capacity(mut self, size: usize) -> Self {
+        self.send_capacity = Some(size);
+        self
+    }
+
+    /// Bind datagram sockets to this local path instead of a generated one.
+    ///
+    /// A datagram socket must be bound for the agent to address its response.
+    /// By default a uniquely named socket is created in the system temporary
+    /// directory. Either way the socket file is removed when the transport is
+    /// dropped. Ignored for stream sockets.
+    #[must_use]
+    pub fn local_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.local_path = Some(path.into());
+        self
+    }
+
+    /// Connect to the socket at `path`.
+    pub async fn connect(
+        self,
+        path: impl AsRef<Path>,
+        kind: UnixSocketKind,
+    ) -> Result<UnixTransport> {
+        let path = path.as_ref();
+        let default_size = match kind {
+            UnixSocketKind::Stream => DEFAULT_STREAM_MESSAGE_SIZE,
+            UnixSocketKind::Datagram => UDP_RECEIVE_BUFFER_SIZE,
+        };
+        let max_message_size = self.max_message_size.unwrap_or(default_size);
+        let receive_limits = ReceiveLimits::tcp(max_message_size)
+            .map_err(|error| Error::Config(error.to_string().into()).boxed())?;
+        let send_capacity = self.send_capacity.unwrap_or(max_message_size);
+        let network_error = |source| {
+            Error::Network {
+                target: UNIX_SOCKET_ADDR,
+                source,
+            }
+            .boxed()
+        };
+
+        let inner = match kind {
+            UnixSocketKind::Stream => {
+                let stream = UnixStream::connect(path).await.map_err(network_error)?;
+                UnixTransportInner::Stream(Arc::new(TcpTransportInner::new(
+                    stream,
+                    UNIX_SOCKET_ADDR,
+                    UNIX_SOCKET_ADDR,
+                    receive_limits,
+                    send_capacity,
+                )))
+            }
+            UnixSocketKind::Datagram => {
+                let local_path = self.local_path.unwrap_or_else(temporary_socket_path);
+                let socket = UnixDatagram::bind(&local_path).map_err(network_error)?;
+                // Own the file from here on so a failed connect still unlinks it.
+                let local_path = SocketPathGuard(local_path);
+                socket.connect(path).map_err(network_error)?;
+                UnixTransportInner::Datagram(Arc::new(UnixDatagramInner::start(
+                    socket,
+                    local_path,
+                    receive_limits,
+                    send_capacity,
+                )))
+            }
+        };
+
+        tracing::debug!(target: "async_snmp::transport::unix", { path = %path.display(), ?kind }, "Unix transport connected");
+        Ok(UnixTransport {
+            inner,
+            path: Arc::from(path),
+        })
+    }
+}
+
+/// Unix domain socket transport for a single agent.
+///
+/// Stream sockets behave like [`TcpTransport`](super::TcpTransport): requests
+/// are serialized over the connection and the client does not retry
+/// ([`is_reliable()`](Transport::is_reliable) returns `true`). Datagram
+/// sockets behave like [`UdpHandle`](super::UdpHandle): concurrent requests
+/// are correlated by request ID and timeouts are retried.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::transport::{UnixSocketKind, UnixTransport};
+/// use async_snmp::{Auth, ClientBuilder, oid};
+///
+/// # async fn example() -> async_snmp::Result<()> {
+/// let transport = UnixTransport::connect("/run/snmpd.sock", UnixSocketKind::Datagram).await?;
+/// let client = ClientBuilder::new(Auth::v2c("public"))
+///     .build_with_transport(transport)?;
+/// let descr = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct UnixTransport {
+    inner: UnixTransportInner,
+    path: Arc<Path>,
+}
+
+#[derive(Clone)]
+enum UnixTransportInner {
+    Stream(Arc<TcpTransportInner<UnixStream>>),
+    Datagram(Arc<UnixDatagramInner>),
+}
+
+struct UnixDatagramInner {
+    socket: Arc<UnixDatagram>,
+    core: Arc<UdpCore>,
+    receive_limits: ReceiveLimits,
+    send_capacity: usize,
+    // Cancels the recv task when the last transport clone drops. The task
+    // holds no reference to this struct.
+    _shutdown_guard: DropGuard,
+    // Declared last so the socket file is unlinked after the task is told to stop.
+    _local_path: SocketPathGuard,
+}
+
+/// Removes a socket file created by this crate when dropped.
+struct SocketPathGuard(PathBuf);
+
+impl Drop for SocketPathGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn temporary_socket_path() -> PathBuf {
+    let mut suffix = [0u8; 8];
+    // A clash only makes bind fail, so a weak fallback is acceptable.
+    if getrandom::fill(&mut suffix).is_err() {
+        suffix = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default()
+            .to_ne_bytes();
+    }
+    std::env::temp_dir().join(format!(
+        "async-snmp-{}-{:016x}.sock",
+        std::process::id(),
+        u64::from_ne_bytes(suffix)
+    ))
+}
+
+impl UnixDatagramInner {
+    fn start(
+        socket: UnixDatagram,
+        local_path: SocketPathGuard,
+        receive_limits: ReceiveLimits,
+        send_capacity: usize,
+    ) -> Self {
+        let socket = Arc::new(socket);
+        let core = Arc::new(UdpCore::new());
+        let shutdown = CancellationToken::new();
+
+        let recv_socket = Arc::clone(&socket);
+        let recv_core = Arc::clone(&core);
+        let recv_shutdown = shutdown.clone();
+        let buffer_size = receive_limits.accepted();
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; buffer_size];
+            let mut cleanup_interval = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                tokio::select! {
+                    biased;
+
+                    () = recv_shutdown.cancelled() => break,
+
+                    _ = cleanup_interval.tick() => recv_core.cleanup_expired(),
+
+                    result = recv_socket.recv(&mut buf) => match result {
+                        Ok(len) => {
+                            let data = Bytes::copy_from_slice(&buf[..len]);
+                            if let Some(request_id) = extract_request_id(&data) {
+                                if !recv_core.deliver(request_id, data, UNIX_SOCKET_ADDR) {
+                                    tracing::debug!(target: "async_snmp::transport::unix", { snmp.request_id = request_id }, "response for unknown request");
+                                }
+                            } else {
+                                recv_core.note_malformed();
+                                tracing::debug!(target: "async_snmp::transport::unix", { snmp.bytes = len }, "malformed response (no request_id)");
+                            }
+                        }
+                        Err(error) => {
+                            tracing::debug!(target: "async_snmp::transport::unix", { %error }, "Unix datagram recv failed");
+                            break;
+                        }
+                    },
+                }
+            }
+            recv_core.close();
+        });
+
+        Self {
+            socket,
+            core,
+            receive_limits,
+            send_capacity,
+            _shutdown_guard: shutdown.drop_guard(),
+            _local_path: local_path,
+        }
+    }
+
+    async fn send(&self, data: &[u8]) -> Result<()> {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity)?;
+        tracing::trace!(target: "async_snmp::transport::unix", { snmp.bytes = data.len() }, "Unix datagram send");
+        self.socket.send(data).await.map_err(|source| {
+            Error::Network {
+                target: UNIX_SOCKET_ADDR,
+                source,
+            }
+            .boxed()
+        })?;
+        Ok(())
+    }
+
+    async fn request_with<T, F>(
+        &self,
+        data: &[u8],
+        registration: RequestRegistration,
+        validate: F,
+    ) -> Result<T>
+    where
+        F: FnMut(Bytes, SocketAddr) -> Result<Candidate<T>>,
+    {
+        crate::message_size::enforce_outbound_size(data.len(), self.send_capacity)?;
+        let registration = self.core.register(registration, UNIX_SOCKET_ADDR, false)?;
+        let deadline = registration.deadline();
+        if tokio::time::Instant::now() >= deadline {
+            return Err(registration.timeout_error(UNIX_SOCKET_ADDR));
+        }
+        tokio::select! {
+            biased;
+            () = tokio::time::sleep_until(deadline) => {
+                return Err(registration.timeout_error(UNIX_SOCKET_ADDR));
+            }
+            result = self.send(data) => result?,
+        }
+        registration.mark_sent();
+        self.core
+            .wait_for_response_with(&registration, UNIX_SOCKET_ADDR, validate)
+            .await
+    }
+}
+
+impl UnixTransport {
+    /// Connect to the socket at `path` with default settings.
+    pub async fn connect(path: impl AsRef<Path>, kind: UnixSocketKind) -> Result<Self> {
+        Self::builder().connect(path, kind).await
+    }
+
+    /// Create a builder for custom configuration.
+    #[must_use]
+    pub fn builder() -> UnixTransportBuilder {
+        UnixTransportBuilder::new()
+    }
+
+    /// Return the path of the agent socket.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return the socket type.
+    #[must_use]
+    pub fn kind(&self) -> UnixSocketKind {
+        match self.inner {
+            UnixTransportInner::Stream(_) => UnixSocketKind::Stream,
+            UnixTransportInner::Datagram(_) => UnixSocketKind::Datagram,
+        }
+    }
+}
+
+impl std::fmt::Debug for UnixTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnixTransport")
+            .field("path", &self.path)
+            .field("kind", &self.kind())
+            .finish()
+    }
+}
+
+impl Transport for UnixTransport {
+    async fn send(&self, data: &[u8]) -> Result<()> {
+        match &self.inner {
+            UnixTransportInner::Stream(inner) => inner.send(data).await,
+            UnixTransportInner::Datagram(inner) => inner.send(data).await,
+        }
+    }
+
+    async fn send_with_timeout(&self, data: &[u8], timeout: Duration) -> Result<()> {
+        match &self.inner {
+            UnixTransportInner::Stream(inner) => inner.send_with_timeout(data, timeout).await,
+            UnixTransportInner::Datagram(inner) => {
+                match tokio::time::timeout(timeout, inner.send(data)).await {
+                    Ok(result) => result,
+                    Err(_) => Err(Error::Timeout {
+                        target: UNIX_SOCKET_ADDR,
+                        elapsed: timeout,
+                        retries: 0,
+                    }
+                    .boxed()),
+                }
+            }
+        }
+    }
+
+    async fn request_with<T, F>(
+        &self,
+        data: &[u8],
+        registration: RequestRegistration,
+        validate: F,
+    ) -> Result<T>
+    where
+        T: Send,
+        F: FnMut(Bytes, SocketAddr) -> Result<Candidate<T>> + Send,
+    {
+        match &self.inner {
+            UnixTransportInner::Stream(inner) => {
+                inner.request_with(data, registration, validate).await
+            }
+            UnixTransportInner::Datagram(inner) => {
+                inner.request_with(data, registration, validate).await
+            }
+        }
+    }
+
+    fn peer_addr(&self) -> SocketAddr {
+        UNIX_SOCKET_ADDR
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        UNIX_SOCKET_ADDR
+    }
+
+    fn is_reliable(&self) -> bool {
+        matches!(self.inner, UnixTransportInner::Stream(_))
+    }
+
+    fn receive_limits(&self) -> ReceiveLimits {
+        match &self.inner {
+            UnixTransportInner::Stream(inner) => inner.receive_limits(),
+            UnixTransportInner::Datagram(inner) => inner.receive_limits,
+        }
+    }
+
+    fn send_capacity(&self) -> usize {
+        match &self.inner {
+            UnixTransportInner::Stream(inner) => inner.send_capacity(),
+            UnixTransportInner::Datagram(inner) => inner.send_capacity,
+        }
+    }
+}
+
+/// A message received on a Unix-domain listener.
+pub(crate) struct UnixInbound {
+    pub(crate) data: Bytes,
+    /// Read by the agent only; notification receivers do not report it.
+    #[cfg_attr(not(feature = "agent"), allow(dead_code))]
+    pub(crate) peer_credentials: Option<PeerCredentials>,
+    pub(crate) reply: UnixReply,
+}
+
+/// Return path for a message received on a Unix-domain listener.
+#[derive(Clone)]
+pub(crate) enum UnixReply {
+    Datagram {
+        socket: Arc<UnixDatagram>,
+        /// `None` when the sender's socket was unbound and cannot be answered.
+        peer: Option<PathBuf>,
+    },
+    Stream(Arc<tokio::sync::Mutex<OwnedWriteHalf>>),
+}
+
+impl UnixReply {
+    pub(crate) async fn send(&self, data: &[u8]) -> std::io::Result<()> {
+        match self {
+            Self::Datagram {
+                socket,
+                peer: Some(peer),
+            } => socket.send_to(data, peer).await.map(|_| ()),
+            Self::Datagram { peer: None, .. } => Err(std::io::Error::new(
+                std::io::ErrorKind::AddrNotAvailable,
+                "Unix datagram sender is not bound to a path",
+            )),
+            Self::Stream(writer) => writer.lock().await.write_all(data).await,
+        }
+    }
+}
+
+/// Bind `path` and forward each received message to `ingress`.
+///
+/// The socket is bound before returning so configuration errors surface at
+/// build time. Binding fails if `path` already exists; the listener does not
+/// remove files it did not create. The background task stops, and unlinks
+/// the socket file, when `cancel` fires or `ingress` is closed.
+pub(crate) fn spawn_unix_listener(
+    path: &Path,
+    kind: UnixSocketKind,
+    max_message_size: usize,
+    ingress: mpsc::Sender<UnixInbound>,
+    cancel: CancellationToken,
+) -> std::io::Result<()> {
+    match kind {
+        UnixSocketKind::Stream => {
+            let listener = UnixListener::bind(path)?;
+            let guard = SocketPathGuard(path.to_path_buf());
+            tokio::spawn(async move {
+                let _guard = guard;
+                loop {
+                    tokio::select! {
+                        () = cancel.cancelled() => break,
+                        () = ingress.closed() => break,
+                        accepted = listener.accept() => match accepted {
+                            Ok((stream, _)) => {
+                                tokio::spawn(serve_unix_stream(
+                                    stream,
+                                    max_message_size,
+                                    ingress.clone(),
+                                    cancel.child_token(),
+                                ));
+                            }
+                            Err(error) => {
+                                tracing::warn!(target: "async_snmp::transport::unix", { %error }, "Unix accept failed");
+                                tokio::time::sleep(Duration::from_millis(100)).await;
+                            }
+                        },
+                    }
+                }
+            });
+        }
+        UnixSocketKind::Datagram => {
+            let socket = Arc::new(UnixDatagram::bind(path)?);
+            let guard = SocketPathGuard(path.to_path_buf());
+            tokio::spawn(async move {
+                let _guard = guard;
+                let mut buf = vec![0u8; max_message_size];
+                loop {
+                    let (len, peer) = tokio::select! {
+                        () = cancel.cancelled() => break,
+                        () = ingress.closed() => break,
+                        received = socket.recv_from(&mut buf) => match received {
+                            Ok(received) => received,
+                            Err(error) => {
+                                tracing::warn!(target: "async_snmp::transport::unix", { %error }, "Unix datagram recv failed");
+                                tokio::time::sleep(Duration::from_millis(100)).await;
+                                continue;
+                            }
+                        },
+                    };
+                    let inbound = UnixInbound {
+                        data: Bytes::copy_from_slice(&buf[..len]),
+                        peer_credentials: None,
+                        reply: UnixReply::Datagram {
+                            socket: Arc::clone(&socket),
+                            peer: peer.as_pathname().map(Path::to_path_buf),
+                        },
+                    };
+                    if ingress.send(inbound).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    }
+    Ok(())
+}
+
+async fn serve_unix_stream(
+    stream: UnixStream,
+    max_message_size: usize,
+    ingress: mpsc::Sender<UnixInbound>,
+    cancel: CancellationToken,
+) {
+    let peer_credentials = stream.peer_cred().ok().map(|cred| PeerCredentials {
+        uid: cred.uid(),
+        gid: cred.gid(),
+        pid: cred.pid(),
+    });
+    let (mut reader, writer) = stream.into_split();
+    let writer = Arc::new(tokio::sync::Mutex::new(writer));
+    loop {
+        let data = tokio::select! {
+            () = cancel.cancelled() => break,
+            () = ingress.closed() => break,
+            read = read_ber_message(&mut reader, UNIX_SOCKET_ADDR, max_message_size) => match read {
+                Ok(data) => data,
+                // EOF, I/O failure, or lost framing all end the connection.
+                Err(error) => {
+                    tracing::debug!(target: "async_snmp::transport::unix", { %error }, "Unix stream connection closed");
+                    break;
+                }
+            },
+        };
+        let inbound = UnixInbound {
+            data,
+            peer_credentials,
+            reply: UnixReply::Stream(Arc::clone(&writer)),
+        };
+        if ingress.send(inbound).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::CommunityMessage;
+    use crate::pdu::Pdu;
+    use crate::{Auth, ClientBuilder, Community, Retry, Value, VarBind, oid};
+
+    fn socket_path(name: &str) -> PathBuf {
+        let path = temporary_socket_path();
+        path.with_file_name(format!(
+            "{name}-{}",
+            path.file_name().unwrap().to_string_lossy()
+        ))
+    }
+
+    /// Answer every request with a sysDescr response naming the socket kind.
+    async fn spawn_responder(path: &Path, kind: UnixSocketKind) -> CancellationToken {
+        let (tx, mut rx) = mpsc::channel(8);
+        let cancel = CancellationToken::new();
+        spawn_unix_listener(path, kind, UDP_RECEIVE_BUFFER_SIZE, tx, cancel.clone()).unwrap();
+        tokio::spawn(async move {
+            while let Some(inbound) = rx.recv().await {
+                let request = CommunityMessage::decode(inbound.data, Default::default())
+                    .unwrap()
+                    .value;
+                let request_id = request.pdu().standard().unwrap().request_id;
+                let response = CommunityMessage::v2c(
+                    request.community().clone(),
+                    Pdu::response(
+                        request_id,
+                        0,
+                        0,
+                        vec![VarBind::new(
+                            oid!(1, 3, 6, 1, 2, 1, 1, 1, 0),
+                            Value::OctetString(format!("{kind:?}").into()),
+                        )],
+                    ),
+                )
+                .unwrap();
+                inbound
+                    .reply
+                    .send(&response.encode().unwrap())
+                    .await
+                    .unwrap();
+            }
+        });
+        cancel
+    }
+
+    async fn round_trip(kind: UnixSocketKind) {
+        let path = socket_path("agent");
+        let _cancel = spawn_responder(&path, kind).await.drop_guard();
+        let transport = UnixTransport::connect(&path, kind).await.unwrap();
+        assert_eq!(transport.kind(), kind);
+        assert_eq!(transport.is_reliable(), kind == UnixSocketKind::Stream);
+        let client = ClientBuilder::new(Auth::v2c("public"))
+            .request_timeout(Duration::from_secs(2))
+            .retry(Retry::none())
+            .build_with_transport(transport)
+            .unwrap();
+
+        for _ in 0..2 {
+            let response = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).await.unwrap();
+            assert_eq!(
+                response.single().unwrap().value,
+                Value::OctetString(format!("{kind:?}").into())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_round_trip() {
+        round_trip(UnixSocketKind::Stream).await;
+    }
+
+    #[tokio::test]
+    async fn datagram_round_trip() {
+        round_trip(UnixSocketKind::Datagram).await;
+    }
+
+    #[tokio::test]
+    async fn datagram_client_unlinks_generated_path_on_drop() {
+        let agent = socket_path("agent");
+        let _cancel = spawn_responder(&agent, UnixSocketKind::Datagram)
+            .await
+            .drop_guard();
+        let local = socket_path("client");
+        let transport = UnixTransport::builder()
+            .local_path(&local)
+            .connect(&agent, UnixSocketKind::Datagram)
+            .await
+            .unwrap();
+        assert!(local.exists());
+        drop(transport);
+        assert!(!local.exists());
+    }
+
+    #[tokio::test]
+    async fn stream_listener_reports_peer_credentials() {
+        let path = socket_path("creds");
+        let (tx, mut rx) = mpsc::channel(1);
+        let cancel = CancellationToken::new();
+        spawn_unix_listener(&path, UnixSocketKind::Stream, 1024, tx, cancel.clone()).unwrap();
+        let transport = UnixTransport::connect(&path, UnixSocketKind::Stream)
+            .await
+            .unwrap();
+        let message = CommunityMessage::v2c(
+            Community::from("public"),
+            Pdu::get_request(1, &[oid!(1, 3, 6, 1)]),
+        )
+        .unwrap();
+        transport.send(&message.encode().unwrap()).await.unwrap();
+
+        let inbound = rx.recv().await.unwrap();
+        let credentials = inbound.peer_credentials.unwrap();
+        // The listener's socket file is owned by this process's effective uid.
+        let uid = std::os::unix::fs::MetadataExt::uid(&std::fs::metadata(&path).unwrap());
+        assert_eq!(credentials.uid, uid);
+        assert_eq!(credentials.pid, Some(std::process::id() as i32));
+
+        cancel.cancel();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while path.exists() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("listener unlinks its socket on cancel");
+    }
+}
@@ -4,7 +4,7 @@
 //! Windows, FreeBSD, NetBSD, OpenBSD, and DragonFly BSD when the socket accepts
 //! the relevant option. Other platforms do not provide an implementation here.
 //! If metadata setup is unavailable or fails, construction emits a warning and
-//! receive and send use Tokio's ordinary UDP operations, allowing the kernel to
+//! receive and send use ordinary UDP operations, allowing the kernel to
 //! select the response source address.
 
 use std::io;
@@ -20,7 +20,7 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 ))]
 use std::sync::Arc;
 
-use tokio::net::UdpSocket;
+use crate::runtime::{Interest, UdpSocket};
 
 /// Metadata needed to reply to a received datagram.
 #[derive(Clone, Copy, Debug)]
@@ -395,7 +395,7 @@ impl UdpResponder {
 
         loop {
             socket.readable().await?;
-            match socket.try_io(tokio::io::Interest::READABLE, || {
+            match socket.try_io(Interest::Readable, || {
                 platform::recv_with_packet_info(socket, buf)
             }) {
                 Ok(received) => return Ok(received),
@@ -608,7 +608,7 @@ impl UdpResponder {
     ) -> io::Result<()> {
         loop {
             socket.writable().await?;
-            match socket.try_io(tokio::io::Interest::WRITABLE, || {
+            match socket.try_io(Interest::Writable, || {
                 platform::send_with_source(socket, data, destination, source, unspecified_source)
             }) {
                 Ok(()) => return Ok(()),
@@ -2473,7 +2473,7 @@ mod tests {
     async fn broadcast_reply_uses_received_interface_without_duplicates() {
         let server = UdpSocket::bind("0.0.0.0:0").await.unwrap();
         let responder = UdpResponder::new(&server);
-        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
         client.set_broadcast(true).unwrap();
         let destination =
             SocketAddr::from(([127, 255, 255, 255], server.local_addr().unwrap().port()));
@@ -5,9 +5,10 @@ use std::io;
 use std::net::SocketAddr;
 
 use crate::Community;
+use crate::runtime::UdpSocket;
+use crate::transport::SocketOptions;
 use bytes::Bytes;
 use socket2::{Domain, Protocol, Socket, Type};
-use tokio::net::UdpSocket;
 
 use crate::error::{Error, Result};
 use crate::v3::{AuthoritativeEngine, UsmUser};
@@ -21,12 +22,13 @@ pub(crate) enum EmptyCommunityPolicy {
 }
 
 /// Compare a community against every configured value without early return.
-pub(crate) fn community_matches(
-    configured: &[Community],
+pub(crate) fn community_matches<'a>(
+    configured: impl IntoIterator<Item = &'a Community>,
     community: &[u8],
     empty_policy: EmptyCommunityPolicy,
 ) -> bool {
-    if configured.is_empty() {
+    let mut configured = configured.into_iter().peekable();
+    if configured.peek().is_none() {
         return matches!(empty_policy, EmptyCommunityPolicy::Allow);
     }
 
@@ -55,6 +57,11 @@ pub(crate) struct ValidatedAuthoritativeUsm {
 }
 
 impl ValidatedAuthoritativeUsm {
+    /// Return the validated authoritative engine, if one was configured.
+    pub(crate) fn authoritative_engine(&self) -> Option<&AuthoritativeEngine> {
+        self.authoritative_engine.as_ref()
+    }
+
     /// Finish preparation, generating an engine ID only when none was configured.
     pub(crate) fn prepare(
         self,
@@ -175,12 +182,49 @@ fn validate_authoritative_usm_with(
 ///
 /// # Returns
 ///
-/// A tokio `UdpSocket` bound to the specified address.
+/// A `UdpSocket` bound to the specified address and registered with the
+/// selected async runtime.
 pub(crate) async fn bind_udp_socket(
     addr: SocketAddr,
     recv_buffer_size: Option<usize>,
     send_buffer_size: Option<usize>,
     reuse_address: bool,
+) -> io::Result<UdpSocket> {
+    bind_udp_socket_with(
+        addr,
+        &UdpBindOptions {
+            recv_buffer_size,
+            send_buffer_size,
+            reuse_address,
+            ..UdpBindOptions::default()
+        },
+    )
+    .await
+}
+
+/// Socket options applied by [`bind_udp_socket_with`] before binding.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UdpBindOptions {
+    /// Receive buffer size (`SO_RCVBUF`).
+    pub(crate) recv_buffer_size: Option<usize>,
+    /// Send buffer size (`SO_SNDBUF`).
+    pub(crate) send_buffer_size: Option<usize>,
+    /// Set `SO_REUSEADDR`.
+    pub(crate) reuse_address: bool,
+    /// Set `SO_REUSEPORT` so several sockets can bind the same port.
+    pub(crate) reuse_port: bool,
+    /// Interface and IP header options.
+    pub(crate) socket: SocketOptions,
+}
+
+/// Create and bind a UDP socket with the given options.
+///
+/// See [`bind_udp_socket`] for address-family handling. Fails with
+/// [`io::ErrorKind::Unsupported`] when `reuse_port` is requested on a platform
+/// without `SO_REUSEPORT`.
+pub(crate) async fn bind_udp_socket_with(
+    addr: SocketAddr,
+    options: &UdpBindOptions,
 ) -> io::Result<UdpSocket> {
     let domain = if addr.is_ipv6() {
         Domain::IPV6
@@ -200,19 +244,23 @@ pub(crate) async fn bind_udp_socket(
     // Only enable for client sockets (ephemeral ports) where it helps with quick
     // restarts. Agent and notification listener sockets should not set this to
     // prevent port hijacking.
-    if reuse_address {
+    if options.reuse_address {
         socket.set_reuse_address(true)?;
     }
+    if options.reuse_port {
+        set_reuse_port(&socket)?;
+    }
+    apply_socket_options(&socket, addr.is_ipv6(), &options.socket)?;
 
     // Set buffer sizes if requested (kernel may cap at rmem_max/wmem_max)
-    if let Some(size) = recv_buffer_size {
+    if let Some(size) = options.recv_buffer_size {
         let _ = socket.set_recv_buffer_size(size);
     }
-    if let Some(size) = send_buffer_size {
+    if let Some(size) = options.send_buffer_size {
         let _ = socket.set_send_buffer_size(size);
     }
 
-    // Set non-blocking before converting to tokio socket
+    // Set non-blocking before registering with the async runtime
     socket.set_nonblocking(true)?;
 
     socket.bind(&addr.into())?;
@@ -220,6 +268,169 @@ pub(crate) async fn bind_udp_socket(
     UdpSocket::from_std(socket.into())
 }
 
+/// Create an unconnected, non-blocking TCP socket for `target` with `options`
+/// applied.
+///
+/// When a source address is configured the socket is bound to it on an
+/// ephemeral port. The socket is returned as a `std::net::TcpStream` for
+/// [`crate::runtime::connect_tcp`].
+pub(crate) fn tcp_socket(
+    target: SocketAddr,
+    options: &SocketOptions,
+) -> io::Result<std::net::TcpStream> {
+    let domain = if target.is_ipv6() {
+        Domain::IPV6
+    } else {
+        Domain::IPV4
+    };
+    let socket = Socket::new(domain, Type::STREAM, Some(Protocol::TCP))?;
+    apply_socket_options(&socket, target.is_ipv6(), options)?;
+    if let Some(source) = options.source_addr {
+        socket.bind(&SocketAddr::new(source, 0).into())?;
+    }
+    socket.set_nonblocking(true)?;
+    Ok(socket.into())
+}
+
+/// Create a non-blocking UDP socket connected to `target` with `options`
+/// applied.
+///
+/// When a source address is configured the socket is bound to it on an
+/// ephemeral port before connecting. The socket is returned as a
+/// `std::net::UdpSocket` so the caller can keep a handle for synchronous
+/// I/O before registering it with [`UdpSocket::from_std`].
+#[cfg(feature = "dtls")]
+pub(crate) fn connected_udp_socket(
+    target: SocketAddr,
+    options: &SocketOptions,
+) -> io::Result<std::net::UdpSocket> {
+    let domain = if target.is_ipv6() {
+        Domain::IPV6
+    } else {
+        Domain::IPV4
+    };
+    let socket = Socket::new(domain, Type::DGRAM, Some(Protocol::UDP))?;
+    apply_socket_options(&socket, target.is_ipv6(), options)?;
+    if let Some(source) = options.source_addr {
+        socket.bind(&SocketAddr::new(source, 0).into())?;
+    }
+    socket.connect(&target.into())?;
+    socket.set_nonblocking(true)?;
+    Ok(socket.into())
+}
+
+/// Apply interface and IP header options to an unbound socket.
+fn apply_socket_options(socket: &Socket, ipv6: bool, options: &SocketOptions) -> io::Result<()> {
+    if let Some(device) = &options.bind_device {
+        bind_device(socket, device)?;
+    }
+    if let Some(ttl) = options.ttl {
+        if ipv6 {
+            socket.set_unicast_hops_v6(ttl)?;
+        } else {
+            socket.set_ttl_v4(ttl)?;
+        }
+    }
+    if ipv6 {
+        if let Some(tclass) = options.ipv6_tclass {
+            set_tclass_v6(socket, tclass)?;
+        }
+        // Dual-stack sockets send IPv4-mapped traffic with IP_TOS, which
+        // some platforms reject on IPv6 sockets; best effort only.
+        if let Some(tos) = options.tos {
+            let _ = set_tos_v4(socket, tos);
+        }
+    } else if let Some(tos) = options.tos {
+        set_tos_v4(socket, tos)?;
+    }
+    Ok(())
+}
+
+// Only the fallbacks for platforms lacking an option call this.
+#[cfg_attr(any(target_os = "android", target_os = "linux"), allow(dead_code))]
+fn unsupported(option: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{option} is not supported on this platform"),
+    )
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn bind_device(socket: &Socket, device: &str) -> io::Result<()> {
+    socket.bind_device(Some(device.as_bytes()))
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+fn bind_device(_socket: &Socket, _device: &str) -> io::Result<()> {
+    Err(unsupported("SO_BINDTODEVICE"))
+}
+
+#[cfg(not(any(
+    target_os = "fuchsia",
+    target_os = "redox",
+    target_os = "solaris",
+    target_os = "haiku",
+    target_os = "wasi",
+)))]
+fn set_tos_v4(socket: &Socket, tos: u8) -> io::Result<()> {
+    socket.set_tos_v4(u32::from(tos))
+}
+
+#[cfg(any(
+    target_os = "fuchsia",
+    target_os = "redox",
+    target_os = "solaris",
+    target_os = "haiku",
+    target_os = "wasi",
+))]
+fn set_tos_v4(_socket: &Socket, _tos: u8) -> io::Result<()> {
+    Err(unsupported("IP_TOS"))
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "illumos",
+))]
+fn set_tclass_v6(socket: &Socket, tclass: u8) -> io::Result<()> {
+    socket.set_tclass_v6(u32::from(tclass))
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "illumos",
+)))]
+fn set_tclass_v6(_socket: &Socket, _tclass: u8) -> io::Result<()> {
+    Err(unsupported("IPV6_TCLASS"))
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin"))
+))]
+fn set_reuse_port(socket: &Socket) -> io::Result<()> {
+    socket.set_reuse_port(true)
+}
+
+#[cfg(not(all(
+    unix,
+    not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin"))
+)))]
+fn set_reuse_port(_socket: &Socket) -> io::Result<()> {
+    Err(unsupported("SO_REUSEPORT"))
+}
+
 #[cfg(test)]
 mod usm_validation_tests {
     use super::*;
@@ -332,4 +543,66 @@ mod tests {
         assert!(local.is_ipv4());
         assert_ne!(local.port(), 0);
     }
+
+    #[tokio::test]
+    async fn socket_options_set_ip_header_fields() {
+        let options = UdpBindOptions {
+            socket: SocketOptions::new().tos(0x40).ttl(7),
+            ..UdpBindOptions::default()
+        };
+        let socket = bind_udp_socket_with("127.0.0.1:0".parse().unwrap(), &options)
+            .await
+            .unwrap();
+        let socket = socket2::SockRef::from(&socket);
+        assert_eq!(socket.tos_v4().unwrap(), 0x40);
+        assert_eq!(socket.ttl_v4().unwrap(), 7);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn socket_options_set_ipv6_traffic_class_and_hop_limit() {
+        let options = UdpBindOptions {
+            socket: SocketOptions::new().dscp(46).ttl(9),
+            ..UdpBindOptions::default()
+        };
+        let socket = bind_udp_socket_with("[::1]:0".parse().unwrap(), &options)
+            .await
+            .unwrap();
+        let socket = socket2::SockRef::from(&socket);
+        assert_eq!(socket.tclass_v6().unwrap(), 46 << 2);
+        assert_eq!(socket.unicast_hops_v6().unwrap(), 9);
+    }
+
+    #[tokio::test]
+    async fn tcp_socket_binds_source_address_before_connect() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = listener.local_addr().unwrap();
+        let options = SocketOptions::new()
+            .source_addr("127.0.0.1".parse().unwrap())
+            .ttl(5);
+        let stream = tokio::net::TcpSocket::from_std_stream(tcp_socket(target, &options).unwrap())
+            .connect(target)
+            .await
+            .unwrap();
+        assert_eq!(
+            stream.local_addr().unwrap().ip(),
+            options.source_addr.unwrap()
+        );
+        assert_eq!(socket2::SockRef::from(&stream).ttl_v4().unwrap(), 5);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn reuse_port_sockets_share_one_port() {
+        let options = UdpBindOptions {
+            reuse_port: true,
+            ..UdpBindOptions::default()
+        };
+        let first = bind_udp_socket_with("127.0.0.1:0".parse().unwrap(), &options)
+            .await
+            .unwrap();
+        let local = first.local_addr().unwrap();
+        let second = bind_udp_socket_with(local, &options).await.unwrap();
+        assert_eq!(second.local_addr().unwrap(), local);
+    }
 }
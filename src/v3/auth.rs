@@ -36,7 +36,7 @@
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use super::AuthProtocol;
-use super::crypto::{CryptoBackend, CryptoResult};
+use super::crypto::{CryptoBackend, CryptoResult, ProviderKey};
 
 /// Minimum password length required for password-based key derivation.
 ///
@@ -195,6 +195,7 @@ impl MasterKey {
             key: localized,
             protocol: self.protocol,
             backend: self.backend,
+            provider: None,
         })
     }
 
@@ -253,6 +254,9 @@ pub struct LocalizedKey {
     protocol: AuthProtocol,
     #[zeroize(skip)]
     backend: CryptoBackend,
+    /// Set when a crypto provider holds the key; `key` is then empty.
+    #[zeroize(skip)]
+    provider: Option<ProviderKey>,
 }
 
 impl LocalizedKey {
@@ -351,9 +355,24 @@ impl LocalizedKey {
             key,
             protocol,
             backend,
+            provider: None,
         })
     }
 
+    /// Create a localized key held by a crypto provider.
+    pub(crate) fn from_provider(
+        protocol: AuthProtocol,
+        provider: ProviderKey,
+        backend: CryptoBackend,
+    ) -> Self {
+        Self {
+            key: Vec::new(),
+            protocol,
+            backend,
+            provider: Some(provider),
+        }
+    }
+
     /// Returns the protocol for this key.
     #[must_use]
     pub fn protocol(&self) -> AuthProtocol {
@@ -366,7 +385,16 @@ impl LocalizedKey {
         self.backend
     }
 
+    /// Return whether a [`CryptoProvider`](super::CryptoProvider) holds this key.
+    #[must_use]
+    pub fn is_provider_held(&self) -> bool {
+        self.provider.is_some()
+    }
+
     /// Returns the raw key bytes.
+    ///
+    /// Keys held by a [`CryptoProvider`](super::CryptoProvider) return an
+    /// empty slice.
     #[must_use]
     pub fn as_bytes(&self) -> &[u8] {
         &self.key
@@ -388,7 +416,16 @@ impl LocalizedKey {
     /// Returns [`CryptoError::UnsupportedAlgorithm`](super::CryptoError::UnsupportedAlgorithm) if the active crypto
     /// backend does not support the key's authentication protocol.
     pub fn compute_hmac(&self, data: &[u8]) -> CryptoResult<Vec<u8>> {
-        compute_hmac(self.backend, self.protocol, &self.key, data)
+        self.compute_hmac_slices(&[data])
+    }
+
+    fn compute_hmac_slices(&self, slices: &[&[u8]]) -> CryptoResult<Vec<u8>> {
+        match &self.provider {
+            Some(held) => held
+                .provider
+                .compute_hmac(&held.handle, self.protocol, slices),
+            None => compute_hmac_slices(self.backend, self.protocol, &self.key, slices),
+        }
     }
 
     /// Verify an HMAC.
@@ -400,6 +437,11 @@ impl LocalizedKey {
     /// Returns [`CryptoError::UnsupportedAlgorithm`](super::CryptoError::UnsupportedAlgorithm) if the active crypto
     /// backend does not support the key's authentication protocol.
     pub fn verify_hmac(&self, data: &[u8], expected: &[u8]) -> CryptoResult<bool> {
+        if let Some(held) = &self.provider {
+            return held
+                .provider
+                .verify_hmac(&held.handle, self.protocol, &[data], expected);
+        }
         let computed = self.compute_hmac(data)?;
         // Constant-time comparison
         if computed.len() != expected.len() {
@@ -502,7 +544,7 @@ mod no_backend_tests {
 
 /// Password to key transformation (RFC 3414 Section A.2.1).
 ///
-/// Routes through the active [`CryptoProvider`](super::crypto::CryptoProvider).
+/// Routes through the active [`CryptoBackend`].
 #[cfg(test)]
 fn password_to_key(protocol: AuthProtocol, password: &[u8]) -> CryptoResult<Vec<u8>> {
     password_to_key_with_backend(CryptoBackend::require_default()?, protocol, password)
@@ -527,7 +569,8 @@ fn localize_key_with_backend(
 
 /// Compute HMAC with the appropriate algorithm.
 ///
-/// Routes through the active [`CryptoProvider`](super::crypto::CryptoProvider).
+/// Routes through the active [`CryptoBackend`].
+#[cfg(test)]
 fn compute_hmac(
     backend: CryptoBackend,
     protocol: AuthProtocol,
@@ -539,7 +582,7 @@ fn compute_hmac(
 
 /// HMAC computation over multiple data slices (avoids concatenation allocation).
 ///
-/// Routes through the active [`CryptoProvider`](super::crypto::CryptoProvider).
+/// Routes through the active [`CryptoBackend`].
 fn compute_hmac_slices(
     backend: CryptoBackend,
     protocol: AuthProtocol,
@@ -618,15 +661,14 @@ pub fn verify_message(
 
     // Compute HMAC over the message with zeros in the auth position,
     // feeding three slices to avoid copying the entire message.
-    let computed = {
-        let zeros: [u8; MAX_MAC_LEN] = [0u8; MAX_MAC_LEN];
-        compute_hmac_slices(
-            key.backend,
-            key.protocol,
-            key.as_bytes(),
-            &[&message[..auth_offset], &zeros[..auth_len], &message[end..]],
-        )?
-    };
+    let zeros: [u8; MAX_MAC_LEN] = [0u8; MAX_MAC_LEN];
+    let slices: [&[u8]; 3] = [&message[..auth_offset], &zeros[..auth_len], &message[end..]];
+    if let Some(held) = &key.provider {
+        return held
+            .provider
+            .verify_hmac(&held.handle, key.protocol, &slices, received_mac);
+    }
+    let computed = key.compute_hmac_slices(&slices)?;
 
     // Constant-time comparison
     if computed.len() != received_mac.len() {
@@ -982,6 +1024,132 @@ pub(crate) fn extend_key_reeder_with_backend(
     Ok(result)
 }
 
+/// Compute a `KeyChange` value (RFC 3414 Section 5).
+///
+/// The value written to `usmUserAuthKeyChange`, `usmUserPrivKeyChange`, and
+/// their "Own" variants is `random || delta`, where `delta` is `new_key` XOR a
+/// digest stream of `old_key` and `random` built with the user's
+/// authentication hash. `random` must be as long as the keys; privacy keys use
+/// the same hash as the user's authentication protocol.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidKeyLength`](super::CryptoError::InvalidKeyLength)
+/// if the keys are empty or `old_key`, `new_key`, and `random` differ in length.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+/// # {
+/// use async_snmp::AuthProtocol;
+/// use async_snmp::v3::auth::{LocalizedKey, apply_key_change, key_change};
+///
+/// let engine_id = b"\x80\x00\x1f\x88\x80\xe9\xb1\x04\x61\x73\x61\x00\x00\x00";
+/// let old = LocalizedKey::from_password(AuthProtocol::Sha256, b"oldpassword", engine_id).unwrap();
+/// let new = LocalizedKey::from_password(AuthProtocol::Sha256, b"newpassword", engine_id).unwrap();
+///
+/// let random = [0x5a; 32];
+/// let value = key_change(AuthProtocol::Sha256, old.as_bytes(), new.as_bytes(), &random).unwrap();
+/// assert_eq!(value.len(), 64);
+///
+/// // The agent recovers the new key from the old one.
+/// let recovered = apply_key_change(AuthProtocol::Sha256, old.as_bytes(), &value).unwrap();
+/// assert_eq!(recovered, new.as_bytes());
+/// # }
+/// ```
+pub fn key_change(
+    protocol: AuthProtocol,
+    old_key: &[u8],
+    new_key: &[u8],
+    random: &[u8],
+) -> CryptoResult<Vec<u8>> {
+    key_change_with_backend(
+        CryptoBackend::require_default()?,
+        protocol,
+        old_key,
+        new_key,
+        random,
+    )
+}
+
+/// Recover the new key from a `KeyChange` value (RFC 3414 Section 5).
+///
+/// This is the receiving side of [`key_change`]: the first half of `value` is
+/// the random component and the second half the delta applied to `old_key`.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidKeyLength`](super::CryptoError::InvalidKeyLength)
+/// unless `value` is exactly twice as long as a non-empty `old_key`.
+pub fn apply_key_change(
+    protocol: AuthProtocol,
+    old_key: &[u8],
+    value: &[u8],
+) -> CryptoResult<Vec<u8>> {
+    apply_key_change_with_backend(CryptoBackend::require_default()?, protocol, old_key, value)
+}
+
+pub(crate) fn key_change_with_backend(
+    backend: CryptoBackend,
+    protocol: AuthProtocol,
+    old_key: &[u8],
+    new_key: &[u8],
+    random: &[u8],
+) -> CryptoResult<Vec<u8>> {
+    if old_key.is_empty() || new_key.len() != old_key.len() || random.len() != old_key.len() {
+        return Err(super::CryptoError::InvalidKeyLength);
+    }
+    let mut pad = key_change_pad(backend, protocol, old_key, random)?;
+    let mut value = Vec::with_capacity(random.len() * 2);
+    value.extend_from_slice(random);
+    value.extend(pad.iter().zip(new_key).map(|(pad, key)| pad ^ key));
+    pad.zeroize();
+    Ok(value)
+}
+
+pub(crate) fn apply_key_change_with_backend(
+    backend: CryptoBackend,
+    protocol: AuthProtocol,
+    old_key: &[u8],
+    value: &[u8],
+) -> CryptoResult<Vec<u8>> {
+    if old_key.is_empty() || value.len() != old_key.len() * 2 {
+        return Err(super::CryptoError::InvalidKeyLength);
+    }
+    let (random, delta) = value.split_at(old_key.len());
+    let mut pad = key_change_pad(backend, protocol, old_key, random)?;
+    let new_key = pad
+        .iter()
+        .zip(delta)
+        .map(|(pad, delta)| pad ^ delta)
+        .collect();
+    pad.zeroize();
+    Ok(new_key)
+}
+
+/// Digest stream `H(old || random)`, `H(H(old || random) || random)`, ...
+/// truncated to the key length.
+fn key_change_pad(
+    backend: CryptoBackend,
+    protocol: AuthProtocol,
+    old_key: &[u8],
+    random: &[u8],
+) -> CryptoResult<Vec<u8>> {
+    let mut pad = Vec::with_capacity(old_key.len() + protocol.digest_len());
+    let mut temp = old_key.to_vec();
+    while pad.len() < old_key.len() {
+        temp.extend_from_slice(random);
+        let digest = backend.hash(protocol, &temp)?;
+        temp.zeroize();
+        pad.extend_from_slice(&digest);
+        temp = digest;
+    }
+    temp.zeroize();
+    pad.truncate(old_key.len());
+    Ok(pad)
+}
+
 #[cfg(all(test, any(feature = "crypto-rustcrypto", feature = "crypto-fips")))]
 mod tests {
     use super::*;
@@ -1610,4 +1778,62 @@ mod tests {
         // Extension bytes should differ (different algorithms)
         assert_ne!(&reeder[20..], &blumenthal[20..]);
     }
+
+    #[cfg(feature = "crypto-rustcrypto")]
+    #[test]
+    fn test_key_change_md5_single_digest() {
+        let old: Vec<u8> = (0x00..0x10).collect();
+        let new: Vec<u8> = (0x10..0x20).collect();
+        let random: Vec<u8> = (0xa0..0xb0).collect();
+
+        let value = key_change(AuthProtocol::Md5, &old, &new, &random).unwrap();
+        assert_eq!(
+            encode_hex(&value),
+            "a0a1a2a3a4a5a6a7a8a9aaabacadaeaf9edb4aadda75cf646aa27a88fa2453f7"
+        );
+        assert_eq!(
+            apply_key_change(AuthProtocol::Md5, &old, &value).unwrap(),
+            new
+        );
+    }
+
+    #[test]
+    fn test_key_change_iterates_digest_for_long_keys() {
+        // A 32-octet privacy key needs two chained SHA-1 digests.
+        let old: Vec<u8> = (0x00..0x20).collect();
+        let new: Vec<u8> = (0x20..0x40).collect();
+        let random: Vec<u8> = (0xa0..0xc0).collect();
+
+        let value = key_change(AuthProtocol::Sha1, &old, &new, &random).unwrap();
+        assert_eq!(
+            encode_hex(&value),
+            "a0a1a2a3a4a5a6a7a8a9aaabacadaeafb0b1b2b3b4b5b6b7b8b9babbbcbdbebf\
+             f48b100b6384e364dc180bde8ed141edbd690b88e4cf4ef8e2ed8e69e3982d1c"
+        );
+        assert_eq!(
+            apply_key_change(AuthProtocol::Sha1, &old, &value).unwrap(),
+            new
+        );
+    }
+
+    #[test]
+    fn test_key_change_rejects_mismatched_lengths() {
+        let key = [0u8; 20];
+        assert_eq!(
+            key_change(AuthProtocol::Sha1, &key, &key[..16], &key).unwrap_err(),
+            super::super::CryptoError::InvalidKeyLength
+        );
+        assert_eq!(
+            key_change(AuthProtocol::Sha1, &key, &key, &key[..16]).unwrap_err(),
+            super::super::CryptoError::InvalidKeyLength
+        );
+        assert_eq!(
+            key_change(AuthProtocol::Sha1, &[], &[], &[]).unwrap_err(),
+            super::super::CryptoError::InvalidKeyLength
+        );
+        assert_eq!(
+            apply_key_change(AuthProtocol::Sha1, &key, &key).unwrap_err(),
+            super::super::CryptoError::InvalidKeyLength
+        );
+    }
 }
@@ -3,12 +3,16 @@
 //! [`UsmConfig`] selects the exact credentials and context used for outbound
 //! messages. [`UsmUser`] describes the mechanisms an inbound user supports.
 
+use std::sync::Arc;
+
 use bytes::Bytes;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::message::SecurityLevel;
+use crate::v3::crypto::ProviderKey;
 use crate::v3::{
-    AuthProtocol, CryptoBackend, CryptoError, CryptoResult, LocalizedKey, PrivKey, PrivProtocol,
+    AuthProtocol, CryptoBackend, CryptoError, CryptoProvider, CryptoResult, LocalizedKey, PrivKey,
+    PrivProtocol,
 };
 
 #[derive(Clone, Zeroize, ZeroizeOnDrop)]
@@ -50,6 +54,7 @@ pub struct UsmConfig {
     context_name: Bytes,
     crypto_backend: CryptoBackend,
     crypto_backend_explicit: bool,
+    crypto_provider: Option<Arc<dyn CryptoProvider>>,
 }
 
 /// USM user accepted by an inbound `SNMPv3` application.
@@ -75,6 +80,11 @@ impl UsmUser {
         }
     }
 
+    /// Accept inbound messages with the credentials of an outbound configuration.
+    pub(crate) fn from_config(config: UsmConfig) -> Self {
+        Self { config }
+    }
+
     /// Add password-backed authentication capability.
     pub fn auth(
         mut self,
@@ -111,6 +121,12 @@ impl UsmUser {
         self.config.crypto_backend()
     }
 
+    /// Hold this inbound user's localized keys in a crypto provider.
+    pub fn with_crypto_provider(mut self, provider: Arc<dyn CryptoProvider>) -> CryptoResult<Self> {
+        self.config = self.config.with_crypto_provider(provider)?;
+        Ok(self)
+    }
+
     /// Use pre-computed master keys for this inbound user.
     #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
     pub fn with_master_keys(mut self, master_keys: crate::v3::MasterKeys) -> CryptoResult<Self> {
@@ -161,6 +177,7 @@ impl std::fmt::Debug for UsmUser {
             .field("priv_protocol", &self.priv_protocol())
             .field("maximum_security_level", &self.maximum_security_level())
             .field("crypto_backend", &self.config.crypto_backend)
+            .field("crypto_provider", &self.config.crypto_provider)
             .finish()
     }
 }
@@ -174,6 +191,7 @@ impl UsmConfig {
             context_name: Bytes::new(),
             crypto_backend: CryptoBackend::default_backend().unwrap_or(CryptoBackend::RustCrypto),
             crypto_backend_explicit: false,
+            crypto_provider: None,
         }
     }
 
@@ -239,6 +257,45 @@ impl UsmConfig {
             .then_some(self.crypto_backend)
     }
 
+    /// Hold localized keys in a crypto provider instead of process memory.
+    ///
+    /// The provider localizes the configured passwords for each engine and
+    /// performs all HMAC and privacy operations through the returned
+    /// [`KeyHandle`](crate::v3::KeyHandle)s, so the selected
+    /// [`CryptoBackend`] is not used for this configuration's keys. Both
+    /// protocols are validated against the provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns the provider's capability error for an unsupported protocol,
+    /// or [`CryptoError::KeyMaterialUnavailable`] for master-key credentials,
+    /// which a provider cannot localize.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use async_snmp::{AuthProtocol, PrivProtocol, UsmConfig};
+    /// # use async_snmp::v3::CryptoProvider;
+    /// # use std::sync::Arc;
+    /// # fn example(hsm: Arc<dyn CryptoProvider>) -> async_snmp::v3::CryptoResult<()> {
+    /// let config = UsmConfig::new("admin")
+    ///     .auth_priv(AuthProtocol::Sha256, "authpass123", PrivProtocol::Aes128, "privpass123")?
+    ///     .with_crypto_provider(hsm)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_crypto_provider(mut self, provider: Arc<dyn CryptoProvider>) -> CryptoResult<Self> {
+        self.crypto_provider = Some(provider);
+        self.validate_credential_capabilities()?;
+        Ok(self)
+    }
+
+    /// Return the registered crypto provider, if any.
+    #[must_use]
+    pub fn crypto_provider(&self) -> Option<&Arc<dyn CryptoProvider>> {
+        self.crypto_provider.as_ref()
+    }
+
     /// Set the `SNMPv3` context name for scoped PDUs.
     #[must_use]
     pub fn context_name(mut self, context_name: impl Into<Bytes>) -> Self {
@@ -260,6 +317,9 @@ impl UsmConfig {
         mut self,
         mut master_keys: crate::v3::MasterKeys,
     ) -> CryptoResult<Self> {
+        if self.crypto_provider.is_some() {
+            return Err(CryptoError::KeyMaterialUnavailable);
+        }
         self.crypto_backend
             .validate_auth_protocol(master_keys.auth_protocol())?;
         if let Some(protocol) = master_keys.priv_protocol() {
@@ -324,6 +384,9 @@ impl UsmConfig {
     fn validate_credential_capabilities(&self) -> CryptoResult<()> {
         match &self.credentials {
             UsmCredentials::NoAuthNoPriv => Ok(()),
+            UsmCredentials::MasterKeys(_) if self.crypto_provider.is_some() => {
+                Err(CryptoError::KeyMaterialUnavailable)
+            }
             UsmCredentials::MasterKeys(master_keys) => {
                 if !self.crypto_backend_explicit && CryptoBackend::default_backend().is_none() {
                     return Err(CryptoError::BackendUnavailable);
@@ -343,6 +406,13 @@ impl UsmConfig {
                 {
                     return Err(CryptoError::PasswordTooShort);
                 }
+                if let Some(provider) = &self.crypto_provider {
+                    provider.validate_auth_protocol(auth.0)?;
+                    if let Some((protocol, _)) = privacy {
+                        provider.validate_priv_protocol(*protocol)?;
+                    }
+                    return Ok(());
+                }
                 if !self.crypto_backend_explicit && CryptoBackend::default_backend().is_none() {
                     return Err(CryptoError::BackendUnavailable);
                 }
@@ -369,6 +439,10 @@ impl UsmConfig {
 
         self.validate_credential_capabilities()?;
 
+        if self.crypto_provider.is_some() {
+            // The provider localizes the passwords for each engine.
+            return Ok(());
+        }
         match &self.credentials {
             UsmCredentials::NoAuthNoPriv | UsmCredentials::MasterKeys(_) => Ok(()),
             UsmCredentials::Passwords { auth, privacy } => {
@@ -417,6 +491,47 @@ impl UsmConfig {
                     priv_key,
                 })
             }
+            UsmCredentials::Passwords { auth, privacy } if self.crypto_provider.is_some() => {
+                let provider = self.crypto_provider.as_ref().expect("checked by guard");
+                let (auth_protocol, auth_password) = auth;
+                tracing::trace!(target: "async_snmp::client", { engine_id_len = engine_id.len(), auth_protocol = ?auth_protocol }, "localizing keys in crypto provider");
+                let handle = provider.localize_auth_key(
+                    *auth_protocol,
+                    auth_password.as_ref(),
+                    engine_id,
+                )?;
+                let auth_key = LocalizedKey::from_provider(
+                    *auth_protocol,
+                    ProviderKey {
+                        provider: provider.clone(),
+                        handle,
+                    },
+                    self.crypto_backend,
+                );
+                let priv_key = privacy
+                    .as_ref()
+                    .map(|(priv_protocol, priv_password)| {
+                        let handle = provider.localize_priv_key(
+                            *auth_protocol,
+                            *priv_protocol,
+                            priv_password.as_ref(),
+                            engine_id,
+                        )?;
+                        Ok::<_, CryptoError>(PrivKey::from_provider(
+                            *priv_protocol,
+                            ProviderKey {
+                                provider: provider.clone(),
+                                handle,
+                            },
+                            self.crypto_backend,
+                        ))
+                    })
+                    .transpose()?;
+                Ok(DerivedKeys {
+                    auth_key: Some(auth_key),
+                    priv_key,
+                })
+            }
             UsmCredentials::Passwords { auth, privacy } => {
                 let (auth_protocol, auth_password) = auth;
                 tracing::trace!(target: "async_snmp::client", { engine_id_len = engine_id.len(), auth_protocol = ?auth_protocol }, "deriving localized keys from passwords");
@@ -474,6 +589,7 @@ impl std::fmt::Debug for UsmConfig {
             .field("priv_password", &priv_password)
             .field("context_name", &self.context_name)
             .field("crypto_backend", &self.crypto_backend)
+            .field("crypto_provider", &self.crypto_provider)
             .field("master_keys", &master_keys)
             .finish()
     }
@@ -5,7 +5,7 @@ use aws_lc_rs::cipher::{
 use aws_lc_rs::iv::FixedLength;
 use aws_lc_rs::{digest, hmac};
 
-use super::{CryptoError, CryptoProvider, CryptoResult};
+use super::{CryptoError, CryptoPrimitives, CryptoResult};
 use crate::v3::{AuthProtocol, PrivProtocol};
 
 /// FIPS 140-3 compliant crypto provider backed by aws-lc-rs.
@@ -39,7 +39,7 @@ fn hmac_algorithm(protocol: AuthProtocol) -> CryptoResult<hmac::Algorithm> {
     }
 }
 
-impl CryptoProvider for AwsLcFipsProvider {
+impl CryptoPrimitives for AwsLcFipsProvider {
     fn validate_auth_protocol(&self, protocol: AuthProtocol) -> CryptoResult<()> {
         digest_algorithm(protocol).map(|_| ())
     }
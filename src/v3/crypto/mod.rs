@@ -6,6 +6,10 @@
 //! - `crypto-rustcrypto` (**default**) supports all authentication and privacy
 //!   protocols.
 //! - `crypto-fips` uses aws-lc-rs and rejects MD5, DES, and 3DES.
+//!
+//! Applications that keep localized keys outside process memory, such as in
+//! an HSM or PKCS#11 token, implement [`CryptoProvider`] and register it with
+//! [`UsmConfig::with_crypto_provider`](crate::UsmConfig::with_crypto_provider).
 
 use super::{AuthProtocol, PrivProtocol};
 
@@ -16,6 +20,7 @@ use super::{AuthProtocol, PrivProtocol};
 /// Protocol-level framing errors (e.g., wrong privParameters length) use the
 /// separate privacy error type in builds that enable a crypto backend.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum CryptoError {
     /// No cryptographic backend was compiled into the crate.
     ///
@@ -49,6 +54,11 @@ pub enum CryptoError {
         /// Supplied username length in octets.
         length: usize,
     },
+    /// Key material cannot cross the [`CryptoProvider`] boundary.
+    ///
+    /// Returned when an operation needs the bytes of a provider-held key, or
+    /// when in-memory master keys are combined with a provider.
+    KeyMaterialUnavailable,
 }
 
 impl std::fmt::Display for CryptoError {
@@ -78,6 +88,9 @@ impl std::fmt::Display for CryptoError {
                 f,
                 "USM username must contain 1 through 32 octets (got {length})"
             ),
+            Self::KeyMaterialUnavailable => {
+                write!(f, "key material cannot cross the crypto provider boundary")
+            }
         }
     }
 }
@@ -97,11 +110,16 @@ mod fips;
 #[cfg(feature = "crypto-fips")]
 pub(crate) use fips::AwsLcFipsProvider;
 
+mod provider;
+pub use provider::{CipherParams, CryptoProvider, KeyHandle};
+pub(crate) use provider::{ProviderKey, privacy_key_and_iv};
+
 /// Internal interface implemented by each compile-time cryptographic backend.
 ///
-/// This keeps auth and privacy operations independent of backend APIs without
-/// promising runtime provider injection as part of the public API.
-pub(crate) trait CryptoProvider: Send + Sync + 'static {
+/// This keeps auth and privacy operations independent of backend APIs. Keys
+/// are passed as raw bytes; the public [`CryptoProvider`] trait wraps these
+/// primitives behind key handles.
+pub(crate) trait CryptoPrimitives: Send + Sync + 'static {
     /// Validate that this provider supports an authentication protocol.
     fn validate_auth_protocol(&self, protocol: AuthProtocol) -> CryptoResult<()>;
 
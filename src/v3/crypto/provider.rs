@@ -0,0 +1,321 @@
+//! Runtime-pluggable cryptographic providers.
+//!
+//! A [`CryptoProvider`] performs USM key localization, HMAC, and privacy
+//! cipher operations on keys it identifies by [`KeyHandle`]. The library
+//! never sees the localized key bytes, so a provider can keep them in an HSM
+//! or PKCS#11 token.
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+use super::{CryptoBackend, CryptoError, CryptoResult};
+use crate::v3::{AuthProtocol, PrivProtocol};
+
+/// Opaque reference to a localized key held by a [`CryptoProvider`].
+///
+/// A provider wraps whatever identifies its key (an object handle, a slot
+/// and label, or the key itself) and recovers it with
+/// [`downcast_ref`](Self::downcast_ref). Clones share the wrapped value.
+#[derive(Clone)]
+pub struct KeyHandle(Arc<dyn Any + Send + Sync>);
+
+impl KeyHandle {
+    /// Wrap a provider-specific key reference.
+    pub fn new<T: Any + Send + Sync>(value: T) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Return the wrapped reference if it has type `T`.
+    #[must_use]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+}
+
+impl fmt::Debug for KeyHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KeyHandle([REDACTED])")
+    }
+}
+
+/// Per-message inputs to a USM privacy cipher.
+///
+/// For DES and 3DES (RFC 3414 Section 8.1.1.1) the IV is the key's pre-IV
+/// XOR `salt`, which only the key holder can compute. For AES (RFC 3826
+/// Section 3.1.2.1) the IV is `engine_boots || engine_time || salt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CipherParams {
+    /// Authoritative engine boots.
+    pub engine_boots: u32,
+    /// Authoritative engine time; not used by DES and 3DES.
+    pub engine_time: u32,
+    /// The msgPrivacyParameters salt.
+    pub salt: [u8; 8],
+}
+
+impl CipherParams {
+    /// Return the AES IV.
+    #[must_use]
+    pub fn aes_iv(&self) -> [u8; 16] {
+        let mut iv = [0u8; 16];
+        iv[..4].copy_from_slice(&self.engine_boots.to_be_bytes());
+        iv[4..8].copy_from_slice(&self.engine_time.to_be_bytes());
+        iv[8..].copy_from_slice(&self.salt);
+        iv
+    }
+
+    /// Return the DES or 3DES IV for a key's pre-IV.
+    #[must_use]
+    pub fn des_iv(&self, pre_iv: &[u8; 8]) -> [u8; 8] {
+        std::array::from_fn(|i| pre_iv[i] ^ self.salt[i])
+    }
+}
+
+/// Cryptographic operations for USM keys identified by handle.
+///
+/// Implement this trait to keep localized keys outside process memory and
+/// register the provider with
+/// [`UsmConfig::with_crypto_provider`](crate::UsmConfig::with_crypto_provider).
+/// The library then asks the provider to localize each password for every
+/// authoritative engine it talks to, and performs all message
+/// authentication and encryption through the returned handles.
+///
+/// [`CryptoBackend`] implements this trait with handles that hold the key
+/// bytes in memory, which makes the built-in backends usable wherever a
+/// provider is expected.
+///
+/// Operations that need the key bytes themselves, such as computing
+/// `KeyChange` values, return [`CryptoError::KeyMaterialUnavailable`] for
+/// provider-held keys.
+pub trait CryptoProvider: Send + Sync + fmt::Debug + 'static {
+    /// Validate that this provider supports an authentication protocol.
+    fn validate_auth_protocol(&self, protocol: AuthProtocol) -> CryptoResult<()>;
+
+    /// Validate that this provider supports a privacy protocol.
+    fn validate_priv_protocol(&self, protocol: PrivProtocol) -> CryptoResult<()>;
+
+    /// Derive the localized authentication key for an engine (RFC 3414
+    /// Section A.2).
+    fn localize_auth_key(
+        &self,
+        protocol: AuthProtocol,
+        password: &[u8],
+        engine_id: &[u8],
+    ) -> CryptoResult<KeyHandle>;
+
+    /// Derive the localized privacy key for an engine, including any key
+    /// extension that `priv_protocol` requires.
+    fn localize_priv_key(
+        &self,
+        auth_protocol: AuthProtocol,
+        priv_protocol: PrivProtocol,
+        password: &[u8],
+        engine_id: &[u8],
+    ) -> CryptoResult<KeyHandle>;
+
+    /// Compute the HMAC over the concatenated slices, truncated to
+    /// [`AuthProtocol::mac_len`].
+    fn compute_hmac(
+        &self,
+        key: &KeyHandle,
+        protocol: AuthProtocol,
+        slices: &[&[u8]],
+    ) -> CryptoResult<Vec<u8>>;
+
+    /// Verify a truncated HMAC over the concatenated slices.
+    ///
+    /// The default implementation calls [`compute_hmac`](Self::compute_hmac)
+    /// and compares in constant time.
+    fn verify_hmac(
+        &self,
+        key: &KeyHandle,
+        protocol: AuthProtocol,
+        slices: &[&[u8]],
+        expected: &[u8],
+    ) -> CryptoResult<bool> {
+        let computed = self.compute_hmac(key, protocol, slices)?;
+        if computed.len() != expected.len() {
+            return Ok(false);
+        }
+        Ok(computed.ct_eq(expected).into())
+    }
+
+    /// Encrypt `data` in place.
+    ///
+    /// DES and 3DES pad unaligned plaintext to the block size, extending
+    /// `data` (RFC 3414 Section 8.1.1.2).
+    fn encrypt(
+        &self,
+        key: &KeyHandle,
+        protocol: PrivProtocol,
+        params: &CipherParams,
+        data: &mut Vec<u8>,
+    ) -> CryptoResult<()>;
+
+    /// Decrypt `data` in place.
+    fn decrypt(
+        &self,
+        key: &KeyHandle,
+        protocol: PrivProtocol,
+        params: &CipherParams,
+        data: &mut [u8],
+    ) -> CryptoResult<()>;
+}
+
+/// A provider-held key together with the provider that owns it.
+#[derive(Clone, Debug)]
+pub(crate) struct ProviderKey {
+    pub(crate) provider: Arc<dyn CryptoProvider>,
+    pub(crate) handle: KeyHandle,
+}
+
+/// Split a localized privacy secret into the cipher key and the IV for one
+/// message.
+pub(crate) fn privacy_key_and_iv<'a>(
+    protocol: PrivProtocol,
+    secret: &'a [u8],
+    params: &CipherParams,
+) -> CryptoResult<(&'a [u8], Vec<u8>)> {
+    if secret.len() < protocol.key_len() {
+        return Err(CryptoError::InvalidKeyLength);
+    }
+    let des = |key_len: usize| {
+        let pre_iv: &[u8; 8] = secret[key_len..key_len + 8]
+            .try_into()
+            .expect("pre-IV is eight octets");
+        (&secret[..key_len], params.des_iv(pre_iv).to_vec())
+    };
+    Ok(match protocol {
+        PrivProtocol::Des => des(8),
+        PrivProtocol::Des3 => des(24),
+        PrivProtocol::Aes128 => (&secret[..16], params.aes_iv().to_vec()),
+        PrivProtocol::Aes192Blumenthal | PrivProtocol::Aes192Reeder => {
+            (&secret[..24], params.aes_iv().to_vec())
+        }
+        PrivProtocol::Aes256Blumenthal | PrivProtocol::Aes256Reeder => {
+            (&secret[..32], params.aes_iv().to_vec())
+        }
+    })
+}
+
+/// Key bytes held by a built-in backend's handles.
+struct BackendKey(Zeroizing<Vec<u8>>);
+
+fn backend_key(key: &KeyHandle) -> CryptoResult<&[u8]> {
+    key.downcast_ref::<BackendKey>()
+        .map(|key| key.0.as_slice())
+        .ok_or(CryptoError::InvalidKeyLength)
+}
+
+impl CryptoProvider for CryptoBackend {
+    fn validate_auth_protocol(&self, protocol: AuthProtocol) -> CryptoResult<()> {
+        CryptoBackend::validate_auth_protocol(*self, protocol)
+    }
+
+    fn validate_priv_protocol(&self, protocol: PrivProtocol) -> CryptoResult<()> {
+        CryptoBackend::validate_priv_protocol(*self, protocol)
+    }
+
+    fn localize_auth_key(
+        &self,
+        protocol: AuthProtocol,
+        password: &[u8],
+        engine_id: &[u8],
+    ) -> CryptoResult<KeyHandle> {
+        let key = crate::v3::LocalizedKey::from_password_with_backend(
+            protocol, password, engine_id, *self,
+        )?;
+        Ok(KeyHandle::new(BackendKey(Zeroizing::new(
+            key.as_bytes().to_vec(),
+        ))))
+    }
+
+    fn localize_priv_key(
+        &self,
+        auth_protocol: AuthProtocol,
+        priv_protocol: PrivProtocol,
+        password: &[u8],
+        engine_id: &[u8],
+    ) -> CryptoResult<KeyHandle> {
+        let key = crate::v3::PrivKey::from_password_with_backend(
+            auth_protocol,
+            priv_protocol,
+            password,
+            engine_id,
+            *self,
+        )?;
+        Ok(KeyHandle::new(BackendKey(Zeroizing::new(
+            key.key_change_secret().to_vec(),
+        ))))
+    }
+
+    fn compute_hmac(
+        &self,
+        key: &KeyHandle,
+        protocol: AuthProtocol,
+        slices: &[&[u8]],
+    ) -> CryptoResult<Vec<u8>> {
+        CryptoBackend::compute_hmac(
+            *self,
+            protocol,
+            backend_key(key)?,
+            slices,
+            protocol.mac_len(),
+        )
+    }
+
+    fn encrypt(
+        &self,
+        key: &KeyHandle,
+        protocol: PrivProtocol,
+        params: &CipherParams,
+        data: &mut Vec<u8>,
+    ) -> CryptoResult<()> {
+        let (cipher_key, iv) = privacy_key_and_iv(protocol, backend_key(key)?, params)?;
+        CryptoBackend::encrypt(*self, protocol, cipher_key, &iv, data)
+    }
+
+    fn decrypt(
+        &self,
+        key: &KeyHandle,
+        protocol: PrivProtocol,
+        params: &CipherParams,
+        data: &mut [u8],
+    ) -> CryptoResult<()> {
+        let (cipher_key, iv) = privacy_key_and_iv(protocol, backend_key(key)?, params)?;
+        CryptoBackend::decrypt(*self, protocol, cipher_key, &iv, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cipher_params_build_rfc_ivs() {
+        let params = CipherParams {
+            engine_boots: 0x0102_0304,
+            engine_time: 0x0506_0708,
+            salt: [0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7],
+        };
+        assert_eq!(
+            params.aes_iv(),
+            [
+                1, 2, 3, 4, 5, 6, 7, 8, 0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7
+            ]
+        );
+        assert_eq!(params.des_iv(&[0xff; 8]), params.salt.map(|octet| !octet));
+    }
+
+    #[test]
+    fn key_handle_debug_is_redacted() {
+        let handle = KeyHandle::new(vec![1u8, 2, 3]);
+        assert_eq!(format!("{handle:?}"), "KeyHandle([REDACTED])");
+        assert_eq!(handle.downcast_ref::<Vec<u8>>(), Some(&vec![1, 2, 3]));
+        assert!(handle.downcast_ref::<String>().is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{CryptoError, CryptoProvider, CryptoResult};
+use super::{CryptoError, CryptoPrimitives, CryptoResult};
 use crate::v3::{AuthProtocol, PrivProtocol};
 
 /// Default crypto provider backed by the `RustCrypto` crate ecosystem.
@@ -22,7 +22,7 @@ macro_rules! dispatch_auth {
     };
 }
 
-impl CryptoProvider for RustCryptoProvider {
+impl CryptoPrimitives for RustCryptoProvider {
     fn validate_auth_protocol(&self, _protocol: AuthProtocol) -> CryptoResult<()> {
         Ok(())
     }
@@ -30,7 +30,7 @@ use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::RwLock;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use bytes::Bytes;
 
@@ -38,6 +38,8 @@ use crate::error::{Error, Result};
 use crate::message_size::{MessageSize, UDP_RECEIVE_LIMITS};
 use crate::v3::UsmSecurityParams;
 
+use super::engine_snapshot::{EngineCacheSnapshot, SnapshotEntry};
+
 /// Time window in seconds (RFC 3414 Section 2.2.3).
 pub const TIME_WINDOW: u32 = 150;
 
@@ -306,6 +308,10 @@ impl AuthenticatedEngineTime {
 pub struct EngineState {
     discovered: DiscoveredEngine,
     authenticated_time: Option<AuthenticatedEngineTime>,
+    /// Imported boots/time not yet re-validated by an authenticated message.
+    ///
+    /// It only supplies the outgoing tuple; Step 7(b) never consults it.
+    stale_time: Option<AuthenticatedEngineTime>,
 }
 
 impl EngineState {
@@ -329,6 +335,7 @@ impl EngineState {
                 msg_max_size,
             },
             authenticated_time: None,
+            stale_time: None,
         }
     }
 
@@ -349,6 +356,7 @@ impl EngineState {
                 engine_time,
                 Instant::now(),
             )),
+            stale_time: None,
         }
     }
 
@@ -393,6 +401,14 @@ impl EngineState {
         self.authenticated_time.as_ref()
     }
 
+    /// Return whether the only known boots/time was imported from an
+    /// [`EngineCacheSnapshot`](super::EngineCacheSnapshot) and has not been
+    /// re-validated by an authenticated message.
+    #[must_use]
+    pub fn is_time_stale(&self) -> bool {
+        self.authenticated_time.is_none() && self.stale_time.is_some()
+    }
+
     /// Return the progressing trusted boots/time pair, or `(0, 0)` before the
     /// first authenticated message establishes a notion.
     #[must_use]
@@ -406,6 +422,19 @@ impl EngineState {
             .map_or((0, 0), |time| time.estimated_at(now))
     }
 
+    /// Return the boots/time to place in an outgoing request.
+    ///
+    /// A stale imported tuple is used only when no authenticated notion
+    /// exists, saving the `notInTimeWindows` round trip after a restart. It
+    /// never takes part in evaluating received messages.
+    pub(crate) fn outgoing_boots_time(&self) -> (u32, u32) {
+        let now = Instant::now();
+        match (&self.authenticated_time, &self.stale_time) {
+            (Some(time), _) | (None, Some(time)) => time.estimated_at(now),
+            (None, None) => (0, 0),
+        }
+    }
+
     pub(crate) fn last_authenticated_update_at(&self) -> Option<Instant> {
         self.authenticated_time
             .as_ref()
@@ -428,6 +457,7 @@ impl EngineState {
                     response_time,
                     now,
                 ));
+                self.stale_time = None;
                 true
             }
         }
@@ -443,6 +473,9 @@ impl EngineState {
             .msg_max_size
             .min(other.discovered.msg_max_size);
         let Some(other_time) = &other.authenticated_time else {
+            if self.authenticated_time.is_none() && self.stale_time.is_none() {
+                self.stale_time.clone_from(&other.stale_time);
+            }
             return false;
         };
         match self.authenticated_time.as_mut() {
@@ -453,6 +486,7 @@ impl EngineState {
             ),
             None => {
                 self.authenticated_time = Some(other_time.clone());
+                self.stale_time = None;
                 true
             }
         }
@@ -559,6 +593,8 @@ struct CachedTarget {
 struct EngineCacheInner {
     targets: HashMap<SocketAddr, CachedTarget>,
     authenticated_times: HashMap<Bytes, AuthenticatedEngineTime>,
+    /// Imported boots/time awaiting authenticated re-validation.
+    stale_times: HashMap<Bytes, AuthenticatedEngineTime>,
 }
 
 #[derive(Debug)]
@@ -736,6 +772,7 @@ impl EngineCache {
     fn clear_inner(inner: &mut EngineCacheInner) {
         inner.targets.clear();
         inner.authenticated_times.clear();
+        inner.stale_times.clear();
     }
 
     fn with_inner<R>(&self, mutation: impl FnOnce(&mut EngineCacheInner) -> R) -> R {
@@ -928,11 +965,7 @@ impl EngineCache {
                 .filter(|cached| cached.engine_id != *state.engine_id())
                 .map(|cached| cached.engine_id.clone());
             if let Some(authenticated) = &state.authenticated_time {
-                merge_authenticated_time(
-                    &mut inner.authenticated_times,
-                    state.engine_id(),
-                    authenticated,
-                );
+                merge_authenticated_time(inner, state.engine_id(), authenticated);
                 #[cfg(test)]
                 self.mutation_checkpoint(CacheMutationStage::AuthenticatedTimeStored);
             }
@@ -1135,12 +1168,13 @@ impl EngineCache {
                 state.merge_from(&EngineState {
                     discovered: state.discovered.clone(),
                     authenticated_time: Some(shared_time.clone()),
+                    stale_time: None,
                 });
             }
 
             let timely = state.check_and_update_timeliness_at(msg_boots, msg_time, now);
             if let Some(authenticated) = &state.authenticated_time {
-                merge_authenticated_time(&mut inner.authenticated_times, &engine_id, authenticated);
+                merge_authenticated_time(inner, &engine_id, authenticated);
                 #[cfg(test)]
                 self.mutation_checkpoint(CacheMutationStage::AuthenticatedTimeStored);
             }
@@ -1227,6 +1261,7 @@ impl EngineCache {
             #[cfg(test)]
             self.mutation_checkpoint(CacheMutationStage::TargetsCleared);
             inner.authenticated_times.clear();
+            inner.stale_times.clear();
         });
     }
 
@@ -1240,6 +1275,116 @@ impl EngineCache {
         self.len() == 0
     }
 
+    /// Capture the unexpired target identities for persistence.
+    ///
+    /// Each entry records the engine ID, msgMaxSize, entry age, and the
+    /// engine's estimated boots/time when one is known. Restore the snapshot
+    /// with [`import`](Self::import).
+    #[must_use]
+    pub fn export(&self) -> EngineCacheSnapshot {
+        let now = Instant::now();
+        let entries = self.with_inner(|inner| {
+            inner
+                .targets
+                .iter()
+                .filter_map(|(target, cached)| {
+                    let age = now
+                        .checked_duration_since(cached.refreshed_at)
+                        .unwrap_or_default();
+                    if age > self.ttl {
+                        return None;
+                    }
+                    let boots_time = inner
+                        .authenticated_times
+                        .get(&cached.engine_id)
+                        .or_else(|| inner.stale_times.get(&cached.engine_id))
+                        .map(|time| time.estimated_at(now));
+                    Some(SnapshotEntry {
+                        target: *target,
+                        engine_id: cached.engine_id.clone(),
+                        msg_max_size: cached.msg_max_size,
+                        age,
+                        boots_time,
+                    })
+                })
+                .collect()
+        });
+        EngineCacheSnapshot {
+            taken_at: SystemTime::now(),
+            entries,
+        }
+    }
+
+    /// Restore target identities from a snapshot taken by [`export`](Self::export).
+    ///
+    /// Entry ages include the wall-clock time since the snapshot was taken,
+    /// and entries that would already have expired under this cache's TTL are
+    /// skipped. Targets that are already cached keep their current identity,
+    /// and entries are not imported beyond the cache's capacity.
+    ///
+    /// Imported boots/time is stale: it is rolled forward by the elapsed wall
+    /// time and used only to fill the first outgoing request, so that an
+    /// engine which has not rebooted accepts it without a time
+    /// synchronization round trip. It is never used for the RFC 3414
+    /// Section 3.2 Step 7(b) checks, and
+    /// [`EngineState::authenticated_time`] stays `None` until an
+    /// authenticated message from the engine replaces it.
+    ///
+    /// Returns the number of target identities imported.
+    pub fn import(&self, snapshot: &EngineCacheSnapshot) -> usize {
+        let now = Instant::now();
+        let elapsed = SystemTime::now()
+            .duration_since(snapshot.taken_at)
+            .unwrap_or_default();
+        let imported = self.with_inner(|inner| {
+            let mut imported = 0;
+            for entry in &snapshot.entries {
+                let age = entry.age.saturating_add(elapsed);
+                if age > self.ttl {
+                    continue;
+                }
+                self.expire_target_if_needed(inner, &entry.target, now);
+                if inner.targets.contains_key(&entry.target)
+                    || self
+                        .max_capacity
+                        .is_some_and(|cap| inner.targets.len() >= cap)
+                    || validate_engine_id(&entry.engine_id).is_err()
+                {
+                    continue;
+                }
+                if let Some((boots, time)) = entry.boots_time
+                    && !inner.authenticated_times.contains_key(&entry.engine_id)
+                    && !inner.stale_times.contains_key(&entry.engine_id)
+                {
+                    let (boots, time) = if boots == MAX_ENGINE_TIME {
+                        (boots, time)
+                    } else {
+                        compute_engine_boots_time(
+                            boots,
+                            u64::from(time).saturating_add(elapsed.as_secs()),
+                        )
+                    };
+                    inner.stale_times.insert(
+                        entry.engine_id.clone(),
+                        AuthenticatedEngineTime::new_at(boots, time, now),
+                    );
+                }
+                inner.targets.insert(
+                    entry.target,
+                    CachedTarget {
+                        engine_id: entry.engine_id.clone(),
+                        msg_max_size: entry.msg_max_size,
+                        refreshed_at: now.checked_sub(age).unwrap_or(now),
+                    },
+                );
+                imported += 1;
+            }
+            imported
+        });
+        tracing::debug!(target: "async_snmp::v3", { imported, entries = snapshot.len() }, "imported engine cache snapshot");
+        imported
+    }
+
     #[cfg(test)]
     pub(crate) fn poison_for_test(&self) {
         let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
@@ -1296,14 +1441,18 @@ fn compose_cached_state(inner: &EngineCacheInner, target: &SocketAddr) -> Option
             msg_max_size: cached.msg_max_size,
         },
         authenticated_time: inner.authenticated_times.get(&cached.engine_id).cloned(),
+        stale_time: inner.stale_times.get(&cached.engine_id).cloned(),
     })
 }
 
 fn merge_authenticated_time(
-    authenticated_times: &mut HashMap<Bytes, AuthenticatedEngineTime>,
+    inner: &mut EngineCacheInner,
     engine_id: &Bytes,
     incoming: &AuthenticatedEngineTime,
 ) {
+    // An authenticated notion supersedes any imported tuple for the engine.
+    inner.stale_times.remove(engine_id);
+    let authenticated_times = &mut inner.authenticated_times;
     match authenticated_times.get_mut(engine_id) {
         Some(current) => {
             current.update_at(
@@ -1325,6 +1474,7 @@ fn remove_orphaned_time(inner: &mut EngineCacheInner, engine_id: &Bytes) {
         .any(|cached| cached.engine_id == engine_id)
     {
         inner.authenticated_times.remove(engine_id);
+        inner.stale_times.remove(engine_id);
     }
 }
 
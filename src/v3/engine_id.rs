@@ -0,0 +1,509 @@
+//! Structured SnmpEngineID values (RFC 3411 Section 5).
+//!
+//! An engine ID whose first bit is set carries a private enterprise number in
+//! its first four octets and a format octet selecting how the remaining
+//! octets identify the engine. IDs whose first bit is clear use the 12-octet
+//! layout of RFC 1910. [`EngineId`] builds IDs in each RFC 3411 format and
+//! decodes received IDs, recognizing the net-snmp and Cisco layouts.
+
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use super::engine::validate_engine_id;
+use crate::error::{Error, Result};
+use crate::format::hex;
+
+/// Maximum octets following the format octet in text, octets, and local
+/// formats (RFC 3411 Section 5).
+pub const MAX_ENGINE_ID_DATA_LEN: usize = 27;
+
+/// IANA private enterprise number of Cisco Systems.
+const ENTERPRISE_CISCO: u32 = 9;
+/// IANA private enterprise number of the UCD-SNMP project.
+const ENTERPRISE_UCD_SNMP: u32 = 2021;
+/// IANA private enterprise number of the net-snmp project.
+const ENTERPRISE_NET_SNMP: u32 = 8072;
+
+const FORMAT_IPV4: u8 = 1;
+const FORMAT_IPV6: u8 = 2;
+const FORMAT_MAC: u8 = 3;
+const FORMAT_TEXT: u8 = 4;
+const FORMAT_OCTETS: u8 = 5;
+const FORMAT_LOCAL: u8 = 6;
+/// net-snmp's default format: four random octets and the creation time.
+const FORMAT_NET_SNMP_RANDOM: u8 = 128;
+
+/// Length of an RFC 1910 engine ID.
+const LEGACY_LEN: usize = 12;
+
+/// An SnmpEngineID.
+///
+/// Constructors build IDs in the RFC 3411 formats; [`from_bytes`](Self::from_bytes)
+/// accepts any valid received ID. [`enterprise`](Self::enterprise) and
+/// [`format`](Self::format) decode the layout, and `Display` prints the hex
+/// value followed by the decoded description:
+///
+/// ```
+/// use async_snmp::v3::EngineId;
+/// use std::net::Ipv4Addr;
+///
+/// let id = EngineId::ipv4(8072, Ipv4Addr::new(192, 0, 2, 1)).unwrap();
+/// assert_eq!(id.to_string(), "80001f8801c0000201 (net-snmp, IPv4 192.0.2.1)");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct EngineId(Bytes);
+
+/// The decoded layout of an [`EngineId`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EngineIdFormat {
+    /// Format 1: an IPv4 address.
+    Ipv4(Ipv4Addr),
+    /// Format 2: an IPv6 address.
+    Ipv6(Ipv6Addr),
+    /// Format 3: a MAC address.
+    Mac([u8; 6]),
+    /// Format 4: administratively assigned text.
+    Text(Bytes),
+    /// Format 5: administratively assigned octets.
+    Octets(Bytes),
+    /// Format 6: a local engine ID (RFC 5343 Section 3).
+    Local(Bytes),
+    /// net-snmp and UCD-SNMP format 128: four random octets and the
+    /// creation time, both in the agent's native byte order.
+    NetSnmpRandom {
+        /// Random octets.
+        random: [u8; 4],
+        /// Seconds since the Unix epoch when the ID was created.
+        time: [u8; 4],
+    },
+    /// Reserved or enterprise-specific formats, and RFC 3411 formats whose
+    /// data has the wrong length.
+    Other {
+        /// The format octet.
+        format: u8,
+        /// Octets following the format octet.
+        data: Bytes,
+    },
+    /// Cisco's RFC 1910 layout: two octets followed by a MAC address.
+    CiscoLegacy {
+        /// MAC address of the device.
+        mac: [u8; 6],
+    },
+    /// An RFC 1910 layout with enterprise-defined content.
+    Legacy([u8; 8]),
+    /// A value that follows neither layout, such as a random ID from
+    /// [`generate_engine_id`](super::generate_engine_id).
+    Opaque,
+}
+
+fn check_enterprise(enterprise: u32) -> Result<()> {
+    if enterprise & 0x8000_0000 != 0 {
+        return Err(Error::Config(
+            format!("enterprise number {enterprise} does not fit in 31 bits").into(),
+        )
+        .boxed());
+    }
+    Ok(())
+}
+
+impl EngineId {
+    /// The well-known local engine ID `0x8000000006` (RFC 5343 Section 3),
+    /// used to address the engine receiving a message in context engine ID
+    /// discovery.
+    pub const LOCAL: EngineId =
+        EngineId(Bytes::from_static(&[0x80, 0x00, 0x00, 0x00, FORMAT_LOCAL]));
+
+    fn with_format(enterprise: u32, format: u8, data: &[u8]) -> Result<Self> {
+        check_enterprise(enterprise)?;
+        let mut id = BytesMut::with_capacity(5 + data.len());
+        id.put_u32(enterprise | 0x8000_0000);
+        id.put_u8(format);
+        id.put_slice(data);
+        Ok(Self(id.freeze()))
+    }
+
+    fn with_data(enterprise: u32, format: u8, data: &[u8]) -> Result<Self> {
+        if data.len() > MAX_ENGINE_ID_DATA_LEN {
+            return Err(Error::Config(
+                format!(
+                    "engine ID data length {} exceeds {MAX_ENGINE_ID_DATA_LEN} octets",
+                    data.len()
+                )
+                .into(),
+            )
+            .boxed());
+        }
+        Self::with_format(enterprise, format, data)
+    }
+
+    /// Build a format 1 engine ID from an IPv4 address.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `enterprise` does not fit in 31 bits.
+    pub fn ipv4(enterprise: u32, addr: Ipv4Addr) -> Result<Self> {
+        Self::with_format(enterprise, FORMAT_IPV4, &addr.octets())
+    }
+
+    /// Build a format 2 engine ID from an IPv6 address.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `enterprise` does not fit in 31 bits.
+    pub fn ipv6(enterprise: u32, addr: Ipv6Addr) -> Result<Self> {
+        Self::with_format(enterprise, FORMAT_IPV6, &addr.octets())
+    }
+
+    /// Build a format 3 engine ID from a MAC address.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `enterprise` does not fit in 31 bits.
+    pub fn mac(enterprise: u32, mac: [u8; 6]) -> Result<Self> {
+        Self::with_format(enterprise, FORMAT_MAC, &mac)
+    }
+
+    /// Build a format 4 engine ID from administratively assigned text.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `enterprise` does not fit in 31 bits or
+    /// `text` is longer than [`MAX_ENGINE_ID_DATA_LEN`] octets.
+    pub fn text(enterprise: u32, text: &str) -> Result<Self> {
+        Self::with_data(enterprise, FORMAT_TEXT, text.as_bytes())
+    }
+
+    /// Build a format 5 engine ID from administratively assigned octets.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `enterprise` does not fit in 31 bits or
+    /// `octets` is longer than [`MAX_ENGINE_ID_DATA_LEN`] octets.
+    pub fn octets(enterprise: u32, octets: &[u8]) -> Result<Self> {
+        Self::with_data(enterprise, FORMAT_OCTETS, octets)
+    }
+
+    /// Build a format 6 local engine ID (RFC 5343 Section 3).
+    ///
+    /// [`EngineId::LOCAL`] is the well-known value with enterprise 0 and no
+    /// data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `enterprise` does not fit in 31 bits or
+    /// `data` is longer than [`MAX_ENGINE_ID_DATA_LEN`] octets.
+    pub fn local(enterprise: u32, data: &[u8]) -> Result<Self> {
+        Self::with_data(enterprise, FORMAT_LOCAL, data)
+    }
+
+    /// Wrap a received or configured engine ID.
+    ///
+    /// Any layout is accepted, including opaque values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the value fails [`validate_engine_id`].
+    pub fn from_bytes(bytes: impl Into<Bytes>) -> Result<Self> {
+        let bytes = bytes.into();
+        validate_engine_id(&bytes)?;
+        Ok(Self(bytes))
+    }
+
+    /// Wrap an engine ID that was validated when it was received.
+    pub(crate) fn from_validated(bytes: Bytes) -> Self {
+        Self(bytes)
+    }
+
+    /// Return the encoded engine ID.
+    #[must_use]
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.0
+    }
+
+    /// Return the encoded engine ID.
+    #[must_use]
+    pub fn into_bytes(self) -> Bytes {
+        self.0
+    }
+
+    fn is_legacy(&self) -> bool {
+        self.0.first().is_some_and(|&octet| octet & 0x80 == 0) && self.0.len() == LEGACY_LEN
+    }
+
+    /// Return the private enterprise number, or `None` for an opaque value.
+    #[must_use]
+    pub fn enterprise(&self) -> Option<u32> {
+        let prefix: [u8; 4] = self.0.get(..4)?.try_into().ok()?;
+        let value = u32::from_be_bytes(prefix);
+        if value & 0x8000_0000 != 0 && self.0.len() >= 5 {
+            Some(value & 0x7fff_ffff)
+        } else if self.is_legacy() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Decode the layout following the enterprise number.
+    #[must_use]
+    pub fn format(&self) -> EngineIdFormat {
+        let Some(enterprise) = self.enterprise() else {
+            return EngineIdFormat::Opaque;
+        };
+        if self.is_legacy() {
+            let data: [u8; 8] = self.0[4..].try_into().expect("legacy data is eight octets");
+            return if enterprise == ENTERPRISE_CISCO {
+                EngineIdFormat::CiscoLegacy {
+                    mac: data[2..].try_into().expect("MAC is six octets"),
+                }
+            } else {
+                EngineIdFormat::Legacy(data)
+            };
+        }
+
+        let format = self.0[4];
+        let data = self.0.slice(5..);
+        match (format, data.len()) {
+            (FORMAT_IPV4, 4) => {
+                EngineIdFormat::Ipv4(Ipv4Addr::from(<[u8; 4]>::try_from(&data[..]).unwrap()))
+            }
+            (FORMAT_IPV6, 16) => {
+                EngineIdFormat::Ipv6(Ipv6Addr::from(<[u8; 16]>::try_from(&data[..]).unwrap()))
+            }
+            (FORMAT_MAC, 6) => EngineIdFormat::Mac(data[..].try_into().unwrap()),
+            (FORMAT_TEXT, len) if len <= MAX_ENGINE_ID_DATA_LEN => EngineIdFormat::Text(data),
+            (FORMAT_OCTETS, len) if len <= MAX_ENGINE_ID_DATA_LEN => EngineIdFormat::Octets(data),
+            (FORMAT_LOCAL, len) if len <= MAX_ENGINE_ID_DATA_LEN => EngineIdFormat::Local(data),
+            (FORMAT_NET_SNMP_RANDOM, 8)
+                if matches!(enterprise, ENTERPRISE_NET_SNMP | ENTERPRISE_UCD_SNMP) =>
+            {
+                EngineIdFormat::NetSnmpRandom {
+                    random: data[..4].try_into().unwrap(),
+                    time: data[4..].try_into().unwrap(),
+                }
+            }
+            _ => EngineIdFormat::Other { format, data },
+        }
+    }
+}
+
+fn enterprise_name(enterprise: u32) -> Option<&'static str> {
+    match enterprise {
+        ENTERPRISE_CISCO => Some("Cisco"),
+        ENTERPRISE_UCD_SNMP => Some("UCD-SNMP"),
+        ENTERPRISE_NET_SNMP => Some("net-snmp"),
+        _ => None,
+    }
+}
+
+fn write_mac(f: &mut fmt::Formatter<'_>, mac: &[u8; 6]) -> fmt::Result {
+    for (i, octet) in mac.iter().enumerate() {
+        if i > 0 {
+            f.write_str(":")?;
+        }
+        write!(f, "{octet:02x}")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for EngineIdFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ipv4(addr) => write!(f, "IPv4 {addr}"),
+            Self::Ipv6(addr) => write!(f, "IPv6 {addr}"),
+            Self::Mac(mac) => {
+                f.write_str("MAC ")?;
+                write_mac(f, mac)
+            }
+            Self::Text(text) => write!(f, "text {:?}", String::from_utf8_lossy(text)),
+            Self::Octets(data) => write!(f, "octets {}", hex::Bytes(data)),
+            Self::Local(data) if data.is_empty() => f.write_str("local"),
+            Self::Local(data) => write!(f, "local {}", hex::Bytes(data)),
+            Self::NetSnmpRandom { random, time } => {
+                write!(f, "random {} time {}", hex::Bytes(random), hex::Bytes(time))
+            }
+            Self::Other { format, data } => write!(f, "format {format} {}", hex::Bytes(data)),
+            Self::CiscoLegacy { mac } => {
+                f.write_str("legacy MAC ")?;
+                write_mac(f, mac)
+            }
+            Self::Legacy(data) => write!(f, "legacy {}", hex::Bytes(data)),
+            Self::Opaque => f.write_str("opaque"),
+        }
+    }
+}
+
+impl fmt::Display for EngineId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::Bytes(&self.0))?;
+        let Some(enterprise) = self.enterprise() else {
+            return Ok(());
+        };
+        match enterprise_name(enterprise) {
+            Some(name) => write!(f, " ({name}, {})", self.format()),
+            None => write!(f, " (enterprise {enterprise}, {})", self.format()),
+        }
+    }
+}
+
+impl fmt::Debug for EngineId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EngineId({self})")
+    }
+}
+
+impl AsRef<[u8]> for EngineId {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<EngineId> for Bytes {
+    fn from(id: EngineId) -> Self {
+        id.0
+    }
+}
+
+impl TryFrom<Bytes> for EngineId {
+    type Error = Box<Error>;
+
+    fn try_from(bytes: Bytes) -> Result<Self> {
+        Self::from_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(hex_id: &str) -> EngineId {
+        EngineId::from_bytes(hex::decode(hex_id).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn constructors_encode_rfc3411_formats() {
+        let id = EngineId::ipv4(8072, Ipv4Addr::new(192, 0, 2, 1)).unwrap();
+        assert_eq!(hex::encode(id.as_bytes()), "80001f8801c0000201");
+        assert_eq!(id.enterprise(), Some(8072));
+        assert_eq!(
+            id.format(),
+            EngineIdFormat::Ipv4(Ipv4Addr::new(192, 0, 2, 1))
+        );
+
+        let addr: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let id = EngineId::ipv6(32473, addr).unwrap();
+        assert_eq!(id.as_bytes().len(), 21);
+        assert_eq!(id.format(), EngineIdFormat::Ipv6(addr));
+
+        let id = EngineId::mac(9, [0, 0, 0x0c, 2, 0x58, 8]).unwrap();
+        assert_eq!(hex::encode(id.as_bytes()), "800000090300000c025808");
+        assert_eq!(
+            id.to_string(),
+            "800000090300000c025808 (Cisco, MAC 00:00:0c:02:58:08)"
+        );
+
+        let id = EngineId::text(32473, "core-router").unwrap();
+        assert_eq!(
+            id.format(),
+            EngineIdFormat::Text(Bytes::from_static(b"core-router"))
+        );
+        assert!(
+            id.to_string()
+                .ends_with(r#"(enterprise 32473, text "core-router")"#)
+        );
+
+        let id = EngineId::octets(32473, &[1, 2, 3]).unwrap();
+        assert_eq!(
+            id.format(),
+            EngineIdFormat::Octets(Bytes::from_static(&[1, 2, 3]))
+        );
+
+        assert_eq!(hex::encode(EngineId::LOCAL.as_bytes()), "8000000006");
+        assert_eq!(EngineId::local(0, &[]).unwrap(), EngineId::LOCAL);
+        assert_eq!(
+            EngineId::LOCAL.to_string(),
+            "8000000006 (enterprise 0, local)"
+        );
+    }
+
+    #[test]
+    fn constructors_reject_invalid_input() {
+        assert!(EngineId::ipv4(0x8000_0000, Ipv4Addr::LOCALHOST).is_err());
+        assert!(EngineId::text(1, &"x".repeat(28)).is_err());
+        assert!(EngineId::text(1, &"x".repeat(27)).is_ok());
+        assert!(EngineId::octets(1, &[0; 28]).is_err());
+        assert!(EngineId::from_bytes(vec![0x80, 0, 0, 1]).is_err());
+        assert!(EngineId::from_bytes(vec![0; 12]).is_err());
+    }
+
+    #[test]
+    fn parses_net_snmp_random_format() {
+        let id = parse("80001f8880a1b2c3d45f5e1000");
+        assert_eq!(id.enterprise(), Some(8072));
+        assert_eq!(
+            id.format(),
+            EngineIdFormat::NetSnmpRandom {
+                random: [0xa1, 0xb2, 0xc3, 0xd4],
+                time: [0x5f, 0x5e, 0x10, 0x00],
+            }
+        );
+        assert_eq!(
+            id.to_string(),
+            "80001f8880a1b2c3d45f5e1000 (net-snmp, random a1b2c3d4 time 5f5e1000)"
+        );
+
+        // UCD-SNMP used the same layout under its own enterprise number.
+        assert!(matches!(
+            parse("800007e580a1b2c3d45f5e1000").format(),
+            EngineIdFormat::NetSnmpRandom { .. }
+        ));
+    }
+
+    #[test]
+    fn parses_cisco_legacy_format() {
+        let id = parse("00000009020000000c025808");
+        assert_eq!(id.enterprise(), Some(9));
+        assert_eq!(
+            id.format(),
+            EngineIdFormat::CiscoLegacy {
+                mac: [0, 0, 0x0c, 2, 0x58, 8]
+            }
+        );
+        assert_eq!(
+            id.to_string(),
+            "00000009020000000c025808 (Cisco, legacy MAC 00:00:0c:02:58:08)"
+        );
+
+        let id = parse("000001370102030405060708");
+        assert_eq!(
+            id.format(),
+            EngineIdFormat::Legacy([1, 2, 3, 4, 5, 6, 7, 8])
+        );
+    }
+
+    #[test]
+    fn parses_other_and_opaque_values() {
+        // Enterprise-specific format.
+        let id = parse("80007e59ff0102");
+        assert_eq!(
+            id.format(),
+            EngineIdFormat::Other {
+                format: 0xff,
+                data: Bytes::from_static(&[1, 2])
+            }
+        );
+        // Known format with the wrong data length.
+        assert!(matches!(
+            parse("80007e590101020304050607").format(),
+            EngineIdFormat::Other { format: 1, .. }
+        ));
+        // First bit clear but not the 12-octet legacy layout.
+        let id = parse("0102030405060708");
+        assert_eq!(id.enterprise(), None);
+        assert_eq!(id.format(), EngineIdFormat::Opaque);
+        assert_eq!(id.to_string(), "0102030405060708");
+    }
+}
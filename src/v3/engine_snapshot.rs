@@ -0,0 +1,494 @@
+//! Persistent snapshots of [`EngineCache`] contents.
+//!
+//! A snapshot records each target's discovered engine ID, the remote
+//! msgMaxSize, the entry's age, and the engine's boots/time when one was
+//! known. Restoring it after a process restart lets clients skip discovery.
+//! Restored boots/time is marked stale: it seeds the first outgoing request
+//! only, and the RFC 3414 Section 3.2 Step 7(b) checks treat the engine as
+//! unsynchronized until an authenticated message re-establishes its time.
+//!
+//! # Format
+//!
+//! [`EngineCacheSnapshot::to_bytes`] produces a self-describing binary format:
+//! a magic tag, a format version, the wall-clock time of the snapshot, and
+//! one record per target. [`EngineCacheSnapshot::from_bytes`] rejects
+//! unknown versions rather than guessing at their layout.
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+
+use super::EngineCache;
+use super::engine::validate_engine_id;
+use crate::error::{Error, Result};
+use crate::message_size::MessageSize;
+
+const MAGIC: &[u8; 4] = b"SNEC";
+
+const FAMILY_V4: u8 = 4;
+const FAMILY_V6: u8 = 6;
+
+/// One target's cached identity within a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct SnapshotEntry {
+    pub(super) target: SocketAddr,
+    pub(super) engine_id: Bytes,
+    pub(super) msg_max_size: MessageSize,
+    /// Time since the entry was last refreshed, as of the snapshot.
+    pub(super) age: Duration,
+    /// Estimated engine boots/time as of the snapshot.
+    pub(super) boots_time: Option<(u32, u32)>,
+}
+
+/// Serializable contents of an [`EngineCache`].
+///
+/// Produced by [`EngineCache::export`] and restored with
+/// [`EngineCache::import`]. Use [`to_bytes`](Self::to_bytes) and
+/// [`from_bytes`](Self::from_bytes) to store it.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use async_snmp::EngineCache;
+/// use async_snmp::v3::EngineCacheSnapshot;
+///
+/// # fn example() -> async_snmp::Result<()> {
+/// let cache = EngineCache::new();
+/// if let Ok(data) = std::fs::read("engines.bin") {
+///     cache.import(&EngineCacheSnapshot::from_bytes(&data)?);
+/// }
+///
+/// // ... poll devices ...
+///
+/// std::fs::write("engines.bin", cache.export().to_bytes()).unwrap();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineCacheSnapshot {
+    pub(super) taken_at: SystemTime,
+    pub(super) entries: Vec<SnapshotEntry>,
+}
+
+impl EngineCacheSnapshot {
+    /// Version written by [`to_bytes`](Self::to_bytes).
+    pub const FORMAT_VERSION: u8 = 1;
+
+    /// Return the wall-clock time at which the snapshot was taken.
+    #[must_use]
+    pub fn taken_at(&self) -> SystemTime {
+        self.taken_at
+    }
+
+    /// Return the number of target entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return whether the snapshot holds no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Encode the snapshot in the current binary format.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let taken_at = self
+            .taken_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut out = Vec::with_capacity(17 + self.entries.len() * 64);
+        out.extend_from_slice(MAGIC);
+        out.push(Self::FORMAT_VERSION);
+        out.extend_from_slice(&taken_at.to_be_bytes());
+        out.extend_from_slice(&(self.entries.len() as u32).to_be_bytes());
+        for entry in &self.entries {
+            match entry.target {
+                SocketAddr::V4(addr) => {
+                    out.push(FAMILY_V4);
+                    out.extend_from_slice(&addr.ip().octets());
+                    out.extend_from_slice(&addr.port().to_be_bytes());
+                }
+                SocketAddr::V6(addr) => {
+                    out.push(FAMILY_V6);
+                    out.extend_from_slice(&addr.ip().octets());
+                    out.extend_from_slice(&addr.port().to_be_bytes());
+                    out.extend_from_slice(&addr.scope_id().to_be_bytes());
+                }
+            }
+            // Engine IDs are at most 32 octets.
+            out.push(entry.engine_id.len() as u8);
+            out.extend_from_slice(&entry.engine_id);
+            out.extend_from_slice(&entry.msg_max_size.as_u32().to_be_bytes());
+            let age_ms = u64::try_from(entry.age.as_millis()).unwrap_or(u64::MAX);
+            out.extend_from_slice(&age_ms.to_be_bytes());
+            match entry.boots_time {
+                Some((boots, time)) => {
+                    out.push(1);
+                    out.extend_from_slice(&boots.to_be_bytes());
+                    out.extend_from_slice(&time.to_be_bytes());
+                }
+                None => out.push(0),
+            }
+        }
+        out
+    }
+
+    /// Decode a snapshot produced by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] for data without the snapshot tag, an
+    /// unsupported format version, truncated or trailing data, or an entry
+    /// with an invalid engine ID, msgMaxSize, or boots/time.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut reader = Reader { data };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(invalid("missing snapshot tag"));
+        }
+        let version = reader.u8()?;
+        if version != Self::FORMAT_VERSION {
+            return Err(invalid(&format!("unsupported format version {version}")));
+        }
+        let taken_at = UNIX_EPOCH
+            .checked_add(Duration::from_secs(reader.u64()?))
+            .ok_or_else(|| invalid("timestamp out of range"))?;
+        let count = reader.u32()? as usize;
+        // Each entry needs at least 21 octets; bound the allocation by the input.
+        let mut entries = Vec::with_capacity(count.min(reader.data.len() / 21));
+        for _ in 0..count {
+            let target = match reader.u8()? {
+                FAMILY_V4 => {
+                    let ip: [u8; 4] = reader.array()?;
+                    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::from(ip), reader.u16()?))
+                }
+                FAMILY_V6 => {
+                    let ip: [u8; 16] = reader.array()?;
+                    let port = reader.u16()?;
+                    let scope_id = reader.u32()?;
+                    SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::from(ip), port, 0, scope_id))
+                }
+                family => return Err(invalid(&format!("unknown address family {family}"))),
+            };
+            let engine_id_len = reader.u8()? as usize;
+            let engine_id = Bytes::copy_from_slice(reader.take(engine_id_len)?);
+            validate_engine_id(&engine_id)?;
+            let msg_max_size = MessageSize::new(reader.u32()? as usize)
+                .map_err(|error| invalid(&error.to_string()))?;
+            let age = Duration::from_millis(reader.u64()?);
+            let boots_time = match reader.u8()? {
+                0 => None,
+                1 => {
+                    let boots = reader.u32()?;
+                    let time = reader.u32()?;
+                    if boots > super::MAX_ENGINE_TIME || time > super::MAX_ENGINE_TIME {
+                        return Err(invalid("engine boots/time out of range"));
+                    }
+                    Some((boots, time))
+                }
+                flag => return Err(invalid(&format!("invalid time flag {flag}"))),
+            };
+            entries.push(SnapshotEntry {
+                target,
+                engine_id,
+                msg_max_size,
+                age,
+                boots_time,
+            });
+        }
+        if !reader.data.is_empty() {
+            return Err(invalid("trailing data"));
+        }
+        Ok(Self { taken_at, entries })
+    }
+}
+
+fn invalid(reason: &str) -> Box<Error> {
+    Error::Config(format!("invalid engine cache snapshot: {reason}").into()).boxed()
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(invalid("truncated data"));
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self
+            .take(N)?
+            .try_into()
+            .expect("slice has requested length"))
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_be_bytes(self.array()?))
+    }
+}
+
+/// Receives periodic [`EngineCacheSnapshot`]s.
+///
+/// The hook runs on the snapshot task, so it should return promptly; hand
+/// slow storage writes to a blocking thread or channel. Closures taking an
+/// `EngineCacheSnapshot` implement this trait.
+pub trait EngineCacheSnapshotHook: Send + Sync + 'static {
+    /// Handle one snapshot.
+    fn snapshot(&self, snapshot: EngineCacheSnapshot);
+}
+
+impl<F> EngineCacheSnapshotHook for F
+where
+    F: Fn(EngineCacheSnapshot) + Send + Sync + 'static,
+{
+    fn snapshot(&self, snapshot: EngineCacheSnapshot) {
+        self(snapshot);
+    }
+}
+
+/// Handle to a periodic snapshot task started by
+/// [`EngineCache::spawn_snapshots`].
+///
+/// Dropping the handle stops the task. The task also stops once every
+/// [`Arc`] to the cache has been dropped.
+#[derive(Debug)]
+#[must_use = "dropping the handle stops periodic snapshots"]
+pub struct EngineCacheSnapshotTask {
+    abort: crate::runtime::AbortHandle,
+}
+
+impl EngineCacheSnapshotTask {
+    /// Stop taking snapshots.
+    pub fn stop(self) {}
+}
+
+impl Drop for EngineCacheSnapshotTask {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+impl EngineCache {
+    /// Pass a snapshot of this cache to `hook` every `interval`.
+    ///
+    /// The first snapshot is taken one interval after the call. Take a final
+    /// snapshot with [`export`](Self::export) at shutdown so that identities
+    /// learned since the last interval are not lost.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `interval` is zero.
+    pub fn spawn_snapshots(
+        self: &Arc<Self>,
+        interval: Duration,
+        hook: impl EngineCacheSnapshotHook,
+    ) -> Result<EngineCacheSnapshotTask> {
+        if interval.is_zero() {
+            return Err(Error::Config("snapshot interval must be non-zero".into()).boxed());
+        }
+        let cache: Weak<Self> = Arc::downgrade(self);
+        let handle = crate::runtime::spawn(async move {
+            loop {
+                crate::runtime::sleep(interval).await;
+                let Some(cache) = cache.upgrade() else {
+                    break;
+                };
+                let snapshot = cache.export();
+                drop(cache);
+                tracing::debug!(target: "async_snmp::v3", { entries = snapshot.len() }, "engine cache snapshot");
+                hook.snapshot(snapshot);
+            }
+        });
+        Ok(EngineCacheSnapshotTask {
+            abort: handle.abort_handle(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v3::DiscoveredEngine;
+
+    fn engine(id: &'static [u8]) -> DiscoveredEngine {
+        DiscoveredEngine::new(Bytes::from_static(id), MessageSize::new(1472).unwrap()).unwrap()
+    }
+
+    fn populated_cache() -> EngineCache {
+        let cache = EngineCache::new();
+        cache
+            .insert_discovered(
+                "192.0.2.1:161".parse().unwrap(),
+                engine(b"\x80\x00\x1f\x88\x01"),
+            )
+            .unwrap();
+        cache
+            .seed_authenticated(
+                "[2001:db8::1]:1161".parse().unwrap(),
+                engine(b"\x80\x00\x1f\x88\x02"),
+                3,
+                1000,
+            )
+            .unwrap();
+        cache
+    }
+
+    #[test]
+    fn bytes_roundtrip() {
+        let snapshot = populated_cache().export();
+        assert_eq!(snapshot.len(), 2);
+        let decoded = EngineCacheSnapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+        // The wire format keeps whole seconds only.
+        assert_eq!(
+            decoded
+                .taken_at()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            snapshot
+                .taken_at()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        );
+        let mut expected = snapshot.entries.clone();
+        for entry in &mut expected {
+            entry.age = Duration::from_millis(entry.age.as_millis() as u64);
+        }
+        assert_eq!(decoded.entries, expected);
+    }
+
+    #[test]
+    fn from_bytes_rejects_malformed_data() {
+        let data = populated_cache().export().to_bytes();
+
+        let mut bad_magic = data.clone();
+        bad_magic[0] ^= 0xff;
+        let mut bad_version = data.clone();
+        bad_version[4] = EngineCacheSnapshot::FORMAT_VERSION + 1;
+        let mut trailing = data.clone();
+        trailing.push(0);
+
+        for input in [
+            &bad_magic[..],
+            &bad_version[..],
+            &data[..data.len() - 1],
+            &trailing[..],
+            &[],
+        ] {
+            let error = EngineCacheSnapshot::from_bytes(input).unwrap_err();
+            assert!(matches!(*error, Error::Config(_)), "{error}");
+        }
+    }
+
+    #[test]
+    fn import_marks_time_stale_until_authenticated() {
+        let snapshot = populated_cache().export();
+        let cache = EngineCache::new();
+        assert_eq!(cache.import(&snapshot), 2);
+
+        let discovered = cache.get(&"192.0.2.1:161".parse().unwrap()).unwrap();
+        assert!(!discovered.is_time_stale());
+        assert_eq!(discovered.outgoing_boots_time(), (0, 0));
+
+        let target: SocketAddr = "[2001:db8::1]:1161".parse().unwrap();
+        let state = cache.get(&target).unwrap();
+        assert!(state.is_time_stale());
+        assert!(state.authenticated_time().is_none());
+        assert_eq!(state.estimated_boots_time(), (0, 0));
+        let (boots, time) = state.outgoing_boots_time();
+        assert_eq!(boots, 3);
+        assert!((1000..1010).contains(&time));
+
+        cache
+            .seed_authenticated(target, engine(b"\x80\x00\x1f\x88\x02"), 4, 7)
+            .unwrap();
+        let state = cache.get(&target).unwrap();
+        assert!(!state.is_time_stale());
+        assert_eq!(state.authenticated_time().unwrap().boots(), 4);
+    }
+
+    #[test]
+    fn imported_time_is_not_a_step_7b_high_water_mark() {
+        let cache = EngineCache::new();
+        cache.import(&populated_cache().export());
+        let mut state = cache.get(&"[2001:db8::1]:1161".parse().unwrap()).unwrap();
+
+        // A message behind the imported tuple is still accepted as the first
+        // authenticated notion; the stale tuple is not a high-water mark.
+        assert!(state.check_and_update_timeliness(2, 5));
+        assert!(!state.is_time_stale());
+        assert_eq!(state.estimated_boots_time().0, 2);
+    }
+
+    #[test]
+    fn import_skips_expired_and_existing_entries() {
+        let mut snapshot = populated_cache().export();
+        snapshot.entries[0].age = Duration::from_secs(3600);
+
+        let cache = EngineCache::new();
+        let existing = snapshot.entries[1].target;
+        cache
+            .insert_discovered(existing, engine(b"\x80\x00\x1f\x88\x09"))
+            .unwrap();
+
+        assert_eq!(cache.import(&snapshot), 0);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(
+            cache.get(&existing).unwrap().engine_id().as_ref(),
+            b"\x80\x00\x1f\x88\x09"
+        );
+    }
+
+    #[test]
+    fn import_respects_capacity() {
+        let cache = EngineCache::new().with_max_capacity(1);
+        assert_eq!(cache.import(&populated_cache().export()), 1);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn periodic_snapshots_stop_when_handle_dropped() {
+        let cache = Arc::new(populated_cache().with_ttl(Duration::from_secs(3600)));
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let task = cache
+            .spawn_snapshots(Duration::from_secs(60), move |snapshot| {
+                let _ = sender.send(snapshot);
+            })
+            .unwrap();
+
+        let snapshot = receiver.recv().await.unwrap();
+        assert_eq!(snapshot.len(), 2);
+
+        task.stop();
+        assert!(receiver.recv().await.is_none());
+        assert!(
+            cache
+                .spawn_snapshots(Duration::ZERO, |_: EngineCacheSnapshot| {})
+                .is_err()
+        );
+    }
+}
@@ -1,7 +1,8 @@
 //! `SNMPv3` security module.
 //!
 //! Implements the User-based Security Model (USM) as defined in RFC 3414 and
-//! RFC 7860, including:
+//! RFC 7860, and the Transport Security Model (TSM) of RFC 5591 with RFC 6353
+//! certificate mapping. USM support includes:
 //!
 //! - USM security parameters encoding/decoding
 //! - Key localization (password-to-key derivation)
@@ -45,6 +46,8 @@ mod config;
 mod crypto;
 pub(crate) mod encode;
 mod engine;
+mod engine_id;
+mod engine_snapshot;
 #[cfg_attr(
     not(any(feature = "crypto-rustcrypto", feature = "crypto-fips")),
     doc(hidden)
@@ -59,7 +62,9 @@ mod engine;
 mod privacy;
 pub(crate) mod process;
 mod recency_map;
+pub(crate) mod remote_users;
 mod report;
+mod tsm;
 mod usm;
 
 #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
@@ -75,7 +80,9 @@ pub use config::DerivedKeys;
 #[cfg(not(any(feature = "crypto-rustcrypto", feature = "crypto-fips")))]
 pub(crate) use config::DerivedKeys;
 pub use config::{UsmConfig, UsmUser};
-pub use crypto::{CryptoBackend, CryptoError, CryptoResult};
+pub use crypto::{
+    CipherParams, CryptoBackend, CryptoError, CryptoProvider, CryptoResult, KeyHandle,
+};
 pub use engine::report_oids;
 pub use engine::{
     AuthenticatedEngineTime, DiscoveredEngine, EngineCache, EngineState, MAX_ENGINE_ID_LEN,
@@ -86,6 +93,8 @@ pub use engine::{
 pub(crate) use engine::{
     TimelinessCandidateOutcome, TimelinessPublicationOutcome, discovered_engine_state,
 };
+pub use engine_id::{EngineId, EngineIdFormat, MAX_ENGINE_ID_DATA_LEN};
+pub use engine_snapshot::{EngineCacheSnapshot, EngineCacheSnapshotHook, EngineCacheSnapshotTask};
 pub(crate) use privacy::PrivacyEncryptContext;
 pub use privacy::{
     DesSaltPersistenceError, DesSaltPersistenceOperation, DesSaltState, DesSaltStateError,
@@ -96,6 +105,10 @@ pub use privacy::{DesSaltReservation, PrivKey, SaltCounter};
 #[cfg(not(any(feature = "crypto-rustcrypto", feature = "crypto-fips")))]
 pub(crate) use privacy::{PrivKey, SaltCounter};
 pub use report::{MalformedReport, ReportStatus, classify_report};
+pub use tsm::{
+    CertToTsn, CertToTsnMapping, CertificateFingerprint, FingerprintAlgorithm, LOCAL_ENGINE_ID,
+    MAX_TM_SECURITY_NAME_LEN, ParseFingerprintError, TransportSecurity, TsmConfig,
+};
 pub use usm::UsmSecurityParams;
 
 /// Key extension strategy for privacy key derivation.
@@ -26,7 +26,7 @@ use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use bytes::Bytes;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use super::crypto::{CryptoBackend, CryptoError};
+use super::crypto::{CipherParams, CryptoBackend, CryptoError, ProviderKey, privacy_key_and_iv};
 use super::{AuthProtocol, PrivProtocol};
 
 /// Error type for privacy (encryption/decryption) operations.
@@ -462,6 +462,9 @@ pub struct PrivKey {
     protocol: PrivProtocol,
     #[zeroize(skip)]
     backend: CryptoBackend,
+    /// Set when a crypto provider holds the key; `key` is then empty.
+    #[zeroize(skip)]
+    provider: Option<ProviderKey>,
 }
 
 /// Thread-safe salt counter for shared use across privacy encryptions.
@@ -660,6 +663,7 @@ impl PrivKey {
             key,
             protocol: priv_protocol,
             backend: master.crypto_backend(),
+            provider: None,
         })
     }
 
@@ -702,9 +706,24 @@ impl PrivKey {
             key,
             protocol,
             backend,
+            provider: None,
         })
     }
 
+    /// Create a privacy key held by a crypto provider.
+    pub(crate) fn from_provider(
+        protocol: PrivProtocol,
+        provider: ProviderKey,
+        backend: CryptoBackend,
+    ) -> Self {
+        Self {
+            key: Vec::new(),
+            protocol,
+            backend,
+            provider: Some(provider),
+        }
+    }
+
     /// Returns the privacy protocol.
     pub fn protocol(&self) -> PrivProtocol {
         self.protocol
@@ -715,8 +734,19 @@ impl PrivKey {
         self.backend
     }
 
+    /// Return whether a [`CryptoProvider`](super::CryptoProvider) holds this key.
+    pub fn is_provider_held(&self) -> bool {
+        self.provider.is_some()
+    }
+
     /// Returns the encryption key portion.
+    ///
+    /// Keys held by a [`CryptoProvider`](super::CryptoProvider) return an
+    /// empty slice.
     pub fn encryption_key(&self) -> &[u8] {
+        if self.provider.is_some() {
+            return &[];
+        }
         match self.protocol {
             PrivProtocol::Des => &self.key[..8],
             PrivProtocol::Des3 => &self.key[..24],
@@ -726,6 +756,17 @@ impl PrivKey {
         }
     }
 
+    /// Returns the stored privacy secret covered by `usmUserPrivKeyChange`.
+    ///
+    /// This is the full secret for the protocol, including the DES and 3DES
+    /// pre-IV octets.
+    pub(crate) fn key_change_secret(&self) -> &[u8] {
+        if self.provider.is_some() {
+            return &[];
+        }
+        &self.key[..self.protocol.key_len()]
+    }
+
     /// Encrypt with already selected protocol-specific sender inputs.
     pub(crate) fn encrypt_with_context(
         &self,
@@ -744,47 +785,17 @@ impl PrivKey {
                 u64::from(reservation.salt),
             ),
             (
-                PrivProtocol::Aes128,
-                PrivacyEncryptContext::Aes {
-                    engine_boots,
-                    engine_time,
-                    salt_counter,
-                },
-            ) => self.encrypt_aes_cfb(
-                plaintext,
-                engine_boots,
-                engine_time,
-                salt_counter.next(),
-                16,
-            ),
-            (
-                PrivProtocol::Aes192Blumenthal | PrivProtocol::Aes192Reeder,
+                PrivProtocol::Aes128
+                | PrivProtocol::Aes192Blumenthal
+                | PrivProtocol::Aes192Reeder
+                | PrivProtocol::Aes256Blumenthal
+                | PrivProtocol::Aes256Reeder,
                 PrivacyEncryptContext::Aes {
                     engine_boots,
                     engine_time,
                     salt_counter,
                 },
-            ) => self.encrypt_aes_cfb(
-                plaintext,
-                engine_boots,
-                engine_time,
-                salt_counter.next(),
-                24,
-            ),
-            (
-                PrivProtocol::Aes256Blumenthal | PrivProtocol::Aes256Reeder,
-                PrivacyEncryptContext::Aes {
-                    engine_boots,
-                    engine_time,
-                    salt_counter,
-                },
-            ) => self.encrypt_aes_cfb(
-                plaintext,
-                engine_boots,
-                engine_time,
-                salt_counter.next(),
-                32,
-            ),
+            ) => self.encrypt_aes_cfb(plaintext, engine_boots, engine_time, salt_counter.next()),
             _ => Err(PrivacyError::SenderStateMismatch),
         }
     }
@@ -878,11 +889,6 @@ impl PrivKey {
         engine_boots: u32,
         salt_int: u64,
     ) -> PrivacyResult<(Bytes, Bytes)> {
-        // DES key is first 8 bytes
-        let key = &self.key[..8];
-        // Pre-IV is last 8 bytes of 16-byte privKey
-        let pre_iv = &self.key[8..16];
-
         // Salt = engineBoots (4 bytes MSB) || counter (4 bytes MSB)
         // We use the lower 32 bits of salt_int as the counter
         let mut salt = [0u8; 8];
@@ -890,14 +896,13 @@ impl PrivKey {
         salt[4..].copy_from_slice(&(salt_int as u32).to_be_bytes());
 
         // IV = pre-IV XOR salt
-        let mut iv = [0u8; 8];
-        for i in 0..8 {
-            iv[i] = pre_iv[i] ^ salt[i];
-        }
-
+        let params = CipherParams {
+            engine_boots,
+            engine_time: 0,
+            salt,
+        };
         let mut buffer = plaintext.to_vec();
-        self.backend
-            .encrypt(PrivProtocol::Des, key, &iv, &mut buffer)?;
+        self.encrypt_buffer(&params, &mut buffer)?;
 
         Ok((Bytes::from(buffer), Bytes::copy_from_slice(&salt)))
     }
@@ -912,23 +917,16 @@ impl PrivKey {
             });
         }
 
-        // DES key is first 8 bytes
-        let key = &self.key[..8];
-        // Pre-IV is last 8 bytes of 16-byte privKey
-        let pre_iv = &self.key[8..16];
-
-        // Salt is the privParameters
-        let salt = priv_params;
-
-        // IV = pre-IV XOR salt
-        let mut iv = [0u8; 8];
-        for i in 0..8 {
-            iv[i] = pre_iv[i] ^ salt[i];
-        }
-
+        // Salt is the privParameters; IV = pre-IV XOR salt
+        let params = CipherParams {
+            engine_boots: 0,
+            engine_time: 0,
+            salt: priv_params
+                .try_into()
+                .expect("privParameters length checked"),
+        };
         let mut buffer = ciphertext.to_vec();
-        self.backend
-            .decrypt(PrivProtocol::Des, key, &iv, &mut buffer)?;
+        self.decrypt_buffer(&params, &mut buffer)?;
 
         Ok(Bytes::from(buffer))
     }
@@ -940,25 +938,19 @@ impl PrivKey {
         engine_boots: u32,
         salt_int: u64,
     ) -> PrivacyResult<(Bytes, Bytes)> {
-        // 3DES key is first 24 bytes (K1, K2, K3)
-        let key = &self.key[..24];
-        // Pre-IV is bytes 24-31 of the 32-byte privKey
-        let pre_iv = &self.key[24..32];
-
         // Salt = engineBoots (4 bytes MSB) || counter (4 bytes MSB)
         let mut salt = [0u8; 8];
         salt[..4].copy_from_slice(&engine_boots.to_be_bytes());
         salt[4..].copy_from_slice(&(salt_int as u32).to_be_bytes());
 
-        // IV = pre-IV XOR salt
-        let mut iv = [0u8; 8];
-        for i in 0..8 {
-            iv[i] = pre_iv[i] ^ salt[i];
-        }
-
+        // IV = pre-IV XOR salt, with the pre-IV in bytes 24-31 of the key
+        let params = CipherParams {
+            engine_boots,
+            engine_time: 0,
+            salt,
+        };
         let mut buffer = plaintext.to_vec();
-        self.backend
-            .encrypt(PrivProtocol::Des3, key, &iv, &mut buffer)?;
+        self.encrypt_buffer(&params, &mut buffer)?;
 
         Ok((Bytes::from(buffer), Bytes::copy_from_slice(&salt)))
     }
@@ -973,23 +965,16 @@ impl PrivKey {
             });
         }
 
-        // 3DES key is first 24 bytes (K1, K2, K3)
-        let key = &self.key[..24];
-        // Pre-IV is bytes 24-31 of the 32-byte privKey
-        let pre_iv = &self.key[24..32];
-
-        // Salt is the privParameters
-        let salt = priv_params;
-
-        // IV = pre-IV XOR salt
-        let mut iv = [0u8; 8];
-        for i in 0..8 {
-            iv[i] = pre_iv[i] ^ salt[i];
-        }
-
+        // Salt is the privParameters; IV = pre-IV XOR salt
+        let params = CipherParams {
+            engine_boots: 0,
+            engine_time: 0,
+            salt: priv_params
+                .try_into()
+                .expect("privParameters length checked"),
+        };
         let mut buffer = ciphertext.to_vec();
-        self.backend
-            .decrypt(PrivProtocol::Des3, key, &iv, &mut buffer)?;
+        self.decrypt_buffer(&params, &mut buffer)?;
 
         Ok(Bytes::from(buffer))
     }
@@ -1001,25 +986,18 @@ impl PrivKey {
         engine_boots: u32,
         engine_time: u32,
         salt: u64,
-        key_len: usize,
     ) -> PrivacyResult<(Bytes, Bytes)> {
-        // AES key is first key_len bytes
-        let key = &self.key[..key_len];
-
-        // Salt as 8 bytes (big-endian)
-        let salt_bytes = salt.to_be_bytes();
-
         // IV = engineBoots (4) || engineTime (4) || salt (8) = 16 bytes
         // This is CONCATENATION, not XOR (unlike DES)
-        let mut iv = [0u8; 16];
-        iv[..4].copy_from_slice(&engine_boots.to_be_bytes());
-        iv[4..8].copy_from_slice(&engine_time.to_be_bytes());
-        iv[8..].copy_from_slice(&salt_bytes);
-
+        let params = CipherParams {
+            engine_boots,
+            engine_time,
+            salt: salt.to_be_bytes(),
+        };
         let mut buffer = plaintext.to_vec();
-        self.backend.encrypt(self.protocol, key, &iv, &mut buffer)?;
+        self.encrypt_buffer(&params, &mut buffer)?;
 
-        Ok((Bytes::from(buffer), Bytes::copy_from_slice(&salt_bytes)))
+        Ok((Bytes::from(buffer), Bytes::copy_from_slice(&params.salt)))
     }
 
     /// AES-CFB decryption (RFC 3826 Section 3.1.4).
@@ -1030,27 +1008,44 @@ impl PrivKey {
         engine_time: u32,
         priv_params: &[u8],
     ) -> PrivacyResult<Bytes> {
-        let key_len = match self.protocol {
-            PrivProtocol::Aes128 => 16,
-            PrivProtocol::Aes192Blumenthal | PrivProtocol::Aes192Reeder => 24,
-            PrivProtocol::Aes256Blumenthal | PrivProtocol::Aes256Reeder => 32,
-            _ => unreachable!(),
+        let params = CipherParams {
+            engine_boots,
+            engine_time,
+            salt: priv_params
+                .try_into()
+                .expect("privParameters length checked"),
         };
-
-        // AES key is first key_len bytes
-        let key = &self.key[..key_len];
-
-        // IV = engineBoots (4) || engineTime (4) || salt (8) = 16 bytes
-        let mut iv = [0u8; 16];
-        iv[..4].copy_from_slice(&engine_boots.to_be_bytes());
-        iv[4..8].copy_from_slice(&engine_time.to_be_bytes());
-        iv[8..].copy_from_slice(priv_params);
-
         let mut buffer = ciphertext.to_vec();
-        self.backend.decrypt(self.protocol, key, &iv, &mut buffer)?;
+        self.decrypt_buffer(&params, &mut buffer)?;
 
         Ok(Bytes::from(buffer))
     }
+
+    fn encrypt_buffer(&self, params: &CipherParams, buffer: &mut Vec<u8>) -> PrivacyResult<()> {
+        match &self.provider {
+            Some(held) => held
+                .provider
+                .encrypt(&held.handle, self.protocol, params, buffer)?,
+            None => {
+                let (key, iv) = privacy_key_and_iv(self.protocol, &self.key, params)?;
+                self.backend.encrypt(self.protocol, key, &iv, buffer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn decrypt_buffer(&self, params: &CipherParams, buffer: &mut [u8]) -> PrivacyResult<()> {
+        match &self.provider {
+            Some(held) => held
+                .provider
+                .decrypt(&held.handle, self.protocol, params, buffer)?,
+            None => {
+                let (key, iv) = privacy_key_and_iv(self.protocol, &self.key, params)?;
+                self.backend.decrypt(self.protocol, key, &iv, buffer)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl std::fmt::Debug for PrivKey {
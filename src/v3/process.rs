@@ -9,12 +9,15 @@
 
 use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Mutex;
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
 
 use bytes::Bytes;
 
+use smallvec::SmallVec;
+
 use super::{DerivedKeys, UsmUser};
+use crate::credentials::CredentialSnapshot;
 use crate::error::{Error, Result};
 use crate::message::{
     MsgGlobalData, RawMsgData, RawV3Message, ScopedPdu, SecurityLevel, combine_staged_v3_anomalies,
@@ -22,9 +25,11 @@ use crate::message::{
 };
 use crate::message_size::MessageSize;
 use crate::oid::Oid;
+use crate::security_event::{SecurityEventHub, SecurityEventKind};
 use crate::v3::auth::verify_message;
 use crate::v3::encode::encode_v3_report;
 use crate::v3::recency_map::RecencyMap;
+use crate::v3::remote_users::RemoteUsmUsers;
 use crate::v3::{
     EngineState, LocalizedKey, UsmSecurityParams, in_authoritative_time_window, report_oids,
     validate_engine_id,
@@ -90,6 +95,19 @@ impl UsmFailure {
             Self::DecryptionErrors => report_oids::decryption_errors(),
         }
     }
+
+    /// Audit event for this failure. Unknown engine IDs are not reported:
+    /// every discovery request counts as one.
+    fn security_event_kind(self) -> Option<SecurityEventKind> {
+        match self {
+            Self::UnknownEngineIds => None,
+            Self::UnknownUserNames => Some(SecurityEventKind::UnknownUserName),
+            Self::WrongDigests => Some(SecurityEventKind::WrongDigest),
+            Self::NotInTimeWindows => Some(SecurityEventKind::NotInTimeWindow),
+            Self::UnsupportedSecLevels => Some(SecurityEventKind::UnsupportedSecurityLevel),
+            Self::DecryptionErrors => Some(SecurityEventKind::DecryptionError),
+        }
+    }
 }
 
 /// RFC 3412 MPD statistics counters. Only the agent exposes the MPD MIB;
@@ -115,6 +133,7 @@ pub(crate) enum V3Role<'a> {
     /// seeded from the first authenticated message.
     Receiver {
         remote_engines: &'a Mutex<RemoteEngineTable>,
+        remote_users: &'a Mutex<RemoteUsmUsers>,
     },
 }
 
@@ -135,15 +154,23 @@ impl V3Role<'_> {
         #[cfg(any(feature = "agent", test))]
         match self {
             Self::Authoritative => unreachable!("authoritative role rejected a foreign engine ID"),
-            Self::Receiver { remote_engines } => remote_engines,
+            Self::Receiver { remote_engines, .. } => remote_engines,
         }
 
         #[cfg(not(any(feature = "agent", test)))]
         {
-            let Self::Receiver { remote_engines } = self;
+            let Self::Receiver { remote_engines, .. } = self;
             remote_engines
         }
     }
+
+    fn remote_users(&self) -> Option<&Mutex<RemoteUsmUsers>> {
+        match self {
+            #[cfg(any(feature = "agent", test))]
+            Self::Authoritative => None,
+            Self::Receiver { remote_users, .. } => Some(remote_users),
+        }
+    }
 }
 
 /// Local engine identity, user table, and counters for USM processing.
@@ -164,8 +191,12 @@ pub(crate) struct V3LocalContext<'a> {
     /// Local policy bound for outbound responses and Reports.
     pub(crate) outbound_limit: usize,
     pub(crate) usm_users: &'a HashMap<Bytes, UsmUser>,
+    /// Users from a credential provider, consulted after `usm_users`.
+    pub(crate) rotating_users: Option<&'a CredentialSnapshot>,
     pub(crate) stats: &'a UsmStats,
     pub(crate) mpd: Option<MpdCounters<'a>>,
+    /// Audit subscribers notified of each failed check.
+    pub(crate) security_events: Option<&'a SecurityEventHub>,
     pub(crate) source: SocketAddr,
 }
 
@@ -182,7 +213,7 @@ pub(crate) struct V3InboundMessage {
     pub(crate) usm_params: UsmSecurityParams,
     pub(crate) scoped_pdu: ScopedPdu,
     pub(crate) security_level: SecurityLevel,
-    pub(crate) derived_keys: DerivedKeys,
+    pub(crate) derived_keys: Arc<DerivedKeys>,
     pub(crate) decode_anomalies: Vec<crate::DecodeAnomaly>,
 }
 
@@ -267,6 +298,9 @@ pub(crate) fn process_v3_inbound(
     // unless `auth_key` is given (notInTimeWindows, RFC 3414 3.2 Step 7a).
     let fail = |failure: UsmFailure, auth_key: Option<&LocalizedKey>| -> Result<V3Inbound> {
         let count = ctx.stats.count(failure);
+        if let (Some(events), Some(kind)) = (ctx.security_events, failure.security_event_kind()) {
+            events.user(kind, source, &usm_params.username);
+        }
         // RFC 3412 Section 7.1 Step 3: a Report may only be sent when the
         // PDU is Confirmed Class or, when the PDU class cannot be determined
         // (the case here: the message failed USM processing), when the
@@ -316,37 +350,65 @@ pub(crate) fn process_v3_inbound(
     }
 
     // RFC 3414 Section 3.2 Step 4: the user must exist in the local user
-    // database regardless of security level.
-    let Some(user_config) = ctx.usm_users.get(&usm_params.username) else {
-        tracing::debug!(target: "async_snmp::v3", { snmp.source = %source, snmp.username = ?usm_params.username }, "unknown user");
-        return fail(UsmFailure::UnknownUserNames, None);
-    };
+    // database regardless of security level. For a remote authoritative
+    // engine, users registered for that engine ID take precedence over the
+    // receiver's table. During a credential rotation overlap one username
+    // may have several keys; each is a candidate for Step 6.
+    let remote_users = role.remote_users().filter(|_| !engine_is_local);
+    let remote_keys = remote_users.and_then(|users| {
+        users
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(&usm_params.engine_id, &usm_params.username)
+    });
     // Keys are localized to the message's (authoritative) engine ID: the
     // local engine ID for the authoritative role and informs, the sender's
     // for traps from a remote authoritative engine.
-    let derived_keys = user_config
-        .derive_keys(&usm_params.engine_id)
-        .map_err(|e| Error::Config(e.to_string().into()).boxed())?;
+    let localize = |user_config: &UsmUser| {
+        user_config
+            .derive_keys(&usm_params.engine_id)
+            .map(Arc::new)
+            .map_err(|e| Error::Config(e.to_string().into()).boxed())
+    };
+    let mut candidates: SmallVec<[(Arc<DerivedKeys>, bool); 2]> = SmallVec::new();
+    match remote_keys {
+        Some(keys) => candidates.push((keys, false)),
+        None => {
+            if let Some(user_config) = ctx.usm_users.get(&usm_params.username) {
+                candidates.push((localize(user_config)?, remote_users.is_some()));
+            }
+        }
+    }
+    // Rotating users are never cached: their keys change with the next
+    // rotation.
+    for user_config in ctx
+        .rotating_users
+        .into_iter()
+        .flat_map(|rotating| rotating.usm_users(&usm_params.username))
+    {
+        candidates.push((localize(user_config)?, false));
+    }
+    if candidates.is_empty() {
+        tracing::debug!(target: "async_snmp::v3", { snmp.source = %source, snmp.username = ?usm_params.username }, "unknown user");
+        return fail(UsmFailure::UnknownUserNames, None);
+    }
 
-    // RFC 3414 Section 3.2 Step 5: the user must support the requested
-    // security level, checked before authentication (Step 6) and
-    // timeliness (Step 7).
-    if security_level.requires_auth() {
-        let supported = derived_keys.auth_key.is_some()
-            && (security_level != SecurityLevel::AuthPriv || derived_keys.priv_key.is_some());
-        if !supported {
+    let (derived_keys, localized_on_demand) = if security_level.requires_auth() {
+        // RFC 3414 Section 3.2 Step 5: the user must support the requested
+        // security level, checked before authentication (Step 6) and
+        // timeliness (Step 7).
+        let supports_level = |keys: &DerivedKeys| {
+            keys.auth_key.is_some()
+                && (security_level != SecurityLevel::AuthPriv || keys.priv_key.is_some())
+        };
+        candidates.retain(|(keys, _)| supports_level(keys));
+        if candidates.is_empty() {
             tracing::debug!(target: "async_snmp::v3", { snmp.source = %source, snmp.username = ?usm_params.username }, "user does not support requested security level");
             return fail(UsmFailure::UnsupportedSecLevels, None);
         }
-    }
 
-    if security_level.requires_auth() {
-        // RFC 3414 Section 3.2 Step 6: verify authentication. The auth key
-        // is guaranteed present by the Step 5 check above.
-        let auth_key = derived_keys
-            .auth_key
-            .as_ref()
-            .expect("authenticated message without an auth key is rejected at Step 5");
+        // RFC 3414 Section 3.2 Step 6: verify authentication, selecting the
+        // first candidate whose key authenticates the message.
         let Some((auth_offset, auth_len)) = UsmSecurityParams::find_auth_params_offset(&data)
         else {
             // The USM sequence was structurally decoded above, so this is
@@ -355,12 +417,33 @@ pub(crate) fn process_v3_inbound(
             tracing::debug!(target: "async_snmp::v3", { source = %source }, "could not locate authentication parameters");
             return fail(UsmFailure::WrongDigests, None);
         };
-        if !verify_message(auth_key, &authenticated_data, auth_offset, auth_len)
-            .map_err(|_| Error::Auth { target: source }.boxed())?
-        {
+        let mut authenticated = None;
+        for (keys, cache) in candidates {
+            let auth_key = keys
+                .auth_key
+                .as_ref()
+                .expect("candidates without an auth key are removed at Step 5");
+            if verify_message(auth_key, &authenticated_data, auth_offset, auth_len)
+                .map_err(|_| Error::Auth { target: source }.boxed())?
+            {
+                authenticated = Some((keys, cache));
+                break;
+            }
+        }
+        let Some(authenticated) = authenticated else {
             tracing::debug!(target: "async_snmp::v3", { snmp.source = %source }, "authentication failed");
             return fail(UsmFailure::WrongDigests, None);
-        }
+        };
+        authenticated
+    } else {
+        candidates.swap_remove(0)
+    };
+
+    if security_level.requires_auth() {
+        let auth_key = derived_keys
+            .auth_key
+            .as_ref()
+            .expect("authenticated message without an auth key is rejected at Step 5");
 
         // RFC 3414 Section 3.2 Step 7: timeliness.
         if engine_is_local {
@@ -425,9 +508,29 @@ pub(crate) fn process_v3_inbound(
                 timely
             };
             if !timely {
+                if let Some(events) = ctx.security_events {
+                    events.user(
+                        SecurityEventKind::NotInTimeWindow,
+                        source,
+                        &usm_params.username,
+                    );
+                }
                 return Ok(V3Inbound::RemoteNotInTimeWindow);
             }
         }
+
+        // Cache a localization only once it has authenticated a message, so
+        // spoofed engine IDs cannot displace keys in use.
+        if localized_on_demand && let Some(remote_users) = remote_users {
+            remote_users
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .cache(
+                    Bytes::copy_from_slice(&usm_params.engine_id),
+                    Bytes::copy_from_slice(&usm_params.username),
+                    derived_keys.clone(),
+                );
+        }
     }
 
     // RFC 3414 does not assign a failure counter to extra auth/priv fields at
@@ -536,8 +639,10 @@ mod tests {
             decode_config: crate::DecodeConfig::default(),
             outbound_limit: 8192,
             usm_users,
+            rotating_users: None,
             stats,
             mpd,
+            security_events: None,
             source: "127.0.0.1:9999".parse().unwrap(),
         }
     }
@@ -853,8 +958,10 @@ mod tests {
             b"",
         );
         let remote_engines = Mutex::new(RemoteEngineTable::new(16));
+        let remote_users = Mutex::new(RemoteUsmUsers::new(16));
         let role = V3Role::Receiver {
             remote_engines: &remote_engines,
+            remote_users: &remote_users,
         };
 
         let outcome = process_v3_inbound(data, &ctx, &role).unwrap();
@@ -1313,8 +1420,10 @@ mod tests {
         let stats = UsmStats::default();
         let ctx = test_ctx(&engine_id, &users, &stats, None);
         let remote_engines = Mutex::new(RemoteEngineTable::new(16));
+        let remote_users = Mutex::new(RemoteUsmUsers::new(16));
         let receiver = V3Role::Receiver {
             remote_engines: &remote_engines,
+            remote_users: &remote_users,
         };
 
         for invalid in [
@@ -1357,8 +1466,10 @@ mod tests {
         assert_eq!(failure, UsmFailure::UnknownEngineIds);
 
         let remote_engines = Mutex::new(RemoteEngineTable::new(16));
+        let remote_users = Mutex::new(RemoteUsmUsers::new(16));
         let role = V3Role::Receiver {
             remote_engines: &remote_engines,
+            remote_users: &remote_users,
         };
         let outcome = process_v3_inbound(data, &ctx, &role).unwrap();
         assert!(
@@ -0,0 +1,132 @@
+//! USM keys for remote authoritative engines.
+//!
+//! A notification receiver is non-authoritative for traps, so each sender's
+//! engine ID localizes the keys its messages are authenticated with. Users
+//! registered for one engine ID (net-snmp's `createUser -e`) take precedence;
+//! otherwise a user from the receiver's table is localized on demand and the
+//! result is kept in a bounded cache.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use bytes::Bytes;
+
+use super::DerivedKeys;
+use super::recency_map::RecencyMap;
+
+/// Key of an (engine ID, username) pair.
+type EngineUserKey = (Bytes, Bytes);
+
+/// Engine-scoped USM users and cached on-demand localizations.
+pub(crate) struct RemoteUsmUsers {
+    scoped: HashMap<EngineUserKey, Arc<DerivedKeys>>,
+    localized: RecencyMap<EngineUserKey, Arc<DerivedKeys>>,
+}
+
+impl RemoteUsmUsers {
+    /// Create a table whose on-demand cache holds at most `cache_capacity`
+    /// localizations.
+    pub(crate) fn new(cache_capacity: usize) -> Self {
+        Self {
+            scoped: HashMap::new(),
+            localized: RecencyMap::new(cache_capacity),
+        }
+    }
+
+    /// Register keys localized to `engine_id` for `username`, returning
+    /// whether an entry was replaced.
+    pub(crate) fn insert_scoped(
+        &mut self,
+        engine_id: Bytes,
+        username: Bytes,
+        keys: DerivedKeys,
+    ) -> bool {
+        self.scoped
+            .insert((engine_id, username), Arc::new(keys))
+            .is_some()
+    }
+
+    /// Remove an engine-scoped user, returning whether it existed.
+    pub(crate) fn remove_scoped(&mut self, engine_id: &[u8], username: &[u8]) -> bool {
+        self.scoped
+            .remove(&(
+                Bytes::copy_from_slice(engine_id),
+                Bytes::copy_from_slice(username),
+            ))
+            .is_some()
+    }
+
+    /// Return the engine-scoped keys for a user, or keys cached by an
+    /// earlier on-demand localization.
+    pub(crate) fn get(&mut self, engine_id: &Bytes, username: &Bytes) -> Option<Arc<DerivedKeys>> {
+        let key = (engine_id.clone(), username.clone());
+        if let Some(keys) = self.scoped.get(&key) {
+            return Some(keys.clone());
+        }
+        self.localized
+            .update(&key, |keys| (keys.clone(), Instant::now()))
+    }
+
+    /// Cache an on-demand localization, evicting the least recently used
+    /// entry when the cache is full.
+    pub(crate) fn cache(&mut self, engine_id: Bytes, username: Bytes, keys: Arc<DerivedKeys>) {
+        self.localized
+            .insert((engine_id, username), keys, Instant::now());
+    }
+
+    #[cfg(test)]
+    pub(crate) fn cached_len(&self) -> usize {
+        self.localized.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_keys() -> DerivedKeys {
+        DerivedKeys {
+            auth_key: None,
+            priv_key: None,
+        }
+    }
+
+    #[test]
+    fn scoped_users_take_precedence_over_cached_localizations() {
+        let engine = Bytes::from_static(b"\x80\x00\x00\x09\x04remote");
+        let user = Bytes::from_static(b"trapuser");
+        let mut users = RemoteUsmUsers::new(4);
+        let cached = Arc::new(no_keys());
+        users.cache(engine.clone(), user.clone(), cached.clone());
+        assert!(Arc::ptr_eq(&users.get(&engine, &user).unwrap(), &cached));
+
+        assert!(!users.insert_scoped(engine.clone(), user.clone(), no_keys()));
+        assert!(!Arc::ptr_eq(&users.get(&engine, &user).unwrap(), &cached));
+
+        assert!(users.remove_scoped(&engine, &user));
+        assert!(!users.remove_scoped(&engine, &user));
+        assert!(Arc::ptr_eq(&users.get(&engine, &user).unwrap(), &cached));
+    }
+
+    #[test]
+    fn localization_cache_is_bounded() {
+        let user = Bytes::from_static(b"trapuser");
+        let mut users = RemoteUsmUsers::new(2);
+        for i in 0..5u8 {
+            let engine = Bytes::from(vec![0x80, 0, 0, 9, 5, i]);
+            users.cache(engine, user.clone(), Arc::new(no_keys()));
+        }
+        assert_eq!(users.cached_len(), 2);
+        assert!(
+            users
+                .get(&Bytes::from(vec![0x80, 0, 0, 9, 5, 0]), &user)
+                .is_none()
+        );
+        assert!(
+            users
+                .get(&Bytes::from(vec![0x80, 0, 0, 9, 5, 4]), &user)
+                .is_some()
+        );
+    }
+}
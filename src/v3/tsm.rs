@@ -0,0 +1,852 @@
+//! Transport Security Model (RFC 5591) and certificate identity mapping.
+//!
+//! TSM delegates authentication and privacy to a secure transport such as
+//! TLS or DTLS (RFC 6353). The transport authenticates the peer's X.509
+//! certificate and derives a `tmSecurityName` from it; the security level is
+//! a property of the session, not of per-message keys. msgSecurityParameters
+//! is always empty and msgData is always a plaintext scoped PDU.
+//!
+//! [`CertToTsn`] implements the `snmpTlstmCertToTSNTable` lookup: ordered
+//! entries match a certificate fingerprint anywhere in the presented chain
+//! and map the leaf certificate to a security name.
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use bytes::Bytes;
+
+use super::{AuthProtocol, CryptoBackend};
+use crate::ber::Decoder;
+use crate::error::{Error, Result};
+use crate::message::SecurityLevel;
+use crate::oid::Oid;
+
+/// RFC 5343 `localEngineID`, addressing whichever engine receives the request.
+pub const LOCAL_ENGINE_ID: [u8; 5] = [0x80, 0x00, 0x00, 0x00, 0x06];
+
+/// Maximum length of a derived `tmSecurityName` (RFC 6353 Section 5.3.1).
+pub const MAX_TM_SECURITY_NAME_LEN: usize = 32;
+
+/// Hash algorithm of a certificate fingerprint.
+///
+/// Values follow the TLS `HashAlgorithm` registry used by RFC 6353's
+/// `SnmpTLSFingerprint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FingerprintAlgorithm {
+    /// MD5 (1). Supported for interoperability with existing tables only.
+    Md5 = 1,
+    /// SHA-1 (2).
+    Sha1 = 2,
+    /// SHA-224 (3).
+    Sha224 = 3,
+    /// SHA-256 (4).
+    Sha256 = 4,
+    /// SHA-384 (5).
+    Sha384 = 5,
+    /// SHA-512 (6).
+    Sha512 = 6,
+}
+
+impl FingerprintAlgorithm {
+    const ALL: [Self; 6] = [
+        Self::Md5,
+        Self::Sha1,
+        Self::Sha224,
+        Self::Sha256,
+        Self::Sha384,
+        Self::Sha512,
+    ];
+
+    /// Create from the TLS `HashAlgorithm` identifier.
+    #[must_use]
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|algorithm| *algorithm as u8 == value)
+    }
+
+    /// Returns the TLS `HashAlgorithm` identifier.
+    #[must_use]
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the digest length in octets.
+    #[must_use]
+    pub fn digest_len(self) -> usize {
+        self.auth_protocol().digest_len()
+    }
+
+    fn auth_protocol(self) -> AuthProtocol {
+        match self {
+            Self::Md5 => AuthProtocol::Md5,
+            Self::Sha1 => AuthProtocol::Sha1,
+            Self::Sha224 => AuthProtocol::Sha224,
+            Self::Sha256 => AuthProtocol::Sha256,
+            Self::Sha384 => AuthProtocol::Sha384,
+            Self::Sha512 => AuthProtocol::Sha512,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Md5 => "md5",
+            Self::Sha1 => "sha1",
+            Self::Sha224 => "sha224",
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+        }
+    }
+}
+
+impl fmt::Display for FingerprintAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error returned when parsing a certificate fingerprint fails.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "invalid certificate fingerprint '{input}'; expected '<md5|sha1|sha224|sha256|sha384|sha512>:<hex digest>'"
+)]
+pub struct ParseFingerprintError {
+    input: String,
+}
+
+/// Hash of a DER-encoded X.509 certificate.
+///
+/// Displays as `sha256:AB:CD:...` and parses that form, with or without the
+/// colon separators between digest octets.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CertificateFingerprint {
+    algorithm: FingerprintAlgorithm,
+    digest: Bytes,
+}
+
+impl CertificateFingerprint {
+    /// Create a fingerprint from a precomputed digest.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] when the digest length does not match the
+    /// algorithm.
+    pub fn new(algorithm: FingerprintAlgorithm, digest: impl Into<Bytes>) -> Result<Self> {
+        let digest = digest.into();
+        if digest.len() != algorithm.digest_len() {
+            return Err(Error::Config(
+                format!(
+                    "{algorithm} fingerprint must be {} octets, got {}",
+                    algorithm.digest_len(),
+                    digest.len()
+                )
+                .into(),
+            )
+            .boxed());
+        }
+        Ok(Self { algorithm, digest })
+    }
+
+    /// Hash a DER-encoded certificate with the default crypto backend.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] when no backend is compiled or the backend
+    /// does not support the algorithm.
+    pub fn compute(algorithm: FingerprintAlgorithm, certificate_der: &[u8]) -> Result<Self> {
+        let digest = CryptoBackend::require_default()
+            .and_then(|backend| backend.hash(algorithm.auth_protocol(), certificate_der))
+            .map_err(|error| {
+                Error::Config(format!("cannot compute {algorithm} fingerprint: {error}").into())
+                    .boxed()
+            })?;
+        Ok(Self {
+            algorithm,
+            digest: Bytes::from(digest),
+        })
+    }
+
+    /// Decode an RFC 6353 `SnmpTLSFingerprint` (algorithm octet, then digest).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] for an unknown algorithm or a digest of the
+    /// wrong length.
+    pub fn from_snmp_tls_fingerprint(value: &[u8]) -> Result<Self> {
+        let (&algorithm, digest) = value
+            .split_first()
+            .ok_or_else(|| Error::Config("empty SnmpTLSFingerprint".into()).boxed())?;
+        let algorithm = FingerprintAlgorithm::from_u8(algorithm).ok_or_else(|| {
+            Error::Config(format!("unknown fingerprint hash algorithm {algorithm}").into()).boxed()
+        })?;
+        Self::new(algorithm, Bytes::copy_from_slice(digest))
+    }
+
+    /// Encode as an RFC 6353 `SnmpTLSFingerprint`.
+    #[must_use]
+    pub fn to_snmp_tls_fingerprint(&self) -> Bytes {
+        let mut value = Vec::with_capacity(1 + self.digest.len());
+        value.push(self.algorithm.as_u8());
+        value.extend_from_slice(&self.digest);
+        Bytes::from(value)
+    }
+
+    /// Returns the hash algorithm.
+    #[must_use]
+    pub fn algorithm(&self) -> FingerprintAlgorithm {
+        self.algorithm
+    }
+
+    /// Returns the digest octets.
+    #[must_use]
+    pub fn digest(&self) -> &Bytes {
+        &self.digest
+    }
+
+    /// Returns whether `certificate_der` hashes to this fingerprint.
+    ///
+    /// A certificate that cannot be hashed with the configured backend never
+    /// matches.
+    #[must_use]
+    pub fn matches(&self, certificate_der: &[u8]) -> bool {
+        Self::compute(self.algorithm, certificate_der).is_ok_and(|computed| computed == *self)
+    }
+}
+
+impl fmt::Display for CertificateFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.algorithm)?;
+        for (index, octet) in self.digest.iter().enumerate() {
+            if index > 0 {
+                f.write_str(":")?;
+            }
+            write!(f, "{octet:02X}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CertificateFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CertificateFingerprint({self})")
+    }
+}
+
+impl FromStr for CertificateFingerprint {
+    type Err = ParseFingerprintError;
+
+    fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
+        let error = || ParseFingerprintError {
+            input: input.to_owned(),
+        };
+        let (name, hex_digest) = input.trim().split_once(':').ok_or_else(error)?;
+        let name = name.to_ascii_lowercase().replace('-', "");
+        let algorithm = FingerprintAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == name)
+            .ok_or_else(error)?;
+        let digest = crate::format::hex::decode_relaxed(hex_digest).map_err(|_| error())?;
+        Self::new(algorithm, digest).map_err(|_| error())
+    }
+}
+
+/// How a matched `certToTSN` entry derives the `tmSecurityName`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CertToTsnMapping {
+    /// Use this name verbatim (`snmpTlstmCertSpecified`).
+    Specified(Bytes),
+    /// The first subjectAltName rfc822Name, with its host part lowercased
+    /// (`snmpTlstmCertSANRFC822Name`).
+    SanRfc822Name,
+    /// The first subjectAltName dNSName, lowercased
+    /// (`snmpTlstmCertSANDNSName`).
+    SanDnsName,
+    /// The first subjectAltName iPAddress: dotted decimal for IPv4, 32
+    /// lowercase hex digits for IPv6 (`snmpTlstmCertSANIpAddress`).
+    SanIpAddress,
+    /// The first rfc822Name, dNSName, or iPAddress subjectAltName, mapped as
+    /// above (`snmpTlstmCertSANAny`).
+    SanAny,
+    /// The subject commonName (`snmpTlstmCertCommonName`). RFC 6353
+    /// discourages this mapping because the CN is not constrained.
+    CommonName,
+}
+
+/// Ordered `certToTSN` table (RFC 6353 Section 5.3.1 and the
+/// `snmpTlstmCertToTSNTable`).
+///
+/// Entries are consulted in insertion order. An entry applies when its
+/// fingerprint matches any certificate in the presented chain, so a CA
+/// fingerprint can map every certificate it issued. If an applicable entry's
+/// mapping cannot produce a name of at most 32 octets from the leaf
+/// certificate, the next entry is tried.
+///
+/// ```rust
+/// use async_snmp::v3::{CertToTsn, CertToTsnMapping, CertificateFingerprint};
+///
+/// let ca: CertificateFingerprint = "sha256:00:11:22:33:44:55:66:77:88:99:AA:BB:CC:DD:EE:FF:\
+///     00:11:22:33:44:55:66:77:88:99:AA:BB:CC:DD:EE:FF"
+///     .parse()
+///     .unwrap();
+/// let map = CertToTsn::new().entry(ca, CertToTsnMapping::SanDnsName);
+/// assert_eq!(map.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CertToTsn {
+    entries: Vec<(CertificateFingerprint, CertToTsnMapping)>,
+}
+
+impl CertToTsn {
+    /// Create an empty table.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an entry.
+    #[must_use]
+    pub fn entry(mut self, fingerprint: CertificateFingerprint, mapping: CertToTsnMapping) -> Self {
+        self.entries.push((fingerprint, mapping));
+        self
+    }
+
+    /// Returns the number of entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the table has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Derive the `tmSecurityName` for a presented certificate chain.
+    ///
+    /// `chain` holds DER certificates with the peer's own certificate first.
+    /// Returns `None` when no entry applies, which RFC 6353 treats as a
+    /// failed session.
+    pub fn security_name<C: AsRef<[u8]>>(&self, chain: &[C]) -> Option<Bytes> {
+        let leaf = chain.first()?.as_ref();
+        let mut identity = None;
+        for (fingerprint, mapping) in &self.entries {
+            if !chain
+                .iter()
+                .any(|certificate| fingerprint.matches(certificate.as_ref()))
+            {
+                continue;
+            }
+            let name = match mapping {
+                CertToTsnMapping::Specified(name) => Some(name.clone()),
+                mapping => identity
+                    .get_or_insert_with(|| CertificateIdentity::parse(leaf))
+                    .as_ref()
+                    .and_then(|identity| identity.map(mapping)),
+            };
+            if let Some(name) = name.filter(|name| name.len() <= MAX_TM_SECURITY_NAME_LEN) {
+                return Some(name);
+            }
+        }
+        None
+    }
+}
+
+/// Security properties of an established secure-transport session.
+///
+/// Transports that authenticate their peer report this through
+/// [`Transport::transport_security`](crate::Transport::transport_security);
+/// its presence is what makes a transport usable with [`TsmConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TransportSecurity {
+    /// Transport domain prefix for `snmpTsmConfigurationUsePrefix`, such as
+    /// `"tls"` or `"dtls"`.
+    pub prefix: &'static str,
+    /// Security level the session provides.
+    pub security_level: SecurityLevel,
+    /// `tmSecurityName` derived from the peer's certificate, when the
+    /// transport maps peer identities.
+    pub peer_security_name: Option<Bytes>,
+}
+
+impl TransportSecurity {
+    /// Describe a session with the given prefix and security level.
+    #[must_use]
+    pub fn new(prefix: &'static str, security_level: SecurityLevel) -> Self {
+        Self {
+            prefix,
+            security_level,
+            peer_security_name: None,
+        }
+    }
+
+    /// Record the peer's derived `tmSecurityName`.
+    #[must_use]
+    pub fn with_peer_security_name(mut self, name: impl Into<Bytes>) -> Self {
+        self.peer_security_name = Some(name.into());
+        self
+    }
+}
+
+/// Client configuration for the Transport Security Model.
+///
+/// TSM carries no credentials of its own: the certificate exchange of the
+/// underlying transport authenticates both ends. The client only names the
+/// securityName and level it requests; the level must not exceed what the
+/// session provides. Requests address `contextEngineID` [`LOCAL_ENGINE_ID`]
+/// unless another is configured (RFC 5343).
+///
+/// ```rust
+/// use async_snmp::{Auth, SecurityLevel};
+/// use async_snmp::v3::TsmConfig;
+///
+/// let auth = Auth::from(
+///     TsmConfig::new("monitor")
+///         .security_level(SecurityLevel::AuthPriv)
+///         .context_name("vlan100"),
+/// );
+/// assert_eq!(auth.version(), async_snmp::Version::V3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TsmConfig {
+    security_name: Bytes,
+    security_level: SecurityLevel,
+    context_name: Bytes,
+    context_engine_id: Bytes,
+}
+
+impl TsmConfig {
+    /// Create a configuration requesting `authPriv` for `security_name`.
+    pub fn new(security_name: impl Into<Bytes>) -> Self {
+        Self {
+            security_name: security_name.into(),
+            security_level: SecurityLevel::AuthPriv,
+            context_name: Bytes::new(),
+            context_engine_id: Bytes::from_static(&LOCAL_ENGINE_ID),
+        }
+    }
+
+    /// Set the requested security level.
+    #[must_use]
+    pub fn security_level(mut self, level: SecurityLevel) -> Self {
+        self.security_level = level;
+        self
+    }
+
+    /// Set the context name.
+    #[must_use]
+    pub fn context_name(mut self, name: impl Into<Bytes>) -> Self {
+        self.context_name = name.into();
+        self
+    }
+
+    /// Set the contextEngineID used for requests.
+    #[must_use]
+    pub fn context_engine_id(mut self, engine_id: impl Into<Bytes>) -> Self {
+        self.context_engine_id = engine_id.into();
+        self
+    }
+
+    /// Returns the securityName.
+    #[must_use]
+    pub fn security_name(&self) -> &Bytes {
+        &self.security_name
+    }
+
+    /// Returns the requested security level.
+    #[must_use]
+    pub fn requested_security_level(&self) -> SecurityLevel {
+        self.security_level
+    }
+
+    /// Returns the configured context name.
+    #[must_use]
+    pub fn configured_context_name(&self) -> &Bytes {
+        &self.context_name
+    }
+
+    /// Returns the contextEngineID used for requests.
+    #[must_use]
+    pub fn configured_context_engine_id(&self) -> &Bytes {
+        &self.context_engine_id
+    }
+
+    /// Check this configuration against a transport session (RFC 5591
+    /// Section 5.1): the transport must be secure and provide at least the
+    /// requested level.
+    pub(crate) fn validate_for(&self, session: Option<&TransportSecurity>) -> Result<()> {
+        let session = session.ok_or_else(|| {
+            Error::Config("TSM requires a secure transport such as TLS or DTLS".into()).boxed()
+        })?;
+        if self.security_level > session.security_level {
+            return Err(Error::Config(
+                format!(
+                    "TSM security level {:?} exceeds the {} session level {:?}",
+                    self.security_level, session.prefix, session.security_level
+                )
+                .into(),
+            )
+            .boxed());
+        }
+        Ok(())
+    }
+}
+
+/// subjectAltName entry types used by certToTSN mapping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SubjectAltName {
+    Rfc822Name(String),
+    DnsName(String),
+    IpAddress(IpAddr),
+}
+
+/// Identity fields of an X.509 certificate relevant to certToTSN.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct CertificateIdentity {
+    common_name: Option<String>,
+    subject_alt_names: Vec<SubjectAltName>,
+}
+
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_VERSION: u8 = 0xA0;
+const TAG_EXTENSIONS: u8 = 0xA3;
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_RFC822_NAME: u8 = 0x81;
+const TAG_DNS_NAME: u8 = 0x82;
+const TAG_IP_ADDRESS: u8 = 0x87;
+
+impl CertificateIdentity {
+    /// Extract the subject commonName and subjectAltName entries.
+    ///
+    /// Only the TBSCertificate structure is walked; signatures are the
+    /// transport's concern. Returns `None` for malformed DER.
+    pub(crate) fn parse(der: &[u8]) -> Option<Self> {
+        let mut outer = Decoder::from_slice(der);
+        let mut certificate = outer.read_sequence().ok()?;
+        let mut tbs = certificate.read_sequence().ok()?;
+        if tbs.peek_tag() == Some(TAG_VERSION) {
+            tbs.skip_tlv().ok()?;
+        }
+        // serialNumber, signature, issuer, validity
+        for _ in 0..4 {
+            tbs.skip_tlv().ok()?;
+        }
+        let mut identity = Self {
+            common_name: parse_common_name(tbs.read_sequence().ok()?),
+            subject_alt_names: Vec::new(),
+        };
+        // subjectPublicKeyInfo, then optional unique IDs and extensions
+        tbs.skip_tlv().ok()?;
+        while !tbs.is_empty() {
+            if tbs.peek_tag() != Some(TAG_EXTENSIONS) {
+                tbs.skip_tlv().ok()?;
+                continue;
+            }
+            let mut explicit = tbs.read_constructed(TAG_EXTENSIONS).ok()?;
+            let mut extensions = explicit.read_sequence().ok()?;
+            while !extensions.is_empty() {
+                let mut extension = extensions.read_sequence().ok()?;
+                let id = extension.read_oid().ok()?;
+                if extension.peek_tag() == Some(TAG_BOOLEAN) {
+                    extension.skip_tlv().ok()?;
+                }
+                let value = extension.read_octet_string().ok()?;
+                if id == subject_alt_name_oid() {
+                    identity.subject_alt_names = parse_general_names(&value)?;
+                }
+            }
+        }
+        Some(identity)
+    }
+
+    fn map(&self, mapping: &CertToTsnMapping) -> Option<Bytes> {
+        let name = match mapping {
+            CertToTsnMapping::Specified(name) => return Some(name.clone()),
+            CertToTsnMapping::CommonName => self.common_name.clone(),
+            CertToTsnMapping::SanAny => self.subject_alt_names.first().map(san_security_name),
+            CertToTsnMapping::SanRfc822Name => self
+                .subject_alt_names
+                .iter()
+                .find(|name| matches!(name, SubjectAltName::Rfc822Name(_)))
+                .map(san_security_name),
+            CertToTsnMapping::SanDnsName => self
+                .subject_alt_names
+                .iter()
+                .find(|name| matches!(name, SubjectAltName::DnsName(_)))
+                .map(san_security_name),
+            CertToTsnMapping::SanIpAddress => self
+                .subject_alt_names
+                .iter()
+                .find(|name| matches!(name, SubjectAltName::IpAddress(_)))
+                .map(san_security_name),
+        }?;
+        Some(Bytes::from(name))
+    }
+}
+
+fn common_name_oid() -> Oid {
+    crate::oid!(2, 5, 4, 3)
+}
+
+fn subject_alt_name_oid() -> Oid {
+    crate::oid!(2, 5, 29, 17)
+}
+
+fn parse_common_name(mut name: Decoder<'_>) -> Option<String> {
+    let mut common_name = None;
+    while !name.is_empty() {
+        let mut set = name.read_constructed(0x31).ok()?;
+        while !set.is_empty() {
+            let mut attribute = set.read_sequence().ok()?;
+            let id = attribute.read_oid().ok()?;
+            let tag = attribute.read_tag().ok()?;
+            let len = attribute.read_length().ok()?;
+            let value = attribute.read_bytes(len).ok()?;
+            // UTF8String, PrintableString, TeletexString, IA5String
+            if id == common_name_oid() && matches!(tag, 0x0C | 0x13 | 0x14 | 0x16) {
+                common_name = String::from_utf8(value.to_vec()).ok();
+            }
+        }
+    }
+    common_name
+}
+
+fn parse_general_names(value: &[u8]) -> Option<Vec<SubjectAltName>> {
+    let mut outer = Decoder::from_slice(value);
+    let mut names = outer.read_constructed(TAG_SEQUENCE).ok()?;
+    let mut parsed = Vec::new();
+    while !names.is_empty() {
+        let tag = names.peek_tag()?;
+        if !matches!(tag, TAG_RFC822_NAME | TAG_DNS_NAME | TAG_IP_ADDRESS) {
+            names.skip_tlv().ok()?;
+            continue;
+        }
+        names.read_tag().ok()?;
+        let len = names.read_length().ok()?;
+        let content = names.read_bytes(len).ok()?;
+        let name = match tag {
+            TAG_RFC822_NAME => {
+                SubjectAltName::Rfc822Name(String::from_utf8(content.to_vec()).ok()?)
+            }
+            TAG_DNS_NAME => SubjectAltName::DnsName(String::from_utf8(content.to_vec()).ok()?),
+            _ => match content.len() {
+                4 => IpAddr::from(<[u8; 4]>::try_from(content.as_ref()).ok()?),
+                16 => IpAddr::from(<[u8; 16]>::try_from(content.as_ref()).ok()?),
+                _ => continue,
+            }
+            .into(),
+        };
+        parsed.push(name);
+    }
+    Some(parsed)
+}
+
+impl From<IpAddr> for SubjectAltName {
+    fn from(address: IpAddr) -> Self {
+        Self::IpAddress(address)
+    }
+}
+
+fn san_security_name(name: &SubjectAltName) -> String {
+    match name {
+        SubjectAltName::Rfc822Name(mailbox) => match mailbox.rsplit_once('@') {
+            Some((local, host)) => format!("{local}@{}", host.to_ascii_lowercase()),
+            None => mailbox.clone(),
+        },
+        SubjectAltName::DnsName(name) => name.to_ascii_lowercase(),
+        SubjectAltName::IpAddress(IpAddr::V4(address)) => address.to_string(),
+        SubjectAltName::IpAddress(IpAddr::V6(address)) => {
+            crate::format::hex::encode(&address.octets())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal DER certificate with the given subject CN and SAN entries.
+    ///
+    /// Only the fields [`CertificateIdentity::parse`] walks are meaningful;
+    /// the signature is a placeholder.
+    fn certificate(common_name: &str, san: &[(u8, &[u8])]) -> Vec<u8> {
+        fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+            let mut out = vec![tag];
+            let len = content.len();
+            if len < 0x80 {
+                out.push(len as u8);
+            } else {
+                out.extend([0x82, (len >> 8) as u8, len as u8]);
+            }
+            out.extend_from_slice(content);
+            out
+        }
+        let algorithm = tlv(
+            0x30,
+            &tlv(0x06, &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02]),
+        );
+        let cn_attribute = [
+            tlv(0x06, &[0x55, 0x04, 0x03]),
+            tlv(0x0C, common_name.as_bytes()),
+        ]
+        .concat();
+        let subject = tlv(0x30, &tlv(0x31, &tlv(0x30, &cn_attribute)));
+        let general_names: Vec<u8> = san
+            .iter()
+            .flat_map(|(tag, value)| tlv(*tag, value))
+            .collect();
+        let san_extension = tlv(
+            0x30,
+            &[
+                tlv(0x06, &[0x55, 0x1D, 0x11]),
+                tlv(0x04, &tlv(0x30, &general_names)),
+            ]
+            .concat(),
+        );
+        let tbs = tlv(
+            0x30,
+            &[
+                tlv(0xA0, &tlv(0x02, &[2])),
+                tlv(0x02, &[1]),
+                algorithm.clone(),
+                subject.clone(),
+                tlv(
+                    0x30,
+                    &[tlv(0x17, b"250101000000Z"), tlv(0x17, b"350101000000Z")].concat(),
+                ),
+                subject,
+                tlv(
+                    0x30,
+                    &[algorithm.clone(), tlv(0x03, &[0, 4, 1, 2])].concat(),
+                ),
+                tlv(0xA3, &tlv(0x30, &san_extension)),
+            ]
+            .concat(),
+        );
+        tlv(0x30, &[tbs, algorithm, tlv(0x03, &[0, 0])].concat())
+    }
+
+    #[test]
+    fn fingerprint_display_and_parse_roundtrip() {
+        let fingerprint =
+            CertificateFingerprint::new(FingerprintAlgorithm::Sha1, vec![0xAB; 20]).unwrap();
+        let text = fingerprint.to_string();
+        assert!(text.starts_with("sha1:AB:AB:"));
+        assert_eq!(text.parse::<CertificateFingerprint>().unwrap(), fingerprint);
+        assert_eq!(
+            format!("SHA-1:{}", "ab".repeat(20))
+                .parse::<CertificateFingerprint>()
+                .unwrap(),
+            fingerprint
+        );
+        assert_eq!(
+            CertificateFingerprint::from_snmp_tls_fingerprint(
+                &fingerprint.to_snmp_tls_fingerprint()
+            )
+            .unwrap(),
+            fingerprint
+        );
+        assert!("sha256:AB".parse::<CertificateFingerprint>().is_err());
+        assert!("blake3:AB".parse::<CertificateFingerprint>().is_err());
+    }
+
+    #[test]
+    fn certificate_identity_extracts_common_name_and_alt_names() {
+        let der = certificate(
+            "Router-1",
+            &[
+                (0x82, b"Core.Example.NET"),
+                (0x81, b"Ops@Example.NET"),
+                (0x87, &[192, 0, 2, 7]),
+            ],
+        );
+        let identity = CertificateIdentity::parse(&der).unwrap();
+        assert_eq!(identity.common_name.as_deref(), Some("Router-1"));
+        assert_eq!(
+            identity.map(&CertToTsnMapping::SanDnsName).unwrap(),
+            "core.example.net"
+        );
+        assert_eq!(
+            identity.map(&CertToTsnMapping::SanRfc822Name).unwrap(),
+            "Ops@example.net"
+        );
+        assert_eq!(
+            identity.map(&CertToTsnMapping::SanIpAddress).unwrap(),
+            "192.0.2.7"
+        );
+        assert_eq!(
+            identity.map(&CertToTsnMapping::SanAny).unwrap(),
+            "core.example.net"
+        );
+        assert_eq!(
+            identity.map(&CertToTsnMapping::CommonName).unwrap(),
+            "Router-1"
+        );
+        assert!(CertificateIdentity::parse(&der[..der.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn ipv6_alt_name_maps_to_lowercase_hex() {
+        let address = "2001:db8::1".parse::<std::net::Ipv6Addr>().unwrap();
+        let der = certificate("host", &[(0x87, &address.octets())]);
+        let identity = CertificateIdentity::parse(&der).unwrap();
+        assert_eq!(
+            identity.map(&CertToTsnMapping::SanIpAddress).unwrap(),
+            "20010db8000000000000000000000001"
+        );
+    }
+
+    #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
+    #[test]
+    fn cert_to_tsn_matches_chain_in_entry_order() {
+        let leaf = certificate("leaf", &[(0x82, b"a-very-long-host-name.example.invalid")]);
+        let ca = certificate("issuing-ca", &[]);
+        let unrelated = certificate("other", &[]);
+        let fingerprint = |der: &[u8]| {
+            CertificateFingerprint::compute(FingerprintAlgorithm::Sha256, der).unwrap()
+        };
+
+        let map = CertToTsn::new()
+            .entry(
+                fingerprint(&unrelated),
+                CertToTsnMapping::Specified("wrong".into()),
+            )
+            // The DNS name is longer than 32 octets, so this entry fails over.
+            .entry(fingerprint(&ca), CertToTsnMapping::SanDnsName)
+            .entry(fingerprint(&ca), CertToTsnMapping::CommonName);
+        assert_eq!(map.security_name(&[&leaf, &ca]).unwrap(), "leaf");
+        assert_eq!(map.security_name(&[&ca]).unwrap(), "issuing-ca");
+        assert!(map.security_name(&[leaf.as_slice()]).is_none());
+        assert!(map.security_name::<&[u8]>(&[]).is_none());
+    }
+
+    #[test]
+    fn tsm_config_requires_an_adequate_secure_session() {
+        let config = TsmConfig::new("monitor");
+        assert!(config.validate_for(None).is_err());
+        assert!(
+            config
+                .validate_for(Some(&TransportSecurity::new(
+                    "tls",
+                    SecurityLevel::AuthNoPriv
+                )))
+                .is_err()
+        );
+        assert!(
+            config
+                .validate_for(Some(&TransportSecurity::new(
+                    "tls",
+                    SecurityLevel::AuthPriv
+                )))
+                .is_ok()
+        );
+        assert_eq!(
+            config.configured_context_engine_id().as_ref(),
+            LOCAL_ENGINE_ID
+        );
+    }
+}
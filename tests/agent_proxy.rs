@@ -0,0 +1,382 @@
+#![cfg(feature = "agent")]
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_snmp::agent::{Agent, AgentBuilder, ProxyHandler, ProxyRoute, SecurityModel};
+use async_snmp::message::SecurityLevel;
+use async_snmp::{
+    Auth, BoxFuture, Client, Error, ErrorStatus, GetNextResult, GetResult, HandlerResult,
+    MibHandler, Oid, RequestContext, Retry, Value, oid,
+};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+const SWITCH: &[u8] = b"switch";
+
+fn downstream_oid(index: u32) -> Oid {
+    oid!(1, 3, 6, 1, 4, 1, 99999, index, 0)
+}
+
+/// Read-only scalars 1..=3; scalar 2 is a Counter64.
+struct Scalars;
+
+impl Scalars {
+    fn value(oid: &Oid) -> Option<Value> {
+        match oid {
+            oid if *oid == downstream_oid(1) => Some(Value::Integer(1)),
+            oid if *oid == downstream_oid(2) => Some(Value::Counter64(2)),
+            oid if *oid == downstream_oid(3) => Some(Value::Integer(3)),
+            _ => None,
+        }
+    }
+}
+
+impl MibHandler for Scalars {
+    fn get<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        oid: &'a Oid,
+    ) -> BoxFuture<'a, HandlerResult<GetResult>> {
+        Box::pin(
+            async move { Ok(Self::value(oid).map_or(GetResult::NoSuchObject, GetResult::Value)) },
+        )
+    }
+
+    fn get_next<'a>(
+        &'a self,
+        _ctx: &'a RequestContext,
+        oid: &'a Oid,
+    ) -> BoxFuture<'a, HandlerResult<GetNextResult>> {
+        Box::pin(async move {
+            Ok((1..=3)
+                .map(downstream_oid)
+                .find(|candidate| candidate > oid)
+                .map_or(GetNextResult::EndOfMibView, |next| {
+                    let value = Self::value(&next).unwrap();
+                    GetNextResult::Value(async_snmp::VarBind::new(next, value))
+                }))
+        })
+    }
+}
+
+struct RunningAgent {
+    address: SocketAddr,
+    cancel: CancellationToken,
+    task: JoinHandle<async_snmp::Result<()>>,
+}
+
+impl RunningAgent {
+    async fn spawn(agent: Agent, cancel: CancellationToken) -> Self {
+        let address = agent.local_addr();
+        let task = tokio::spawn(async move { agent.run().await });
+        Self {
+            address,
+            cancel,
+            task,
+        }
+    }
+
+    async fn stop(self) {
+        self.cancel.cancel();
+        self.task.await.unwrap().unwrap();
+    }
+}
+
+async fn downstream() -> RunningAgent {
+    let cancel = CancellationToken::new();
+    let agent = Agent::builder()
+        .bind("127.0.0.1:0")
+        .community(b"south")
+        .handler(oid!(1, 3, 6, 1, 4, 1, 99999), Arc::new(Scalars))
+        .without_builtin_handlers()
+        .allow_all_access()
+        .cancellation_token(cancel.clone())
+        .build()
+        .await
+        .unwrap();
+    RunningAgent::spawn(agent, cancel).await
+}
+
+/// A proxy that maps community `north` to the `switch` context.
+async fn proxy_agent(proxy: &ProxyHandler) -> (Agent, RunningAgent) {
+    proxy_agent_with(proxy, AgentBuilder::allow_all_access).await
+}
+
+async fn proxy_agent_with(
+    proxy: &ProxyHandler,
+    access: impl FnOnce(AgentBuilder) -> AgentBuilder,
+) -> (Agent, RunningAgent) {
+    let cancel = CancellationToken::new();
+    let builder = Agent::builder()
+        .bind("127.0.0.1:0")
+        .community_entry(b"north", "proxy-user", SWITCH, bytes::Bytes::new(), "")
+        .proxy(proxy.clone())
+        .cancellation_token(cancel.clone());
+    let agent = access(builder).build().await.unwrap();
+    (agent.clone(), RunningAgent::spawn(agent, cancel).await)
+}
+
+/// Route for requests from the `north` community's principal.
+fn north_route(client: Client, security_model: SecurityModel) -> ProxyRoute {
+    ProxyRoute::new(
+        client,
+        security_model,
+        "proxy-user",
+        SecurityLevel::NoAuthNoPriv,
+    )
+}
+
+async fn client(address: SocketAddr, auth: Auth) -> Client {
+    Client::builder(address.to_string(), auth)
+        .request_timeout(Duration::from_millis(500))
+        .retry(Retry::none())
+        .connect()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn proxy_forwards_requests_for_routed_context() {
+    let downstream = downstream().await;
+    let proxy = ProxyHandler::new();
+    let south = client(downstream.address, Auth::v2c("south")).await;
+    proxy.route_context_name(
+        SWITCH,
+        north_route(south, SecurityModel::V2c).allow_writes(true),
+    );
+    let (_agent, running) = proxy_agent(&proxy).await;
+    let north = client(running.address, Auth::v2c("north")).await;
+
+    let response = north.get(&downstream_oid(1)).await.unwrap();
+    assert_eq!(response.varbinds[0].value, Value::Integer(1));
+
+    let response = north.get(&downstream_oid(9)).await.unwrap();
+    assert_eq!(response.varbinds[0].value, Value::NoSuchObject);
+
+    let response = north.get_next(&downstream_oid(1)).await.unwrap();
+    assert_eq!(response.varbinds[0].oid, downstream_oid(2));
+    assert_eq!(response.varbinds[0].value, Value::Counter64(2));
+
+    let varbinds = north
+        .get_bulk(&[oid!(1, 3, 6, 1, 4, 1, 99999)], 0, 10)
+        .await
+        .unwrap();
+    let oids: Vec<_> = varbinds.iter().map(|varbind| varbind.oid.clone()).collect();
+    assert_eq!(
+        oids[..3],
+        [downstream_oid(1), downstream_oid(2), downstream_oid(3)]
+    );
+    assert_eq!(varbinds[3].value, Value::EndOfMibView);
+
+    let error = north
+        .set(&downstream_oid(1), Value::Integer(5))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        *error,
+        Error::Snmp {
+            status: ErrorStatus::NotWritable,
+            index: 1,
+            ..
+        }
+    ));
+
+    running.stop().await;
+    downstream.stop().await;
+}
+
+#[tokio::test]
+async fn proxy_translates_exceptions_for_v1_originators() {
+    let downstream = downstream().await;
+    let proxy = ProxyHandler::new();
+    let south = client(downstream.address, Auth::v2c("south")).await;
+    proxy.route_context_name(SWITCH, north_route(south, SecurityModel::V1));
+    let (_agent, running) = proxy_agent(&proxy).await;
+    let north = client(running.address, Auth::v1("north")).await;
+
+    let error = north.get(&downstream_oid(9)).await.unwrap_err();
+    assert!(matches!(
+        *error,
+        Error::Snmp {
+            status: ErrorStatus::NoSuchName,
+            index: 1,
+            ..
+        }
+    ));
+
+    // RFC 3584 Section 4.2.2.1: Counter64 values are stepped over.
+    let response = north.get_next(&downstream_oid(1)).await.unwrap();
+    assert_eq!(response.varbinds[0].oid, downstream_oid(3));
+    assert_eq!(response.varbinds[0].value, Value::Integer(3));
+
+    running.stop().await;
+    downstream.stop().await;
+}
+
+#[tokio::test]
+async fn proxy_counts_downstream_failures_and_follows_table_changes() {
+    let downstream = downstream().await;
+    let silent = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let proxy = ProxyHandler::new();
+    let unreachable = Client::builder(silent.local_addr().unwrap().to_string(), Auth::v2c("south"))
+        .request_timeout(Duration::from_millis(50))
+        .retry(Retry::none())
+        .connect()
+        .await
+        .unwrap();
+    proxy.route_context_name(SWITCH, north_route(unreachable, SecurityModel::V2c));
+    let (agent, running) = proxy_agent(&proxy).await;
+    let north = client(running.address, Auth::v2c("north")).await;
+
+    let error = north.get(&downstream_oid(1)).await.unwrap_err();
+    assert!(matches!(*error, Error::Timeout { .. }));
+    assert_eq!(agent.snmp_proxy_drops(), 1);
+
+    let south = client(downstream.address, Auth::v2c("south")).await;
+    let previous = proxy.route_context_name(SWITCH, north_route(south, SecurityModel::V2c));
+    assert!(previous.is_some());
+    let response = north.get(&downstream_oid(1)).await.unwrap();
+    assert_eq!(response.varbinds[0].value, Value::Integer(1));
+
+    proxy.remove_context_name(SWITCH);
+    let error = north.get(&downstream_oid(1)).await.unwrap_err();
+    assert!(matches!(*error, Error::Timeout { .. }));
+    assert_eq!(agent.snmp_unknown_contexts(), 1);
+    assert_eq!(agent.snmp_proxy_drops(), 1);
+
+    running.stop().await;
+    downstream.stop().await;
+}
+
+#[tokio::test]
+async fn proxy_forwards_v3_requests_to_v2c_devices() {
+    let downstream = downstream().await;
+    let proxy = ProxyHandler::new();
+    let south = client(downstream.address, Auth::v2c("south")).await;
+    proxy.route_context_name(
+        SWITCH,
+        ProxyRoute::new(
+            south,
+            SecurityModel::Usm,
+            "operator",
+            SecurityLevel::NoAuthNoPriv,
+        ),
+    );
+    let cancel = CancellationToken::new();
+    let engine = async_snmp::AuthoritativeEngine::install(b"proxy-engine".to_vec(), |_| {
+        Ok::<(), std::convert::Infallible>(())
+    })
+    .unwrap();
+    let agent = Agent::builder()
+        .bind("127.0.0.1:0")
+        .authoritative_engine(engine)
+        .usm_user("operator", Ok)
+        .unwrap()
+        .proxy(proxy)
+        .allow_all_access()
+        .cancellation_token(cancel.clone())
+        .build()
+        .await
+        .unwrap();
+    let running = RunningAgent::spawn(agent, cancel).await;
+    let north = client(
+        running.address,
+        Auth::from(async_snmp::UsmConfig::new("operator").context_name(SWITCH)),
+    )
+    .await;
+
+    let response = north.get(&downstream_oid(3)).await.unwrap();
+    assert_eq!(response.varbinds[0].value, Value::Integer(3));
+
+    running.stop().await;
+    downstream.stop().await;
+}
+
+#[tokio::test]
+async fn proxy_refuses_sets_from_unauthorized_principals() {
+    let downstream = downstream().await;
+    let proxy = ProxyHandler::new();
+    let south = client(downstream.address, Auth::v2c("south")).await;
+    proxy.route_context_name(SWITCH, north_route(south.clone(), SecurityModel::V2c));
+    let (_agent, running) = proxy_agent(&proxy).await;
+    let north = client(running.address, Auth::v2c("north")).await;
+
+    // The route does not allow writes.
+    let error = north
+        .set(&downstream_oid(1), Value::Integer(5))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        *error,
+        Error::Snmp {
+            status: ErrorStatus::NoAccess,
+            index: 1,
+            ..
+        }
+    ));
+    running.stop().await;
+
+    // The route allows writes, but the principal only has a read view.
+    proxy.route_context_name(
+        SWITCH,
+        north_route(south, SecurityModel::V2c).allow_writes(true),
+    );
+    let (_agent, running) = proxy_agent_with(&proxy, |builder| {
+        builder.vacm(|vacm| {
+            vacm.group("proxy-user", SecurityModel::V2c, "readers")
+                .access(
+                    "readers",
+                    SecurityModel::V2c,
+                    SecurityLevel::NoAuthNoPriv,
+                    |access| access.context_prefix(SWITCH).read_view("all"),
+                )
+                .view("all", |view| view.include(oid!(1, 3, 6, 1)))
+        })
+    })
+    .await;
+    let north = client(running.address, Auth::v2c("north")).await;
+
+    let response = north.get(&downstream_oid(1)).await.unwrap();
+    assert_eq!(response.varbinds[0].value, Value::Integer(1));
+    let error = north
+        .set(&downstream_oid(1), Value::Integer(5))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        *error,
+        Error::Snmp {
+            status: ErrorStatus::AuthorizationError,
+            ..
+        }
+    ));
+
+    running.stop().await;
+    downstream.stop().await;
+}
+
+#[tokio::test]
+async fn proxy_drops_requests_from_other_principals() {
+    let downstream = downstream().await;
+    let proxy = ProxyHandler::new();
+    let south = client(downstream.address, Auth::v2c("south")).await;
+    proxy.route_context_name(
+        SWITCH,
+        ProxyRoute::new(
+            south,
+            SecurityModel::V2c,
+            "operator",
+            SecurityLevel::NoAuthNoPriv,
+        ),
+    );
+    let (agent, running) = proxy_agent(&proxy).await;
+    let north = client(running.address, Auth::v2c("north")).await;
+
+    let error = north.get(&downstream_oid(1)).await.unwrap_err();
+    assert!(matches!(*error, Error::Timeout { .. }));
+    assert_eq!(agent.snmp_proxy_drops(), 1);
+
+    running.stop().await;
+    downstream.stop().await;
+}
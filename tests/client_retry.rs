@@ -89,6 +89,63 @@ async fn client_retries_on_timeout() {
     assert!(result.is_ok());
 }
 
+/// Client recovers from dropped requests by retrying (UDP).
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn client_retries_after_dropped_requests() {
+    use async_snmp::testing::FaultyTransport;
+    use async_snmp::{ClientBuilder, UdpTransport};
+
+    let agent = TestAgent::new().await;
+    let udp = UdpTransport::bind("127.0.0.1:0").await.unwrap();
+    let faulty = FaultyTransport::builder(udp.handle(agent.addr()).unwrap())
+        .drop_first_requests(2)
+        .build();
+    let client = ClientBuilder::new(Auth::v2c("public"))
+        .request_timeout(Duration::from_millis(50))
+        .retry(Retry::fixed(2, Duration::ZERO).unwrap())
+        .build_with_transport(faulty.clone())
+        .unwrap();
+
+    let result = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).await;
+    assert!(result.is_ok());
+    let stats = faulty.stats();
+    assert_eq!(stats.requests, 3);
+    assert_eq!(stats.dropped_requests, 2);
+}
+
+/// A response delayed past the request timeout is a timeout (UDP).
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn delayed_requests_time_out_or_complete() {
+    use async_snmp::testing::FaultyTransport;
+    use async_snmp::{ClientBuilder, UdpTransport};
+
+    let agent = TestAgent::new().await;
+    let udp = UdpTransport::bind("127.0.0.1:0").await.unwrap();
+    let client = |delay| {
+        let faulty = FaultyTransport::builder(udp.handle(agent.addr()).unwrap())
+            .delay(delay, delay)
+            .build();
+        ClientBuilder::new(Auth::v2c("public"))
+            .request_timeout(Duration::from_millis(100))
+            .retry(Retry::none())
+            .build_with_transport(faulty)
+            .unwrap()
+    };
+
+    let result = client(Duration::from_millis(10))
+        .get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0))
+        .await;
+    assert!(result.is_ok());
+
+    let error = client(Duration::from_millis(300))
+        .get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0))
+        .await
+        .unwrap_err();
+    assert!(matches!(*error, Error::Timeout { .. }));
+}
+
 /// Client gives up after max retries.
 #[tokio::test]
 async fn client_fails_after_max_retries() {
@@ -307,6 +307,44 @@ async fn v3_auth_priv_sha256_aes128() {
     assert_eq!(result.varbinds[0].value.as_str(), Some("Test SNMP Agent"));
 }
 
+/// V3 authPriv with keys held by a registered crypto provider.
+#[tokio::test]
+async fn v3_auth_priv_with_crypto_provider() {
+    let agent = TestAgentBuilder::new()
+        .usm_user(V3User::auth_priv(
+            b"authprivuser".to_vec(),
+            AuthProtocol::Sha256,
+            AUTH_PASS.as_bytes().to_vec(),
+            PrivProtocol::Aes128,
+            PRIV_PASS.as_bytes().to_vec(),
+        ))
+        .build()
+        .await;
+
+    let provider = std::sync::Arc::new(async_snmp::v3::CryptoBackend::default_backend().unwrap());
+    let client = Client::builder(
+        agent.addr().to_string(),
+        async_snmp::UsmConfig::new("authprivuser")
+            .auth_priv(
+                AuthProtocol::Sha256,
+                AUTH_PASS,
+                PrivProtocol::Aes128,
+                PRIV_PASS,
+            )
+            .unwrap()
+            .with_crypto_provider(provider)
+            .unwrap(),
+    )
+    .connect()
+    .await
+    .unwrap();
+
+    let result = client.get(&oid!(1, 3, 6, 1, 2, 1, 1, 1, 0)).await.unwrap();
+
+    assert!(result.anomalies.is_empty());
+    assert_eq!(result.varbinds[0].value.as_str(), Some("Test SNMP Agent"));
+}
+
 /// V3 with MD5 auth (legacy support).
 #[cfg(feature = "crypto-rustcrypto")]
 #[tokio::test]
@@ -666,3 +704,173 @@ async fn report_pdu_counter_matches_agent_counter_unknown_user() {
         "agent usm_unknown_usernames counter should be 1"
     );
 }
+
+const USM_USER_SPIN_LOCK: [u32; 11] = [1, 3, 6, 1, 6, 3, 15, 1, 2, 1, 0];
+
+fn usm_user_column(column: u32, engine_id: &[u8], username: &[u8]) -> async_snmp::Oid {
+    let mut arcs = vec![1, 3, 6, 1, 6, 3, 15, 1, 2, 2, 1, column];
+    arcs.push(engine_id.len() as u32);
+    arcs.extend(engine_id.iter().map(|&octet| u32::from(octet)));
+    arcs.push(username.len() as u32);
+    arcs.extend(username.iter().map(|&octet| u32::from(octet)));
+    async_snmp::Oid::from_slice(&arcs)
+}
+
+fn key_change_data() -> std::collections::BTreeMap<async_snmp::Oid, Value> {
+    let mut data = common::system_mib();
+    data.insert(
+        async_snmp::Oid::from_slice(&USM_USER_SPIN_LOCK),
+        Value::Integer(42),
+    );
+    data
+}
+
+/// Changing another user's keys writes KeyChange values that decode to the new
+/// keys under the old ones, then verifies the new credentials.
+#[tokio::test]
+async fn v3_change_usm_keys_for_other_user() {
+    use async_snmp::v3::auth::apply_key_change;
+
+    // The agent already knows the operator's new passwords, so only a correct
+    // verification GET with the rotated credentials succeeds.
+    let agent = TestAgentBuilder::new()
+        .data(key_change_data())
+        .usm_user(V3User::auth_priv(
+            b"admin".to_vec(),
+            AuthProtocol::Sha256,
+            AUTH_PASS.as_bytes().to_vec(),
+            PrivProtocol::Aes128,
+            PRIV_PASS.as_bytes().to_vec(),
+        ))
+        .usm_user(V3User::auth_priv(
+            b"operator".to_vec(),
+            AuthProtocol::Sha256,
+            b"newauthpassword".to_vec(),
+            PrivProtocol::Aes128,
+            b"newprivpassword".to_vec(),
+        ))
+        .build()
+        .await;
+
+    let client = Client::builder(
+        agent.addr().to_string(),
+        async_snmp::UsmConfig::new("admin")
+            .auth_priv(
+                AuthProtocol::Sha256,
+                AUTH_PASS,
+                PrivProtocol::Aes128,
+                PRIV_PASS,
+            )
+            .unwrap(),
+    )
+    .connect()
+    .await
+    .unwrap();
+    let operator = async_snmp::UsmConfig::new("operator")
+        .auth_priv(
+            AuthProtocol::Sha256,
+            "oldauthpassword",
+            PrivProtocol::Aes128,
+            "oldprivpassword",
+        )
+        .unwrap();
+
+    let rotated = client
+        .change_usm_keys(&operator, b"newauthpassword", Some(b"newprivpassword"))
+        .await
+        .unwrap();
+    assert_eq!(rotated.username().as_ref(), b"operator");
+    assert_eq!(rotated.priv_protocol(), Some(PrivProtocol::Aes128));
+
+    let engine_id = agent.agent().engine_id().to_vec();
+    assert_eq!(
+        agent.get(&async_snmp::Oid::from_slice(&USM_USER_SPIN_LOCK)),
+        Some(Value::Integer(42))
+    );
+    let old = operator.derive_keys(&engine_id).unwrap();
+    let new = rotated.derive_keys(&engine_id).unwrap();
+
+    let Some(Value::OctetString(auth_change)) =
+        agent.get(&usm_user_column(6, &engine_id, b"operator"))
+    else {
+        panic!("usmUserAuthKeyChange was not written");
+    };
+    assert_eq!(
+        apply_key_change(
+            AuthProtocol::Sha256,
+            old.auth_key.as_ref().unwrap().as_bytes(),
+            &auth_change
+        )
+        .unwrap(),
+        new.auth_key.as_ref().unwrap().as_bytes()
+    );
+    let Some(Value::OctetString(priv_change)) =
+        agent.get(&usm_user_column(9, &engine_id, b"operator"))
+    else {
+        panic!("usmUserPrivKeyChange was not written");
+    };
+    assert_eq!(
+        apply_key_change(
+            AuthProtocol::Sha256,
+            old.priv_key.as_ref().unwrap().encryption_key(),
+            &priv_change
+        )
+        .unwrap(),
+        new.priv_key.as_ref().unwrap().encryption_key()
+    );
+    assert!(
+        agent
+            .get(&usm_user_column(7, &engine_id, b"operator"))
+            .is_none()
+    );
+}
+
+/// Changing the client's own keys writes the "Own" columns, and a remote
+/// engine that did not apply them fails the verification GET.
+#[tokio::test]
+async fn v3_change_usm_keys_for_own_user_uses_own_columns() {
+    let agent = TestAgentBuilder::new()
+        .data(key_change_data())
+        .usm_user(V3User::auth_only(
+            b"authuser".to_vec(),
+            AuthProtocol::Sha256,
+            AUTH_PASS.as_bytes().to_vec(),
+        ))
+        .build()
+        .await;
+
+    let current = async_snmp::UsmConfig::new("authuser")
+        .auth(AuthProtocol::Sha256, AUTH_PASS)
+        .unwrap();
+    let client = Client::builder(agent.addr().to_string(), current.clone())
+        .request_timeout(Duration::from_millis(300))
+        .retry(Retry::none())
+        .connect()
+        .await
+        .unwrap();
+
+    let result = client
+        .change_usm_keys(&current, b"newauthpassword", None)
+        .await;
+    assert!(
+        matches!(result, Err(ref e) if matches!(**e, Error::Timeout { .. })),
+        "expected verification to fail, got {result:?}"
+    );
+
+    let engine_id = agent.agent().engine_id().to_vec();
+    assert!(matches!(
+        agent.get(&usm_user_column(7, &engine_id, b"authuser")),
+        Some(Value::OctetString(ref value)) if value.len() == 64
+    ));
+    assert!(
+        agent
+            .get(&usm_user_column(6, &engine_id, b"authuser"))
+            .is_none()
+    );
+
+    // The privacy password must match the target's security level.
+    let result = client
+        .change_usm_keys(&current, b"newauthpassword", Some(b"newprivpassword"))
+        .await;
+    assert!(matches!(result, Err(ref e) if matches!(**e, Error::Config(_))));
+}
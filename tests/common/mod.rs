@@ -14,8 +14,6 @@ pub mod v3;
 
 #[cfg(feature = "agent")]
 pub mod agent;
-#[cfg(feature = "agent")]
-pub mod faulty;
 
 // Re-export MIB data fixtures
 pub use fixtures::system_mib;
@@ -37,6 +35,4 @@ pub use fixtures::{combined, interface_table};
 #[cfg(feature = "agent")]
 pub use agent::{TestAgent, TestAgentBuilder, V3User};
 #[cfg(feature = "agent")]
-pub use faulty::{LossyAgent, PausableAgent, SlowAgent};
-#[cfg(feature = "agent")]
 pub use handler::TestHandler;
@@ -9,11 +9,17 @@
 //! - RFC 7860: SHA-2 authentication protocols (uses RFC 3414 algorithm)
 //! - RFC 6234: HMAC test vectors for SHA-1/SHA-2
 //! - RFC 3414 A.5: Key change vectors
+//! - Provider vectors: HMAC and cipher outputs through `CryptoProvider` handles
+
+use std::sync::{Arc, Mutex};
 
 use async_snmp::format::hex::{decode, encode};
-use async_snmp::v3::{AuthProtocol, LocalizedKey, PrivKey, PrivProtocol, SaltCounter};
 #[cfg(feature = "crypto-fips")]
-use async_snmp::v3::{CryptoBackend, CryptoError, MasterKey};
+use async_snmp::v3::MasterKey;
+use async_snmp::v3::{
+    AuthProtocol, CipherParams, CryptoBackend, CryptoError, CryptoProvider, CryptoResult,
+    KeyHandle, LocalizedKey, PrivKey, PrivProtocol, SaltCounter,
+};
 
 /// RFC 3414 Appendix A.3.1: Password to Key using MD5
 ///
@@ -940,3 +946,194 @@ fn test_golden_aes256_roundtrip() {
         "Decrypted plaintext must match original"
     );
 }
+
+// --- Crypto provider KATs ---
+//
+// `testing::check_crypto_provider` drives its vectors through the public
+// `CryptoProvider` interface, where keys are opaque handles, so it applies to
+// the built-in backends and to HSM-style providers alike.
+
+const PROVIDER_KAT_ENGINE_ID: &str = "000000000000000000000002";
+const PROVIDER_KAT_MESSAGE: &[u8; 24] = b"async-snmp provider KAT!";
+#[cfg(all(feature = "testing", feature = "crypto-rustcrypto"))]
+#[test]
+fn rustcrypto_backend_passes_provider_kats() {
+    async_snmp::testing::check_crypto_provider(&CryptoBackend::RustCrypto, true);
+}
+
+#[cfg(all(feature = "testing", feature = "crypto-fips"))]
+#[test]
+fn fips_backend_passes_provider_kats() {
+    async_snmp::testing::check_crypto_provider(&CryptoBackend::AwsLcFips, false);
+}
+
+/// Provider that hands out slot numbers and keeps key material to itself,
+/// like an HSM session.
+#[derive(Debug)]
+struct SlotProvider {
+    inner: Arc<dyn CryptoProvider>,
+    slots: Mutex<Vec<KeyHandle>>,
+}
+
+struct Slot(usize);
+
+impl SlotProvider {
+    fn new() -> Self {
+        Self {
+            inner: Arc::new(CryptoBackend::default_backend().unwrap()),
+            slots: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn store(&self, key: KeyHandle) -> KeyHandle {
+        let mut slots = self.slots.lock().unwrap();
+        slots.push(key);
+        KeyHandle::new(Slot(slots.len() - 1))
+    }
+
+    fn load(&self, handle: &KeyHandle) -> CryptoResult<KeyHandle> {
+        let slot = handle
+            .downcast_ref::<Slot>()
+            .ok_or(CryptoError::InvalidKeyLength)?;
+        Ok(self.slots.lock().unwrap()[slot.0].clone())
+    }
+}
+
+impl CryptoProvider for SlotProvider {
+    fn validate_auth_protocol(&self, protocol: AuthProtocol) -> CryptoResult<()> {
+        self.inner.validate_auth_protocol(protocol)
+    }
+
+    fn validate_priv_protocol(&self, protocol: PrivProtocol) -> CryptoResult<()> {
+        self.inner.validate_priv_protocol(protocol)
+    }
+
+    fn localize_auth_key(
+        &self,
+        protocol: AuthProtocol,
+        password: &[u8],
+        engine_id: &[u8],
+    ) -> CryptoResult<KeyHandle> {
+        let key = self
+            .inner
+            .localize_auth_key(protocol, password, engine_id)?;
+        Ok(self.store(key))
+    }
+
+    fn localize_priv_key(
+        &self,
+        auth_protocol: AuthProtocol,
+        priv_protocol: PrivProtocol,
+        password: &[u8],
+        engine_id: &[u8],
+    ) -> CryptoResult<KeyHandle> {
+        let key =
+            self.inner
+                .localize_priv_key(auth_protocol, priv_protocol, password, engine_id)?;
+        Ok(self.store(key))
+    }
+
+    fn compute_hmac(
+        &self,
+        key: &KeyHandle,
+        protocol: AuthProtocol,
+        slices: &[&[u8]],
+    ) -> CryptoResult<Vec<u8>> {
+        self.inner.compute_hmac(&self.load(key)?, protocol, slices)
+    }
+
+    fn encrypt(
+        &self,
+        key: &KeyHandle,
+        protocol: PrivProtocol,
+        params: &CipherParams,
+        data: &mut Vec<u8>,
+    ) -> CryptoResult<()> {
+        self.inner.encrypt(&self.load(key)?, protocol, params, data)
+    }
+
+    fn decrypt(
+        &self,
+        key: &KeyHandle,
+        protocol: PrivProtocol,
+        params: &CipherParams,
+        data: &mut [u8],
+    ) -> CryptoResult<()> {
+        self.inner.decrypt(&self.load(key)?, protocol, params, data)
+    }
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn handle_only_provider_passes_provider_kats() {
+    async_snmp::testing::check_crypto_provider(
+        &SlotProvider::new(),
+        cfg!(feature = "crypto-rustcrypto"),
+    );
+}
+
+#[test]
+fn usm_config_routes_key_operations_through_provider() {
+    let engine_id = decode(PROVIDER_KAT_ENGINE_ID).unwrap();
+    let config = async_snmp::UsmConfig::new("user")
+        .auth_priv(
+            AuthProtocol::Sha1,
+            b"maplesyrup",
+            PrivProtocol::Aes128,
+            b"maplesyrup",
+        )
+        .unwrap();
+    let provider = Arc::new(SlotProvider::new());
+    let held = config
+        .clone()
+        .with_crypto_provider(provider.clone())
+        .unwrap();
+
+    let keys = held.derive_keys(&engine_id).unwrap();
+    let reference = config.derive_keys(&engine_id).unwrap();
+    assert_eq!(provider.slots.lock().unwrap().len(), 2);
+
+    let auth_key = keys.auth_key.unwrap();
+    assert!(auth_key.is_provider_held());
+    assert!(auth_key.as_bytes().is_empty());
+    assert_eq!(
+        encode(&auth_key.compute_hmac(PROVIDER_KAT_MESSAGE).unwrap()),
+        "55e6d8d4a1d3598779549b64"
+    );
+    let reference_mac = reference
+        .auth_key
+        .unwrap()
+        .compute_hmac(PROVIDER_KAT_MESSAGE)
+        .unwrap();
+    assert!(
+        auth_key
+            .verify_hmac(PROVIDER_KAT_MESSAGE, &reference_mac)
+            .unwrap()
+    );
+
+    let priv_key = keys.priv_key.unwrap();
+    assert!(priv_key.is_provider_held());
+    assert!(priv_key.encryption_key().is_empty());
+    let (ciphertext, salt) = priv_key
+        .encrypt_aes(PROVIDER_KAT_MESSAGE, 1, 2, &SaltCounter::new().unwrap())
+        .unwrap();
+    let plaintext = reference
+        .priv_key
+        .unwrap()
+        .decrypt(&ciphertext, 1, 2, &salt)
+        .unwrap();
+    assert_eq!(plaintext.as_ref(), PROVIDER_KAT_MESSAGE);
+}
+
+#[test]
+fn provider_rejects_master_keys() {
+    let master_keys = async_snmp::MasterKeys::new(AuthProtocol::Sha1, b"maplesyrup").unwrap();
+    let provider: Arc<dyn CryptoProvider> = Arc::new(SlotProvider::new());
+    let config = async_snmp::UsmConfig::new("user")
+        .with_master_keys(master_keys)
+        .unwrap();
+    assert_eq!(
+        config.with_crypto_provider(provider).unwrap_err(),
+        CryptoError::KeyMaterialUnavailable
+    );
+}
@@ -5,7 +5,7 @@
 #[cfg(any(feature = "crypto-rustcrypto", feature = "crypto-fips"))]
 mod common;
 
-use async_snmp::agent::{Agent, SinkSkipReason, SinkStatus};
+use async_snmp::agent::{Agent, NotificationForwarder, SinkSkipReason, SinkStatus, TrapCommunity};
 use async_snmp::message::CommunityMessage;
 use async_snmp::notification::{
     Notification, NotificationAcceptance, NotificationReceiver, NotificationVarbindValidation,
@@ -945,6 +945,119 @@ async fn agent_v2c_inform_to_sink() {
     }
 }
 
+#[tokio::test]
+async fn forwarder_converts_v1_traps_for_trap_and_inform_sinks() {
+    use async_snmp::notification::oids;
+    use async_snmp::pdu::GenericTrap;
+
+    let front = NotificationReceiver::bind("127.0.0.1:0").await.unwrap();
+    let trap_collector = NotificationReceiver::bind("127.0.0.1:0").await.unwrap();
+    let inform_collector = NotificationReceiver::bind("127.0.0.1:0").await.unwrap();
+
+    let agent = Agent::builder()
+        .bind("127.0.0.1:0")
+        .trap_sink(
+            NotificationSinkId::new("traps").unwrap(),
+            trap_collector.local_addr().to_string(),
+            Auth::v2c("collector"),
+        )
+        .trap_sink(
+            NotificationSinkId::new("informs").unwrap(),
+            inform_collector.local_addr().to_string(),
+            Auth::v2c("collector"),
+        )
+        .allow_all_access()
+        .build()
+        .await
+        .unwrap();
+    let forwarder = NotificationForwarder::new(agent)
+        .inform_sink(NotificationSinkId::new("informs").unwrap())
+        .trap_community(TrapCommunity::Received)
+        .filter(|received| received.notification.version() == Version::V1);
+
+    let device = Client::builder(front.local_addr().to_string(), Auth::v1("device"))
+        .connect()
+        .await
+        .unwrap();
+    let trap = TrapV1Notification::new(
+        oid!(1, 3, 6, 1, 4, 1, 9999),
+        [10, 0, 0, 1],
+        GenericTrap::EnterpriseSpecific,
+        42,
+        777,
+        vec![VarBind::new(
+            oid!(1, 3, 6, 1, 2, 1, 1, 1, 0),
+            Value::from("hello"),
+        )],
+    )
+    .unwrap();
+    device.send_v1_trap(trap).await.unwrap();
+    let received = tokio::time::timeout(Duration::from_secs(5), front.recv())
+        .await
+        .expect("timeout waiting for v1 trap")
+        .unwrap();
+
+    let inform_handle = tokio::spawn(async move {
+        tokio::time::timeout(Duration::from_secs(5), inform_collector.recv())
+            .await
+            .expect("timeout")
+            .unwrap()
+    });
+    let outcome = forwarder.forward(&received).await.unwrap().unwrap();
+    assert!(outcome.all_succeeded());
+    assert_eq!(outcome.len(), 2);
+
+    let expected_varbinds = vec![
+        VarBind::new(oid!(1, 3, 6, 1, 2, 1, 1, 1, 0), Value::from("hello")),
+        VarBind::new(oids::snmp_trap_address(), Value::IpAddress([10, 0, 0, 1])),
+        VarBind::new(
+            oids::snmp_trap_community(),
+            Value::OctetString(Bytes::from_static(b"device")),
+        ),
+        VarBind::new(
+            oids::snmp_trap_enterprise(),
+            Value::ObjectIdentifier(oid!(1, 3, 6, 1, 4, 1, 9999)),
+        ),
+    ];
+    let trapped = tokio::time::timeout(Duration::from_secs(5), trap_collector.recv())
+        .await
+        .expect("timeout")
+        .unwrap()
+        .notification;
+    match trapped {
+        Notification::TrapV2c {
+            uptime,
+            trap_oid,
+            varbinds,
+            ..
+        } => {
+            assert_eq!(uptime, 777);
+            assert_eq!(trap_oid, oid!(1, 3, 6, 1, 4, 1, 9999, 0, 42));
+            assert_eq!(varbinds, expected_varbinds);
+        }
+        other => panic!("expected TrapV2c, got {other:?}"),
+    }
+    match inform_handle.await.unwrap().notification {
+        Notification::InformV2c { varbinds, .. } => assert_eq!(varbinds, expected_varbinds),
+        other => panic!("expected InformV2c, got {other:?}"),
+    }
+
+    // The filter only admits v1 traps.
+    let v2c_device = Client::builder(front.local_addr().to_string(), Auth::v2c("device"))
+        .connect()
+        .await
+        .unwrap();
+    v2c_device
+        .send_trap(&oid!(1, 3, 6, 1, 6, 3, 1, 1, 5, 1), 0, vec![])
+        .await
+        .unwrap();
+    let received = tokio::time::timeout(Duration::from_secs(5), front.recv())
+        .await
+        .expect("timeout waiting for v2c trap")
+        .unwrap();
+    assert!(forwarder.forward(&received).await.unwrap().is_none());
+}
+
 #[tokio::test]
 async fn agent_inform_sinks_share_source_endpoint() {
     let first_receiver = NotificationReceiver::bind("127.0.0.1:0").await.unwrap();
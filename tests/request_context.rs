@@ -225,7 +225,9 @@ async fn agent_captures_complete_v3_contexts_at_every_security_level() {
             )
         })
         .unwrap()
-        .handler(oid!(1, 3, 6, 1, 4, 1, 99999), capture.clone())
+        .context(CONTEXT_NAME, |ctx| {
+            ctx.handler(oid!(1, 3, 6, 1, 4, 1, 99999), capture.clone())
+        })
         .without_builtin_handlers()
         .vacm(|vacm| {
             let vacm = vacm.group(USERNAME, SecurityModel::Usm, GROUP);